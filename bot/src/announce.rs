@@ -0,0 +1,76 @@
+//! Result announcements
+//!
+//! The server has no push mechanism, so the bot polls the groups and announces every game that
+//! has been played since the previous poll.
+use crate::api::Api;
+use crate::format;
+use poise::serenity_prelude::{ChannelId, Http};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use wwc_core::game::GameId;
+use wwc_core::group::game::PlayedGroupGame;
+use wwc_core::group::Groups;
+
+/// Games played in `groups` but not in `known`
+pub fn new_results(known: &HashSet<GameId>, groups: &Groups) -> Vec<PlayedGroupGame> {
+    groups
+        .values()
+        .flat_map(|group| group.played_games())
+        .filter(|game| !known.contains(&game.id))
+        .copied()
+        .collect()
+}
+
+/// Poll the server every `interval` and post new results to `channel`
+///
+/// Games already played when the bot starts are not announced.
+/// Failed polls are logged and retried at the next interval.
+pub async fn announce_results(http: Arc<Http>, api: Api, channel: ChannelId, interval: Duration) {
+    let mut known: Option<HashSet<GameId>> = None;
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let (groups, teams) = match tokio::try_join!(api.groups(), api.teams()) {
+            Ok(data) => data,
+            Err(err) => {
+                eprintln!("Failed to poll results: {}", err);
+                continue;
+            }
+        };
+        let played = new_results(known.as_ref().unwrap_or(&HashSet::new()), &groups);
+        if known.is_some() {
+            for game in &played {
+                if let Err(err) = channel.say(&http, format::result(game, &teams)).await {
+                    eprintln!("Failed to announce game {}: {}", game.id, err);
+                }
+            }
+        }
+        known
+            .get_or_insert_with(HashSet::new)
+            .extend(played.iter().map(|game| game.id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wwc_core::game::Score;
+    use wwc_core::group::{mock_data, GroupId};
+
+    #[test]
+    fn only_new_games() {
+        let (mut groups, _) = mock_data();
+        let known: HashSet<GameId> = new_results(&HashSet::new(), &groups)
+            .iter()
+            .map(|game| game.id)
+            .collect();
+        assert!(new_results(&known, &groups).is_empty());
+        let group = groups.get_mut(&GroupId::from('A')).unwrap();
+        let id = group.unplayed_games().next().unwrap().id;
+        group.play_game(id, Score::new(2, 2));
+        let new = new_results(&known, &groups);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].id, id);
+    }
+}
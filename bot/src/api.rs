@@ -0,0 +1,283 @@
+//! Server API client
+//!
+//! Thin async wrapper around the http api of `wwc_server`.
+//! The bot has no database access of its own, everything goes through the server.
+//! Requests go to the versioned routes of the server, see [`API_VERSION_PREFIX`].
+//! Routes requiring authentication take an API key, see [`Api::with_api_key`], routes of a player
+//! the session of the player, see [`Api::login`].
+//! Error responses carry an [`ErrorBody`], the codes the bot acts on have their own [`ApiError`]
+//! variants.
+use crate::link::LinkedPlayer;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use thiserror::Error;
+use wwc_core::auth::LoginToken;
+use wwc_core::export::{LeaderboardRow, StandingRow};
+use wwc_core::game::GameId;
+use wwc_core::group::Groups;
+use wwc_core::parse::result::{parse_result, ResultParseError};
+use wwc_core::player::{Player, PlayerId, PlayerPredictions, Prediction, PredictionReport};
+use wwc_core::team::resolve::TeamResolver;
+use wwc_core::team::{TeamId, Teams};
+
+/// Prefix of the version of the server api the bot is written against
+pub const API_VERSION_PREFIX: &str = "/api/v1";
+
+/// Header with the API key of a request, see `wwc_server::auth`
+const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Cookie with the session token of a logged in player, see `wwc_server::auth`
+const SESSION_COOKIE: &str = "wwc_session";
+
+#[derive(Debug, Clone)]
+pub struct Api {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl Api {
+    /// Client for the server at `base_url`, without the version prefix
+    pub fn new(base_url: &str) -> Self {
+        Api {
+            client: reqwest::Client::new(),
+            base_url: format!("{}{}", base_url.trim_end_matches('/'), API_VERSION_PREFIX),
+            api_key: None,
+        }
+    }
+
+    /// Send `key` in the [`API_KEY_HEADER`] of every request
+    pub fn with_api_key(self, key: String) -> Self {
+        Api {
+            api_key: Some(key),
+            ..self
+        }
+    }
+
+    pub async fn groups(&self) -> Result<Groups, ApiError> {
+        self.get("/get_groups").await
+    }
+
+    pub async fn teams(&self) -> Result<Teams, ApiError> {
+        self.get("/get_teams").await
+    }
+
+    /// Resolver for typed team names, with the same aliases and translated names as the server
+    pub async fn team_resolver(&self) -> Result<TeamResolver, ApiError> {
+        let teams = self.teams().await?;
+        let aliases: Vec<(String, TeamId)> = self.get("/teams/aliases").await?;
+        Ok(TeamResolver::new(&teams).with_aliases(aliases))
+    }
+
+    pub async fn players(&self) -> Result<Vec<Player>, ApiError> {
+        self.get("/players").await
+    }
+
+    /// Log in with the token of a personal login link of a player
+    ///
+    /// Responds with the session of the player, see [`link`](crate::link).
+    pub async fn login(&self, token: &str) -> Result<LinkedPlayer, ApiError> {
+        let request = self
+            .client
+            .get(format!("{}/login/{}", self.base_url, token));
+        let response = self.check(request).await?;
+        let session = response
+            .headers()
+            .get_all(reqwest::header::SET_COOKIE)
+            .iter()
+            .filter_map(|cookie| cookie.to_str().ok())
+            .find_map(|cookie| {
+                let (name, rest) = cookie.split_once('=')?;
+                let value = rest.split(';').next()?;
+                Some(String::from(value)).filter(|_| name.trim() == SESSION_COOKIE)
+            })
+            .ok_or_else(|| ApiError::Server(String::from("No session in the login response")))?;
+        let token: LoginToken = response.json().await?;
+        Ok(LinkedPlayer {
+            player: token.player,
+            session,
+            expires_at: token.expires_at,
+        })
+    }
+
+    /// Player with a given id
+    ///
+    /// Chat users are matched with players by their link, see [`link`](crate::link).
+    pub async fn player(&self, id: PlayerId) -> Result<Option<Player>, ApiError> {
+        Ok(self
+            .players()
+            .await?
+            .into_iter()
+            .find(|player| player.id() == id))
+    }
+
+    pub async fn preds(&self, id: PlayerId) -> Result<Vec<Prediction>, ApiError> {
+        self.get(&format!("/get_preds/{}", id)).await
+    }
+
+    pub async fn group_standings(&self, group: &str) -> Result<Vec<StandingRow>, ApiError> {
+        self.get(&format!("/groups/{}/standings.json", group)).await
+    }
+
+    pub async fn leaderboard(&self) -> Result<Vec<LeaderboardRow>, ApiError> {
+        self.get("/leaderboard.json").await
+    }
+
+    /// Submit predictions, with the `session` of the player, see [`Api::login`]
+    ///
+    /// The server validates every prediction (e.g. against the kickoff of the game)
+    /// and reports which ones were accepted.
+    pub async fn submit_preds(
+        &self,
+        session: &str,
+        preds: &PlayerPredictions,
+    ) -> Result<Vec<PredictionReport>, ApiError> {
+        let request = self
+            .client
+            .put(format!("{}/predictions/group-stage", self.base_url))
+            .header(
+                reqwest::header::COOKIE,
+                format!("{}={}", SESSION_COOKIE, session),
+            )
+            .json(preds);
+        self.send(request).await
+    }
+
+    /// Enter the result of a group game typed as text, e.g. "GER-FRA 2:1 (yellow: ger 2)"
+    ///
+    /// The server resolves the teams, matches the unplayed game and stores the score and the
+    /// cards. Responds with the id of the game.
+    /// The game is matched here as well, to send the version of the game along with the result,
+    /// so that the server rejects the result if someone else enters the game at the same time.
+    /// Requires an admin API key, see [`Api::with_api_key`].
+    pub async fn post_result(&self, text: &str) -> Result<GameId, ApiError> {
+        let (teams, groups) = tokio::try_join!(self.team_resolver(), self.groups())?;
+        let entry = parse_result(text, &teams)?;
+        let game = entry.submission(&groups)?.game_id;
+        let version = self.game_version(game, entry.home).await?;
+        let request = self
+            .client
+            .post(format!("{}/results", self.base_url))
+            .json(&serde_json::json!({ "text": text, "version": version }));
+        self.send(request).await
+    }
+
+    /// Current version of a game of `team`, the `row_version` results are checked against
+    async fn game_version(&self, game: GameId, team: TeamId) -> Result<i32, ApiError> {
+        let page: GamePage = self.get(&format!("/games?team={}", team.0)).await?;
+        page.games
+            .into_iter()
+            .find(|row| GameId::from(row.id) == game)
+            .map(|row| row.row_version)
+            .ok_or_else(|| ApiError::Server(format!("No game with id {}", game)))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ApiError> {
+        let request = self.client.get(format!("{}{}", self.base_url, path));
+        self.send(request).await
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, ApiError> {
+        Ok(self.check(request).await?.json().await?)
+    }
+
+    /// Send `request`, failing unless the response is a success
+    async fn check(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, ApiError> {
+        let request = match &self.api_key {
+            Some(key) => request.header(API_KEY_HEADER, key),
+            None => request,
+        };
+        let response = request.send().await?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            let text = response.text().await?;
+            // Responses not from the server itself, e.g. from a proxy, have no error body.
+            Err(match serde_json::from_str::<ErrorBody>(&text) {
+                Ok(body) => ApiError::from(body),
+                Err(_) => ApiError::Server(text),
+            })
+        }
+    }
+}
+
+/// Body of an error response of the server, see `wwc_core::error`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ErrorBody {
+    /// Machine readable code, one of `wwc_core::error::ERROR_CODES`
+    pub code: String,
+    pub message: String,
+    pub details: Option<serde_json::Value>,
+}
+
+/// Page of `/games`, with the fields the bot needs
+#[derive(Deserialize)]
+struct GamePage {
+    games: Vec<GameRow>,
+}
+
+#[derive(Deserialize)]
+struct GameRow {
+    id: u32,
+    row_version: i32,
+}
+
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("Request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("{0}")]
+    Result(#[from] ResultParseError),
+    #[error("The server is read-only for now, try again later")]
+    ReadOnly,
+    #[error("Someone else changed the game in the meantime, check it and try again")]
+    VersionConflict(ErrorBody),
+    #[error("Not logged in as the player, link your account again")]
+    NotLoggedIn,
+    #[error("Server error: {}", .0.message)]
+    Response(ErrorBody),
+    #[error("Server error: {0}")]
+    Server(String),
+}
+
+impl From<ErrorBody> for ApiError {
+    fn from(body: ErrorBody) -> Self {
+        match body.code.as_str() {
+            "READ_ONLY" => ApiError::ReadOnly,
+            "GAME_VERSION_CONFLICT" => ApiError::VersionConflict(body),
+            "NOT_LOGGED_IN_AS_PLAYER" => ApiError::NotLoggedIn,
+            _ => ApiError::Response(body),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_codes() {
+        let error = |json: &str| ApiError::from(serde_json::from_str::<ErrorBody>(json).unwrap());
+        assert!(matches!(
+            error(r#"{"code": "READ_ONLY", "message": "Read-only", "details": null}"#),
+            ApiError::ReadOnly
+        ));
+        let conflict =
+            r#"{"code": "GAME_VERSION_CONFLICT", "message": "Changed", "details": {"game": 3}}"#;
+        match error(conflict) {
+            ApiError::VersionConflict(body) => {
+                assert_eq!(body.details, Some(serde_json::json!({"game": 3})))
+            }
+            other => panic!("Expected a version conflict, got {:?}", other),
+        }
+        assert!(matches!(
+            error(r#"{"code": "NOT_LOGGED_IN_AS_PLAYER", "message": "Not you", "details": null}"#),
+            ApiError::NotLoggedIn
+        ));
+        let other = error(r#"{"code": "UNKNOWN_GAME", "message": "No game with id 9"}"#);
+        assert_eq!(other.to_string(), "Server error: No game with id 9");
+    }
+}
@@ -0,0 +1,186 @@
+//! Telegram bot for the betting pool
+//!
+//! For players who would rather chat than use the web ui: standings, the leaderboard and
+//! submitting predictions with chat commands.
+//! Telegram users link their account to a player once, with `/link` and a personal login link of
+//! the player, see [`wwc_bot::link`]. Commands about the player of the user reject unlinked
+//! users.
+//!
+//! Configured with environment variables:
+//!
+//! - `TELEGRAM_TOKEN`: bot token (required)
+//! - `WWC_API_URL`: url of the `wwc_server` api (default `http://localhost:8000`)
+//! - `WWC_API_KEY`: API key sent to the server (optional)
+//! - `WWC_LINKS_FILE`: file storing the linked users (default `wwc_telegram_links.json`)
+#![forbid(unsafe_code)]
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use teloxide::prelude::*;
+use teloxide::types::ParseMode;
+use teloxide::utils::command::BotCommands;
+use thiserror::Error;
+use wwc_bot::api::{Api, ApiError};
+use wwc_bot::format::{self, Markup};
+use wwc_bot::link::{login_token, ChatLinks, LinkError, LinkedPlayer};
+use wwc_bot::lookup::{self, LookupError};
+use wwc_core::game::{GameId, Score};
+use wwc_core::player::{PlayerId, PlayerPredictions, Prediction};
+use wwc_core::Date;
+
+const DEFAULT_API_URL: &str = "http://localhost:8000";
+const DEFAULT_LINKS_FILE: &str = "wwc_telegram_links.json";
+
+type Links = Arc<Mutex<ChatLinks>>;
+
+#[derive(BotCommands, Clone)]
+#[command(
+    rename_rule = "lowercase",
+    description = "Wednesday World Cup commands:"
+)]
+enum Command {
+    #[command(description = "show this message")]
+    Help,
+    #[command(
+        description = "link your account to your player, in a private chat: /link <your login link>"
+    )]
+    Link(String),
+    #[command(description = "show the table of a group, e.g. /standings A or /standings Sweden")]
+    Standings(String),
+    #[command(description = "show the leaderboard")]
+    Leaderboard,
+    #[command(
+        description = "predict the score of a game, e.g. /predict 12 2-1",
+        parse_with = "split"
+    )]
+    Predict { game: u32, score: Score },
+    #[command(description = "show your predictions")]
+    MyPredictions,
+}
+
+async fn answer(
+    bot: Bot,
+    msg: Message,
+    cmd: Command,
+    api: Api,
+    links: Links,
+) -> ResponseResult<()> {
+    let text = match respond(&msg, cmd, &api, &links).await {
+        Ok(text) => text,
+        Err(err) => Markup::Html.text(&err.to_string()),
+    };
+    bot.send_message(msg.chat.id, text)
+        .parse_mode(ParseMode::Html)
+        .await?;
+    Ok(())
+}
+
+async fn respond(
+    msg: &Message,
+    cmd: Command,
+    api: &Api,
+    links: &Links,
+) -> Result<String, BotError> {
+    Ok(match cmd {
+        Command::Help => Markup::Html.text(&Command::descriptions().to_string()),
+        Command::Link(link) => {
+            let user = user(msg)?;
+            let linked = api.login(login_token(&link)).await?;
+            let player = linked.player;
+            links
+                .lock()
+                .expect("Links lock poisoned")
+                .insert(user, linked)?;
+            let name = player_name(api, player).await?;
+            Markup::Html.text(&format!("Linked to player {}", name))
+        }
+        Command::Standings(group) => {
+            let group =
+                lookup::group_of(&group, &api.groups().await?, &api.team_resolver().await?)?;
+            let group = group.to_string();
+            let rows = api.group_standings(&group).await?;
+            format::standings(&group, &rows, Markup::Html)
+        }
+        Command::Leaderboard => format::leaderboard(&api.leaderboard().await?, Markup::Html),
+        Command::Predict { game, score } => {
+            let linked = linked(msg, links)?;
+            let pred = Prediction(GameId::from(game), score);
+            // The deadline is checked by the server, against the server clock.
+            let reports = api
+                .submit_preds(
+                    &linked.session,
+                    &PlayerPredictions::new(linked.player, vec![pred]),
+                )
+                .await?;
+            let report = reports
+                .first()
+                .ok_or_else(|| BotError::Api(ApiError::Server(String::from("Empty response"))))?;
+            let (groups, teams) = tokio::try_join!(api.groups(), api.teams())?;
+            format::submitted(&pred, report, &groups, &teams, Markup::Html)
+        }
+        Command::MyPredictions => {
+            let player = linked(msg, links)?.player;
+            let (preds, groups, teams, name) = tokio::try_join!(
+                api.preds(player),
+                api.groups(),
+                api.teams(),
+                player_name(api, player)
+            )?;
+            format::predictions(&name, &preds, &groups, &teams, Markup::Html)
+        }
+    })
+}
+
+/// Telegram user id of the sender
+fn user(msg: &Message) -> Result<u64, BotError> {
+    Ok(msg.from().ok_or(BotError::NoUser)?.id.0)
+}
+
+/// Player linked to the sender, see [`wwc_bot::link`]
+fn linked(msg: &Message, links: &Links) -> Result<LinkedPlayer, BotError> {
+    let user = user(msg)?;
+    Ok(links
+        .lock()
+        .expect("Links lock poisoned")
+        .get(user, Date::now())?
+        .clone())
+}
+
+async fn player_name(api: &Api, player: PlayerId) -> Result<String, ApiError> {
+    Ok(api
+        .player(player)
+        .await?
+        .map_or_else(|| player.to_string(), |found| String::from(found.name())))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), BotError> {
+    let token = std::env::var("TELEGRAM_TOKEN").map_err(|_| BotError::Config("TELEGRAM_TOKEN"))?;
+    let api_url = std::env::var("WWC_API_URL").unwrap_or_else(|_| String::from(DEFAULT_API_URL));
+    let api = match std::env::var("WWC_API_KEY") {
+        Ok(key) => Api::new(&api_url).with_api_key(key),
+        Err(_) => Api::new(&api_url),
+    };
+    let links_file =
+        std::env::var("WWC_LINKS_FILE").unwrap_or_else(|_| String::from(DEFAULT_LINKS_FILE));
+    let links: Links = Arc::new(Mutex::new(ChatLinks::load(PathBuf::from(links_file))?));
+    let bot = Bot::new(token);
+    Command::repl(bot, move |bot: Bot, msg: Message, cmd: Command| {
+        answer(bot, msg, cmd, api.clone(), links.clone())
+    })
+    .await;
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+enum BotError {
+    #[error("Missing or invalid config: {0}")]
+    Config(&'static str),
+    #[error("Could not tell who sent the command")]
+    NoUser,
+    #[error("{0}")]
+    Link(#[from] LinkError),
+    #[error("{0}")]
+    Api(#[from] ApiError),
+    #[error("{0}")]
+    Lookup(#[from] LookupError),
+}
@@ -0,0 +1,224 @@
+//! Message formatting
+//!
+//! Chat clients render code blocks in a monospace font, which is the only way to get aligned
+//! tables. Discord uses markdown for that, Telegram is easier to get right with html.
+use wwc_core::export::{LeaderboardRow, StandingRow};
+use wwc_core::game::{GameId, Score};
+use wwc_core::group::game::PlayedGroupGame;
+use wwc_core::group::Groups;
+use wwc_core::player::{Prediction, PredictionReport, PredictionStatus};
+use wwc_core::team::{TeamId, Teams};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Markup {
+    Markdown,
+    Html,
+}
+
+impl Markup {
+    fn bold(self, text: &str) -> String {
+        match self {
+            Markup::Markdown => format!("**{}**", text),
+            Markup::Html => format!("<b>{}</b>", escape_html(text)),
+        }
+    }
+
+    fn code_block(self, text: &str) -> String {
+        match self {
+            Markup::Markdown => format!("```\n{}\n```", text),
+            Markup::Html => format!("<pre>{}</pre>", escape_html(text)),
+        }
+    }
+
+    /// Plain text, escaped if needed
+    pub fn text(self, text: &str) -> String {
+        match self {
+            Markup::Markdown => text.to_string(),
+            Markup::Html => escape_html(text),
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn standings(group: &str, rows: &[StandingRow], markup: Markup) -> String {
+    let table = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "{:>2} {:<4} {:>2} {:>2} {:>2} {:>2} {:>3} {:>3}",
+                row.rank,
+                row.fifa_code,
+                row.played,
+                row.wins,
+                row.draws,
+                row.losses,
+                row.goal_diff,
+                row.points
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{}\n{}",
+        markup.bold(&format!("Group {}", group.to_uppercase())),
+        markup.code_block(&format!(" #  Team  P  W  D  L  GD Pts\n{}", table))
+    )
+}
+
+pub fn leaderboard(rows: &[LeaderboardRow], markup: Markup) -> String {
+    if rows.is_empty() {
+        return String::from("No players yet.");
+    }
+    let table = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "{:>2} {:<16} {:>6} {:>6}{}",
+                row.rank,
+                row.player,
+                row.points,
+                row.max_points,
+                if row.eliminated { " (out)" } else { "" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{}\n{}",
+        markup.bold("Leaderboard"),
+        markup.code_block(&format!(" #  Player           Points    Max\n{}", table))
+    )
+}
+
+pub fn predictions(
+    player: &str,
+    preds: &[Prediction],
+    groups: &Groups,
+    teams: &Teams,
+    markup: Markup,
+) -> String {
+    if preds.is_empty() {
+        return markup.text(&format!("{} has no predictions yet.", player));
+    }
+    let lines = preds
+        .iter()
+        .map(|Prediction(id, pred)| format!("{:<11} {}", game(*id, groups, teams), score(pred)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{}\n{}",
+        markup.bold(&format!("Predictions: {}", player)),
+        markup.code_block(&lines)
+    )
+}
+
+/// Response to a submitted prediction
+pub fn submitted(
+    pred: &Prediction,
+    report: &PredictionReport,
+    groups: &Groups,
+    teams: &Teams,
+    markup: Markup,
+) -> String {
+    let Prediction(id, pred) = pred;
+    let text = match &report.status {
+        PredictionStatus::Accepted => {
+            format!("Saved: {} {}", game(*id, groups, teams), score(pred))
+        }
+        PredictionStatus::Rejected { message, .. } => format!("Rejected: {}", message),
+    };
+    markup.text(&text)
+}
+
+/// Confirmation of a result entered as text, see [`Api::post_result`](crate::api::Api::post_result)
+pub fn entered(id: GameId, groups: &Groups, teams: &Teams, markup: Markup) -> String {
+    let text = groups
+        .values()
+        .flat_map(|group| group.played_games())
+        .find(|game| game.id == id)
+        .map(|played| {
+            format!(
+                "Saved: {} {}",
+                game(id, groups, teams),
+                score(&played.score)
+            )
+        })
+        .unwrap_or_else(|| format!("Saved: {}", game(id, groups, teams)));
+    markup.text(&text)
+}
+
+pub fn result(game: &PlayedGroupGame, teams: &Teams) -> String {
+    format!(
+        ":soccer: Full time: {} {} {}",
+        team_name(game.home, teams),
+        score(&game.score),
+        team_name(game.away, teams)
+    )
+}
+
+fn score(score: &Score) -> String {
+    format!("{}-{}", score.home, score.away)
+}
+
+/// Short game description, e.g. "SWE - ENG"
+fn game(id: GameId, groups: &Groups, teams: &Teams) -> String {
+    game_teams(id, groups)
+        .map(|(home, away)| format!("{} - {}", team_code(home, teams), team_code(away, teams)))
+        .unwrap_or_else(|| format!("Game {}", id))
+}
+
+fn game_teams(id: GameId, groups: &Groups) -> Option<(TeamId, TeamId)> {
+    groups.values().find_map(|group| {
+        group
+            .played_games()
+            .find(|game| game.id == id)
+            .map(|game| (game.home, game.away))
+            .or_else(|| {
+                group
+                    .unplayed_games()
+                    .find(|game| game.id == id)
+                    .map(|game| (game.home, game.away))
+            })
+    })
+}
+
+fn team_name(id: TeamId, teams: &Teams) -> String {
+    teams
+        .get(&id)
+        .map(|team| team.name.to_string())
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn team_code(id: TeamId, teams: &Teams) -> String {
+    teams
+        .get(&id)
+        .map(|team| team.fifa_code.to_string())
+        .unwrap_or_else(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_escaped() {
+        let rows = vec![LeaderboardRow {
+            rank: 1,
+            player_id: 1,
+            player: String::from("<script>"),
+            points: 3.0,
+            exact_scores: 0,
+            max_points: 10.0,
+            eliminated: false,
+        }];
+        let html = leaderboard(&rows, Markup::Html);
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(leaderboard(&rows, Markup::Markdown).contains("<script>"));
+    }
+}
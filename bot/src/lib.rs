@@ -0,0 +1,10 @@
+//! Chat bots for the betting pool
+//!
+//! The server api client and message formatting shared by the Discord (`wwc_bot`) and
+//! Telegram (`wwc_telegram`) bots.
+#![forbid(unsafe_code)]
+pub mod announce;
+pub mod api;
+pub mod format;
+pub mod link;
+pub mod lookup;
@@ -0,0 +1,121 @@
+//! Chat users linked to players
+//!
+//! A chat user is linked to a player once, with a personal login link of the player, created by
+//! an admin with `POST /players/<id>/login-link`. The bot logs in with the link and keeps the
+//! session, predictions of the user are submitted with it, see
+//! [`Api::login`](crate::api::Api::login).
+//! Users are identified by their numeric chat id, which unlike a username can neither be changed
+//! nor taken by someone else.
+//!
+//! The links are stored in a JSON file, to survive a restart of the bot.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+use wwc_core::player::PlayerId;
+use wwc_core::Date;
+
+/// Player of a chat user, with the session of the player
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkedPlayer {
+    pub player: PlayerId,
+    /// Value of the session cookie
+    pub session: String,
+    pub expires_at: Date,
+}
+
+/// Chat users linked to players, by chat user id
+#[derive(Debug, Default)]
+pub struct ChatLinks {
+    path: Option<PathBuf>,
+    links: HashMap<u64, LinkedPlayer>,
+}
+
+impl ChatLinks {
+    /// Links stored in the file at `path`, none if there is no file yet
+    pub fn load(path: PathBuf) -> Result<Self, LinkError> {
+        let links = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(ChatLinks {
+            path: Some(path),
+            links,
+        })
+    }
+
+    /// Player of the chat `user`, as long as the session lasts
+    pub fn get(&self, user: u64, now: Date) -> Result<&LinkedPlayer, LinkError> {
+        let linked = self.links.get(&user).ok_or(LinkError::NotLinked)?;
+        if now.is_before(linked.expires_at) {
+            Ok(linked)
+        } else {
+            Err(LinkError::Expired(linked.expires_at))
+        }
+    }
+
+    /// Link the chat `user` to a player, replacing any previous link of the user
+    pub fn insert(&mut self, user: u64, linked: LinkedPlayer) -> Result<(), LinkError> {
+        self.links.insert(user, linked);
+        if let Some(path) = &self.path {
+            fs::write(path, serde_json::to_string(&self.links)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Token of a login link, given as the token or as the whole link
+pub fn login_token(link: &str) -> &str {
+    link.trim()
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+}
+
+#[derive(Error, Debug)]
+pub enum LinkError {
+    #[error("Link your account first, with /link and your personal login link")]
+    NotLinked,
+    #[error("Your link expired at {0}, link your account again with a new login link")]
+    Expired(Date),
+    #[error("Could not store the link: {0}")]
+    Io(#[from] io::Error),
+    #[error("Could not read the stored links: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_from_link() {
+        assert_eq!(login_token("1.1700000000.ab12"), "1.1700000000.ab12");
+        assert_eq!(
+            login_token(" https://wwc.example/api/v1/login/1.1700000000.ab12 "),
+            "1.1700000000.ab12"
+        );
+    }
+
+    #[test]
+    fn unlinked_and_expired() {
+        let now = Date::mock();
+        let mut links = ChatLinks::default();
+        assert!(matches!(links.get(7, now), Err(LinkError::NotLinked)));
+        let linked = LinkedPlayer {
+            player: PlayerId::from(1),
+            session: String::from("1.1700000000.ab12"),
+            expires_at: now.add_hours(1),
+        };
+        links.insert(7, linked.clone()).unwrap();
+        assert_eq!(links.get(7, now).unwrap(), &linked);
+        assert!(matches!(
+            links.get(7, now.add_hours(2)),
+            Err(LinkError::Expired(_))
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+//! Look up groups from free text
+//!
+//! Chat users type "A", "sweden" or "Sverige" rather than ids.
+//! Team names are resolved like the server does it, see [`Api::team_resolver`](crate::api::Api::team_resolver).
+use thiserror::Error;
+use wwc_core::group::{GroupId, Groups};
+use wwc_core::team::resolve::{ResolveError, TeamResolver};
+
+/// Group named by `query`, either a group id, e.g. "A", or a team in the group, e.g. "Sweden"
+///
+/// A single letter is taken to be a group id if there is such a group.
+pub fn group_of(
+    query: &str,
+    groups: &Groups,
+    teams: &TeamResolver,
+) -> Result<GroupId, LookupError> {
+    let query = query.trim();
+    let mut chars = query.chars();
+    if let (Some(id), None) = (chars.next(), chars.next()) {
+        if let Ok(id) = GroupId::try_new(id) {
+            let id = id.into_uppercase();
+            if groups.contains_key(&id) {
+                return Ok(id);
+            }
+        }
+    }
+    let team = teams.resolve(query)?;
+    groups
+        .iter()
+        .find(|(_, group)| group.contains(team))
+        .map(|(id, _)| *id)
+        .ok_or_else(|| LookupError::NoGroup(String::from(query)))
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum LookupError {
+    #[error("{0}")]
+    Resolve(#[from] ResolveError),
+    #[error("'{0}' is not in any group")]
+    NoGroup(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wwc_core::group::mock_data;
+    use wwc_core::team::TeamId;
+
+    #[test]
+    fn group_or_team() {
+        let (groups, teams) = mock_data();
+        let resolver = TeamResolver::new(&teams).with_aliases(vec![("Sverige", TeamId(1))]);
+        let group_a = GroupId::try_new('A').unwrap();
+        let group_b = GroupId::try_new('B').unwrap();
+        assert_eq!(group_of("a", &groups, &resolver), Ok(group_a));
+        assert_eq!(group_of(" B ", &groups, &resolver), Ok(group_b));
+        assert_eq!(group_of("sverige", &groups, &resolver), Ok(group_a));
+        assert_eq!(group_of("ESP", &groups, &resolver), Ok(group_b));
+        assert!(matches!(
+            group_of("Xanadu", &groups, &resolver),
+            Err(LookupError::Resolve(_))
+        ));
+    }
+}
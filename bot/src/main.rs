@@ -0,0 +1,243 @@
+//! Discord bot for the betting pool
+//!
+//! Slash commands for standings, the leaderboard and personal predictions,
+//! entering results for server administrators, and (optionally) announcements of new results
+//! in a channel.
+//! Discord users link their account to a player once, with `/link` and a personal login link of
+//! the player, see [`wwc_bot::link`]. Commands about the player of the user reject unlinked
+//! users.
+//!
+//! Configured with environment variables:
+//!
+//! - `DISCORD_TOKEN`: bot token (required)
+//! - `WWC_API_URL`: url of the `wwc_server` api (default `http://localhost:8000`)
+//! - `WWC_API_KEY`: API key sent to the server (optional), an admin key to enter results
+//! - `WWC_LINKS_FILE`: file storing the linked users (default `wwc_discord_links.json`)
+//! - `WWC_ANNOUNCE_CHANNEL`: channel id for result announcements (optional)
+//! - `WWC_POLL_INTERVAL`: seconds between polls for new results (default 60)
+#![forbid(unsafe_code)]
+use poise::serenity_prelude as serenity;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use thiserror::Error;
+use wwc_bot::announce;
+use wwc_bot::api::{Api, ApiError};
+use wwc_bot::format::{self, Markup};
+use wwc_bot::link::{login_token, ChatLinks, LinkError};
+use wwc_bot::lookup::{self, LookupError};
+use wwc_core::Date;
+
+const DEFAULT_API_URL: &str = "http://localhost:8000";
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+const DEFAULT_LINKS_FILE: &str = "wwc_discord_links.json";
+
+struct Data {
+    api: Api,
+    links: Mutex<ChatLinks>,
+}
+
+type Context<'a> = poise::Context<'a, Data, BotError>;
+
+/// Show the table of a group
+#[poise::command(slash_command)]
+async fn standings(
+    ctx: Context<'_>,
+    #[description = "Group id or team, e.g. A or Sweden"] group: String,
+) -> Result<(), BotError> {
+    let api = &ctx.data().api;
+    let group = lookup::group_of(&group, &api.groups().await?, &api.team_resolver().await?)?;
+    let group = group.to_string();
+    let rows = api.group_standings(&group).await?;
+    ctx.say(format::standings(&group, &rows, Markup::Markdown))
+        .await?;
+    Ok(())
+}
+
+/// Show the betting pool leaderboard
+#[poise::command(slash_command)]
+async fn leaderboard(ctx: Context<'_>) -> Result<(), BotError> {
+    let rows = ctx.data().api.leaderboard().await?;
+    ctx.say(format::leaderboard(&rows, Markup::Markdown))
+        .await?;
+    Ok(())
+}
+
+/// Link your Discord account to your player
+///
+/// With the personal login link of the player, the response is private.
+#[poise::command(slash_command, ephemeral)]
+async fn link(
+    ctx: Context<'_>,
+    #[description = "Your personal login link"] link: String,
+) -> Result<(), BotError> {
+    let api = &ctx.data().api;
+    let linked = api.login(login_token(&link)).await?;
+    let player = linked.player;
+    ctx.data()
+        .links
+        .lock()
+        .expect("Links lock poisoned")
+        .insert(ctx.author().id.get(), linked)?;
+    let name = api
+        .player(player)
+        .await?
+        .map_or_else(|| player.to_string(), |found| String::from(found.name()));
+    ctx.say(Markup::Markdown.text(&format!("Linked to player {}", name)))
+        .await?;
+    Ok(())
+}
+
+/// Show your predictions
+///
+/// Only for users who linked their account to a player, see `/link`.
+#[poise::command(slash_command)]
+async fn mypredictions(ctx: Context<'_>) -> Result<(), BotError> {
+    let player = ctx
+        .data()
+        .links
+        .lock()
+        .expect("Links lock poisoned")
+        .get(ctx.author().id.get(), Date::now())?
+        .player;
+    let api = &ctx.data().api;
+    let (preds, groups, teams, found) = tokio::try_join!(
+        api.preds(player),
+        api.groups(),
+        api.teams(),
+        api.player(player)
+    )?;
+    let name = found.map_or_else(|| player.to_string(), |found| String::from(found.name()));
+    // Predictions can contain a full tournament, keep the response private.
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format::predictions(
+                &name,
+                &preds,
+                &groups,
+                &teams,
+                Markup::Markdown,
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Enter the result of a group game, with its cards
+///
+/// Only for administrators of the server, the bot needs an admin API key.
+#[poise::command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn result(
+    ctx: Context<'_>,
+    #[description = "Result, e.g. Sweden 3-0 England (yellow: swe 2)"] text: String,
+) -> Result<(), BotError> {
+    let api = &ctx.data().api;
+    let game = api.post_result(&text).await?;
+    let (groups, teams) = tokio::try_join!(api.groups(), api.teams())?;
+    ctx.say(format::entered(game, &groups, &teams, Markup::Markdown))
+        .await?;
+    Ok(())
+}
+
+async fn on_error(error: poise::FrameworkError<'_, Data, BotError>) {
+    match error {
+        poise::FrameworkError::Command { error, ctx, .. } => {
+            if let Err(err) = ctx.say(error.to_string()).await {
+                eprintln!("Failed to report error: {}", err);
+            }
+        }
+        error => {
+            if let Err(err) = poise::builtins::on_error(error).await {
+                eprintln!("Error while handling error: {}", err);
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), BotError> {
+    let token = std::env::var("DISCORD_TOKEN").map_err(|_| BotError::Config("DISCORD_TOKEN"))?;
+    let api_url = std::env::var("WWC_API_URL").unwrap_or_else(|_| String::from(DEFAULT_API_URL));
+    let api_key = std::env::var("WWC_API_KEY").ok();
+    let links_file =
+        std::env::var("WWC_LINKS_FILE").unwrap_or_else(|_| String::from(DEFAULT_LINKS_FILE));
+    let links = Mutex::new(ChatLinks::load(PathBuf::from(links_file))?);
+    let channel = std::env::var("WWC_ANNOUNCE_CHANNEL")
+        .ok()
+        .map(|id| id.parse().map(serenity::ChannelId::new))
+        .transpose()
+        .map_err(|_| BotError::Config("WWC_ANNOUNCE_CHANNEL"))?;
+    let interval = std::env::var("WWC_POLL_INTERVAL")
+        .ok()
+        .map(|secs| secs.parse())
+        .transpose()
+        .map_err(|_| BotError::Config("WWC_POLL_INTERVAL"))?
+        .map_or(
+            Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS),
+            Duration::from_secs,
+        );
+
+    let framework = poise::Framework::builder()
+        .options(poise::FrameworkOptions {
+            commands: vec![
+                standings(),
+                leaderboard(),
+                link(),
+                mypredictions(),
+                result(),
+            ],
+            on_error: |error| Box::pin(on_error(error)),
+            ..Default::default()
+        })
+        .setup(move |ctx, _ready, framework| {
+            Box::pin(async move {
+                poise::builtins::register_globally(ctx, &framework.options().commands).await?;
+                let api = match api_key {
+                    Some(key) => Api::new(&api_url).with_api_key(key),
+                    None => Api::new(&api_url),
+                };
+                if let Some(channel) = channel {
+                    tokio::spawn(announce::announce_results(
+                        ctx.http.clone(),
+                        api.clone(),
+                        channel,
+                        interval,
+                    ));
+                }
+                Ok(Data { api, links })
+            })
+        })
+        .build();
+
+    serenity::ClientBuilder::new(token, serenity::GatewayIntents::non_privileged())
+        .framework(framework)
+        .await?
+        .start()
+        .await?;
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum BotError {
+    #[error("Missing or invalid config: {0}")]
+    Config(&'static str),
+    #[error("{0}")]
+    Link(#[from] LinkError),
+    #[error("{0}")]
+    Api(#[from] ApiError),
+    #[error("{0}")]
+    Lookup(#[from] LookupError),
+    #[error("Discord: {0}")]
+    Discord(Box<serenity::Error>),
+}
+
+impl From<serenity::Error> for BotError {
+    fn from(err: serenity::Error) -> Self {
+        BotError::Discord(Box::new(err))
+    }
+}
@@ -1,33 +1,78 @@
 #![forbid(unsafe_code)]
+mod report;
+mod static_site;
 use itertools::Itertools;
+use report::{Report, ReportFormat};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 use thiserror::Error;
+use wwc_core::api_key::Scope;
+use wwc_core::auth::{LoginSecret, LoginToken, TokenKind, LOGIN_SECRET_VAR};
 use wwc_core::error::WwcError;
+use wwc_core::export::{self, GroupStandings};
+use wwc_core::fair_play::CardEvent;
 use wwc_core::game::GameId;
-use wwc_core::group::{Group, GroupId};
-use wwc_core::team::Team;
+use wwc_core::goal_model::PoissonModel;
+use wwc_core::group::order::{audit_order, rules_by_name};
+use wwc_core::group::schedule::{assign_final_rounds, check_schedule, final_round};
+use wwc_core::group::withdrawal::WithdrawalChange;
+use wwc_core::group::{Group, GroupId, Groups};
+use wwc_core::odds::Odds;
+use wwc_core::parse::result::parse_result;
+use wwc_core::parse::result_csv::{in_kickoff_order, parse_result_csv};
+use wwc_core::player::PlayerId;
+use wwc_core::team::i18n::{Lang, LangError};
+use wwc_core::team::{Team, TeamId, Teams};
+use wwc_core::Date;
 use wwc_data::lsv::get_data;
 use wwc_data::lsv::LsvParseError;
 use wwc_data::lsv::{Euro2021Data, Fifa2018Data, LsvData};
+use wwc_data::template::Template;
+use wwc_db::backup::Snapshot;
 
 type Tournament = Euro2021Data;
 const DATA_PATH: &str = "data/tests/data/euro-2021.json";
+/// Overrides [`DATA_PATH`], e.g. `data/tests/data/wc-2026.json` for the WC 2026 template
+const DATA_PATH_VAR: &str = "WWC_DATA_PATH";
 
 fn main() -> Result<(), CliError> {
     let opt = Opt::from_args();
     match opt {
         Opt::Register(new_instance) => match new_instance {
             Instance::Player { name } => register_player(name),
+            Instance::Tournament {
+                name,
+                sandbox,
+                rules,
+            } => Ok(wwc_db::tournament::register_tournament(
+                &name,
+                sandbox,
+                rules.map(|template| template.rules()).as_ref(),
+            )?),
+            Instance::League { name, rules } => register_league(&name, rules.as_deref()),
+            Instance::Member {
+                league,
+                player,
+                joined_at,
+            } => Ok(wwc_db::league::add_member(
+                league,
+                PlayerId::from(player),
+                joined_at.unwrap_or_else(Date::now),
+            )?),
         },
         Opt::Add(table) => match table {
             Table::Players => Ok(()),
             Table::Teams => add_teams(),
             Table::Games => add_games(),
             Table::GroupGameMaps => add_groups(),
+            Table::Bracket => add_bracket(),
             Table::All => {
                 add_teams()?;
                 add_games()?;
-                add_groups()
+                add_groups()?;
+                add_bracket()
             }
         },
         Opt::List(table) => match table {
@@ -35,33 +80,144 @@ fn main() -> Result<(), CliError> {
             Table::Teams => list_teams(),
             Table::Games => list_games(),
             Table::GroupGameMaps => list_group_maps(),
+            Table::Bracket => list_bracket(),
             Table::All => {
                 list_players()?;
                 list_teams()?;
                 list_games()?;
-                list_group_maps()
+                list_group_maps()?;
+                list_bracket()
             }
         },
+        Opt::Calibrate => calibrate_goal_model(),
+        Opt::AuditOrder { group, rules, runs } => audit_group_order(group, rules, runs),
+        Opt::Standings { group, rules } => print_standings(group, rules),
+        Opt::Result { text } => enter_result(&text),
+        Opt::ImportResults { file, skip_invalid } => import_results(&file, skip_invalid),
+        Opt::ArchiveTournament { id } => {
+            let num_players = wwc_db::hall_of_fame::archive_tournament(id)?;
+            println!("Archived the points of {} players", num_players);
+            let num_players = wwc_db::strategy::archive_strategies(id)?;
+            println!("Archived the strategies of {} players", num_players);
+            Ok(())
+        }
+        Opt::Invite { league, hours } => {
+            let invite = wwc_db::invite::create_invite(league, Date::now().add_hours(hours))?;
+            println!("{} (expires at {})", invite.token, invite.expires_at);
+            Ok(())
+        }
+        Opt::LoginLink { player, hours } => login_link(player, hours),
+        Opt::ApiKey { name, scope } => {
+            let (key, _) = wwc_db::api_key::create_api_key(&name, scope, Date::now())?;
+            println!("{}", key);
+            Ok(())
+        }
+        Opt::RefreshScores => {
+            let num_games = wwc_db::score::refresh_scores()?;
+            println!("Scored {} games", num_games);
+            let num_groups = wwc_db::standings::refresh_standings()?;
+            println!("Stored the standings of {} groups", num_groups);
+            let num_groups = wwc_db::standings_history::refresh_standings_history()?;
+            println!("Stored the standings history of {} groups", num_groups);
+            Ok(())
+        }
+        Opt::Replay { restore } => replay(restore),
+        Opt::TeamNames { lang, file } => add_team_names(&lang, &file),
+        Opt::Odds { file } => import_odds(&file),
+        Opt::Cards { file } => import_cards(&file),
+        Opt::Alias(cmd) => match cmd {
+            AliasCmd::Set { alias, team } => set_alias(&alias, &team),
+            AliasCmd::Remove { alias } => Ok(wwc_db::team_alias::remove_team_alias(&alias)?),
+            AliasCmd::List => list_aliases(),
+        },
+        Opt::PlayoffWinner { game, team } => Ok(wwc_db::playoff::set_playoff_winner(
+            GameId::from(game),
+            team.map(TeamId),
+        )?),
+        Opt::Withdraw { team } => withdraw_team(&team),
+        Opt::Report { format } => {
+            println!("{}", Report::load()?.render(format)?);
+            Ok(())
+        }
+        Opt::ExportStatic { dir } => static_site::export_static(&dir),
+        Opt::Seed { template } => seed(template),
+        Opt::ScheduleFinalRounds {
+            slots,
+            max_per_day,
+            dry_run,
+        } => schedule_final_rounds(&slots, max_per_day, dry_run),
+        Opt::Backup { file } => backup(&file),
+        Opt::Restore { file } => restore(&file),
         Opt::Clear(table) => match table {
-            Table::Players => Ok(wwc_db::clear_players()?),
-            Table::Teams => Ok(wwc_db::clear_teams()?),
-            Table::Games => Ok(wwc_db::clear_games()?),
+            Table::Players => {
+                wwc_db::invite::clear_invites()?;
+                wwc_db::hall_of_fame::clear_tournament_results()?;
+                wwc_db::strategy::clear_strategies()?;
+                wwc_db::achievement::clear_achievements()?;
+                wwc_db::score::clear_scores()?;
+                Ok(wwc_db::clear_players()?)
+            }
+            Table::Teams => {
+                wwc_db::withdrawal::clear_withdrawals()?;
+                wwc_db::team_alias::clear_team_aliases()?;
+                wwc_db::team_name::clear_team_names()?;
+                Ok(wwc_db::clear_teams()?)
+            }
+            Table::Games => {
+                wwc_db::withdrawal::clear_withdrawals()?;
+                wwc_db::venue::clear_venues()?;
+                wwc_db::odds::clear_odds()?;
+                wwc_db::live::clear_live_scores()?;
+                wwc_db::fair_play::clear_card_events()?;
+                wwc_db::score::clear_scores()?;
+                wwc_db::standings::clear_standings()?;
+                wwc_db::standings_history::clear_standings_history()?;
+                wwc_db::last_result::clear_last_results()?;
+                wwc_db::event::clear_events()?;
+                Ok(wwc_db::clear_games()?)
+            }
             Table::GroupGameMaps => Ok(wwc_db::clear_group_game_maps()?),
+            Table::Bracket => Ok(wwc_db::playoff::clear_bracket()?),
             Table::All => {
+                wwc_db::team_alias::clear_team_aliases()?;
+                wwc_db::team_name::clear_team_names()?;
+                wwc_db::standings::clear_standings()?;
+                wwc_db::standings_history::clear_standings_history()?;
+                wwc_db::withdrawal::clear_withdrawals()?;
                 wwc_db::clear_teams()?;
+                wwc_db::venue::clear_venues()?;
+                wwc_db::odds::clear_odds()?;
+                wwc_db::live::clear_live_scores()?;
+                wwc_db::fair_play::clear_card_events()?;
+                wwc_db::score::clear_scores()?;
+                wwc_db::last_result::clear_last_results()?;
+                wwc_db::event::clear_events()?;
                 wwc_db::clear_games()?;
-                Ok(wwc_db::clear_group_game_maps()?)
+                wwc_db::clear_group_game_maps()?;
+                Ok(wwc_db::playoff::clear_bracket()?)
             }
         },
     }
 }
 
+fn data_path() -> String {
+    std::env::var(DATA_PATH_VAR).unwrap_or_else(|_| String::from(DATA_PATH))
+}
+
 fn register_player(name: String) -> Result<(), CliError> {
     Ok(wwc_db::register_player(&name)?)
 }
 
+fn register_league(name: &str, rules: Option<&str>) -> Result<(), CliError> {
+    let rules = match rules {
+        Some(rules) => serde_json::from_str(rules)?,
+        None => wwc_db::tournament::get_active_rules()?.scoring,
+    };
+    Ok(wwc_db::league::register_league(name, &rules)?)
+}
+
 fn add_teams() -> Result<(), CliError> {
-    let teams = get_data::<Tournament>(DATA_PATH)?
+    let teams = get_data::<Tournament>(&data_path())?
         .try_teams()?
         .values()
         .cloned()
@@ -70,11 +226,10 @@ fn add_teams() -> Result<(), CliError> {
 }
 
 fn add_games() -> Result<(), CliError> {
-    let groups = get_data::<Tournament>(DATA_PATH)?
-        .try_groups()?
-        .values()
-        .cloned()
-        .collect::<Vec<Group>>();
+    let data = get_data::<Tournament>(&data_path())?;
+    let groups = data.try_groups()?;
+    warn_schedule(&groups);
+    let groups = groups.into_values().collect::<Vec<Group>>();
 
     let unplayed_games: Vec<_> = groups
         .iter()
@@ -88,25 +243,382 @@ fn add_games() -> Result<(), CliError> {
         .cloned()
         .collect();
     wwc_db::insert_games(&played_games)?;
+    wwc_db::venue::insert_game_venues(&data.try_game_venues()?)?;
     Ok(())
 }
 
 fn add_groups() -> Result<(), CliError> {
-    let groups = get_data::<Tournament>(DATA_PATH)?.try_groups()?;
+    let groups = get_data::<Tournament>(&data_path())?.try_groups()?;
 
     let group_games: Vec<(GroupId, GameId)> = groups
         .iter()
-        .flat_map(move |(id, group)| {
-            group
-                .played_games()
-                .map(move |game| (*id, game.id))
-                .chain(group.unplayed_games().map(move |game| (*id, game.id)))
-        })
+        .flat_map(move |(id, group)| group.games().map(move |game| (*id, game.id())))
         .collect();
     wwc_db::insert_group_game_mappings(&group_games)?;
     Ok(())
 }
 
+/// Parse a result and store it, with its cards, for the matching unplayed game
+fn enter_result(text: &str) -> Result<(), CliError> {
+    let teams = wwc_db::team_alias::get_team_resolver()?;
+    let groups = wwc_db::get_groups()?;
+    let submission = parse_result(text, &teams)
+        .and_then(|entry| entry.submission(&groups))
+        .map_err(|err| CliError::Invalid(err.to_string()))?;
+    wwc_db::play_game_with_cards(submission.game_id, submission.score, submission.fair_play)?;
+    println!(
+        "Game {}: {}-{}",
+        submission.game_id, submission.score.home, submission.score.away
+    );
+    let num_badges = wwc_db::achievement::refresh_achievements()?;
+    if num_badges > 0 {
+        println!("{} new achievements", num_badges);
+    }
+    Ok(())
+}
+
+/// Validate every row of a CSV file and store the results in kickoff order
+fn import_results(file: &Path, skip_invalid: bool) -> Result<(), CliError> {
+    let teams = wwc_db::team_alias::get_team_resolver()?;
+    let groups = wwc_db::get_groups()?;
+    let rows = parse_result_csv(&fs::read_to_string(file)?, &teams, &groups);
+    for row in &rows {
+        match &row.result {
+            Ok(result) => println!(
+                "Row {}: Game {}: {}-{}",
+                row.row,
+                result.submission.game_id,
+                result.submission.score.home,
+                result.submission.score.away
+            ),
+            Err(err) => println!("Row {}: {}", row.row, err),
+        }
+    }
+    let num_invalid = rows.iter().filter(|row| row.result.is_err()).count();
+    if num_invalid > 0 && !skip_invalid {
+        return Err(CliError::Invalid(format!(
+            "{} invalid rows, nothing stored",
+            num_invalid
+        )));
+    }
+    let results = in_kickoff_order(&rows);
+    wwc_db::result_import::import_results(&results)?;
+    println!("Stored {} results", results.len());
+    let num_badges = wwc_db::achievement::refresh_achievements()?;
+    if num_badges > 0 {
+        println!("{} new achievements", num_badges);
+    }
+    Ok(())
+}
+
+/// Print a login link for a player, see [`wwc_core::auth`]
+fn login_link(player: i32, hours: u32) -> Result<(), CliError> {
+    let secret = LoginSecret::from_env()
+        .ok_or_else(|| CliError::Invalid(format!("{} is not set", LOGIN_SECRET_VAR)))?;
+    if !wwc_db::get_players()?
+        .iter()
+        .any(|stored| stored.id == player)
+    {
+        return Err(CliError::NotFound(format!("No player with id {}", player)));
+    }
+    let token = LoginToken::new(PlayerId::from(player), Date::now().add_hours(hours));
+    println!(
+        "/api/v1/login/{} (expires at {})",
+        token.sign(TokenKind::Link, &secret),
+        token.expires_at
+    );
+    Ok(())
+}
+
+/// Store an alias for a team, the team itself may be given by any name the resolver knows
+fn set_alias(alias: &str, team: &str) -> Result<(), CliError> {
+    let team = wwc_db::team_alias::get_team_resolver()?
+        .resolve(team)
+        .map_err(|err| CliError::Invalid(err.to_string()))?;
+    Ok(wwc_db::team_alias::set_team_alias(alias, team)?)
+}
+
+/// Withdraw a team and print the changed games
+fn withdraw_team(team: &str) -> Result<(), CliError> {
+    let team = wwc_db::team_alias::get_team_resolver()?
+        .resolve(team)
+        .map_err(|err| CliError::Invalid(err.to_string()))?;
+    for change in wwc_db::withdrawal::withdraw_team(team)? {
+        match change {
+            WithdrawalChange::Annulled { game } => println!("Game {}: annulled", game),
+            WithdrawalChange::Forfeited { game, score } => {
+                println!("Game {}: {}-{} (forfeit)", game, score.home, score.away)
+            }
+        }
+    }
+    Ok(())
+}
+
+fn add_team_names(lang: &str, file: &Path) -> Result<(), CliError> {
+    let lang: Lang = lang
+        .parse()
+        .map_err(|err: LangError| CliError::Invalid(err.to_string()))?;
+    let names: HashMap<String, String> = serde_json::from_str(&fs::read_to_string(file)?)?;
+    let teams = wwc_db::team_alias::get_team_resolver()?;
+    // Resolve every team before storing anything
+    let names = names
+        .iter()
+        .map(|(team, name)| teams.resolve(team).map(|team| (team, name)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| CliError::Invalid(err.to_string()))?;
+    for (team, name) in names {
+        wwc_db::team_name::set_team_name(team, &lang, name)?;
+    }
+    Ok(())
+}
+
+/// Import decimal odds from a JSON file, e.g. '{"1": {"home": 1.8, "draw": 3.4, "away": 4.5}}'
+fn import_odds(file: &Path) -> Result<(), CliError> {
+    let odds: HashMap<u32, Odds> = serde_json::from_str(&fs::read_to_string(file)?)?;
+    let odds: Vec<(GameId, Odds)> = odds
+        .into_iter()
+        .map(|(game, odds)| (GameId::from(game), odds))
+        .collect();
+    let num_games = wwc_db::odds::import_odds(&odds)?;
+    println!("Imported odds for {} games", num_games);
+    Ok(())
+}
+
+/// Import card events per game from a JSON file
+fn import_cards(file: &Path) -> Result<(), CliError> {
+    let cards: HashMap<u32, Vec<CardEvent>> = serde_json::from_str(&fs::read_to_string(file)?)?;
+    for (game, events) in &cards {
+        wwc_db::fair_play::set_card_events(GameId::from(*game), events)?;
+    }
+    println!("Imported cards for {} games", cards.len());
+    Ok(())
+}
+
+fn list_aliases() -> Result<(), CliError> {
+    let teams: Teams = wwc_db::get_teams()?.map(|team| (team.id, team)).collect();
+    wwc_db::team_alias::get_team_aliases()?
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .for_each(|(alias, team)| match teams.get(&team) {
+            Some(team) => println!("{}: {} ({})", alias, team.name, team.fifa_code),
+            None => println!("{}: unknown team {}", alias, team),
+        });
+    Ok(())
+}
+
+fn seed(template: Template) -> Result<(), CliError> {
+    let rules = match wwc_db::tournament::get_active_id()? {
+        Some(_) => wwc_db::tournament::get_active_rules()?,
+        None => template.rules(),
+    };
+    let data = template.load()?.with_bracket_of(&rules)?;
+    warn_schedule(&data.groups);
+    wwc_db::seed::seed(
+        &data.teams,
+        &data.groups,
+        data.bracket.as_ref(),
+        &data.venues,
+    )?;
+    println!(
+        "Seeded {}: {} teams, {} groups",
+        template,
+        data.teams.len(),
+        data.groups.len()
+    );
+    Ok(())
+}
+
+/// Print a warning for every group whose final round is not simultaneous
+fn warn_schedule(groups: &Groups) {
+    let violations = check_schedule(groups);
+    for violation in &violations {
+        println!("Warning: {}", violation);
+    }
+    if !violations.is_empty() {
+        println!("Run 'schedule-final-rounds' to reschedule the final rounds");
+    }
+}
+
+/// Reschedule the final round of every group to a common kickoff slot
+///
+/// Without `slots`, the current kickoffs of the final round games are the candidate slots.
+fn schedule_final_rounds(
+    slots: &[Date],
+    max_per_day: usize,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let groups = wwc_db::get_groups()?;
+    let venues = wwc_db::venue::get_game_venues()?;
+    let slots: Vec<Date> = if slots.is_empty() {
+        groups
+            .values()
+            .flat_map(|group| {
+                final_round(group)
+                    .iter()
+                    .map(|game| game.date())
+                    .collect::<Vec<_>>()
+            })
+            .sorted()
+            .dedup()
+            .collect()
+    } else {
+        slots.to_vec()
+    };
+    let assigned =
+        assign_final_rounds(&groups, &venues, &slots, max_per_day).map_err(WwcError::from)?;
+    let now = Date::now();
+    for (id, kickoff) in assigned {
+        println!("Group {}: final round at {}", id, kickoff);
+        if dry_run {
+            continue;
+        }
+        for game in final_round(&groups[&id]) {
+            if game.date() != kickoff {
+                wwc_db::reschedule_game(game.id(), kickoff, now)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn add_bracket() -> Result<(), CliError> {
+    let bracket = get_data::<Tournament>(&data_path())?.try_bracket()?;
+    Ok(wwc_db::playoff::insert_bracket(&bracket)?)
+}
+
+fn list_bracket() -> Result<(), CliError> {
+    let bracket = wwc_db::playoff::get_bracket()?;
+    bracket
+        .games()
+        .sorted_by_key(|game| (game.round, game.id))
+        .for_each(|game| {
+            println!(
+                "Round {}, game {}: {} - {}",
+                game.round.0, game.id, game.home, game.away
+            )
+        });
+    Ok(())
+}
+
+/// Calibrate a Poisson goal model from the played games in the db and print it
+fn calibrate_goal_model() -> Result<(), CliError> {
+    let (played_games, _, _) = wwc_db::get_group_games()?;
+    let model = PoissonModel::calibrate(&played_games);
+    println!(
+        "Base rate: {:.2}, home advantage: {:.2}",
+        model.base_rate, model.home_advantage
+    );
+    model
+        .strengths
+        .iter()
+        .sorted_by_key(|(id, _)| id.0)
+        .for_each(|(id, strength)| {
+            println!(
+                "{}: attack {:.2}, defence {:.2}",
+                id, strength.attack, strength.defence
+            )
+        });
+    Ok(())
+}
+
+/// Check if the order of a group is decided by a random tiebreaker, i.e. a pending drawing of lots
+fn audit_group_order(group_id: char, rules: Option<String>, runs: usize) -> Result<(), CliError> {
+    let group_id = GroupId::try_new(group_id)
+        .map_err(WwcError::from)?
+        .into_uppercase();
+    let groups = wwc_db::get_groups()?;
+    let group = groups
+        .get(&group_id)
+        .ok_or_else(|| CliError::NotFound(format!("Group {}", group_id)))?;
+    let teams: HashMap<TeamId, Team> = wwc_db::get_teams()?.map(|team| (team.id, team)).collect();
+    let ranking = teams.values().map(|team| (team.id, team.rank)).collect();
+    let all_groups: Vec<Group> = groups.values().cloned().collect();
+    let rules = group_rules_or_active(rules)?;
+    let rules = rules_by_name(&rules, &all_groups, ranking).map_err(WwcError::from)?;
+    let audit = audit_order(group, &rules, runs);
+
+    let name = |id: &TeamId| {
+        teams
+            .get(id)
+            .map(|team| team.fifa_code.to_string())
+            .unwrap_or_else(|| id.to_string())
+    };
+    let names = |ids: &[TeamId]| ids.iter().map(name).join(", ");
+    println!("Group {}: {} runs", group_id, audit.runs);
+    for tie in &audit.ties {
+        println!("Tie decided by the tiebreaker: {}", names(tie));
+    }
+    for (order, count) in &audit.orders {
+        println!("{:>5} x {}", count, names(order));
+    }
+    if !audit.is_deterministic() {
+        println!("Order is not deterministic, a drawing of lots is pending.");
+    } else if audit.uses_tiebreaker() {
+        println!("Order is deterministic but decided by the tiebreaker.");
+    } else {
+        println!("Order is deterministic.");
+    }
+    Ok(())
+}
+
+/// Print the table of a group, or of every group
+/// Name of the group ordering rules, those of the active tournament if none is given
+fn group_rules_or_active(rules: Option<String>) -> Result<String, CliError> {
+    Ok(match rules {
+        Some(rules) => rules,
+        None => wwc_db::tournament::get_active_rules()?.group_rules,
+    })
+}
+
+fn print_standings(group_id: Option<char>, rules: Option<String>) -> Result<(), CliError> {
+    let group_id = group_id
+        .map(GroupId::try_new)
+        .transpose()
+        .map_err(WwcError::from)?
+        .map(GroupId::into_uppercase);
+    let groups = wwc_db::get_groups()?;
+    if let Some(id) = group_id {
+        if !groups.contains_key(&id) {
+            return Err(CliError::NotFound(format!("Group {}", id)));
+        }
+    }
+    let teams: HashMap<TeamId, Team> = wwc_db::get_teams()?.map(|team| (team.id, team)).collect();
+    let ranking = teams.values().map(|team| (team.id, team.rank)).collect();
+    let all_groups: Vec<Group> = groups.values().cloned().collect();
+    let rules = group_rules_or_active(rules)?;
+    let rules = rules_by_name(&rules, &all_groups, ranking).map_err(WwcError::from)?;
+    let tables = groups
+        .iter()
+        .filter(|(id, _)| group_id.is_none() || group_id == Some(**id))
+        .map(|(id, group)| {
+            let standings = GroupStandings::from(export::group_standings(group, &teams, &rules));
+            format!("Group {}\n{}", id, standings)
+        });
+    println!("{}", tables.format("\n"));
+    Ok(())
+}
+
+fn backup(file: &Path) -> Result<(), CliError> {
+    let snapshot = wwc_db::backup::backup()?;
+    fs::write(file, serde_json::to_string_pretty(&snapshot)?)?;
+    Ok(())
+}
+
+fn restore(file: &Path) -> Result<(), CliError> {
+    let snapshot: Snapshot = serde_json::from_str(&fs::read_to_string(file)?)?;
+    Ok(wwc_db::backup::restore(&snapshot)?)
+}
+
+fn replay(restore: bool) -> Result<(), CliError> {
+    if restore {
+        let num_games = wwc_db::replay::restore_from_events()?;
+        println!("Restored {} games from the event log", num_games);
+    }
+    let report = wwc_db::replay::check_replay()?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
 fn list_players() -> Result<(), CliError> {
     let players = wwc_db::get_players()?;
     // Very strange bug:
@@ -158,6 +670,181 @@ pub enum Opt {
     List(Table),
     #[structopt(name = "clear")]
     Clear(Table),
+    #[structopt(name = "calibrate")]
+    Calibrate,
+    /// Order a group repeatedly and report ties which are decided by the tiebreaker
+    #[structopt(name = "audit-order")]
+    AuditOrder {
+        /// Group id, e.g. 'A'
+        group: char,
+        /// Ordering rules, e.g. 'fifa_2018' or 'euro_2020', those of the active tournament if not
+        /// given
+        #[structopt(long)]
+        rules: Option<String>,
+        /// Number of times to order the group
+        #[structopt(long, default_value = "100")]
+        runs: usize,
+    },
+    /// Print group tables as aligned text, all groups if no group is given
+    #[structopt(name = "standings")]
+    Standings {
+        /// Group id, e.g. 'A'
+        group: Option<char>,
+        /// Ordering rules, e.g. 'fifa_2018' or 'euro_2020', those of the active tournament if not
+        /// given
+        #[structopt(long)]
+        rules: Option<String>,
+    },
+    /// Enter a group game result as text, e.g. "GER-FRA 2:1" or "Sweden 3-0 England (yellow: swe 2)"
+    #[structopt(name = "result")]
+    Result { text: String },
+    /// Enter group game results from a CSV file, e.g. to catch up after downtime
+    ///
+    /// Columns 'game,home,away,home_goals,away_goals,cards', where either the game id or the teams
+    /// may be left empty and cards are given as in `result`, e.g. "yellow: swe 2; red: eng 1".
+    /// Nothing is stored if a row is invalid, unless `--skip-invalid` is given.
+    #[structopt(name = "import-results")]
+    ImportResults {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+        /// Store the valid rows even if other rows are invalid
+        #[structopt(long)]
+        skip_invalid: bool,
+    },
+    /// Store the current points of every player as the final result of a tournament, see
+    /// `/hall-of-fame`, and how every player predicted, see `/players/<id>/preds/strategy`
+    ///
+    /// Archive a tournament before replacing its games with the next tournament.
+    #[structopt(name = "archive-tournament")]
+    ArchiveTournament { id: i32 },
+    /// Recompute the stored scores of every played game, see `/leaderboard/materialized`, the
+    /// standings of every group, see `/groups/<id>/standings/materialized`, and the standings
+    /// history of every group, see `/groups/<id>/standings/history`
+    RefreshScores,
+    /// Rebuild the results, standings and leaderboard from the event log and report any
+    /// difference to the stored state, see `/admin/events`
+    #[structopt(name = "replay")]
+    Replay {
+        /// Write the replayed results back to the games, e.g. to recover a lost games table
+        #[structopt(long)]
+        restore: bool,
+    },
+    /// Create an API key for an integration, prints the key
+    ///
+    /// Only the hash of the key is stored, the key can not be shown again.
+    /// Sent in the `X-Api-Key` header, an admin key is needed to manage keys over the API.
+    #[structopt(name = "api-key")]
+    ApiKey {
+        /// Description of the integration, e.g. 'Discord bot'
+        name: String,
+        /// 'read' or 'admin'
+        #[structopt(long, default_value = "read")]
+        scope: Scope,
+    },
+    /// Create a single-use invite to a league, prints the token to hand out
+    ///
+    /// New players register with the token at `/register` and join the league.
+    #[structopt(name = "invite")]
+    Invite {
+        league: i32,
+        /// Validity in hours
+        #[structopt(long, default_value = "168")]
+        hours: u32,
+    },
+    /// Create a personal login link for a player, signed with the secret in `WWC_LOGIN_SECRET`
+    ///
+    /// Prints the path of the link, append it to the address of the server and hand it out.
+    #[structopt(name = "login-link")]
+    LoginLink {
+        player: i32,
+        /// Validity in hours
+        #[structopt(long, default_value = "336")]
+        hours: u32,
+    },
+    /// Add translated team names from a JSON file, e.g. '{"GER": "Tyskland"}'
+    ///
+    /// Teams are given by name or Fifa code.
+    #[structopt(name = "team-names")]
+    TeamNames {
+        /// Language code, e.g. 'sv'
+        lang: String,
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Import decimal odds per game from a JSON file, e.g. '{"1": {"home": 1.8, "draw": 3.4, "away": 4.5}}'
+    ///
+    /// Replaces any previous odds of the games, see the `odds_bonus` of the league scoring rules.
+    #[structopt(name = "odds")]
+    Odds {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Import card events per game from a JSON file, e.g. '{"1": [{"team": 1, "player": "Chiellini", "minute": 72, "kind": "Yellow"}]}'
+    ///
+    /// Replaces any previous card events of the games, see the fair play ranking.
+    #[structopt(name = "cards")]
+    Cards {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Alternative team names, used when resolving teams in free text
+    #[structopt(name = "alias")]
+    Alias(AliasCmd),
+    /// Set the winner of a playoff game, clears the winner if no team is given
+    #[structopt(name = "playoff-winner")]
+    PlayoffWinner { game: u32, team: Option<u32> },
+    /// Withdraw a team from its group, its games are annulled or forfeited by the tournament
+    /// rules. The team may be given by any name the resolver knows.
+    #[structopt(name = "withdraw")]
+    Withdraw { team: String },
+    /// Print a full tournament report
+    #[structopt(name = "report")]
+    Report {
+        /// Output format: 'md' or 'html'
+        #[structopt(short, long, default_value = "md")]
+        format: ReportFormat,
+    },
+    /// Export standings, schedule and leaderboard as a static site
+    #[structopt(name = "export-static")]
+    ExportStatic {
+        /// Output directory
+        #[structopt(parse(from_os_str))]
+        dir: PathBuf,
+    },
+    /// Populate an empty database with a built in tournament template
+    ///
+    /// The bracket is taken from the bracket template of the active tournament rules.
+    #[structopt(name = "seed")]
+    Seed {
+        /// Template name: 'euro-2020', 'wc-2018', 'wc-2022' or 'wc-2026'
+        template: Template,
+    },
+    /// Reschedule the final round of every group to a common kickoff slot
+    ///
+    /// A venue hosts at most one game per day.
+    #[structopt(name = "schedule-final-rounds")]
+    ScheduleFinalRounds {
+        /// Candidate kickoff slots, defaults to the current kickoffs of the final rounds
+        #[structopt(long = "slot")]
+        slots: Vec<Date>,
+        #[structopt(long, default_value = "2")]
+        max_per_day: usize,
+        /// Print the assigned slots without rescheduling any game
+        #[structopt(long)]
+        dry_run: bool,
+    },
+    /// Write the entire database to a JSON snapshot
+    #[structopt(name = "backup")]
+    Backup {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Restore a JSON snapshot into an empty database
+    #[structopt(name = "restore")]
+    Restore {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
 }
 
 #[derive(Debug, StructOpt)]
@@ -165,6 +852,46 @@ pub enum Opt {
 pub enum Instance {
     #[structopt(name = "player")]
     Player { name: String },
+    #[structopt(name = "tournament")]
+    Tournament {
+        name: String,
+        /// Sandbox tournament, for testing with fake results
+        #[structopt(long)]
+        sandbox: bool,
+        /// Rules of a built in template: 'euro-2020', 'wc-2018', 'wc-2022' or 'wc-2026', default
+        /// rules if left out
+        #[structopt(long)]
+        rules: Option<Template>,
+    },
+    #[structopt(name = "league")]
+    League {
+        name: String,
+        /// Scoring rules as JSON, e.g. '{"exact": 5, "goal_diff": 3, "outcome": 1}', the scoring
+        /// of the tournament rules if left out
+        #[structopt(long)]
+        rules: Option<String>,
+    },
+    /// Add a player to a league
+    #[structopt(name = "member")]
+    Member {
+        league: i32,
+        player: i32,
+        /// Time the player joined, e.g. '2021-06-15 18:00', now if left out.
+        /// Played games before the join time are missed, see the late joiner scoring rule.
+        #[structopt(long)]
+        joined_at: Option<Date>,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+pub enum AliasCmd {
+    /// Add an alias for a team, given by name or Fifa code
+    #[structopt(name = "set")]
+    Set { alias: String, team: String },
+    #[structopt(name = "remove")]
+    Remove { alias: String },
+    #[structopt(name = "list")]
+    List,
 }
 
 #[derive(Debug, StructOpt)]
@@ -178,6 +905,8 @@ pub enum Table {
     Games,
     #[structopt(name = "group-game-maps")]
     GroupGameMaps,
+    #[structopt(name = "bracket")]
+    Bracket,
     #[structopt(name = "all")]
     All,
 }
@@ -190,4 +919,14 @@ pub enum CliError {
     WwcCore(#[from] WwcError),
     #[error("Parse: {0}")]
     Parse(#[from] LsvParseError),
+    #[error("Report: {0}")]
+    Report(#[from] askama::Error),
+    #[error("IO: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Invalid input: {0}")]
+    Invalid(String),
 }
@@ -0,0 +1,298 @@
+//! Tournament report
+//!
+//! End-of-tournament summary (group tables, knockout qualifiers, the playoff bracket, fun stats,
+//! top scorers, the fair play ranking and the leaderboard), rendered to Markdown or HTML with the templates in `cli/templates`.
+//! Tables and points follow the rules of the active tournament, like on the server.
+use crate::CliError;
+use askama::Template;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::str::FromStr;
+use wwc_core::error::WwcError;
+use wwc_core::export::{self, LeaderboardRow, StandingRow};
+use wwc_core::fair_play::fair_play_award;
+use wwc_core::group::stats::{fun_stats, GameHighlight};
+use wwc_core::group::{Group, Groups};
+use wwc_core::leaderboard::{knockout_standings, stage_standings, standings};
+use wwc_core::player::PlayerId;
+use wwc_core::playoff::bracket::{BracketNode, BracketTeam};
+use wwc_core::rules::TournamentRules;
+use wwc_core::team::{TeamId, Teams};
+use wwc_core::top_scorer::top_scorers;
+
+/// All data in the report, pre-formatted for the templates
+pub struct Report {
+    pub groups: Vec<GroupSection>,
+    pub qualifiers: Vec<Qualifiers>,
+    pub bracket: Vec<BracketRow>,
+    pub top_scorers: Vec<ScorerRow>,
+    pub fair_play: Vec<FairPlayRow>,
+    /// Winner of the fair play award, empty without played games
+    pub fair_play_award: String,
+    pub leaderboard: Vec<LeaderboardRow>,
+}
+
+pub struct GroupSection {
+    pub id: String,
+    pub standings: Vec<StandingRow>,
+    pub biggest_win: String,
+    pub most_goals: String,
+    pub clean_sheets: String,
+}
+
+pub struct Qualifiers {
+    pub group: String,
+    pub winner: String,
+    pub runner_up: String,
+}
+
+/// Playoff game, teams which are not decided yet are shown by their feeder, e.g. "1A"
+pub struct BracketRow {
+    pub round: String,
+    pub game: String,
+    pub home: String,
+    pub away: String,
+    /// "-" until the game is played
+    pub winner: String,
+}
+
+pub struct ScorerRow {
+    pub player: String,
+    pub team: String,
+    pub goals: String,
+}
+
+pub struct FairPlayRow {
+    pub team: String,
+    pub games: String,
+    pub value: String,
+    /// "yes" or "no", whether the team can win the award
+    pub eligible: String,
+}
+
+#[derive(Template)]
+#[template(path = "report.md")]
+struct MarkdownReport<'a> {
+    report: &'a Report,
+}
+
+#[derive(Template)]
+#[template(path = "report.html")]
+struct HtmlReport<'a> {
+    report: &'a Report,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" | "markdown" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(format!(
+                "Unknown report format '{}', expected 'md' or 'html'",
+                s
+            )),
+        }
+    }
+}
+
+impl Report {
+    /// Collect the report data from the db
+    pub fn load() -> Result<Self, CliError> {
+        let groups = wwc_db::get_groups()?;
+        let teams: Teams = wwc_db::get_teams()?.map(|team| (team.id, team)).collect();
+        let team_name = |id: TeamId| {
+            teams
+                .get(&id)
+                .map(|team| team.name.to_string())
+                .unwrap_or_else(|| id.to_string())
+        };
+        let rules = wwc_db::tournament::get_active_rules()?;
+        let group_list: Vec<Group> = groups.values().cloned().collect();
+        let group_rules = rules
+            .group_rules(&group_list, &teams)
+            .map_err(WwcError::from)?;
+        let layout = wwc_db::playoff::get_bracket_layout()?;
+
+        let group_sections = groups
+            .iter()
+            .map(|(id, group)| {
+                let stats = fun_stats(group);
+                let highlight = |game: Option<GameHighlight>| {
+                    game.map_or(String::from("-"), |game| {
+                        format!(
+                            "{} {}-{} {}",
+                            team_name(game.home),
+                            game.score.home,
+                            game.score.away,
+                            team_name(game.away)
+                        )
+                    })
+                };
+                let mut clean_sheets: Vec<_> = stats
+                    .clean_sheets
+                    .iter()
+                    .filter(|(_, num)| num.0 > 0)
+                    .map(|(team, num)| (team_name(*team), num.0))
+                    .collect();
+                clean_sheets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                let clean_sheets = if clean_sheets.is_empty() {
+                    String::from("-")
+                } else {
+                    clean_sheets
+                        .iter()
+                        .map(|(team, num)| format!("{} ({})", team, num))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                GroupSection {
+                    id: id.to_string(),
+                    standings: export::group_standings(group, &teams, &group_rules)
+                        .into_iter()
+                        .map(|row| StandingRow {
+                            points: rules.points.record_points(row.wins, row.draws, row.losses),
+                            ..row
+                        })
+                        .collect(),
+                    biggest_win: highlight(stats.biggest_win),
+                    most_goals: highlight(stats.most_goals),
+                    clean_sheets,
+                }
+            })
+            .collect();
+
+        let qualifiers = groups
+            .iter()
+            .filter(|(_, group)| group.unplayed_games().next().is_none())
+            .map(|(id, group)| {
+                let order = group.rank_teams(&group_rules);
+                Qualifiers {
+                    group: id.to_string(),
+                    winner: order.winner().map(team_name).unwrap_or_default(),
+                    runner_up: order.runner_up().map(team_name).unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        let bracket = layout
+            .iter()
+            .sorted_by_key(|node| (node.round, node.slot))
+            .map(|node| {
+                let team = |team: &BracketTeam| {
+                    team.team
+                        .map(team_name)
+                        .unwrap_or_else(|| team.feeder.to_string())
+                };
+                BracketRow {
+                    round: node.round.0.to_string(),
+                    game: node.game_id.to_string(),
+                    home: team(&node.home),
+                    away: team(&node.away),
+                    winner: node.winner.map_or(String::from("-"), team_name),
+                }
+            })
+            .collect();
+
+        let goal_events = wwc_db::get_goal_events()?;
+        let scorers = top_scorers(&goal_events)
+            .into_iter()
+            .map(|scorer| ScorerRow {
+                player: scorer.player.to_string(),
+                team: team_name(scorer.team),
+                goals: scorer.goals.to_string(),
+            })
+            .collect();
+
+        let fair_play = wwc_db::fair_play::get_fair_play_ranking()?;
+        let fair_play_award = fair_play_award(&fair_play)
+            .map(|standing| team_name(standing.team))
+            .unwrap_or_default();
+        let fair_play = fair_play
+            .into_iter()
+            .map(|standing| FairPlayRow {
+                team: team_name(standing.team),
+                games: standing.games.to_string(),
+                value: standing.value.to_string(),
+                eligible: String::from(if standing.eligible { "yes" } else { "no" }),
+            })
+            .collect();
+
+        Ok(Report {
+            groups: group_sections,
+            qualifiers,
+            bracket,
+            top_scorers: scorers,
+            fair_play,
+            fair_play_award,
+            leaderboard: leaderboard(&groups, &rules, &layout)?,
+        })
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String, CliError> {
+        Ok(match format {
+            ReportFormat::Markdown => MarkdownReport { report: self }.render()?,
+            ReportFormat::Html => HtmlReport { report: self }.render()?,
+        })
+    }
+}
+
+/// Total leaderboard of the group and the knockout stage, scored by the `rules` of the tournament
+fn leaderboard(
+    groups: &Groups,
+    rules: &TournamentRules,
+    layout: &[BracketNode],
+) -> Result<Vec<LeaderboardRow>, CliError> {
+    let results = groups
+        .values()
+        .flat_map(|group| group.played_games())
+        .map(|game| (game.id, game.score))
+        .collect();
+    let remaining: Vec<_> = groups
+        .values()
+        .flat_map(|group| group.unplayed_games())
+        .map(|game| game.id)
+        .collect();
+    let players = wwc_db::get_players()?;
+    let names: HashMap<PlayerId, String> = players
+        .iter()
+        .map(|player| (PlayerId::from(player.id), player.name.clone()))
+        .collect();
+    let preds = players
+        .iter()
+        .map(|player| {
+            let id = PlayerId::from(player.id);
+            wwc_db::get_preds(id).map(|preds| (id, preds))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let playoff_preds = players
+        .iter()
+        .map(|player| {
+            let id = PlayerId::from(player.id);
+            wwc_db::playoff::get_playoff_prediction(id).map(|prediction| (id, prediction))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let scoring = &rules.scoring;
+    let group_stage = standings(
+        &preds,
+        &wwc_db::joker::get_all_jokers()?,
+        &results,
+        &remaining,
+        scoring,
+    );
+    let knockout = knockout_standings(
+        &playoff_preds,
+        layout,
+        |round| scoring.winner_points(round),
+        &scoring.tiebreaks,
+    );
+    Ok(export::leaderboard(
+        &stage_standings(group_stage, knockout, &scoring.tiebreaks).total,
+        &names,
+    ))
+}
@@ -0,0 +1,104 @@
+//! Static site export
+//!
+//! Snapshot of the current standings, schedule and leaderboard as static JSON and HTML files,
+//! so that the pool can be hosted without the server, e.g. on GitHub Pages.
+//!
+//! Layout of the output directory:
+//!
+//! - `index.html`: links to everything below
+//! - `report.html`: the full tournament report, see [`Report`]
+//! - `schedule.html`, `schedule.json`, `schedule.ics`
+//! - `leaderboard.json`
+//! - `groups/<id>.json`: group tables
+use crate::report::{Report, ReportFormat};
+use crate::CliError;
+use askama::Template;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use wwc_core::export::{self, ScheduleRow};
+use wwc_core::team::Teams;
+use wwc_core::Date;
+
+/// Schedule line, pre-formatted for the template
+struct ScheduleLine {
+    kickoff: String,
+    group: String,
+    home: String,
+    away: String,
+    venue: String,
+    result: String,
+}
+
+impl From<&ScheduleRow> for ScheduleLine {
+    fn from(row: &ScheduleRow) -> Self {
+        let result = match (row.home_goals, row.away_goals) {
+            (Some(home), Some(away)) => format!("{}-{}", home, away),
+            _ => String::from("-"),
+        };
+        ScheduleLine {
+            kickoff: row.kickoff.to_string(),
+            group: row.group.clone(),
+            home: row.home.clone(),
+            away: row.away.clone(),
+            venue: row.venue.clone().unwrap_or_default(),
+            result,
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "schedule.html")]
+struct ScheduleHtml {
+    games: Vec<ScheduleLine>,
+}
+
+#[derive(Template)]
+#[template(path = "static_index.html")]
+struct IndexHtml {
+    groups: Vec<String>,
+    generated: String,
+}
+
+/// Write the static site to `dir`, created if missing
+///
+/// Existing files with the same names are overwritten, other files in `dir` are left as is.
+pub fn export_static(dir: &Path) -> Result<(), CliError> {
+    let groups = wwc_db::get_groups()?;
+    let teams: Teams = wwc_db::get_teams()?.map(|team| (team.id, team)).collect();
+    let report = Report::load()?;
+    let venues = wwc_db::venue::get_game_venues()?;
+    let schedule = export::schedule(&groups, &teams, &venues);
+    let generated = Date::now();
+
+    fs::create_dir_all(dir.join("groups"))?;
+    for group in &report.groups {
+        write_json(
+            &dir.join("groups").join(format!("{}.json", group.id)),
+            &group.standings,
+        )?;
+    }
+    write_json(&dir.join("leaderboard.json"), &report.leaderboard)?;
+    write_json(&dir.join("schedule.json"), &schedule)?;
+    fs::write(
+        dir.join("schedule.ics"),
+        export::to_ics(&schedule, generated),
+    )?;
+
+    fs::write(dir.join("report.html"), report.render(ReportFormat::Html)?)?;
+    let schedule = ScheduleHtml {
+        games: schedule.iter().map(ScheduleLine::from).collect(),
+    };
+    fs::write(dir.join("schedule.html"), schedule.render()?)?;
+    let index = IndexHtml {
+        groups: report.groups.iter().map(|group| group.id.clone()).collect(),
+        generated: generated.to_string(),
+    };
+    fs::write(dir.join("index.html"), index.render()?)?;
+    Ok(())
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &Path, data: &T) -> Result<(), CliError> {
+    fs::write(path, serde_json::to_string_pretty(data)?)?;
+    Ok(())
+}
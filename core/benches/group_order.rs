@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use wwc_core::goal_model::PoissonModel;
+use wwc_core::group::order::{fifa_2018, non_strict_order_group, order_group};
+use wwc_core::group::{Group, GroupId, Groups};
+use wwc_core::simulation::{simulate_n, CancellationToken};
+
+/// Fully played random groups of four, with their share of ties for the sub-orders to split
+fn played_groups() -> Vec<Group> {
+    (0..100)
+        .map(|seed| Group::random(6, 4, 6, Some(seed)))
+        .collect()
+}
+
+fn order(c: &mut Criterion) {
+    let groups = played_groups();
+    let rules = fifa_2018();
+    c.bench_function("non_strict_order_group", |b| {
+        b.iter(|| {
+            for group in &groups {
+                black_box(non_strict_order_group(black_box(group), &rules));
+            }
+        })
+    });
+    c.bench_function("order_group", |b| {
+        b.iter(|| {
+            for group in &groups {
+                black_box(order_group(black_box(group), &rules));
+            }
+        })
+    });
+}
+
+/// The Monte Carlo loop, where ordering the simulated groups dominates
+fn simulation(c: &mut Criterion) {
+    let groups: Groups = ('A'..='F')
+        .zip(0..)
+        .map(|(id, seed)| {
+            (
+                GroupId::try_new(id).unwrap(),
+                Group::random(6, 4, 0, Some(seed)),
+            )
+        })
+        .collect();
+    let rules = fifa_2018();
+    let model = PoissonModel::new(1.3, 1.1);
+    let token = CancellationToken::new();
+    c.bench_function("simulate_n 1000", |b| {
+        b.iter(|| simulate_n(1000, &groups, &rules, &model, Some(0), &token, |_| {}).unwrap())
+    });
+}
+
+criterion_group!(benches, order, simulation);
+criterion_main!(benches);
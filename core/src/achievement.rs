@@ -0,0 +1,336 @@
+//! Achievements
+//!
+//! Badges awarded to players for notable predictions, e.g. the first exact score.
+//! Like the points, badges are derived from the predictions and the played games,
+//! by a set of [`AchievementRule`]s, see [`default_rules`].
+//! Every achievement is earned at most once, by the first game that qualifies.
+use crate::game::{GameId, Outcome, Score};
+use crate::group::game::PlayedGroupGame;
+use crate::group::Groups;
+use crate::joker::matchday;
+use crate::player::Prediction;
+use crate::team::Teams;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Minimum rank difference for a win to count as an upset
+pub const UPSET_RANK_GAP: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    /// First exact score
+    FirstExactScore,
+    /// Predicted the win of a team ranked far below its opponent, see [`UPSET_RANK_GAP`]
+    UpsetCalled,
+    /// Correct outcome of every game of a matchday
+    PerfectMatchday,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 3] = [
+        Achievement::FirstExactScore,
+        Achievement::UpsetCalled,
+        Achievement::PerfectMatchday,
+    ];
+
+    /// Stable name, used when storing the achievement
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::FirstExactScore => "first_exact_score",
+            Achievement::UpsetCalled => "upset_called",
+            Achievement::PerfectMatchday => "perfect_matchday",
+        }
+    }
+}
+
+impl fmt::Display for Achievement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Achievement {
+    type Err = AchievementError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Achievement::ALL
+            .iter()
+            .find(|achievement| achievement.name() == s)
+            .copied()
+            .ok_or_else(|| AchievementError(String::from(s)))
+    }
+}
+
+/// Earned achievement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Badge {
+    pub achievement: Achievement,
+    /// The game which earned the badge, the last game of the matchday for
+    /// [`Achievement::PerfectMatchday`]
+    pub game: GameId,
+}
+
+/// Prediction of a played game
+#[derive(Debug, Clone, Copy)]
+pub struct ScoredPrediction<'a> {
+    pub game: &'a PlayedGroupGame,
+    pub pred: Score,
+}
+
+impl ScoredPrediction<'_> {
+    fn correct_outcome(&self) -> bool {
+        self.pred.home_outcome() == self.game.score.home_outcome()
+    }
+}
+
+pub trait AchievementRule {
+    fn achievement(&self) -> Achievement;
+
+    /// The first game which earns the achievement, if any
+    ///
+    /// `preds` are the predictions of a single player, in kickoff order.
+    fn earned(&self, preds: &[ScoredPrediction], groups: &Groups, teams: &Teams) -> Option<GameId>;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FirstExactScore;
+
+impl AchievementRule for FirstExactScore {
+    fn achievement(&self) -> Achievement {
+        Achievement::FirstExactScore
+    }
+
+    fn earned(&self, preds: &[ScoredPrediction], _: &Groups, _: &Teams) -> Option<GameId> {
+        preds
+            .iter()
+            .find(|pred| pred.pred == pred.game.score)
+            .map(|pred| pred.game.id)
+    }
+}
+
+/// Upsets are wins by a team ranked more than `rank_gap` below its opponent
+#[derive(Debug, Clone, Copy)]
+pub struct UpsetCalled {
+    pub rank_gap: u32,
+}
+
+impl AchievementRule for UpsetCalled {
+    fn achievement(&self) -> Achievement {
+        Achievement::UpsetCalled
+    }
+
+    fn earned(&self, preds: &[ScoredPrediction], _: &Groups, teams: &Teams) -> Option<GameId> {
+        preds
+            .iter()
+            .filter(|pred| pred.correct_outcome())
+            .find(|pred| {
+                let game = pred.game;
+                let (winner, loser) = match game.score.home_outcome() {
+                    Outcome::Win => (game.home, game.away),
+                    Outcome::Lose => (game.away, game.home),
+                    Outcome::Draw => return false,
+                };
+                match (teams.get(&winner), teams.get(&loser)) {
+                    // A small rank is a better rank
+                    (Some(winner), Some(loser)) => {
+                        winner.rank.0.saturating_sub(loser.rank.0) > self.rank_gap
+                    }
+                    _ => false,
+                }
+            })
+            .map(|pred| pred.game.id)
+    }
+}
+
+/// Only complete matchdays count, i.e. matchdays without unplayed games
+#[derive(Debug, Clone, Copy)]
+pub struct PerfectMatchday;
+
+impl AchievementRule for PerfectMatchday {
+    fn achievement(&self) -> Achievement {
+        Achievement::PerfectMatchday
+    }
+
+    fn earned(&self, preds: &[ScoredPrediction], groups: &Groups, _: &Teams) -> Option<GameId> {
+        let correct: HashMap<GameId, bool> = preds
+            .iter()
+            .map(|pred| (pred.game.id, pred.correct_outcome()))
+            .collect();
+        let unplayed_days: Vec<_> = groups
+            .values()
+            .flat_map(|group| group.unplayed_games())
+            .map(|game| matchday(game.date()))
+            .collect();
+        groups
+            .values()
+            .flat_map(|group| group.played_games())
+            .into_group_map_by(|game| matchday(game.date()))
+            .into_iter()
+            .filter(|(day, _)| !unplayed_days.contains(day))
+            .sorted_by_key(|(day, _)| *day)
+            .find_map(|(_, games)| {
+                if games
+                    .iter()
+                    .all(|game| correct.get(&game.id) == Some(&true))
+                {
+                    games
+                        .iter()
+                        .max_by_key(|game| (game.date(), game.id))
+                        .map(|game| game.id)
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+/// The rules behind [`Achievement::ALL`]
+pub fn default_rules() -> Vec<Box<dyn AchievementRule>> {
+    vec![
+        Box::new(FirstExactScore),
+        Box::new(UpsetCalled {
+            rank_gap: UPSET_RANK_GAP,
+        }),
+        Box::new(PerfectMatchday),
+    ]
+}
+
+/// Badges earned by the predictions of a player, in the order of the rules
+pub fn achievements(
+    preds: &[Prediction],
+    groups: &Groups,
+    teams: &Teams,
+    rules: &[Box<dyn AchievementRule>],
+) -> Vec<Badge> {
+    let played: HashMap<GameId, &PlayedGroupGame> = groups
+        .values()
+        .flat_map(|group| group.played_games())
+        .map(|game| (game.id, game))
+        .collect();
+    let scored: Vec<ScoredPrediction> = preds
+        .iter()
+        .filter_map(|Prediction(id, pred)| {
+            played
+                .get(id)
+                .map(|game| ScoredPrediction { game, pred: *pred })
+        })
+        .sorted_by_key(|pred| (pred.game.date(), pred.game.id))
+        .collect();
+    rules
+        .iter()
+        .filter_map(|rule| {
+            rule.earned(&scored, groups, teams).map(|game| Badge {
+                achievement: rule.achievement(),
+                game,
+            })
+        })
+        .collect()
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Unknown achievement '{0}'")]
+pub struct AchievementError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::group::game::UnplayedGroupGame;
+    use crate::group::{Group, GroupId};
+    use crate::team::{Team, TeamId, TeamRank};
+    use crate::Date;
+    use std::collections::BTreeMap;
+
+    fn date(day: u32, hour: u32) -> Date {
+        format!("2021-06-{:02} {:02}:00", day, hour)
+            .parse()
+            .unwrap()
+    }
+
+    /// Day 11: 1-2 (2-0), 3-4 (0-1), day 12: 1-3 (1-1), day 13: 2-4 unplayed
+    fn data() -> (Groups, Teams) {
+        let game = |id, home, away, day, score: (u32, u32)| {
+            UnplayedGroupGame::try_new(id, home, away, date(day, 18))
+                .unwrap()
+                .play(Score::from(score), FairPlayScore::default())
+        };
+        let group = Group::try_new(
+            vec![UnplayedGroupGame::try_new(4, 2, 4, date(13, 18)).unwrap()],
+            vec![
+                game(1, 1, 2, 11, (2, 0)),
+                game(2, 3, 4, 11, (0, 1)),
+                game(3, 1, 3, 12, (1, 1)),
+            ],
+        )
+        .unwrap();
+        let mut groups = BTreeMap::new();
+        groups.insert(GroupId::from('A'), group);
+        let teams = vec![
+            Team::new(TeamId(1), "Sweden", "SWE", "se", TeamRank(10)),
+            Team::new(TeamId(2), "England", "ENG", "gb-eng", TeamRank(4)),
+            Team::new(TeamId(3), "France", "FRA", "fr", TeamRank(2)),
+            Team::new(TeamId(4), "Malta", "MLT", "mt", TeamRank(170)),
+        ]
+        .into_iter()
+        .map(|team| (team.id, team))
+        .collect();
+        (groups, teams)
+    }
+
+    fn pred(id: u32, score: (u32, u32)) -> Prediction {
+        Prediction(GameId::from(id), Score::from(score))
+    }
+
+    #[test]
+    fn badges() {
+        let (groups, teams) = data();
+        let preds = vec![pred(1, (1, 0)), pred(2, (0, 3)), pred(3, (1, 1))];
+        let badges = achievements(&preds, &groups, &teams, &default_rules());
+        assert_eq!(
+            badges,
+            vec![
+                Badge {
+                    achievement: Achievement::FirstExactScore,
+                    game: GameId::from(3)
+                },
+                Badge {
+                    achievement: Achievement::UpsetCalled,
+                    game: GameId::from(2)
+                },
+                Badge {
+                    achievement: Achievement::PerfectMatchday,
+                    game: GameId::from(2)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn incomplete_matchday() {
+        let (groups, teams) = data();
+        // Day 11 has a wrong outcome and day 13 is not played yet.
+        let preds = vec![pred(1, (0, 0)), pred(2, (0, 1)), pred(4, (1, 1))];
+        let badges = achievements(&preds, &groups, &teams, &default_rules());
+        assert_eq!(
+            badges
+                .iter()
+                .map(|badge| badge.achievement)
+                .collect::<Vec<_>>(),
+            vec![Achievement::FirstExactScore, Achievement::UpsetCalled]
+        );
+        // Matchdays without predictions are not perfect
+        assert!(PerfectMatchday.earned(&[], &groups, &teams).is_none());
+    }
+
+    #[test]
+    fn names() {
+        for achievement in &Achievement::ALL {
+            assert_eq!(achievement.name().parse::<Achievement>(), Ok(*achievement));
+        }
+        assert!("best_player".parse::<Achievement>().is_err());
+    }
+}
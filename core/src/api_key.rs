@@ -0,0 +1,117 @@
+//! API keys
+//!
+//! Authentication of integrations, e.g. bots and dashboards, independent of the players.
+//! Every key has a [`Scope`], read-only or admin.
+//!
+//! Only the hash of a key is stored, the key itself is shown once when it is created.
+//! Keys are long random strings, so a plain SHA-256 hash is enough, there is nothing to gain
+//! from a slow password hash.
+use crate::Date;
+use derive_more::{Display, From, Into};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Permissions of an API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// Read-only requests
+    Read,
+    /// Every request, including the management of API keys
+    Admin,
+}
+
+impl Scope {
+    /// Stable name, used when storing the scope
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Admin => "admin",
+        }
+    }
+
+    /// Whether a key with this scope may make requests which require `required`
+    pub fn allows(&self, required: Scope) -> bool {
+        match self {
+            Scope::Admin => true,
+            Scope::Read => required == Scope::Read,
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ScopeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Scope::Read),
+            "admin" => Ok(Scope::Admin),
+            _ => Err(ScopeError(String::from(s))),
+        }
+    }
+}
+
+/// Secret API key
+#[derive(Display, Debug, Clone, From, Into, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    /// New key, 128 random bits as hex
+    #[cfg(feature = "random")]
+    pub fn random() -> Self {
+        use rand::Rng;
+        ApiKey(format!("{:032x}", rand::thread_rng().gen::<u128>()))
+    }
+
+    /// SHA-256 hash of the key as hex, the form in which the key is stored
+    pub fn hash(&self) -> String {
+        Sha256::digest(self.0.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+/// Stored API key, without the key itself
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiKeyInfo {
+    pub id: i32,
+    /// Description of the integration using the key, e.g. "Discord bot"
+    pub name: String,
+    pub scope: Scope,
+    pub created_at: Date,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Unknown scope '{0}', expected 'read' or 'admin'")]
+pub struct ScopeError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_allows() {
+        assert!(Scope::Admin.allows(Scope::Read));
+        assert!(Scope::Admin.allows(Scope::Admin));
+        assert!(Scope::Read.allows(Scope::Read));
+        assert!(!Scope::Read.allows(Scope::Admin));
+    }
+
+    #[test]
+    fn hash() {
+        let key = ApiKey::from(String::from("abc"));
+        assert_eq!(
+            key.hash(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
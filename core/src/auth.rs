@@ -0,0 +1,228 @@
+//! Magic login links
+//!
+//! Lets players log in without passwords or email addresses: the admin generates a personal
+//! link for every player and hands it out, e.g. in the group chat of the pool. Visiting the link
+//! starts a session.
+//!
+//! Links and sessions are [`LoginToken`]s, `<player>.<expiry>.<signature>` with the expiry as a
+//! unix timestamp and the signature an HMAC-SHA256 of the rest, keyed by the [`LoginSecret`] of
+//! the server. Nothing is stored: a link can be used on any number of devices until it expires,
+//! and changing the secret invalidates every link and session.
+use crate::error::ErrorCode;
+use crate::player::PlayerId;
+use crate::Date;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use thiserror::Error;
+
+/// Environment variable with the secret of the server, shared by the server and the CLI
+pub const LOGIN_SECRET_VAR: &str = "WWC_LOGIN_SECRET";
+
+/// Validity of a new link, unless given: two weeks
+pub const DEFAULT_LINK_VALIDITY_HOURS: u32 = 14 * 24;
+
+/// Validity of a session, started by visiting a link
+pub const SESSION_VALIDITY_HOURS: u32 = 30 * 24;
+
+const BLOCK_SIZE: usize = 64;
+
+/// Secret key signing the tokens
+#[derive(Clone)]
+pub struct LoginSecret(Vec<u8>);
+
+impl LoginSecret {
+    pub fn new(secret: &[u8]) -> Self {
+        LoginSecret(secret.to_vec())
+    }
+
+    /// Secret from [`LOGIN_SECRET_VAR`], `None` if unset or empty
+    pub fn from_env() -> Option<Self> {
+        std::env::var(LOGIN_SECRET_VAR)
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| LoginSecret::new(secret.as_bytes()))
+    }
+
+    /// HMAC-SHA256 of `message`
+    fn sign(&self, message: &str) -> Vec<u8> {
+        let mut key = [0u8; BLOCK_SIZE];
+        if self.0.len() > BLOCK_SIZE {
+            key[..32].copy_from_slice(&Sha256::digest(&self.0));
+        } else {
+            key[..self.0.len()].copy_from_slice(&self.0);
+        }
+        let padded = |pad: u8| key.iter().map(|byte| byte ^ pad).collect::<Vec<_>>();
+        let mut inner = Sha256::new();
+        inner.update(padded(0x36));
+        inner.update(message.as_bytes());
+        let mut outer = Sha256::new();
+        outer.update(padded(0x5c));
+        outer.update(inner.finalize());
+        outer.finalize().to_vec()
+    }
+}
+
+/// Never print the secret
+impl fmt::Debug for LoginSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LoginSecret(..)")
+    }
+}
+
+/// What a token is for, a link can not be used as a session and vice versa
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Link,
+    Session,
+}
+
+impl TokenKind {
+    fn name(&self) -> &'static str {
+        match self {
+            TokenKind::Link => "link",
+            TokenKind::Session => "session",
+        }
+    }
+}
+
+/// Signed claim that the holder is a player, until the expiry
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoginToken {
+    pub player: PlayerId,
+    pub expires_at: Date,
+}
+
+impl LoginToken {
+    /// Token until `expires_at`, in whole seconds like the signed expiry
+    pub fn new(player: PlayerId, expires_at: Date) -> Self {
+        LoginToken {
+            player,
+            expires_at: Date::from_timestamp(expires_at.timestamp()).unwrap_or(expires_at),
+        }
+    }
+
+    /// The token as a string, e.g. for the path of a link
+    pub fn sign(&self, kind: TokenKind, secret: &LoginSecret) -> String {
+        let claim = format!("{}.{}", self.player, self.expires_at.timestamp());
+        format!(
+            "{}.{}",
+            claim,
+            hex(&secret.sign(&signed_message(kind, &claim)))
+        )
+    }
+
+    /// Check the signature and expiry of a token
+    ///
+    /// # Errors
+    ///
+    /// The token is malformed, not signed by `secret` for `kind`, or expired at `now`.
+    pub fn verify(
+        token: &str,
+        kind: TokenKind,
+        secret: &LoginSecret,
+        now: Date,
+    ) -> Result<Self, LoginError> {
+        let (claim, signature) = token.rsplit_once('.').ok_or(LoginError::Invalid)?;
+        let expected = hex(&secret.sign(&signed_message(kind, claim)));
+        if !constant_time_eq(expected.as_bytes(), signature.as_bytes()) {
+            return Err(LoginError::Invalid);
+        }
+        let (player, expiry) = claim.split_once('.').ok_or(LoginError::Invalid)?;
+        let player = player.parse::<i32>().map_err(|_| LoginError::Invalid)?;
+        let expires_at = expiry
+            .parse::<i64>()
+            .ok()
+            .and_then(Date::from_timestamp)
+            .ok_or(LoginError::Invalid)?;
+        if !now.is_before(expires_at) {
+            return Err(LoginError::Expired(expires_at));
+        }
+        Ok(LoginToken::new(PlayerId::from(player), expires_at))
+    }
+}
+
+fn signed_message(kind: TokenKind, claim: &str) -> String {
+    format!("{}:{}", kind.name(), claim)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Compare without leaking the length of the common prefix through the time taken
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum LoginError {
+    #[error("Invalid login link")]
+    Invalid,
+    #[error("Login link expired at {0}")]
+    Expired(Date),
+    #[error("Player {0} is deleted")]
+    Deleted(PlayerId),
+}
+
+impl ErrorCode for LoginError {
+    fn code(&self) -> &'static str {
+        match self {
+            LoginError::Invalid => "INVALID_LOGIN_TOKEN",
+            LoginError::Expired(_) => "LOGIN_TOKEN_EXPIRED",
+            LoginError::Deleted(_) => "LOGIN_PLAYER_DELETED",
+        }
+    }
+
+    fn details(&self) -> Option<Value> {
+        match self {
+            LoginError::Invalid => None,
+            LoginError::Expired(expires_at) => Some(json!({ "expires_at": expires_at })),
+            LoginError::Deleted(player) => Some(json!({ "player": player })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac() {
+        // RFC 4231, test case 2
+        let secret = LoginSecret::new(b"Jefe");
+        assert_eq!(
+            hex(&secret.sign("what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let secret = LoginSecret::new(b"secret");
+        let now = Date::mock();
+        let token = LoginToken::new(PlayerId::from(3), now.add_hours(1));
+        let signed = token.sign(TokenKind::Link, &secret);
+        let verified = LoginToken::verify(&signed, TokenKind::Link, &secret, now).unwrap();
+        assert_eq!(verified, token);
+        assert_eq!(
+            LoginToken::verify(&signed, TokenKind::Link, &secret, now.add_hours(1)),
+            Err(LoginError::Expired(verified.expires_at))
+        );
+        assert_eq!(
+            LoginToken::verify(&signed, TokenKind::Session, &secret, now),
+            Err(LoginError::Invalid)
+        );
+        let other = LoginSecret::new(b"other");
+        assert_eq!(
+            LoginToken::verify(&signed, TokenKind::Link, &other, now),
+            Err(LoginError::Invalid)
+        );
+        let forged = signed.replacen('3', "4", 1);
+        assert_eq!(
+            LoginToken::verify(&forged, TokenKind::Link, &secret, now),
+            Err(LoginError::Invalid)
+        );
+    }
+}
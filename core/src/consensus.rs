@@ -0,0 +1,206 @@
+//! Community consensus
+//!
+//! Distribution of the predictions of all players for a single game,
+//! e.g. the most common score and the share of players picking each outcome,
+//! or the full [`ScoreHeatmap`] of predicted scores.
+//! Playoff games are predicted by the winner, their consensus is the share of players picking
+//! each team, see [`winner_consensus`].
+use crate::game::{Outcome, Score};
+use crate::team::TeamId;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// Share of the predictions picking each outcome, in percent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeShares {
+    pub home_win: f32,
+    pub draw: f32,
+    pub away_win: f32,
+}
+
+/// Share of the predictions picking a team as the winner of a playoff game, in percent
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WinnerShare {
+    pub team: TeamId,
+    pub share: f32,
+}
+
+/// Aggregated predictions for a game
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Consensus {
+    pub num_preds: usize,
+    /// Most common predicted score, `None` without predictions
+    ///
+    /// Equally common scores are ordered by home goals, then away goals, the first one is used.
+    pub most_common: Option<Score>,
+    /// Number of predictions of the most common score
+    pub most_common_count: usize,
+    /// Average predicted goals for the home team, zero without predictions
+    pub average_home_goals: f32,
+    /// Average predicted goals for the away team, zero without predictions
+    pub average_away_goals: f32,
+    pub outcomes: OutcomeShares,
+    /// Predicted winners of a playoff game, the most picked team first, empty for group games
+    #[serde(default)]
+    pub winners: Vec<WinnerShare>,
+}
+
+/// Aggregate the predictions of a game
+pub fn consensus(preds: &[Score]) -> Consensus {
+    if preds.is_empty() {
+        return Consensus::default();
+    }
+    let num_preds = preds.len();
+    let goals = |score: &Score| (u32::from(score.home), u32::from(score.away));
+    let (most_common, most_common_count) = preds
+        .iter()
+        .into_group_map_by(|score| goals(score))
+        .into_iter()
+        .map(|(goals, scores)| (goals, scores.len()))
+        // Max by count, the smallest score on ties
+        .min_by_key(|(goals, count)| (std::cmp::Reverse(*count), *goals))
+        .expect("Non-empty predictions");
+    let average = |total: u32| total as f32 / num_preds as f32;
+    let share = |outcome: Outcome| {
+        let count = preds
+            .iter()
+            .filter(|score| score.home_outcome() == outcome)
+            .count();
+        100.0 * count as f32 / num_preds as f32
+    };
+    Consensus {
+        num_preds,
+        most_common: Some(Score::from(most_common)),
+        most_common_count,
+        average_home_goals: average(preds.iter().map(|score| goals(score).0).sum()),
+        average_away_goals: average(preds.iter().map(|score| goals(score).1).sum()),
+        outcomes: OutcomeShares {
+            home_win: share(Outcome::Win),
+            draw: share(Outcome::Draw),
+            away_win: share(Outcome::Lose),
+        },
+        winners: Vec::new(),
+    }
+}
+
+/// Aggregate the predicted winners of a playoff game
+///
+/// A playoff prediction has no score, so only the number of predictions and the `winners` are
+/// set. Equally picked teams are ordered by id.
+pub fn winner_consensus(winners: &[TeamId]) -> Consensus {
+    let num_preds = winners.len();
+    let winners = winners
+        .iter()
+        .counts()
+        .into_iter()
+        .sorted_by_key(|(team, count)| (std::cmp::Reverse(*count), team.0))
+        .map(|(team, count)| WinnerShare {
+            team: *team,
+            share: 100.0 * count as f32 / num_preds as f32,
+        })
+        .collect();
+    Consensus {
+        num_preds,
+        winners,
+        ..Consensus::default()
+    }
+}
+
+/// Goals per team in the last row and column of a [`ScoreHeatmap`], which count this many goals
+/// or more
+pub const HEATMAP_MAX_GOALS: usize = 5;
+
+/// Number of predictions of every score of a game
+///
+/// `counts[home][away]` is the number of predictions with `home` and `away` goals,
+/// the last row and column count [`HEATMAP_MAX_GOALS`] or more goals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreHeatmap {
+    pub num_preds: usize,
+    pub counts: Vec<Vec<usize>>,
+}
+
+/// Count the predictions of a game per score, see [`ScoreHeatmap`]
+pub fn heatmap(preds: &[Score]) -> ScoreHeatmap {
+    let mut counts = vec![vec![0; HEATMAP_MAX_GOALS + 1]; HEATMAP_MAX_GOALS + 1];
+    let bin = |goals| (u32::from(goals) as usize).min(HEATMAP_MAX_GOALS);
+    for score in preds {
+        counts[bin(score.home)][bin(score.away)] += 1;
+    }
+    ScoreHeatmap {
+        num_preds: preds.len(),
+        counts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distribution() {
+        let preds: Vec<Score> = vec![(2, 1), (1, 1), (2, 1), (0, 3), (1, 1)]
+            .into_iter()
+            .map(Score::from)
+            .collect();
+        let consensus = consensus(&preds);
+        assert_eq!(consensus.num_preds, 5);
+        assert_eq!(consensus.most_common, Some(Score::from((1, 1))));
+        assert_eq!(consensus.most_common_count, 2);
+        assert!((consensus.average_home_goals - 1.2).abs() < 1e-6);
+        assert!((consensus.average_away_goals - 1.4).abs() < 1e-6);
+        assert_eq!(
+            consensus.outcomes,
+            OutcomeShares {
+                home_win: 40.0,
+                draw: 40.0,
+                away_win: 20.0
+            }
+        );
+    }
+
+    #[test]
+    fn score_heatmap() {
+        let preds: Vec<Score> = vec![(2, 1), (1, 1), (2, 1), (7, 0), (5, 6)]
+            .into_iter()
+            .map(Score::from)
+            .collect();
+        let heatmap = heatmap(&preds);
+        assert_eq!(heatmap.num_preds, 5);
+        assert_eq!(heatmap.counts.len(), HEATMAP_MAX_GOALS + 1);
+        assert_eq!(heatmap.counts[2][1], 2);
+        assert_eq!(heatmap.counts[1][1], 1);
+        assert_eq!(heatmap.counts[5][0], 1);
+        assert_eq!(heatmap.counts[5][5], 1);
+        let total: usize = heatmap.counts.iter().flatten().sum();
+        assert_eq!(total, 5);
+    }
+
+    #[test]
+    fn playoff_winners() {
+        let winners: Vec<TeamId> = vec![3, 1, 3, 3].into_iter().map(TeamId).collect();
+        let consensus = winner_consensus(&winners);
+        assert_eq!(consensus.num_preds, 4);
+        assert_eq!(consensus.most_common, None);
+        assert_eq!(
+            consensus.winners,
+            vec![
+                WinnerShare {
+                    team: TeamId(3),
+                    share: 75.0
+                },
+                WinnerShare {
+                    team: TeamId(1),
+                    share: 25.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn no_preds() {
+        let consensus = consensus(&[]);
+        assert_eq!(consensus.num_preds, 0);
+        assert_eq!(consensus.most_common, None);
+    }
+}
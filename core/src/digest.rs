@@ -0,0 +1,495 @@
+//! Matchday digest
+//!
+//! Summary of a matchday, composed once the last game of the day is played: the results of the
+//! group and playoff games, how the group standings and the leaderboard moved, and the
+//! predictions worth a mention.
+//! A [`Digest`] is structured data for bots and dashboards, [`Digest::to_markdown`] renders it
+//! for chat.
+//!
+//! Like for the jokers, a matchday is a calendar day in UTC, see [`matchday`].
+//! The standings before the matchday are derived by unplaying the games of the day,
+//! nothing has to be stored.
+use crate::game::{GameId, Score};
+use crate::group::game::PlayedGroupGame;
+use crate::group::order::non_strict_order_group;
+use crate::group::{Group, GroupId, Groups, Rules, Tiebreaker};
+use crate::joker::{matchday, PlayerJokers};
+use crate::leaderboard::standings;
+use crate::player::{PlayerId, Prediction};
+use crate::playoff::bracket::BracketNode;
+use crate::playoff::RoundIdx;
+use crate::pred_score::{PredScore, PredScoreFn};
+use crate::team::{TeamId, Teams};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Number of climbers in a digest
+pub const MAX_CLIMBERS: usize = 3;
+
+/// Result of a game of the matchday
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct DigestResult {
+    pub game: GameId,
+    pub group: GroupId,
+    pub home: TeamId,
+    pub away: TeamId,
+    pub score: Score,
+}
+
+/// Decided playoff game of the matchday
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PlayoffDigestResult {
+    pub game: GameId,
+    pub round: RoundIdx,
+    pub home: Option<TeamId>,
+    pub away: Option<TeamId>,
+    pub winner: TeamId,
+}
+
+/// Team with a new rank in its group, ranks start at 1
+///
+/// Teams on equal terms share a rank, the tiebreaker (e.g. drawing lots) is not applied.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct StandingMove {
+    pub group: GroupId,
+    pub team: TeamId,
+    pub old_rank: usize,
+    pub new_rank: usize,
+}
+
+/// Player who moved up the leaderboard, ranks start at 1
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Climber {
+    pub player: PlayerId,
+    pub old_rank: usize,
+    pub new_rank: usize,
+    /// Points from the games of the matchday
+    pub points_gained: PredScore,
+}
+
+/// Exactly predicted score of a game of the matchday
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct NotablePrediction {
+    pub player: PlayerId,
+    pub game: GameId,
+    pub score: Score,
+    /// Number of players who predicted the score, the fewer the more notable
+    pub hits: usize,
+}
+
+/// Summary of a matchday, see the [module docs](self)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Digest {
+    pub day: NaiveDate,
+    /// Results of the group games, ordered by kickoff
+    pub results: Vec<DigestResult>,
+    /// Decided playoff games, ordered by kickoff
+    pub playoff_results: Vec<PlayoffDigestResult>,
+    /// Teams with a changed rank, ordered by group and new rank
+    pub standing_moves: Vec<StandingMove>,
+    /// At most [`MAX_CLIMBERS`] players, the most places gained first
+    pub climbers: Vec<Climber>,
+    /// Exact scores, the rarest first
+    pub notable_predictions: Vec<NotablePrediction>,
+}
+
+/// Compose the digest of a matchday
+///
+/// `None` if there are no games on `day` or if some game of the day is not yet played, a
+/// playoff game is played once its winner is set in the bracket layout `playoff`.
+/// Void games are not part of a matchday.
+pub fn digest<T: Tiebreaker, S: PredScoreFn>(
+    day: NaiveDate,
+    groups: &Groups,
+    playoff: &[BracketNode],
+    players: &[(PlayerId, Vec<Prediction>)],
+    jokers: &PlayerJokers,
+    score_fn: &S,
+    rules: &Rules<T>,
+) -> Option<Digest> {
+    let on_day = |date| matchday(date) == day;
+    if groups
+        .values()
+        .flat_map(|group| group.unplayed_games())
+        .any(|game| on_day(game.date()))
+    {
+        return None;
+    }
+    let mut playoff_games: Vec<&BracketNode> =
+        playoff.iter().filter(|node| on_day(node.kickoff)).collect();
+    playoff_games.sort_by_key(|node| (node.kickoff, node.game_id));
+    let playoff_results = playoff_games
+        .iter()
+        .map(|node| {
+            Some(PlayoffDigestResult {
+                game: node.game_id,
+                round: node.round,
+                home: node.home.team,
+                away: node.away.team,
+                winner: node.winner?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let mut played: Vec<(GroupId, &PlayedGroupGame)> = groups
+        .iter()
+        .flat_map(|(id, group)| group.played_games().map(move |game| (*id, game)))
+        .filter(|(_, game)| on_day(game.date()))
+        .collect();
+    if played.is_empty() && playoff_results.is_empty() {
+        return None;
+    }
+    played.sort_by_key(|(_, game)| (game.date(), game.id));
+    let results: Vec<DigestResult> = played
+        .iter()
+        .map(|(group, game)| DigestResult {
+            game: game.id,
+            group: *group,
+            home: game.home,
+            away: game.away,
+            score: game.score,
+        })
+        .collect();
+
+    let mut before = groups.clone();
+    for result in &results {
+        if let Some(group) = before.get_mut(&result.group) {
+            group.unplay_game(result.game);
+        }
+    }
+    let standing_moves = groups
+        .iter()
+        .filter(|(id, _)| results.iter().any(|result| result.group == **id))
+        .flat_map(|(id, group)| {
+            let old: HashMap<TeamId, usize> = group_ranks(&before[id], rules).into_iter().collect();
+            group_ranks(group, rules)
+                .into_iter()
+                .filter_map(|(team, new_rank)| {
+                    let old_rank = old[&team];
+                    (old_rank != new_rank).then_some(StandingMove {
+                        group: *id,
+                        team,
+                        old_rank,
+                        new_rank,
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let (old_results, old_remaining) = results_and_remaining(&before);
+    let (new_results, new_remaining) = results_and_remaining(groups);
+    let old_board = standings(players, jokers, &old_results, &old_remaining, score_fn);
+    let new_board = standings(players, jokers, &new_results, &new_remaining, score_fn);
+    let mut climbers: Vec<Climber> = new_board
+        .iter()
+        .enumerate()
+        .filter_map(|(new_idx, new)| {
+            let old_idx = old_board.iter().position(|old| old.player == new.player)?;
+            (old_idx > new_idx).then(|| Climber {
+                player: new.player,
+                old_rank: old_idx + 1,
+                new_rank: new_idx + 1,
+                points_gained: new.points - old_board[old_idx].points,
+            })
+        })
+        .collect();
+    climbers.sort_by_key(|climber| climber.new_rank as isize - climber.old_rank as isize);
+    climbers.truncate(MAX_CLIMBERS);
+
+    let mut notable_predictions: Vec<NotablePrediction> = results
+        .iter()
+        .flat_map(|result| {
+            let hits: Vec<PlayerId> = players
+                .iter()
+                .filter(|(_, preds)| preds.contains(&Prediction(result.game, result.score)))
+                .map(|(player, _)| *player)
+                .collect();
+            let num_hits = hits.len();
+            hits.into_iter().map(move |player| NotablePrediction {
+                player,
+                game: result.game,
+                score: result.score,
+                hits: num_hits,
+            })
+        })
+        .collect();
+    notable_predictions.sort_by_key(|pred| pred.hits);
+
+    Some(Digest {
+        day,
+        results,
+        playoff_results,
+        standing_moves,
+        climbers,
+        notable_predictions,
+    })
+}
+
+/// Rank of every team in a group, tied teams share a rank
+fn group_ranks<T: Tiebreaker>(group: &Group, rules: &Rules<T>) -> Vec<(TeamId, usize)> {
+    let mut rank = 1;
+    let mut ranks = Vec::new();
+    for tied in non_strict_order_group(group, rules).iter() {
+        ranks.extend(tied.iter().map(|team| (*team, rank)));
+        rank += tied.len();
+    }
+    ranks
+}
+
+fn results_and_remaining(groups: &Groups) -> (HashMap<GameId, Score>, Vec<GameId>) {
+    let results = groups
+        .values()
+        .flat_map(|group| group.played_games())
+        .map(|game| (game.id, game.score))
+        .collect();
+    let remaining = groups
+        .values()
+        .flat_map(|group| group.unplayed_games())
+        .map(|game| game.id)
+        .collect();
+    (results, remaining)
+}
+
+impl Digest {
+    /// Render the digest as Markdown
+    ///
+    /// Teams are named by their FIFA code, players by `names`.
+    /// Teams or players missing in the maps are shown by id.
+    pub fn to_markdown(&self, teams: &Teams, names: &HashMap<PlayerId, String>) -> String {
+        let team = |id: TeamId| {
+            teams
+                .get(&id)
+                .map(|team| team.fifa_code.to_string())
+                .unwrap_or_else(|| id.to_string())
+        };
+        let player = |id: PlayerId| names.get(&id).cloned().unwrap_or_else(|| id.to_string());
+        let game = |id: GameId| {
+            self.results
+                .iter()
+                .find(|result| result.game == id)
+                .map(|result| format!("{}-{}", team(result.home), team(result.away)))
+                .unwrap_or_else(|| id.to_string())
+        };
+        let mut md = String::new();
+        // Writing to a `String` never fails
+        let _ = writeln!(md, "# Matchday {}", self.day);
+        if !self.results.is_empty() {
+            let _ = writeln!(md, "\n## Results\n");
+        }
+        for result in &self.results {
+            let _ = writeln!(
+                md,
+                "- Group {}: {} {}-{} {}",
+                result.group,
+                team(result.home),
+                result.score.home,
+                result.score.away,
+                team(result.away)
+            );
+        }
+        if !self.playoff_results.is_empty() {
+            let _ = writeln!(md, "\n## Playoff\n");
+            let side = |id: Option<TeamId>| id.map(&team).unwrap_or_else(|| String::from("?"));
+            for result in &self.playoff_results {
+                let _ = writeln!(
+                    md,
+                    "- Round {}: {}-{}, {} advances",
+                    result.round.0 + 1,
+                    side(result.home),
+                    side(result.away),
+                    team(result.winner)
+                );
+            }
+        }
+        if !self.standing_moves.is_empty() {
+            let _ = writeln!(md, "\n## Standings\n");
+            for mv in &self.standing_moves {
+                let arrow = if mv.new_rank < mv.old_rank {
+                    "▲"
+                } else {
+                    "▼"
+                };
+                let _ = writeln!(
+                    md,
+                    "- Group {}: {} {} {} → {}",
+                    mv.group,
+                    team(mv.team),
+                    arrow,
+                    mv.old_rank,
+                    mv.new_rank
+                );
+            }
+        }
+        if !self.climbers.is_empty() {
+            let _ = writeln!(md, "\n## Climbers\n");
+            for climber in &self.climbers {
+                let _ = writeln!(
+                    md,
+                    "- {}: {} → {} (+{} points)",
+                    player(climber.player),
+                    climber.old_rank,
+                    climber.new_rank,
+                    climber.points_gained
+                );
+            }
+        }
+        if !self.notable_predictions.is_empty() {
+            let _ = writeln!(md, "\n## Exact scores\n");
+            for pred in &self.notable_predictions {
+                let _ = writeln!(
+                    md,
+                    "- {}: {} {}-{} ({} {})",
+                    player(pred.player),
+                    game(pred.game),
+                    pred.score.home,
+                    pred.score.away,
+                    pred.hits,
+                    if pred.hits == 1 { "hit" } else { "hits" },
+                );
+            }
+        }
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group::game::UnplayedGroupGame;
+    use crate::group::order::fifa_2018;
+    use crate::playoff::bracket::BracketTeam;
+    use crate::pred_score::SimplePredScoreFn;
+    use crate::Date;
+    use std::collections::BTreeMap;
+
+    fn date(day: u32) -> Date {
+        format!("2018-06-{:02} 16:00", day).parse().unwrap()
+    }
+
+    fn groups() -> Groups {
+        let games = vec![(0, 0, 1, 14), (1, 2, 3, 14), (2, 0, 2, 15), (3, 1, 3, 15)]
+            .into_iter()
+            .map(|(id, home, away, day)| {
+                UnplayedGroupGame::try_new(id, home, away, date(day)).unwrap()
+            })
+            .collect();
+        let mut groups = BTreeMap::new();
+        groups.insert(GroupId::from('A'), Group::try_new(games, vec![]).unwrap());
+        groups
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2018, 6, day).unwrap()
+    }
+
+    #[test]
+    fn matchday_digest() {
+        let mut groups = groups();
+        let group = groups.get_mut(&GroupId::from('A')).unwrap();
+        group.play_game(GameId::from(0), Score::from((1, 0)));
+        group.play_game(GameId::from(1), Score::from((0, 0)));
+        let players = vec![
+            (
+                PlayerId::from(1),
+                vec![Prediction(GameId::from(0), Score::from((0, 2)))],
+            ),
+            (
+                PlayerId::from(2),
+                vec![
+                    Prediction(GameId::from(2), Score::from((0, 3))),
+                    Prediction(GameId::from(3), Score::from((1, 0))),
+                ],
+            ),
+        ];
+        let score_fn = SimplePredScoreFn::default();
+        let rules = fifa_2018();
+        let jokers = PlayerJokers::new();
+        assert!(digest(day(15), &groups, &[], &players, &jokers, &score_fn, &rules).is_none());
+        assert!(digest(day(16), &groups, &[], &players, &jokers, &score_fn, &rules).is_none());
+
+        let group = groups.get_mut(&GroupId::from('A')).unwrap();
+        group.play_game(GameId::from(2), Score::from((0, 3)));
+        group.play_game(GameId::from(3), Score::from((0, 0)));
+        let digest = digest(day(15), &groups, &[], &players, &jokers, &score_fn, &rules).unwrap();
+        assert_eq!(digest.results.len(), 2);
+        // Team 2 climbs from second (shared with team 3) to first
+        assert!(digest.standing_moves.contains(&StandingMove {
+            group: GroupId::from('A'),
+            team: TeamId(2),
+            old_rank: 2,
+            new_rank: 1,
+        }));
+        assert_eq!(digest.climbers.len(), 1);
+        assert_eq!(digest.climbers[0].player, PlayerId::from(2));
+        assert_eq!(digest.climbers[0].old_rank, 2);
+        assert_eq!(digest.climbers[0].new_rank, 1);
+        assert_eq!(digest.notable_predictions.len(), 1);
+        assert_eq!(digest.notable_predictions[0].game, GameId::from(2));
+        assert_eq!(digest.notable_predictions[0].hits, 1);
+
+        let md = digest.to_markdown(&Teams::new(), &HashMap::new());
+        assert!(md.starts_with("# Matchday 2018-06-15"));
+        assert!(md.contains("- Group A: 0 0-3 2"));
+    }
+
+    #[test]
+    fn playoff_digest() {
+        let side = |feeder: &str, team| BracketTeam {
+            feeder: feeder.parse().unwrap(),
+            team: Some(TeamId(team)),
+        };
+        let mut node = BracketNode {
+            game_id: GameId::from(4),
+            round: RoundIdx(0),
+            slot: 0,
+            kickoff: date(17),
+            home: side("1A", 2),
+            away: side("2A", 0),
+            winner: None,
+        };
+        let groups = groups();
+        let players = Vec::new();
+        let score_fn = SimplePredScoreFn::default();
+        let rules = fifa_2018();
+        let jokers = PlayerJokers::new();
+        let playoff = vec![node.clone()];
+        assert!(digest(
+            day(17),
+            &groups,
+            &playoff,
+            &players,
+            &jokers,
+            &score_fn,
+            &rules
+        )
+        .is_none());
+
+        node.winner = Some(TeamId(2));
+        let playoff = vec![node];
+        let digest = digest(
+            day(17),
+            &groups,
+            &playoff,
+            &players,
+            &jokers,
+            &score_fn,
+            &rules,
+        )
+        .unwrap();
+        assert!(digest.results.is_empty());
+        assert_eq!(
+            digest.playoff_results,
+            vec![PlayoffDigestResult {
+                game: GameId::from(4),
+                round: RoundIdx(0),
+                home: Some(TeamId(2)),
+                away: Some(TeamId(0)),
+                winner: TeamId(2),
+            }]
+        );
+        let md = digest.to_markdown(&Teams::new(), &HashMap::new());
+        assert!(md.contains("- Round 1: 2-0, 2 advances"));
+    }
+}
@@ -0,0 +1,253 @@
+//! Discipline
+//!
+//! Suspensions derived from card events, see [`CardEvent`].
+//!
+//! Like the group statistics, nothing is stored: the disciplinary record of a team or player is
+//! calculated from the card events of the games played so far.
+//! Players are only tracked if the card events name them, teams are always tracked.
+use crate::fair_play::{CardEvent, CardKind};
+use crate::game::GameId;
+use crate::team::{SquadPlayer, TeamId};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Suspension rules
+///
+/// Yellow cards are accumulated over games and result in a suspension once the threshold is
+/// reached, after which the accumulation starts over.
+/// At most one yellow card per player and game is accumulated. Two yellow cards in the same game
+/// should be recorded as a [`CardKind::IndirectRed`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SuspensionRules {
+    /// Number of yellow cards (in separate games) resulting in a suspension
+    pub yellow_threshold: u32,
+    /// Games suspended for reaching the yellow card threshold
+    pub yellow_ban: u32,
+    /// Games suspended for a second yellow card in a game
+    pub indirect_red_ban: u32,
+    /// Games suspended for a direct red card
+    pub direct_red_ban: u32,
+    /// Games suspended for a yellow and a direct red card in the same game
+    pub yellow_and_direct_red_ban: u32,
+}
+
+impl SuspensionRules {
+    /// Standard tournament rules
+    ///
+    /// Two yellow cards in separate games or any red card result in a one game suspension.
+    /// The disciplinary committee can extend suspensions for direct red cards, use
+    /// [`Discipline::add_ban`] for that.
+    pub fn standard() -> Self {
+        SuspensionRules {
+            yellow_threshold: 2,
+            yellow_ban: 1,
+            indirect_red_ban: 1,
+            direct_red_ban: 1,
+            yellow_and_direct_red_ban: 1,
+        }
+    }
+}
+
+impl Default for SuspensionRules {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// Card events of a single game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameCards {
+    pub id: GameId,
+    pub home: TeamId,
+    pub away: TeamId,
+    pub events: Vec<CardEvent>,
+}
+
+/// Disciplinary record for a single player
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PlayerRecord {
+    /// Yellow cards accumulated towards the next suspension
+    pub yellow_cards: u32,
+    /// Number of upcoming games the player is suspended for
+    pub games_suspended: u32,
+}
+
+/// Disciplinary state after a sequence of games
+#[derive(Debug, Clone, Default)]
+pub struct Discipline {
+    rules: SuspensionRules,
+    team_yellow_cards: HashMap<TeamId, u32>,
+    team_red_cards: HashMap<TeamId, u32>,
+    players: HashMap<(TeamId, SquadPlayer), PlayerRecord>,
+}
+
+impl Discipline {
+    pub fn new(rules: SuspensionRules) -> Self {
+        Discipline {
+            rules,
+            ..Default::default()
+        }
+    }
+
+    /// Discipline from games in chronological order
+    pub fn from_games<'a>(
+        rules: SuspensionRules,
+        games: impl IntoIterator<Item = &'a GameCards>,
+    ) -> Self {
+        games
+            .into_iter()
+            .fold(Discipline::new(rules), |mut acc, game| {
+                acc.add_game(game);
+                acc
+            })
+    }
+
+    /// Update the record with the next game
+    ///
+    /// Suspended players in the two teams serve one game of their suspension,
+    /// then the cards of the game are added.
+    pub fn add_game(&mut self, game: &GameCards) {
+        self.players
+            .iter_mut()
+            .filter(|((team, _), _)| *team == game.home || *team == game.away)
+            .for_each(|(_, record)| {
+                record.games_suspended = record.games_suspended.saturating_sub(1)
+            });
+
+        let mut yellow_this_game = HashSet::new();
+        for event in &game.events {
+            match event.kind {
+                CardKind::Yellow => *self.team_yellow_cards.entry(event.team).or_insert(0) += 1,
+                _ => *self.team_red_cards.entry(event.team).or_insert(0) += 1,
+            }
+            let player = match &event.player {
+                Some(player) => (event.team, player.clone()),
+                None => continue,
+            };
+            let rules = self.rules;
+            let record = self.players.entry(player.clone()).or_default();
+            match event.kind {
+                CardKind::Yellow => {
+                    if yellow_this_game.insert(player) {
+                        record.yellow_cards += 1;
+                        if record.yellow_cards >= rules.yellow_threshold {
+                            record.yellow_cards = 0;
+                            record.games_suspended += rules.yellow_ban;
+                        }
+                    }
+                }
+                CardKind::IndirectRed => record.games_suspended += rules.indirect_red_ban,
+                CardKind::DirectRed => record.games_suspended += rules.direct_red_ban,
+                CardKind::YellowAndDirectRed => {
+                    record.games_suspended += rules.yellow_and_direct_red_ban
+                }
+            }
+        }
+    }
+
+    /// Extend (or impose) a suspension manually
+    ///
+    /// E.g. when a disciplinary committee decides on a longer ban for a red card.
+    pub fn add_ban(&mut self, team: TeamId, player: SquadPlayer, games: u32) {
+        self.players
+            .entry((team, player))
+            .or_default()
+            .games_suspended += games;
+    }
+
+    /// Players of a team suspended for the team's next game
+    pub fn suspended(&self, team: TeamId) -> impl Iterator<Item = &SquadPlayer> {
+        self.players
+            .iter()
+            .filter(move |((player_team, _), record)| {
+                *player_team == team && record.games_suspended > 0
+            })
+            .map(|((_, player), _)| player)
+    }
+
+    /// Players of a team one yellow card away from a suspension
+    pub fn at_risk(&self, team: TeamId) -> impl Iterator<Item = &SquadPlayer> + '_ {
+        let threshold = self.rules.yellow_threshold;
+        self.players
+            .iter()
+            .filter(move |((player_team, _), record)| {
+                *player_team == team && record.yellow_cards + 1 == threshold
+            })
+            .map(|((_, player), _)| player)
+    }
+
+    /// Record for a player, `None` if the player has not received any cards
+    pub fn player_record(&self, team: TeamId, player: &SquadPlayer) -> Option<PlayerRecord> {
+        self.players.get(&(team, player.clone())).copied()
+    }
+
+    /// Total number of yellow cards for a team
+    pub fn team_yellow_cards(&self, team: TeamId) -> u32 {
+        self.team_yellow_cards.get(&team).copied().unwrap_or(0)
+    }
+
+    /// Total number of red cards (of any kind) for a team
+    pub fn team_red_cards(&self, team: TeamId) -> u32 {
+        self.team_red_cards.get(&team).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(team: u32, player: &str, kind: CardKind) -> CardEvent {
+        CardEvent::new(TeamId(team), Some(SquadPlayer::new(player)), 10, kind)
+    }
+
+    fn game(id: u32, events: Vec<CardEvent>) -> GameCards {
+        GameCards {
+            id: GameId::from(id),
+            home: TeamId(0),
+            away: TeamId(1),
+            events,
+        }
+    }
+
+    #[test]
+    fn two_yellows_separate_games() {
+        let zlatan = SquadPlayer::new("Zlatan");
+        let games = vec![
+            game(0, vec![card(0, "Zlatan", CardKind::Yellow)]),
+            game(1, vec![card(0, "Zlatan", CardKind::Yellow)]),
+        ];
+        let discipline = Discipline::from_games(SuspensionRules::standard(), &games);
+        assert_eq!(
+            discipline.suspended(TeamId(0)).collect::<Vec<_>>(),
+            vec![&zlatan]
+        );
+        assert_eq!(discipline.team_yellow_cards(TeamId(0)), 2);
+    }
+
+    #[test]
+    fn two_yellows_same_game_counted_once() {
+        let games = vec![game(
+            0,
+            vec![
+                card(0, "Zlatan", CardKind::Yellow),
+                card(0, "Zlatan", CardKind::Yellow),
+            ],
+        )];
+        let discipline = Discipline::from_games(SuspensionRules::standard(), &games);
+        assert_eq!(discipline.suspended(TeamId(0)).count(), 0);
+        assert_eq!(discipline.at_risk(TeamId(0)).count(), 1);
+    }
+
+    #[test]
+    fn suspension_served() {
+        let games = vec![
+            game(0, vec![card(1, "Kane", CardKind::DirectRed)]),
+            game(1, vec![]),
+        ];
+        let discipline = Discipline::from_games(SuspensionRules::standard(), &games[..1]);
+        assert_eq!(discipline.suspended(TeamId(1)).count(), 1);
+        let discipline = Discipline::from_games(SuspensionRules::standard(), &games);
+        assert_eq!(discipline.suspended(TeamId(1)).count(), 0);
+        assert_eq!(discipline.team_red_cards(TeamId(1)), 1);
+    }
+}
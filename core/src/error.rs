@@ -1,4 +1,12 @@
+//! Errors and their machine readable codes
+//!
+//! Every error surfaced by the API has a stable code, e.g. `PREDICTION_DEADLINE_PASSED`, see
+//! [`ErrorCode`]. Clients should branch on the code rather than on the message, which is only
+//! meant for humans and may change.
+//! All codes are listed in [`ERROR_CODES`].
 use crate::group::GroupError;
+use serde::Serialize;
+use serde_json::Value;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone, Copy)]
@@ -6,3 +14,219 @@ pub enum WwcError {
     #[error("Group error: {0}")]
     Group(#[from] GroupError),
 }
+
+/// Error with a machine readable code
+pub trait ErrorCode: std::fmt::Display {
+    /// Upper snake case code, one of [`ERROR_CODES`]
+    fn code(&self) -> &'static str;
+
+    /// Structured details of the error, e.g. the id of the game
+    fn details(&self) -> Option<Value> {
+        None
+    }
+}
+
+impl ErrorCode for WwcError {
+    fn code(&self) -> &'static str {
+        match self {
+            WwcError::Group(err) => err.code(),
+        }
+    }
+
+    fn details(&self) -> Option<Value> {
+        match self {
+            WwcError::Group(err) => err.details(),
+        }
+    }
+}
+
+/// Error code with a description of when it is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ErrorCodeInfo {
+    pub code: &'static str,
+    pub description: &'static str,
+}
+
+const fn info(code: &'static str, description: &'static str) -> ErrorCodeInfo {
+    ErrorCodeInfo { code, description }
+}
+
+/// Every error code of the API
+pub const ERROR_CODES: &[ErrorCodeInfo] = &[
+    // Requests
+    info(
+        "INVALID_INPUT",
+        "Malformed request, e.g. an unknown time zone",
+    ),
+    info(
+        "VALIDATION_FAILED",
+        "Invalid fields of a request body, listed in the details",
+    ),
+    info("NOT_FOUND", "The requested resource does not exist"),
+    info("UNAUTHORIZED", "Missing API key"),
+    info("FORBIDDEN", "The API key does not allow the request"),
+    info("UNKNOWN_GAME", "No game with the id"),
+    info("UNKNOWN_TEAM", "No team with the id"),
+    info("UNKNOWN_TOURNAMENT", "No tournament with the id"),
+    info("UNKNOWN_LEAGUE", "No league with the id"),
+    info(
+        "INVALID_GROUP_LABEL",
+        "Group label is not a single upper case letter",
+    ),
+    info(
+        "GAME_VERSION_CONFLICT",
+        "The game changed since the submitted version",
+    ),
+    // Predictions and jokers
+    info("PREDICTION_DEADLINE_PASSED", "The game has kicked off"),
+    info("UNREASONABLE_SCORE", "Too many goals in a predicted score"),
+    info("JOKER_DEADLINE_PASSED", "The joker game has kicked off"),
+    info(
+        "JOKER_MATCHDAY_TAKEN",
+        "Another joker is played on the same matchday",
+    ),
+    info("NOT_A_JOKER", "The game is not a joker of the player"),
+    // Login
+    info(
+        "INVALID_LOGIN_TOKEN",
+        "The login link or session is not valid, e.g. not signed by the server",
+    ),
+    info(
+        "LOGIN_TOKEN_EXPIRED",
+        "The login link or session has expired",
+    ),
+    info(
+        "LOGIN_LINKS_DISABLED",
+        "The server has no secret for signing login links",
+    ),
+    info(
+        "LOGIN_PLAYER_DELETED",
+        "The player of the login link or session is deleted",
+    ),
+    info(
+        "NOT_LOGGED_IN_AS_PLAYER",
+        "The session belongs to another player",
+    ),
+    // Leagues
+    info("UNKNOWN_INVITE", "No invite with the token"),
+    info("INVITE_EXPIRED", "The invite has expired"),
+    info("INVITE_USED", "The invite has already been used"),
+    info("INVALID_SCORING_RULES", "Scoring rules could not be parsed"),
+    info("INVALID_ODDS", "Decimal odds below 1.0"),
+    // Tournament
+    info(
+        "NOT_A_SANDBOX",
+        "Fake results are only allowed in sandbox tournaments",
+    ),
+    info(
+        "TOURNAMENT_NOT_ACTIVE",
+        "Only the active tournament, the one of the games, can be reset",
+    ),
+    info(
+        "INVALID_TOURNAMENT_RULES",
+        "Tournament rules could not be parsed",
+    ),
+    info(
+        "DUPLICATE_GAME_ID",
+        "A game id is used by more than one fixture",
+    ),
+    info(
+        "GROUP_TEAMS_NOT_UNIQUE",
+        "A team plays itself in a group game",
+    ),
+    info(
+        "GROUP_GAME_IDS_NOT_UNIQUE",
+        "A game id is used twice in a group",
+    ),
+    info(
+        "GROUP_NON_STRICT_ORDER",
+        "The group rules do not order the teams strictly",
+    ),
+    info("INVALID_GROUP_ID", "Group id is not an ascii letter"),
+    info(
+        "UNKNOWN_GROUP_RULES",
+        "No group ordering rules with the name",
+    ),
+    info(
+        "GROUP_POINTS_MISMATCH",
+        "Point system differs from the one of the group ordering rules",
+    ),
+    info(
+        "GAME_NOT_VOID",
+        "The game is neither abandoned nor annulled",
+    ),
+    info(
+        "NO_KICKOFF_SLOT",
+        "No kickoff slot for the final round of a group",
+    ),
+    info("TEAM_NOT_IN_GROUP", "The team does not play in the group"),
+    info("GROUP_SAME_TEAM", "A team is compared with itself"),
+    info("GROUP_ERROR", "Other invalid group"),
+    info(
+        "BRACKET_FEEDER_PARSE",
+        "A bracket feeder could not be parsed",
+    ),
+    info(
+        "BRACKET_GAME_IDS_NOT_UNIQUE",
+        "A game id is used twice in the bracket",
+    ),
+    info(
+        "BRACKET_INVALID_FEEDER",
+        "A bracket game is fed by a game in a later round",
+    ),
+    info(
+        "PLAYOFF_WINNER_NOT_IN_GAME",
+        "The winner of a playoff game is not one of its two teams",
+    ),
+    // Server
+    info("OPERATION_FAILED", "The request could not be carried out"),
+    info(
+        "UNSUPPORTED_SNAPSHOT_VERSION",
+        "Backup from an unsupported version",
+    ),
+    info(
+        "DATABASE_NOT_EMPTY",
+        "Restoring a backup requires an empty database",
+    ),
+    info("DATABASE_ERROR", "Internal database error"),
+    info(
+        "READ_ONLY",
+        "The server is in read-only mode, e.g. during maintenance",
+    ),
+    info("EXPORT_FAILED", "Export could not be generated"),
+    info("RENDER_FAILED", "Page could not be rendered"),
+    info("HTTP_ERROR", "Other error response, see the status code"),
+];
+
+/// Description of a code, `None` for unknown codes
+pub fn describe(code: &str) -> Option<&'static str> {
+    ERROR_CODES
+        .iter()
+        .find(|info| info.code == code)
+        .map(|info| info.description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameId;
+    use crate::player::PredictionError;
+    use std::collections::HashSet;
+
+    #[test]
+    fn registry() {
+        let codes: HashSet<&str> = ERROR_CODES.iter().map(|info| info.code).collect();
+        assert_eq!(codes.len(), ERROR_CODES.len());
+        assert!(codes
+            .iter()
+            .all(|code| code.chars().all(|c| c.is_ascii_uppercase() || c == '_')));
+        let err = PredictionError::DeadlinePassed(GameId::from(3));
+        assert_eq!(err.code(), "PREDICTION_DEADLINE_PASSED");
+        assert!(describe(err.code()).is_some());
+        assert_eq!(err.details(), Some(serde_json::json!({"game": 3})));
+        assert_eq!(
+            WwcError::from(GroupError::NonStrictOrder).code(),
+            "GROUP_NON_STRICT_ORDER"
+        );
+    }
+}
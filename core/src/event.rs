@@ -0,0 +1,199 @@
+//! Domain events
+//!
+//! Every change of the tournament state is recorded as a [`DomainEvent`] in a single,
+//! append-only stream.
+//! The derived state, e.g. materialized scores and the standings history, and the audit log
+//! follow the stream instead of hooking into every write.
+//! The stream is complete enough to rebuild the results and the predictions, see
+//! [`replay`](crate::replay).
+use crate::game::{GameId, GameStatus, Score};
+use crate::group::withdrawal::WithdrawalPolicy;
+use crate::group::{Group, GroupId, Groups};
+use crate::player::{PlayerId, Prediction};
+use crate::team::TeamId;
+use crate::Date;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DomainEvent {
+    /// First result of a game
+    ResultPosted {
+        game: GameId,
+        score: Score,
+    },
+    /// The result of a played game is corrected
+    ResultAmended {
+        game: GameId,
+        previous: Score,
+        score: Score,
+    },
+    /// A game is annulled, abandoned, restored or to be replayed
+    GameStatusChanged {
+        game: GameId,
+        status: GameStatus,
+        /// Result kept with the status, see [`GameStatus`]
+        score: Option<Score>,
+    },
+    GameRescheduled {
+        game: GameId,
+        /// `None` if the game had no kickoff, i.e. was open for predictions
+        old_kickoff: Option<Date>,
+        new_kickoff: Date,
+        /// Time of the rescheduling, `None` in events logged before it was recorded
+        #[serde(default)]
+        extended_at: Option<Date>,
+    },
+    /// New prediction versions of a player
+    PredictionSubmitted {
+        player: PlayerId,
+        preds: Vec<Prediction>,
+        submitted: Date,
+    },
+    /// Every prediction version of a player, or of every player, is soft-deleted
+    PredictionsDeleted {
+        player: Option<PlayerId>,
+    },
+    /// The soft-deleted prediction versions of a player, or of every player, are restored
+    PredictionsRestored {
+        player: Option<PlayerId>,
+    },
+    PlayerDeleted {
+        player: PlayerId,
+    },
+    PlayerRestored {
+        player: PlayerId,
+    },
+    /// The display name of a player is changed, the name itself is not logged
+    PlayerRenamed {
+        player: PlayerId,
+    },
+    /// The personal data of a player is erased, the player is anonymized
+    PlayerErased {
+        player: PlayerId,
+    },
+    StageCompleted {
+        stage: Stage,
+    },
+    /// A team withdraws from its group, the changes to its games are events of their own
+    TeamWithdrawn {
+        team: TeamId,
+        group: GroupId,
+        policy: WithdrawalPolicy,
+    },
+    /// The winner of a playoff game is set, or cleared with `None`
+    PlayoffWinnerSet {
+        game: GameId,
+        winner: Option<TeamId>,
+    },
+}
+
+/// Completed part of the tournament, see [`completed_stages`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Group(GroupId),
+    GroupStage,
+}
+
+impl DomainEvent {
+    /// Stable name of the event, the `kind` of the serialized event
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DomainEvent::ResultPosted { .. } => "result_posted",
+            DomainEvent::ResultAmended { .. } => "result_amended",
+            DomainEvent::GameStatusChanged { .. } => "game_status_changed",
+            DomainEvent::GameRescheduled { .. } => "game_rescheduled",
+            DomainEvent::PredictionSubmitted { .. } => "prediction_submitted",
+            DomainEvent::PredictionsDeleted { .. } => "predictions_deleted",
+            DomainEvent::PredictionsRestored { .. } => "predictions_restored",
+            DomainEvent::PlayerDeleted { .. } => "player_deleted",
+            DomainEvent::PlayerRestored { .. } => "player_restored",
+            DomainEvent::PlayerRenamed { .. } => "player_renamed",
+            DomainEvent::PlayerErased { .. } => "player_erased",
+            DomainEvent::StageCompleted { .. } => "stage_completed",
+            DomainEvent::TeamWithdrawn { .. } => "team_withdrawn",
+            DomainEvent::PlayoffWinnerSet { .. } => "playoff_winner_set",
+        }
+    }
+
+    /// The game changed by the event, if any
+    pub fn game(&self) -> Option<GameId> {
+        match self {
+            DomainEvent::ResultPosted { game, .. }
+            | DomainEvent::ResultAmended { game, .. }
+            | DomainEvent::GameStatusChanged { game, .. }
+            | DomainEvent::GameRescheduled { game, .. }
+            | DomainEvent::PlayoffWinnerSet { game, .. } => Some(*game),
+            DomainEvent::PredictionSubmitted { .. }
+            | DomainEvent::PredictionsDeleted { .. }
+            | DomainEvent::PredictionsRestored { .. }
+            | DomainEvent::PlayerDeleted { .. }
+            | DomainEvent::PlayerRestored { .. }
+            | DomainEvent::PlayerRenamed { .. }
+            | DomainEvent::PlayerErased { .. }
+            | DomainEvent::StageCompleted { .. }
+            | DomainEvent::TeamWithdrawn { .. } => None,
+        }
+    }
+}
+
+/// Stages completed by playing `game`
+///
+/// The group of the game, if it has no unplayed game left, and the group stage if every group
+/// is complete. Empty for games outside the group stage.
+pub fn completed_stages(groups: &Groups, game: GameId) -> Vec<Stage> {
+    let complete = |group: &Group| group.unplayed_games().next().is_none();
+    let group = groups
+        .iter()
+        .find(|(_, group)| group.games().any(|group_game| group_game.id() == game));
+    match group {
+        Some((id, group)) if complete(group) => {
+            let mut stages = vec![Stage::Group(*id)];
+            if groups.values().all(complete) {
+                stages.push(Stage::GroupStage);
+            }
+            stages
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::group::game::UnplayedGroupGame;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn serialized_kind() {
+        let event = DomainEvent::ResultPosted {
+            game: GameId::from(1),
+            score: Score::from((2, 1)),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["kind"], event.kind());
+    }
+
+    #[test]
+    fn completed() {
+        let game =
+            |id, home, away| UnplayedGroupGame::try_new(id, home, away, Date::mock()).unwrap();
+        let played = game(0, 1, 2).play(Score::from((1, 0)), FairPlayScore::default());
+        let mut groups = BTreeMap::new();
+        groups.insert(
+            GroupId::from('A'),
+            Group::try_new(vec![], vec![played]).unwrap(),
+        );
+        groups.insert(
+            GroupId::from('B'),
+            Group::try_new(vec![game(1, 3, 4)], vec![]).unwrap(),
+        );
+        assert_eq!(
+            completed_stages(&groups, GameId::from(0)),
+            vec![Stage::Group(GroupId::from('A'))]
+        );
+        assert!(completed_stages(&groups, GameId::from(1)).is_empty());
+    }
+}
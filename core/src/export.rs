@@ -0,0 +1,515 @@
+//! Export
+//!
+//! Flat rows of group standings, the schedule and the leaderboard, for archiving or pasting into a
+//! spreadsheet.
+//! The rows serialize to CSV (see [`to_csv`]) or JSON, group tables and the leaderboard also lay
+//! out as plain text tables, see [`to_ascii_table`].
+//!
+//! The field names are the column names of the exported files.
+//! Archived files are compared across tournaments, so the names must not change.
+//!
+//! Exports are polled by clients, [`content_hash`] tags the content, or the version of the data
+//! the content is derived from, so that unchanged responses can be skipped.
+use crate::game::GameId;
+use crate::group::stats::{TableStats, UnaryStat};
+use crate::group::{order_group, Group, GroupId, GroupOrder, Groups, Rules, Tiebreaker};
+use crate::leaderboard::Standing;
+use crate::player::PlayerId;
+use crate::team::{TeamId, Teams};
+pub use crate::utils::table::to_ascii_table;
+use crate::utils::table::{Align, TableRow};
+use crate::venue::{GameVenues, Venue};
+use crate::Date;
+use derive_more::From;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use thiserror::Error;
+
+/// Row in an exported group table
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StandingRow {
+    pub rank: usize,
+    pub team_id: u32,
+    pub team: String,
+    pub fifa_code: String,
+    pub played: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub goals_scored: u32,
+    pub goals_conceded: u32,
+    pub goal_diff: i32,
+    pub points: u32,
+}
+
+impl TableRow for StandingRow {
+    fn columns() -> Vec<(&'static str, Align)> {
+        vec![
+            ("Pos", Align::Right),
+            ("Team", Align::Left),
+            ("P", Align::Right),
+            ("W", Align::Right),
+            ("D", Align::Right),
+            ("L", Align::Right),
+            ("GF", Align::Right),
+            ("GA", Align::Right),
+            ("GD", Align::Right),
+            ("Pts", Align::Right),
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.rank.to_string(),
+            self.team.clone(),
+            self.played.to_string(),
+            self.wins.to_string(),
+            self.draws.to_string(),
+            self.losses.to_string(),
+            self.goals_scored.to_string(),
+            self.goals_conceded.to_string(),
+            self.goal_diff.to_string(),
+            self.points.to_string(),
+        ]
+    }
+}
+
+/// Group table for display, rows ordered best first, e.g. from [`group_standings`]
+///
+/// Displays as an aligned plain text table, see [`GroupStandings::to_ascii_table`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, From)]
+#[serde(transparent)]
+pub struct GroupStandings(pub Vec<StandingRow>);
+
+impl GroupStandings {
+    pub fn rows(&self) -> &[StandingRow] {
+        &self.0
+    }
+
+    /// Aligned text table with the columns Pos, Team, P, W, D, L, GF, GA, GD and Pts
+    pub fn to_ascii_table(&self) -> String {
+        to_ascii_table(&self.0)
+    }
+}
+
+impl fmt::Display for GroupStandings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_ascii_table())
+    }
+}
+
+/// Group table, ordered by `rules`
+///
+/// Teams missing in `teams` are exported with empty names.
+pub fn group_standings<T: Tiebreaker>(
+    group: &Group,
+    teams: &Teams,
+    rules: &Rules<T>,
+) -> Vec<StandingRow> {
+    ordered_standings(group, teams, order_group(group, rules))
+}
+
+/// Group table in a given order, e.g. from a
+/// [`GroupOrderCache`](crate::group::order_cache::GroupOrderCache)
+pub fn ordered_standings(group: &Group, teams: &Teams, order: GroupOrder) -> Vec<StandingRow> {
+    let stats = TableStats::team_stats(group);
+    order
+        .into_iter()
+        .enumerate()
+        .map(|(idx, id)| {
+            let stat = stats[&id];
+            let team = teams.get(&id);
+            StandingRow {
+                rank: idx + 1,
+                team_id: id.0,
+                team: team.map(|team| team.name.to_string()).unwrap_or_default(),
+                fifa_code: team
+                    .map(|team| team.fifa_code.to_string())
+                    .unwrap_or_default(),
+                played: stat.games_played.0,
+                wins: stat.wins.0,
+                draws: stat.draws.0,
+                losses: stat.losses.0,
+                goals_scored: stat.goals_scored.into(),
+                goals_conceded: stat.goals_conceded.into(),
+                goal_diff: stat.goal_diff.0,
+                points: u32::from(stat.points.0),
+            }
+        })
+        .collect()
+}
+
+/// Group table with an id, for laying out all groups
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GroupTable {
+    pub id: GroupId,
+    pub rows: Vec<StandingRow>,
+}
+
+/// Tables of all groups, sorted by group id, see [`group_standings`]
+pub fn group_tables<T: Tiebreaker>(
+    groups: &Groups,
+    teams: &Teams,
+    rules: &Rules<T>,
+) -> Vec<GroupTable> {
+    groups
+        .iter()
+        .map(|(id, group)| GroupTable {
+            id: *id,
+            rows: group_standings(group, teams, rules),
+        })
+        .collect()
+}
+
+/// Row in an exported schedule
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduleRow {
+    pub game_id: u32,
+    pub group: String,
+    pub kickoff: Date,
+    pub home: String,
+    pub away: String,
+    /// Final score, for played games only
+    pub home_goals: Option<u32>,
+    pub away_goals: Option<u32>,
+    /// Stadium and city, see [`Venue::label`]
+    pub venue: Option<String>,
+}
+
+/// All group games, ordered by kickoff
+///
+/// Teams missing in `teams` are exported with empty names.
+pub fn schedule(groups: &Groups, teams: &Teams, venues: &GameVenues) -> Vec<ScheduleRow> {
+    let name = |id: TeamId| {
+        teams
+            .get(&id)
+            .map(|team| team.name.to_string())
+            .unwrap_or_default()
+    };
+    let venue = |id: GameId| venues.get(&id).map(Venue::label);
+    let mut rows: Vec<ScheduleRow> = groups
+        .iter()
+        .flat_map(|(group_id, group)| {
+            let played = group.played_games().map(move |game| ScheduleRow {
+                game_id: game.id.into(),
+                group: group_id.to_string(),
+                kickoff: game.date(),
+                home: name(game.home),
+                away: name(game.away),
+                home_goals: Some(game.score.home.into()),
+                away_goals: Some(game.score.away.into()),
+                venue: venue(game.id),
+            });
+            let unplayed = group.unplayed_games().map(move |game| ScheduleRow {
+                game_id: game.id.into(),
+                group: group_id.to_string(),
+                kickoff: game.date(),
+                home: name(game.home),
+                away: name(game.away),
+                home_goals: None,
+                away_goals: None,
+                venue: venue(game.id),
+            });
+            played.chain(unplayed)
+        })
+        .collect();
+    rows.sort_by_key(|row| (row.kickoff, row.game_id));
+    rows
+}
+
+/// Row in an exported leaderboard
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LeaderboardRow {
+    /// Players with equal points share the rank, e.g. 1, 2, 2, 4.
+    pub rank: usize,
+    pub player_id: i32,
+    pub player: String,
+    pub points: f32,
+    pub exact_scores: u32,
+    pub max_points: f32,
+    pub eliminated: bool,
+}
+
+impl TableRow for LeaderboardRow {
+    fn columns() -> Vec<(&'static str, Align)> {
+        vec![
+            ("#", Align::Right),
+            ("Player", Align::Left),
+            ("Points", Align::Right),
+            ("Exact", Align::Right),
+            ("Max", Align::Right),
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let player = if self.eliminated {
+            format!("{} (out)", self.player)
+        } else {
+            self.player.clone()
+        };
+        vec![
+            self.rank.to_string(),
+            player,
+            self.points.to_string(),
+            self.exact_scores.to_string(),
+            self.max_points.to_string(),
+        ]
+    }
+}
+
+/// Leaderboard rows from standings sorted best first, see
+/// [`standings`](crate::leaderboard::standings).
+pub fn leaderboard(
+    standings: &[Standing],
+    names: &HashMap<PlayerId, String>,
+) -> Vec<LeaderboardRow> {
+    standings
+        .iter()
+        .map(|standing| LeaderboardRow {
+            rank: 1 + standings
+                .iter()
+                .filter(|other| other.points > standing.points)
+                .count(),
+            player_id: standing.player.into(),
+            player: names.get(&standing.player).cloned().unwrap_or_default(),
+            points: standing.points.into(),
+            exact_scores: standing.exact_scores,
+            max_points: standing.max_points.into(),
+            eliminated: standing.eliminated,
+        })
+        .collect()
+}
+
+/// Serialize rows to CSV, with a header row
+pub fn to_csv<R: Serialize>(rows: &[R]) -> Result<String, ExportError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| ExportError::Csv(err.into_error().into()))?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+/// Length of a game in the calendar, including half time
+const GAME_HOURS: u32 = 2;
+
+/// Schedule as an iCalendar file, one event per game
+///
+/// The event of a game keeps its id across exports, so that calendar apps update rescheduled
+/// games instead of adding duplicates.
+/// Every event is stamped with the time the file is `generated`.
+pub fn to_ics(rows: &[ScheduleRow], generated: Date) -> String {
+    let escape = |text: &str| {
+        text.replace('\\', "\\\\")
+            .replace(',', "\\,")
+            .replace(';', "\\;")
+    };
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//Wednesday World Cup//Schedule//EN"),
+    ];
+    for row in rows {
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!("UID:game-{}@wednesday-world-cup", row.game_id));
+        lines.push(format!("DTSTAMP:{}", generated.to_ics_string()));
+        lines.push(format!("DTSTART:{}", row.kickoff.to_ics_string()));
+        lines.push(format!(
+            "DTEND:{}",
+            row.kickoff.add_hours(GAME_HOURS).to_ics_string()
+        ));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape(&format!(
+                "{} - {} (group {})",
+                row.home, row.away, row.group
+            ))
+        ));
+        if let Some(venue) = &row.venue {
+            lines.push(format!("LOCATION:{}", escape(venue)));
+        }
+        lines.push(String::from("END:VEVENT"));
+    }
+    lines.push(String::from("END:VCALENDAR"));
+    lines.iter().map(|line| fold_ics_line(line)).collect()
+}
+
+/// Longest line of an iCalendar file, in octets and excluding the line break
+const ICS_LINE_OCTETS: usize = 75;
+
+/// Content line folded into lines of at most [`ICS_LINE_OCTETS`], each ending with a line break
+///
+/// A continuation line starts with a space, see RFC 5545, section 3.1.
+/// Lines are only folded between characters, never inside a multi-byte character.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 3);
+    let mut octets = 0;
+    for ch in line.chars() {
+        if octets + ch.len_utf8() > ICS_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(ch);
+        octets += ch.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Hash of the content, as a quoted entity tag (`ETag`)
+///
+/// The hash is of the JSON serialization, so equal content gets the same tag,
+/// also across server restarts.
+pub fn content_hash<R: Serialize + ?Sized>(content: &R) -> Result<String, ExportError> {
+    let json = serde_json::to_vec(content)?;
+    Ok(format!("\"{:016x}\"", fnv1a(&json)))
+}
+
+/// 64 bit FNV-1a, stable unlike the std hasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes.iter().fold(OFFSET, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Non UTF-8 output: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group::order::fifa_2018;
+    use crate::group::{mock_data, GroupId};
+    use crate::pred_score::PredScore;
+    use crate::venue::VenueId;
+
+    #[test]
+    fn group_csv() {
+        let (groups, teams) = mock_data();
+        let group = groups.get(&GroupId::from('A')).unwrap();
+        let rows = group_standings(group, &teams, &fifa_2018());
+        assert_eq!(rows[0].team, "Sweden");
+        let csv = to_csv(&rows).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "rank,team_id,team,fifa_code,played,wins,draws,losses,goals_scored,goals_conceded,goal_diff,points"
+        );
+        assert_eq!(lines.next().unwrap(), "1,1,Sweden,SWE,1,1,0,0,2,1,1,3");
+    }
+
+    #[test]
+    fn group_ascii_table() {
+        let (groups, teams) = mock_data();
+        let group = groups.get(&GroupId::from('A')).unwrap();
+        let standings = GroupStandings::from(group_standings(group, &teams, &fifa_2018()));
+        let table = standings.to_string();
+        let mut lines = table.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "Pos | Team    | P | W | D | L | GF | GA | GD | Pts"
+        );
+        lines.next();
+        assert_eq!(
+            lines.next().unwrap(),
+            "  1 | Sweden  | 1 | 1 | 0 | 0 |  2 |  1 |  1 |   3"
+        );
+        assert_eq!(table.lines().count(), 2 + standings.rows().len());
+    }
+
+    #[test]
+    fn hash() {
+        let (groups, teams) = mock_data();
+        let rows = schedule(&groups, &teams, &GameVenues::new());
+        let tag = content_hash(&rows).unwrap();
+        assert_eq!(
+            tag,
+            content_hash(&schedule(&groups, &teams, &GameVenues::new())).unwrap()
+        );
+        assert_ne!(tag, content_hash(&rows[1..]).unwrap());
+        assert!(tag.starts_with('"') && tag.ends_with('"'));
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn schedule_rows() {
+        let (groups, teams) = mock_data();
+        let rows = schedule(&groups, &teams, &GameVenues::new());
+        assert_eq!(rows.len(), 4);
+        let played = rows.iter().find(|row| row.game_id == 1).unwrap();
+        assert_eq!(played.home, "Sweden");
+        assert_eq!((played.home_goals, played.away_goals), (Some(2), Some(1)));
+        let unplayed = rows.iter().find(|row| row.game_id == 2).unwrap();
+        assert_eq!(unplayed.home_goals, None);
+    }
+
+    #[test]
+    fn calendar() {
+        let (groups, teams) = mock_data();
+        let mut venues = GameVenues::new();
+        venues.insert(
+            GameId::from(1),
+            Venue {
+                id: VenueId::from(String::from("FRI")),
+                name: String::from("Friends Arena"),
+                city: String::from("Stockholm"),
+            },
+        );
+        let rows = schedule(&groups, &teams, &venues);
+        assert_eq!(rows[0].venue.as_deref(), Some("Friends Arena, Stockholm"));
+        let generated = Date::mock();
+        let ics = to_ics(&rows, generated);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), rows.len());
+        assert!(ics.contains("LOCATION:Friends Arena\\, Stockholm\r\n"));
+        let stamp = format!("DTSTAMP:{}\r\n", generated.to_ics_string());
+        assert_eq!(ics.matches(&stamp).count(), rows.len());
+    }
+
+    #[test]
+    fn folded_ics_lines() {
+        assert_eq!(fold_ics_line("SUMMARY:short"), "SUMMARY:short\r\n");
+        let long = format!("LOCATION:{}", "å".repeat(40));
+        let folded = fold_ics_line(&long);
+        let lines: Vec<&str> = folded.split_terminator("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= ICS_LINE_OCTETS));
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(lines.concat().replacen(" å", "å", 1), long);
+    }
+
+    #[test]
+    fn shared_rank() {
+        let standing = |id: i32, points: f32| Standing {
+            player: PlayerId::from(id),
+            points: PredScore::from(points),
+            compensation: PredScore::default(),
+            exact_scores: 0,
+            max_points: PredScore::from(points),
+            eliminated: false,
+        };
+        let standings = vec![
+            standing(1, 5.0),
+            standing(2, 3.0),
+            standing(3, 3.0),
+            standing(4, 0.0),
+        ];
+        let ranks: Vec<_> = leaderboard(&standings, &HashMap::new())
+            .iter()
+            .map(|row| row.rank)
+            .collect();
+        assert_eq!(ranks, vec![1, 2, 2, 4]);
+    }
+}
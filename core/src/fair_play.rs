@@ -1,6 +1,17 @@
 //! Fair play scoring
+//!
+//! Fair play values per game and the tournament wide fair play ranking, see
+//! [`fair_play_ranking`].
+#[cfg(feature = "tournament")]
+use crate::discipline::GameCards;
+use crate::game::GameMinute;
+use crate::team::{SquadPlayer, TeamId};
 use derive_more::{Add, AddAssign, Display, From};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "tournament")]
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::iter;
 use std::ops::Mul;
 
 /// Fair play data
@@ -17,7 +28,7 @@ use std::ops::Mul;
 /// let fair_play = FairPlay::new(1, 2, 3, 4);
 /// assert_eq!(FifaFairPlayValue::from(39), FifaFairPlayValue::from_fair_play(&fair_play));
 /// ```
-#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, Hash)]
 pub struct FairPlay {
     yellow: CardCount,
     indirect_red: CardCount,
@@ -39,9 +50,101 @@ impl FairPlay {
             yellow_and_direct: yellow_and_direct.into(),
         }
     }
+
+    /// Aggregate card events
+    ///
+    /// Counts all events, regardless of team.
+    /// Use [`FairPlayScore::from_events`] to split events by team.
+    ///
+    /// ```
+    /// # use wwc_core::fair_play::{CardEvent, CardKind, FairPlay, FifaFairPlayValue, FairPlayValue};
+    /// # use wwc_core::team::TeamId;
+    /// let events = vec![
+    ///     CardEvent::new(TeamId(0), None, 10, CardKind::Yellow),
+    ///     CardEvent::new(TeamId(0), None, 80, CardKind::DirectRed),
+    /// ];
+    /// let fair_play = FairPlay::from_events(&events);
+    /// assert_eq!(FifaFairPlayValue::from(5), FifaFairPlayValue::from_fair_play(&fair_play));
+    /// ```
+    pub fn from_events<'a>(events: impl IntoIterator<Item = &'a CardEvent>) -> Self {
+        events
+            .into_iter()
+            .fold(FairPlay::default(), |mut acc, event| {
+                acc.add_card(event.kind);
+                acc
+            })
+    }
+
+    /// Add a single card to the aggregate
+    pub fn add_card(&mut self, kind: CardKind) {
+        let count = match kind {
+            CardKind::Yellow => &mut self.yellow,
+            CardKind::IndirectRed => &mut self.indirect_red,
+            CardKind::DirectRed => &mut self.direct_red,
+            CardKind::YellowAndDirectRed => &mut self.yellow_and_direct,
+        };
+        *count += CardCount(1);
+    }
+
+    /// Every card of the aggregate, e.g. two yellow cards as two [`CardKind::Yellow`]
+    pub fn cards(&self) -> impl Iterator<Item = CardKind> {
+        vec![
+            (CardKind::Yellow, self.yellow),
+            (CardKind::IndirectRed, self.indirect_red),
+            (CardKind::DirectRed, self.direct_red),
+            (CardKind::YellowAndDirectRed, self.yellow_and_direct),
+        ]
+        .into_iter()
+        .flat_map(|(kind, CardCount(count))| {
+            iter::repeat_n(kind, usize::try_from(count).expect("u32 -> usize conv"))
+        })
+    }
 }
 
-#[derive(Copy, Clone, Deserialize, Serialize, Debug, Default)]
+/// Kind of card shown to a player
+///
+/// The kinds are mutually exclusive, in the sense that a player shown a second yellow card (and
+/// consequently a red) in a game is represented by a single [`CardKind::IndirectRed`] event, not a
+/// yellow and an indirect red.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub enum CardKind {
+    Yellow,
+    /// Second yellow card
+    IndirectRed,
+    DirectRed,
+    YellowAndDirectRed,
+}
+
+/// Card event
+///
+/// The atomic fair play data.
+/// Aggregate counts ([`FairPlay`]) are derived from lists of card events.
+/// The player is optional since not all data sources provide it.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CardEvent {
+    pub team: TeamId,
+    pub player: Option<SquadPlayer>,
+    pub minute: GameMinute,
+    pub kind: CardKind,
+}
+
+impl CardEvent {
+    pub fn new<M: Into<GameMinute>>(
+        team: TeamId,
+        player: Option<SquadPlayer>,
+        minute: M,
+        kind: CardKind,
+    ) -> Self {
+        CardEvent {
+            team,
+            player,
+            minute: minute.into(),
+            kind,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Deserialize, Serialize, Debug, Default, Hash)]
 pub struct FairPlayScore {
     pub home: FairPlay,
     pub away: FairPlay,
@@ -51,6 +154,31 @@ impl FairPlayScore {
     pub fn new(home: FairPlay, away: FairPlay) -> Self {
         Self { home, away }
     }
+
+    /// Aggregate card events from a single game
+    ///
+    /// Events for teams other than `home` and `away` are ignored.
+    pub fn from_events(home: TeamId, away: TeamId, events: &[CardEvent]) -> Self {
+        Self {
+            home: FairPlay::from_events(events.iter().filter(|event| event.team == home)),
+            away: FairPlay::from_events(events.iter().filter(|event| event.team == away)),
+        }
+    }
+
+    /// Card events of the aggregates, without players and at minute 0
+    ///
+    /// For cards only known by their count, e.g. from a result typed as text.
+    pub fn events(&self, home: TeamId, away: TeamId) -> Vec<CardEvent> {
+        let team_events = |team, fair_play: &FairPlay| {
+            fair_play
+                .cards()
+                .map(move |kind| CardEvent::new(team, None, 0, kind))
+                .collect::<Vec<_>>()
+        };
+        let mut events = team_events(home, &self.home);
+        events.extend(team_events(away, &self.away));
+        events
+    }
 }
 
 pub trait FairPlayValue {
@@ -102,6 +230,7 @@ impl num::Zero for FifaFairPlayValue {
 #[derive(
     Copy,
     Clone,
+    Display,
     Debug,
     Serialize,
     Deserialize,
@@ -141,7 +270,7 @@ impl num::Zero for UefaFairPlayValue {
 }
 
 #[derive(
-    Debug, Copy, Clone, Default, Serialize, Deserialize, Eq, PartialEq, From, Add, AddAssign,
+    Debug, Copy, Clone, Default, Serialize, Deserialize, Eq, PartialEq, Hash, From, Add, AddAssign,
 )]
 pub struct CardCount(u32);
 
@@ -151,3 +280,146 @@ impl Mul<i32> for CardCount {
         self.0 as i32 * rhs
     }
 }
+
+/// Entry in the fair play ranking, see [`fair_play_ranking`]
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FairPlayStanding {
+    pub team: TeamId,
+    /// Games with card data
+    pub games: u32,
+    pub cards: FairPlay,
+    pub value: FifaFairPlayValue,
+    /// Whether the team can win the fair play award, see [`fair_play_award`]
+    pub eligible: bool,
+}
+
+/// Fair play ranking of every team in `games`
+///
+/// By the FIFA Fair Play Trophy criteria: the cards of all games count, with the
+/// [`FifaFairPlayValue`] deductions, best value first.
+/// Equal values are ranked by the number of games, more games being the better average,
+/// then by team id.
+///
+/// Only the teams in `eligible` can win the award, the FIFA criteria only admit the teams
+/// reaching the knockout stage. `None` makes every team eligible, e.g. before the knockout
+/// stage is known.
+#[cfg(feature = "tournament")]
+pub fn fair_play_ranking<'a>(
+    games: impl IntoIterator<Item = &'a GameCards>,
+    eligible: Option<&HashSet<TeamId>>,
+) -> Vec<FairPlayStanding> {
+    let mut records: HashMap<TeamId, (u32, FairPlay)> = HashMap::new();
+    for game in games {
+        for team in [game.home, game.away].iter() {
+            let (num_games, cards) = records.entry(*team).or_default();
+            *num_games += 1;
+            for event in game.events.iter().filter(|event| event.team == *team) {
+                cards.add_card(event.kind);
+            }
+        }
+    }
+    let mut ranking: Vec<FairPlayStanding> = records
+        .into_iter()
+        .map(|(team, (games, cards))| FairPlayStanding {
+            team,
+            games,
+            cards,
+            value: FifaFairPlayValue::from_fair_play(&cards),
+            eligible: eligible
+                .map(|eligible| eligible.contains(&team))
+                .unwrap_or(true),
+        })
+        .collect();
+    ranking.sort_by(|a, b| {
+        b.value
+            .cmp(&a.value)
+            .then_with(|| b.games.cmp(&a.games))
+            .then_with(|| a.team.0.cmp(&b.team.0))
+    });
+    ranking
+}
+
+/// Winner of the fair play award, the best eligible team of a [`fair_play_ranking`]
+pub fn fair_play_award(ranking: &[FairPlayStanding]) -> Option<&FairPlayStanding> {
+    ranking.iter().find(|standing| standing.eligible)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "tournament")]
+    use crate::game::GameId;
+
+    #[cfg(feature = "tournament")]
+    #[test]
+    fn ranking_and_award() {
+        let game = |id: u32, home, away, events| GameCards {
+            id: GameId::from(id),
+            home: TeamId(home),
+            away: TeamId(away),
+            events,
+        };
+        let games = vec![
+            game(
+                1,
+                0,
+                1,
+                vec![
+                    CardEvent::new(TeamId(0), None, 10, CardKind::Yellow),
+                    CardEvent::new(TeamId(1), None, 20, CardKind::DirectRed),
+                ],
+            ),
+            game(
+                2,
+                2,
+                0,
+                vec![CardEvent::new(TeamId(2), None, 30, CardKind::Yellow)],
+            ),
+            game(3, 1, 2, Vec::new()),
+            game(4, 2, 1, Vec::new()),
+        ];
+        let ranking = fair_play_ranking(&games, None);
+        let order: Vec<(u32, u32)> = ranking
+            .iter()
+            .map(|standing| (standing.team.0, standing.games))
+            .collect();
+        // Team 0 and 2 are both at -1, team 2 in more games
+        assert_eq!(order, vec![(2, 3), (0, 2), (1, 3)]);
+        assert_eq!(ranking[2].value, FifaFairPlayValue::from(4));
+        assert_eq!(fair_play_award(&ranking).unwrap().team, TeamId(2));
+
+        let eligible: HashSet<TeamId> = [TeamId(0), TeamId(1)].iter().copied().collect();
+        let ranking = fair_play_ranking(&games, Some(&eligible));
+        assert!(!ranking[0].eligible);
+        assert_eq!(fair_play_award(&ranking).unwrap().team, TeamId(0));
+    }
+    #[test]
+    fn events_split_by_team() {
+        let events = vec![
+            CardEvent::new(TeamId(0), None, 10, CardKind::Yellow),
+            CardEvent::new(TeamId(1), None, 20, CardKind::Yellow),
+            CardEvent::new(TeamId(1), None, 30, CardKind::IndirectRed),
+            CardEvent::new(TeamId(2), None, 40, CardKind::DirectRed),
+        ];
+        let score = FairPlayScore::from_events(TeamId(0), TeamId(1), &events);
+        assert_eq!(
+            FifaFairPlayValue::from_fair_play(&score.home),
+            FifaFairPlayValue::from(1)
+        );
+        assert_eq!(
+            FifaFairPlayValue::from_fair_play(&score.away),
+            FifaFairPlayValue::from(4)
+        );
+    }
+
+    #[test]
+    fn counts_to_events() {
+        let score = FairPlayScore::new(FairPlay::new(2, 0, 1, 0), FairPlay::new(0, 1, 0, 0));
+        let events = score.events(TeamId(3), TeamId(4));
+        assert_eq!(events.len(), 4);
+        let back = FairPlayScore::from_events(TeamId(3), TeamId(4), &events);
+        let value = |fair_play: &FairPlay| FifaFairPlayValue::from_fair_play(fair_play);
+        assert_eq!(value(&back.home), value(&score.home));
+        assert_eq!(value(&back.away), value(&score.away));
+    }
+}
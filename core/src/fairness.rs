@@ -0,0 +1,237 @@
+//! Suspicious prediction patterns
+//!
+//! Helps the admin police cheating in larger pools, from the versioned prediction history.
+//! Two patterns are flagged, see [`check_fairness`]:
+//!
+//! - Identical sheets: accounts with the same effective prediction for every game, e.g. one
+//!   player entering the pool twice to double the chance of winning.
+//! - Edits before an extension: prediction versions submitted shortly before the kickoff of a
+//!   game was moved later, see [`DeadlineExtension`]. Versions submitted after the original
+//!   kickoff are only counted because of the extension, e.g. a player seeing the first minutes
+//!   of a game which was then suspended.
+//!
+//! A flag is not proof, friends do copy each other's sheets. It points the admin to the
+//! accounts to look into.
+use crate::game::GameId;
+use crate::player::{effective_prediction, PlayerId, PredictionVersion};
+use crate::Date;
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Fewest predicted games for identical sheets to count, short sheets are identical by chance
+pub const MIN_IDENTICAL_GAMES: usize = 5;
+
+/// Edits this long before a deadline extension are flagged
+pub const EXTENSION_WINDOW_SECONDS: i64 = 60 * 60;
+
+/// The kickoff of a game moved later, by an admin at `extended_at`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DeadlineExtension {
+    pub game: GameId,
+    pub old_kickoff: Date,
+    pub new_kickoff: Date,
+    pub extended_at: Date,
+}
+
+/// Accounts with the same effective sheet
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdenticalSheets {
+    /// Sorted by id
+    pub players: Vec<PlayerId>,
+    /// Number of predicted games
+    pub num_games: usize,
+}
+
+/// Versions of a player submitted shortly before a deadline extension
+#[derive(Debug, Clone, Serialize)]
+pub struct EditsBeforeExtension {
+    pub player: PlayerId,
+    pub extension: DeadlineExtension,
+    /// Oldest first
+    pub versions: Vec<PredictionVersion>,
+    /// Some version was submitted at or after the original kickoff
+    pub after_original_kickoff: bool,
+}
+
+/// Result of [`check_fairness`]
+#[derive(Debug, Clone, Serialize)]
+pub struct FairnessReport {
+    pub identical_sheets: Vec<IdenticalSheets>,
+    pub edits_before_extension: Vec<EditsBeforeExtension>,
+}
+
+/// Flag suspicious patterns in the prediction versions of every player
+///
+/// The effective sheets are derived with the current `kickoffs`, games without a kickoff use
+/// the latest version. Extensions which moved a kickoff earlier are ignored.
+pub fn check_fairness(
+    versions: &[(PlayerId, Vec<PredictionVersion>)],
+    kickoffs: &HashMap<GameId, Date>,
+    extensions: &[DeadlineExtension],
+) -> FairnessReport {
+    FairnessReport {
+        identical_sheets: identical_sheets(versions, kickoffs),
+        edits_before_extension: extensions
+            .iter()
+            .filter(|extension| extension.new_kickoff.is_after(extension.old_kickoff))
+            .flat_map(|extension| edits_before(versions, extension))
+            .collect(),
+    }
+}
+
+fn identical_sheets(
+    versions: &[(PlayerId, Vec<PredictionVersion>)],
+    kickoffs: &HashMap<GameId, Date>,
+) -> Vec<IdenticalSheets> {
+    let mut by_sheet: HashMap<Vec<(u32, u32, u32)>, Vec<PlayerId>> = HashMap::new();
+    for (player, player_versions) in versions {
+        let sheet: Vec<(u32, u32, u32)> = player_versions
+            .iter()
+            .map(|version| (version.pred.0, version))
+            .into_group_map()
+            .into_iter()
+            .filter_map(|(game, game_versions)| match kickoffs.get(&game) {
+                Some(kickoff) => effective_prediction(game_versions.into_iter(), *kickoff),
+                None => game_versions
+                    .into_iter()
+                    .max_by_key(|version| version.submitted)
+                    .map(|version| version.pred),
+            })
+            .map(|pred| {
+                (
+                    u32::from(pred.0),
+                    u32::from(pred.1.home),
+                    u32::from(pred.1.away),
+                )
+            })
+            .sorted()
+            .collect();
+        if sheet.len() >= MIN_IDENTICAL_GAMES {
+            by_sheet.entry(sheet).or_default().push(*player);
+        }
+    }
+    by_sheet
+        .into_iter()
+        .filter(|(_, players)| players.len() > 1)
+        .map(|(sheet, players)| IdenticalSheets {
+            players: players
+                .into_iter()
+                .sorted_by_key(|id| i32::from(*id))
+                .collect(),
+            num_games: sheet.len(),
+        })
+        .sorted_by_key(|sheets| i32::from(sheets.players[0]))
+        .collect()
+}
+
+fn edits_before(
+    versions: &[(PlayerId, Vec<PredictionVersion>)],
+    extension: &DeadlineExtension,
+) -> Vec<EditsBeforeExtension> {
+    versions
+        .iter()
+        .filter_map(|(player, player_versions)| {
+            let edits: Vec<PredictionVersion> = player_versions
+                .iter()
+                .filter(|version| version.pred.0 == extension.game)
+                .filter(|version| {
+                    let before = version.submitted.seconds_until(extension.extended_at);
+                    before > 0 && before <= EXTENSION_WINDOW_SECONDS
+                })
+                .copied()
+                .sorted_by_key(|version| version.submitted)
+                .collect();
+            if edits.is_empty() {
+                return None;
+            }
+            Some(EditsBeforeExtension {
+                player: *player,
+                extension: *extension,
+                after_original_kickoff: edits
+                    .iter()
+                    .any(|version| !version.submitted.is_before(extension.old_kickoff)),
+                versions: edits,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Score;
+    use crate::player::Prediction;
+
+    fn date(date: &str) -> Date {
+        date.parse().unwrap()
+    }
+
+    fn version(game: u32, home: u32, away: u32, submitted: &str) -> PredictionVersion {
+        PredictionVersion::new(
+            Prediction(GameId::from(game), Score::new(home, away)),
+            date(submitted),
+        )
+    }
+
+    #[test]
+    fn identical() {
+        let sheet = |home: u32| {
+            (1..=5)
+                .map(|game| version(game, home, 0, "2021-06-01 12:00"))
+                .collect::<Vec<_>>()
+        };
+        let mut revised = sheet(2);
+        revised.extend((1..=5).map(|game| version(game, 1, 0, "2021-06-02 12:00")));
+        let versions = vec![
+            (PlayerId::from(1), sheet(1)),
+            (PlayerId::from(2), sheet(2)),
+            (PlayerId::from(3), revised),
+            (PlayerId::from(4), sheet(1)[..4].to_vec()),
+        ];
+        let report = check_fairness(&versions, &HashMap::new(), &[]);
+        // Player 3 revised every game to the sheet of player 1, player 4 predicted too few games
+        assert_eq!(
+            report.identical_sheets,
+            vec![IdenticalSheets {
+                players: vec![PlayerId::from(1), PlayerId::from(3)],
+                num_games: 5,
+            }]
+        );
+    }
+
+    #[test]
+    fn edits_before_extension() {
+        let extension = DeadlineExtension {
+            game: GameId::from(1),
+            old_kickoff: date("2021-06-11 19:00"),
+            new_kickoff: date("2021-06-11 21:00"),
+            extended_at: date("2021-06-11 19:30"),
+        };
+        let versions = vec![
+            (
+                PlayerId::from(1),
+                vec![version(1, 1, 0, "2021-06-11 19:10")],
+            ),
+            (
+                PlayerId::from(2),
+                vec![version(1, 1, 0, "2021-06-11 18:45")],
+            ),
+            (
+                PlayerId::from(3),
+                vec![version(1, 1, 0, "2021-06-11 12:00")],
+            ),
+            (
+                PlayerId::from(4),
+                vec![version(2, 1, 0, "2021-06-11 19:10")],
+            ),
+        ];
+        let report = check_fairness(&versions, &HashMap::new(), &[extension]);
+        let flagged: Vec<(i32, bool)> = report
+            .edits_before_extension
+            .iter()
+            .map(|edits| (i32::from(edits.player), edits.after_original_kickoff))
+            .collect();
+        assert_eq!(flagged, vec![(1, true), (2, false)]);
+    }
+}
@@ -7,7 +7,10 @@ use crate::team::TeamId;
 use derive_more::{Add, AddAssign, Display, From, Into, Neg};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::ops::Sub;
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -84,6 +87,63 @@ pub enum Outcome {
     Lose,
 }
 
+/// Status of a game
+///
+/// Abandoned and annulled games are void: they are kept in the schedule, but do not count in
+/// any stats or standings, see [`GameStatus::is_void`].
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum GameStatus {
+    Scheduled,
+    Played,
+    /// Stopped before full time, e.g. due to the weather.
+    /// Void until replayed or until a result is awarded, then it is played.
+    Abandoned,
+    /// Result voided by the organizers, the game does not count.
+    Annulled,
+}
+
+impl GameStatus {
+    pub const ALL: [GameStatus; 4] = [
+        GameStatus::Scheduled,
+        GameStatus::Played,
+        GameStatus::Abandoned,
+        GameStatus::Annulled,
+    ];
+
+    /// Stable name, used when storing the status
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameStatus::Scheduled => "scheduled",
+            GameStatus::Played => "played",
+            GameStatus::Abandoned => "abandoned",
+            GameStatus::Annulled => "annulled",
+        }
+    }
+
+    /// Abandoned or annulled, the game does not count
+    pub fn is_void(&self) -> bool {
+        matches!(self, GameStatus::Abandoned | GameStatus::Annulled)
+    }
+}
+
+impl fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for GameStatus {
+    type Err = GameError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GameStatus::ALL
+            .iter()
+            .find(|status| status.name() == s)
+            .copied()
+            .ok_or_else(|| GameError::UnknownStatus(String::from(s)))
+    }
+}
+
 #[derive(
     Debug,
     Display,
@@ -115,15 +175,49 @@ pub struct GameId(u32);
     PartialEq,
     Ord,
     PartialOrd,
-    Add,
-    AddAssign,
 )]
 pub struct GoalCount(u32);
 
+impl GoalCount {
+    /// Addition, `None` on overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(GoalCount)
+    }
+
+    /// Addition, clamped at the numeric bound
+    pub fn saturating_add(self, other: Self) -> Self {
+        GoalCount(self.0.saturating_add(other.0))
+    }
+}
+
+/// Saturating addition, see [`GoalCount::saturating_add`]
+impl Add for GoalCount {
+    type Output = Self;
+    fn add(self, other: Self) -> Self::Output {
+        self.saturating_add(other)
+    }
+}
+
+impl AddAssign for GoalCount {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sum for GoalCount {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(GoalCount(0), Add::add)
+    }
+}
+
+/// Goal difference between two goal counts
+///
+/// Saturates at the bounds of [`GoalDiff`] rather than wrapping.
 impl Sub for GoalCount {
     type Output = GoalDiff;
     fn sub(self, other: Self) -> Self::Output {
-        GoalDiff(self.0 as i32 - other.0 as i32)
+        let diff = i64::from(self.0) - i64::from(other.0);
+        GoalDiff(i32::try_from(diff).unwrap_or(if diff > 0 { i32::MAX } else { i32::MIN }))
     }
 }
 
@@ -150,11 +244,41 @@ impl num::Zero for GoalCount {
     PartialEq,
     Ord,
     PartialOrd,
-    Add,
-    AddAssign,
 )]
 pub struct GoalDiff(pub i32);
 
+impl GoalDiff {
+    /// Addition, `None` on overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(GoalDiff)
+    }
+
+    /// Addition, clamped at the numeric bounds
+    pub fn saturating_add(self, other: Self) -> Self {
+        GoalDiff(self.0.saturating_add(other.0))
+    }
+}
+
+/// Saturating addition, see [`GoalDiff::saturating_add`]
+impl Add for GoalDiff {
+    type Output = Self;
+    fn add(self, other: Self) -> Self::Output {
+        self.saturating_add(other)
+    }
+}
+
+impl AddAssign for GoalDiff {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sum for GoalDiff {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(GoalDiff(0), Add::add)
+    }
+}
+
 impl num::Zero for GoalDiff {
     fn zero() -> GoalDiff {
         GoalDiff(0)
@@ -192,6 +316,28 @@ impl num::Zero for NumGames {
     }
 }
 
+/// Minute of a game in which an event occurred
+///
+/// Counted from kick-off, stoppage time is added to the minute of the half it extends,
+/// e.g. an event in the third minute of first half stoppage time is minute 48.
+#[derive(
+    Default,
+    Debug,
+    Display,
+    Deserialize,
+    Serialize,
+    Clone,
+    Copy,
+    From,
+    Into,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+)]
+pub struct GameMinute(pub u8);
+
 pub trait Game {
     fn home_team(&self) -> TeamId;
     fn away_team(&self) -> TeamId;
@@ -201,4 +347,40 @@ pub trait Game {
 pub enum GameError {
     #[error("Error parsing score: '{0}'")]
     ScoreParse(String),
+    #[error("Unknown game status '{0}'")]
+    UnknownStatus(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn goal_count_saturates() {
+        let max = GoalCount(u32::MAX);
+        assert_eq!(max + GoalCount(1), max);
+        assert_eq!(max.checked_add(GoalCount(1)), None);
+        assert_eq!(GoalCount(1).checked_add(GoalCount(2)), Some(GoalCount(3)));
+    }
+
+    #[test]
+    fn goal_count_diff_saturates() {
+        assert_eq!(GoalCount(u32::MAX) - GoalCount(0), GoalDiff(i32::MAX));
+        assert_eq!(GoalCount(0) - GoalCount(u32::MAX), GoalDiff(i32::MIN));
+        assert_eq!(GoalCount(1) - GoalCount(3), GoalDiff(-2));
+    }
+
+    #[test]
+    fn goal_diff_saturates() {
+        assert_eq!(GoalDiff(i32::MAX) + GoalDiff(1), GoalDiff(i32::MAX));
+        assert_eq!(GoalDiff(i32::MIN) + GoalDiff(-1), GoalDiff(i32::MIN));
+        assert_eq!(GoalDiff(i32::MIN).checked_add(GoalDiff(-1)), None);
+    }
+
+    #[test]
+    fn sums() {
+        let counts = vec![GoalCount(1), GoalCount(2), GoalCount(u32::MAX)];
+        assert_eq!(counts.into_iter().sum::<GoalCount>(), GoalCount(u32::MAX));
+        let diffs = vec![GoalDiff(1), GoalDiff(-3)];
+        assert_eq!(diffs.into_iter().sum::<GoalDiff>(), GoalDiff(-2));
+    }
 }
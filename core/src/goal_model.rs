@@ -0,0 +1,228 @@
+//! Goal models
+//!
+//! Probability models for the score of a game, used by the [`simulation`](crate::simulation).
+use crate::game::Score;
+use crate::group::game::PlayedGroupGame;
+use crate::team::TeamId;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Probability model for the score of a game
+pub trait GoalModel {
+    /// Sample a score for a game between `home` and `away`
+    fn sample<R: Rng + ?Sized>(&self, home: TeamId, away: TeamId, rng: &mut R) -> Score;
+}
+
+/// Team strength
+///
+/// Multiplicative factors relative to an average team (1.0).
+/// A high `attack` means more goals scored, a high `defence` means more goals conceded.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TeamStrength {
+    pub attack: f64,
+    pub defence: f64,
+}
+
+impl TeamStrength {
+    pub fn new(attack: f64, defence: f64) -> Self {
+        TeamStrength { attack, defence }
+    }
+}
+
+impl Default for TeamStrength {
+    fn default() -> Self {
+        TeamStrength::new(1.0, 1.0)
+    }
+}
+
+/// Poisson goal model
+///
+/// The goals of each team are independent and Poisson distributed with mean
+///
+/// `base_rate * attack(team) * defence(opponent)`
+///
+/// where the mean of the home team is also multiplied with `home_advantage`.
+/// Teams without a strength are considered average.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PoissonModel {
+    /// Mean number of goals for a team in a game between two average teams
+    pub base_rate: f64,
+    /// Factor for the mean number of goals of the home team
+    pub home_advantage: f64,
+    pub strengths: HashMap<TeamId, TeamStrength>,
+}
+
+/// Weight (in games) of the average team prior in [`PoissonModel::calibrate`]
+const PRIOR_GAMES: f64 = 2.0;
+
+impl PoissonModel {
+    pub fn new(base_rate: f64, home_advantage: f64) -> Self {
+        PoissonModel {
+            base_rate,
+            home_advantage,
+            strengths: HashMap::new(),
+        }
+    }
+
+    pub fn with_strength(mut self, team: TeamId, strength: TeamStrength) -> Self {
+        self.strengths.insert(team, strength);
+        self
+    }
+
+    /// Calibrate a model from played games
+    ///
+    /// Simple moment matching: the base rate is the mean number of goals per team and game,
+    /// the home advantage is the ratio of home to away goals and
+    /// the strengths are the goals scored (conceded) per game relative to the base rate.
+    ///
+    /// The strengths are shrunk towards an average team, corresponding to a couple of extra
+    /// games with an average outcome, otherwise a single 0-0 game would give a team zero attack.
+    pub fn calibrate<'a>(games: impl IntoIterator<Item = &'a PlayedGroupGame>) -> Self {
+        let mut num_games = 0.0;
+        let (mut home_goals, mut away_goals) = (0.0, 0.0);
+        let mut team_goals: HashMap<TeamId, (f64, f64, f64)> = HashMap::new();
+        for game in games {
+            let (home, away) = (
+                f64::from(u32::from(game.score.home)),
+                f64::from(u32::from(game.score.away)),
+            );
+            num_games += 1.0;
+            home_goals += home;
+            away_goals += away;
+            let entry = team_goals.entry(game.home).or_default();
+            *entry = (entry.0 + 1.0, entry.1 + home, entry.2 + away);
+            let entry = team_goals.entry(game.away).or_default();
+            *entry = (entry.0 + 1.0, entry.1 + away, entry.2 + home);
+        }
+        if num_games == 0.0 || home_goals + away_goals == 0.0 {
+            return Self::default();
+        }
+        let base_rate = (home_goals + away_goals) / (2.0 * num_games);
+        let home_advantage = if away_goals > 0.0 {
+            home_goals / away_goals
+        } else {
+            1.0
+        };
+        let strengths = team_goals
+            .into_iter()
+            .map(|(team, (games, scored, conceded))| {
+                let prior = PRIOR_GAMES * base_rate;
+                let attack = (scored + prior) / ((games + PRIOR_GAMES) * base_rate);
+                let defence = (conceded + prior) / ((games + PRIOR_GAMES) * base_rate);
+                (team, TeamStrength::new(attack, defence))
+            })
+            .collect();
+        PoissonModel {
+            base_rate,
+            home_advantage,
+            strengths,
+        }
+    }
+
+    fn strength(&self, team: TeamId) -> TeamStrength {
+        self.strengths.get(&team).copied().unwrap_or_default()
+    }
+
+    /// Expected number of goals (home, away)
+    pub fn expected_goals(&self, home: TeamId, away: TeamId) -> (f64, f64) {
+        let (home_str, away_str) = (self.strength(home), self.strength(away));
+        (
+            self.base_rate * self.home_advantage * home_str.attack * away_str.defence,
+            self.base_rate * away_str.attack * home_str.defence,
+        )
+    }
+
+    /// Most likely score
+    ///
+    /// The goals are independent, so the most likely score is the mode of each Poisson
+    /// distribution, the expected goals rounded down.
+    pub fn most_likely_score(&self, home: TeamId, away: TeamId) -> Score {
+        let (home_mean, away_mean) = self.expected_goals(home, away);
+        let mode = |mean: f64| mean.max(0.0).floor() as u32;
+        Score::new(mode(home_mean), mode(away_mean))
+    }
+}
+
+/// Average international football: about 1.3 goals per team and game, no home advantage.
+impl Default for PoissonModel {
+    fn default() -> Self {
+        PoissonModel::new(1.3, 1.0)
+    }
+}
+
+impl GoalModel for PoissonModel {
+    fn sample<R: Rng + ?Sized>(&self, home: TeamId, away: TeamId, rng: &mut R) -> Score {
+        let (home_mean, away_mean) = self.expected_goals(home, away);
+        Score::new(
+            sample_poisson(home_mean, rng),
+            sample_poisson(away_mean, rng),
+        )
+    }
+}
+
+/// Sample from a Poisson distribution
+///
+/// Knuth's multiplication method, fine for the small means of football scores.
+fn sample_poisson<R: Rng + ?Sized>(mean: f64, rng: &mut R) -> u32 {
+    let limit = (-mean).exp();
+    let mut count = 0;
+    let mut prod: f64 = rng.gen();
+    while prod > limit {
+        count += 1;
+        prod *= rng.gen::<f64>();
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::group::game::UnplayedGroupGame;
+    use crate::Date;
+    use assert_approx_eq::assert_approx_eq;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn poisson_mean() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let n = 10_000;
+        let sum: u32 = (0..n).map(|_| sample_poisson(1.5, &mut rng)).sum();
+        assert_approx_eq!(f64::from(sum) / f64::from(n), 1.5, 0.05);
+    }
+
+    #[test]
+    fn calibrate() {
+        let games: Vec<PlayedGroupGame> = vec![(0, 1, 2, (3, 0)), (1, 2, 3, (1, 1))]
+            .into_iter()
+            .map(|(id, home, away, score)| {
+                UnplayedGroupGame::try_new(id, home, away, Date::mock())
+                    .unwrap()
+                    .play(Score::from(score), FairPlayScore::default())
+            })
+            .collect();
+        let model = PoissonModel::calibrate(&games);
+        assert_approx_eq!(model.base_rate, 1.25);
+        assert_approx_eq!(model.home_advantage, 4.0);
+        let (strong, weak) = (model.strength(TeamId(1)), model.strength(TeamId(2)));
+        assert!(strong.attack > 1.0 && strong.defence < 1.0);
+        assert!(weak.attack < 1.0 && weak.defence > 1.0);
+        assert_eq!(model.strength(TeamId(4)), TeamStrength::default());
+    }
+
+    #[test]
+    fn most_likely() {
+        let model = PoissonModel::new(1.3, 1.0)
+            .with_strength(TeamId(1), TeamStrength::new(2.0, 0.5))
+            .with_strength(TeamId(2), TeamStrength::new(0.5, 2.0));
+        assert_eq!(
+            model.most_likely_score(TeamId(1), TeamId(2)),
+            Score::from((5, 0))
+        );
+        assert_eq!(
+            model.most_likely_score(TeamId(3), TeamId(4)),
+            Score::from((1, 1))
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Ordering across groups
+//!
+//! Some formats advance the best teams of a group position across all groups,
+//! e.g. the four best third placed teams of Euro 2020 or the eight best of WC 2026.
+//! Teams from different groups have not played each other, so only sub-orders based on all
+//! group games apply, see [`CrossGroupRules`].
+//!
+//! With groups of unequal size, or to not reward a weak group, some games are discarded before
+//! comparing the teams, see [`GameFilter`].
+use crate::fair_play::{FifaFairPlayValue, UefaFairPlayValue};
+use crate::game::{GoalCount, GoalDiff};
+use crate::group::order::{order_group, order_teams, AllGroupStat, GroupRank, Rules};
+use crate::group::order::{AnyTiebreaker, Tiebreaker, UefaRanking};
+use crate::group::stats::NumWins;
+use crate::group::{Group, GroupError, GroupId, GroupPoint, Groups};
+use crate::team::{TeamId, TeamRank};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Group games counted when comparing teams from different groups
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameFilter {
+    /// Every game counts
+    All,
+    /// Discard games against the teams at these positions in their group,
+    /// e.g. `[GroupRank(3)]` for results against the fourth placed team.
+    ExcludeAgainst(Vec<GroupRank>),
+    /// Discard games against the lowest placed teams in groups larger than the smallest group,
+    /// so that every team is compared on the same number of games.
+    EqualizeGroupSizes,
+}
+
+impl GameFilter {
+    /// Teams in a group whose games are discarded
+    ///
+    /// `order` is the final order of the group, `min_size` the size of the smallest group.
+    fn excluded(&self, order: &[TeamId], min_size: usize) -> HashSet<TeamId> {
+        match self {
+            GameFilter::All => HashSet::new(),
+            GameFilter::ExcludeAgainst(ranks) => ranks
+                .iter()
+                .filter_map(|rank| order.get(rank.0).copied())
+                .collect(),
+            GameFilter::EqualizeGroupSizes => order.iter().skip(min_size).copied().collect(),
+        }
+    }
+}
+
+/// Rules for ordering teams from different groups
+///
+/// The sub-orders only compare stats from all (filtered) group games,
+/// head-to-head sub-orders are meaningless across groups.
+pub struct CrossGroupRules<T: Tiebreaker> {
+    rules: Rules<T>,
+    filter: GameFilter,
+}
+
+impl<T: Tiebreaker> CrossGroupRules<T> {
+    /// Replace the game filter, the presets count every game
+    pub fn with_filter(self, filter: GameFilter) -> Self {
+        CrossGroupRules { filter, ..self }
+    }
+
+    pub fn filter(&self) -> &GameFilter {
+        &self.filter
+    }
+}
+
+/// Team at a position in its group, see [`rank_across_groups`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrossGroupEntry {
+    pub group: GroupId,
+    pub team: TeamId,
+}
+
+/// Rank the teams at `position` of every group, best first
+///
+/// Every group is first ordered by `group_rules`, groups without a team at `position` are left
+/// out.
+/// Teams are then compared by `rules`, on the games left by the rules' [`GameFilter`].
+/// Games against the compared teams themselves are never discarded.
+/// Teams without any game left, e.g. from a group which has not started, are ranked last,
+/// by group id.
+///
+/// # Errors
+///
+/// Game id's are not unique across the groups.
+pub fn rank_across_groups<T: Tiebreaker, U: Tiebreaker>(
+    groups: &Groups,
+    group_rules: &Rules<T>,
+    position: GroupRank,
+    rules: &CrossGroupRules<U>,
+) -> Result<Vec<CrossGroupEntry>, GroupError> {
+    let min_size = groups.values().map(Group::num_teams).min().unwrap_or(0);
+    let mut entries = Vec::new();
+    let mut played_games = Vec::new();
+    for (id, group) in groups {
+        let order: Vec<TeamId> = order_group(group, group_rules).into_iter().collect();
+        let team = match order.get(position.0) {
+            Some(team) => *team,
+            None => continue,
+        };
+        let mut excluded = rules.filter.excluded(&order, min_size);
+        excluded.remove(&team);
+        played_games.extend(
+            group
+                .played_games()
+                .filter(|game| game.home == team || game.away == team)
+                .filter(|game| !excluded.contains(&game.home) && !excluded.contains(&game.away))
+                .cloned(),
+        );
+        entries.push(CrossGroupEntry { group: *id, team });
+    }
+    // Merging the games of the different groups into a single group lets the sub-orders compare
+    // the teams.
+    let merged = Group::try_new(vec![], played_games)?;
+    let (compared, without_games): (Vec<_>, Vec<_>) = entries
+        .into_iter()
+        .partition(|entry| merged.contains(entry.team));
+    let order = order_teams(
+        &merged,
+        compared.iter().map(|entry| entry.team).collect(),
+        &rules.rules,
+    );
+    Ok(order
+        .into_iter()
+        .filter_map(|team| compared.iter().find(|entry| entry.team == team).copied())
+        .chain(without_games)
+        .collect())
+}
+
+/// Fifa World Cup 2026, third placed teams
+///
+/// 1. Points in all group matches
+/// 2. Goal difference in all group matches
+/// 3. Goals scored in all group matches
+/// 4. Fair play points in all group matches
+/// 5. Fifa ranking, the [`UefaRanking`] tiebreaker works for any ranking
+pub fn fifa_2026(ranking: UefaRanking) -> CrossGroupRules<UefaRanking> {
+    let group_point: AllGroupStat<GroupPoint> = AllGroupStat::new("points");
+    let goal_diff: AllGroupStat<GoalDiff> = AllGroupStat::new("goal difference");
+    let goal_count: AllGroupStat<GoalCount> = AllGroupStat::new("goals scored");
+    let fair_play: AllGroupStat<FifaFairPlayValue> = AllGroupStat::new("fair play");
+    CrossGroupRules {
+        rules: Rules::new(
+            vec![
+                Box::new(group_point),
+                Box::new(goal_diff),
+                Box::new(goal_count),
+                Box::new(fair_play),
+            ],
+            ranking,
+        ),
+        filter: GameFilter::All,
+    }
+}
+
+/// Uefa Euro 2020, third placed teams
+///
+/// 1. Points
+/// 2. Goal difference
+/// 3. Goals scored
+/// 4. Wins
+/// 5. Disciplinary points
+/// 6. European Qualifiers overall ranking
+pub fn euro_2020(ranking: UefaRanking) -> CrossGroupRules<UefaRanking> {
+    let group_point: AllGroupStat<GroupPoint> = AllGroupStat::new("points");
+    let goal_diff: AllGroupStat<GoalDiff> = AllGroupStat::new("goal difference");
+    let goal_count: AllGroupStat<GoalCount> = AllGroupStat::new("goals scored");
+    let num_wins: AllGroupStat<NumWins> = AllGroupStat::new("wins");
+    let fair_play: AllGroupStat<UefaFairPlayValue> = AllGroupStat::new("fair play");
+    CrossGroupRules {
+        rules: Rules::new(
+            vec![
+                Box::new(group_point),
+                Box::new(goal_diff),
+                Box::new(goal_count),
+                Box::new(num_wins),
+                Box::new(fair_play),
+            ],
+            ranking,
+        ),
+        filter: GameFilter::All,
+    }
+}
+
+/// Names of the rules available through [`rules_by_name`]
+pub const RULE_NAMES: [&str; 2] = ["euro_2020", "fifa_2026"];
+
+/// Cross group rules, selected by name, see [`RULE_NAMES`]
+///
+/// Like [`order::rules_by_name`](crate::group::order::rules_by_name), the `ranking` must include
+/// every team in `groups`.
+pub fn rules_by_name(
+    name: &str,
+    groups: &[Group],
+    ranking: HashMap<TeamId, TeamRank>,
+) -> Result<CrossGroupRules<AnyTiebreaker>, GroupError> {
+    let rules = match name {
+        "euro_2020" => euro_2020(UefaRanking::try_new(groups, ranking)?),
+        "fifa_2026" => fifa_2026(UefaRanking::try_new(groups, ranking)?),
+        _ => return Err(GroupError::UnknownRules),
+    };
+    Ok(CrossGroupRules {
+        rules: rules.rules.map_tiebreaker(AnyTiebreaker::UefaRanking),
+        filter: rules.filter,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::game::Score;
+    use crate::group::game::{PlayedGroupGame, UnplayedGroupGame};
+    use crate::group::order::fifa_2018;
+    use crate::Date;
+    use std::collections::BTreeMap;
+
+    fn game(id: u32, home: u32, away: u32, score: (u32, u32)) -> PlayedGroupGame {
+        UnplayedGroupGame::try_new(id, home, away, Date::mock())
+            .unwrap()
+            .play(Score::from(score), FairPlayScore::default())
+    }
+
+    /// Group A: 1 > 2 > 3, group B: 4 > 5 > 6 > 7
+    ///
+    /// Runner-up 2 has 3 points and +1, runner-up 5 has 4 points, but 3 of them and +4 against 7.
+    fn groups() -> Groups {
+        let group_a = Group::try_new(
+            vec![],
+            vec![
+                game(1, 1, 2, (1, 0)),
+                game(2, 2, 3, (2, 0)),
+                game(3, 1, 3, (1, 0)),
+            ],
+        )
+        .unwrap();
+        let group_b = Group::try_new(
+            vec![],
+            vec![
+                game(4, 4, 5, (1, 0)),
+                game(5, 4, 6, (1, 0)),
+                game(6, 4, 7, (1, 0)),
+                game(7, 5, 6, (0, 0)),
+                game(8, 5, 7, (4, 0)),
+                game(9, 6, 7, (1, 0)),
+            ],
+        )
+        .unwrap();
+        let mut groups = BTreeMap::new();
+        groups.insert(GroupId('A'), group_a);
+        groups.insert(GroupId('B'), group_b);
+        groups
+    }
+
+    fn ranking(groups: &Groups) -> UefaRanking {
+        let ranking: HashMap<TeamId, TeamRank> =
+            (1..=7).map(|id| (TeamId(id), TeamRank(id))).collect();
+        UefaRanking::try_new(&groups.values().cloned().collect::<Vec<_>>(), ranking).unwrap()
+    }
+
+    #[test]
+    fn runners_up() {
+        let groups = groups();
+        let rules = fifa_2026(ranking(&groups));
+        let order = rank_across_groups(&groups, &fifa_2018(), GroupRank(1), &rules).unwrap();
+        let teams: Vec<TeamId> = order.iter().map(|entry| entry.team).collect();
+        assert_eq!(teams, vec![TeamId(5), TeamId(2)]);
+        assert_eq!(order[0].group, GroupId('B'));
+    }
+
+    #[test]
+    fn equalize_group_sizes() {
+        let groups = groups();
+        // Without the game against 7, runner-up 5 has 1 point and -1.
+        let rules = fifa_2026(ranking(&groups)).with_filter(GameFilter::EqualizeGroupSizes);
+        let order = rank_across_groups(&groups, &fifa_2018(), GroupRank(1), &rules).unwrap();
+        let teams: Vec<TeamId> = order.iter().map(|entry| entry.team).collect();
+        assert_eq!(teams, vec![TeamId(2), TeamId(5)]);
+    }
+
+    #[test]
+    fn missing_position() {
+        let groups = groups();
+        let rules = fifa_2026(ranking(&groups));
+        let order = rank_across_groups(&groups, &fifa_2018(), GroupRank(3), &rules).unwrap();
+        assert_eq!(
+            order,
+            vec![CrossGroupEntry {
+                group: GroupId('B'),
+                team: TeamId(7)
+            }]
+        );
+    }
+}
@@ -7,8 +7,9 @@
 //! The two game structs [`UnplayedGroupGame`] and [`PlayedGroupGame`]
 //! are the fundamental datastructure for the group; all other properties and statistics are
 //! derived from them.
+//! Abandoned and annulled games are kept apart as [`VoidGroupGame`]s, they do not count.
 use crate::fair_play::FairPlayScore;
-use crate::game::{Game, GameId, GoalCount, GoalDiff, Score};
+use crate::game::{Game, GameId, GameStatus, GoalCount, GoalDiff, Score};
 use crate::group::stats::UnaryStat;
 use crate::group::{GroupError, GroupPoint};
 use crate::team::TeamId;
@@ -64,6 +65,35 @@ impl UnplayedGroupGame {
             fair_play,
         }
     }
+
+    /// Transform unplayed game to void, i.e. abandoned or annulled
+    ///
+    /// # Errors
+    ///
+    /// The status must be void, see [`GameStatus::is_void`].
+    pub fn void(self, status: GameStatus) -> Result<VoidGroupGame, GroupError> {
+        if status.is_void() {
+            Ok(VoidGroupGame {
+                id: self.id,
+                home: self.home,
+                away: self.away,
+                date: self.date,
+                status,
+            })
+        } else {
+            Err(GroupError::NotVoid(status))
+        }
+    }
+
+    /// Kick-off date
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    /// Check if `now` is strictly before kick-off, i.e. the game is still open for predictions
+    pub fn is_before_kickoff(&self, now: Date) -> bool {
+        now.is_before(self.date)
+    }
 }
 
 impl Game for UnplayedGroupGame {
@@ -84,6 +114,8 @@ pub struct PlayedGroupGame {
     pub home: TeamId,
     pub away: TeamId,
     pub score: Score,
+    /// No cards if omitted when deserializing
+    #[serde(default)]
     pub(crate) fair_play: FairPlayScore,
     pub(crate) date: Date,
 }
@@ -136,6 +168,11 @@ impl PlayedGroupGame {
         }
     }
 
+    /// Kick-off date
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
     /// Points awarded to (home, away) teams respectively.
     pub fn points(&self) -> (GroupPoint, GroupPoint) {
         GroupPoint::stat(self)
@@ -161,6 +198,119 @@ impl Game for PlayedGroupGame {
     }
 }
 
+/// Abandoned or annulled group game
+///
+/// Kept in the group, so that the teams and the schedule are complete, but excluded from all
+/// stats and standings.
+/// Constructed by invoking the [`UnplayedGroupGame::void`] method.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub struct VoidGroupGame {
+    pub id: GameId,
+    pub home: TeamId,
+    pub away: TeamId,
+    date: Date,
+    status: GameStatus,
+}
+
+impl VoidGroupGame {
+    /// Transform void game back to unplayed, e.g. to replay an abandoned game
+    pub fn restore(self) -> UnplayedGroupGame {
+        UnplayedGroupGame {
+            id: self.id,
+            home: self.home,
+            away: self.away,
+            date: self.date,
+        }
+    }
+
+    /// Kick-off date
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    /// Abandoned or annulled
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+}
+
+impl Game for VoidGroupGame {
+    fn home_team(&self) -> TeamId {
+        self.home
+    }
+    fn away_team(&self) -> TeamId {
+        self.away
+    }
+}
+
+/// Group game, played or not
+///
+/// Borrowed view used to iterate over all games of a group, see [`Group::games`].
+///
+/// [`Group::games`]: crate::group::Group::games
+#[derive(Debug, Clone, Copy)]
+pub enum GroupGame<'a> {
+    Played(&'a PlayedGroupGame),
+    Unplayed(&'a UnplayedGroupGame),
+    Void(&'a VoidGroupGame),
+}
+
+impl GroupGame<'_> {
+    pub fn id(&self) -> GameId {
+        match self {
+            GroupGame::Played(game) => game.id,
+            GroupGame::Unplayed(game) => game.id,
+            GroupGame::Void(game) => game.id,
+        }
+    }
+
+    /// Kick-off date
+    pub fn date(&self) -> Date {
+        match self {
+            GroupGame::Played(game) => game.date(),
+            GroupGame::Unplayed(game) => game.date(),
+            GroupGame::Void(game) => game.date(),
+        }
+    }
+
+    /// Score, `None` if the game is not played yet or void
+    pub fn score(&self) -> Option<Score> {
+        match self {
+            GroupGame::Played(game) => Some(game.score),
+            GroupGame::Unplayed(_) | GroupGame::Void(_) => None,
+        }
+    }
+
+    pub fn is_played(&self) -> bool {
+        matches!(self, GroupGame::Played(_))
+    }
+
+    pub fn status(&self) -> GameStatus {
+        match self {
+            GroupGame::Played(_) => GameStatus::Played,
+            GroupGame::Unplayed(_) => GameStatus::Scheduled,
+            GroupGame::Void(game) => game.status(),
+        }
+    }
+}
+
+impl Game for GroupGame<'_> {
+    fn home_team(&self) -> TeamId {
+        match self {
+            GroupGame::Played(game) => game.home,
+            GroupGame::Unplayed(game) => game.home,
+            GroupGame::Void(game) => game.home,
+        }
+    }
+    fn away_team(&self) -> TeamId {
+        match self {
+            GroupGame::Played(game) => game.away,
+            GroupGame::Unplayed(game) => game.away,
+            GroupGame::Void(game) => game.away,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
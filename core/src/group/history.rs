@@ -0,0 +1,104 @@
+//! Standings history
+//!
+//! The group standings after every played game, for charting how the ranks evolve over the group
+//! stage.
+//! The standings after a game count the games played up to and including it, by kickoff,
+//! regardless of the order in which the results were entered.
+use crate::game::GameId;
+use crate::group::game::PlayedGroupGame;
+use crate::group::order::GroupRank;
+use crate::group::stats::UnaryStat;
+use crate::group::view::GroupView;
+use crate::group::{order_group, Group, GroupPoint, Rules, Tiebreaker};
+use crate::team::TeamId;
+use crate::Date;
+use serde::{Deserialize, Serialize};
+
+/// Rank and points of a team in a [`StandingsSnapshot`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotRow {
+    pub team: TeamId,
+    pub rank: GroupRank,
+    pub points: GroupPoint,
+}
+
+/// Group standings after a played game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandingsSnapshot {
+    pub game_id: GameId,
+    pub kickoff: Date,
+    /// Every team of the group, best first
+    pub rows: Vec<SnapshotRow>,
+}
+
+/// Standings after every played game of a group, in kickoff order
+///
+/// Games with the same kickoff are taken in game id order, like [`Group::games`].
+pub fn standings_history<T: Tiebreaker>(group: &Group, rules: &Rules<T>) -> Vec<StandingsSnapshot> {
+    let mut played: Vec<PlayedGroupGame> = group.played_games().copied().collect();
+    played.sort_by_key(|game| (game.date, game.id));
+    // The group with every game unplayed, the results are added back one by one through a view
+    let unplayed = group
+        .unplayed_games()
+        .copied()
+        .chain(played.iter().map(|game| game.unplay()))
+        .collect();
+    let base = Group::try_new(unplayed, vec![])
+        .and_then(|base| base.with_void_games(group.void_games().copied().collect()))
+        .expect("Game ids of a group are unique");
+    (1..=played.len())
+        .map(|num_played| {
+            let view = GroupView::with_results(&base, &played[..num_played]);
+            let points = GroupPoint::team_stats(view);
+            let rows = order_group(view, rules)
+                .into_iter()
+                .enumerate()
+                .map(|(rank, team)| SnapshotRow {
+                    team,
+                    rank: GroupRank(rank),
+                    points: points[&team],
+                })
+                .collect();
+            let game = &played[num_played - 1];
+            StandingsSnapshot {
+                game_id: game.id,
+                kickoff: game.date,
+                rows,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::game::Score;
+    use crate::group::game::UnplayedGroupGame;
+    use crate::group::order::fifa_2018;
+
+    #[test]
+    fn kickoff_order() {
+        let late = Date::mock().add_hours(48);
+        let games = vec![(0, 0, 1, Date::mock(), (0, 2)), (1, 0, 1, late, (3, 0))]
+            .into_iter()
+            .map(|(id, home, away, kickoff, score)| {
+                UnplayedGroupGame::try_new(id, home, away, kickoff)
+                    .unwrap()
+                    .play(Score::from(score), FairPlayScore::default())
+            })
+            .rev()
+            .collect();
+        let group = Group::try_new(vec![], games).unwrap();
+        let history = standings_history(&group, &fifa_2018());
+
+        let game_ids: Vec<GameId> = history.iter().map(|snapshot| snapshot.game_id).collect();
+        assert_eq!(game_ids, vec![GameId::from(0), GameId::from(1)]);
+        let first = &history[0].rows[0];
+        assert_eq!((first.team, first.points), (TeamId(1), GroupPoint(3)));
+        // Team 0 overtakes on goal difference
+        let first = &history[1].rows[0];
+        assert_eq!((first.team, first.points), (TeamId(0), GroupPoint(3)));
+        assert_eq!(history[1].rows[1].rank, GroupRank(1));
+    }
+}
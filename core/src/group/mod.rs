@@ -1,23 +1,41 @@
 //! Tournament group play
+pub mod cross_group;
 pub mod game;
+pub mod history;
 pub mod order;
+pub mod order_cache;
+#[cfg(feature = "tournament")]
+pub mod projection;
+#[cfg(feature = "tournament")]
+pub mod schedule;
 pub mod stats;
+pub mod view;
+pub mod withdrawal;
+#[cfg(feature = "tournament")]
+use crate::error::ErrorCode;
 use crate::fair_play::FairPlayScore;
-use crate::game::{Game, GoalCount, GoalDiff, Score};
-use crate::game::{GameId, NumGames};
+
+use crate::game::GameId;
+#[cfg(feature = "random")]
+use crate::game::NumGames;
+use crate::game::{Game, GameStatus, GoalCount, GoalDiff, Score};
 use crate::team::TeamId;
-use derive_more::{Add, AddAssign, Display, From, Into};
-use game::{PlayedGroupGame, UnplayedGroupGame};
+use derive_more::{Display, From, Into};
+use game::{GroupGame, PlayedGroupGame, UnplayedGroupGame, VoidGroupGame};
 use itertools::Itertools;
 pub use order::{order_group, GroupOrder, Rules, Tiebreaker};
+#[cfg(feature = "random")]
 use rand::{
     distributions::Distribution, distributions::Uniform, rngs::StdRng, seq::IteratorRandom,
     thread_rng, SeedableRng,
 };
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "tournament")]
+use serde_json::{json, Value};
 use stats::UnaryStat;
 use std::collections::{BTreeMap, HashMap};
 use std::iter;
+use std::ops;
 use thiserror::Error;
 
 /// Type alias for a mapping of `GroupId` to `Group`
@@ -72,10 +90,22 @@ impl GroupId {
 /// Intuitively, one might expect it to hold group stats, whether it is finished, a ranked list of the
 /// teams et c.
 /// Fundamentally though, the only data are the games. Everything else can be derived from them.
+///
+/// Deserializes from e.g. `{"played_games": [..], "unplayed_games": [..]}`, an omitted list is
+/// empty.
+/// Abandoned and annulled games are kept in `void_games`, they do not count in any stats, see
+/// [`VoidGroupGame`].
+/// Every list is kept sorted by kick-off and game id, so [`games`](Group::games) merges them
+/// without sorting.
 #[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(from = "UnsortedGroup")]
 pub struct Group {
+    #[serde(default)]
     played_games: Vec<PlayedGroupGame>,
+    #[serde(default)]
     unplayed_games: Vec<UnplayedGroupGame>,
+    #[serde(default)]
+    void_games: Vec<VoidGroupGame>,
 }
 
 impl Group {
@@ -94,11 +124,27 @@ impl Group {
         unplayed_games: Vec<UnplayedGroupGame>,
         played_games: Vec<PlayedGroupGame>,
     ) -> Result<Self, GroupError> {
-        if Self::game_ids_unique(&played_games, &unplayed_games) {
-            Ok(Self {
+        if Self::game_ids_unique(&played_games, &unplayed_games, &[]) {
+            Ok(Self::from(UnsortedGroup {
                 played_games,
                 unplayed_games,
-            })
+                void_games: Vec::new(),
+            }))
+        } else {
+            Err(GroupError::GameIdsNotUnique)
+        }
+    }
+
+    /// Add abandoned and annulled games to the group
+    ///
+    /// # Errors
+    ///
+    /// Every game must still have a unique game id, see [`Group::try_new`].
+    pub fn with_void_games(mut self, void_games: Vec<VoidGroupGame>) -> Result<Self, GroupError> {
+        self.void_games.extend(void_games);
+        self.void_games.sort_by_key(|game| (game.date(), game.id));
+        if Self::game_ids_unique(&self.played_games, &self.unplayed_games, &self.void_games) {
+            Ok(self)
         } else {
             Err(GroupError::GameIdsNotUnique)
         }
@@ -108,13 +154,39 @@ impl Group {
     ///
     /// Finds all team id's in the group games
     /// (played and upcoming).
-    /// Returns an iterator over unique team id's
-    pub fn team_ids(&self) -> impl Iterator<Item = TeamId> + '_ {
-        Group::unique_teams_in_games(&self.played_games)
-            .chain(Group::unique_teams_in_games(&self.unplayed_games))
+    /// Returns an iterator over unique team id's, in the order they first appear in
+    /// [`games`](Group::games).
+    pub fn teams(&self) -> impl Iterator<Item = TeamId> + '_ {
+        self.games()
+            .flat_map(|game| iter::once(game.home_team()).chain(iter::once(game.away_team())))
             .unique()
     }
 
+    /// Check if a team plays in the group
+    pub fn contains(&self, team: TeamId) -> bool {
+        self.played_games
+            .iter()
+            .map(GroupGame::Played)
+            .chain(self.unplayed_games.iter().map(GroupGame::Unplayed))
+            .chain(self.void_games.iter().map(GroupGame::Void))
+            .any(|game| game.home_team() == team || game.away_team() == team)
+    }
+
+    /// All games, played, upcoming and void
+    ///
+    /// Ordered by kick-off, games with the same kick-off by game id.
+    pub fn games(&self) -> impl Iterator<Item = GroupGame<'_>> + '_ {
+        let kickoff_order = |a: &GroupGame, b: &GroupGame| (a.date(), a.id()) <= (b.date(), b.id());
+        self.played_games
+            .iter()
+            .map(GroupGame::Played)
+            .merge_by(
+                self.unplayed_games.iter().map(GroupGame::Unplayed),
+                kickoff_order,
+            )
+            .merge_by(self.void_games.iter().map(GroupGame::Void), kickoff_order)
+    }
+
     /// Games accessor
     pub fn unplayed_games(&self) -> impl Iterator<Item = &UnplayedGroupGame> {
         self.unplayed_games.iter()
@@ -125,6 +197,11 @@ impl Group {
         self.played_games.iter()
     }
 
+    /// Abandoned and annulled games
+    pub fn void_games(&self) -> impl Iterator<Item = &VoidGroupGame> {
+        self.void_games.iter()
+    }
+
     pub fn play_game(&mut self, game_id: GameId, score: Score) {
         let idx = self
             .unplayed_games()
@@ -132,9 +209,9 @@ impl Group {
             .unwrap_or_else(|| panic!("No game with id: {:?}", game_id));
         let game = self
             .unplayed_games
-            .swap_remove(idx)
+            .remove(idx)
             .play(score, FairPlayScore::default());
-        self.played_games.push(game);
+        insert_game(&mut self.played_games, game, |game| (game.date(), game.id));
     }
 
     pub fn unplay_game(&mut self, game_id: GameId) {
@@ -143,13 +220,15 @@ impl Group {
             .iter()
             .position(|game| game.id == game_id)
             .unwrap();
-        let game = self.played_games.swap_remove(idx).unplay();
-        self.unplayed_games.push(game);
+        let game = self.played_games.remove(idx).unplay();
+        insert_game(&mut self.unplayed_games, game, |game| {
+            (game.date(), game.id)
+        });
     }
 
     /// Group size by teams
     pub fn num_teams(&self) -> usize {
-        self.team_ids().count()
+        self.teams().count()
     }
 
     /// Calculate group winner
@@ -161,15 +240,17 @@ impl Group {
 
     /// Calculate group winner
     ///
-    /// Order group according to `rules` and return first in order.
-    pub fn winner<T: Tiebreaker>(&self, rules: &Rules<T>) -> TeamId {
+    /// Order group according to `rules` and return first in order,
+    /// `None` for a group without games.
+    pub fn winner<T: Tiebreaker>(&self, rules: &Rules<T>) -> Option<TeamId> {
         order_group(self, rules).winner()
     }
 
     /// Calculate group runner up
     ///
-    /// Order group according to `rules` and return second in order.
-    pub fn runner_up<T: Tiebreaker>(&self, rules: &Rules<T>) -> TeamId {
+    /// Order group according to `rules` and return second in order,
+    /// `None` for a group without games.
+    pub fn runner_up<T: Tiebreaker>(&self, rules: &Rules<T>) -> Option<TeamId> {
         order_group(self, rules).runner_up()
     }
 
@@ -188,6 +269,7 @@ impl Group {
         GoalCount::team_stats(self)
     }
 
+    #[cfg(feature = "random")]
     pub fn random<NG>(
         num_games: NG,
         num_teams: u32,
@@ -240,22 +322,51 @@ impl Group {
     fn game_ids_unique(
         played_games: &[PlayedGroupGame],
         unplayed_games: &[UnplayedGroupGame],
+        void_games: &[VoidGroupGame],
     ) -> bool {
         let played_ids = played_games.iter().map(|x| x.id);
         let unplayed_ids = unplayed_games.iter().map(|x| x.id);
-        let unique_game_ids = played_ids.chain(unplayed_ids).unique();
-        unique_game_ids.count() == played_games.len() + unplayed_games.len()
+        let void_ids = void_games.iter().map(|x| x.id);
+        let unique_game_ids = played_ids.chain(unplayed_ids).chain(void_ids).unique();
+        unique_game_ids.count() == played_games.len() + unplayed_games.len() + void_games.len()
     }
+}
 
-    /// Iterator over unique group teams from list of games
-    fn unique_teams_in_games<T: Game>(games: &[T]) -> impl Iterator<Item = TeamId> + '_ {
-        games
-            .iter()
-            .flat_map(|game| iter::once(game.home_team()).chain(iter::once(game.away_team())))
-            .unique()
+/// A group as deserialized, before the games are sorted
+#[derive(Deserialize)]
+struct UnsortedGroup {
+    #[serde(default)]
+    played_games: Vec<PlayedGroupGame>,
+    #[serde(default)]
+    unplayed_games: Vec<UnplayedGroupGame>,
+    #[serde(default)]
+    void_games: Vec<VoidGroupGame>,
+}
+
+impl From<UnsortedGroup> for Group {
+    fn from(group: UnsortedGroup) -> Self {
+        let UnsortedGroup {
+            mut played_games,
+            mut unplayed_games,
+            mut void_games,
+        } = group;
+        played_games.sort_by_key(|game| (game.date(), game.id));
+        unplayed_games.sort_by_key(|game| (game.date(), game.id));
+        void_games.sort_by_key(|game| (game.date(), game.id));
+        Group {
+            played_games,
+            unplayed_games,
+            void_games,
+        }
     }
 }
 
+/// Insert a game into games sorted by kick-off and game id, keeping them sorted
+fn insert_game<G>(games: &mut Vec<G>, game: G, key: impl Fn(&G) -> (Date, GameId)) {
+    let idx = games.partition_point(|other| key(other) <= key(&game));
+    games.insert(idx, game);
+}
+
 /// Group point
 ///
 /// Represents the primary score of a team in a group, either accumulated over multiple games or
@@ -264,11 +375,81 @@ impl Group {
 /// - Win: 3 group points
 /// - Draw: 1 group point
 /// - Loss: 0 group points
+///
+/// Group points never go negative and never overflow: all arithmetic saturates at the bounds.
+/// E.g. a point deduction larger than the accumulated points results in zero points.
 #[derive(
-    Default, Debug, Display, Clone, Copy, From, Eq, PartialEq, Ord, PartialOrd, Add, AddAssign,
+    Default,
+    Debug,
+    Display,
+    Clone,
+    Copy,
+    From,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
 )]
 pub struct GroupPoint(pub u8);
 
+impl GroupPoint {
+    /// Addition, `None` on overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(GroupPoint)
+    }
+
+    /// Subtraction, `None` if the result would be negative
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(GroupPoint)
+    }
+
+    /// Addition, clamped at the upper bound
+    pub fn saturating_add(self, other: Self) -> Self {
+        GroupPoint(self.0.saturating_add(other.0))
+    }
+
+    /// Subtraction, clamped at zero
+    pub fn saturating_sub(self, other: Self) -> Self {
+        GroupPoint(self.0.saturating_sub(other.0))
+    }
+}
+
+/// Saturating addition, see [`GroupPoint::saturating_add`]
+impl ops::Add for GroupPoint {
+    type Output = Self;
+    fn add(self, other: Self) -> Self::Output {
+        self.saturating_add(other)
+    }
+}
+
+impl ops::AddAssign for GroupPoint {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// Saturating subtraction, see [`GroupPoint::saturating_sub`]
+impl ops::Sub for GroupPoint {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self::Output {
+        self.saturating_sub(other)
+    }
+}
+
+impl ops::SubAssign for GroupPoint {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl iter::Sum for GroupPoint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(GroupPoint(0), ops::Add::add)
+    }
+}
+
 impl num::Zero for GroupPoint {
     fn zero() -> GroupPoint {
         GroupPoint(0)
@@ -278,7 +459,7 @@ impl num::Zero for GroupPoint {
     }
 }
 
-#[derive(Error, Debug, Copy, Clone)]
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum GroupError {
     #[error("Teams in game not unique")]
     GameTeamsNotUnique,
@@ -288,10 +469,51 @@ pub enum GroupError {
     NonStrictOrder,
     #[error("Group Id '{0}' not an ascii letter (A-Z, a-z)")]
     InvalidGroupId(char),
+    #[error("Unknown group ordering rules")]
+    UnknownRules,
+    #[error("Point system does not match the group ordering rules")]
+    PointsMismatch,
+    #[error("Game status '{0}' is not abandoned or annulled")]
+    NotVoid(GameStatus),
+    #[error("No kickoff slot for the final round of group {0}")]
+    NoKickoffSlot(GroupId),
+    #[error("Team {0} does not play in the group")]
+    TeamNotInGroup(TeamId),
+    #[error("Team {0} can not be compared with itself")]
+    SameTeam(TeamId),
     #[error("Generic")]
     GenericError,
 }
 
+#[cfg(feature = "tournament")]
+impl ErrorCode for GroupError {
+    fn code(&self) -> &'static str {
+        match self {
+            GroupError::GameTeamsNotUnique => "GROUP_TEAMS_NOT_UNIQUE",
+            GroupError::GameIdsNotUnique => "GROUP_GAME_IDS_NOT_UNIQUE",
+            GroupError::NonStrictOrder => "GROUP_NON_STRICT_ORDER",
+            GroupError::InvalidGroupId(_) => "INVALID_GROUP_ID",
+            GroupError::UnknownRules => "UNKNOWN_GROUP_RULES",
+            GroupError::PointsMismatch => "GROUP_POINTS_MISMATCH",
+            GroupError::NotVoid(_) => "GAME_NOT_VOID",
+            GroupError::NoKickoffSlot(_) => "NO_KICKOFF_SLOT",
+            GroupError::TeamNotInGroup(_) => "TEAM_NOT_IN_GROUP",
+            GroupError::SameTeam(_) => "GROUP_SAME_TEAM",
+            GroupError::GenericError => "GROUP_ERROR",
+        }
+    }
+
+    fn details(&self) -> Option<Value> {
+        match self {
+            GroupError::NoKickoffSlot(group) => Some(json!({ "group": group })),
+            GroupError::TeamNotInGroup(team) | GroupError::SameTeam(team) => {
+                Some(json!({ "team": team }))
+            }
+            _ => None,
+        }
+    }
+}
+
 use crate::team::{Team, TeamRank, Teams};
 use crate::Date;
 pub fn mock_data() -> (Groups, Teams) {
@@ -320,6 +542,36 @@ pub fn mock_data() -> (Groups, Teams) {
     (groups, teams)
 }
 
+/// Group of teams 0 to 3 with the four unplayed games of the first two rounds, ids 0 to 3
+#[cfg(test)]
+pub(crate) fn mock_unplayed_group() -> Group {
+    let games = vec![(0, 0, 1), (1, 2, 3), (2, 0, 2), (3, 1, 3)]
+        .into_iter()
+        .map(|(id, home, away)| UnplayedGroupGame::try_new(id, home, away, Date::mock()).unwrap())
+        .collect();
+    Group::try_new(games, vec![]).unwrap()
+}
+
+/// Group of played games, given as `(id, home, away, score)`, without cards
+#[cfg(test)]
+pub(crate) fn mock_played_group(games: Vec<(u32, u32, u32, (u32, u32))>) -> Group {
+    let games = games
+        .into_iter()
+        .map(|(id, home, away, score)| {
+            PlayedGroupGame::try_new(
+                id,
+                home,
+                away,
+                score,
+                FairPlayScore::default(),
+                Date::mock(),
+            )
+            .unwrap()
+        })
+        .collect();
+    Group::try_new(vec![], games).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -328,6 +580,7 @@ mod tests {
     use crate::group::game::UnplayedGroupGame;
     use crate::team::{TeamId, TeamName};
     use crate::Date;
+    use serde_json::json;
     use std::collections::HashSet;
     #[test]
     fn mock_data_access() {
@@ -337,6 +590,24 @@ mod tests {
             TeamName(String::from("Sweden"))
         );
     }
+    #[test]
+    fn small_groups() {
+        let rules = order::fifa_2018();
+        let empty = Group::try_new(vec![], vec![]).unwrap();
+        assert_eq!(empty.winner(&rules), None);
+        assert_eq!(empty.runner_up(&rules), None);
+        let game =
+            PlayedGroupGame::try_new(0, 1, 2, (0, 1), FairPlayScore::default(), Date::mock())
+                .unwrap();
+        let pair = Group::try_new(vec![], vec![game]).unwrap();
+        let order = pair.rank_teams(&rules);
+        assert_eq!(order.len(), 2);
+        assert_eq!(order.winner(), Some(TeamId(2)));
+        assert_eq!(order.rank_of(TeamId(1)), Some(order::GroupRank::RUNNER_UP));
+        assert_eq!(order.get(order::GroupRank::RUNNER_UP.next()), None);
+        assert_eq!(order::GroupRank::WINNER.previous(), None);
+    }
+
     #[test]
     fn group_unique_game_ids_fail() {
         let game_1 = UnplayedGroupGame::try_new(1, 0, 1, Date::mock()).unwrap();
@@ -346,7 +617,7 @@ mod tests {
             PlayedGroupGame::try_new(2, 2, 1, (1, 2), FairPlayScore::default(), Date::mock())
                 .unwrap();
         let played = vec![game_3];
-        assert_eq!(Group::game_ids_unique(&played, &upcoming), false);
+        assert_eq!(Group::game_ids_unique(&played, &upcoming, &[]), false);
     }
     #[test]
     fn group_unique_game_ids_ok() {
@@ -357,19 +628,81 @@ mod tests {
             PlayedGroupGame::try_new(3, 2, 1, (1, 2), FairPlayScore::default(), Date::mock())
                 .unwrap();
         let played = vec![game_3];
-        assert_eq!(Group::game_ids_unique(&played, &upcoming), true);
+        assert_eq!(Group::game_ids_unique(&played, &upcoming, &[]), true);
     }
     #[test]
-    fn test_team_from_game_vec() {
-        let game_1 = UnplayedGroupGame::try_new(1, 0, 1, Date::mock()).unwrap();
-        let game_2 = UnplayedGroupGame::try_new(2, 0, 3, Date::mock()).unwrap();
-        let parsed_teams: HashSet<TeamId> =
-            Group::unique_teams_in_games(&[game_1, game_2]).collect();
-        let mut true_teams = HashSet::new();
-        true_teams.insert(TeamId(0));
-        true_teams.insert(TeamId(1));
-        true_teams.insert(TeamId(3));
-        assert_eq!(true_teams, parsed_teams)
+    fn games_in_kickoff_order() {
+        let date = |day: u32| format!("2021-06-{:02} 18:00", day).parse().unwrap();
+        let game_1 = UnplayedGroupGame::try_new(1, 3, 0, date(13)).unwrap();
+        let game_2 = UnplayedGroupGame::try_new(2, 0, 1, date(11))
+            .unwrap()
+            .play(Score::from((1, 0)), FairPlayScore::default());
+        let game_3 = UnplayedGroupGame::try_new(3, 1, 2, date(12)).unwrap();
+        let group = Group::try_new(vec![game_1, game_3], vec![game_2]).unwrap();
+        let ids: Vec<GameId> = group.games().map(|game| game.id()).collect();
+        assert_eq!(ids, vec![GameId::from(2), GameId::from(3), GameId::from(1)]);
+        assert!(group.games().next().unwrap().is_played());
+        let teams: Vec<TeamId> = group.teams().collect();
+        assert_eq!(teams, vec![TeamId(0), TeamId(1), TeamId(2), TeamId(3)]);
+        assert!(group.contains(TeamId(3)));
+        assert!(!group.contains(TeamId(4)));
+
+        let mut group = group;
+        group.play_game(GameId::from(1), Score::from((0, 0)));
+        group.unplay_game(GameId::from(2));
+        let ids: Vec<GameId> = group.games().map(|game| game.id()).collect();
+        assert_eq!(ids, vec![GameId::from(2), GameId::from(3), GameId::from(1)]);
+        let json = serde_json::to_value(&group).unwrap();
+        let unsorted = json!({
+            "played_games": json["played_games"],
+            "unplayed_games": [json["unplayed_games"][1], json["unplayed_games"][0]],
+        });
+        let group: Group = serde_json::from_value(unsorted).unwrap();
+        let ids: Vec<GameId> = group.games().map(|game| game.id()).collect();
+        assert_eq!(ids, vec![GameId::from(2), GameId::from(3), GameId::from(1)]);
+    }
+    #[test]
+    fn annulled_game_excluded() {
+        let game_1 = UnplayedGroupGame::try_new(1, 0, 1, Date::mock())
+            .unwrap()
+            .play(Score::from((1, 0)), FairPlayScore::default());
+        let game_2 = UnplayedGroupGame::try_new(2, 1, 2, Date::mock())
+            .unwrap()
+            .void(GameStatus::Annulled)
+            .unwrap();
+        let group = Group::try_new(vec![], vec![game_1])
+            .unwrap()
+            .with_void_games(vec![game_2])
+            .unwrap();
+        assert_eq!(group.num_teams(), 3);
+        assert_eq!(group.points()[&TeamId(2)], GroupPoint(0));
+        assert_eq!(group.goals_scored()[&TeamId(1)], GoalCount::from(0));
+        let status: Vec<GameStatus> = group.games().map(|game| game.status()).collect();
+        assert_eq!(status, vec![GameStatus::Played, GameStatus::Annulled]);
+        let game_3 = UnplayedGroupGame::try_new(2, 0, 2, Date::mock()).unwrap();
+        assert!(Group::try_new(vec![game_3], vec![])
+            .unwrap()
+            .with_void_games(vec![game_2])
+            .is_err());
+        assert!(game_3.void(GameStatus::Played).is_err());
+    }
+    #[test]
+    fn group_point_saturates() {
+        assert_eq!(GroupPoint(u8::MAX) + GroupPoint(3), GroupPoint(u8::MAX));
+        assert_eq!(GroupPoint(2) - GroupPoint(3), GroupPoint(0));
+        let mut points = GroupPoint(1);
+        points -= GroupPoint(5);
+        assert_eq!(points, GroupPoint(0));
+        assert_eq!(GroupPoint(u8::MAX).checked_add(GroupPoint(1)), None);
+        assert_eq!(GroupPoint(0).checked_sub(GroupPoint(1)), None);
+        assert_eq!(GroupPoint(4).checked_sub(GroupPoint(1)), Some(GroupPoint(3)));
+    }
+    #[test]
+    fn group_point_sum() {
+        let points = vec![GroupPoint(3), GroupPoint(1), GroupPoint(0)];
+        assert_eq!(points.into_iter().sum::<GroupPoint>(), GroupPoint(4));
+        let points = vec![GroupPoint(200), GroupPoint(100)];
+        assert_eq!(points.into_iter().sum::<GroupPoint>(), GroupPoint(u8::MAX));
     }
     #[test]
     fn test_group_teams() {
@@ -379,7 +712,7 @@ mod tests {
             .play(Score::from((2, 0)), FairPlayScore::default());
         let parsed_teams: HashSet<TeamId> = Group::try_new(vec![game_1], vec![game_2])
             .unwrap()
-            .team_ids()
+            .teams()
             .collect();
         let mut true_teams = HashSet::new();
         true_teams.insert(TeamId(0));
@@ -387,4 +720,16 @@ mod tests {
         true_teams.insert(TeamId(2));
         assert_eq!(true_teams, parsed_teams)
     }
+    #[test]
+    fn group_from_plain_json() {
+        let group: Group = serde_json::from_str(
+            r#"{"played_games": [
+                {"id": 1, "home": 1, "away": 2, "score": {"home": 2, "away": 1}, "date": "2021-06-11 19:00"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(group.played_games().count(), 1);
+        assert_eq!(group.unplayed_games().count(), 0);
+        assert_eq!(group.points()[&TeamId(1)], GroupPoint(3));
+    }
 }
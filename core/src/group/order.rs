@@ -10,6 +10,9 @@
 //! implements [`UnaryStat`] + [`Ord`] + [`Copy`], making the
 //! composition of new rules straightforward, see ([`euro_2020`], [`fifa_2018`]).
 //!
+//! Criteria which only make sense for a pair of teams, e.g. the direct result, implement
+//! [`BinaryStat`] instead and are turned into a sub-order by [`PairwiseStat`].
+//!
 //! Smaller sub-orders can also be grouped together. E.g., you could group points, goal diff.
 //! and goals scored into one struct, implement `UnaryStat` and `Ord` for it and use that as a
 //! sub-order. This might be more efficient since you would avoid iterating over the played games
@@ -18,14 +21,22 @@
 //! approach.
 use crate::fair_play::{FifaFairPlayValue, UefaFairPlayValue};
 use crate::game::{GoalCount, GoalDiff};
-use crate::group::stats::{NumWins, UnaryStat};
+use crate::group::stats::{
+    BinaryStat, InternalGames, NumWins, Points, ThreePoints, TwoPoints, UnaryStat,
+};
+use crate::group::view::GroupView;
 use crate::group::{Group, GroupError, GroupPoint};
 use crate::team::{TeamId, TeamRank};
+use itertools::Itertools;
+#[cfg(feature = "random")]
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
-use std::iter::FromIterator;
+use std::fmt;
+use std::sync::OnceLock;
 
 /// Group ordering rules
 ///
@@ -41,25 +52,195 @@ use std::iter::FromIterator;
 /// - Euro 2020 rules use a similar (but not the same) list of non-strict rules
 /// but instead lets the team rank define the tiebreaker.
 pub struct Rules<T: Tiebreaker> {
-    non_strict: Vec<Box<dyn SubOrdering>>,
+    non_strict: Vec<Box<dyn SubOrdering + Send + Sync>>,
     tiebreaker: T,
 }
 
+impl<T: Tiebreaker> Rules<T> {
+    pub(crate) fn new(
+        non_strict: Vec<Box<dyn SubOrdering + Send + Sync>>,
+        tiebreaker: T,
+    ) -> Self {
+        Rules {
+            non_strict,
+            tiebreaker,
+        }
+    }
+
+    /// Complete a non-strict order with the tiebreaker
+    ///
+    /// # Panics
+    ///
+    /// Does not panic since the unwrapping match arm is checked to be strict.
+    pub(crate) fn break_ties(&self, group: &GroupView, order: NonStrictGroupOrder) -> GroupOrder {
+        if !order.is_strict() {
+            self.tiebreaker.order(group, order)
+        } else {
+            order.try_into().unwrap()
+        }
+    }
+
+    /// Explain the relative order of two teams in a group
+    ///
+    /// Follows the same greedy procedure as [`order_group`], tracking the sub-group containing
+    /// the two teams, and reports the first sub-order which separates them.
+    /// If no sub-order separates the teams, the order is decided by the tiebreaker.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`GroupError::SameTeam`] if the teams are not distinct and with
+    /// [`GroupError::TeamNotInGroup`] if a team does not play in the group.
+    pub fn explain_pair(
+        &self,
+        group: &Group,
+        id_1: TeamId,
+        id_2: TeamId,
+    ) -> Result<RuleDecision, GroupError> {
+        if id_1 == id_2 {
+            return Err(GroupError::SameTeam(id_1));
+        }
+        if let Some(team) = [id_1, id_2].iter().find(|team| !group.contains(**team)) {
+            return Err(GroupError::TeamNotInGroup(*team));
+        }
+        let view = GroupView::new(group);
+        let mut sub_group: Vec<TeamId> = group.teams().collect();
+        for (index, rule) in self.non_strict.iter().enumerate() {
+            let stats = rule.stats(&view, &sub_group);
+            let order = rule.order(&view, sub_group);
+            // Both teams are in the order, the first sub-group containing either one is the
+            // sub-group of the better team (or of both teams).
+            let next = order
+                .into_iter()
+                .find(|x| x.contains(&id_1) || x.contains(&id_2))
+                .expect("Team missing in sub-order");
+            if next.contains(&id_1) && next.contains(&id_2) {
+                sub_group = next;
+            } else {
+                let ahead = if next.contains(&id_1) { id_1 } else { id_2 };
+                let stat = |id| stats.get(&id).cloned().unwrap_or_default();
+                return Ok(RuleDecision::SubOrder {
+                    index,
+                    name: String::from(rule.name()),
+                    ahead,
+                    stats: (stat(id_1), stat(id_2)),
+                });
+            }
+        }
+        Ok(RuleDecision::Tiebreaker)
+    }
+}
+
+/// Explanation of the relative order of two teams, see [`Rules::explain_pair`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleDecision {
+    /// The teams are separated by a sub-order
+    SubOrder {
+        /// Index of the sub-order in the rules
+        index: usize,
+        /// Name of the sub-order, e.g. "internal goal difference"
+        name: String,
+        /// The team ranked higher
+        ahead: TeamId,
+        /// Stat values for the two teams, in the order they were given.
+        stats: (String, String),
+    },
+    /// No sub-order separates the teams, the tiebreaker decides.
+    Tiebreaker,
+}
+
 /// Order group based on rules
 ///
 /// First orders by a list of non-strict sub-orders.
 /// If the sub-order is not strict, the rules' tiebreaker is used.
 ///
-/// # Panics
-///
-/// Does not panic since the unwrapping match arm is checked to be strict.
-pub fn order_group<T: Tiebreaker>(group: &Group, rules: &Rules<T>) -> GroupOrder {
+/// Takes a `&Group` or a [`GroupView`] with hypothetical results.
+pub fn order_group<'a, T: Tiebreaker>(
+    group: impl Into<GroupView<'a>>,
+    rules: &Rules<T>,
+) -> GroupOrder {
+    let group = group.into();
     let possibly_non_strict =
-        non_strict_ordering(group, &rules.non_strict, NonStrictGroupOrder::init(group));
-    if !possibly_non_strict.is_strict() {
-        rules.tiebreaker.order(group, possibly_non_strict)
-    } else {
-        possibly_non_strict.try_into().unwrap()
+        non_strict_ordering(&group, &rules.non_strict, NonStrictGroupOrder::init(&group));
+    rules.break_ties(&group, possibly_non_strict)
+}
+
+/// Order a subset of the teams in a group
+///
+/// Like [`order_group`], but only `teams` are ordered.
+/// Every team must have a stat in the group, i.e. be part of at least one game.
+pub(crate) fn order_teams<T: Tiebreaker>(
+    group: &Group,
+    teams: Vec<TeamId>,
+    rules: &Rules<T>,
+) -> GroupOrder {
+    if teams.is_empty() {
+        return GroupOrder(teams);
+    }
+    let group = GroupView::new(group);
+    let possibly_non_strict = non_strict_ordering(
+        &group,
+        &rules.non_strict,
+        NonStrictGroupOrder::single(&teams),
+    );
+    rules.break_ties(&group, possibly_non_strict)
+}
+
+/// Order group based on the non-strict sub-orders only
+///
+/// Like [`order_group`] but without applying the tiebreaker,
+/// i.e. the remaining ties are kept in the returned order.
+pub fn non_strict_order_group<'a, T: Tiebreaker>(
+    group: impl Into<GroupView<'a>>,
+    rules: &Rules<T>,
+) -> NonStrictGroupOrder {
+    let group = group.into();
+    non_strict_ordering(&group, &rules.non_strict, NonStrictGroupOrder::init(&group))
+}
+
+/// Check if ordering a group is deterministic
+///
+/// Runs [`order_group`] `runs` times on the same group and collects the distinct orders.
+/// The ties left by the sub-orders are the ones passed on to the tiebreaker,
+/// if the orders differ one of them was decided by a random tiebreaker,
+/// i.e. a drawing of lots is pending.
+pub fn audit_order<T: Tiebreaker>(group: &Group, rules: &Rules<T>, runs: usize) -> OrderAudit {
+    let ties = non_strict_order_group(group, rules)
+        .tied_groups()
+        .map(|sub_group| sub_group.iter().copied().sorted_by_key(|id| id.0).collect())
+        .collect();
+    let mut orders: Vec<(Vec<TeamId>, usize)> = Vec::new();
+    for _ in 0..runs {
+        let order: Vec<TeamId> = order_group(group, rules).into_iter().collect();
+        match orders.iter_mut().find(|(seen, _)| *seen == order) {
+            Some((_, count)) => *count += 1,
+            None => orders.push((order, 1)),
+        }
+    }
+    orders.sort_by(|(_, count_1), (_, count_2)| count_2.cmp(count_1));
+    OrderAudit { runs, orders, ties }
+}
+
+/// Outcome of [`audit_order`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderAudit {
+    /// Number of times the group was ordered
+    pub runs: usize,
+    /// Distinct orders with their number of occurrences, most common first.
+    pub orders: Vec<(Vec<TeamId>, usize)>,
+    /// Ties not separated by any sub-order, i.e. decided by the tiebreaker.
+    /// Teams within a tie are sorted by id.
+    pub ties: Vec<Vec<TeamId>>,
+}
+
+impl OrderAudit {
+    /// Every run gave the same order
+    pub fn is_deterministic(&self) -> bool {
+        self.orders.len() <= 1
+    }
+
+    /// The tiebreaker decided at least one tie
+    pub fn uses_tiebreaker(&self) -> bool {
+        !self.ties.is_empty()
     }
 }
 
@@ -68,53 +249,131 @@ pub fn order_group<T: Tiebreaker>(group: &Group, rules: &Rules<T>) -> GroupOrder
 /// Returns the input group order if it is strict or if there are no more rules left to apply.
 /// Otherwise recursively calls itself with the next rule.
 fn non_strict_ordering(
-    group: &Group,
-    rules: &[Box<dyn SubOrdering>],
+    group: &GroupView,
+    rules: &[Box<dyn SubOrdering + Send + Sync>],
+    sub_order: NonStrictGroupOrder,
+) -> NonStrictGroupOrder {
+    non_strict_ordering_cached(group, rules, sub_order, &mut InternalGamesCache::default())
+}
+
+fn non_strict_ordering_cached<'a>(
+    group: &GroupView<'a>,
+    rules: &[Box<dyn SubOrdering + Send + Sync>],
     sub_order: NonStrictGroupOrder,
+    cache: &mut InternalGamesCache<'a>,
 ) -> NonStrictGroupOrder {
     if sub_order.is_strict() || rules.is_empty() {
         sub_order
     } else {
         let (current_rule, remaining_rules) = rules.split_at(1);
-        let sub_order = sub_order
-            .into_iter()
-            .fold(NonStrictGroupOrder::empty(), |acc, x| {
-                // Don't apply rule if the sub-order is already strict,
-                // i.e. if x consists of a single TeamId
-                // TODO: benchmark, possible that the allocation in the else branch is more costly.
-                let new_order = if x.len() > 1 {
-                    current_rule[0].order(group, x)
-                } else {
-                    NonStrictGroupOrder::single(x)
-                };
+        let mut new_order = NonStrictGroupOrder::empty();
+        for sub_group in sub_order.sub_groups() {
+            // Don't apply rule if the sub-order is already strict,
+            // i.e. if the sub-group consists of a single TeamId
+            if sub_group.len() > 1 {
+                new_order.extend(&current_rule[0].order_cached(group, sub_group.to_vec(), cache));
+            } else {
+                new_order.add_sub_order(sub_group[0]);
+            }
+        }
+        non_strict_ordering_cached(group, remaining_rules, new_order, cache)
+    }
+}
+
+/// Internal games of the sub-groups met while ordering a group
+///
+/// A sub-group which a sub-order does not split is passed on unchanged to the next sub-order,
+/// e.g. from internal points to internal goal difference, which then reuses the same filtered
+/// [`InternalGames`].
+#[derive(Debug, Default)]
+pub struct InternalGamesCache<'a>(HashMap<Vec<u32>, InternalGames<'a>>);
 
-                acc.extend(new_order)
-            });
-        non_strict_ordering(group, remaining_rules, sub_order)
+impl<'a> InternalGamesCache<'a> {
+    /// Internal games of `teams`, filtered on the first call for the same set of teams
+    pub fn get(&mut self, group: &GroupView<'a>, teams: &[TeamId]) -> &InternalGames<'a> {
+        let mut key: Vec<u32> = teams.iter().map(|team| team.0).collect();
+        key.sort_unstable();
+        self.0
+            .entry(key)
+            .or_insert_with(|| InternalGames::new(group, teams))
     }
 }
 
 /// Indexes [`GroupOrder`]
-#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq)]
+///
+/// Zero based, the winner has rank 0.
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
 pub struct GroupRank(pub usize);
 
+impl GroupRank {
+    pub const WINNER: GroupRank = GroupRank(0);
+    pub const RUNNER_UP: GroupRank = GroupRank(1);
+
+    /// The rank below
+    pub fn next(self) -> Self {
+        GroupRank(self.0 + 1)
+    }
+
+    /// The rank above, `None` for the winner
+    pub fn previous(self) -> Option<Self> {
+        self.0.checked_sub(1).map(GroupRank)
+    }
+
+    /// Position in the table, counted from 1
+    pub fn position(self) -> usize {
+        self.0 + 1
+    }
+}
+
 /// List of TeamId's
 ///
 /// Sorted from best to worst team.
+/// Indexing with a [`GroupRank`] panics for ranks outside the group, use [`GroupOrder::get`] for
+/// groups of unknown size, e.g. in sandbox data.
 #[derive(Debug, PartialEq)]
 pub struct GroupOrder(Vec<TeamId>);
 
 impl GroupOrder {
-    pub fn winner(&self) -> TeamId {
-        self[GroupRank(0)]
+    /// First team, `None` for a group without teams
+    pub fn winner(&self) -> Option<TeamId> {
+        self.get(GroupRank::WINNER)
+    }
+
+    /// Second team, `None` for a group with fewer than two teams
+    pub fn runner_up(&self) -> Option<TeamId> {
+        self.get(GroupRank::RUNNER_UP)
+    }
+
+    /// Team at `rank`, `None` if the group has fewer teams
+    pub fn get(&self, rank: GroupRank) -> Option<TeamId> {
+        self.0.get(rank.0).copied()
+    }
+
+    /// Rank of `team`, `None` if the team is not in the group
+    pub fn rank_of(&self, team: TeamId) -> Option<GroupRank> {
+        self.0.iter().position(|id| *id == team).map(GroupRank)
+    }
+
+    /// Number of teams
+    pub fn len(&self) -> usize {
+        self.0.len()
     }
-    pub fn runner_up(&self) -> TeamId {
-        self[GroupRank(1)]
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &TeamId> {
         self.0.iter()
     }
+
+    /// Teams with their ranks, best first
+    pub fn ranked(&self) -> impl Iterator<Item = (GroupRank, TeamId)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(rank, team)| (GroupRank(rank), *team))
+    }
 }
 
 impl IntoIterator for GroupOrder {
@@ -138,7 +397,7 @@ impl TryFrom<NonStrictGroupOrder> for GroupOrder {
 
     fn try_from(value: NonStrictGroupOrder) -> Result<Self, Self::Error> {
         if value.is_strict() {
-            Ok(GroupOrder(value.0.into_iter().map(|x| x[0]).collect()))
+            Ok(GroupOrder(value.teams.into_vec()))
         } else {
             Err(GroupError::NonStrictOrder)
         }
@@ -147,54 +406,168 @@ impl TryFrom<NonStrictGroupOrder> for GroupOrder {
 
 /// Intermediate group order representation
 ///
-/// A non-strict group order is represented as a sorted vector of vectors of equal teams.
-#[derive(Debug, PartialEq)]
-pub struct NonStrictGroupOrder(Vec<Vec<TeamId>>);
+/// A non-strict group order is a sorted list of sub-groups of equal teams.
+/// It is stored flat, as all teams from best to worst together with the end index of every
+/// sub-group, inline for groups of up to [`INLINE_TEAMS`] teams. Ordering a group in a simulation
+/// loop then does not allocate per sub-group.
+/// The sub-groups as vectors, see [`NonStrictGroupOrder::iter`], are only built when asked for.
+///
+/// Displayed from best to worst, with equal teams in brackets, e.g. `[1] > [2, 3] > [4]`.
+///
+/// ```
+/// # use wwc_core::group::order::{non_strict_order_group, fifa_2018};
+/// # use wwc_core::group::game::UnplayedGroupGame;
+/// # use wwc_core::group::Group;
+/// # use wwc_core::game::Score;
+/// # use wwc_core::team::TeamId;
+/// # use wwc_core::fair_play::FairPlayScore;
+/// # use wwc_core::Date;
+/// let game = UnplayedGroupGame::try_new(0, 1, 2, Date::mock())
+///     .unwrap()
+///     .play(Score::from((1, 1)), FairPlayScore::default());
+/// let group = Group::try_new(vec![], vec![game]).unwrap();
+/// let order = non_strict_order_group(&group, &fifa_2018());
+/// assert!(order.contains_tie_between(TeamId(1), TeamId(2)));
+/// assert_eq!(order.tied_groups().count(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NonStrictGroupOrder {
+    /// All teams, from best to worst
+    teams: SmallVec<[TeamId; INLINE_TEAMS]>,
+    /// End (exclusive) of every sub-group in `teams`
+    ends: SmallVec<[usize; INLINE_TEAMS]>,
+    /// The sub-groups as vectors, built by the first call to [`NonStrictGroupOrder::iter`]
+    sub_group_vecs: OnceLock<Vec<Vec<TeamId>>>,
+}
+
+impl PartialEq for NonStrictGroupOrder {
+    fn eq(&self, other: &Self) -> bool {
+        self.teams == other.teams && self.ends == other.ends
+    }
+}
+
+/// Largest group stored inline by [`NonStrictGroupOrder`]
+pub const INLINE_TEAMS: usize = 8;
 
 impl NonStrictGroupOrder {
-    fn single(x: Vec<TeamId>) -> Self {
-        NonStrictGroupOrder(vec![x])
+    /// Order with all teams in `x` equal, the empty order if there are no teams
+    fn single(x: &[TeamId]) -> Self {
+        NonStrictGroupOrder {
+            teams: SmallVec::from_slice(x),
+            ends: if x.is_empty() {
+                SmallVec::new()
+            } else {
+                smallvec![x.len()]
+            },
+            sub_group_vecs: OnceLock::new(),
+        }
     }
     fn empty() -> Self {
-        NonStrictGroupOrder(vec![])
+        NonStrictGroupOrder {
+            teams: SmallVec::new(),
+            ends: SmallVec::new(),
+            sub_group_vecs: OnceLock::new(),
+        }
     }
 
-    // TODO: Did not manage to impl w/ Iterator trait.
-    fn iter(&self) -> impl Iterator<Item = &Vec<TeamId>> {
-        self.0.iter()
+    /// Iterate over the sub-groups of equal teams, from best to worst
+    ///
+    /// The sub-groups are copied into vectors on the first call.
+    pub fn iter(&self) -> impl Iterator<Item = &Vec<TeamId>> {
+        self.sub_group_vecs
+            .get_or_init(|| self.sub_groups().map(<[TeamId]>::to_vec).collect())
+            .iter()
+    }
+
+    /// Iterate over the sub-groups of equal teams as slices, without allocating
+    fn sub_groups(&self) -> impl Iterator<Item = &[TeamId]> {
+        self.ends.iter().scan(0, move |start, &end| {
+            let sub_group = &self.teams[*start..end];
+            *start = end;
+            Some(sub_group)
+        })
+    }
+
+    /// Sub-groups with more than one team, i.e. the unresolved ties
+    pub fn tied_groups(&self) -> impl Iterator<Item = &[TeamId]> {
+        self.sub_groups().filter(|sub_group| sub_group.len() > 1)
+    }
+
+    /// Check if two (distinct) teams are tied, i.e. in the same sub-group
+    pub fn contains_tie_between(&self, id_1: TeamId, id_2: TeamId) -> bool {
+        id_1 != id_2
+            && self
+                .tied_groups()
+                .any(|sub_group| sub_group.contains(&id_1) && sub_group.contains(&id_2))
     }
 
     /// Initialise an equal order
     ///
-    /// A group with all teams equal are represented as a vector with a single element,
-    /// where this element is a vector containing all the teams in the group.
-    fn init(group: &Group) -> Self {
-        NonStrictGroupOrder(vec![group.team_ids().collect()])
+    /// A group with all teams equal is represented by a single sub-group,
+    /// containing all the teams in the group.
+    fn init(group: &GroupView) -> Self {
+        let teams: SmallVec<[TeamId; INLINE_TEAMS]> = group.teams().collect();
+        NonStrictGroupOrder::single(&teams)
     }
 
     /// Strict ordering check
     ///
-    /// Check if all subgroups (with equal elements) are of size 1.
-    /// Subgroup s with |s| > 1 => non-strict ordering
-    /// Subgroup s with |s| < 1 (= 0) => Bug, trivial subgroups are not removed correctly.
-    fn is_strict(&self) -> bool {
-        self.iter().all(|x| x.len() == 1)
+    /// Check if all subgroups (with equal elements) are of size 1,
+    /// i.e. if there are as many subgroups as teams.
+    /// Subgroups are never empty, an empty subgroup would be a bug.
+    pub fn is_strict(&self) -> bool {
+        self.ends.len() == self.teams.len()
+    }
+
+    /// Add a team to the last sub-group
+    fn extend_sub_order(&mut self, team: TeamId) {
+        self.teams.push(team);
+        match self.ends.last_mut() {
+            Some(end) => *end = self.teams.len(),
+            None => self.ends.push(self.teams.len()),
+        }
+    }
+
+    /// Add a team as a new sub-group
+    fn add_sub_order(&mut self, team: TeamId) {
+        self.teams.push(team);
+        self.ends.push(self.teams.len());
     }
 
-    fn extend_sub_order(mut self, team: TeamId) -> Self {
-        let mut new_inner = self.0.pop().unwrap_or_else(Vec::new);
-        new_inner.push(team);
-        self.0.push(new_inner);
-        self
+    /// Append the sub-groups of another order
+    fn extend(&mut self, sub_order: &NonStrictGroupOrder) {
+        let offset = self.teams.len();
+        self.teams.extend_from_slice(&sub_order.teams);
+        self.ends
+            .extend(sub_order.ends.iter().map(|end| end + offset));
     }
 
-    fn add_sub_order(mut self, team: TeamId) -> Self {
-        self.0.push(vec![team]);
-        self
+    /// Sub-groups of equal teams split by the values of `team_stats`, higher values first
+    fn from_sorted_stats<T: Eq>(team_stats: &[(TeamId, T)]) -> Self {
+        let mut order = NonStrictGroupOrder::empty();
+        let mut previous = None;
+        for (team, stat) in team_stats.iter().rev() {
+            if previous == Some(stat) {
+                order.extend_sub_order(*team);
+            } else {
+                order.add_sub_order(*team);
+            }
+            previous = Some(stat);
+        }
+        order
     }
+}
 
-    fn extend(self, sub_order: NonStrictGroupOrder) -> Self {
-        NonStrictGroupOrder([&self.0[..], &sub_order.0[..]].concat())
+impl fmt::Display for NonStrictGroupOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sub_groups: Vec<String> = self
+            .sub_groups()
+            .map(|sub_group| {
+                let teams: Vec<String> = sub_group.iter().map(TeamId::to_string).collect();
+                format!("[{}]", teams.join(", "))
+            })
+            .collect();
+        write!(f, "{}", sub_groups.join(" > "))
     }
 }
 
@@ -202,8 +575,11 @@ impl IntoIterator for NonStrictGroupOrder {
     type Item = Vec<TeamId>;
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.sub_group_vecs
+            .take()
+            .unwrap_or_else(|| self.sub_groups().map(<[TeamId]>::to_vec).collect())
+            .into_iter()
     }
 }
 
@@ -213,7 +589,26 @@ impl IntoIterator for NonStrictGroupOrder {
 /// which implements this trait. I.e. they can take a vector of teams and split them into a
 /// NonStrictGroupOrder.
 pub trait SubOrdering {
-    fn order(&self, group: &Group, order: Vec<TeamId>) -> NonStrictGroupOrder;
+    fn order(&self, group: &GroupView, order: Vec<TeamId>) -> NonStrictGroupOrder;
+
+    /// Like [`SubOrdering::order`], with the internal games of the sub-groups shared between
+    /// the sub-orders of a rule
+    ///
+    /// Only sub-orders based on the internal games need to override the default.
+    fn order_cached<'a>(
+        &self,
+        group: &GroupView<'a>,
+        order: Vec<TeamId>,
+        _cache: &mut InternalGamesCache<'a>,
+    ) -> NonStrictGroupOrder {
+        self.order(group, order)
+    }
+
+    /// Human readable name of the sub-order
+    fn name(&self) -> &str;
+
+    /// The stat values the sub-order compares, for the teams in `order`
+    fn stats(&self, group: &GroupView, order: &[TeamId]) -> HashMap<TeamId, String>;
 }
 
 /// Ordering stat based on all games in the group
@@ -224,16 +619,34 @@ pub trait SubOrdering {
 ///
 /// AllGroupStat sub-orderings based on points, goal difference and goals scored are commonly the
 /// first three sub-orderings in a group rule.
-struct AllGroupStat<T: UnaryStat>(std::marker::PhantomData<T>);
+pub(crate) struct AllGroupStat<T: UnaryStat> {
+    name: &'static str,
+    stat: std::marker::PhantomData<T>,
+}
 
 impl<T: UnaryStat> AllGroupStat<T> {
-    fn new() -> Self {
-        AllGroupStat(std::marker::PhantomData::<T>)
+    pub(crate) fn new(name: &'static str) -> Self {
+        AllGroupStat {
+            name,
+            stat: std::marker::PhantomData::<T>,
+        }
     }
 }
 
-impl<T: UnaryStat + Ord + Copy> SubOrdering for AllGroupStat<T> {
-    fn order(&self, group: &Group, order: Vec<TeamId>) -> NonStrictGroupOrder {
+impl<T: UnaryStat + Ord + Copy + fmt::Display> SubOrdering for AllGroupStat<T> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn stats(&self, group: &GroupView, order: &[TeamId]) -> HashMap<TeamId, String> {
+        T::team_stats(group)
+            .into_iter()
+            .filter(|(id, _)| order.contains(id))
+            .map(|(id, stat)| (id, stat.to_string()))
+            .collect()
+    }
+
+    fn order(&self, group: &GroupView, order: Vec<TeamId>) -> NonStrictGroupOrder {
         // TODO: Not efficient to calc stats for all teams, but efficient is not very important
         // here.
         let stats_all_teams = T::team_stats(group);
@@ -244,18 +657,7 @@ impl<T: UnaryStat + Ord + Copy> SubOrdering for AllGroupStat<T> {
             .map(|(id, x)| (id, *x.unwrap()))
             .collect();
         team_stats.sort_by_key(|x| x.1);
-        let team_stats = team_stats;
-        let (_, new_order) = team_stats.iter().rev().fold(
-            (team_stats[0].1, NonStrictGroupOrder::empty()),
-            |acc, x| {
-                if acc.0 == x.1 {
-                    (x.1, acc.1.extend_sub_order(x.0))
-                } else {
-                    (x.1, acc.1.add_sub_order(x.0))
-                }
-            },
-        );
-        new_order
+        NonStrictGroupOrder::from_sorted_stats(&team_stats)
     }
 }
 
@@ -264,17 +666,43 @@ impl<T: UnaryStat + Ord + Copy> SubOrdering for AllGroupStat<T> {
 /// SubOrdering which orders by a metric based on a UnaryStat.
 /// The metric is calculated from the games in the group, where both teams involved are members of
 /// the subset of teams being ordered.
-struct InternalGroupStat<T: UnaryStat>(std::marker::PhantomData<T>);
+struct InternalGroupStat<T: UnaryStat> {
+    name: &'static str,
+    stat: std::marker::PhantomData<T>,
+}
 
 impl<T: UnaryStat> InternalGroupStat<T> {
-    fn new() -> Self {
-        InternalGroupStat(std::marker::PhantomData::<T>)
+    fn new(name: &'static str) -> Self {
+        InternalGroupStat {
+            name,
+            stat: std::marker::PhantomData::<T>,
+        }
     }
 }
 
-impl<T: UnaryStat + Ord + Copy> SubOrdering for InternalGroupStat<T> {
-    fn order(&self, group: &Group, order: Vec<TeamId>) -> NonStrictGroupOrder {
-        let internal_stats = T::internal_team_stats(group, &HashSet::from_iter(&order));
+impl<T: UnaryStat + Ord + Copy + fmt::Display> SubOrdering for InternalGroupStat<T> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn stats(&self, group: &GroupView, order: &[TeamId]) -> HashMap<TeamId, String> {
+        T::internal_stats(&InternalGames::new(group, order))
+            .into_iter()
+            .map(|(id, stat)| (id, stat.to_string()))
+            .collect()
+    }
+
+    fn order(&self, group: &GroupView, order: Vec<TeamId>) -> NonStrictGroupOrder {
+        self.order_cached(group, order, &mut InternalGamesCache::default())
+    }
+
+    fn order_cached<'a>(
+        &self,
+        group: &GroupView<'a>,
+        order: Vec<TeamId>,
+        cache: &mut InternalGamesCache<'a>,
+    ) -> NonStrictGroupOrder {
+        let internal_stats = T::internal_stats(cache.get(group, &order));
         let mut team_stats: Vec<(TeamId, T)> = order
             .into_iter()
             .map(|id| (id, internal_stats.get(&id)))
@@ -282,35 +710,85 @@ impl<T: UnaryStat + Ord + Copy> SubOrdering for InternalGroupStat<T> {
             .map(|(id, x)| (id, *x.unwrap()))
             .collect();
         team_stats.sort_by_key(|x| x.1);
-        let team_stats = team_stats;
-        let (_, new_order) = team_stats.iter().rev().fold(
-            (team_stats[0].1, NonStrictGroupOrder::empty()),
-            |acc, x| {
-                if acc.0 == x.1 {
-                    (x.1, acc.1.extend_sub_order(x.0))
-                } else {
-                    (x.1, acc.1.add_sub_order(x.0))
-                }
-            },
-        );
-        new_order
+        NonStrictGroupOrder::from_sorted_stats(&team_stats)
+    }
+}
+
+/// Ordering stat based on pairwise comparisons within a teams subset
+///
+/// SubOrdering which orders by a [`BinaryStat`].
+/// Every team in the subset is compared to every other team, and the teams are ordered by the
+/// number of comparisons won minus the number lost. For a pair of teams this is simply the
+/// comparison of the two; a cycle, e.g. three teams each winning one direct game, stays a tie.
+pub struct PairwiseStat<T: BinaryStat> {
+    name: &'static str,
+    stat: std::marker::PhantomData<T>,
+}
+
+impl<T: BinaryStat> PairwiseStat<T> {
+    pub fn new(name: &'static str) -> Self {
+        PairwiseStat {
+            name,
+            stat: std::marker::PhantomData::<T>,
+        }
+    }
+
+    /// Comparisons won minus lost, for every team in `order`
+    fn scores(group: &GroupView, order: &[TeamId]) -> HashMap<TeamId, i32> {
+        order
+            .iter()
+            .map(|team| {
+                let score = order
+                    .iter()
+                    .filter(|other| *other != team)
+                    .map(|other| match T::compare(group, *team, *other) {
+                        Ordering::Greater => 1,
+                        Ordering::Equal => 0,
+                        Ordering::Less => -1,
+                    })
+                    .sum();
+                (*team, score)
+            })
+            .collect()
+    }
+}
+
+impl<T: BinaryStat> SubOrdering for PairwiseStat<T> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn stats(&self, group: &GroupView, order: &[TeamId]) -> HashMap<TeamId, String> {
+        Self::scores(group, order)
+            .into_iter()
+            .map(|(id, score)| (id, format!("{:+}", score)))
+            .collect()
+    }
+
+    fn order(&self, group: &GroupView, order: Vec<TeamId>) -> NonStrictGroupOrder {
+        let scores = Self::scores(group, &order);
+        let mut team_stats: Vec<(TeamId, i32)> =
+            order.into_iter().map(|id| (id, scores[&id])).collect();
+        team_stats.sort_by_key(|x| x.1);
+        NonStrictGroupOrder::from_sorted_stats(&team_stats)
     }
 }
 
 /// Associated with [`Rules`] to ensure strict total order.
 pub trait Tiebreaker {
-    fn order(&self, group: &Group, non_strict: NonStrictGroupOrder) -> GroupOrder {
-        GroupOrder(non_strict.0.into_iter().fold(Vec::new(), |mut acc, x| {
-            if x.len() == 1 {
-                acc.push(x[0]);
-                acc
+    fn order(&self, group: &GroupView, non_strict: NonStrictGroupOrder) -> GroupOrder {
+        let mut order = Vec::with_capacity(non_strict.teams.len());
+        for sub_group in non_strict.sub_groups() {
+            if sub_group.len() == 1 {
+                order.push(sub_group[0]);
             } else {
-                [acc, self.order_sub_group(group, &x).0].concat()
+                order.extend(self.order_sub_group(group, sub_group).0);
             }
-        }))
+        }
+        GroupOrder(order)
     }
 
-    fn order_sub_group(&self, _: &Group, order: &[TeamId]) -> GroupOrder {
+    fn order_sub_group(&self, _: &GroupView, order: &[TeamId]) -> GroupOrder {
         //TODO: There must be a more efficient way to do this?
         let mut tmp_order = order.to_vec();
         tmp_order.sort_by(|a, b| self.cmp(*a, *b));
@@ -340,10 +818,14 @@ impl Tiebreaker for Manual {
 }
 
 /// Random tiebreaker
+///
+/// Without the `random` feature the lots are drawn deterministically from the team id's,
+/// which is arbitrary but stable.
 #[derive(Debug, Clone, Copy)]
 pub struct Random;
 
 impl Tiebreaker for Random {
+    #[cfg(feature = "random")]
     fn cmp(&self, _id_1: TeamId, _id_2: TeamId) -> Ordering {
         let mut rng = rand::thread_rng();
         if rng.gen::<f32>() > 0.5 {
@@ -352,6 +834,14 @@ impl Tiebreaker for Random {
             Ordering::Greater
         }
     }
+
+    #[cfg(not(feature = "random"))]
+    fn cmp(&self, id_1: TeamId, id_2: TeamId) -> Ordering {
+        // Fibonacci hashing, scrambles the order of consecutive id's.
+        // Multiplying with an odd number is a bijection, distinct teams never draw the same lot.
+        let lot = |id: TeamId| u32::from(id).wrapping_mul(0x9E37_79B9);
+        lot(id_1).cmp(&lot(id_2))
+    }
 }
 
 /// Rank tiebreaker
@@ -363,7 +853,7 @@ impl UefaRanking {
         ranking_map: HashMap<TeamId, TeamRank>,
     ) -> Result<Self, GroupError> {
         // TODO: Why does this need to be mut?
-        let mut all_teams = groups.iter().flat_map(|x| x.team_ids());
+        let mut all_teams = groups.iter().flat_map(|x| x.teams());
         let exists = all_teams.all(|x| ranking_map.get(&x).is_some());
         if exists {
             Ok(UefaRanking(ranking_map))
@@ -409,13 +899,16 @@ impl Tiebreaker for UefaRanking {
 ///     - Yellow card and direct red card: -5 points
 /// 8. Drawing of lots by the FIFA.
 pub fn fifa_2018() -> Rules<Random> {
-    let group_point: AllGroupStat<GroupPoint> = AllGroupStat::new();
-    let goal_diff: AllGroupStat<GoalDiff> = AllGroupStat::new();
-    let goal_count: AllGroupStat<GoalCount> = AllGroupStat::new();
-    let int_group_point: InternalGroupStat<GroupPoint> = InternalGroupStat::new();
-    let int_goal_diff: InternalGroupStat<GoalDiff> = InternalGroupStat::new();
-    let int_goal_count: InternalGroupStat<GoalCount> = InternalGroupStat::new();
-    let fair_play: AllGroupStat<FifaFairPlayValue> = AllGroupStat::new();
+    let group_point: AllGroupStat<GroupPoint> = AllGroupStat::new("points");
+    let goal_diff: AllGroupStat<GoalDiff> = AllGroupStat::new("goal difference");
+    let goal_count: AllGroupStat<GoalCount> = AllGroupStat::new("goals scored");
+    let int_group_point: InternalGroupStat<GroupPoint> =
+        InternalGroupStat::new("head-to-head points");
+    let int_goal_diff: InternalGroupStat<GoalDiff> =
+        InternalGroupStat::new("head-to-head goal difference");
+    let int_goal_count: InternalGroupStat<GoalCount> =
+        InternalGroupStat::new("head-to-head goals scored");
+    let fair_play: AllGroupStat<FifaFairPlayValue> = AllGroupStat::new("fair play");
     Rules {
         non_strict: vec![
             Box::new(group_point),
@@ -462,13 +955,17 @@ pub fn fifa_2018() -> Rules<Random> {
 /// - The penalty shootout in 9 is pretty straightforward but needs manual data.
 /// - The FairPlayValue is incorrectly calculated (of course Fifa and Uefa have different weights.)
 pub fn euro_2020(ranking: UefaRanking) -> Rules<UefaRanking> {
-    let group_point: AllGroupStat<GroupPoint> = AllGroupStat::new();
-    let int_group_point: InternalGroupStat<GroupPoint> = InternalGroupStat::new();
-    let int_goal_diff: InternalGroupStat<GoalDiff> = InternalGroupStat::new();
-    let int_goal_count: InternalGroupStat<GoalCount> = InternalGroupStat::new();
-    let goal_diff: AllGroupStat<GoalDiff> = AllGroupStat::new();
-    let num_wins: AllGroupStat<NumWins> = AllGroupStat::new();
-    let fair_play: InternalGroupStat<UefaFairPlayValue> = InternalGroupStat::new();
+    let group_point: AllGroupStat<GroupPoint> = AllGroupStat::new("points");
+    let int_group_point: InternalGroupStat<GroupPoint> =
+        InternalGroupStat::new("head-to-head points");
+    let int_goal_diff: InternalGroupStat<GoalDiff> =
+        InternalGroupStat::new("head-to-head goal difference");
+    let int_goal_count: InternalGroupStat<GoalCount> =
+        InternalGroupStat::new("head-to-head goals scored");
+    let goal_diff: AllGroupStat<GoalDiff> = AllGroupStat::new("goal difference");
+    let num_wins: AllGroupStat<NumWins> = AllGroupStat::new("wins");
+    let fair_play: InternalGroupStat<UefaFairPlayValue> =
+        InternalGroupStat::new("head-to-head fair play");
     Rules {
         non_strict: vec![
             Box::new(group_point),
@@ -483,6 +980,185 @@ pub fn euro_2020(ranking: UefaRanking) -> Rules<UefaRanking> {
     }
 }
 
+/// Fifa World Cup 2026 Order
+///
+/// Compared to 2018, the head-to-head criteria come before the overall goal difference,
+/// and the drawing of lots is replaced by the Fifa ranking.
+///
+/// 1. Greatest number of points obtained in all group matches
+/// 2. Greatest number of points obtained in the group matches between the teams concerned
+/// 3. Goal difference resulting from the group matches between the teams concerned
+/// 4. Greatest number of goals scored in the group matches between the teams concerned
+/// 5. Goal difference in all group matches
+/// 6. Greatest number of goals scored in all group matches
+/// 7. Greatest number of fair play points in all group matches, see [`fifa_2018`]
+/// 8. Fifa ranking, the [`UefaRanking`] tiebreaker works for any ranking
+///
+/// Third placed teams across groups are compared with
+/// [`cross_group::fifa_2026`](crate::group::cross_group::fifa_2026).
+pub fn fifa_2026(ranking: UefaRanking) -> Rules<UefaRanking> {
+    let group_point: AllGroupStat<GroupPoint> = AllGroupStat::new("points");
+    let int_group_point: InternalGroupStat<GroupPoint> =
+        InternalGroupStat::new("head-to-head points");
+    let int_goal_diff: InternalGroupStat<GoalDiff> =
+        InternalGroupStat::new("head-to-head goal difference");
+    let int_goal_count: InternalGroupStat<GoalCount> =
+        InternalGroupStat::new("head-to-head goals scored");
+    let goal_diff: AllGroupStat<GoalDiff> = AllGroupStat::new("goal difference");
+    let goal_count: AllGroupStat<GoalCount> = AllGroupStat::new("goals scored");
+    let fair_play: AllGroupStat<FifaFairPlayValue> = AllGroupStat::new("fair play");
+    Rules {
+        non_strict: vec![
+            Box::new(group_point),
+            Box::new(int_group_point),
+            Box::new(int_goal_diff),
+            Box::new(int_goal_count),
+            Box::new(goal_diff),
+            Box::new(goal_count),
+            Box::new(fair_play),
+        ],
+        tiebreaker: ranking,
+    }
+}
+
+/// Fifa World Cup 1990 Order
+///
+/// Two points for a win, see [`TwoPoints`], and the overall goal difference before the
+/// head-to-head criteria.
+///
+/// 1. Greatest number of points obtained in all group matches
+/// 2. Goal difference in all group matches
+/// 3. Greatest number of goals scored in all group matches
+/// 4. Greatest number of points obtained in the group matches between the teams concerned
+/// 5. Goal difference resulting from the group matches between the teams concerned
+/// 6. Greatest number of goals scored in the group matches between the teams concerned
+/// 7. Drawing of lots by the FIFA.
+pub fn fifa_1990() -> Rules<Random> {
+    let group_point: AllGroupStat<Points<TwoPoints>> = AllGroupStat::new("points");
+    let goal_diff: AllGroupStat<GoalDiff> = AllGroupStat::new("goal difference");
+    let goal_count: AllGroupStat<GoalCount> = AllGroupStat::new("goals scored");
+    let int_group_point: InternalGroupStat<Points<TwoPoints>> =
+        InternalGroupStat::new("head-to-head points");
+    let int_goal_diff: InternalGroupStat<GoalDiff> =
+        InternalGroupStat::new("head-to-head goal difference");
+    let int_goal_count: InternalGroupStat<GoalCount> =
+        InternalGroupStat::new("head-to-head goals scored");
+    Rules {
+        non_strict: vec![
+            Box::new(group_point),
+            Box::new(goal_diff),
+            Box::new(goal_count),
+            Box::new(int_group_point),
+            Box::new(int_goal_diff),
+            Box::new(int_goal_count),
+        ],
+        tiebreaker: Random {},
+    }
+}
+
+/// Fifa World Cup 1994 Order
+///
+/// The first tournament with three points for a win, see [`ThreePoints`].
+/// Like 1990, the head-to-head result only separates teams level on the overall criteria,
+/// e.g. Ireland above Italy in group E.
+///
+/// 1. Greatest number of points obtained in all group matches
+/// 2. Goal difference in all group matches
+/// 3. Greatest number of goals scored in all group matches
+/// 4. Greatest number of points obtained in the group matches between the teams concerned
+/// 5. Drawing of lots by the FIFA.
+pub fn fifa_1994() -> Rules<Random> {
+    let group_point: AllGroupStat<Points<ThreePoints>> = AllGroupStat::new("points");
+    let goal_diff: AllGroupStat<GoalDiff> = AllGroupStat::new("goal difference");
+    let goal_count: AllGroupStat<GoalCount> = AllGroupStat::new("goals scored");
+    let int_group_point: InternalGroupStat<Points<ThreePoints>> =
+        InternalGroupStat::new("head-to-head points");
+    Rules {
+        non_strict: vec![
+            Box::new(group_point),
+            Box::new(goal_diff),
+            Box::new(goal_count),
+            Box::new(int_group_point),
+        ],
+        tiebreaker: Random {},
+    }
+}
+
+/// Names of the rules available through [`rules_by_name`]
+pub const RULE_NAMES: [&str; 5] = [
+    "fifa_1990",
+    "fifa_1994",
+    "fifa_2018",
+    "euro_2020",
+    "fifa_2026",
+];
+
+/// Tiebreaker of rules selected at runtime, see [`rules_by_name`]
+pub enum AnyTiebreaker {
+    Random(Random),
+    UefaRanking(UefaRanking),
+}
+
+impl Tiebreaker for AnyTiebreaker {
+    fn order(&self, group: &GroupView, non_strict: NonStrictGroupOrder) -> GroupOrder {
+        match self {
+            AnyTiebreaker::Random(tiebreaker) => tiebreaker.order(group, non_strict),
+            AnyTiebreaker::UefaRanking(tiebreaker) => tiebreaker.order(group, non_strict),
+        }
+    }
+
+    fn order_sub_group(&self, group: &GroupView, order: &[TeamId]) -> GroupOrder {
+        match self {
+            AnyTiebreaker::Random(tiebreaker) => tiebreaker.order_sub_group(group, order),
+            AnyTiebreaker::UefaRanking(tiebreaker) => tiebreaker.order_sub_group(group, order),
+        }
+    }
+
+    fn cmp(&self, id_1: TeamId, id_2: TeamId) -> Ordering {
+        match self {
+            AnyTiebreaker::Random(tiebreaker) => tiebreaker.cmp(id_1, id_2),
+            AnyTiebreaker::UefaRanking(tiebreaker) => tiebreaker.cmp(id_1, id_2),
+        }
+    }
+}
+
+impl<T: Tiebreaker> Rules<T> {
+    pub(crate) fn map_tiebreaker<U: Tiebreaker>(self, f: impl FnOnce(T) -> U) -> Rules<U> {
+        Rules {
+            non_strict: self.non_strict,
+            tiebreaker: f(self.tiebreaker),
+        }
+    }
+}
+
+/// Group ordering rules, selected by name
+///
+/// For when the rules are not known at compile time, e.g. in configuration files or bindings to
+/// other languages. See [`RULE_NAMES`] for the available names.
+///
+/// The `ranking` is only used by rules with a ranking tiebreaker, it must then include every team
+/// in `groups`.
+pub fn rules_by_name(
+    name: &str,
+    groups: &[Group],
+    ranking: HashMap<TeamId, TeamRank>,
+) -> Result<Rules<AnyTiebreaker>, GroupError> {
+    match name {
+        "fifa_1990" => Ok(fifa_1990().map_tiebreaker(AnyTiebreaker::Random)),
+        "fifa_1994" => Ok(fifa_1994().map_tiebreaker(AnyTiebreaker::Random)),
+        "fifa_2018" => Ok(fifa_2018().map_tiebreaker(AnyTiebreaker::Random)),
+        "euro_2020" => {
+            let ranking = UefaRanking::try_new(groups, ranking)?;
+            Ok(euro_2020(ranking).map_tiebreaker(AnyTiebreaker::UefaRanking))
+        }
+        "fifa_2026" => {
+            let ranking = UefaRanking::try_new(groups, ranking)?;
+            Ok(fifa_2026(ranking).map_tiebreaker(AnyTiebreaker::UefaRanking))
+        }
+        _ => Err(GroupError::UnknownRules),
+    }
+}
+
 #[cfg(test)]
 mod fifa_2018_ordering_tests {
     use super::*;
@@ -599,6 +1275,246 @@ mod fifa_2018_ordering_tests {
     }
 }
 
+#[cfg(test)]
+mod historical_ordering_tests {
+    use super::*;
+    use crate::group::mock_played_group;
+
+    /// Team 0 has a win and a heavy loss, team 3 two draws.
+    fn win_or_draws() -> Group {
+        mock_played_group(vec![
+            (0, 0, 1, (1, 0)),
+            (1, 2, 0, (3, 0)),
+            (2, 3, 1, (0, 0)),
+            (3, 3, 2, (0, 0)),
+        ])
+    }
+
+    #[test]
+    fn win_worth_two_draws() {
+        let group = win_or_draws();
+        let order = |ids: Vec<u32>| GroupOrder(ids.into_iter().map(TeamId).collect());
+        assert_eq!(order_group(&group, &fifa_1994()), order(vec![2, 0, 3, 1]));
+        assert_eq!(order_group(&group, &fifa_1990()), order(vec![2, 3, 0, 1]));
+        assert_eq!(
+            fifa_1990()
+                .explain_pair(&group, TeamId(0), TeamId(3))
+                .unwrap(),
+            RuleDecision::SubOrder {
+                index: 1,
+                name: String::from("goal difference"),
+                ahead: TeamId(3),
+                stats: (String::from("-2"), String::from("0")),
+            }
+        );
+    }
+
+    /// World Cup 1994 group E: Mexico (0), Ireland (1), Italy (2) and Norway (3) all finished on
+    /// four points with a goal difference of zero.
+    #[test]
+    fn head_to_head_after_goals_scored() {
+        let group = mock_played_group(vec![
+            (0, 1, 2, (1, 0)),
+            (1, 3, 0, (1, 0)),
+            (2, 2, 3, (1, 0)),
+            (3, 0, 1, (2, 1)),
+            (4, 2, 0, (1, 1)),
+            (5, 1, 3, (0, 0)),
+        ]);
+        let order = GroupOrder(vec![0, 1, 2, 3].into_iter().map(TeamId).collect());
+        assert_eq!(order_group(&group, &fifa_1994()), order);
+        assert_eq!(
+            fifa_1994()
+                .explain_pair(&group, TeamId(1), TeamId(2))
+                .unwrap(),
+            RuleDecision::SubOrder {
+                index: 3,
+                name: String::from("head-to-head points"),
+                ahead: TeamId(1),
+                stats: (String::from("3"), String::from("0")),
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod pairwise_tests {
+    use super::*;
+    use crate::group::mock_played_group;
+    use crate::group::stats::{DirectAwayGoals, DirectResult};
+
+    fn rules() -> Rules<Random> {
+        Rules::new(
+            vec![
+                Box::new(AllGroupStat::<GroupPoint>::new("points")),
+                Box::new(PairwiseStat::<DirectResult>::new("direct result")),
+                Box::new(PairwiseStat::<DirectAwayGoals>::new("direct away goals")),
+            ],
+            Random,
+        )
+    }
+
+    #[test]
+    fn away_goals_in_direct_game() {
+        let group = mock_played_group(vec![
+            (0, 0, 1, (1, 1)),
+            (1, 0, 2, (2, 0)),
+            (2, 1, 2, (2, 0)),
+        ]);
+        let order = |ids: Vec<u32>| GroupOrder(ids.into_iter().map(TeamId).collect());
+        assert_eq!(order_group(&group, &rules()), order(vec![1, 0, 2]));
+        assert_eq!(
+            rules().explain_pair(&group, TeamId(1), TeamId(0)).unwrap(),
+            RuleDecision::SubOrder {
+                index: 2,
+                name: String::from("direct away goals"),
+                ahead: TeamId(1),
+                stats: (String::from("+1"), String::from("-1")),
+            }
+        );
+    }
+
+    #[test]
+    fn cycle_stays_tied() {
+        let group = mock_played_group(vec![
+            (0, 0, 1, (1, 0)),
+            (1, 1, 2, (1, 0)),
+            (2, 2, 0, (1, 0)),
+        ]);
+        let order = non_strict_order_group(&group, &rules());
+        assert_eq!(order.tied_groups().count(), 1);
+        assert!(order.contains_tie_between(TeamId(0), TeamId(2)));
+    }
+}
+
+#[cfg(test)]
+mod explain_pair_tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::group::game::PlayedGroupGame;
+    use crate::Date;
+
+    #[test]
+    fn internal_game_decides() {
+        // Same setup as `fifa_2018_ordering_tests::internal_game`
+        let games = vec![
+            (0, 0, 2, (1, 0)),
+            (1, 1, 2, (1, 0)),
+            (2, 1, 2, (1, 0)),
+            (3, 0, 1, (1, 0)),
+            (4, 0, 3, (0, 1)),
+        ]
+        .into_iter()
+        .map(|(id, home, away, score)| {
+            PlayedGroupGame::try_new(
+                id,
+                home,
+                away,
+                score,
+                FairPlayScore::default(),
+                Date::mock(),
+            )
+            .unwrap()
+        })
+        .collect();
+        let group = Group::try_new(vec![], games).unwrap();
+        let rules = fifa_2018();
+        assert_eq!(
+            rules.explain_pair(&group, TeamId(1), TeamId(0)).unwrap(),
+            RuleDecision::SubOrder {
+                index: 3,
+                name: String::from("head-to-head points"),
+                ahead: TeamId(0),
+                stats: (String::from("0"), String::from("3")),
+            }
+        );
+        match rules.explain_pair(&group, TeamId(0), TeamId(2)).unwrap() {
+            RuleDecision::SubOrder { index, ahead, .. } => {
+                assert_eq!(index, 0);
+                assert_eq!(ahead, TeamId(0));
+            }
+            RuleDecision::Tiebreaker => panic!("Separated by points"),
+        }
+        assert!(matches!(
+            rules.explain_pair(&group, TeamId(0), TeamId(0)),
+            Err(GroupError::SameTeam(TeamId(0)))
+        ));
+        assert!(matches!(
+            rules.explain_pair(&group, TeamId(0), TeamId(9)),
+            Err(GroupError::TeamNotInGroup(TeamId(9)))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod audit_tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::group::game::PlayedGroupGame;
+    use crate::Date;
+
+    fn drawn_group() -> Group {
+        let game =
+            PlayedGroupGame::try_new(0, 0, 1, (1, 1), FairPlayScore::default(), Date::mock())
+                .unwrap();
+        Group::try_new(vec![], vec![game]).unwrap()
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn random_draw_pending() {
+        let audit = audit_order(&drawn_group(), &fifa_2018(), 100);
+        assert_eq!(audit.ties, vec![vec![TeamId(0), TeamId(1)]]);
+        assert!(!audit.is_deterministic());
+        assert_eq!(
+            audit.orders.iter().map(|(_, count)| count).sum::<usize>(),
+            100
+        );
+    }
+
+    #[test]
+    fn ranking_decides() {
+        let group = drawn_group();
+        let ranking = vec![(TeamId(0), TeamRank(2)), (TeamId(1), TeamRank(1))]
+            .into_iter()
+            .collect();
+        let rules = euro_2020(UefaRanking::try_new(std::slice::from_ref(&group), ranking).unwrap());
+        let audit = audit_order(&group, &rules, 10);
+        assert!(audit.uses_tiebreaker());
+        assert!(audit.is_deterministic());
+        assert_eq!(audit.orders, vec![(vec![TeamId(1), TeamId(0)], 10)]);
+    }
+}
+
+#[cfg(test)]
+mod non_strict_order_tests {
+    use super::*;
+
+    fn mock_order() -> NonStrictGroupOrder {
+        let mut order = NonStrictGroupOrder::single(&[TeamId(0)]);
+        order.extend(&NonStrictGroupOrder::single(&[TeamId(1), TeamId(2)]));
+        order.add_sub_order(TeamId(3));
+        order
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(mock_order().to_string(), "[0] > [1, 2] > [3]");
+    }
+
+    #[test]
+    fn ties() {
+        let order = mock_order();
+        assert_eq!(
+            order.tied_groups().collect::<Vec<_>>(),
+            vec![&[TeamId(1), TeamId(2)][..]]
+        );
+        assert!(order.contains_tie_between(TeamId(2), TeamId(1)));
+        assert!(!order.contains_tie_between(TeamId(0), TeamId(1)));
+        assert!(!order.contains_tie_between(TeamId(1), TeamId(1)));
+    }
+}
+
 #[cfg(test)]
 mod tiebreaker_test {
     use super::*;
@@ -611,4 +1527,20 @@ mod tiebreaker_test {
         let ranking = UefaRanking(ranking);
         assert_eq!(ranking.cmp(TeamId(0), TeamId(1)), Ordering::Greater);
     }
+
+    #[test]
+    fn named_rules() {
+        let (groups, teams) = crate::group::mock_data();
+        let groups: Vec<Group> = groups.into_values().collect();
+        let ranking: HashMap<_, _> = teams.iter().map(|(id, team)| (*id, team.rank)).collect();
+        let rules = rules_by_name("euro_2020", &groups, ranking.clone()).unwrap();
+        let expected = euro_2020(UefaRanking::try_new(&groups, ranking.clone()).unwrap());
+        for group in &groups {
+            assert_eq!(order_group(group, &rules), order_group(group, &expected));
+        }
+        assert!(matches!(
+            rules_by_name("wc_1930", &groups, ranking),
+            Err(GroupError::UnknownRules)
+        ));
+    }
 }
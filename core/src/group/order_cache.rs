@@ -0,0 +1,156 @@
+//! Group order cache
+//!
+//! Ordering a group is repeated a lot with the same input: every simulation orders every group,
+//! often ending up with the same results, and every read of the standings orders the same,
+//! unchanged, groups again.
+//!
+//! [`GroupOrderCache`] memoizes the order, keyed by [`game_set_hash`] of the games of the group
+//! and an id of the rules. A new result or a changed score gives a new hash, so a stale order is
+//! never returned, there is nothing to invalidate by hand.
+//!
+//! Only the non-strict order is cached, the tiebreaker is applied on every lookup.
+//! With a random tiebreaker, the ties are drawn anew every time, just like with [`order_group`].
+//!
+//! [`order_group`]: crate::group::order_group
+use crate::game::Game;
+use crate::group::game::GroupGame;
+use crate::group::order::{non_strict_order_group, GroupOrder, NonStrictGroupOrder};
+use crate::group::view::GroupView;
+use crate::group::{Rules, Tiebreaker};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Hash of the games of a group and the id of the rules ordering it
+///
+/// Covers the teams, status and score (including fair play) of every game, but not the kickoff,
+/// which does not affect the order. The hash is independent of the order of the games.
+/// A [`GroupView`] hashes like the group with its hypothetical results played.
+pub fn game_set_hash<'a>(group: impl Into<GroupView<'a>>, rules_id: &str) -> u64 {
+    let mut games: Vec<GroupGame> = group.into().games().collect();
+    games.sort_by_key(|game| game.id());
+    let mut hasher = DefaultHasher::new();
+    rules_id.hash(&mut hasher);
+    for game in games {
+        game.id().hash(&mut hasher);
+        game.home_team().hash(&mut hasher);
+        game.away_team().hash(&mut hasher);
+        game.status().hash(&mut hasher);
+        if let GroupGame::Played(game) = game {
+            u32::from(game.score.home).hash(&mut hasher);
+            u32::from(game.score.away).hash(&mut hasher);
+            game.fair_play.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// Memoized group orders, see the [module docs](self)
+///
+/// Holds at most `capacity` orders, when full it is cleared before the next order is stored.
+#[derive(Debug, Clone)]
+pub struct GroupOrderCache {
+    capacity: usize,
+    orders: HashMap<u64, NonStrictGroupOrder>,
+}
+
+impl GroupOrderCache {
+    pub const DEFAULT_CAPACITY: usize = 4096;
+
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        GroupOrderCache {
+            capacity: capacity.max(1),
+            orders: HashMap::new(),
+        }
+    }
+
+    /// Order a group, like [`order_group`](crate::group::order_group)
+    ///
+    /// `rules_id` identifies `rules`, e.g. a name from
+    /// [`RULE_NAMES`](crate::group::order::RULE_NAMES).
+    /// Different rules must not share an id within one cache.
+    pub fn order<'a, T: Tiebreaker>(
+        &mut self,
+        group: impl Into<GroupView<'a>>,
+        rules_id: &str,
+        rules: &Rules<T>,
+    ) -> GroupOrder {
+        let group = group.into();
+        let key = game_set_hash(group, rules_id);
+        let non_strict = match self.orders.get(&key) {
+            Some(order) => order.clone(),
+            None => {
+                let order = non_strict_order_group(group, rules);
+                if self.orders.len() >= self.capacity {
+                    self.orders.clear();
+                }
+                self.orders.insert(key, order.clone());
+                order
+            }
+        };
+        rules.break_ties(&group, non_strict)
+    }
+
+    /// Number of cached orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.orders.clear()
+    }
+}
+
+impl Default for GroupOrderCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Score;
+    use crate::group::mock_unplayed_group;
+    use crate::group::order::{fifa_2018, order_group};
+
+    #[test]
+    fn invalidated_by_result() {
+        let rules = fifa_2018();
+        let mut cache = GroupOrderCache::new();
+        let mut group = mock_unplayed_group();
+        group.play_game(0.into(), Score::from((2, 0)));
+        group.play_game(1.into(), Score::from((0, 1)));
+        let first = cache.order(&group, "fifa-2018", &rules);
+        assert_eq!(first, order_group(&group, &rules));
+        assert_eq!(cache.order(&group, "fifa-2018", &rules), first);
+        assert_eq!(cache.len(), 1);
+
+        group.play_game(2.into(), Score::from((0, 3)));
+        let hash = game_set_hash(&group, "fifa-2018");
+        assert_ne!(hash, game_set_hash(&group, "fifa-1994"));
+        let second = cache.order(&group, "fifa-2018", &rules);
+        assert_eq!(second, order_group(&group, &rules));
+        assert_ne!(second, first);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn bounded() {
+        let rules = fifa_2018();
+        let mut cache = GroupOrderCache::with_capacity(2);
+        let group = mock_unplayed_group();
+        for id in &["a", "b", "c"] {
+            cache.order(&group, id, &rules);
+        }
+        assert_eq!(cache.len(), 1);
+    }
+}
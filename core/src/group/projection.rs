@@ -0,0 +1,121 @@
+//! Projected standings
+//!
+//! Group order under incomplete data: the unplayed games are filled in with a projected score,
+//! e.g. to show the table "if your predictions come true".
+//! The projected scores only exist in a copy of the group, see [`project_group`], or in a
+//! [`GroupView`] when ordering.
+use crate::fair_play::FairPlayScore;
+use crate::game::{GameId, Score};
+use crate::group::game::{PlayedGroupGame, UnplayedGroupGame};
+use crate::group::view::GroupView;
+use crate::group::{order_group, Group, GroupOrder, Rules, Tiebreaker};
+use crate::player::Prediction;
+use crate::team::{TeamId, TeamRank};
+use std::collections::HashMap;
+
+/// Score of an unplayed game, for projected standings
+#[derive(Debug, Clone, Copy)]
+pub enum ScoreProjection<'a> {
+    /// Every game ends 0-0
+    Draw,
+    /// The better ranked team (lower rank) wins 1-0, equally ranked teams draw 0-0.
+    /// Games with an unranked team are left unplayed.
+    Ranking(&'a HashMap<TeamId, TeamRank>),
+    /// A player's predictions, games without a prediction are left unplayed
+    Predictions(&'a [Prediction]),
+}
+
+impl ScoreProjection<'_> {
+    /// Projected score of a game, `None` if the game is left unplayed
+    pub fn score(&self, game: &UnplayedGroupGame) -> Option<Score> {
+        match self {
+            ScoreProjection::Draw => Some(Score::from((0, 0))),
+            ScoreProjection::Ranking(ranking) => {
+                let home = ranking.get(&game.home)?;
+                let away = ranking.get(&game.away)?;
+                Some(Score::from(match home.cmp(away) {
+                    std::cmp::Ordering::Less => (1, 0),
+                    std::cmp::Ordering::Equal => (0, 0),
+                    std::cmp::Ordering::Greater => (0, 1),
+                }))
+            }
+            ScoreProjection::Predictions(preds) => preds
+                .iter()
+                .find(|Prediction(id, _)| *id == game.id)
+                .map(|Prediction(_, score)| *score),
+        }
+    }
+}
+
+/// Unplayed games played with their projected scores, games left unplayed are left out
+fn projected_results(group: &Group, projection: &ScoreProjection) -> Vec<PlayedGroupGame> {
+    group
+        .unplayed_games()
+        .filter_map(|game| {
+            projection
+                .score(game)
+                .map(|score| game.play(score, FairPlayScore::default()))
+        })
+        .collect()
+}
+
+/// Copy of a group with the unplayed games played with their projected scores
+pub fn project_group(group: &Group, projection: &ScoreProjection) -> Group {
+    let scores: Vec<(GameId, Score)> = group
+        .unplayed_games()
+        .filter_map(|game| projection.score(game).map(|score| (game.id, score)))
+        .collect();
+    let mut projected = group.clone();
+    for (id, score) in scores {
+        projected.play_game(id, score);
+    }
+    projected
+}
+
+/// Order of a group with the unplayed games filled in by `projection`, like ordering
+/// [`project_group`] but without copying the group
+pub fn order_group_projected<T: Tiebreaker>(
+    group: &Group,
+    rules: &Rules<T>,
+    projection: &ScoreProjection,
+) -> GroupOrder {
+    let results = projected_results(group, projection);
+    order_group(GroupView::with_results(group, &results), rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group::mock_unplayed_group;
+    use crate::group::order::{euro_2020, GroupRank, UefaRanking};
+
+    fn ranking() -> HashMap<TeamId, TeamRank> {
+        (0..4).map(|id| (TeamId(id), TeamRank(10 - id))).collect()
+    }
+
+    #[test]
+    fn projections() {
+        let mut group = mock_unplayed_group();
+        group.play_game(GameId::from(0), Score::from((0, 2)));
+        let rules = euro_2020(UefaRanking::try_new(&[group.clone()], ranking()).unwrap());
+
+        let drawn = project_group(&group, &ScoreProjection::Draw);
+        assert_eq!(drawn.unplayed_games().count(), 0);
+        assert_eq!(group.unplayed_games().count(), 3);
+        let order = order_group_projected(&group, &rules, &ScoreProjection::Draw);
+        assert_eq!(order[GroupRank(0)], TeamId(1));
+
+        // Team 3 is the best ranked, and wins both its games
+        let ranking = ranking();
+        let order = order_group_projected(&group, &rules, &ScoreProjection::Ranking(&ranking));
+        assert_eq!(order[GroupRank(0)], TeamId(3));
+
+        // Only the predicted game is played, team 1 is ahead of team 0 by head-to-head
+        let preds = vec![Prediction(GameId::from(2), Score::from((5, 0)))];
+        let projected = project_group(&group, &ScoreProjection::Predictions(&preds));
+        assert_eq!(projected.unplayed_games().count(), 2);
+        let order = order_group_projected(&group, &rules, &ScoreProjection::Predictions(&preds));
+        assert_eq!(order[GroupRank(0)], TeamId(1));
+        assert_eq!(order[GroupRank(1)], TeamId(0));
+    }
+}
@@ -0,0 +1,277 @@
+//! Group stage schedule
+//!
+//! The final round of a group, i.e. the last game of every team, kicks off simultaneously, so
+//! that no team plays its last game knowing exactly which result it needs.
+//! [`check_schedule`] flags the groups where this is not the case and [`assign_final_rounds`]
+//! finds a kickoff slot for the final round of every group, without playing two games at the same
+//! venue on the same day.
+//!
+//! Groups with an odd number of teams have no simultaneous final round, some team always rests,
+//! and are never flagged nor assigned a slot, see [`has_final_round`].
+use crate::game::{Game, GameId};
+use crate::group::game::GroupGame;
+use crate::group::{Group, GroupError, GroupId, Groups};
+use crate::venue::{GameVenues, VenueId};
+use crate::Date;
+use chrono::NaiveDate;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// Final round of a group: the last game of every team, ordered by kickoff
+///
+/// Void games are not counted, see [`GameStatus::is_void`](crate::game::GameStatus::is_void).
+pub fn final_round(group: &Group) -> Vec<GroupGame<'_>> {
+    let games: Vec<GroupGame> = group
+        .games()
+        .filter(|game| !game.status().is_void())
+        .collect();
+    let last_games: HashSet<GameId> = games
+        .iter()
+        .flat_map(|game| vec![(game.home_team(), game.id()), (game.away_team(), game.id())])
+        .collect::<HashMap<_, _>>()
+        .into_values()
+        .collect();
+    games
+        .into_iter()
+        .filter(|game| last_games.contains(&game.id()))
+        .collect()
+}
+
+/// A group with an even number of teams, where every team plays in the final round
+pub fn has_final_round(group: &Group) -> bool {
+    group.num_teams() % 2 != 1
+}
+
+/// Final round of a group not kicking off simultaneously
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleViolation {
+    pub group: GroupId,
+    /// Games of the final round with their kickoff
+    pub games: Vec<(GameId, Date)>,
+}
+
+impl fmt::Display for ScheduleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let games: Vec<String> = self
+            .games
+            .iter()
+            .map(|(id, kickoff)| format!("game {} at {}", id, kickoff))
+            .collect();
+        write!(
+            f,
+            "Final round of group {} is not simultaneous: {}",
+            self.group,
+            games.join(", ")
+        )
+    }
+}
+
+/// Check that the final round of every group kicks off simultaneously
+pub fn check_schedule(groups: &Groups) -> Vec<ScheduleViolation> {
+    groups
+        .iter()
+        .filter(|(_, group)| has_final_round(group))
+        .filter_map(|(id, group)| {
+            let games: Vec<(GameId, Date)> = final_round(group)
+                .iter()
+                .map(|game| (game.id(), game.date()))
+                .collect();
+            if games.iter().map(|(_, kickoff)| kickoff).all_equal() {
+                None
+            } else {
+                Some(ScheduleViolation { group: *id, games })
+            }
+        })
+        .collect()
+}
+
+/// Assign a kickoff slot to the final round of every group
+///
+/// Every slot is used by at most one group and at most `max_per_day` final rounds are played on
+/// the same day. The final round of a group is played on a later day than the other games of the
+/// group.
+/// A venue hosts at most one game per day, counting the games outside of the final rounds and the
+/// final rounds already assigned. Games without a known venue never clash.
+///
+/// The slots are assigned greedily: the group with the earliest other games first, gets the
+/// earliest available slot. This may fail even if there is a valid assignment.
+/// Groups without a final round get no slot, see [`has_final_round`].
+///
+/// # Errors
+///
+/// Fails with [`GroupError::NoKickoffSlot`] for the first group without an available slot.
+pub fn assign_final_rounds(
+    groups: &Groups,
+    venues: &GameVenues,
+    slots: &[Date],
+    max_per_day: usize,
+) -> Result<BTreeMap<GroupId, Date>, GroupError> {
+    let slots: Vec<Date> = slots.iter().copied().sorted().collect();
+    let mut used = vec![false; slots.len()];
+    let mut per_day: HashMap<NaiveDate, usize> = HashMap::new();
+    let final_rounds: HashMap<GroupId, HashSet<GameId>> = groups
+        .iter()
+        .filter(|(_, group)| has_final_round(group))
+        .map(|(id, group)| (*id, final_round(group).iter().map(|x| x.id()).collect()))
+        .collect();
+    let venue_of = |game: GameId| venues.get(&game).map(|venue| venue.id.clone());
+    let mut booked: HashSet<(NaiveDate, VenueId)> = groups
+        .iter()
+        .flat_map(|(id, group)| {
+            let final_ids = final_rounds.get(id);
+            group
+                .games()
+                .filter(|game| !game.status().is_void())
+                .filter(move |game| !matches!(final_ids, Some(ids) if ids.contains(&game.id())))
+                .filter_map(|game| Some((game.date().day(), venue_of(game.id())?)))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let groups = final_rounds
+        .iter()
+        .map(|(id, final_ids)| {
+            let earlier = groups[id]
+                .games()
+                .filter(|game| !final_ids.contains(&game.id()))
+                .map(|game| game.date())
+                .max();
+            let venues: Vec<VenueId> = final_ids.iter().filter_map(|id| venue_of(*id)).collect();
+            (*id, earlier, venues)
+        })
+        .sorted_by_key(|(id, earlier, _)| (*earlier, *id));
+    let mut assigned = BTreeMap::new();
+    for (id, earlier, venues) in groups {
+        let slot = slots.iter().enumerate().position(|(idx, slot)| {
+            !used[idx]
+                && per_day.get(&slot.day()).copied().unwrap_or(0) < max_per_day
+                && !matches!(earlier, Some(earlier) if earlier.day() >= slot.day())
+                && venues
+                    .iter()
+                    .all(|venue| !booked.contains(&(slot.day(), venue.clone())))
+        });
+        let idx = slot.ok_or(GroupError::NoKickoffSlot(id))?;
+        used[idx] = true;
+        *per_day.entry(slots[idx].day()).or_insert(0) += 1;
+        booked.extend(venues.into_iter().map(|venue| (slots[idx].day(), venue)));
+        assigned.insert(id, slots[idx]);
+    }
+    Ok(assigned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group::game::UnplayedGroupGame;
+    use crate::stage::round_robin;
+    use crate::team::TeamId;
+    use crate::venue::Venue;
+
+    fn date(day: u32, hour: u32) -> Date {
+        format!("2018-06-{:02} {:02}:00", day, hour)
+            .parse()
+            .unwrap()
+    }
+
+    /// Round robin of four teams, one round per day from `day`, the final round at `hours`
+    fn group(first_id: u32, day: u32, hours: (u32, u32)) -> Group {
+        let t = first_id;
+        let games = vec![
+            (t, t + 1, date(day, 12)),
+            (t + 2, t + 3, date(day, 15)),
+            (t, t + 2, date(day + 1, 12)),
+            (t + 1, t + 3, date(day + 1, 15)),
+            (t, t + 3, date(day + 2, hours.0)),
+            (t + 1, t + 2, date(day + 2, hours.1)),
+        ];
+        let games = games
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (home, away, kickoff))| {
+                UnplayedGroupGame::try_new(first_id + idx as u32, home, away, kickoff).unwrap()
+            })
+            .collect();
+        Group::try_new(games, vec![]).unwrap()
+    }
+
+    #[test]
+    fn final_round_simultaneous() {
+        let mut groups = Groups::new();
+        groups.insert(GroupId::from('A'), group(0, 14, (16, 16)));
+        groups.insert(GroupId::from('B'), group(10, 14, (16, 20)));
+        let ids: Vec<GameId> = final_round(&groups[&GroupId::from('A')])
+            .iter()
+            .map(|game| game.id())
+            .collect();
+        assert_eq!(ids, vec![GameId::from(4), GameId::from(5)]);
+        let violations = check_schedule(&groups);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].group, GroupId::from('B'));
+    }
+
+    #[test]
+    fn assign_slots() {
+        let mut groups = Groups::new();
+        groups.insert(GroupId::from('A'), group(0, 14, (16, 16)));
+        groups.insert(GroupId::from('B'), group(10, 14, (16, 16)));
+        groups.insert(GroupId::from('C'), group(20, 15, (16, 16)));
+        let slots = vec![date(16, 16), date(16, 20), date(17, 16), date(17, 20)];
+        let assigned = assign_final_rounds(&groups, &GameVenues::new(), &slots, 2).unwrap();
+        assert_eq!(assigned[&GroupId::from('A')], date(16, 16));
+        assert_eq!(assigned[&GroupId::from('B')], date(16, 20));
+        assert_eq!(assigned[&GroupId::from('C')], date(17, 16));
+        // Group C can not play its final round on the 16th
+        assert!(matches!(
+            assign_final_rounds(&groups, &GameVenues::new(), &slots, 1),
+            Err(GroupError::NoKickoffSlot(id)) if id == GroupId::from('C')
+        ));
+    }
+
+    #[test]
+    fn venue_clash() {
+        let mut groups = Groups::new();
+        groups.insert(GroupId::from('A'), group(0, 14, (16, 16)));
+        groups.insert(GroupId::from('C'), group(20, 15, (16, 16)));
+        let venue = |id: &str| Venue {
+            id: VenueId(String::from(id)),
+            name: String::from(id),
+            city: String::from(id),
+        };
+        let slots = vec![date(16, 16), date(17, 16), date(18, 16)];
+        let assigned = assign_final_rounds(&groups, &GameVenues::new(), &slots, 2).unwrap();
+        assert_eq!(assigned[&GroupId::from('A')], date(16, 16));
+        // A game of group C is played at the venue of the final round of group A on the 16th
+        let mut venues = GameVenues::new();
+        venues.insert(GameId::from(4), venue("X"));
+        venues.insert(GameId::from(22), venue("X"));
+        let assigned = assign_final_rounds(&groups, &venues, &slots, 2).unwrap();
+        assert_eq!(assigned[&GroupId::from('A')], date(17, 16));
+        assert_eq!(assigned[&GroupId::from('C')], date(18, 16));
+    }
+
+    #[test]
+    fn group_sizes() {
+        // A group of `size` teams, one matchday per day from the 14th
+        let group = |size: u32| {
+            let teams: Vec<TeamId> = (1..=size).map(TeamId).collect();
+            let games = round_robin(&teams, 1, GameId::from(0), |matchday| {
+                date(14 + matchday as u32, 18)
+            })
+            .unwrap();
+            Group::try_new(games, vec![]).unwrap()
+        };
+        assert_eq!(final_round(&group(6)).len(), 3);
+        let mut groups = Groups::new();
+        groups.insert(GroupId::from('A'), group(3));
+        groups.insert(GroupId::from('B'), group(5));
+        groups.insert(GroupId::from('C'), group(6));
+        assert!(check_schedule(&groups).is_empty());
+        let assigned =
+            assign_final_rounds(&groups, &GameVenues::new(), &[date(25, 18)], 1).unwrap();
+        assert_eq!(
+            assigned.keys().collect::<Vec<_>>(),
+            vec![&GroupId::from('C')]
+        );
+    }
+}
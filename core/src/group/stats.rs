@@ -1,13 +1,17 @@
 //! Group statistics
 use crate::fair_play::{FairPlayValue, FifaFairPlayValue};
-use crate::game::{GoalCount, GoalDiff, NumGames};
+use crate::game::{GameId, GoalCount, GoalDiff, NumGames, Outcome, Score};
 use crate::group::game::PlayedGroupGame;
+use crate::group::view::GroupView;
 use crate::group::{Group, GroupPoint};
 use crate::team::TeamId;
 use derive_more::{Add, AddAssign};
+use num::Zero;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::marker::PhantomData;
 use std::ops;
 
 // It looks like this could be more efficient with the cool grouping
@@ -16,6 +20,85 @@ use std::ops;
 // I tested it and it is indeed faster when all teams occur in the played games. This is of course
 // not true in general and adding that extra hashmap merge makes it solidly slower than the naive
 // impl (also requires an additional 'Clone' constraint on the 'UnaryStat' trait).
+/// Newtype statistic over a number, e.g. a count of games
+///
+/// Defines the struct with the numeric impls needed to order by it:
+/// `Debug`, `Clone`, `Copy`, `Eq`, `Ord`, `Display`, `Add`, `AddAssign` and `Zero`,
+/// and implements [`UnaryStat`] with the given closure, which maps a played game to the inner
+/// values for the (home, away) team.
+///
+/// The inner type needs the same impls, e.g. `u32` or [`NumGames`](crate::game::NumGames).
+///
+/// ```
+/// # use wwc_core::unary_stat;
+/// # use wwc_core::game::GoalCount;
+/// # use wwc_core::group::game::UnplayedGroupGame;
+/// # use wwc_core::group::stats::UnaryStat;
+/// # use wwc_core::fair_play::FairPlayScore;
+/// # use wwc_core::Date;
+/// unary_stat! {
+///     /// Number of games without conceding a goal
+///     pub struct CleanSheets(pub u32);
+///     |game| (
+///         (game.score.away == GoalCount::from(0)) as u32,
+///         (game.score.home == GoalCount::from(0)) as u32,
+///     )
+/// }
+///
+/// let game = UnplayedGroupGame::try_new(0, 1, 2, Date::mock())
+///     .unwrap()
+///     .play((2, 0).into(), FairPlayScore::default());
+/// assert_eq!(CleanSheets::stat(&game), (CleanSheets(1), CleanSheets(0)));
+/// ```
+#[macro_export]
+macro_rules! unary_stat {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident($inner_vis:vis $inner:ty);
+        |$game:ident| $stat:expr
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+        $vis struct $name($inner_vis $inner);
+
+        impl ::std::ops::Add for $name {
+            type Output = Self;
+            fn add(self, other: Self) -> Self {
+                $name(self.0 + other.0)
+            }
+        }
+
+        impl ::std::ops::AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                self.0 += other.0
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl $crate::__private::num::Zero for $name {
+            fn zero() -> Self {
+                $name(<$inner as $crate::__private::num::Zero>::zero())
+            }
+
+            fn is_zero(&self) -> bool {
+                $crate::__private::num::Zero::is_zero(&self.0)
+            }
+        }
+
+        impl $crate::group::stats::UnaryStat for $name {
+            fn stat($game: &$crate::group::game::PlayedGroupGame) -> (Self, Self) {
+                let (home, away) = $stat;
+                ($name(home), $name(away))
+            }
+        }
+    };
+}
+
 /// Statistic calculated from a single game.
 ///
 /// Implentor needs to provide the actual [`UnaryStat::stat`] function,
@@ -32,11 +115,12 @@ pub trait UnaryStat: num::Zero + ops::AddAssign {
     /// Calculate statistic for all played games in a group.
     ///
     /// Statistics for all games are summed up and stored in a map of the teams.
-    fn team_stats(group: &Group) -> HashMap<TeamId, Self> {
-        let team_map = group.team_ids().map(|team| (team, Self::zero())).collect();
+    /// Takes a `&Group` or a [`GroupView`] with hypothetical results.
+    fn team_stats<'a>(group: impl Into<GroupView<'a>>) -> HashMap<TeamId, Self> {
+        let group = group.into();
+        let team_map = group.teams().map(|team| (team, Self::zero())).collect();
         group
-            .played_games
-            .iter()
+            .played_games()
             .fold(team_map, |acc, game| calc_and_assign_stat(acc, game))
     }
 
@@ -45,18 +129,59 @@ pub trait UnaryStat: num::Zero + ops::AddAssign {
     /// Only games where both home and away teams are members of the `team_filter` set.
     /// Statistics for the games are summed up and stored in a map of the teams.
     fn internal_team_stats(group: &Group, team_filter: &HashSet<&TeamId>) -> HashMap<TeamId, Self> {
-        let team_map = team_filter
+        let teams: Vec<TeamId> = team_filter.iter().map(|team| **team).collect();
+        Self::internal_stats(&InternalGames::new(group, &teams))
+    }
+
+    /// Calculate statistic for the internal games of a subset of teams.
+    ///
+    /// Like [`UnaryStat::internal_team_stats`], with the games already filtered.
+    fn internal_stats(games: &InternalGames) -> HashMap<TeamId, Self> {
+        let team_map = games
+            .teams
             .iter()
-            .map(|team| (**team, Self::zero()))
+            .map(|team| (*team, Self::zero()))
             .collect();
-        group
-            .played_games
-            .iter()
-            .filter(|game| team_filter.contains(&game.home) && team_filter.contains(&game.away))
+        games
+            .games()
             .fold(team_map, |acc, game| calc_and_assign_stat(acc, game))
     }
 }
 
+/// Played games between the teams of a subset of a group
+///
+/// The games are filtered in one pass when created. Several internal stats of the same subset,
+/// e.g. internal points and then internal goal difference, share one `InternalGames`,
+/// see [`UnaryStat::internal_stats`].
+#[derive(Debug, Clone)]
+pub struct InternalGames<'a> {
+    teams: Vec<TeamId>,
+    games: Vec<&'a PlayedGroupGame>,
+}
+
+impl<'a> InternalGames<'a> {
+    pub fn new(group: impl Into<GroupView<'a>>, teams: &[TeamId]) -> Self {
+        let team_filter: HashSet<TeamId> = teams.iter().copied().collect();
+        let games = group
+            .into()
+            .played_games()
+            .filter(|game| team_filter.contains(&game.home) && team_filter.contains(&game.away))
+            .collect();
+        InternalGames {
+            teams: teams.to_vec(),
+            games,
+        }
+    }
+
+    pub fn teams(&self) -> &[TeamId] {
+        &self.teams
+    }
+
+    pub fn games(&self) -> impl Iterator<Item = &'a PlayedGroupGame> + '_ {
+        self.games.iter().copied()
+    }
+}
+
 /// Calculate stat for a game and assign to team map.
 ///
 /// Internal helper function for the [`UnaryStat`] trait.
@@ -65,9 +190,9 @@ pub trait UnaryStat: num::Zero + ops::AddAssign {
 ///
 /// Unwrap's do not panic if [`TeamId`]'s of `game.home` and `game.away` are members of `acc`:
 /// - Calling this from [`UnaryStat::team_stats`], [`TeamId`]'s will always be present, checked in [Group] constructor.
-/// - Calling this from [`UnaryStat::internal_team_stats`] is ok since the unwrap's would panic iff `acc` would
+/// - Calling this from [`UnaryStat::internal_stats`] is ok since the unwrap's would panic iff `acc` would
 ///   not contain `game.home` or `game.away`, which is exactly the predicate that the
-///   `group.played_games` are filtered by.
+///   [`InternalGames`] are filtered by.
 /// - Other calls do not exist (private fn), when adding a call: Take care to uphold this invariant!
 fn calc_and_assign_stat<T: UnaryStat>(
     acc: HashMap<TeamId, T>,
@@ -84,9 +209,220 @@ fn calc_and_assign_stat<T: UnaryStat>(
     acc
 }
 
+/// Statistic of a pair of teams, from the games between them
+///
+/// Some criteria are inherently pairwise, e.g. the direct result, and can not be summed up per
+/// team like a [`UnaryStat`].
+/// Used as a sub-order with [`PairwiseStat`](crate::group::order::PairwiseStat).
+pub trait BinaryStat {
+    /// Relative order of two teams, `Greater` if `team` ranks above `other`
+    ///
+    /// `Equal` if the stat does not separate the teams, e.g. if they have not played each other.
+    fn compare(group: &GroupView, team: TeamId, other: TeamId) -> Ordering;
+}
+
+/// Played games between two teams, in either direction
+fn direct_games<'a>(
+    group: &GroupView<'a>,
+    team: TeamId,
+    other: TeamId,
+) -> impl Iterator<Item = &'a PlayedGroupGame> {
+    group.played_games().filter(move |game| {
+        (game.home, game.away) == (team, other) || (game.home, game.away) == (other, team)
+    })
+}
+
+/// Direct result, the goal difference over the games between the two teams
+#[derive(Debug, Clone, Copy)]
+pub struct DirectResult;
+
+impl BinaryStat for DirectResult {
+    fn compare(group: &GroupView, team: TeamId, other: TeamId) -> Ordering {
+        let goal_diff: GoalDiff = direct_games(group, team, other)
+            .map(|game| {
+                let goal_diff = game.score.home - game.score.away;
+                if game.home == team {
+                    goal_diff
+                } else {
+                    -goal_diff
+                }
+            })
+            .sum();
+        goal_diff.cmp(&GoalDiff::zero())
+    }
+}
+
+/// Away goals in the games between the two teams
+#[derive(Debug, Clone, Copy)]
+pub struct DirectAwayGoals;
+
+impl BinaryStat for DirectAwayGoals {
+    fn compare(group: &GroupView, team: TeamId, other: TeamId) -> Ordering {
+        let away_goals = |id: TeamId| -> u32 {
+            direct_games(group, team, other)
+                .filter(|game| game.away == id)
+                .map(|game| u32::from(game.score.away))
+                .sum()
+        };
+        away_goals(team).cmp(&away_goals(other))
+    }
+}
+
+/// Group points awarded for the outcome of a game
+///
+/// Used as the parameter of the [`Points`] stat.
+/// Schemes which depend on more than the outcome, e.g. bonus points, override
+/// [`PointScheme::points`], see [`GoalBonus`] and [`LosingBonus`].
+pub trait PointScheme {
+    const WIN: GroupPoint;
+    const DRAW: GroupPoint;
+    const LOSS: GroupPoint;
+
+    /// Points for the home and away team
+    fn points(score: Score) -> (GroupPoint, GroupPoint) {
+        let points = |outcome| match outcome {
+            Outcome::Win => Self::WIN,
+            Outcome::Draw => Self::DRAW,
+            Outcome::Lose => Self::LOSS,
+        };
+        (points(score.home_outcome()), points(score.away_outcome()))
+    }
+}
+
+/// Three points for a win, one for a draw, the standard since the 1994 World Cup
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ThreePoints;
+
+impl PointScheme for ThreePoints {
+    const WIN: GroupPoint = GroupPoint(3);
+    const DRAW: GroupPoint = GroupPoint(1);
+    const LOSS: GroupPoint = GroupPoint(0);
+}
+
+/// Two points for a win, one for a draw, the standard up until the 1994 World Cup
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct TwoPoints;
+
+impl PointScheme for TwoPoints {
+    const WIN: GroupPoint = GroupPoint(2);
+    const DRAW: GroupPoint = GroupPoint(1);
+    const LOSS: GroupPoint = GroupPoint(0);
+}
+
+/// Bonus point variant of the scheme `S`
+///
+/// One extra point for every team scoring at least `GOALS` goals in a game, win or lose.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct GoalBonus<S, const GOALS: u32>(PhantomData<S>);
+
+impl<S: PointScheme, const GOALS: u32> PointScheme for GoalBonus<S, GOALS> {
+    const WIN: GroupPoint = S::WIN;
+    const DRAW: GroupPoint = S::DRAW;
+    const LOSS: GroupPoint = S::LOSS;
+
+    fn points(score: Score) -> (GroupPoint, GroupPoint) {
+        let (home, away) = S::points(score);
+        let bonus = |goals: GoalCount| GroupPoint((u32::from(goals) >= GOALS) as u8);
+        (home + bonus(score.home), away + bonus(score.away))
+    }
+}
+
+/// Bonus point variant of the scheme `S`
+///
+/// One point for a team losing by at most `MARGIN` goals, like the losing bonus in rugby.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct LosingBonus<S, const MARGIN: u32>(PhantomData<S>);
+
+impl<S: PointScheme, const MARGIN: u32> PointScheme for LosingBonus<S, MARGIN> {
+    const WIN: GroupPoint = S::WIN;
+    const DRAW: GroupPoint = S::DRAW;
+    const LOSS: GroupPoint = S::LOSS;
+
+    fn points(score: Score) -> (GroupPoint, GroupPoint) {
+        let (home, away) = S::points(score);
+        let bonus = GroupPoint((margin(score) <= MARGIN) as u8);
+        match score.home_outcome() {
+            Outcome::Win => (home, away + bonus),
+            Outcome::Lose => (home + bonus, away),
+            Outcome::Draw => (home, away),
+        }
+    }
+}
+
+/// Group points under a [`PointScheme`]
+///
+/// The [`GroupPoint`] stat itself uses [`ThreePoints`].
+///
+/// ```
+/// # use wwc_core::group::stats::{Points, TwoPoints, UnaryStat};
+/// # use wwc_core::group::game::UnplayedGroupGame;
+/// # use wwc_core::game::Score;
+/// # use wwc_core::group::GroupPoint;
+/// # use wwc_core::Date;
+/// # use wwc_core::fair_play::FairPlayScore;
+/// let game = UnplayedGroupGame::try_new(0, 1, 2, Date::mock())
+///     .unwrap()
+///     .play(Score::from((1, 0)), FairPlayScore::default());
+/// let (home, away) = Points::<TwoPoints>::stat(&game);
+/// assert_eq!(home.points, GroupPoint(2));
+/// assert_eq!(away.points, GroupPoint(0));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Points<S> {
+    pub points: GroupPoint,
+    scheme: PhantomData<S>,
+}
+
+impl<S> From<GroupPoint> for Points<S> {
+    fn from(points: GroupPoint) -> Self {
+        Points {
+            points,
+            scheme: PhantomData,
+        }
+    }
+}
+
+impl<S: PointScheme> UnaryStat for Points<S> {
+    fn stat(game: &PlayedGroupGame) -> (Self, Self) {
+        let (home, away) = S::points(game.score);
+        (Points::from(home), Points::from(away))
+    }
+}
+
+impl<S> ops::Add for Points<S> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Points::from(self.points + other.points)
+    }
+}
+
+impl<S> ops::AddAssign for Points<S> {
+    fn add_assign(&mut self, other: Self) {
+        self.points += other.points;
+    }
+}
+
+impl<S> num::Zero for Points<S> {
+    fn zero() -> Self {
+        Points::from(GroupPoint::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.points.is_zero()
+    }
+}
+
+impl<S> fmt::Display for Points<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.points)
+    }
+}
+
 impl UnaryStat for GroupPoint {
     /// Group points from played game.
     ///
+    /// Always [`ThreePoints`], other schemes are ordered by the [`Points`] stat.
+    ///
     /// ```
     /// # use wwc_core::group::stats::UnaryStat;
     /// # use wwc_core::group::game::{UnplayedGroupGame};
@@ -105,12 +441,7 @@ impl UnaryStat for GroupPoint {
     /// assert_eq!(away, GroupPoint(0));
     /// ```
     fn stat(game: &PlayedGroupGame) -> (Self, Self) {
-        let score = &game.score;
-        match score.home.cmp(&score.away) {
-            Ordering::Greater => (GroupPoint(3), GroupPoint(0)),
-            Ordering::Less => (GroupPoint(0), GroupPoint(3)),
-            Ordering::Equal => (GroupPoint(1), GroupPoint(1)),
-        }
+        ThreePoints::points(game.score)
     }
 }
 
@@ -139,26 +470,19 @@ where
     }
 }
 
-#[derive(Add, AddAssign, Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Copy)]
-pub struct NumWins(NumGames);
-
-impl UnaryStat for NumWins {
-    fn stat(game: &PlayedGroupGame) -> (Self, Self) {
-        let (points_home, points_away) = GroupPoint::stat(game);
-        let wins_home = NumWins(NumGames((points_home == GroupPoint(3)) as u32));
-        let wins_away = NumWins(NumGames((points_away == GroupPoint(3)) as u32));
-        (wins_home, wins_away)
-    }
+unary_stat! {
+    pub struct NumWins(NumGames);
+    |game| (
+        count_outcome(game.score.home_outcome(), Outcome::Win),
+        count_outcome(game.score.away_outcome(), Outcome::Win),
+    )
 }
 
-impl num::Zero for NumWins {
-    fn zero() -> Self {
-        NumWins(NumGames::zero())
-    }
-
-    fn is_zero(&self) -> bool {
-        self.0.is_zero()
-    }
+/// One game if `outcome` is `counted`, otherwise zero
+///
+/// Counted from the score rather than the points, which depend on the [`PointScheme`].
+fn count_outcome(outcome: Outcome, counted: Outcome) -> NumGames {
+    NumGames((outcome == counted) as u32)
 }
 
 ///Convenience struct for combining all common stats
@@ -214,10 +538,10 @@ impl UnaryStat for TableStats {
         let (goals_scored_home, goals_scored_away) = GoalCount::stat(game);
         let (fair_play_home, fair_play_away) = FifaFairPlayValue::stat(game);
         let (wins_home, wins_away) = NumWins::stat(game);
-        let losses_home = NumGames((points_home == GroupPoint(0)) as u32);
-        let draws_home = NumGames((points_home == GroupPoint(1)) as u32);
-        let losses_away = NumGames((points_away == GroupPoint(0)) as u32);
-        let draws_away = NumGames((points_away == GroupPoint(1)) as u32);
+        let losses_home = count_outcome(game.score.home_outcome(), Outcome::Lose);
+        let draws_home = count_outcome(game.score.home_outcome(), Outcome::Draw);
+        let losses_away = count_outcome(game.score.away_outcome(), Outcome::Lose);
+        let draws_away = count_outcome(game.score.away_outcome(), Outcome::Draw);
         let home = TableStats::new(
             points_home,
             goals_scored_home,
@@ -278,6 +602,79 @@ impl std::fmt::Display for TableStats {
     }
 }
 
+/// Notable game, used in [`FunStats`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub struct GameHighlight {
+    pub id: GameId,
+    pub home: TeamId,
+    pub away: TeamId,
+    pub score: Score,
+}
+
+impl From<&PlayedGroupGame> for GameHighlight {
+    fn from(game: &PlayedGroupGame) -> Self {
+        GameHighlight {
+            id: game.id,
+            home: game.home,
+            away: game.away,
+            score: game.score,
+        }
+    }
+}
+
+/// Trivia statistics for a group
+///
+/// Not used for ordering, only for presentation.
+/// If several games share the largest margin (or the most goals), the first played game is kept.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FunStats {
+    /// Game with the largest goal margin, `None` if no game has a winner
+    pub biggest_win: Option<GameHighlight>,
+    /// Game with the most goals in total, `None` if no goals are scored
+    pub most_goals: Option<GameHighlight>,
+    /// Number of games without conceding a goal, for every team in the group
+    pub clean_sheets: HashMap<TeamId, NumGames>,
+}
+
+/// Calculate [`FunStats`] for a group
+///
+/// All stats are calculated in a single pass over the played games.
+pub fn fun_stats(group: &Group) -> FunStats {
+    let init = FunStats {
+        biggest_win: None,
+        most_goals: None,
+        clean_sheets: group.teams().map(|team| (team, NumGames::zero())).collect(),
+    };
+    group.played_games().fold(init, |mut acc, game| {
+        if margin(game.score) > acc.biggest_win.map_or(0, |best| margin(best.score)) {
+            acc.biggest_win = Some(GameHighlight::from(game));
+        }
+        if total_goals(game.score) > acc.most_goals.map_or(0, |best| total_goals(best.score)) {
+            acc.most_goals = Some(GameHighlight::from(game));
+        }
+        if game.score.away.is_zero() {
+            *acc.clean_sheets
+                .entry(game.home)
+                .or_insert_with(NumGames::zero) += NumGames(1);
+        }
+        if game.score.home.is_zero() {
+            *acc.clean_sheets
+                .entry(game.away)
+                .or_insert_with(NumGames::zero) += NumGames(1);
+        }
+        acc
+    })
+}
+
+fn margin(score: Score) -> u32 {
+    let (home, away) = (u32::from(score.home), u32::from(score.away));
+    home.max(away) - home.min(away)
+}
+
+fn total_goals(score: Score) -> u32 {
+    u32::from(score.home) + u32::from(score.away)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -304,6 +701,26 @@ mod tests {
             .collect();
         assert_eq!(truth, stat_teams);
     }
+    #[test]
+    fn internal_games() {
+        let (groups, _) = mock_data();
+        let group_a = groups.get(&GroupId::from('A')).unwrap();
+        let teams = vec![TeamId::from(1), TeamId::from(2), TeamId::from(3)];
+        let games = InternalGames::new(group_a, &teams);
+        assert_eq!(games.games().count(), 1);
+        let filter: HashSet<&TeamId> = teams.iter().collect();
+        assert_eq!(
+            TableStats::internal_stats(&games),
+            TableStats::internal_team_stats(group_a, &filter)
+        );
+        let games = InternalGames::new(group_a, &teams[1..]);
+        assert_eq!(games.games().count(), 0);
+        assert_eq!(
+            GroupPoint::internal_stats(&games)[&TeamId::from(2)],
+            GroupPoint(0)
+        );
+    }
+
     #[test]
     fn mock_teams_stats() {
         let (groups, _) = mock_data();
@@ -315,4 +732,56 @@ mod tests {
         truth.insert(TeamId::from(4), TableStats::zero());
         assert_eq!(truth, TableStats::team_stats(group_a));
     }
+
+    #[test]
+    fn bonus_points() {
+        type Bonus = LosingBonus<GoalBonus<ThreePoints, 3>, 1>;
+        assert_eq!(
+            Bonus::points(Score::from((3, 2))),
+            (GroupPoint(4), GroupPoint(1))
+        );
+        assert_eq!(
+            Bonus::points(Score::from((0, 4))),
+            (GroupPoint(0), GroupPoint(4))
+        );
+        assert_eq!(
+            Bonus::points(Score::from((3, 3))),
+            (GroupPoint(2), GroupPoint(2))
+        );
+        assert_eq!(
+            TwoPoints::points(Score::from((2, 1))),
+            (GroupPoint(2), GroupPoint(0))
+        );
+        let game = UnplayedGroupGame::try_new(1, 1, 2, Date::mock())
+            .unwrap()
+            .play(Score::from((2, 1)), FairPlayScore::default());
+        let (home, away) = Points::<Bonus>::stat(&game);
+        assert_eq!((home.points, away.points), (GroupPoint(3), GroupPoint(1)));
+        assert_eq!(
+            NumWins::stat(&game),
+            (NumWins(NumGames(1)), NumWins(NumGames(0)))
+        );
+    }
+
+    #[test]
+    fn fun_stats_single_pass() {
+        let games = vec![
+            UnplayedGroupGame::try_new(1, 1, 2, Date::mock())
+                .unwrap()
+                .play(Score::from((3, 0)), FairPlayScore::default()),
+            UnplayedGroupGame::try_new(2, 3, 4, Date::mock())
+                .unwrap()
+                .play(Score::from((3, 2)), FairPlayScore::default()),
+            UnplayedGroupGame::try_new(3, 1, 3, Date::mock())
+                .unwrap()
+                .play(Score::from((0, 0)), FairPlayScore::default()),
+        ];
+        let group = Group::try_new(vec![], games).unwrap();
+        let stats = fun_stats(&group);
+        assert_eq!(stats.biggest_win.unwrap().id, GameId::from(1));
+        assert_eq!(stats.most_goals.unwrap().id, GameId::from(2));
+        assert_eq!(stats.clean_sheets[&TeamId::from(1)], NumGames(2));
+        assert_eq!(stats.clean_sheets[&TeamId::from(3)], NumGames(1));
+        assert_eq!(stats.clean_sheets[&TeamId::from(4)], NumGames(0));
+    }
 }
@@ -0,0 +1,145 @@
+//! Borrowed group views
+//!
+//! Ordering a group only reads its games. A [`GroupView`] borrows a group together with
+//! hypothetical results for some of its unplayed games, e.g. the sampled scores of a simulation,
+//! so that the group can be ordered as if those games were played, without cloning the group.
+//!
+//! [`order_group`](crate::group::order_group) and
+//! [`UnaryStat::team_stats`](crate::group::stats::UnaryStat::team_stats) accept a `&Group` as
+//! well as a view.
+use crate::group::game::{GroupGame, PlayedGroupGame, UnplayedGroupGame};
+use crate::group::Group;
+use crate::team::TeamId;
+use itertools::Itertools;
+
+/// A group with hypothetical results appended, see the [module docs](self)
+#[derive(Debug, Clone, Copy)]
+pub struct GroupView<'a> {
+    group: &'a Group,
+    /// Results of unplayed games of `group`
+    hypothetical: &'a [PlayedGroupGame],
+}
+
+impl<'a> GroupView<'a> {
+    /// View of the group as it is
+    pub fn new(group: &'a Group) -> Self {
+        GroupView {
+            group,
+            hypothetical: &[],
+        }
+    }
+
+    /// View of the group with the `hypothetical` results added to the played games
+    ///
+    /// Every hypothetical game must be an unplayed game of the group, given at most once.
+    /// This is only checked in debug builds, the view is meant for hot loops.
+    pub fn with_results(group: &'a Group, hypothetical: &'a [PlayedGroupGame]) -> Self {
+        debug_assert!(
+            hypothetical.iter().all(|game| group
+                .unplayed_games()
+                .any(|unplayed| unplayed.id == game.id)),
+            "Hypothetical result for a game which is not unplayed in the group"
+        );
+        debug_assert!(
+            hypothetical.iter().map(|game| game.id).all_unique(),
+            "Several hypothetical results for the same game"
+        );
+        GroupView {
+            group,
+            hypothetical,
+        }
+    }
+
+    /// The underlying group, without the hypothetical results
+    pub fn group(&self) -> &'a Group {
+        self.group
+    }
+
+    /// Teams in the group, see [`Group::teams`]
+    ///
+    /// The hypothetical games are games of the group, they never add a team.
+    pub fn teams(&self) -> impl Iterator<Item = TeamId> + 'a {
+        self.group.teams()
+    }
+
+    /// Check if a team plays in the group
+    pub fn contains(&self, team: TeamId) -> bool {
+        self.group.contains(team)
+    }
+
+    /// Played games, including the hypothetical results
+    pub fn played_games(&self) -> impl Iterator<Item = &'a PlayedGroupGame> + 'a {
+        self.group.played_games().chain(self.hypothetical.iter())
+    }
+
+    /// Unplayed games without a hypothetical result
+    pub fn unplayed_games(&self) -> impl Iterator<Item = &'a UnplayedGroupGame> + 'a {
+        let hypothetical = self.hypothetical;
+        self.group
+            .unplayed_games()
+            .filter(move |game| !hypothetical.iter().any(|played| played.id == game.id))
+    }
+
+    /// All games as in the view, see [`Group::games`]
+    pub fn games(&self) -> impl Iterator<Item = GroupGame<'a>> + 'a {
+        self.played_games()
+            .map(GroupGame::Played)
+            .chain(self.unplayed_games().map(GroupGame::Unplayed))
+            .chain(self.group.void_games().map(GroupGame::Void))
+            .sorted_by_key(|game| (game.date(), game.id()))
+    }
+}
+
+impl<'a> From<&'a Group> for GroupView<'a> {
+    fn from(group: &'a Group) -> Self {
+        GroupView::new(group)
+    }
+}
+
+impl<'a> From<&GroupView<'a>> for GroupView<'a> {
+    fn from(view: &GroupView<'a>) -> Self {
+        *view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::game::{GameId, Score};
+    use crate::group::mock_unplayed_group;
+    use crate::group::order::{fifa_2018, order_group, GroupRank};
+    use crate::group::stats::UnaryStat;
+    use crate::group::GroupPoint;
+
+    #[test]
+    fn same_as_played() {
+        let group = mock_unplayed_group();
+        let results = [(0, (0, 1)), (2, (3, 0))];
+        let hypothetical: Vec<PlayedGroupGame> = group
+            .unplayed_games()
+            .filter_map(|game| {
+                results
+                    .iter()
+                    .find(|(id, _)| GameId::from(*id) == game.id)
+                    .map(|(_, score)| game.play(Score::from(*score), FairPlayScore::default()))
+            })
+            .collect();
+        let view = GroupView::with_results(&group, &hypothetical);
+        let mut played = group.clone();
+        for (id, score) in results.iter() {
+            played.play_game(GameId::from(*id), Score::from(*score));
+        }
+
+        assert_eq!(view.played_games().count(), 2);
+        assert_eq!(view.unplayed_games().count(), 2);
+        assert_eq!(group.played_games().count(), 0);
+        assert_eq!(
+            GroupPoint::team_stats(view),
+            GroupPoint::team_stats(&played)
+        );
+        let rules = fifa_2018();
+        assert_eq!(order_group(view, &rules), order_group(&played, &rules));
+        assert_eq!(order_group(view, &rules)[GroupRank(0)], TeamId(0));
+    }
+}
@@ -0,0 +1,192 @@
+//! Team withdrawal
+//!
+//! A team withdrawing from a group in the middle of the group stage leaves its games behind.
+//! What becomes of them is decided by the [`WithdrawalPolicy`] of the tournament, see
+//! [`withdraw`].
+//! The games are changed with the existing statuses, so the standings follow from the games as
+//! always: annulled games do not count, the remaining games can be forfeited, i.e. played with
+//! a [`FORFEIT_GOALS`]-0 result for the opponent.
+use crate::game::{Game, GameId, Score};
+use crate::group::{Group, GroupError};
+use crate::team::TeamId;
+use serde::{Deserialize, Serialize};
+
+/// Goals of the opponent of a withdrawn team in a forfeited game, 3-0 by FIFA and UEFA
+/// regulations
+pub const FORFEIT_GOALS: u32 = 3;
+
+/// What becomes of the games of a team withdrawing from a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WithdrawalPolicy {
+    /// Every game of the team is annulled, played or not, as if the team never took part.
+    /// The World Cup regulations.
+    AnnulAll,
+    /// The played games stand, the remaining games are forfeited
+    ForfeitRemaining,
+    /// Every game is annulled if the team played fewer than half of its games, otherwise the
+    /// played games stand and the remaining games are forfeited.
+    /// The UEFA qualifying regulations.
+    HalfPlayed,
+}
+
+/// Annul every game, see [`WithdrawalPolicy::AnnulAll`]
+impl Default for WithdrawalPolicy {
+    fn default() -> Self {
+        WithdrawalPolicy::AnnulAll
+    }
+}
+
+/// New state of a game of a withdrawn team
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum WithdrawalChange {
+    Annulled {
+        game: GameId,
+    },
+    /// Played, with the result awarded to the opponent
+    Forfeited {
+        game: GameId,
+        score: Score,
+    },
+}
+
+impl WithdrawalChange {
+    pub fn game(&self) -> GameId {
+        match self {
+            WithdrawalChange::Annulled { game } | WithdrawalChange::Forfeited { game, .. } => *game,
+        }
+    }
+}
+
+/// Changes to the games of a group when `team` withdraws, by `policy`
+///
+/// Games already abandoned or annulled are left as they are.
+/// Sorted by game id.
+///
+/// # Errors
+///
+/// Fails with [`GroupError::TeamNotInGroup`] if the team does not play in the group.
+pub fn withdraw(
+    group: &Group,
+    team: TeamId,
+    policy: WithdrawalPolicy,
+) -> Result<Vec<WithdrawalChange>, GroupError> {
+    if !group.contains(team) {
+        return Err(GroupError::TeamNotInGroup(team));
+    }
+    let plays = |home: TeamId, away: TeamId| home == team || away == team;
+    let played: Vec<GameId> = group
+        .played_games()
+        .filter(|game| plays(game.home, game.away))
+        .map(|game| game.id)
+        .collect();
+    let unplayed: Vec<(GameId, bool)> = group
+        .unplayed_games()
+        .filter(|game| plays(game.home, game.away))
+        .map(|game| (game.id, game.home_team() == team))
+        .collect();
+    let annul_all = match policy {
+        WithdrawalPolicy::AnnulAll => true,
+        WithdrawalPolicy::ForfeitRemaining => false,
+        WithdrawalPolicy::HalfPlayed => 2 * played.len() < played.len() + unplayed.len(),
+    };
+    let mut changes: Vec<WithdrawalChange> = if annul_all {
+        played
+            .into_iter()
+            .chain(unplayed.into_iter().map(|(game, _)| game))
+            .map(|game| WithdrawalChange::Annulled { game })
+            .collect()
+    } else {
+        unplayed
+            .into_iter()
+            .map(|(game, withdrawn_home)| {
+                let score = if withdrawn_home {
+                    Score::new(0, FORFEIT_GOALS)
+                } else {
+                    Score::new(FORFEIT_GOALS, 0)
+                };
+                WithdrawalChange::Forfeited { game, score }
+            })
+            .collect()
+    };
+    changes.sort_by_key(WithdrawalChange::game);
+    Ok(changes)
+}
+
+#[cfg(all(test, feature = "tournament"))]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::group::game::UnplayedGroupGame;
+    use crate::stage::round_robin;
+    use crate::Date;
+
+    /// Round robin of teams 1 to 4, the first `num_played` games won by the home team
+    fn group(num_played: usize) -> Group {
+        let teams: Vec<TeamId> = (1..=4).map(TeamId).collect();
+        let games = round_robin(&teams, 1, GameId::from(0), |_| Date::mock()).unwrap();
+        let (played, unplayed) = games.split_at(num_played);
+        let played = played
+            .iter()
+            .cloned()
+            .map(|game: UnplayedGroupGame| game.play(Score::new(1, 0), FairPlayScore::default()))
+            .collect();
+        Group::try_new(unplayed.to_vec(), played).unwrap()
+    }
+
+    fn annulled(changes: &[WithdrawalChange]) -> usize {
+        changes
+            .iter()
+            .filter(|change| matches!(change, WithdrawalChange::Annulled { .. }))
+            .count()
+    }
+
+    #[test]
+    fn policies() {
+        // Team 1 has played one of three games after two games
+        let group = group(2);
+        let changes = withdraw(&group, TeamId(1), WithdrawalPolicy::AnnulAll).unwrap();
+        assert_eq!((changes.len(), annulled(&changes)), (3, 3));
+        let changes = withdraw(&group, TeamId(1), WithdrawalPolicy::HalfPlayed).unwrap();
+        assert_eq!((changes.len(), annulled(&changes)), (3, 3));
+        let changes = withdraw(&group, TeamId(1), WithdrawalPolicy::ForfeitRemaining).unwrap();
+        assert_eq!((changes.len(), annulled(&changes)), (2, 0));
+        for change in changes {
+            let game = group
+                .unplayed_games()
+                .find(|game| game.id == change.game())
+                .unwrap();
+            let expected = if game.home == TeamId(1) {
+                Score::new(0, 3)
+            } else {
+                Score::new(3, 0)
+            };
+            assert_eq!(
+                change,
+                WithdrawalChange::Forfeited {
+                    game: game.id,
+                    score: expected
+                }
+            );
+        }
+        assert!(matches!(
+            withdraw(&group, TeamId(9), WithdrawalPolicy::AnnulAll),
+            Err(GroupError::TeamNotInGroup(TeamId(9)))
+        ));
+    }
+
+    #[test]
+    fn half_played() {
+        // Every team has played two of three games after four games
+        let group = group(4);
+        let changes = withdraw(&group, TeamId(2), WithdrawalPolicy::HalfPlayed).unwrap();
+        assert_eq!((changes.len(), annulled(&changes)), (1, 0));
+        // Only the withdrawn team's games change
+        let changed = group
+            .games()
+            .find(|game| game.id() == changes[0].game())
+            .unwrap();
+        assert!(changed.home_team() == TeamId(2) || changed.away_team() == TeamId(2));
+    }
+}
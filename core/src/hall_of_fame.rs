@@ -0,0 +1,181 @@
+//! Hall of fame
+//!
+//! Leaderboard across tournaments, for pools played every tournament.
+//! Points are not comparable between tournaments, e.g. with a different number of games or
+//! different scoring rules, so the points of a tournament are normalized by the points of its
+//! winner: the winner gets 1.0 and a player with half the points of the winner 0.5.
+//! The normalized points are then aggregated over the tournaments, see [`Aggregation`].
+//!
+//! Players are identified across tournaments by their [`PlayerId`].
+use crate::player::PlayerId;
+use crate::pred_score::PredScore;
+use derive_more::{Display, From, Into};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Numeric tournament id for db.
+#[derive(Display, Debug, Copy, Clone, From, Into, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct TournamentId(i32);
+
+/// Final points of a player in a tournament
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TournamentResult {
+    pub tournament: TournamentId,
+    pub player: PlayerId,
+    pub points: PredScore,
+}
+
+/// Aggregation of the normalized points of a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    /// Sum over the tournaments, rewards taking part in many tournaments
+    Sum,
+    /// Average over the tournaments the player took part in
+    Average,
+}
+
+impl FromStr for Aggregation {
+    type Err = AggregationError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(Aggregation::Sum),
+            "average" => Ok(Aggregation::Average),
+            _ => Err(AggregationError(String::from(s))),
+        }
+    }
+}
+
+/// Hall of fame entry of a player
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct HallOfFameEntry {
+    pub player: PlayerId,
+    /// Number of tournaments the player took part in
+    pub tournaments: usize,
+    /// Number of tournaments won, including shared wins
+    pub wins: usize,
+    /// Aggregated normalized points
+    pub score: f32,
+}
+
+/// Aggregate the results of several tournaments
+///
+/// In a tournament where nobody got any points, every player gets zero normalized points and
+/// nobody wins.
+///
+/// Sorted by score, best first, equal scores by the number of wins.
+pub fn hall_of_fame(
+    results: &[TournamentResult],
+    aggregation: Aggregation,
+) -> Vec<HallOfFameEntry> {
+    let normalized: Vec<(PlayerId, f32, bool)> = results
+        .iter()
+        .into_group_map_by(|result| result.tournament)
+        .into_values()
+        .flat_map(|results| {
+            let best = results
+                .iter()
+                .map(|result| f32::from(result.points))
+                .fold(0.0, f32::max);
+            results.into_iter().map(move |result| {
+                let points = f32::from(result.points);
+                if best > 0.0 {
+                    (result.player, points / best, points >= best)
+                } else {
+                    (result.player, 0.0, false)
+                }
+            })
+        })
+        .collect();
+    normalized
+        .into_iter()
+        .into_group_map_by(|(player, _, _)| *player)
+        .into_iter()
+        .map(|(player, results)| {
+            let total: f32 = results.iter().map(|(_, points, _)| points).sum();
+            let tournaments = results.len();
+            HallOfFameEntry {
+                player,
+                tournaments,
+                wins: results.iter().filter(|(_, _, won)| *won).count(),
+                score: match aggregation {
+                    Aggregation::Sum => total,
+                    Aggregation::Average => total / tournaments as f32,
+                },
+            }
+        })
+        .sorted_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.wins.cmp(&a.wins))
+                .then(i32::from(a.player).cmp(&i32::from(b.player)))
+        })
+        .collect()
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Unknown aggregation '{0}', expected 'sum' or 'average'")]
+pub struct AggregationError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn result(tournament: i32, player: i32, points: f32) -> TournamentResult {
+        TournamentResult {
+            tournament: TournamentId::from(tournament),
+            player: PlayerId::from(player),
+            points: PredScore::from(points),
+        }
+    }
+
+    /// Player 1 wins the first tournament, player 2 the second, player 3 only plays the second
+    fn results() -> Vec<TournamentResult> {
+        vec![
+            result(1, 1, 40.0),
+            result(1, 2, 20.0),
+            result(2, 1, 15.0),
+            result(2, 2, 30.0),
+            result(2, 3, 27.0),
+        ]
+    }
+
+    #[test]
+    fn sum() {
+        let entries = hall_of_fame(&results(), Aggregation::Sum);
+        let players: Vec<i32> = entries
+            .iter()
+            .map(|entry| i32::from(entry.player))
+            .collect();
+        // Equal scores and wins, by player id
+        assert_eq!(players, vec![1, 2, 3]);
+        assert_approx_eq!(entries[0].score, 1.5);
+        assert_approx_eq!(entries[1].score, 1.5);
+        assert_eq!((entries[0].wins, entries[0].tournaments), (1, 2));
+        assert_approx_eq!(entries[2].score, 0.9);
+    }
+
+    #[test]
+    fn average() {
+        let entries = hall_of_fame(&results(), Aggregation::Average);
+        let players: Vec<i32> = entries
+            .iter()
+            .map(|entry| i32::from(entry.player))
+            .collect();
+        assert_eq!(players, vec![3, 1, 2]);
+        assert_approx_eq!(entries[0].score, 0.9);
+        assert_approx_eq!(entries[1].score, 0.75);
+    }
+
+    #[test]
+    fn no_points() {
+        let entries = hall_of_fame(&[result(1, 1, 0.0), result(1, 2, 0.0)], Aggregation::Sum);
+        assert!(entries
+            .iter()
+            .all(|entry| entry.wins == 0 && entry.score == 0.0));
+    }
+}
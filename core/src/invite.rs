@@ -0,0 +1,99 @@
+//! Invites
+//!
+//! Keeps a pool private without the admin creating every account by hand: the admin of a league
+//! hands out invites and new players register themselves with the token of an invite,
+//! joining the league of the invite.
+//! An invite expires after a while and can only be used once.
+use crate::error::ErrorCode;
+use crate::player::PlayerId;
+use crate::Date;
+use derive_more::{Display, From, Into};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Validity of a new invite, unless given: one week
+pub const DEFAULT_VALIDITY_HOURS: u32 = 7 * 24;
+
+/// Secret token of an invite
+#[derive(Display, Debug, Clone, From, Into, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct InviteToken(String);
+
+impl InviteToken {
+    /// New token, 128 random bits as hex
+    #[cfg(feature = "random")]
+    pub fn random() -> Self {
+        use rand::Rng;
+        InviteToken(format!("{:032x}", rand::thread_rng().gen::<u128>()))
+    }
+}
+
+/// Invite to a league
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Invite {
+    pub token: InviteToken,
+    /// Id of the league the new player joins
+    pub league: i32,
+    pub expires_at: Date,
+    /// The player registered with the invite, `None` until used
+    pub used_by: Option<PlayerId>,
+}
+
+impl Invite {
+    /// Check that the invite can be used at `now`
+    ///
+    /// # Errors
+    ///
+    /// The invite is already used or expired.
+    pub fn check(&self, now: Date) -> Result<(), InviteError> {
+        if self.used_by.is_some() {
+            Err(InviteError::Used)
+        } else if !now.is_before(self.expires_at) {
+            Err(InviteError::Expired(self.expires_at))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum InviteError {
+    #[error("Unknown invite")]
+    Unknown,
+    #[error("Invite expired at {0}")]
+    Expired(Date),
+    #[error("Invite already used")]
+    Used,
+}
+
+impl ErrorCode for InviteError {
+    fn code(&self) -> &'static str {
+        match self {
+            InviteError::Unknown => "UNKNOWN_INVITE",
+            InviteError::Expired(_) => "INVITE_EXPIRED",
+            InviteError::Used => "INVITE_USED",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_use_until_expiry() {
+        let now: Date = "2021-06-01T12:00:00+00:00".parse().unwrap();
+        let mut invite = Invite {
+            token: InviteToken::from(String::from("secret")),
+            league: 1,
+            expires_at: now.add_hours(DEFAULT_VALIDITY_HOURS),
+            used_by: None,
+        };
+        assert_eq!(invite.check(now), Ok(()));
+        assert_eq!(
+            invite.check(now.add_hours(DEFAULT_VALIDITY_HOURS)),
+            Err(InviteError::Expired(invite.expires_at))
+        );
+        invite.used_by = Some(PlayerId::from(1));
+        assert_eq!(invite.check(now), Err(InviteError::Used));
+    }
+}
@@ -0,0 +1,271 @@
+//! Joker games
+//!
+//! A player may mark one game per matchday as a joker,
+//! the prediction for that game is worth [`JOKER_MULTIPLIER`] times the usual points.
+//! Like predictions, a joker can be set or cleared up until the kickoff of the game.
+//! A rescheduled game can land on a matchday with another joker of the player, the joker of the
+//! rescheduled game is then dropped, see [`clashing_jokers`].
+use crate::error::ErrorCode;
+use crate::game::GameId;
+use crate::group::Groups;
+use crate::player::PlayerId;
+use crate::Date;
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// Points multiplier for a joker game
+pub const JOKER_MULTIPLIER: f32 = 2.0;
+
+/// Joker games of a single player
+pub type Jokers = HashSet<GameId>;
+
+/// Joker games of every player
+pub type PlayerJokers = HashMap<PlayerId, Jokers>;
+
+/// Points multiplier for the prediction of a game
+pub fn multiplier(jokers: &Jokers, game_id: GameId) -> f32 {
+    if jokers.contains(&game_id) {
+        JOKER_MULTIPLIER
+    } else {
+        1.0
+    }
+}
+
+/// Matchday of a date, the calendar day in UTC
+pub fn matchday(date: Date) -> NaiveDate {
+    date.utc().day()
+}
+
+/// Validate a new joker game
+///
+/// # Errors
+///
+/// The joker is rejected if
+///
+/// - the game is not a group game,
+/// - the game has already kicked off (at `now`),
+/// - another game on the same matchday is already a joker.
+///
+/// Setting an existing joker again is allowed.
+pub fn validate_joker(
+    game_id: GameId,
+    jokers: &Jokers,
+    groups: &Groups,
+    now: Date,
+) -> Result<(), JokerError> {
+    let date = kickoff_before_deadline(game_id, groups, now)?;
+    if jokers.contains(&game_id) {
+        return Ok(());
+    }
+    let kickoffs = kickoffs(groups);
+    match jokers
+        .iter()
+        .find(|joker| kickoffs.get(joker).map(|date| matchday(*date)) == Some(matchday(date)))
+    {
+        Some(joker) => Err(JokerError::MatchdayTaken(game_id, *joker)),
+        None => Ok(()),
+    }
+}
+
+/// Validate clearing a joker game
+///
+/// # Errors
+///
+/// Like setting, clearing is only allowed before the kickoff (at `now`),
+/// and only for an existing joker.
+pub fn validate_clear(
+    game_id: GameId,
+    jokers: &Jokers,
+    groups: &Groups,
+    now: Date,
+) -> Result<(), JokerError> {
+    if !jokers.contains(&game_id) {
+        return Err(JokerError::NotAJoker(game_id));
+    }
+    kickoff_before_deadline(game_id, groups, now).map(|_| ())
+}
+
+/// Players with a joker on `game_id` and another joker on the matchday of `kickoff`
+///
+/// Checked when the game is rescheduled to `kickoff`, the other jokers keep the kickoffs in
+/// `groups`.
+pub fn clashing_jokers(
+    game_id: GameId,
+    kickoff: Date,
+    player_jokers: &PlayerJokers,
+    groups: &Groups,
+) -> Vec<PlayerId> {
+    let kickoffs = kickoffs(groups);
+    player_jokers
+        .iter()
+        .filter(|(_, jokers)| jokers.contains(&game_id))
+        .filter(|(_, jokers)| {
+            jokers.iter().any(|joker| {
+                *joker != game_id
+                    && kickoffs.get(joker).map(|date| matchday(*date)) == Some(matchday(kickoff))
+            })
+        })
+        .map(|(player, _)| *player)
+        .collect()
+}
+
+/// Kickoff of an unplayed group game, if before `now`
+fn kickoff_before_deadline(
+    game_id: GameId,
+    groups: &Groups,
+    now: Date,
+) -> Result<Date, JokerError> {
+    let unplayed = groups
+        .values()
+        .flat_map(|group| group.unplayed_games())
+        .find(|game| game.id == game_id);
+    match unplayed {
+        Some(game) if game.is_before_kickoff(now) => Ok(game.date()),
+        Some(_) => Err(JokerError::DeadlinePassed(game_id)),
+        None if kickoffs(groups).contains_key(&game_id) => Err(JokerError::DeadlinePassed(game_id)),
+        None => Err(JokerError::UnknownGame(game_id)),
+    }
+}
+
+/// Kickoff of every group game, played or not
+fn kickoffs(groups: &Groups) -> HashMap<GameId, Date> {
+    groups
+        .values()
+        .flat_map(|group| group.games().map(|game| (game.id(), game.date())))
+        .collect()
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum JokerError {
+    #[error("No group game with id {0}")]
+    UnknownGame(GameId),
+    #[error("Deadline passed for game {0}")]
+    DeadlinePassed(GameId),
+    #[error("Game {0} is on the same matchday as joker game {1}")]
+    MatchdayTaken(GameId, GameId),
+    #[error("Game {0} is not a joker")]
+    NotAJoker(GameId),
+}
+
+impl ErrorCode for JokerError {
+    fn code(&self) -> &'static str {
+        match self {
+            JokerError::UnknownGame(_) => "UNKNOWN_GAME",
+            JokerError::DeadlinePassed(_) => "JOKER_DEADLINE_PASSED",
+            JokerError::MatchdayTaken(..) => "JOKER_MATCHDAY_TAKEN",
+            JokerError::NotAJoker(_) => "NOT_A_JOKER",
+        }
+    }
+
+    fn details(&self) -> Option<Value> {
+        Some(match self {
+            JokerError::MatchdayTaken(game, joker) => json!({ "game": game, "joker": joker }),
+            JokerError::UnknownGame(game)
+            | JokerError::DeadlinePassed(game)
+            | JokerError::NotAJoker(game) => json!({ "game": game }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::game::Score;
+    use crate::group::game::UnplayedGroupGame;
+    use crate::group::{Group, GroupId};
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    fn groups() -> Groups {
+        let game = |id: u32, home: u32, away: u32, kickoff: &str| {
+            UnplayedGroupGame::try_new(id, home, away, date(kickoff)).unwrap()
+        };
+        let played =
+            game(1, 1, 2, "2021-06-11 19:00").play(Score::new(1, 0), FairPlayScore::default());
+        let unplayed = vec![
+            game(2, 3, 4, "2021-06-11 21:00"),
+            game(3, 1, 3, "2021-06-12 19:00"),
+            game(4, 2, 4, "2021-06-12 21:00"),
+        ];
+        let group = Group::try_new(unplayed, vec![played]).unwrap();
+        vec![(GroupId::from('A'), group)].into_iter().collect()
+    }
+
+    #[test]
+    fn one_joker_per_matchday() {
+        let groups = groups();
+        let now = date("2021-06-11 20:00");
+        let jokers: Jokers = vec![GameId::from(3)].into_iter().collect();
+        assert_eq!(
+            validate_joker(GameId::from(2), &jokers, &groups, now),
+            Ok(())
+        );
+        assert_eq!(
+            validate_joker(GameId::from(3), &jokers, &groups, now),
+            Ok(())
+        );
+        assert_eq!(
+            validate_joker(GameId::from(4), &jokers, &groups, now),
+            Err(JokerError::MatchdayTaken(GameId::from(4), GameId::from(3)))
+        );
+        assert_eq!(
+            validate_joker(GameId::from(1), &Jokers::new(), &groups, now),
+            Err(JokerError::DeadlinePassed(GameId::from(1)))
+        );
+        assert_eq!(
+            validate_joker(GameId::from(5), &Jokers::new(), &groups, now),
+            Err(JokerError::UnknownGame(GameId::from(5)))
+        );
+    }
+
+    #[test]
+    fn rescheduled_joker() {
+        let groups = groups();
+        let player_jokers: PlayerJokers = vec![
+            (PlayerId::from(1), vec![GameId::from(2), GameId::from(3)]),
+            (PlayerId::from(2), vec![GameId::from(3)]),
+            (PlayerId::from(3), vec![GameId::from(2), GameId::from(4)]),
+        ]
+        .into_iter()
+        .map(|(player, jokers)| (player, jokers.into_iter().collect()))
+        .collect();
+        // Game 3 moves from the 12th to the 11th, the matchday of game 2
+        let clashes = clashing_jokers(
+            GameId::from(3),
+            date("2021-06-11 23:00"),
+            &player_jokers,
+            &groups,
+        );
+        assert_eq!(clashes, vec![PlayerId::from(1)]);
+        assert!(clashing_jokers(
+            GameId::from(3),
+            date("2021-06-13 19:00"),
+            &player_jokers,
+            &groups
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn clear_joker() {
+        let groups = groups();
+        let jokers: Jokers = vec![GameId::from(3)].into_iter().collect();
+        assert_eq!(
+            validate_clear(GameId::from(3), &jokers, &groups, date("2021-06-11 20:00")),
+            Ok(())
+        );
+        assert_eq!(
+            validate_clear(GameId::from(3), &jokers, &groups, date("2021-06-12 19:00")),
+            Err(JokerError::DeadlinePassed(GameId::from(3)))
+        );
+        assert_eq!(
+            validate_clear(GameId::from(2), &jokers, &groups, date("2021-06-11 20:00")),
+            Err(JokerError::NotAJoker(GameId::from(2)))
+        );
+    }
+}
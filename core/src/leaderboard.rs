@@ -0,0 +1,868 @@
+//! Leaderboard
+//!
+//! Standings in the betting pool.
+//! Like everything else, the points of a player are not stored but derived from the player's
+//! predictions and the played games, scored with a [`PredScoreFn`].
+//! The points for a joker game are multiplied, see [`joker`](crate::joker).
+//!
+//! Players on equal points are ordered by a list of [`Tiebreak`]s, configured in the scoring
+//! rules of a league or tournament, by default [`DEFAULT_TIEBREAKS`]: most exact scores, then
+//! earliest registration, see [`PredScoreFn::tiebreaks`].
+//!
+//! Players who join a league after the first kickoff are compensated for the games they missed
+//! by the [`LateJoiner`] policy of the league, see [`late_joiner_points`].
+//!
+//! Besides the total, the group stage and the knockout stage have leaderboards of their own,
+//! see [`ScoringStage`] and [`stage_standings`].
+use crate::game::{GameId, Score};
+use crate::joker::{multiplier, Jokers, PlayerJokers};
+use crate::player::{PlayerId, Prediction, MAX_PREDICTED_GOALS};
+use crate::playoff::bracket::BracketNode;
+use crate::playoff::prediction::PlayoffPrediction;
+use crate::playoff::RoundIdx;
+use crate::pred_score::{PredScore, PredScoreFn};
+use crate::Date;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Points from the predictions of played games
+///
+/// `results` maps game id's to final scores, predictions for other games are ignored.
+pub fn points<'a, S: PredScoreFn>(
+    preds: impl IntoIterator<Item = &'a Prediction>,
+    jokers: &Jokers,
+    results: &HashMap<GameId, Score>,
+    score_fn: &S,
+) -> PredScore {
+    preds
+        .into_iter()
+        .filter_map(|Prediction(id, pred)| {
+            results.get(id).map(|truth| {
+                PredScore::from(
+                    f32::from(score_fn.game_score(*id, *pred, *truth)) * multiplier(jokers, *id),
+                )
+            })
+        })
+        .fold(PredScore::default(), |acc, score| acc + score)
+}
+
+/// Number of exactly predicted scores among the played games
+///
+/// Jokers do not count twice.
+pub fn exact_scores<'a>(
+    preds: impl IntoIterator<Item = &'a Prediction>,
+    results: &HashMap<GameId, Score>,
+) -> u32 {
+    preds
+        .into_iter()
+        .filter(|Prediction(id, pred)| results.get(id) == Some(pred))
+        .count() as u32
+}
+
+/// Criterion ordering players on equal points
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Tiebreak {
+    /// Most exactly predicted scores first
+    ExactScores,
+    /// Earliest registered player first, i.e. the lowest player id.
+    /// Never a tie between two players, so it makes the order strict.
+    Registration,
+}
+
+/// Most exact scores, then earliest registration
+pub const DEFAULT_TIEBREAKS: [Tiebreak; 2] = [Tiebreak::ExactScores, Tiebreak::Registration];
+
+impl Tiebreak {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tiebreak::ExactScores => "exact scores",
+            Tiebreak::Registration => "registration",
+        }
+    }
+
+    /// Order of two standings, `Less` if `a` ranks above `b`
+    fn cmp(&self, a: &Standing, b: &Standing) -> Ordering {
+        match self {
+            Tiebreak::ExactScores => b.exact_scores.cmp(&a.exact_scores),
+            Tiebreak::Registration => i32::from(a.player).cmp(&i32::from(b.player)),
+        }
+    }
+}
+
+impl fmt::Display for Tiebreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Sort standings by points, best first, players on equal points by `tiebreaks`
+pub fn rank(standings: &mut [Standing], tiebreaks: &[Tiebreak]) {
+    standings.sort_by(|a, b| {
+        b.points
+            .partial_cmp(&a.points)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| {
+                tiebreaks
+                    .iter()
+                    .map(|tiebreak| tiebreak.cmp(a, b))
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+    });
+}
+
+/// The tiebreak deciding the order of two players on equal points
+///
+/// `None` if the players do not have equal points, or if no tiebreak separates them.
+pub fn decisive_tiebreak(a: &Standing, b: &Standing, tiebreaks: &[Tiebreak]) -> Option<Tiebreak> {
+    if a.points != b.points {
+        return None;
+    }
+    tiebreaks
+        .iter()
+        .find(|tiebreak| tiebreak.cmp(a, b) != Ordering::Equal)
+        .copied()
+}
+
+/// Current standing of a player
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Standing {
+    pub player: PlayerId,
+    /// Points from the played games, the compensation included
+    pub points: PredScore,
+    /// Points for the games missed by joining late, see [`late_joiner_points`]
+    #[serde(default)]
+    pub compensation: PredScore,
+    /// Exactly predicted scores, the first tiebreak, see [`exact_scores`]
+    pub exact_scores: u32,
+    /// Upper bound for the final points
+    pub max_points: PredScore,
+    /// The player can no longer win the pool (not even shared)
+    pub eliminated: bool,
+}
+
+/// Current leaderboard
+///
+/// `remaining` are the games still to be played.
+/// `jokers` are the joker games of the players, players without jokers can be left out.
+/// A player is eliminated if some other player will finish ahead, whatever the outcome of the
+/// remaining games, see [`can_catch_up`].
+///
+/// Sorted by points, best first, players on equal points by the tiebreaks of `score_fn`.
+pub fn standings<S: PredScoreFn>(
+    players: &[(PlayerId, Vec<Prediction>)],
+    jokers: &PlayerJokers,
+    results: &HashMap<GameId, Score>,
+    remaining: &[GameId],
+    score_fn: &S,
+) -> Vec<Standing> {
+    standings_with_compensation(
+        players,
+        &HashMap::new(),
+        jokers,
+        results,
+        remaining,
+        score_fn,
+    )
+}
+
+/// Current leaderboard, with points for the games missed by late joiners
+///
+/// `compensation` is added to the points of a player, see [`late_joiner_points`], players
+/// without compensation can be left out. Otherwise the same as [`standings`].
+pub fn standings_with_compensation<S: PredScoreFn>(
+    players: &[(PlayerId, Vec<Prediction>)],
+    compensation: &HashMap<PlayerId, PredScore>,
+    jokers: &PlayerJokers,
+    results: &HashMap<GameId, Score>,
+    remaining: &[GameId],
+    score_fn: &S,
+) -> Vec<Standing> {
+    let no_jokers = Jokers::new();
+    let jokers_of = |player: &PlayerId| jokers.get(player).unwrap_or(&no_jokers);
+    let compensation_of = |player: &PlayerId| compensation.get(player).copied().unwrap_or_default();
+    let points: Vec<PredScore> = players
+        .iter()
+        .map(|(player, preds)| {
+            points(preds, jokers_of(player), results, score_fn) + compensation_of(player)
+        })
+        .collect();
+    let mut standings: Vec<Standing> =
+        players
+            .iter()
+            .zip(points.iter())
+            .map(|((player, preds), current)| {
+                let remaining_max: f32 = preds
+                    .iter()
+                    .filter(|Prediction(id, _)| remaining.contains(id))
+                    .map(|Prediction(id, _)| {
+                        f32::from(score_fn.game_max_score(*id)) * multiplier(jokers_of(player), *id)
+                    })
+                    .sum();
+                let max_points = *current + PredScore::from(remaining_max);
+                let eliminated = players.iter().zip(points.iter()).any(
+                    |((rival, rival_preds), rival_points)| {
+                        rival != player
+                            && (max_points < *rival_points
+                                || !can_catch_up(
+                                    (preds, jokers_of(player), *current),
+                                    (rival_preds, jokers_of(rival), *rival_points),
+                                    remaining,
+                                    score_fn,
+                                ))
+                    },
+                );
+                Standing {
+                    player: *player,
+                    points: *current,
+                    compensation: compensation_of(player),
+                    exact_scores: exact_scores(preds, results),
+                    max_points,
+                    eliminated,
+                }
+            })
+            .collect();
+    rank(&mut standings, score_fn.tiebreaks());
+    standings
+}
+
+/// Part of the tournament scored by a leaderboard
+///
+/// The predicted scores of the group games are scored in the group stage, the predicted
+/// winners of the playoff games in the knockout stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringStage {
+    GroupStage,
+    Knockout,
+}
+
+/// Leaderboards of the stages and of the whole tournament, see [`stage_standings`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StageStandings {
+    pub total: Vec<Standing>,
+    pub group_stage: Vec<Standing>,
+    pub knockout: Vec<Standing>,
+}
+
+impl StageStandings {
+    /// Leaderboard of a stage, the total for `None`
+    pub fn stage(&self, stage: Option<ScoringStage>) -> &[Standing] {
+        match stage {
+            None => &self.total,
+            Some(ScoringStage::GroupStage) => &self.group_stage,
+            Some(ScoringStage::Knockout) => &self.knockout,
+        }
+    }
+}
+
+/// Knockout leaderboard, from the predicted winners of the playoff games
+///
+/// `actual` is the bracket layout, every correct winner is worth `per_winner` of the round of the
+/// game, see [`PlayoffPrediction::points`].
+/// Correct winners take the place of exact scores in the `tiebreaks`.
+/// A player is eliminated if a rival has more points than the player can reach.
+pub fn knockout_standings(
+    players: &[(PlayerId, PlayoffPrediction)],
+    actual: &[BracketNode],
+    per_winner: impl Fn(RoundIdx) -> f32,
+    tiebreaks: &[Tiebreak],
+) -> Vec<Standing> {
+    let mut standings: Vec<Standing> = players
+        .iter()
+        .map(|(player, prediction)| {
+            let points = prediction.points(actual, &per_winner);
+            Standing {
+                player: *player,
+                points: points.points,
+                compensation: PredScore::default(),
+                exact_scores: points.correct,
+                max_points: points.max_points,
+                eliminated: false,
+            }
+        })
+        .collect();
+    eliminate_out_of_reach(&mut standings);
+    rank(&mut standings, tiebreaks);
+    standings
+}
+
+/// Leaderboards of the group stage, the knockout stage and their total
+///
+/// `group_stage` is the leaderboard of the group games, see [`standings`], and `knockout` of
+/// the playoff games, see [`knockout_standings`].
+/// A player missing from one of them has no points in that stage.
+/// The total adds up the points, compensation, exact scores (and correct winners) and the
+/// upper bound of the points of both stages. A player is eliminated from the total if a rival
+/// has more points than the player can reach, players on equal points are ordered by
+/// `tiebreaks`.
+pub fn stage_standings(
+    group_stage: Vec<Standing>,
+    knockout: Vec<Standing>,
+    tiebreaks: &[Tiebreak],
+) -> StageStandings {
+    let mut total: Vec<Standing> = group_stage.clone();
+    for standing in &knockout {
+        match total
+            .iter_mut()
+            .find(|total| total.player == standing.player)
+        {
+            Some(total) => {
+                total.points += standing.points;
+                total.compensation += standing.compensation;
+                total.exact_scores += standing.exact_scores;
+                total.max_points += standing.max_points;
+            }
+            None => total.push(*standing),
+        }
+    }
+    eliminate_out_of_reach(&mut total);
+    rank(&mut total, tiebreaks);
+    StageStandings {
+        total,
+        group_stage,
+        knockout,
+    }
+}
+
+/// Mark the players who can no longer reach the points of a rival as eliminated
+fn eliminate_out_of_reach(standings: &mut [Standing]) {
+    let best = standings.iter().map(|standing| standing.points).fold(
+        PredScore::default(),
+        |best, points| {
+            if points > best {
+                points
+            } else {
+                best
+            }
+        },
+    );
+    for standing in standings.iter_mut() {
+        standing.eliminated = standing.max_points < best;
+    }
+}
+
+/// Points of a player for the played games before joining a league
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LateJoiner {
+    /// No points
+    Zero,
+    /// The median points of the other players who predicted the game
+    Median,
+    /// The average points of the player in the played games the player predicted
+    ProRated,
+}
+
+/// Late joiners get no points for the games they missed
+impl Default for LateJoiner {
+    fn default() -> Self {
+        LateJoiner::Zero
+    }
+}
+
+/// Points for the games missed by late joiners, by the `policy` of the league
+///
+/// A player missed the played games without a prediction which kicked off before the player
+/// joined, `joined` maps players to the time they joined, players left out joined from the
+/// start. `kickoffs` maps game id's to kickoffs.
+/// Jokers do not count, neither for the late joiner nor for the other players.
+///
+/// Only players who missed a game are in the returned map.
+pub fn late_joiner_points<S: PredScoreFn>(
+    policy: LateJoiner,
+    players: &[(PlayerId, Vec<Prediction>)],
+    joined: &HashMap<PlayerId, Date>,
+    kickoffs: &HashMap<GameId, Date>,
+    results: &HashMap<GameId, Score>,
+    score_fn: &S,
+) -> HashMap<PlayerId, PredScore> {
+    let game_points = |preds: &[Prediction]| -> HashMap<GameId, f32> {
+        preds
+            .iter()
+            .filter_map(|Prediction(id, pred)| {
+                let truth = results.get(id)?;
+                Some((*id, f32::from(score_fn.game_score(*id, *pred, *truth))))
+            })
+            .collect()
+    };
+    let game_points: Vec<(PlayerId, HashMap<GameId, f32>)> = players
+        .iter()
+        .map(|(player, preds)| (*player, game_points(preds)))
+        .collect();
+    game_points
+        .iter()
+        .filter_map(|(player, own)| {
+            let joined = joined.get(player)?;
+            let missed: Vec<GameId> = results
+                .keys()
+                .filter(|id| !own.contains_key(id))
+                .filter(|id| matches!(kickoffs.get(id), Some(kickoff) if kickoff < joined))
+                .copied()
+                .collect();
+            if missed.is_empty() {
+                return None;
+            }
+            let points: f32 = match policy {
+                LateJoiner::Zero => 0.0,
+                LateJoiner::Median => missed
+                    .iter()
+                    .map(|id| {
+                        let others: Vec<f32> = game_points
+                            .iter()
+                            .filter(|(other, _)| other != player)
+                            .filter_map(|(_, points)| points.get(id).copied())
+                            .collect();
+                        median(others)
+                    })
+                    .sum(),
+                LateJoiner::ProRated if own.is_empty() => 0.0,
+                LateJoiner::ProRated => {
+                    own.values().sum::<f32>() / own.len() as f32 * missed.len() as f32
+                }
+            };
+            Some((*player, PredScore::from(points)))
+        })
+        .collect()
+}
+
+/// Median, zero if there are no values
+fn median(mut values: Vec<f32>) -> f32 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let mid = values.len() / 2;
+    match values.len() {
+        0 => 0.0,
+        len if len % 2 == 0 => (values[mid - 1] + values[mid]) / 2.0,
+        _ => values[mid],
+    }
+}
+
+/// Check if a player can still reach (at least) the points of a rival
+///
+/// The games are independent, so the best case for the player is the outcome of each remaining
+/// game that maximises the player's score minus the rival's score.
+/// All scores up to [`MAX_PREDICTED_GOALS`] are considered as outcomes.
+///
+/// This is exact for a pair of players, but a player who can catch up with every rival
+/// individually might still be unable to catch up with all of them at the same time.
+/// Hence elimination based on this check is never wrong, but might come later than possible.
+pub fn can_catch_up<S: PredScoreFn>(
+    (preds, jokers, points): (&[Prediction], &Jokers, PredScore),
+    (rival_preds, rival_jokers, rival_points): (&[Prediction], &Jokers, PredScore),
+    remaining: &[GameId],
+    score_fn: &S,
+) -> bool {
+    let pred_for = |preds: &[Prediction], id: GameId| {
+        preds
+            .iter()
+            .find(|Prediction(pred_id, _)| *pred_id == id)
+            .map(|Prediction(_, score)| *score)
+    };
+    let score = |id, pred: Option<Score>, truth, multiplier| {
+        pred.map_or(0.0, |pred| {
+            f32::from(score_fn.game_score(id, pred, truth)) * multiplier
+        })
+    };
+    let max_gain: f32 = remaining
+        .iter()
+        .map(|id| {
+            let (pred, rival_pred) = (pred_for(preds, *id), pred_for(rival_preds, *id));
+            (0..=MAX_PREDICTED_GOALS)
+                .flat_map(|home| (0..=MAX_PREDICTED_GOALS).map(move |away| Score::new(home, away)))
+                .map(|truth| {
+                    score(*id, pred, truth, multiplier(jokers, *id))
+                        - score(*id, rival_pred, truth, multiplier(rival_jokers, *id))
+                })
+                .fold(f32::MIN, f32::max)
+        })
+        .sum();
+    f32::from(points) + max_gain >= f32::from(rival_points)
+}
+
+/// Projected final points of a player
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Projection {
+    pub player: PlayerId,
+    /// Points from the played games
+    pub points: PredScore,
+    /// Expected points from the remaining games
+    pub expected_remaining: PredScore,
+    pub expected_total: PredScore,
+}
+
+/// Project the final leaderboard
+///
+/// The expected points from the remaining games are averaged over `outcomes`,
+/// a set of simulated results for the unplayed games (see
+/// [`simulate_scores`](crate::simulation::simulate_scores)).
+/// All players are evaluated on the same outcomes.
+///
+/// Sorted by expected total, best first.
+pub fn project<S: PredScoreFn>(
+    players: &[(PlayerId, Vec<Prediction>)],
+    jokers: &PlayerJokers,
+    results: &HashMap<GameId, Score>,
+    outcomes: &[HashMap<GameId, Score>],
+    score_fn: &S,
+) -> Vec<Projection> {
+    let no_jokers = Jokers::new();
+    let mut projections: Vec<Projection> = players
+        .iter()
+        .map(|(player, preds)| {
+            let jokers = jokers.get(player).unwrap_or(&no_jokers);
+            let expected_remaining = if outcomes.is_empty() {
+                PredScore::default()
+            } else {
+                let total: f32 = outcomes
+                    .iter()
+                    .map(|outcome| f32::from(points(preds, jokers, outcome, score_fn)))
+                    .sum();
+                PredScore::from(total / outcomes.len() as f32)
+            };
+            let points = points(preds, jokers, results, score_fn);
+            Projection {
+                player: *player,
+                points,
+                expected_remaining,
+                expected_total: points + expected_remaining,
+            }
+        })
+        .collect();
+    projections.sort_by(|a, b| {
+        b.expected_total
+            .partial_cmp(&a.expected_total)
+            .unwrap_or(Ordering::Equal)
+    });
+    projections
+}
+
+/// Provisional points of a player, including games in progress
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ProvisionalStanding {
+    pub player: PlayerId,
+    /// Points from the played games, final
+    pub settled: PredScore,
+    /// Points from the games in progress, if they were to end with the current score
+    pub live: PredScore,
+    pub total: PredScore,
+}
+
+/// Provisional leaderboard
+///
+/// `live` are the current, non-final, scores of games in progress.
+/// The live points are always derived from the latest live score,
+/// so a changed (or removed) live score never leaves any points behind.
+/// Games with a final result in `results` only count as settled.
+///
+/// Sorted by total, best first.
+pub fn provisional_standings<S: PredScoreFn>(
+    players: &[(PlayerId, Vec<Prediction>)],
+    jokers: &PlayerJokers,
+    results: &HashMap<GameId, Score>,
+    live: &HashMap<GameId, Score>,
+    score_fn: &S,
+) -> Vec<ProvisionalStanding> {
+    let no_jokers = Jokers::new();
+    let live: HashMap<GameId, Score> = live
+        .iter()
+        .filter(|(id, _)| !results.contains_key(id))
+        .map(|(id, score)| (*id, *score))
+        .collect();
+    let mut standings: Vec<ProvisionalStanding> = players
+        .iter()
+        .map(|(player, preds)| {
+            let jokers = jokers.get(player).unwrap_or(&no_jokers);
+            let settled = points(preds, jokers, results, score_fn);
+            let live = points(preds, jokers, &live, score_fn);
+            ProvisionalStanding {
+                player: *player,
+                settled,
+                live,
+                total: settled + live,
+            }
+        })
+        .collect();
+    standings.sort_by(|a, b| b.total.partial_cmp(&a.total).unwrap_or(Ordering::Equal));
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pred_score::SimplePredScoreFn;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn stages() {
+        let standing = |player: i32, points: f32, max_points: f32| Standing {
+            player: PlayerId::from(player),
+            points: PredScore::from(points),
+            compensation: PredScore::default(),
+            exact_scores: 0,
+            max_points: PredScore::from(max_points),
+            eliminated: false,
+        };
+        let group_stage = vec![standing(1, 10.0, 10.0), standing(2, 8.0, 8.0)];
+        let knockout = vec![standing(2, 4.0, 6.0), standing(3, 0.0, 2.0)];
+        let stages = stage_standings(group_stage, knockout, &DEFAULT_TIEBREAKS);
+        let total: Vec<(i32, f32, bool)> = stages
+            .stage(None)
+            .iter()
+            .map(|standing| {
+                (
+                    i32::from(standing.player),
+                    f32::from(standing.points),
+                    standing.eliminated,
+                )
+            })
+            .collect();
+        // Player 1 can no longer get more than 10 points, player 3 not more than 2
+        assert_eq!(
+            total,
+            vec![(2, 12.0, false), (1, 10.0, true), (3, 0.0, true)]
+        );
+        assert_eq!(
+            stages.stage(Some(ScoringStage::GroupStage))[0].player,
+            PlayerId::from(1)
+        );
+        assert_eq!(stages.stage(Some(ScoringStage::Knockout)).len(), 2);
+    }
+
+    #[test]
+    fn projection() {
+        let score_fn = SimplePredScoreFn::new(3.0, 2.0);
+        let game = |id: u32, home: u32, away: u32| (GameId::from(id), Score::new(home, away));
+        let results: HashMap<_, _> = vec![game(0, 1, 0)].into_iter().collect();
+        let outcomes: Vec<HashMap<_, _>> = vec![
+            vec![game(1, 2, 0)].into_iter().collect(),
+            vec![game(1, 0, 0)].into_iter().collect(),
+        ];
+        let pred =
+            |id: u32, home: u32, away: u32| Prediction(GameId::from(id), Score::new(home, away));
+        let players = vec![
+            (PlayerId::from(1), vec![pred(0, 0, 1), pred(1, 2, 0)]),
+            (PlayerId::from(2), vec![pred(0, 1, 0), pred(1, 1, 1)]),
+        ];
+        let projections = project(
+            &players,
+            &PlayerJokers::new(),
+            &results,
+            &outcomes,
+            &score_fn,
+        );
+        // Player 2: 5 points, expected (0 + 3) / 2
+        // Player 1: 0 points, expected (5 + 0) / 2
+        assert_eq!(projections[0].player, PlayerId::from(2));
+        assert_approx_eq!(f32::from(projections[0].expected_total), 6.5);
+        assert_approx_eq!(f32::from(projections[1].expected_total), 2.5);
+    }
+
+    #[test]
+    fn eliminated() {
+        let score_fn = SimplePredScoreFn::new(3.0, 2.0);
+        let results: HashMap<_, _> = vec![(GameId::from(0), Score::new(1, 0))]
+            .into_iter()
+            .collect();
+        let remaining = vec![GameId::from(1)];
+        let pred =
+            |id: u32, home: u32, away: u32| Prediction(GameId::from(id), Score::new(home, away));
+        let players = vec![
+            // 5 points
+            (PlayerId::from(1), vec![pred(0, 1, 0), pred(1, 1, 0)]),
+            // 0 points, same remaining prediction as player 1: can never catch up.
+            (PlayerId::from(2), vec![pred(0, 0, 1), pred(1, 1, 0)]),
+            // 0 points, but 5 points if 0-1 and player 1 gets nothing.
+            (PlayerId::from(3), vec![pred(0, 0, 1), pred(1, 0, 1)]),
+        ];
+        let standings = standings(
+            &players,
+            &PlayerJokers::new(),
+            &results,
+            &remaining,
+            &score_fn,
+        );
+        let eliminated = |id: i32| {
+            standings
+                .iter()
+                .find(|standing| standing.player == PlayerId::from(id))
+                .unwrap()
+                .eliminated
+        };
+        assert!(!eliminated(1));
+        assert!(eliminated(2));
+        assert!(!eliminated(3));
+        assert_approx_eq!(f32::from(standings[0].max_points), 10.0);
+    }
+
+    #[test]
+    fn tiebreaks() {
+        let score_fn = SimplePredScoreFn::new(2.0, 2.0);
+        let game = |id: u32, home: u32, away: u32| (GameId::from(id), Score::new(home, away));
+        let results: HashMap<_, _> = vec![game(0, 1, 0), game(1, 2, 1), game(2, 0, 0)]
+            .into_iter()
+            .collect();
+        let pred =
+            |id: u32, home: u32, away: u32| Prediction(GameId::from(id), Score::new(home, away));
+        let players = vec![
+            // 4 + 0 + 0 points, one exact score
+            (
+                PlayerId::from(3),
+                vec![pred(0, 1, 0), pred(1, 0, 1), pred(2, 1, 0)],
+            ),
+            // 2 + 0 + 2 points, no exact score
+            (
+                PlayerId::from(2),
+                vec![pred(0, 2, 0), pred(1, 0, 2), pred(2, 1, 1)],
+            ),
+            // Same as player 2, registered earlier
+            (
+                PlayerId::from(1),
+                vec![pred(0, 2, 0), pred(1, 0, 2), pred(2, 1, 1)],
+            ),
+        ];
+        let standings = standings(&players, &PlayerJokers::new(), &results, &[], &score_fn);
+        let order: Vec<_> = standings.iter().map(|s| i32::from(s.player)).collect();
+        assert_eq!(order, vec![3, 1, 2]);
+        assert_eq!(standings[0].exact_scores, 1);
+        assert_eq!(
+            decisive_tiebreak(&standings[0], &standings[1], &DEFAULT_TIEBREAKS),
+            Some(Tiebreak::ExactScores)
+        );
+        assert_eq!(
+            decisive_tiebreak(&standings[1], &standings[2], &DEFAULT_TIEBREAKS),
+            Some(Tiebreak::Registration)
+        );
+        let mut by_registration = standings.clone();
+        rank(&mut by_registration, &[Tiebreak::Registration]);
+        let order: Vec<_> = by_registration
+            .iter()
+            .map(|s| i32::from(s.player))
+            .collect();
+        assert_eq!(order, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn joker_points() {
+        let score_fn = SimplePredScoreFn::new(3.0, 2.0);
+        let results: HashMap<_, _> = vec![(GameId::from(0), Score::new(1, 0))]
+            .into_iter()
+            .collect();
+        let remaining = vec![GameId::from(1)];
+        let pred =
+            |id: u32, home: u32, away: u32| Prediction(GameId::from(id), Score::new(home, away));
+        let players = vec![
+            (PlayerId::from(1), vec![pred(0, 1, 0), pred(1, 1, 0)]),
+            (PlayerId::from(2), vec![pred(0, 2, 0), pred(1, 0, 1)]),
+        ];
+        let jokers: PlayerJokers = vec![
+            (
+                PlayerId::from(1),
+                vec![GameId::from(0)].into_iter().collect(),
+            ),
+            (
+                PlayerId::from(2),
+                vec![GameId::from(1)].into_iter().collect(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let standings = standings(&players, &jokers, &results, &remaining, &score_fn);
+        // Player 1: double 5 points for the joker, 5 more possible.
+        assert_eq!(standings[0].player, PlayerId::from(1));
+        assert_approx_eq!(f32::from(standings[0].points), 10.0);
+        assert_approx_eq!(f32::from(standings[0].max_points), 15.0);
+        // Player 2: 3 points, double 5 possible from the joker, enough to catch up.
+        assert_approx_eq!(f32::from(standings[1].points), 3.0);
+        assert_approx_eq!(f32::from(standings[1].max_points), 13.0);
+        assert!(!standings[1].eliminated);
+    }
+
+    #[test]
+    fn late_joiner() {
+        let score_fn = SimplePredScoreFn::new(3.0, 2.0);
+        let results: HashMap<_, _> = vec![
+            (GameId::from(0), Score::new(1, 0)),
+            (GameId::from(1), Score::new(0, 0)),
+        ]
+        .into_iter()
+        .collect();
+        let kickoffs: HashMap<_, _> = vec![
+            (GameId::from(0), Date::mock()),
+            (GameId::from(1), Date::mock().add_hours(24)),
+        ]
+        .into_iter()
+        .collect();
+        let pred =
+            |id: u32, home: u32, away: u32| Prediction(GameId::from(id), Score::new(home, away));
+        let players = vec![
+            // 5 + 3 points
+            (PlayerId::from(1), vec![pred(0, 1, 0), pred(1, 1, 1)]),
+            // 0 + 0 points
+            (PlayerId::from(2), vec![pred(0, 0, 1), pred(1, 1, 0)]),
+            // Joined after game 0: 5 points
+            (PlayerId::from(3), vec![pred(1, 0, 0)]),
+        ];
+        let joined: HashMap<_, _> = vec![(PlayerId::from(3), Date::mock().add_hours(1))]
+            .into_iter()
+            .collect();
+        let compensation =
+            |policy| late_joiner_points(policy, &players, &joined, &kickoffs, &results, &score_fn);
+        let late = |policy| f32::from(compensation(policy)[&PlayerId::from(3)]);
+        assert_approx_eq!(late(LateJoiner::Zero), 0.0);
+        assert_approx_eq!(late(LateJoiner::Median), 2.5);
+        assert_approx_eq!(late(LateJoiner::ProRated), 5.0);
+        assert_eq!(compensation(LateJoiner::Median).len(), 1);
+
+        let standings = standings_with_compensation(
+            &players,
+            &compensation(LateJoiner::ProRated),
+            &PlayerJokers::new(),
+            &results,
+            &[],
+            &score_fn,
+        );
+        assert_eq!(standings[0].player, PlayerId::from(3));
+        assert_approx_eq!(f32::from(standings[0].points), 10.0);
+        assert_approx_eq!(f32::from(standings[0].compensation), 5.0);
+    }
+
+    #[test]
+    fn provisional() {
+        let score_fn = SimplePredScoreFn::new(3.0, 2.0);
+        let game = |id: u32, home: u32, away: u32| (GameId::from(id), Score::new(home, away));
+        let results: HashMap<_, _> = vec![game(0, 1, 0)].into_iter().collect();
+        let pred =
+            |id: u32, home: u32, away: u32| Prediction(GameId::from(id), Score::new(home, away));
+        let players = vec![
+            (PlayerId::from(1), vec![pred(0, 1, 0), pred(1, 0, 0)]),
+            (PlayerId::from(2), vec![pred(0, 2, 0), pred(1, 1, 0)]),
+        ];
+        let provisional = |live: Vec<(GameId, Score)>| {
+            provisional_standings(
+                &players,
+                &PlayerJokers::new(),
+                &results,
+                &live.into_iter().collect(),
+                &score_fn,
+            )
+        };
+        // Game 1 is 1-0 in progress: player 2 leads provisionally.
+        let standings = provisional(vec![game(1, 1, 0)]);
+        assert_eq!(standings[0].player, PlayerId::from(2));
+        assert_approx_eq!(f32::from(standings[0].settled), 3.0);
+        assert_approx_eq!(f32::from(standings[0].live), 5.0);
+        assert_approx_eq!(f32::from(standings[1].total), 5.0);
+        // An equalizer moves the live points, the settled points stay.
+        let standings = provisional(vec![game(1, 1, 1)]);
+        assert_eq!(standings[0].player, PlayerId::from(1));
+        assert_approx_eq!(f32::from(standings[0].live), 3.0);
+        assert_approx_eq!(f32::from(standings[1].live), 0.0);
+        // A live score for a game with a final result is ignored.
+        let standings = provisional(vec![game(0, 0, 1)]);
+        assert_approx_eq!(f32::from(standings[0].total), 5.0);
+        assert_approx_eq!(f32::from(standings[0].live), 0.0);
+    }
+}
@@ -10,6 +10,19 @@
 //! This makes for a very clean and composable API with consistent results. The down-side is of course that calculations
 //! are repeated unecessarily, but then again, the size of the average tournament is very small and
 //! the overhead will be miniscule.
+//!
+//! ## Features
+//! The group ordering and stats only need the default-less build, e.g. for running
+//! [`order_group`](group::order_group) in the WASM frontend.
+//!
+//! - `random` (default): Random groups, goal models and simulation, pulls in `rand` and `rayon`.
+//!   Without it, the [`Random`](group::order::Random) tiebreaker draws lots deterministically.
+//! - `clock` (default): The system clock, see [`Date::now`].
+//! - `tournament` (default): Predictions, leaderboards, playoffs, events and the rest of a
+//!   tournament, pulls in `chrono` and `serde_json`.
+//!   Without it, [`Date`] is a plain UTC timestamp which only orders games by kickoff.
+//! - `export` (default): CSV, calendar and table exports and CSV result import, pulls in `csv`.
+//! - `auth` (default): API keys and signed login links, pulls in `sha2`.
 #![forbid(unsafe_code)]
 // Enable clippy if our Cargo.toml file asked us to do so.
 #![cfg_attr(feature = "clippy", feature(plugin))]
@@ -43,15 +56,73 @@
 #![cfg_attr(feature = "clippy", warn(unseparated_literal_suffix))]
 #![cfg_attr(feature = "clippy", warn(wrong_pub_self_convention))]
 
+#[cfg(feature = "tournament")]
+pub mod achievement;
+#[cfg(feature = "auth")]
+pub mod api_key;
+#[cfg(feature = "auth")]
+pub mod auth;
+#[cfg(feature = "tournament")]
+pub mod consensus;
+#[cfg(feature = "tournament")]
+pub mod digest;
+#[cfg(feature = "tournament")]
+pub mod discipline;
+#[cfg(feature = "tournament")]
 pub mod error;
+#[cfg(feature = "tournament")]
+pub mod event;
+#[cfg(feature = "export")]
+pub mod export;
 pub mod fair_play;
+#[cfg(feature = "tournament")]
+pub mod fairness;
 pub mod game;
+#[cfg(feature = "random")]
+pub mod goal_model;
 pub mod group;
+#[cfg(feature = "tournament")]
+pub mod hall_of_fame;
+#[cfg(feature = "tournament")]
+pub mod invite;
+#[cfg(feature = "tournament")]
+pub mod joker;
+#[cfg(feature = "tournament")]
+pub mod leaderboard;
+#[cfg(feature = "tournament")]
+pub mod notification;
+#[cfg(feature = "tournament")]
+pub mod odds;
+#[cfg(feature = "tournament")]
+pub mod parse;
+#[cfg(feature = "tournament")]
 pub mod player;
+#[cfg(feature = "tournament")]
 pub mod playoff;
+#[cfg(feature = "tournament")]
+pub mod pred;
+#[cfg(feature = "tournament")]
 pub mod pred_score;
+#[cfg(feature = "tournament")]
+pub mod replay;
+#[cfg(feature = "tournament")]
+pub mod rules;
+#[cfg(feature = "random")]
+pub mod simulation;
+#[cfg(feature = "tournament")]
+pub mod stage;
 pub mod team;
+#[cfg(feature = "tournament")]
+pub mod top_scorer;
 pub mod utils;
+#[cfg(feature = "tournament")]
+pub mod venue;
+
+/// Dependencies of the exported macros, not part of the API
+#[doc(hidden)]
+pub mod __private {
+    pub use num;
+}
 // Exports
 pub use team::Team;
 pub use utils::date::Date;
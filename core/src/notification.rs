@@ -0,0 +1,30 @@
+//! Notifications
+//!
+//! Changes to the tournament that players need to hear about, e.g. a moved kickoff, and the
+//! digest of every completed matchday.
+//! How they are delivered (api feed, chat bots, ...) is up to the consumer.
+use crate::digest::Digest;
+use crate::game::GameId;
+use crate::player::PlayerId;
+use crate::Date;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum Notification {
+    /// The kickoff of a game has moved
+    GameRescheduled {
+        game: GameId,
+        /// `None` if the game had no kickoff before
+        old_kickoff: Option<Date>,
+        new_kickoff: Date,
+        /// Players whose effective prediction for the game changed with the new deadline
+        affected_players: Vec<PlayerId>,
+    },
+    /// The last game of a matchday is played, see [`digest`](crate::digest)
+    MatchdayDigest {
+        digest: Digest,
+        /// The digest rendered for chat, see [`Digest::to_markdown`]
+        markdown: String,
+    },
+}
@@ -0,0 +1,153 @@
+//! Betting odds
+//!
+//! Decimal odds of the outcomes of a game, e.g. odds of 2.5 return 2.5 times the stake.
+//! Used to reward brave picks: correctly predicting an unlikely outcome earns a bonus, see
+//! [`OddsBonus`].
+use crate::error::ErrorCode;
+use crate::game::{GameId, Outcome, Score};
+use crate::leaderboard::Tiebreak;
+use crate::pred_score::{PredScore, PredScoreFn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Decimal odds of the home win, draw and away win of a game
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Odds {
+    pub home: f32,
+    pub draw: f32,
+    pub away: f32,
+}
+
+impl Odds {
+    /// Fallible constructor
+    ///
+    /// # Errors
+    ///
+    /// Decimal odds are finite and at least 1.0, see [`Odds::is_valid`].
+    pub fn try_new(home: f32, draw: f32, away: f32) -> Result<Self, OddsError> {
+        if Self::is_valid(home) && Self::is_valid(draw) && Self::is_valid(away) {
+            Ok(Odds { home, draw, away })
+        } else {
+            Err(OddsError::Invalid { home, draw, away })
+        }
+    }
+
+    /// Decimal odds are finite and at least 1.0, i.e. a winning bet never loses money
+    pub fn is_valid(odds: f32) -> bool {
+        odds.is_finite() && odds >= 1.0
+    }
+
+    /// Odds of an outcome, from the perspective of the home team
+    pub fn of(&self, home_outcome: Outcome) -> f32 {
+        match home_outcome {
+            Outcome::Win => self.home,
+            Outcome::Draw => self.draw,
+            Outcome::Lose => self.away,
+        }
+    }
+
+    fn max(&self) -> f32 {
+        self.home.max(self.draw).max(self.away)
+    }
+}
+
+/// Bonus for correct outcomes, proportional to the odds of the outcome
+///
+/// A correct outcome with odds `x` earns `factor * (x - 1)` points on top of the `base` score.
+/// E.g. with factor 1, a correctly predicted underdog win at odds 4.0 earns another 3 points,
+/// while a favourite win at 1.2 earns 0.2.
+/// Games without odds get no bonus.
+#[derive(Debug, Clone, Copy)]
+pub struct OddsBonus<'a, S> {
+    base: S,
+    odds: &'a HashMap<GameId, Odds>,
+    factor: f32,
+}
+
+impl<'a, S: PredScoreFn> OddsBonus<'a, S> {
+    pub fn new(base: S, odds: &'a HashMap<GameId, Odds>, factor: f32) -> Self {
+        OddsBonus { base, odds, factor }
+    }
+
+    fn bonus(&self, game: GameId, pred: Score, truth: Score) -> f32 {
+        match self.odds.get(&game) {
+            Some(odds) if pred.home_outcome() == truth.home_outcome() => {
+                self.factor * (odds.of(truth.home_outcome()) - 1.0)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+impl<S: PredScoreFn> PredScoreFn for OddsBonus<'_, S> {
+    /// Without the game, there is no bonus
+    fn pred_score(&self, pred: Score, truth: Score) -> PredScore {
+        self.base.pred_score(pred, truth)
+    }
+
+    /// Bound for a game with the largest odds
+    fn max_score(&self) -> PredScore {
+        let max_odds = self.odds.values().map(Odds::max).fold(1.0, f32::max);
+        self.base.max_score() + PredScore::from(self.factor * (max_odds - 1.0))
+    }
+
+    fn game_score(&self, game: GameId, pred: Score, truth: Score) -> PredScore {
+        self.base.game_score(game, pred, truth) + PredScore::from(self.bonus(game, pred, truth))
+    }
+
+    fn game_max_score(&self, game: GameId) -> PredScore {
+        let max_odds = self.odds.get(&game).map_or(1.0, Odds::max);
+        self.base.game_max_score(game) + PredScore::from(self.factor * (max_odds - 1.0))
+    }
+
+    fn tiebreaks(&self) -> &[Tiebreak] {
+        self.base.tiebreaks()
+    }
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum OddsError {
+    #[error("Invalid odds {home}/{draw}/{away}, decimal odds are at least 1.0")]
+    Invalid { home: f32, draw: f32, away: f32 },
+}
+
+impl ErrorCode for OddsError {
+    fn code(&self) -> &'static str {
+        match self {
+            OddsError::Invalid { .. } => "INVALID_ODDS",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pred_score::ScoringRules;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn bonus_for_correct_outcome() {
+        let mut odds = HashMap::new();
+        odds.insert(GameId::from(1), Odds::try_new(1.5, 3.5, 6.0).unwrap());
+        let score_fn = OddsBonus::new(ScoringRules::new(5.0, 3.0, 1.0), &odds, 0.5);
+        let truth = Score::new(0, 2);
+        let score = |game: u32, home, away| {
+            f32::from(score_fn.game_score(GameId::from(game), Score::new(home, away), truth))
+        };
+        // Outcome 1 + 0.5 * 5.0
+        assert_approx_eq!(score(1, 0, 1), 3.5);
+        assert_approx_eq!(score(1, 1, 1), 0.0);
+        // No odds, no bonus
+        assert_approx_eq!(score(2, 0, 1), 1.0);
+        assert_approx_eq!(f32::from(score_fn.game_max_score(GameId::from(1))), 7.5);
+        assert_approx_eq!(f32::from(score_fn.game_max_score(GameId::from(2))), 5.0);
+    }
+
+    #[test]
+    fn invalid_odds() {
+        assert!(Odds::try_new(0.9, 3.0, 4.0).is_err());
+        assert!(Odds::try_new(1.1, f32::NAN, 4.0).is_err());
+        assert!(Odds::try_new(1.0, 3.0, 4.0).is_ok());
+    }
+}
@@ -0,0 +1,4 @@
+//! Parsing of free text input
+pub mod result;
+#[cfg(feature = "export")]
+pub mod result_csv;
@@ -0,0 +1,347 @@
+//! Game results from free text
+//!
+//! Parses results as they are typed in a chat or a terminal, e.g.
+//!
+//! - `GER-FRA 2:1`
+//! - `Sweden 3-0 England (yellow: swe 2)`
+//! - `sweden v england 1 - 1 (yellow: swe 2, eng 1; red: eng 1)`
+//!
+//! Teams are given by name, Fifa code or alias, allowing for typos, see [`TeamResolver`].
+//! Card counts are given in parentheses after the game, as `<kind>: <team> <count>` separated
+//! by `,` or `;`. The kind carries over to the following counts until a new kind is given.
+//! Card kinds are `yellow`, `second yellow`, `red` and `yellow red`, see [`FairPlay`].
+//!
+//! The parsed [`ResultEntry`] is then matched against the unplayed games,
+//! see [`ResultEntry::submission`].
+use crate::fair_play::{FairPlay, FairPlayScore};
+use crate::game::{GameId, Score};
+use crate::group::Groups;
+use crate::team::resolve::{ResolveError, TeamResolver};
+use crate::team::TeamId;
+use thiserror::Error;
+
+const SCORE_SEPARATORS: [char; 3] = ['-', ':', '–'];
+const TEAM_SEPARATORS: [&str; 5] = [" vs ", " v ", "-", "–", ":"];
+
+/// Parsed result, not yet matched against a game
+#[derive(Debug, Clone, Copy)]
+pub struct ResultEntry {
+    pub home: TeamId,
+    pub away: TeamId,
+    pub score: Score,
+    pub fair_play: FairPlayScore,
+}
+
+/// Result of a specific unplayed game, ready to be stored
+#[derive(Debug, Clone, Copy)]
+pub struct ResultSubmission {
+    pub game_id: GameId,
+    pub score: Score,
+    pub fair_play: FairPlayScore,
+}
+
+/// Parse a result, resolving team names with `teams`
+pub fn parse_result(input: &str, teams: &TeamResolver) -> Result<ResultEntry, ResultParseError> {
+    let (game, cards) = match input.find('(') {
+        Some(open) => {
+            let close = input[open..]
+                .find(')')
+                .map(|close| open + close)
+                .ok_or_else(|| ResultParseError::Cards(String::from(&input[open..])))?;
+            (
+                format!("{} {}", &input[..open], &input[close + 1..]),
+                Some(&input[open + 1..close]),
+            )
+        }
+        None => (String::from(input), None),
+    };
+    let (start, end, score) =
+        find_score(&game).ok_or_else(|| ResultParseError::NoScore(String::from(input)))?;
+    let (before, after) = (game[..start].trim(), game[end..].trim());
+    let (home, away) = match (before.is_empty(), after.is_empty()) {
+        (false, false) => (before, after),
+        (false, true) => split_teams(before)?,
+        (true, false) => split_teams(after)?,
+        (true, true) => return Err(ResultParseError::Teams(String::from(input))),
+    };
+    let home = teams.resolve(home)?;
+    let away = teams.resolve(away)?;
+    if home == away {
+        return Err(ResultParseError::Teams(String::from(input)));
+    }
+    let fair_play = match cards {
+        Some(cards) => parse_cards(cards, home, away, teams)?,
+        None => FairPlayScore::default(),
+    };
+    Ok(ResultEntry {
+        home,
+        away,
+        score,
+        fair_play,
+    })
+}
+
+impl ResultEntry {
+    /// Match the result with an unplayed group game between the two teams
+    ///
+    /// The teams may be given in any order, the score and cards are swapped to match the game.
+    pub fn submission(&self, groups: &Groups) -> Result<ResultSubmission, ResultParseError> {
+        let same = |home, away| home == self.home && away == self.away;
+        let swapped = |home, away| home == self.away && away == self.home;
+        if let Some(game) = groups
+            .values()
+            .flat_map(|group| group.played_games())
+            .find(|game| same(game.home, game.away) || swapped(game.home, game.away))
+        {
+            return Err(ResultParseError::AlreadyPlayed(game.id));
+        }
+        let game = groups
+            .values()
+            .flat_map(|group| group.unplayed_games())
+            .find(|game| same(game.home, game.away) || swapped(game.home, game.away))
+            .ok_or(ResultParseError::NoGame(self.home, self.away))?;
+        if same(game.home, game.away) {
+            Ok(ResultSubmission {
+                game_id: game.id,
+                score: self.score,
+                fair_play: self.fair_play,
+            })
+        } else {
+            Ok(ResultSubmission {
+                game_id: game.id,
+                score: Score::new(self.score.away, self.score.home),
+                fair_play: FairPlayScore::new(self.fair_play.away, self.fair_play.home),
+            })
+        }
+    }
+}
+
+/// Byte range and value of the first `<goals><separator><goals>` in the input
+///
+/// The goals must not be part of a word, spaces around the separator are allowed.
+fn find_score(input: &str) -> Option<(usize, usize, Score)> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let byte_idx = |idx: usize| chars.get(idx).map_or(input.len(), |(byte, _)| *byte);
+    let digits_end = |mut idx: usize| {
+        while idx < chars.len() && chars[idx].1.is_ascii_digit() {
+            idx += 1;
+        }
+        idx
+    };
+    let spaces_end = |mut idx: usize| {
+        while idx < chars.len() && chars[idx].1.is_whitespace() {
+            idx += 1;
+        }
+        idx
+    };
+    for start in 0..chars.len() {
+        if !chars[start].1.is_ascii_digit() || (start > 0 && chars[start - 1].1.is_alphanumeric()) {
+            continue;
+        }
+        let home_end = digits_end(start);
+        let sep = spaces_end(home_end);
+        if sep >= chars.len() || !SCORE_SEPARATORS.contains(&chars[sep].1) {
+            continue;
+        }
+        let away_start = spaces_end(sep + 1);
+        let away_end = digits_end(away_start);
+        if away_end == away_start
+            || matches!(chars.get(away_end), Some((_, c)) if c.is_alphanumeric())
+        {
+            continue;
+        }
+        let home = input[byte_idx(start)..byte_idx(home_end)]
+            .parse::<u32>()
+            .ok()?;
+        let away = input[byte_idx(away_start)..byte_idx(away_end)]
+            .parse::<u32>()
+            .ok()?;
+        return Some((
+            byte_idx(start),
+            byte_idx(away_end),
+            Score::from((home, away)),
+        ));
+    }
+    None
+}
+
+/// Split `"GER-FRA"` or `"Sweden vs England"` into two teams
+fn split_teams(input: &str) -> Result<(&str, &str), ResultParseError> {
+    // Ascii lowercase keeps the byte offsets of `input`.
+    let lower = input.to_ascii_lowercase();
+    TEAM_SEPARATORS
+        .iter()
+        .filter_map(|sep| {
+            lower
+                .find(sep)
+                .map(|idx| (input[..idx].trim(), input[idx + sep.len()..].trim()))
+        })
+        .find(|(home, away)| !home.is_empty() && !away.is_empty())
+        .ok_or_else(|| ResultParseError::Teams(String::from(input)))
+}
+
+/// Card kinds, in the order of the [`FairPlay::new`] arguments
+#[derive(Clone, Copy)]
+enum CardKind {
+    Yellow = 0,
+    SecondYellow = 1,
+    Red = 2,
+    YellowRed = 3,
+}
+
+impl CardKind {
+    fn parse(kind: &str) -> Option<Self> {
+        let kind = kind
+            .to_lowercase()
+            .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        match kind.as_str() {
+            "yellow" => Some(CardKind::Yellow),
+            "second yellow" | "indirect red" => Some(CardKind::SecondYellow),
+            "red" | "direct red" => Some(CardKind::Red),
+            "yellow red" | "yellow and red" => Some(CardKind::YellowRed),
+            _ => None,
+        }
+    }
+}
+
+/// Parse `"yellow: swe 2, eng 1; red: eng 1"`
+pub(crate) fn parse_cards(
+    input: &str,
+    home: TeamId,
+    away: TeamId,
+    teams: &TeamResolver,
+) -> Result<FairPlayScore, ResultParseError> {
+    let err = || ResultParseError::Cards(String::from(input));
+    let mut counts = [[0u32; 4]; 2];
+    let mut kind = None;
+    for item in input
+        .split(&[',', ';'][..])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        let count = match item.split_once(':') {
+            Some((new_kind, count)) => {
+                kind = Some(CardKind::parse(new_kind).ok_or_else(err)?);
+                count.trim()
+            }
+            None => item,
+        };
+        let (team, num) = count.rsplit_once(char::is_whitespace).ok_or_else(err)?;
+        let num: u32 = num.parse().map_err(|_| err())?;
+        let side = match teams.resolve(team)? {
+            id if id == home => 0,
+            id if id == away => 1,
+            _ => return Err(err()),
+        };
+        counts[side][kind.ok_or_else(err)? as usize] += num;
+    }
+    let fair_play = |[yellow, second_yellow, red, yellow_red]: [u32; 4]| {
+        FairPlay::new(yellow, second_yellow, red, yellow_red)
+    };
+    Ok(FairPlayScore::new(
+        fair_play(counts[0]),
+        fair_play(counts[1]),
+    ))
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ResultParseError {
+    #[error("No score, e.g. '2-1', in '{0}'")]
+    NoScore(String),
+    #[error("Could not find two teams in '{0}'")]
+    Teams(String),
+    #[error(transparent)]
+    Team(#[from] ResolveError),
+    #[error("Could not parse cards '{0}'")]
+    Cards(String),
+    #[error("No unplayed game between {0} and {1}")]
+    NoGame(TeamId, TeamId),
+    #[error("Game {0} is already played")]
+    AlreadyPlayed(GameId),
+    #[error("No group game with id {0}")]
+    UnknownGame(GameId),
+    #[error("The teams do not play game {0}")]
+    TeamsMismatch(GameId),
+    #[error("Game {0} is given more than once")]
+    DuplicateGame(GameId),
+    #[error("Could not read row: {0}")]
+    Csv(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::{FairPlayValue, FifaFairPlayValue};
+    use crate::group::mock_data;
+
+    #[test]
+    fn formats() {
+        let (_, teams) = mock_data();
+        let teams = TeamResolver::new(&teams);
+        for input in &[
+            "SWE-ENG 2:1",
+            "swe - eng 2-1",
+            "Sweden 2-1 England",
+            "sweden vs ENGLAND 2 : 1",
+            "2-1 SWE v ENG",
+        ] {
+            let entry = parse_result(input, &teams).unwrap();
+            assert_eq!(
+                (entry.home, entry.away),
+                (TeamId(1), TeamId(2)),
+                "{}",
+                input
+            );
+            assert_eq!(entry.score, Score::from((2, 1)), "{}", input);
+        }
+        assert_eq!(
+            parse_result("SWE-ENG", &teams).unwrap_err(),
+            ResultParseError::NoScore(String::from("SWE-ENG"))
+        );
+        assert_eq!(
+            parse_result("SWE-XYZ 1-0", &teams).unwrap_err(),
+            ResultParseError::Team(ResolveError::Unknown(String::from("XYZ")))
+        );
+    }
+
+    #[test]
+    fn cards() {
+        let (_, teams) = mock_data();
+        let teams = TeamResolver::new(&teams);
+        let entry = parse_result(
+            "Sweden 3-0 England (yellow: swe 2, eng 1; red: ENG 1)",
+            &teams,
+        )
+        .unwrap();
+        let value = |fair_play| FifaFairPlayValue::from_fair_play(&fair_play);
+        assert_eq!(value(entry.fair_play.home), FifaFairPlayValue::from(2));
+        assert_eq!(value(entry.fair_play.away), FifaFairPlayValue::from(5));
+        assert!(parse_result("SWE-ENG 1-0 (yellow: fra 1)", &teams).is_err());
+        assert!(parse_result("SWE-ENG 1-0 (blue: swe 1)", &teams).is_err());
+    }
+
+    #[test]
+    fn submission() {
+        let (groups, teams) = mock_data();
+        let teams = TeamResolver::new(&teams);
+        // Game 2 is France - Brazil
+        let entry = parse_result("BRA-FRA 1:0 (yellow: bra 1)", &teams).unwrap();
+        let submission = entry.submission(&groups).unwrap();
+        assert_eq!(submission.game_id, GameId::from(2));
+        assert_eq!(submission.score, Score::from((0, 1)));
+        assert_eq!(
+            FifaFairPlayValue::from_fair_play(&submission.fair_play.away),
+            FifaFairPlayValue::from(1)
+        );
+        let entry = parse_result("SWE-ENG 1:0", &teams).unwrap();
+        assert_eq!(
+            entry.submission(&groups).unwrap_err(),
+            ResultParseError::AlreadyPlayed(GameId::from(1))
+        );
+        let entry = parse_result("SWE-FRA 1:0", &teams).unwrap();
+        assert!(entry.submission(&groups).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Game results from a CSV file
+//!
+//! For entering many results at once, e.g. catching up on a matchday after downtime.
+//! The file has a header and the columns
+//!
+//! - `game`: game id, optional if the teams are given
+//! - `home`, `away`: teams by name, Fifa code or alias, optional if the game id is given
+//! - `home_goals`, `away_goals`
+//! - `cards`: optional, same syntax as in a typed result, e.g. `yellow: swe 2; red: eng 1`
+//!
+//! e.g.
+//!
+//! ```csv
+//! game,home,away,home_goals,away_goals,cards
+//! 3,,,2,1,
+//! ,Sweden,England,1,1,"yellow: swe 2, eng 1"
+//! ```
+//!
+//! If both the game id and the teams are given, the teams must play the game.
+//! Like [`ResultEntry::submission`], the teams may be given in any order.
+//! Every row is validated on its own, see [`parse_result_csv`].
+use crate::fair_play::FairPlayScore;
+use crate::game::{GameId, Score};
+use crate::group::game::UnplayedGroupGame;
+use crate::group::Groups;
+use crate::parse::result::{parse_cards, ResultEntry, ResultParseError, ResultSubmission};
+use crate::team::resolve::TeamResolver;
+use crate::Date;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Deserialize)]
+struct CsvRow {
+    game: Option<u32>,
+    home: Option<String>,
+    away: Option<String>,
+    home_goals: u32,
+    away_goals: u32,
+    cards: Option<String>,
+}
+
+/// Validated row, ready to be stored
+#[derive(Debug, Clone, Copy)]
+pub struct ImportedResult {
+    pub submission: ResultSubmission,
+    pub kickoff: Date,
+    /// Whether the row had cards, otherwise the cards of the game are left as they are
+    pub has_cards: bool,
+}
+
+/// Row of the file, numbered from 1 after the header
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    pub row: usize,
+    pub result: Result<ImportedResult, ResultParseError>,
+}
+
+/// Parse and validate every row against the unplayed group games
+///
+/// Rows are returned in file order. A game given by more than one row is only accepted once.
+pub fn parse_result_csv(input: &str, teams: &TeamResolver, groups: &Groups) -> Vec<ImportRow> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(input.as_bytes());
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(err) => {
+            return vec![ImportRow {
+                row: 0,
+                result: Err(ResultParseError::Csv(err.to_string())),
+            }]
+        }
+    };
+    let mut seen = HashSet::new();
+    reader
+        .records()
+        .enumerate()
+        .map(|(idx, record)| {
+            let result = record
+                .and_then(|record| record.deserialize::<CsvRow>(Some(&headers)))
+                .map_err(|err| ResultParseError::Csv(err.to_string()))
+                .and_then(|row| import_row(&row, teams, groups))
+                .and_then(|imported| {
+                    if seen.insert(imported.submission.game_id) {
+                        Ok(imported)
+                    } else {
+                        Err(ResultParseError::DuplicateGame(imported.submission.game_id))
+                    }
+                });
+            ImportRow {
+                row: idx + 1,
+                result,
+            }
+        })
+        .collect()
+}
+
+/// Valid rows, in kickoff order
+pub fn in_kickoff_order(rows: &[ImportRow]) -> Vec<ImportedResult> {
+    let mut results: Vec<ImportedResult> = rows
+        .iter()
+        .filter_map(|row| row.result.as_ref().ok().copied())
+        .collect();
+    results.sort_by_key(|result| (result.kickoff, result.submission.game_id));
+    results
+}
+
+fn import_row(
+    row: &CsvRow,
+    teams: &TeamResolver,
+    groups: &Groups,
+) -> Result<ImportedResult, ResultParseError> {
+    let non_empty = |field: &Option<String>| field.clone().filter(|field| !field.is_empty());
+    let score = Score::from((row.home_goals, row.away_goals));
+    let cards = non_empty(&row.cards);
+    let submission = match (row.game, non_empty(&row.home), non_empty(&row.away)) {
+        (Some(game_id), home, away) => {
+            let game_id = GameId::from(game_id);
+            let game = find_unplayed(groups, game_id)?;
+            let swapped = match (home, away) {
+                (Some(home), Some(away)) => match (teams.resolve(&home)?, teams.resolve(&away)?) {
+                    (home, away) if (home, away) == (game.home, game.away) => false,
+                    (home, away) if (home, away) == (game.away, game.home) => true,
+                    _ => return Err(ResultParseError::TeamsMismatch(game_id)),
+                },
+                (None, None) => false,
+                _ => return Err(ResultParseError::Teams(format!("game {}", game_id))),
+            };
+            ResultSubmission {
+                game_id,
+                score: if swapped {
+                    Score::new(score.away, score.home)
+                } else {
+                    score
+                },
+                fair_play: match &cards {
+                    Some(cards) => parse_cards(cards, game.home, game.away, teams)?,
+                    None => FairPlayScore::default(),
+                },
+            }
+        }
+        (None, Some(home), Some(away)) => {
+            let (home_name, away_name) = (home, away);
+            let home = teams.resolve(&home_name)?;
+            let away = teams.resolve(&away_name)?;
+            if home == away {
+                return Err(ResultParseError::Teams(format!(
+                    "{} - {}",
+                    home_name, away_name
+                )));
+            }
+            let fair_play = match &cards {
+                Some(cards) => parse_cards(cards, home, away, teams)?,
+                None => FairPlayScore::default(),
+            };
+            ResultEntry {
+                home,
+                away,
+                score,
+                fair_play,
+            }
+            .submission(groups)?
+        }
+        (None, _, _) => {
+            return Err(ResultParseError::Csv(String::from(
+                "Neither a game id nor two teams",
+            )))
+        }
+    };
+    let kickoff = find_unplayed(groups, submission.game_id)?.date();
+    Ok(ImportedResult {
+        submission,
+        kickoff,
+        has_cards: cards.is_some(),
+    })
+}
+
+fn find_unplayed(groups: &Groups, game_id: GameId) -> Result<&UnplayedGroupGame, ResultParseError> {
+    if groups
+        .values()
+        .flat_map(|group| group.played_games())
+        .any(|game| game.id == game_id)
+    {
+        return Err(ResultParseError::AlreadyPlayed(game_id));
+    }
+    groups
+        .values()
+        .flat_map(|group| group.unplayed_games())
+        .find(|game| game.id == game_id)
+        .ok_or(ResultParseError::UnknownGame(game_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::{FairPlayValue, FifaFairPlayValue};
+    use crate::group::mock_data;
+
+    #[test]
+    fn rows() {
+        let (groups, teams) = mock_data();
+        let teams = TeamResolver::new(&teams);
+        // Game 1 Sweden - England is played, game 2 France - Brazil is not
+        let input = "game,home,away,home_goals,away_goals,cards
+2,BRA,FRA,1,0,\"yellow: bra 1\"
+1,,,2,0,
+,France,Brazil,0,0,
+9,,,1,1,
+,Sweden,France,x,1,";
+        let rows = parse_result_csv(input, &teams, &groups);
+        assert_eq!(rows.len(), 5);
+        let first = rows[0].result.clone().unwrap();
+        assert_eq!(first.submission.game_id, GameId::from(2));
+        assert_eq!(first.submission.score, Score::from((0, 1)));
+        assert!(first.has_cards);
+        assert_eq!(
+            FifaFairPlayValue::from_fair_play(&first.submission.fair_play.away),
+            FifaFairPlayValue::from(1)
+        );
+        assert_eq!(
+            rows[1].result.clone().unwrap_err(),
+            ResultParseError::AlreadyPlayed(GameId::from(1))
+        );
+        assert_eq!(
+            rows[2].result.clone().unwrap_err(),
+            ResultParseError::DuplicateGame(GameId::from(2))
+        );
+        assert_eq!(
+            rows[3].result.clone().unwrap_err(),
+            ResultParseError::UnknownGame(GameId::from(9))
+        );
+        assert!(matches!(rows[4].result, Err(ResultParseError::Csv(_))));
+        assert_eq!(in_kickoff_order(&rows).len(), 1);
+    }
+}
@@ -1,10 +1,29 @@
 //! Player
 //!
 //! Player/User/Better representation
-use crate::game::{GameId, Score};
-use crate::group::game::PlayedGroupGame;
+use crate::error::ErrorCode;
+use crate::game::{Game, GameId, Score};
+use crate::group::game::{PlayedGroupGame, UnplayedGroupGame};
+use crate::group::Groups;
+use crate::team::{TeamId, Teams};
+use crate::Date;
 use derive_more::{Display, From, Into};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// Upper bound for the predicted number of goals for a single team.
+///
+/// Anything above this is considered a typo rather than a prediction.
+pub const MAX_PREDICTED_GOALS: u32 = 20;
+
+/// Upper bound for the number of characters in a player name
+pub const MAX_NAME_LEN: usize = 32;
+
+/// Upper bound for the number of characters in an emoji avatar,
+/// enough for flags and multi-person sequences
+pub const MAX_EMOJI_LEN: usize = 8;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
@@ -13,6 +32,10 @@ pub struct Player {
 }
 
 impl Player {
+    pub fn new(id: PlayerId, name: String) -> Self {
+        Player { name, id }
+    }
+
     pub fn dummy() -> Self {
         Player {
             name: String::from("Dummy"),
@@ -23,10 +46,96 @@ impl Player {
     pub fn id(&self) -> PlayerId {
         self.id
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Player preferences
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Profile {
+    /// IANA time zone name, e.g. "Europe/Stockholm".
+    /// Kickoff times are shown in UTC if not set.
+    pub timezone: Option<String>,
+    /// Image URL or emoji, see [`validate_avatar`]
+    #[serde(default)]
+    pub avatar: Option<String>,
+}
+
+/// Name and emoji of a player in a league
+///
+/// The same account can be "Zlatan_4ever" in one league and go by the real name in another.
+/// Unset fields fall back to the name and avatar of the player.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LeagueAlias {
+    /// See [`validate_name`]
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// See [`validate_emoji`]
+    #[serde(default)]
+    pub emoji: Option<String>,
+}
+
+/// Check a display name
+///
+/// # Errors
+///
+/// A name is not blank, at most [`MAX_NAME_LEN`] characters and has no control characters.
+pub fn validate_name(name: &str) -> Result<(), ProfileError> {
+    if name.trim().is_empty() {
+        Err(ProfileError::EmptyName)
+    } else if name.chars().count() > MAX_NAME_LEN {
+        Err(ProfileError::NameTooLong(MAX_NAME_LEN))
+    } else if name.chars().any(char::is_control) {
+        Err(ProfileError::InvalidName(String::from(name)))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check an avatar
+///
+/// # Errors
+///
+/// An avatar is either an http(s) URL without whitespace or an emoji: at most [`MAX_EMOJI_LEN`]
+/// characters, none of them ascii.
+pub fn validate_avatar(avatar: &str) -> Result<(), ProfileError> {
+    let is_url = (avatar.starts_with("https://") || avatar.starts_with("http://"))
+        && !avatar.chars().any(char::is_whitespace);
+    if is_url || validate_emoji(avatar).is_ok() {
+        Ok(())
+    } else {
+        Err(ProfileError::InvalidAvatar(String::from(avatar)))
+    }
+}
+
+/// Check an emoji
+///
+/// # Errors
+///
+/// An emoji is at most [`MAX_EMOJI_LEN`] characters, none of them ascii or whitespace.
+pub fn validate_emoji(emoji: &str) -> Result<(), ProfileError> {
+    if !emoji.is_empty()
+        && emoji.chars().count() <= MAX_EMOJI_LEN
+        && !emoji.chars().any(|c| c.is_ascii() || c.is_whitespace())
+    {
+        Ok(())
+    } else {
+        Err(ProfileError::InvalidEmoji(String::from(emoji)))
+    }
+}
+
+/// Name of an erased player
+///
+/// Erased accounts are anonymized rather than removed, their predictions still count in the
+/// leaderboards.
+pub fn anonymous_name(id: PlayerId) -> String {
+    format!("Deleted player {}", id)
 }
 
 /// Numeric player id for db.
-#[derive(Display, Debug, Copy, Clone, From, Into, Serialize, Deserialize)]
+#[derive(Display, Debug, Copy, Clone, From, Into, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct PlayerId(i32);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,7 +159,7 @@ impl PlayerPredictions {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct Prediction(pub GameId, pub Score);
 
 impl From<PlayedGroupGame> for Prediction {
@@ -58,3 +167,422 @@ impl From<PlayedGroupGame> for Prediction {
         Prediction(game.id, game.score)
     }
 }
+
+/// A single, timestamped, version of a prediction
+///
+/// A player may revise a prediction any number of times before the deadline.
+/// Every revision is kept and the effective prediction is derived from the versions,
+/// see [`effective_prediction`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PredictionVersion {
+    pub pred: Prediction,
+    pub submitted: Date,
+}
+
+impl PredictionVersion {
+    pub fn new(pred: Prediction, submitted: Date) -> Self {
+        PredictionVersion { pred, submitted }
+    }
+}
+
+/// Effective prediction
+///
+/// The latest version submitted strictly before the `deadline`.
+/// Versions submitted at or after the deadline are ignored.
+/// Returns `None` if there is no version before the deadline.
+pub fn effective_prediction<'a>(
+    versions: impl Iterator<Item = &'a PredictionVersion>,
+    deadline: Date,
+) -> Option<Prediction> {
+    versions
+        .filter(|version| version.submitted.is_before(deadline))
+        .max_by_key(|version| version.submitted)
+        .map(|version| version.pred)
+}
+
+/// Check if moving a deadline changes the effective prediction
+///
+/// When a game is rescheduled to an earlier kickoff, versions submitted between the new and the
+/// old kickoff no longer count, see [`effective_prediction`].
+/// Without an `old` kickoff the game was open and the latest version counted.
+pub fn deadline_change_affects(
+    versions: &[PredictionVersion],
+    old: Option<Date>,
+    new: Date,
+) -> bool {
+    let old_prediction = match old {
+        Some(old) => effective_prediction(versions.iter(), old),
+        None => versions
+            .iter()
+            .max_by_key(|version| version.submitted)
+            .map(|version| version.pred),
+    };
+    old_prediction != effective_prediction(versions.iter(), new)
+}
+
+/// Game open for predictions, see [`upcoming_games`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct UpcomingGame {
+    pub game: GameId,
+    pub kickoff: Date,
+    /// Only versions submitted strictly before the deadline count, see [`effective_prediction`]
+    pub deadline: Date,
+    /// Whole seconds until the deadline
+    pub seconds_left: i64,
+    /// The player has an effective prediction for the game
+    pub submitted: bool,
+}
+
+/// Unplayed games still open for predictions at `now`, earliest deadline first
+///
+/// The deadline of a game is its kickoff, see [`Prediction::validate`].
+/// `preds` are the effective predictions of a player.
+pub fn upcoming_games(groups: &Groups, preds: &[Prediction], now: Date) -> Vec<UpcomingGame> {
+    let mut games: Vec<UpcomingGame> = groups
+        .values()
+        .flat_map(|group| group.unplayed_games())
+        .filter(|game| game.is_before_kickoff(now))
+        .map(|game| UpcomingGame {
+            game: game.id,
+            kickoff: game.date(),
+            deadline: game.date(),
+            seconds_left: now.seconds_until(game.date()),
+            submitted: preds.iter().any(|Prediction(id, _)| *id == game.id),
+        })
+        .collect();
+    games.sort_by_key(|game| (game.deadline, game.game));
+    games
+}
+
+impl Prediction {
+    /// Validate prediction for an upcoming game
+    ///
+    /// # Errors
+    ///
+    /// The prediction is rejected if
+    ///
+    /// - the game has already kicked off (at `now`),
+    /// - any of the teams in the game are not in `teams`,
+    /// - the predicted score is unreasonably large, see [`MAX_PREDICTED_GOALS`].
+    pub fn validate(
+        &self,
+        game: &UnplayedGroupGame,
+        teams: &Teams,
+        now: Date,
+    ) -> Result<(), PredictionError> {
+        if !game.is_before_kickoff(now) {
+            return Err(PredictionError::DeadlinePassed(self.0));
+        }
+        self.validate_open(game, teams)
+    }
+
+    /// Validate prediction for a game without a kickoff yet, which is always open
+    ///
+    /// # Errors
+    ///
+    /// Like [`Prediction::validate`], except for the deadline.
+    pub fn validate_open(
+        &self,
+        game: &UnplayedGroupGame,
+        teams: &Teams,
+    ) -> Result<(), PredictionError> {
+        if let Some(team) = [game.home_team(), game.away_team()]
+            .iter()
+            .find(|team| !teams.contains_key(*team))
+        {
+            return Err(PredictionError::UnknownTeam(*team));
+        }
+        if u32::from(self.1.home) > MAX_PREDICTED_GOALS
+            || u32::from(self.1.away) > MAX_PREDICTED_GOALS
+        {
+            return Err(PredictionError::UnreasonableScore(self.0));
+        }
+        Ok(())
+    }
+}
+
+/// Validate a full sheet of predictions
+///
+/// Every prediction is checked individually against the group games, see [`Prediction::validate`].
+/// Predictions for games that are already played are rejected, as are predictions for games not
+/// found in any group.
+/// The `unscheduled` games have no kickoff yet and are open, see [`Prediction::validate_open`].
+/// Returns a report entry for each prediction, in the same order as `preds`.
+pub fn validate_predictions<'a>(
+    preds: impl Iterator<Item = &'a Prediction>,
+    groups: &Groups,
+    teams: &Teams,
+    unscheduled: &HashSet<GameId>,
+    now: Date,
+) -> Vec<(Prediction, Result<(), PredictionError>)> {
+    preds
+        .map(|pred| {
+            let unplayed = groups
+                .values()
+                .flat_map(|group| group.unplayed_games())
+                .find(|game| game.id == pred.0);
+            let res = match unplayed {
+                Some(game) if unscheduled.contains(&game.id) => pred.validate_open(game, teams),
+                Some(game) => pred.validate(game, teams, now),
+                None if groups
+                    .values()
+                    .flat_map(|group| group.played_games())
+                    .any(|game| game.id == pred.0) =>
+                {
+                    Err(PredictionError::DeadlinePassed(pred.0))
+                }
+                None => Err(PredictionError::UnknownGame(pred.0)),
+            };
+            (*pred, res)
+        })
+        .collect()
+}
+
+/// Per prediction outcome of a bulk submission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredictionReport {
+    pub game_id: GameId,
+    pub status: PredictionStatus,
+}
+
+impl PredictionReport {
+    pub fn new(game_id: GameId, res: &Result<(), PredictionError>) -> Self {
+        let status = match res {
+            Ok(()) => PredictionStatus::Accepted,
+            Err(err) => PredictionStatus::Rejected {
+                code: String::from(err.code()),
+                message: err.to_string(),
+            },
+        };
+        PredictionReport { game_id, status }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PredictionStatus {
+    Accepted,
+    /// Rejected with an [`ErrorCode`], e.g. `PREDICTION_DEADLINE_PASSED`
+    Rejected {
+        code: String,
+        message: String,
+    },
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum PredictionError {
+    #[error("No group game with id {0}")]
+    UnknownGame(GameId),
+    #[error("No team with id {0}")]
+    UnknownTeam(TeamId),
+    #[error("Deadline passed for game {0}")]
+    DeadlinePassed(GameId),
+    #[error("Unreasonable score for game {0}")]
+    UnreasonableScore(GameId),
+}
+
+impl ErrorCode for PredictionError {
+    fn code(&self) -> &'static str {
+        match self {
+            PredictionError::UnknownGame(_) => "UNKNOWN_GAME",
+            PredictionError::UnknownTeam(_) => "UNKNOWN_TEAM",
+            PredictionError::DeadlinePassed(_) => "PREDICTION_DEADLINE_PASSED",
+            PredictionError::UnreasonableScore(_) => "UNREASONABLE_SCORE",
+        }
+    }
+
+    fn details(&self) -> Option<Value> {
+        Some(match self {
+            PredictionError::UnknownTeam(team) => json!({ "team": team }),
+            PredictionError::UnknownGame(game)
+            | PredictionError::DeadlinePassed(game)
+            | PredictionError::UnreasonableScore(game) => json!({ "game": game }),
+        })
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ProfileError {
+    #[error("Empty name")]
+    EmptyName,
+    #[error("Name longer than {0} characters")]
+    NameTooLong(usize),
+    #[error("Invalid name '{0}'")]
+    InvalidName(String),
+    #[error("Avatar '{0}' is neither an http(s) URL nor an emoji")]
+    InvalidAvatar(String),
+    #[error("'{0}' is not an emoji")]
+    InvalidEmoji(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group::{mock_data, GroupId};
+    use crate::utils::clock::{Clock, TestClock};
+
+    fn future() -> Date {
+        "2100-01-01T00:00:00+00:00".parse().unwrap()
+    }
+
+    #[test]
+    fn upcoming() {
+        // Unplayed games 2, 3 and 4, all kicking off at the mock date
+        let groups = mock_data().0;
+        let before = "1632-11-05T10:00:00+00:00".parse::<Date>().unwrap();
+        let preds = vec![Prediction(GameId::from(3), Score::new(1, 0))];
+        let upcoming = upcoming_games(&groups, &preds, before);
+        let submitted: Vec<(u32, bool)> = upcoming
+            .iter()
+            .map(|game| (u32::from(game.game), game.submitted))
+            .collect();
+        assert_eq!(submitted, vec![(2, false), (3, true), (4, false)]);
+        assert_eq!(upcoming[0].seconds_left, before.seconds_until(Date::mock()));
+        assert!(upcoming[0].seconds_left > 0);
+        assert!(upcoming_games(&groups, &preds, Date::mock()).is_empty());
+    }
+
+    #[test]
+    fn earlier_deadline() {
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        let version = |home, submitted| {
+            PredictionVersion::new(
+                Prediction(GameId::from(1), Score::new(home, 0)),
+                date(submitted),
+            )
+        };
+        let versions = vec![
+            version(1, "2021-06-10T12:00:00+02:00"),
+            version(2, "2021-06-11T10:00:00+02:00"),
+        ];
+        let old = Some(date("2021-06-11T21:00:00+02:00"));
+        assert!(deadline_change_affects(
+            &versions,
+            old,
+            date("2021-06-11T09:00:00+02:00")
+        ));
+        assert!(!deadline_change_affects(
+            &versions,
+            old,
+            date("2021-06-12T21:00:00+02:00")
+        ));
+        // Without a kickoff the latest version counted
+        assert!(!deadline_change_affects(
+            &versions,
+            None,
+            date("2021-06-12T21:00:00+02:00")
+        ));
+        assert!(deadline_change_affects(
+            &versions,
+            None,
+            date("2021-06-11T09:00:00+02:00")
+        ));
+    }
+
+    #[test]
+    fn latest_version_before_deadline() {
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        let version = |home, submitted| {
+            PredictionVersion::new(
+                Prediction(GameId::from(1), Score::new(home, 0)),
+                date(submitted),
+            )
+        };
+        let versions = vec![
+            version(1, "2021-06-10T12:00:00+02:00"),
+            version(3, "2021-06-11T20:00:00+02:00"),
+            version(2, "2021-06-11T10:00:00+02:00"),
+            version(4, "2021-06-11T21:00:00+02:00"),
+        ];
+        let effective =
+            effective_prediction(versions.iter(), date("2021-06-11T21:00:00+02:00")).unwrap();
+        assert_eq!(effective.1, Score::new(3, 0));
+        assert!(effective_prediction(versions.iter(), date("2021-06-01T21:00:00+02:00")).is_none());
+    }
+
+    #[test]
+    fn deadline_passed() {
+        let (groups, teams) = mock_data();
+        let pred = Prediction(GameId::from(2), Score::new(1, 0));
+        let game = groups
+            .get(&GroupId::from('A'))
+            .unwrap()
+            .unplayed_games()
+            .next()
+            .unwrap();
+        let clock = TestClock::new("2021-06-11 19:00".parse().unwrap());
+        assert_eq!(
+            pred.validate(game, &teams, clock.now()),
+            Err(PredictionError::DeadlinePassed(GameId::from(2)))
+        );
+    }
+
+    #[test]
+    fn unscheduled_game_is_open() {
+        let (groups, teams) = mock_data();
+        let preds = vec![Prediction(GameId::from(2), Score::new(1, 0))];
+        let now: Date = "2021-06-11 19:00".parse().unwrap();
+        let unscheduled: HashSet<GameId> = vec![GameId::from(2)].into_iter().collect();
+        let report = validate_predictions(preds.iter(), &groups, &teams, &unscheduled, now);
+        assert_eq!(report[0].1, Ok(()));
+        let report = validate_predictions(preds.iter(), &groups, &teams, &HashSet::new(), now);
+        assert_eq!(
+            report[0].1,
+            Err(PredictionError::DeadlinePassed(GameId::from(2)))
+        );
+    }
+
+    #[test]
+    fn sheet_report() {
+        let (groups, teams) = mock_data();
+        let date = future();
+        let groups = groups
+            .into_iter()
+            .map(|(id, group)| {
+                let games = group
+                    .unplayed_games()
+                    .map(|game| {
+                        UnplayedGroupGame::try_new(game.id, game.home, game.away, date).unwrap()
+                    })
+                    .collect();
+                let played = group.played_games().cloned().collect();
+                (id, crate::group::Group::try_new(games, played).unwrap())
+            })
+            .collect();
+        let preds = vec![
+            Prediction(GameId::from(1), Score::new(1, 0)),
+            Prediction(GameId::from(2), Score::new(1, 0)),
+            Prediction(GameId::from(3), Score::new(21, 0)),
+            Prediction(GameId::from(9), Score::new(1, 0)),
+        ];
+        let report: Vec<_> =
+            validate_predictions(preds.iter(), &groups, &teams, &HashSet::new(), Date::mock())
+                .into_iter()
+                .map(|(_, res)| res)
+                .collect();
+        assert_eq!(
+            report,
+            vec![
+                Err(PredictionError::DeadlinePassed(GameId::from(1))),
+                Ok(()),
+                Err(PredictionError::UnreasonableScore(GameId::from(3))),
+                Err(PredictionError::UnknownGame(GameId::from(9))),
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_validation() {
+        assert!(validate_name("Anna").is_ok());
+        assert_eq!(validate_name("  "), Err(ProfileError::EmptyName));
+        assert!(validate_name(&"x".repeat(MAX_NAME_LEN + 1)).is_err());
+        assert!(validate_name("tab\tname").is_err());
+        assert!(validate_avatar("https://example.com/me.png").is_ok());
+        assert!(validate_avatar("⚽").is_ok());
+        assert!(validate_avatar("🇸🇪").is_ok());
+        assert!(validate_avatar("me.png").is_err());
+        assert!(validate_avatar("https://example.com/a b.png").is_err());
+        assert!(validate_avatar("").is_err());
+        assert!(validate_emoji("🦁").is_ok());
+        assert!(validate_emoji(":lion:").is_err());
+    }
+}
@@ -0,0 +1,630 @@
+//! Knockout bracket
+//!
+//! A [`Bracket`] is the template of the playoff: for every game, where its teams come from.
+//! Combined with the group results and the winners of the played playoff games,
+//! it is laid out as a list of [`BracketNode`]s, see [`Bracket::layout`].
+use crate::error::ErrorCode;
+use crate::game::GameId;
+use crate::group::cross_group::{CrossGroupEntry, CrossGroupRules};
+use crate::group::order::{GroupRank, Rules, Tiebreaker};
+use crate::group::{GroupError, GroupId, Groups};
+use crate::playoff::RoundIdx;
+use crate::stage::advancement::{advance, AdvancementRule};
+use crate::stage::GroupStage;
+use crate::team::TeamId;
+use crate::Date;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Origin of a team in a playoff game
+///
+/// The text representation, see [`FromStr`], is compact enough to store in a single column:
+///
+/// ```
+/// # use wwc_core::playoff::bracket::Feeder;
+/// # use wwc_core::group::GroupId;
+/// let feeder: Feeder = "3ADEF".parse().unwrap();
+/// assert_eq!(
+///     feeder,
+///     Feeder::ThirdPlace(vec![GroupId::from('A'), GroupId::from('D'), GroupId::from('E'), GroupId::from('F')])
+/// );
+/// assert_eq!(feeder.to_string(), "3ADEF");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Feeder {
+    /// "1A"
+    GroupWinner(GroupId),
+    /// "2A"
+    GroupRunnerUp(GroupId),
+    /// One of the best third placed teams, from one of the listed groups, e.g. "3ABC".
+    ///
+    /// Which team goes where depends on tournament specific tables and is not resolved.
+    ThirdPlace(Vec<GroupId>),
+    /// "W37"
+    Winner(GameId),
+    /// "L49"
+    Loser(GameId),
+}
+
+impl Feeder {
+    /// The playoff game feeding this team, if any
+    pub fn game(&self) -> Option<GameId> {
+        match self {
+            Feeder::Winner(id) | Feeder::Loser(id) => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Feeder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Feeder::GroupWinner(id) => write!(f, "1{}", id),
+            Feeder::GroupRunnerUp(id) => write!(f, "2{}", id),
+            Feeder::ThirdPlace(ids) => write!(f, "3{}", ids.iter().join("")),
+            Feeder::Winner(id) => write!(f, "W{}", id),
+            Feeder::Loser(id) => write!(f, "L{}", id),
+        }
+    }
+}
+
+impl FromStr for Feeder {
+    type Err = BracketError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || BracketError::FeederParse(String::from(s));
+        let mut chars = s.chars();
+        let kind = chars.next().ok_or_else(err)?;
+        let rest = chars.as_str();
+        let groups = || {
+            rest.chars()
+                .map(|id| GroupId::try_new(id).map(GroupId::into_uppercase))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| err())
+        };
+        let single_group = || match groups()?.as_slice() {
+            [id] => Ok(*id),
+            _ => Err(err()),
+        };
+        let game = || rest.parse::<u32>().map(GameId::from).map_err(|_| err());
+        match kind {
+            '1' => Ok(Feeder::GroupWinner(single_group()?)),
+            '2' => Ok(Feeder::GroupRunnerUp(single_group()?)),
+            '3' if !rest.is_empty() => Ok(Feeder::ThirdPlace(groups()?)),
+            'W' | 'w' => Ok(Feeder::Winner(game()?)),
+            'L' | 'l' => Ok(Feeder::Loser(game()?)),
+            _ => Err(err()),
+        }
+    }
+}
+
+/// Playoff game in the bracket template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketGame {
+    pub id: GameId,
+    pub round: RoundIdx,
+    pub home: Feeder,
+    pub away: Feeder,
+    pub kickoff: Date,
+}
+
+/// Playoff template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bracket(Vec<BracketGame>);
+
+impl Bracket {
+    /// Fallible constructor.
+    ///
+    /// # Errors
+    ///
+    /// - Every game must have a unique id.
+    /// - Winners and losers must come from a game in an earlier round.
+    pub fn try_new(games: Vec<BracketGame>) -> Result<Self, BracketError> {
+        let rounds: HashMap<GameId, RoundIdx> =
+            games.iter().map(|game| (game.id, game.round)).collect();
+        if rounds.len() != games.len() {
+            return Err(BracketError::GameIdsNotUnique);
+        }
+        for game in &games {
+            for feeder_game in [&game.home, &game.away].iter().filter_map(|x| x.game()) {
+                match rounds.get(&feeder_game) {
+                    Some(round) if *round < game.round => {}
+                    _ => return Err(BracketError::InvalidFeeder(game.id, feeder_game)),
+                }
+            }
+        }
+        Ok(Bracket(games))
+    }
+
+    pub fn games(&self) -> impl Iterator<Item = &BracketGame> {
+        self.0.iter()
+    }
+
+    /// Layout of the bracket
+    ///
+    /// Teams from the groups are resolved once the group is finished, ordered by `rules`.
+    /// Third placed teams are resolved once every group is finished, the best ones ranked by
+    /// `third_place_rules` qualify, see [`Bracket::assign_third_places`].
+    /// Which teams advance from the groups follows from the feeders, see [`Bracket::advancement`].
+    /// Teams from playoff games are resolved from `winners`, a map from playoff game to winning
+    /// team.
+    ///
+    /// The nodes are sorted by round and slot, where the final is in slot 0 of the last round
+    /// and the games feeding the winners to slot `s` are in slots `2s` (home) and `2s + 1` (away)
+    /// of the previous round.
+    /// Games not feeding a winner on, e.g. a third place play-off, come after the other games
+    /// of their round.
+    pub fn layout<T: Tiebreaker, U: Tiebreaker>(
+        &self,
+        groups: &Groups,
+        rules: &Rules<T>,
+        third_place_rules: &CrossGroupRules<U>,
+        winners: &HashMap<GameId, TeamId>,
+    ) -> Result<Vec<BracketNode>, BracketError> {
+        let stage = GroupStage::new(groups, rules).with_cross_group_rules(third_place_rules);
+        let entrants = advance(&stage, &self.advancement())?;
+        let qualified = |group: &GroupId, rank: GroupRank| {
+            entrants
+                .iter()
+                .find(|entrant| entrant.group == Some(*group) && entrant.rank == rank)
+                .map(|entrant| entrant.team)
+        };
+        let thirds: Vec<CrossGroupEntry> = entrants
+            .iter()
+            .filter(|entrant| entrant.rank == GroupRank(2))
+            .filter_map(|entrant| {
+                entrant.group.map(|group| CrossGroupEntry {
+                    group,
+                    team: entrant.team,
+                })
+            })
+            .collect();
+        let third_places = self.assign_third_places(&thirds);
+        let slots = self.slots();
+
+        let mut resolved: HashMap<GameId, (Option<TeamId>, Option<TeamId>)> = HashMap::new();
+        let mut nodes: Vec<BracketNode> = Vec::new();
+        for game in self.games().sorted_by_key(|game| (game.round, game.id)) {
+            let team = |feeder: &Feeder, side: usize| match feeder {
+                Feeder::GroupWinner(id) => qualified(id, GroupRank::WINNER),
+                Feeder::GroupRunnerUp(id) => qualified(id, GroupRank::RUNNER_UP),
+                Feeder::ThirdPlace(_) => third_places.get(&(game.id, side)).copied(),
+                Feeder::Winner(id) => resolved.get(id).and_then(|(winner, _)| *winner),
+                Feeder::Loser(id) => resolved.get(id).and_then(|(_, loser)| *loser),
+            };
+            let home = team(&game.home, 0);
+            let away = team(&game.away, 1);
+            let winner = winners.get(&game.id).copied();
+            let loser = match (home, away, winner) {
+                (Some(home), Some(away), Some(winner)) if winner == home => Some(away),
+                (Some(home), Some(away), Some(winner)) if winner == away => Some(home),
+                _ => None,
+            };
+            resolved.insert(game.id, (winner, loser));
+            nodes.push(BracketNode {
+                game_id: game.id,
+                round: game.round,
+                slot: slots[&game.id],
+                kickoff: game.kickoff,
+                home: BracketTeam {
+                    feeder: game.home.clone(),
+                    team: home,
+                },
+                away: BracketTeam {
+                    feeder: game.away.clone(),
+                    team: away,
+                },
+                winner,
+            });
+        }
+        nodes.sort_by_key(|node| (node.round, node.slot));
+        Ok(nodes)
+    }
+
+    /// Teams advancing from the group stage to the bracket
+    ///
+    /// The best teams of every group, as many as the bracket takes from a single group,
+    /// and one of the best third placed teams for every [`Feeder::ThirdPlace`].
+    pub fn advancement(&self) -> Vec<AdvancementRule> {
+        let feeders = || self.games().flat_map(|game| vec![&game.home, &game.away]);
+        let top = feeders()
+            .map(|feeder| match feeder {
+                Feeder::GroupWinner(_) => 1,
+                Feeder::GroupRunnerUp(_) => 2,
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0);
+        let num_thirds = feeders()
+            .filter(|feeder| matches!(feeder, Feeder::ThirdPlace(_)))
+            .count();
+        let mut rules = Vec::new();
+        if top > 0 {
+            rules.push(AdvancementRule::Top(top));
+        }
+        if num_thirds > 0 {
+            rules.push(AdvancementRule::BestAtRank {
+                rank: GroupRank(2),
+                num: num_thirds,
+            });
+        }
+        rules
+    }
+
+    /// Place the qualified third placed teams in the bracket
+    ///
+    /// `thirds` are the third placed teams, best first, the best ones qualify,
+    /// one for every [`Feeder::ThirdPlace`] in the bracket.
+    /// Every qualified team is placed in a game open to its group,
+    /// games are filled in game id order and trying the groups in alphabetical order.
+    /// Tournaments publish a table of the placement for every combination of qualified groups,
+    /// it matches this one as long as the table is the alphabetically first valid placement.
+    ///
+    /// Returns a map from (game, side) to team, where the home side is 0 and the away side 1.
+    /// The map is empty if there are too few third placed teams or no valid placement.
+    pub fn assign_third_places(
+        &self,
+        thirds: &[CrossGroupEntry],
+    ) -> HashMap<(GameId, usize), TeamId> {
+        let open: Vec<((GameId, usize), &[GroupId])> = self
+            .games()
+            .sorted_by_key(|game| game.id)
+            .flat_map(|game| {
+                [&game.home, &game.away]
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(side, feeder)| match feeder {
+                        Feeder::ThirdPlace(ids) => Some(((game.id, side), &ids[..])),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        if open.is_empty() || thirds.len() < open.len() {
+            return HashMap::new();
+        }
+        let qualified: HashMap<GroupId, TeamId> = thirds[..open.len()]
+            .iter()
+            .map(|entry| (entry.group, entry.team))
+            .collect();
+        fn place(
+            open: &[((GameId, usize), &[GroupId])],
+            available: &mut Vec<GroupId>,
+            placed: &mut Vec<GroupId>,
+        ) -> bool {
+            let (_, allowed) = match open.first() {
+                Some(slot) => slot,
+                None => return true,
+            };
+            for idx in 0..available.len() {
+                if !allowed.contains(&available[idx]) {
+                    continue;
+                }
+                let group = available.remove(idx);
+                placed.push(group);
+                if place(&open[1..], available, placed) {
+                    return true;
+                }
+                placed.pop();
+                available.insert(idx, group);
+            }
+            false
+        }
+        let mut available: Vec<GroupId> = qualified.keys().copied().sorted().collect();
+        let mut placed = Vec::new();
+        if !place(&open, &mut available, &mut placed) {
+            return HashMap::new();
+        }
+        open.iter()
+            .zip(placed)
+            .map(|((slot, _), group)| (*slot, qualified[&group]))
+            .collect()
+    }
+
+    /// Vertical slot of every game, see [`Bracket::layout`]
+    fn slots(&self) -> HashMap<GameId, usize> {
+        // Game fed by the winner of a game, and on which side.
+        let parents: HashMap<GameId, (GameId, usize)> = self
+            .games()
+            .flat_map(|game| {
+                let side = |feeder: &Feeder, side| match feeder {
+                    Feeder::Winner(id) => Some((*id, (game.id, side))),
+                    _ => None,
+                };
+                side(&game.home, 0).into_iter().chain(side(&game.away, 1))
+            })
+            .collect();
+        let mut slots = HashMap::new();
+        let rounds = self.games().into_group_map_by(|game| game.round);
+        for round in rounds.keys().sorted().rev() {
+            let mut orphans = Vec::new();
+            for game in rounds[round].iter().sorted_by_key(|game| game.id) {
+                match parents.get(&game.id) {
+                    Some((parent, side)) => {
+                        slots.insert(game.id, 2 * slots[parent] + side);
+                    }
+                    None => orphans.push(game.id),
+                }
+            }
+            // Winner-fed games first, e.g. the final before the third place play-off.
+            orphans.sort_by_key(|id| {
+                let game = rounds[round].iter().find(|game| game.id == *id).unwrap();
+                let fed_by_loser = |feeder: &Feeder| matches!(feeder, Feeder::Loser(_));
+                fed_by_loser(&game.home) || fed_by_loser(&game.away)
+            });
+            let taken: HashSet<usize> = rounds[round]
+                .iter()
+                .filter_map(|game| slots.get(&game.id).copied())
+                .collect();
+            let mut free = (0..).filter(|slot| !taken.contains(slot));
+            for id in orphans {
+                slots.insert(id, free.next().unwrap());
+            }
+        }
+        slots
+    }
+}
+
+/// Playoff game in a bracket layout, see [`Bracket::layout`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketNode {
+    pub game_id: GameId,
+    pub round: RoundIdx,
+    /// Vertical position in the round
+    pub slot: usize,
+    pub kickoff: Date,
+    pub home: BracketTeam,
+    pub away: BracketTeam,
+    pub winner: Option<TeamId>,
+}
+
+/// Team in a [`BracketNode`], `None` until it is decided
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketTeam {
+    pub feeder: Feeder,
+    pub team: Option<TeamId>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BracketError {
+    #[error("Could not parse feeder '{0}'")]
+    FeederParse(String),
+    #[error("Game Id's in bracket not unique")]
+    GameIdsNotUnique,
+    #[error("Game {0} fed by game {1}, which is not in an earlier round")]
+    InvalidFeeder(GameId, GameId),
+    #[error("Team {1} does not play playoff game {0}")]
+    WinnerNotInGame(GameId, TeamId),
+    #[error("Group error: {0}")]
+    Group(#[from] GroupError),
+}
+
+impl ErrorCode for BracketError {
+    fn code(&self) -> &'static str {
+        match self {
+            BracketError::FeederParse(_) => "BRACKET_FEEDER_PARSE",
+            BracketError::GameIdsNotUnique => "BRACKET_GAME_IDS_NOT_UNIQUE",
+            BracketError::InvalidFeeder(..) => "BRACKET_INVALID_FEEDER",
+            BracketError::WinnerNotInGame(..) => "PLAYOFF_WINNER_NOT_IN_GAME",
+            BracketError::Group(err) => err.code(),
+        }
+    }
+
+    fn details(&self) -> Option<Value> {
+        match self {
+            BracketError::InvalidFeeder(game, feeder) => {
+                Some(json!({ "game": game, "feeder": feeder }))
+            }
+            BracketError::WinnerNotInGame(game, team) => {
+                Some(json!({ "game": game, "team": team }))
+            }
+            BracketError::Group(err) => err.details(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::group::cross_group::{self, GameFilter};
+    use crate::group::game::{PlayedGroupGame, UnplayedGroupGame};
+    use crate::group::order::{fifa_2018, UefaRanking};
+    use crate::group::Group;
+    use crate::stage::tests::{lower_id_wins, played_round_robin, teams};
+
+    fn third_place_rules() -> CrossGroupRules<UefaRanking> {
+        let ranking = (1..=8).map(|id| (TeamId(id), id.into())).collect();
+        cross_group::fifa_2026(UefaRanking::try_new(&[], ranking).unwrap())
+    }
+
+    fn game(id: u32, round: u8, home: &str, away: &str) -> BracketGame {
+        BracketGame {
+            id: GameId::from(id),
+            round: RoundIdx(round),
+            home: home.parse().unwrap(),
+            away: away.parse().unwrap(),
+            kickoff: Date::mock(),
+        }
+    }
+
+    fn mock_bracket() -> Bracket {
+        Bracket::try_new(vec![
+            game(10, 0, "1A", "2B"),
+            game(11, 0, "1B", "2A"),
+            game(12, 0, "1C", "3ABD"),
+            game(13, 0, "1D", "2C"),
+            game(20, 1, "W11", "W10"),
+            game(21, 1, "W12", "W13"),
+            game(31, 2, "L20", "L21"),
+            game(30, 2, "W20", "W21"),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn feeder_round_trip() {
+        for text in &["1A", "2B", "3ADEF", "W37", "L49"] {
+            let feeder: Feeder = text.parse().unwrap();
+            assert_eq!(&feeder.to_string(), text);
+        }
+        assert_eq!(
+            "w7".parse::<Feeder>().unwrap(),
+            Feeder::Winner(GameId::from(7))
+        );
+        assert!("1AB".parse::<Feeder>().is_err());
+        assert!("3".parse::<Feeder>().is_err());
+        assert!("X1".parse::<Feeder>().is_err());
+    }
+
+    #[test]
+    fn invalid_feeder() {
+        assert_eq!(
+            Bracket::try_new(vec![game(10, 0, "W11", "2B"), game(11, 0, "1B", "2A")]),
+            Err(BracketError::InvalidFeeder(
+                GameId::from(10),
+                GameId::from(11)
+            ))
+        );
+    }
+
+    #[test]
+    fn slots() {
+        let slots: Vec<(u32, u8, usize)> = mock_bracket()
+            .layout(
+                &Groups::new(),
+                &fifa_2018(),
+                &third_place_rules(),
+                &HashMap::new(),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|node| (node.game_id.into(), node.round.0, node.slot))
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                (11, 0, 0),
+                (10, 0, 1),
+                (12, 0, 2),
+                (13, 0, 3),
+                (20, 1, 0),
+                (21, 1, 1),
+                (30, 2, 0),
+                (31, 2, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolved_teams() {
+        let played = |id, home, away, score| {
+            PlayedGroupGame::try_new(
+                id,
+                home,
+                away,
+                score,
+                FairPlayScore::default(),
+                Date::mock(),
+            )
+            .unwrap()
+        };
+        let mut groups = Groups::new();
+        let group_a = Group::try_new(vec![], vec![played(1, 1, 2, (2, 1))]).unwrap();
+        let group_b = Group::try_new(vec![], vec![played(2, 3, 4, (0, 1))]).unwrap();
+        let unplayed = UnplayedGroupGame::try_new(3, 5, 6, Date::mock()).unwrap();
+        let group_c = Group::try_new(vec![unplayed], vec![]).unwrap();
+        groups.insert(GroupId::from('A'), group_a);
+        groups.insert(GroupId::from('B'), group_b);
+        groups.insert(GroupId::from('C'), group_c);
+        let winners: HashMap<GameId, TeamId> = vec![(10, 3), (11, 4), (20, 3)]
+            .into_iter()
+            .map(|(game, team)| (GameId::from(game), TeamId(team)))
+            .collect();
+
+        let nodes = mock_bracket()
+            .layout(&groups, &fifa_2018(), &third_place_rules(), &winners)
+            .unwrap();
+        let teams = |id: u32| {
+            let node = nodes
+                .iter()
+                .find(|node| node.game_id == GameId::from(id))
+                .unwrap();
+            (node.home.team, node.away.team)
+        };
+        assert_eq!(teams(10), (Some(TeamId(1)), Some(TeamId(3))));
+        assert_eq!(teams(11), (Some(TeamId(4)), Some(TeamId(2))));
+        assert_eq!(teams(12), (None, None));
+        assert_eq!(teams(20), (Some(TeamId(4)), Some(TeamId(3))));
+        assert_eq!(teams(31), (Some(TeamId(4)), None));
+    }
+
+    #[test]
+    fn group_sizes() {
+        // Group A of teams 1 to 3, where 1 beats 2 by 1-0 and 3 by 2-0, and 2 and 3 draw.
+        // Group B of teams 4 to 8, where the lower id wins every game.
+        let goals = |team: TeamId, opponent: TeamId| match (team.0, opponent.0) {
+            (1, 2) => 1,
+            (1, 3) => 2,
+            _ => 0,
+        };
+        let mut groups = Groups::new();
+        groups.insert(
+            GroupId::from('A'),
+            played_round_robin(&teams(&[1, 2, 3]), 1, |home, away| {
+                (goals(home, away), goals(away, home))
+            }),
+        );
+        groups.insert(
+            GroupId::from('B'),
+            played_round_robin(&teams(&[4, 5, 6, 7, 8]), 10, lower_id_wins),
+        );
+        let bracket = Bracket::try_new(vec![
+            game(30, 0, "1A", "2B"),
+            game(31, 0, "1B", "3AB"),
+            game(40, 1, "W30", "W31"),
+        ])
+        .unwrap();
+        // Like the tournament rules for groups of unequal size, see
+        // `TournamentRules::third_place_rules`
+        let third_place_rules = third_place_rules().with_filter(GameFilter::EqualizeGroupSizes);
+        let nodes = bracket
+            .layout(&groups, &fifa_2018(), &third_place_rules, &HashMap::new())
+            .unwrap();
+        let teams = |id: u32| {
+            let node = nodes
+                .iter()
+                .find(|node| node.game_id == GameId::from(id))
+                .unwrap();
+            (node.home.team, node.away.team)
+        };
+        assert_eq!(teams(30), (Some(TeamId(1)), Some(TeamId(5))));
+        // Without the games against 7 and 8, third placed 6 has no points, against the point
+        // of 3.
+        assert_eq!(teams(31), (Some(TeamId(4)), Some(TeamId(3))));
+    }
+
+    #[test]
+    fn third_places() {
+        let entry = |group: char, team| CrossGroupEntry {
+            group: GroupId::from(group),
+            team: TeamId(team),
+        };
+        let thirds = vec![entry('C', 3), entry('A', 1), entry('B', 2)];
+        // The first game takes A or C, but only A fits the second game.
+        let bracket =
+            Bracket::try_new(vec![game(1, 0, "1B", "3AC"), game(2, 0, "3AB", "1C")]).unwrap();
+        let placed = bracket.assign_third_places(&thirds);
+        assert_eq!(placed[&(GameId::from(1), 1)], TeamId(3));
+        assert_eq!(placed[&(GameId::from(2), 0)], TeamId(1));
+        // Only the two best qualify, B can not be placed.
+        let bracket =
+            Bracket::try_new(vec![game(1, 0, "1B", "3B"), game(2, 0, "3AC", "1C")]).unwrap();
+        assert!(bracket.assign_third_places(&thirds).is_empty());
+    }
+}
@@ -1,7 +1,11 @@
 //! Tournament playoff
+pub mod bracket;
 mod game;
+pub mod prediction;
+pub mod two_legged;
 use self::game::PlayoffGame;
 use crate::game::GameId;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 struct Playoff {
@@ -12,7 +16,9 @@ struct Round {
     games: HashMap<GameId, PlayoffGame>,
 }
 
-struct RoundIdx(u8);
+/// Playoff round, counted from 0 for the first round after the group stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RoundIdx(pub u8);
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,247 @@
+//! Playoff predictions
+//!
+//! A player predicts the winner of every playoff game, see [`PlayoffPrediction`].
+//! The predicted bracket follows from the predicted winners: the teams of a game fed by earlier
+//! playoff games are the predicted winners (or losers) of those games, while the teams from the
+//! group stage are the actual ones, once decided.
+//! [`PlayoffPrediction::compare`] overlays the predicted bracket with the actual one, e.g. for
+//! showing a player's personal bracket, and [`PlayoffPrediction::points`] scores it.
+use crate::game::GameId;
+use crate::playoff::bracket::{BracketNode, Feeder};
+use crate::playoff::RoundIdx;
+use crate::pred_score::PredScore;
+use crate::team::TeamId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Predicted winners of the playoff games of a player
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayoffPrediction(HashMap<GameId, TeamId>);
+
+impl PlayoffPrediction {
+    pub fn new(winners: HashMap<GameId, TeamId>) -> Self {
+        PlayoffPrediction(winners)
+    }
+
+    /// Predicted winner of a game, if any
+    pub fn winner(&self, game: GameId) -> Option<TeamId> {
+        self.0.get(&game).copied()
+    }
+
+    pub fn winners(&self) -> &HashMap<GameId, TeamId> {
+        &self.0
+    }
+
+    /// Overlay the predicted bracket with the actual bracket layout
+    ///
+    /// `actual` is a layout from [`Bracket::layout`](crate::playoff::bracket::Bracket::layout),
+    /// the comparison has one slot per node, in the same order.
+    pub fn compare(&self, actual: &[BracketNode]) -> Vec<SlotComparison> {
+        let mut rounds: Vec<&BracketNode> = actual.iter().collect();
+        rounds.sort_by_key(|node| node.round);
+        // Predicted (home, away) of every game, resolved round by round
+        let mut predicted: HashMap<GameId, (Option<TeamId>, Option<TeamId>)> = HashMap::new();
+        for node in rounds {
+            let team = |feeder: &Feeder, actual: Option<TeamId>| match feeder {
+                Feeder::Winner(id) => self.winner(*id),
+                Feeder::Loser(id) => {
+                    let (home, away) = predicted.get(id).copied().unwrap_or((None, None));
+                    match self.winner(*id) {
+                        Some(winner) if home == Some(winner) => away,
+                        Some(winner) if away == Some(winner) => home,
+                        _ => None,
+                    }
+                }
+                _ => actual,
+            };
+            let teams = (
+                team(&node.home.feeder, node.home.team),
+                team(&node.away.feeder, node.away.team),
+            );
+            predicted.insert(node.game_id, teams);
+        }
+        actual
+            .iter()
+            .map(|node| {
+                let (home, away) = predicted[&node.game_id];
+                let predicted_winner = self.winner(node.game_id);
+                SlotComparison {
+                    game_id: node.game_id,
+                    round: node.round,
+                    slot: node.slot,
+                    home: PredictedTeam {
+                        predicted: home,
+                        actual: node.home.team,
+                    },
+                    away: PredictedTeam {
+                        predicted: away,
+                        actual: node.away.team,
+                    },
+                    predicted_winner,
+                    actual_winner: node.winner,
+                    status: SlotStatus::of(predicted_winner, node),
+                }
+            })
+            .collect()
+    }
+
+    /// Points for the correctly predicted winners in the actual bracket layout, `per_winner` of
+    /// the round of the game each, see
+    /// [`ScoringRules::winner_points`](crate::pred_score::ScoringRules::winner_points)
+    pub fn points(
+        &self,
+        actual: &[BracketNode],
+        per_winner: impl Fn(RoundIdx) -> f32,
+    ) -> KnockoutPoints {
+        let slots = self.compare(actual);
+        let sum = |status: SlotStatus| {
+            slots
+                .iter()
+                .filter(|slot| slot.status == status && slot.predicted_winner.is_some())
+                .fold((0, 0.0), |(count, points), slot| {
+                    (count + 1, points + per_winner(slot.round))
+                })
+        };
+        let (correct, points) = sum(SlotStatus::Correct);
+        let (_, pending) = sum(SlotStatus::Pending);
+        KnockoutPoints {
+            correct,
+            points: PredScore::from(points),
+            max_points: PredScore::from(points + pending),
+        }
+    }
+}
+
+/// Points of a playoff prediction, see [`PlayoffPrediction::points`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KnockoutPoints {
+    /// Number of correctly predicted winners
+    pub correct: u32,
+    pub points: PredScore,
+    /// Upper bound for the final points, if every pending prediction is correct
+    pub max_points: PredScore,
+}
+
+/// Predicted and actual team on one side of a playoff game
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PredictedTeam {
+    pub predicted: Option<TeamId>,
+    pub actual: Option<TeamId>,
+}
+
+/// Outcome of the prediction of a playoff game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotStatus {
+    /// The predicted winner won the game
+    Correct,
+    /// The game was won by another team, or the predicted winner does not play the game,
+    /// or there is no prediction for a decided game
+    Incorrect,
+    /// Not yet decided
+    Pending,
+}
+
+impl SlotStatus {
+    fn of(predicted_winner: Option<TeamId>, node: &BracketNode) -> Self {
+        match (predicted_winner, node.winner) {
+            (Some(predicted), Some(actual)) if predicted == actual => SlotStatus::Correct,
+            (_, Some(_)) => SlotStatus::Incorrect,
+            (None, None) => SlotStatus::Pending,
+            (Some(predicted), None) => match (node.home.team, node.away.team) {
+                (Some(home), Some(away)) if predicted != home && predicted != away => {
+                    SlotStatus::Incorrect
+                }
+                _ => SlotStatus::Pending,
+            },
+        }
+    }
+}
+
+/// A playoff game of a player's bracket, see [`PlayoffPrediction::compare`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SlotComparison {
+    pub game_id: GameId,
+    pub round: RoundIdx,
+    /// Vertical position in the round, as in the [`BracketNode`]
+    pub slot: usize,
+    pub home: PredictedTeam,
+    pub away: PredictedTeam,
+    pub predicted_winner: Option<TeamId>,
+    pub actual_winner: Option<TeamId>,
+    pub status: SlotStatus,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playoff::bracket::BracketTeam;
+    use crate::Date;
+
+    fn node(
+        id: u32,
+        round: u8,
+        home: (&str, Option<u32>),
+        away: (&str, Option<u32>),
+    ) -> BracketNode {
+        let side = |(feeder, team): (&str, Option<u32>)| BracketTeam {
+            feeder: feeder.parse().unwrap(),
+            team: team.map(TeamId),
+        };
+        BracketNode {
+            game_id: GameId::from(id),
+            round: RoundIdx(round),
+            slot: 0,
+            kickoff: Date::mock(),
+            home: side(home),
+            away: side(away),
+            winner: None,
+        }
+    }
+
+    #[test]
+    fn personal_bracket() {
+        let mut semi_1 = node(1, 0, ("1A", Some(1)), ("2B", Some(2)));
+        semi_1.winner = Some(TeamId(1));
+        let mut semi_2 = node(2, 0, ("1B", Some(3)), ("2A", Some(4)));
+        semi_2.winner = Some(TeamId(3));
+        let final_ = node(3, 1, ("W1", Some(1)), ("W2", Some(3)));
+        let third = node(4, 1, ("L1", Some(2)), ("L2", Some(4)));
+        let actual = vec![semi_1, semi_2, final_, third];
+
+        let winners = vec![(1, 1), (2, 4), (3, 4), (4, 2)]
+            .into_iter()
+            .map(|(game, team)| (GameId::from(game), TeamId(team)))
+            .collect();
+        let prediction = PlayoffPrediction::new(winners);
+        let comparison = prediction.compare(&actual);
+        let status: Vec<SlotStatus> = comparison.iter().map(|slot| slot.status).collect();
+        assert_eq!(
+            status,
+            vec![
+                SlotStatus::Correct,
+                SlotStatus::Incorrect,
+                // Team 4 was predicted to win the final, but is already out
+                SlotStatus::Incorrect,
+                SlotStatus::Pending,
+            ]
+        );
+        assert_eq!(comparison[2].away.predicted, Some(TeamId(4)));
+        assert_eq!(comparison[2].away.actual, Some(TeamId(3)));
+        // The predicted loser of game 2 is team 3
+        assert_eq!(comparison[3].away.predicted, Some(TeamId(3)));
+
+        let points = prediction.points(&actual, |_| 2.0);
+        assert_eq!(points.correct, 1);
+        assert_eq!(points.points, PredScore::from(2.0));
+        assert_eq!(points.max_points, PredScore::from(4.0));
+
+        // The final round is worth double
+        let points = prediction.points(
+            &actual,
+            |round| if round == RoundIdx(1) { 4.0 } else { 2.0 },
+        );
+        assert_eq!(points.points, PredScore::from(2.0));
+        assert_eq!(points.max_points, PredScore::from(6.0));
+    }
+}
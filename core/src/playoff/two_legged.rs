@@ -0,0 +1,229 @@
+//! Two-legged ties
+//!
+//! In a two-legged tie, e.g. a qualification playoff, the two teams play each other twice, once at
+//! home each, and the aggregate score over both legs decides who advances.
+//! A level aggregate is decided by the away goals, if the [`TieRules`] say so, and otherwise by
+//! extra time and penalties at the end of the second leg.
+//! Extra time goals are part of the score of the second leg.
+use crate::game::{GoalCount, Score};
+use crate::team::TeamId;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use thiserror::Error;
+
+/// How a level aggregate is decided
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TieRules {
+    /// More goals scored away wins a level aggregate, before penalties
+    pub away_goals: bool,
+}
+
+impl TieRules {
+    /// Aggregate, then penalties, as in the UEFA competitions since 2021
+    pub fn aggregate_only() -> Self {
+        TieRules { away_goals: false }
+    }
+
+    /// Aggregate, away goals, then penalties
+    pub fn away_goals() -> Self {
+        TieRules { away_goals: true }
+    }
+}
+
+/// Home and away tie between two teams
+///
+/// The scores are from the perspective of the home team of the leg, like any [`Score`]:
+/// `first` hosts the first leg and `second` hosts the second leg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TwoLeggedTie {
+    pub first: TeamId,
+    pub second: TeamId,
+    pub first_leg: Option<Score>,
+    /// Including extra time
+    pub second_leg: Option<Score>,
+    /// Shoot-out after the second leg, `second` is the home team
+    pub penalties: Option<Score>,
+}
+
+/// Goals of the teams of a [`TwoLeggedTie`] over both legs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Aggregate {
+    /// Goals of the `first` team
+    pub first: GoalCount,
+    /// Goals of the `second` team
+    pub second: GoalCount,
+    /// Goals of the `first` team in the second leg
+    pub first_away: GoalCount,
+    /// Goals of the `second` team in the first leg
+    pub second_away: GoalCount,
+}
+
+/// What decided a [`TwoLeggedTie`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decider {
+    Aggregate,
+    AwayGoals,
+    Penalties,
+}
+
+/// The team advancing from a [`TwoLeggedTie`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TieWinner {
+    pub winner: TeamId,
+    pub loser: TeamId,
+    pub decider: Decider,
+}
+
+impl TwoLeggedTie {
+    /// Tie without any leg played
+    ///
+    /// # Errors
+    ///
+    /// A team can not play itself.
+    pub fn try_new(first: TeamId, second: TeamId) -> Result<Self, TieError> {
+        if first == second {
+            return Err(TieError::SameTeam(first));
+        }
+        Ok(TwoLeggedTie {
+            first,
+            second,
+            first_leg: None,
+            second_leg: None,
+            penalties: None,
+        })
+    }
+
+    /// Goals over both legs, `None` until both legs are played
+    pub fn aggregate(&self) -> Option<Aggregate> {
+        let (first_leg, second_leg) = (self.first_leg?, self.second_leg?);
+        Some(Aggregate {
+            first: first_leg.home + second_leg.away,
+            second: first_leg.away + second_leg.home,
+            first_away: second_leg.away,
+            second_away: first_leg.away,
+        })
+    }
+
+    /// The advancing team, `None` until both legs are played
+    ///
+    /// # Errors
+    ///
+    /// - A level tie needs the penalties, and they need a winner.
+    /// - Penalties are only taken in a level tie.
+    pub fn winner(&self, rules: &TieRules) -> Result<Option<TieWinner>, TieError> {
+        let aggregate = match self.aggregate() {
+            Some(aggregate) => aggregate,
+            None => return Ok(None),
+        };
+        let decided = |ordering: Ordering, decider: Decider| match ordering {
+            Ordering::Greater => Some(self.decided(self.first, decider)),
+            Ordering::Less => Some(self.decided(self.second, decider)),
+            Ordering::Equal => None,
+        };
+        let before_penalties = decided(aggregate.first.cmp(&aggregate.second), Decider::Aggregate)
+            .or_else(|| {
+                if rules.away_goals {
+                    decided(
+                        aggregate.first_away.cmp(&aggregate.second_away),
+                        Decider::AwayGoals,
+                    )
+                } else {
+                    None
+                }
+            });
+        match (before_penalties, self.penalties) {
+            (Some(_), Some(_)) => Err(TieError::UnneededPenalties),
+            (Some(winner), None) => Ok(Some(winner)),
+            (None, None) => Err(TieError::MissingPenalties),
+            // The second team hosts the second leg and is the home team of the shoot-out
+            (None, Some(penalties)) => {
+                decided(penalties.away.cmp(&penalties.home), Decider::Penalties)
+                    .map(Some)
+                    .ok_or(TieError::LevelPenalties)
+            }
+        }
+    }
+
+    fn decided(&self, winner: TeamId, decider: Decider) -> TieWinner {
+        let loser = if winner == self.first {
+            self.second
+        } else {
+            self.first
+        };
+        TieWinner {
+            winner,
+            loser,
+            decider,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieError {
+    #[error("Team {0} can not play itself")]
+    SameTeam(TeamId),
+    #[error("The tie is level, but there are no penalties")]
+    MissingPenalties,
+    #[error("Penalties in a tie which is not level")]
+    UnneededPenalties,
+    #[error("Penalties without a winner")]
+    LevelPenalties,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tie(first_leg: (u32, u32), second_leg: (u32, u32)) -> TwoLeggedTie {
+        TwoLeggedTie {
+            first_leg: Some(Score::from(first_leg)),
+            second_leg: Some(Score::from(second_leg)),
+            ..TwoLeggedTie::try_new(TeamId(0), TeamId(1)).unwrap()
+        }
+    }
+
+    #[test]
+    fn aggregate() {
+        let tie = tie((2, 1), (1, 1));
+        assert_eq!(tie.aggregate().unwrap().first, GoalCount::from(3));
+        let winner = tie.winner(&TieRules::aggregate_only()).unwrap().unwrap();
+        assert_eq!(
+            (winner.winner, winner.decider),
+            (TeamId(0), Decider::Aggregate)
+        );
+    }
+
+    #[test]
+    fn away_goals() {
+        // 2-2 on aggregate, the second team scored one away goal and the first team two
+        let tie = tie((0, 1), (1, 2));
+        let winner = tie.winner(&TieRules::away_goals()).unwrap().unwrap();
+        assert_eq!(
+            (winner.winner, winner.decider),
+            (TeamId(0), Decider::AwayGoals)
+        );
+        assert_eq!(
+            tie.winner(&TieRules::aggregate_only()),
+            Err(TieError::MissingPenalties)
+        );
+    }
+
+    #[test]
+    fn penalties() {
+        let mut tie = tie((1, 1), (1, 1));
+        tie.penalties = Some(Score::from((3, 4)));
+        let winner = tie.winner(&TieRules::away_goals()).unwrap().unwrap();
+        assert_eq!(
+            (winner.winner, winner.loser, winner.decider),
+            (TeamId(0), TeamId(1), Decider::Penalties)
+        );
+    }
+
+    #[test]
+    fn unfinished() {
+        let mut tie = TwoLeggedTie::try_new(TeamId(0), TeamId(1)).unwrap();
+        tie.first_leg = Some(Score::from((1, 0)));
+        assert_eq!(tie.winner(&TieRules::default()), Ok(None));
+    }
+}
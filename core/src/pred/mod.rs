@@ -0,0 +1,5 @@
+//! Predictions
+//!
+//! The predictions themselves are in [`player`](crate::player), their scoring in
+//! [`pred_score`](crate::pred_score). This module is about how players predict, see [`strategy`].
+pub mod strategy;
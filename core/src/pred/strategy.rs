@@ -0,0 +1,323 @@
+//! Prediction strategies
+//!
+//! A [`Strategy`] describes how a player predicts rather than what, e.g. "the better ranked team
+//! wins 2-1".
+//! It is inferred from the predictions of one tournament, see [`Strategy::infer`], and applied to
+//! the games of the next, see [`Strategy::predict`], as a starting point for the player to edit.
+//!
+//! The missing predictions of a player can also be filled in automatically, by a pluggable
+//! [`AutoPick`] strategy, see [`autofill`]:
+//!
+//! - [`RankedStrategy`], e.g. the better ranked team wins, or every game is a draw
+//! - [`RandomPick`], random scores from a seed
+//! - [`MostLikely`], the most likely score of a Poisson goal model
+use crate::game::{Game, GameId, GoalCount, Outcome, Score};
+#[cfg(feature = "random")]
+use crate::goal_model::PoissonModel;
+use crate::group::game::UnplayedGroupGame;
+use crate::group::Groups;
+use crate::player::Prediction;
+use crate::team::{TeamId, TeamRank};
+use crate::Date;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet};
+
+/// Team picked to win
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pick {
+    /// The better ranked team, i.e. with the lower rank
+    Favourite,
+    Underdog,
+    Home,
+    Draw,
+}
+
+/// How a player predicts, see the [module docs](self)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Strategy {
+    pub pick: Pick,
+    /// Goals of the picked team, of both teams for a draw
+    pub winner_goals: GoalCount,
+    /// Goals of the other team, not used for a draw
+    pub loser_goals: GoalCount,
+}
+
+impl Strategy {
+    /// The most common pick in `preds`, with the most common score of that pick
+    ///
+    /// A prediction counts for every pick it agrees with, e.g. a home win of the better ranked
+    /// team counts for [`Pick::Favourite`] and [`Pick::Home`].
+    /// Equal counts are decided in the order favourite, home, underdog, draw, and equal scores by
+    /// the fewest goals.
+    /// Predictions of games not in `groups` are ignored, `None` if no prediction is left.
+    pub fn infer(
+        preds: &[Prediction],
+        groups: &Groups,
+        ranking: &HashMap<TeamId, TeamRank>,
+    ) -> Option<Self> {
+        let teams: HashMap<GameId, (TeamId, TeamId)> = groups
+            .values()
+            .flat_map(|group| group.games())
+            .map(|game| (game.id(), (game.home_team(), game.away_team())))
+            .collect();
+        let mut scores: HashMap<Pick, HashMap<(u32, u32), usize>> = HashMap::new();
+        for Prediction(game, score) in preds {
+            let (home, away) = match teams.get(game) {
+                Some(teams) => *teams,
+                None => continue,
+            };
+            let (home_goals, away_goals) = (u32::from(score.home), u32::from(score.away));
+            let winner_loser = (home_goals.max(away_goals), home_goals.min(away_goals));
+            let picks = Pick::ALL
+                .iter()
+                .filter(|pick| pick.winner(home, away, ranking) == Some(score.home_outcome()));
+            for pick in picks {
+                *scores
+                    .entry(*pick)
+                    .or_default()
+                    .entry(winner_loser)
+                    .or_default() += 1;
+            }
+        }
+        let (pick, counts) = Pick::ALL
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, pick)| scores.get(pick).map(|counts| (idx, *pick, counts)))
+            .max_by_key(|(idx, _, counts)| (counts.values().sum::<usize>(), Reverse(*idx)))
+            .map(|(_, pick, counts)| (pick, counts))?;
+        let (winner_goals, loser_goals) = counts
+            .iter()
+            .max_by_key(|(goals, count)| (**count, Reverse(**goals)))
+            .map(|(goals, _)| *goals)?;
+        Some(Strategy {
+            pick,
+            winner_goals: GoalCount::from(winner_goals),
+            loser_goals: GoalCount::from(loser_goals),
+        })
+    }
+
+    /// Predictions of `games` by the strategy
+    ///
+    /// Games where the pick is undecided, e.g. the favourite of two equally ranked teams, are
+    /// predicted as a draw with the goals of the loser.
+    pub fn predict<'a>(
+        &self,
+        games: impl IntoIterator<Item = &'a UnplayedGroupGame>,
+        ranking: &HashMap<TeamId, TeamRank>,
+    ) -> Vec<Prediction> {
+        games
+            .into_iter()
+            .map(|game| Prediction(game.id, self.score(game, ranking)))
+            .collect()
+    }
+
+    /// Prediction of a single game, see [`Strategy::predict`]
+    pub fn score(&self, game: &UnplayedGroupGame, ranking: &HashMap<TeamId, TeamRank>) -> Score {
+        match self.pick.winner(game.home, game.away, ranking) {
+            Some(Outcome::Win) => Score::new(self.winner_goals, self.loser_goals),
+            Some(Outcome::Lose) => Score::new(self.loser_goals, self.winner_goals),
+            Some(Outcome::Draw) => Score::new(self.winner_goals, self.winner_goals),
+            None => Score::new(self.loser_goals, self.loser_goals),
+        }
+    }
+}
+
+/// Fills in the predictions of a player, see [`autofill`]
+pub trait AutoPick {
+    /// Predicted score of a game, `None` to leave the game without a prediction
+    fn pick(&mut self, game: &UnplayedGroupGame) -> Option<Score>;
+}
+
+/// A [`Strategy`] applied with a ranking of the teams, e.g. the better ranked team wins 1-0
+pub struct RankedStrategy<'a> {
+    pub strategy: Strategy,
+    pub ranking: &'a HashMap<TeamId, TeamRank>,
+}
+
+impl AutoPick for RankedStrategy<'_> {
+    fn pick(&mut self, game: &UnplayedGroupGame) -> Option<Score> {
+        Some(self.strategy.score(game, self.ranking))
+    }
+}
+
+/// Random scores, reproducible from a seed
+#[cfg(feature = "random")]
+pub struct RandomPick(rand::rngs::StdRng);
+
+#[cfg(feature = "random")]
+impl RandomPick {
+    /// Most goals of a team in a random score
+    const MAX_GOALS: u32 = 3;
+
+    pub fn seeded(seed: u64) -> Self {
+        use rand::SeedableRng;
+        RandomPick(rand::rngs::StdRng::seed_from_u64(seed))
+    }
+}
+
+#[cfg(feature = "random")]
+impl AutoPick for RandomPick {
+    fn pick(&mut self, _game: &UnplayedGroupGame) -> Option<Score> {
+        use rand::Rng;
+        Some(Score::new(
+            self.0.gen_range(0, Self::MAX_GOALS + 1),
+            self.0.gen_range(0, Self::MAX_GOALS + 1),
+        ))
+    }
+}
+
+/// The most likely score of a goal model, see [`PoissonModel::most_likely_score`]
+#[cfg(feature = "random")]
+pub struct MostLikely<'a>(pub &'a PoissonModel);
+
+#[cfg(feature = "random")]
+impl AutoPick for MostLikely<'_> {
+    fn pick(&mut self, game: &UnplayedGroupGame) -> Option<Score> {
+        Some(self.0.most_likely_score(game.home, game.away))
+    }
+}
+
+/// Predictions for the games without a prediction in `preds` which kick off after `now`
+///
+/// The games are picked in the order of their id's, so that a seeded [`RandomPick`] gives the
+/// same predictions for the same games.
+pub fn autofill<'a>(
+    preds: &[Prediction],
+    games: impl IntoIterator<Item = &'a UnplayedGroupGame>,
+    now: Date,
+    picker: &mut dyn AutoPick,
+) -> Vec<Prediction> {
+    let predicted: HashSet<GameId> = preds.iter().map(|Prediction(game, _)| *game).collect();
+    games
+        .into_iter()
+        .filter(|game| !predicted.contains(&game.id) && game.date().is_after(now))
+        .sorted_by_key(|game| game.id)
+        .filter_map(|game| picker.pick(game).map(|score| Prediction(game.id, score)))
+        .collect()
+}
+
+impl Pick {
+    /// Picks in the order ties are broken when inferring a strategy
+    const ALL: [Pick; 4] = [Pick::Favourite, Pick::Home, Pick::Underdog, Pick::Draw];
+
+    /// Outcome for the home team if the pick wins, `None` if undecided
+    fn winner(
+        self,
+        home: TeamId,
+        away: TeamId,
+        ranking: &HashMap<TeamId, TeamRank>,
+    ) -> Option<Outcome> {
+        let by_rank = || match ranking.get(&home)?.cmp(ranking.get(&away)?) {
+            Ordering::Less => Some(Outcome::Win),
+            Ordering::Equal => None,
+            Ordering::Greater => Some(Outcome::Lose),
+        };
+        match self {
+            Pick::Favourite => by_rank(),
+            Pick::Underdog => by_rank().map(|outcome| match outcome {
+                Outcome::Win => Outcome::Lose,
+                _ => Outcome::Win,
+            }),
+            Pick::Home => Some(Outcome::Win),
+            Pick::Draw => Some(Outcome::Draw),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group::{Group, GroupId};
+    use crate::Date;
+
+    fn groups() -> Groups {
+        let games = vec![(0, 1, 2), (1, 3, 1), (2, 2, 3)]
+            .into_iter()
+            .map(|(id, home, away)| UnplayedGroupGame::try_new(id, home, away, Date::mock()))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let mut groups = Groups::new();
+        groups.insert(GroupId::from('A'), Group::try_new(games, vec![]).unwrap());
+        groups
+    }
+
+    /// Before the kickoff of every game in [`groups`]
+    fn before_kickoff() -> Date {
+        "1632-11-06 00:00".parse().unwrap()
+    }
+
+    fn ranking() -> HashMap<TeamId, TeamRank> {
+        vec![(1, 1), (2, 2), (3, 3)]
+            .into_iter()
+            .map(|(team, rank)| (TeamId(team), TeamRank(rank)))
+            .collect()
+    }
+
+    #[test]
+    fn favourite() {
+        let preds = vec![
+            Prediction(GameId::from(0), Score::from((2, 1))),
+            Prediction(GameId::from(1), Score::from((1, 2))),
+            Prediction(GameId::from(2), Score::from((1, 1))),
+        ];
+        let strategy = Strategy::infer(&preds, &groups(), &ranking()).unwrap();
+        assert_eq!(
+            strategy,
+            Strategy {
+                pick: Pick::Favourite,
+                winner_goals: GoalCount::from(2),
+                loser_goals: GoalCount::from(1),
+            }
+        );
+        let groups = groups();
+        let games = groups[&GroupId::from('A')].unplayed_games();
+        let predicted = strategy.predict(games, &ranking());
+        assert!(predicted.contains(&Prediction(GameId::from(1), Score::from((1, 2)))));
+        assert!(predicted.contains(&Prediction(GameId::from(2), Score::from((2, 1)))));
+    }
+
+    #[test]
+    fn autofill_missing() {
+        let groups = groups();
+        let games: Vec<&UnplayedGroupGame> = groups[&GroupId::from('A')].unplayed_games().collect();
+        let preds = vec![Prediction(GameId::from(0), Score::from((3, 3)))];
+        let draws = Strategy {
+            pick: Pick::Draw,
+            winner_goals: GoalCount::from(1),
+            loser_goals: GoalCount::from(1),
+        };
+        let ranking = ranking();
+        let mut picker = RankedStrategy {
+            strategy: draws,
+            ranking: &ranking,
+        };
+        let filled = autofill(&preds, games.clone(), before_kickoff(), &mut picker);
+        assert_eq!(
+            filled,
+            vec![
+                Prediction(GameId::from(1), Score::from((1, 1))),
+                Prediction(GameId::from(2), Score::from((1, 1)))
+            ]
+        );
+        let after_deadline = Date::mock().add_hours(1);
+        assert!(autofill(&preds, games, after_deadline, &mut picker).is_empty());
+    }
+
+    #[test]
+    fn seeded_random() {
+        let groups = groups();
+        let games = || groups[&GroupId::from('A')].unplayed_games();
+        let first = autofill(&[], games(), before_kickoff(), &mut RandomPick::seeded(1));
+        let second = autofill(&[], games(), before_kickoff(), &mut RandomPick::seeded(1));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn no_known_games() {
+        let preds = vec![Prediction(GameId::from(9), Score::from((2, 1)))];
+        assert_eq!(Strategy::infer(&preds, &groups(), &ranking()), None);
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! The objective when betting on a tournament is to give accurate predictions
 //! This module defines various measurements of the quality of a prediction
-use crate::game::Score;
+use crate::game::{GameId, Score};
+use crate::leaderboard::{LateJoiner, Tiebreak, DEFAULT_TIEBREAKS};
+use crate::playoff::RoundIdx;
 use derive_more::{Add, AddAssign, Display, From, Into, Neg, Sub};
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +16,29 @@ use serde::{Deserialize, Serialize};
 // calculation of the score.
 pub trait PredScoreFn {
     fn pred_score(&self, pred: Score, truth: Score) -> PredScore;
+
+    /// Upper bound for the score of a single prediction,
+    /// used to bound the points a player can still get.
+    fn max_score(&self) -> PredScore;
+
+    /// Score of a prediction of a specific game
+    ///
+    /// Same as [`pred_score`](PredScoreFn::pred_score), unless the score depends on the game,
+    /// e.g. [`OddsBonus`](crate::odds::OddsBonus).
+    fn game_score(&self, _game: GameId, pred: Score, truth: Score) -> PredScore {
+        self.pred_score(pred, truth)
+    }
+
+    /// Upper bound for the score of a prediction of a specific game, see
+    /// [`game_score`](PredScoreFn::game_score)
+    fn game_max_score(&self, _game: GameId) -> PredScore {
+        self.max_score()
+    }
+
+    /// Order of players on equal points, see [`rank`](crate::leaderboard::rank)
+    fn tiebreaks(&self) -> &[Tiebreak] {
+        &DEFAULT_TIEBREAKS
+    }
 }
 
 // Here is an example of a concrete type that implements the `PredScoreFn` trait.
@@ -25,6 +50,19 @@ pub struct SimplePredScoreFn {
     result: f32,
 }
 
+impl SimplePredScoreFn {
+    pub fn new(outcome: f32, result: f32) -> Self {
+        SimplePredScoreFn { outcome, result }
+    }
+}
+
+// The scoring used in the pool: 3 points for the correct outcome and another 2 for the exact result.
+impl Default for SimplePredScoreFn {
+    fn default() -> Self {
+        SimplePredScoreFn::new(3.0, 2.0)
+    }
+}
+
 // A trait is implemented by providing this type of `impl TraitX for ConcreteTypeY` block
 // if this block does not implement all the functions specified in the above `PredScoreFn` trait,
 // the compiler will give an error.
@@ -46,6 +84,165 @@ impl PredScoreFn for SimplePredScoreFn {
         // return PredScore(score);
         // but it is considered un-idiomatic.
     }
+
+    // A correct result is necessarily also a correct outcome.
+    fn max_score(&self) -> PredScore {
+        PredScore(self.outcome + self.result)
+    }
+}
+
+/// Serializable scoring rule set
+///
+/// A prediction gets the points of the best tier it reaches, the tiers are not added up:
+///
+/// - `exact`: the exact result,
+/// - `goal_diff`: the correct goal difference (which includes the outcome),
+/// - `outcome`: the correct outcome, win/draw/loss.
+///
+/// E.g. a "5/3/1" pool is `ScoringRules::new(5.0, 3.0, 1.0)` and a "3/1" pool, without extra
+/// points for the goal difference, is `ScoringRules::new(3.0, 1.0, 1.0)`.
+///
+/// Optionally, correct outcomes get a bonus from the betting odds, see
+/// [`OddsBonus`](crate::odds::OddsBonus). The bonus needs the odds of the games, so it is not
+/// part of [`pred_score`](PredScoreFn::pred_score).
+/// Every correctly predicted winner of a playoff game is worth `knockout_winner` points, weighted
+/// by the round of the game, scored on the knockout leaderboard, see
+/// [`ScoringStage`](crate::leaderboard::ScoringStage) and [`ScoringRules::winner_points`].
+/// E.g. `with_bracket_weights(&[1.0, 1.0, 2.0, 3.0])` doubles the points of the semi-finals and
+/// triples those of the final in a bracket from the quarter-finals.
+///
+/// Players on equal points are ordered by `tiebreaks`, see
+/// [`Tiebreak`](crate::leaderboard::Tiebreak).
+///
+/// Stored per league as JSON, rules stored without `odds_bonus` have no bonus, rules stored
+/// without `late_joiner` give late joiners no points for the games they missed, rules stored
+/// without `knockout_winner` give [`KNOCKOUT_WINNER_POINTS`] per winner, rules stored without
+/// `bracket_weights` weigh every round the same, and rules stored without `tiebreaks` use the
+/// [`DEFAULT_TIEBREAKS`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScoringRules {
+    pub exact: f32,
+    pub goal_diff: f32,
+    pub outcome: f32,
+    /// Factor of the odds bonus
+    #[serde(default)]
+    pub odds_bonus: Option<f32>,
+    /// Points for the games missed by players who joined late, see
+    /// [`late_joiner_points`](crate::leaderboard::late_joiner_points)
+    #[serde(default)]
+    pub late_joiner: LateJoiner,
+    /// Points for a correctly predicted winner of a playoff game
+    #[serde(default = "default_knockout_winner")]
+    pub knockout_winner: f32,
+    /// Weight of the `knockout_winner` points per playoff round, from the first round after the
+    /// group stage, rounds without a weight have weight one
+    #[serde(default)]
+    pub bracket_weights: Vec<f32>,
+    /// Order of players on equal points
+    #[serde(default = "default_tiebreaks")]
+    pub tiebreaks: Vec<Tiebreak>,
+}
+
+/// Default points for a correctly predicted winner of a playoff game, see [`ScoringRules`]
+pub const KNOCKOUT_WINNER_POINTS: f32 = 2.0;
+
+fn default_knockout_winner() -> f32 {
+    KNOCKOUT_WINNER_POINTS
+}
+
+fn default_tiebreaks() -> Vec<Tiebreak> {
+    DEFAULT_TIEBREAKS.to_vec()
+}
+
+impl ScoringRules {
+    pub fn new(exact: f32, goal_diff: f32, outcome: f32) -> Self {
+        ScoringRules {
+            exact,
+            goal_diff,
+            outcome,
+            odds_bonus: None,
+            late_joiner: LateJoiner::default(),
+            knockout_winner: KNOCKOUT_WINNER_POINTS,
+            bracket_weights: Vec::new(),
+            tiebreaks: default_tiebreaks(),
+        }
+    }
+
+    pub fn with_odds_bonus(self, factor: f32) -> Self {
+        ScoringRules {
+            odds_bonus: Some(factor),
+            ..self
+        }
+    }
+
+    pub fn with_late_joiner(self, policy: LateJoiner) -> Self {
+        ScoringRules {
+            late_joiner: policy,
+            ..self
+        }
+    }
+
+    pub fn with_knockout_winner(self, points: f32) -> Self {
+        ScoringRules {
+            knockout_winner: points,
+            ..self
+        }
+    }
+
+    pub fn with_bracket_weights(self, weights: &[f32]) -> Self {
+        ScoringRules {
+            bracket_weights: weights.to_vec(),
+            ..self
+        }
+    }
+
+    /// Points for a correctly predicted winner of a playoff game in the `round`
+    pub fn winner_points(&self, round: RoundIdx) -> f32 {
+        let weight = self
+            .bracket_weights
+            .get(usize::from(round.0))
+            .copied()
+            .unwrap_or(1.0);
+        self.knockout_winner * weight
+    }
+
+    pub fn with_tiebreaks(self, tiebreaks: &[Tiebreak]) -> Self {
+        ScoringRules {
+            tiebreaks: tiebreaks.to_vec(),
+            ..self
+        }
+    }
+}
+
+/// Same scoring as the default [`SimplePredScoreFn`]:
+/// 3 points for the outcome and another 2 for the exact result.
+impl Default for ScoringRules {
+    fn default() -> Self {
+        ScoringRules::new(5.0, 3.0, 3.0)
+    }
+}
+
+impl PredScoreFn for ScoringRules {
+    fn pred_score(&self, pred: Score, truth: Score) -> PredScore {
+        let score = if pred == truth {
+            self.exact
+        } else if pred.home - pred.away == truth.home - truth.away {
+            self.goal_diff
+        } else if pred.home_outcome() == truth.home_outcome() {
+            self.outcome
+        } else {
+            0.0
+        };
+        PredScore(score)
+    }
+
+    fn max_score(&self) -> PredScore {
+        PredScore(self.exact.max(self.goal_diff).max(self.outcome))
+    }
+
+    fn tiebreaks(&self) -> &[Tiebreak] {
+        &self.tiebreaks
+    }
 }
 
 // This is a typical construct in this code.
@@ -92,5 +289,40 @@ mod test {
         let true_score = Score::new(2, 1);
         let pred = Score::new(3, 1);
         assert_approx_eq!(score_fn.pred_score(pred, true_score).0, PredScore(3.0).0);
+
+        assert_approx_eq!(score_fn.max_score().0, PredScore(5.0).0);
+    }
+
+    #[test]
+    fn test_scoring_rules() {
+        let rules = ScoringRules::new(5.0, 3.0, 1.0);
+        let truth = Score::new(2, 1);
+        let score = |home, away| f32::from(rules.pred_score(Score::new(home, away), truth));
+        assert_approx_eq!(score(2, 1), 5.0);
+        assert_approx_eq!(score(1, 0), 3.0);
+        assert_approx_eq!(score(3, 0), 1.0);
+        assert_approx_eq!(score(1, 1), 0.0);
+        assert_approx_eq!(f32::from(rules.max_score()), 5.0);
+    }
+
+    #[test]
+    fn default_rules_match_simple_score_fn() {
+        let (rules, simple) = (ScoringRules::default(), SimplePredScoreFn::default());
+        let truth = Score::new(1, 1);
+        for pred in &[Score::new(1, 1), Score::new(0, 0), Score::new(1, 0)] {
+            assert_approx_eq!(
+                f32::from(rules.pred_score(*pred, truth)),
+                f32::from(simple.pred_score(*pred, truth))
+            );
+        }
+    }
+
+    #[test]
+    fn tiebreaks() {
+        let stored = r#"{"exact": 5.0, "goal_diff": 3.0, "outcome": 1.0}"#;
+        let rules: ScoringRules = serde_json::from_str(stored).unwrap();
+        assert_eq!(rules.tiebreaks(), &DEFAULT_TIEBREAKS);
+        let rules = rules.with_tiebreaks(&[Tiebreak::Registration]);
+        assert_eq!(rules.tiebreaks(), &[Tiebreak::Registration]);
     }
 }
@@ -0,0 +1,276 @@
+//! Replay of the event stream
+//!
+//! The results, the kickoffs and the predictions are rebuilt from the [`DomainEvent`]s alone,
+//! see [`replay`].
+//! Combined with the fixtures, which are not part of the stream, this gives the standings and
+//! the leaderboard, e.g. to check the stored state against the stream, or to recover it.
+use crate::event::DomainEvent;
+use crate::fair_play::FairPlayScore;
+use crate::game::{GameId, GameStatus, Score};
+use crate::group::game::{GroupGame, UnplayedGroupGame};
+use crate::group::{Group, GroupError, Groups};
+use crate::player::{effective_prediction, PlayerId, Prediction, PredictionVersion};
+use crate::Date;
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Status of a game after the replayed events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct GameState {
+    pub status: GameStatus,
+    /// Played games have a score, void games may keep one, see [`GameStatus`]
+    pub score: Option<Score>,
+}
+
+/// The state of a game in a group, void games without their kept result
+impl From<GroupGame<'_>> for GameState {
+    fn from(game: GroupGame<'_>) -> Self {
+        GameState {
+            status: game.status(),
+            score: game.score(),
+        }
+    }
+}
+
+/// State rebuilt from the events, see [`replay`]
+#[derive(Debug, Clone, Default)]
+pub struct ReplayedState {
+    /// Games with a result or status event
+    pub games: BTreeMap<GameId, GameState>,
+    /// Games with a reschedule event, by the latest kickoff
+    pub kickoffs: HashMap<GameId, Date>,
+    pub deleted_players: HashSet<PlayerId>,
+    /// Prediction versions, flagged if soft-deleted
+    versions: HashMap<PlayerId, Vec<(PredictionVersion, bool)>>,
+}
+
+/// Apply the events in order
+pub fn replay<'a>(events: impl IntoIterator<Item = &'a DomainEvent>) -> ReplayedState {
+    let mut state = ReplayedState::default();
+    for event in events {
+        state.apply(event);
+    }
+    state
+}
+
+impl ReplayedState {
+    pub fn apply(&mut self, event: &DomainEvent) {
+        match event {
+            DomainEvent::ResultPosted { game, score }
+            | DomainEvent::ResultAmended { game, score, .. } => {
+                self.games.insert(
+                    *game,
+                    GameState {
+                        status: GameStatus::Played,
+                        score: Some(*score),
+                    },
+                );
+            }
+            DomainEvent::GameStatusChanged {
+                game,
+                status,
+                score,
+            } => {
+                self.games.insert(
+                    *game,
+                    GameState {
+                        status: *status,
+                        score: *score,
+                    },
+                );
+            }
+            DomainEvent::GameRescheduled {
+                game, new_kickoff, ..
+            } => {
+                self.kickoffs.insert(*game, *new_kickoff);
+            }
+            DomainEvent::PredictionSubmitted {
+                player,
+                preds,
+                submitted,
+            } => self.versions.entry(*player).or_default().extend(
+                preds
+                    .iter()
+                    .map(|pred| (PredictionVersion::new(*pred, *submitted), false)),
+            ),
+            DomainEvent::PredictionsDeleted { player } => self.flag_versions(*player, true),
+            DomainEvent::PredictionsRestored { player } => self.flag_versions(*player, false),
+            DomainEvent::PlayerDeleted { player } => {
+                self.deleted_players.insert(*player);
+            }
+            DomainEvent::PlayerRestored { player } => {
+                self.deleted_players.remove(player);
+            }
+            DomainEvent::PlayerRenamed { .. }
+            | DomainEvent::PlayerErased { .. }
+            | DomainEvent::StageCompleted { .. }
+            | DomainEvent::TeamWithdrawn { .. }
+            | DomainEvent::PlayoffWinnerSet { .. } => {}
+        }
+    }
+
+    /// The fixtures with the replayed results and kickoffs
+    ///
+    /// Games without an event are unplayed, whatever their state in `fixtures`.
+    /// Fair play scores are not part of the events, a game keeps the fair play score of the
+    /// fixture if it is played in both.
+    ///
+    /// # Errors
+    ///
+    /// A void status is only valid for abandoned and annulled games.
+    pub fn groups(&self, fixtures: &Groups) -> Result<Groups, GroupError> {
+        fixtures
+            .iter()
+            .map(|(id, group)| Ok((*id, self.group(group)?)))
+            .collect()
+    }
+
+    fn group(&self, fixtures: &Group) -> Result<Group, GroupError> {
+        let mut unplayed = Vec::new();
+        let mut played = Vec::new();
+        let mut void = Vec::new();
+        for game in fixtures.games() {
+            let (base, fair_play) = match game {
+                GroupGame::Played(game) => (game.unplay(), game.fair_play),
+                GroupGame::Unplayed(game) => (*game, FairPlayScore::default()),
+                GroupGame::Void(game) => (game.restore(), FairPlayScore::default()),
+            };
+            let base = match self.kickoffs.get(&base.id) {
+                Some(kickoff) => {
+                    UnplayedGroupGame::try_new(base.id, base.home, base.away, *kickoff)?
+                }
+                None => base,
+            };
+            match self.games.get(&base.id) {
+                Some(GameState {
+                    status: GameStatus::Played,
+                    score: Some(score),
+                }) => played.push(base.play(*score, fair_play)),
+                Some(GameState { status, .. }) if status.is_void() => {
+                    void.push(base.void(*status)?)
+                }
+                _ => unplayed.push(base),
+            }
+        }
+        Group::try_new(unplayed, played)?.with_void_games(void)
+    }
+
+    /// Effective predictions of every player who is not deleted, by the kickoffs of `groups`
+    ///
+    /// Soft-deleted versions are left out, see [`effective_prediction`].
+    pub fn predictions(&self, groups: &Groups) -> Vec<(PlayerId, Vec<Prediction>)> {
+        let deadlines: HashMap<GameId, Date> = groups
+            .values()
+            .flat_map(|group| group.games())
+            .map(|game| (game.id(), game.date()))
+            .collect();
+        self.versions
+            .iter()
+            .filter(|(player, _)| !self.deleted_players.contains(player))
+            .sorted_by_key(|(player, _)| i32::from(**player))
+            .map(|(player, versions)| {
+                let preds = versions
+                    .iter()
+                    .filter(|(_, deleted)| !deleted)
+                    .map(|(version, _)| version)
+                    .into_group_map_by(|version| version.pred.0)
+                    .into_iter()
+                    .sorted_by_key(|(game, _)| *game)
+                    .filter_map(|(game, versions)| match deadlines.get(&game) {
+                        Some(deadline) => effective_prediction(versions.into_iter(), *deadline),
+                        None => versions
+                            .into_iter()
+                            .max_by_key(|version| version.submitted)
+                            .map(|version| version.pred),
+                    })
+                    .collect();
+                (*player, preds)
+            })
+            .collect()
+    }
+
+    /// Flag the versions of a player, or of every player
+    fn flag_versions(&mut self, player: Option<PlayerId>, deleted: bool) {
+        self.versions
+            .iter_mut()
+            .filter(|(id, _)| player.is_none() || player == Some(**id))
+            .flat_map(|(_, versions)| versions.iter_mut())
+            .for_each(|(_, flag)| *flag = deleted);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group::GroupId;
+
+    fn fixtures() -> Groups {
+        let games = vec![(0, 1, 2), (1, 3, 4)]
+            .into_iter()
+            .map(|(id, home, away)| {
+                UnplayedGroupGame::try_new(id, home, away, Date::mock().add_hours(1))
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let mut groups = Groups::new();
+        groups.insert(GroupId::from('A'), Group::try_new(games, vec![]).unwrap());
+        groups
+    }
+
+    #[test]
+    fn amended_and_annulled() {
+        let (game_0, game_1) = (GameId::from(0), GameId::from(1));
+        let events = vec![
+            DomainEvent::ResultPosted {
+                game: game_0,
+                score: Score::from((1, 0)),
+            },
+            DomainEvent::ResultAmended {
+                game: game_0,
+                previous: Score::from((1, 0)),
+                score: Score::from((2, 0)),
+            },
+            DomainEvent::ResultPosted {
+                game: game_1,
+                score: Score::from((0, 0)),
+            },
+            DomainEvent::GameStatusChanged {
+                game: game_1,
+                status: GameStatus::Annulled,
+                score: Some(Score::from((0, 0))),
+            },
+        ];
+        let groups = replay(&events).groups(&fixtures()).unwrap();
+        let group = &groups[&GroupId::from('A')];
+        let played: Vec<(GameId, Score)> = group
+            .played_games()
+            .map(|game| (game.id, game.score))
+            .collect();
+        assert_eq!(played, vec![(game_0, Score::from((2, 0)))]);
+        assert_eq!(group.void_games().count(), 1);
+    }
+
+    #[test]
+    fn deleted_predictions() {
+        let (player_1, player_2) = (PlayerId::from(1), PlayerId::from(2));
+        let submitted = |player| DomainEvent::PredictionSubmitted {
+            player,
+            preds: vec![Prediction(GameId::from(0), Score::from((1, 1)))],
+            submitted: Date::mock(),
+        };
+        let events = vec![
+            submitted(player_1),
+            submitted(player_2),
+            DomainEvent::PredictionsDeleted {
+                player: Some(player_1),
+            },
+            DomainEvent::PlayerDeleted { player: player_2 },
+        ];
+        let mut state = replay(&events);
+        let preds = state.predictions(&fixtures());
+        assert_eq!(preds, vec![(player_1, vec![])]);
+        state.apply(&DomainEvent::PredictionsRestored { player: None });
+        assert_eq!(state.predictions(&fixtures())[0].1.len(), 1);
+    }
+}
@@ -0,0 +1,226 @@
+//! Tournament rules
+//!
+//! Everything that differs between tournaments in one record: the group ordering rules, the
+//! point scheme of the group tables, the bracket template, how the best third placed teams are
+//! compared, what becomes of the games of a withdrawn team and the default scoring of the
+//! predictions.
+//!
+//! The rules are serializable and stored per tournament. The ordering rules are therefore
+//! selected by name, see [`order::rules_by_name`](crate::group::order::rules_by_name) and
+//! [`cross_group::rules_by_name`](crate::group::cross_group::rules_by_name).
+use crate::game::Score;
+use crate::group::cross_group::{self, CrossGroupRules, GameFilter};
+use crate::group::order::{self, AnyTiebreaker, Rules};
+use crate::group::stats::{PointScheme, ThreePoints, TwoPoints};
+use crate::group::withdrawal::WithdrawalPolicy;
+use crate::group::{Group, GroupError, GroupPoint};
+use crate::pred_score::ScoringRules;
+use crate::team::Teams;
+use serde::{Deserialize, Serialize};
+
+/// The rules of a tournament
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TournamentRules {
+    /// Group ordering rules, one of [`order::RULE_NAMES`]
+    pub group_rules: String,
+    /// Points awarded in the group tables
+    pub points: PointSystem,
+    /// Name of the template the bracket is seeded from, e.g. "euro-2020", if there is a
+    /// knockout stage
+    pub bracket: Option<String>,
+    pub third_place: ThirdPlacePolicy,
+    /// Games of a team withdrawing from its group, annulled if left out
+    #[serde(default)]
+    pub withdrawal: WithdrawalPolicy,
+    /// Default scoring of the predictions, leagues have their own
+    pub scoring: ScoringRules,
+}
+
+/// Points for the outcome of a group game, see [`PointScheme`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PointSystem {
+    ThreePoints,
+    TwoPoints,
+}
+
+/// Comparison of the third placed teams from different groups
+///
+/// Only used if the bracket takes third placed teams, see
+/// [`Feeder::ThirdPlace`](crate::playoff::bracket::Feeder::ThirdPlace).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThirdPlacePolicy {
+    /// Cross group rules, one of [`cross_group::RULE_NAMES`]
+    pub rules: String,
+    pub filter: GameFilter,
+}
+
+/// The rules used before they were configurable: Fifa 2018 group rules, three points for a win,
+/// Euro 2020 rules for the third placed teams, the games of a withdrawn team annulled and the
+/// default scoring.
+impl Default for TournamentRules {
+    fn default() -> Self {
+        TournamentRules {
+            group_rules: String::from("fifa_2018"),
+            points: PointSystem::ThreePoints,
+            bracket: None,
+            third_place: ThirdPlacePolicy {
+                rules: String::from("euro_2020"),
+                filter: GameFilter::All,
+            },
+            withdrawal: WithdrawalPolicy::default(),
+            scoring: ScoringRules::default(),
+        }
+    }
+}
+
+impl TournamentRules {
+    /// Check that the rule names are known and that the points are those of the group rules
+    pub fn validate(&self) -> Result<(), GroupError> {
+        if !cross_group::RULE_NAMES.contains(&self.third_place.rules.as_str()) {
+            return Err(GroupError::UnknownRules);
+        }
+        match PointSystem::of_rules(&self.group_rules) {
+            None => Err(GroupError::UnknownRules),
+            Some(points) if points != self.points => Err(GroupError::PointsMismatch),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Group ordering rules, ranking tiebreakers use the rank of the `teams`
+    pub fn group_rules(
+        &self,
+        groups: &[Group],
+        teams: &Teams,
+    ) -> Result<Rules<AnyTiebreaker>, GroupError> {
+        let ranking = teams.values().map(|team| (team.id, team.rank)).collect();
+        order::rules_by_name(&self.group_rules, groups, ranking)
+    }
+
+    /// Rules for the third placed teams, with the filter of the [`ThirdPlacePolicy`]
+    ///
+    /// Teams from groups of unequal size are compared on the same number of games, a filter
+    /// counting every game is replaced by [`GameFilter::EqualizeGroupSizes`].
+    pub fn third_place_rules(
+        &self,
+        groups: &[Group],
+        teams: &Teams,
+    ) -> Result<CrossGroupRules<AnyTiebreaker>, GroupError> {
+        let ranking = teams.values().map(|team| (team.id, team.rank)).collect();
+        let sizes = groups.iter().map(Group::num_teams);
+        let filter = match &self.third_place.filter {
+            GameFilter::All if sizes.clone().min() != sizes.max() => GameFilter::EqualizeGroupSizes,
+            filter => filter.clone(),
+        };
+        Ok(
+            cross_group::rules_by_name(&self.third_place.rules, groups, ranking)?
+                .with_filter(filter),
+        )
+    }
+}
+
+impl PointSystem {
+    /// Point system of the group ordering rules with the `name`, two points for a win before 1994
+    pub fn of_rules(name: &str) -> Option<PointSystem> {
+        match name {
+            "fifa_1990" => Some(PointSystem::TwoPoints),
+            name if order::RULE_NAMES.contains(&name) => Some(PointSystem::ThreePoints),
+            _ => None,
+        }
+    }
+
+    /// Points for the home and away team
+    pub fn points(self, score: Score) -> (GroupPoint, GroupPoint) {
+        match self {
+            PointSystem::ThreePoints => ThreePoints::points(score),
+            PointSystem::TwoPoints => TwoPoints::points(score),
+        }
+    }
+
+    /// Points of a team from its record
+    pub fn record_points(self, wins: u32, draws: u32, losses: u32) -> u32 {
+        let (win, draw, loss) = match self {
+            PointSystem::ThreePoints => (ThreePoints::WIN, ThreePoints::DRAW, ThreePoints::LOSS),
+            PointSystem::TwoPoints => (TwoPoints::WIN, TwoPoints::DRAW, TwoPoints::LOSS),
+        };
+        wins * u32::from(win.0) + draws * u32::from(draw.0) + losses * u32::from(loss.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group::mock_data;
+    use crate::stage::tests::{lower_id_wins, played_round_robin, teams};
+
+    #[test]
+    fn round_trip() {
+        let rules = TournamentRules {
+            points: PointSystem::TwoPoints,
+            bracket: Some(String::from("euro-2020")),
+            ..TournamentRules::default()
+        };
+        let json = serde_json::to_string(&rules).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TournamentRules>(&json).unwrap(),
+            rules
+        );
+    }
+
+    #[test]
+    fn unknown_rules() {
+        let rules = TournamentRules {
+            group_rules: String::from("fifa_1930"),
+            ..TournamentRules::default()
+        };
+        assert!(matches!(rules.validate(), Err(GroupError::UnknownRules)));
+        let (groups, teams) = mock_data();
+        let groups: Vec<Group> = groups.values().cloned().collect();
+        assert!(TournamentRules::default()
+            .third_place_rules(&groups, &teams)
+            .is_ok());
+    }
+
+    #[test]
+    fn unequal_groups_are_equalized() {
+        let (_, teams_) = mock_data();
+        let rules = TournamentRules::default();
+        let filter = |sizes: &[u32]| {
+            let groups: Vec<Group> = sizes
+                .iter()
+                .map(|size| {
+                    let ids: Vec<u32> = (1..=*size).collect();
+                    played_round_robin(&teams(&ids), 1, lower_id_wins)
+                })
+                .collect();
+            rules
+                .third_place_rules(&groups, &teams_)
+                .unwrap()
+                .filter()
+                .clone()
+        };
+        assert_eq!(filter(&[4, 4]), GameFilter::All);
+        assert_eq!(filter(&[3, 5]), GameFilter::EqualizeGroupSizes);
+    }
+
+    #[test]
+    fn points_of_rules() {
+        let rules = TournamentRules {
+            group_rules: String::from("fifa_1990"),
+            ..TournamentRules::default()
+        };
+        assert!(matches!(rules.validate(), Err(GroupError::PointsMismatch)));
+        let rules = TournamentRules {
+            points: PointSystem::TwoPoints,
+            ..rules
+        };
+        assert!(rules.validate().is_ok());
+        assert!(TournamentRules::default().validate().is_ok());
+    }
+
+    #[test]
+    fn record_points() {
+        assert_eq!(PointSystem::ThreePoints.record_points(2, 1, 0), 7);
+        assert_eq!(PointSystem::TwoPoints.record_points(2, 1, 0), 5);
+    }
+}
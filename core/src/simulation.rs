@@ -0,0 +1,283 @@
+//! Monte Carlo simulation
+//!
+//! Plays the remaining group games many times and counts the final group positions of every team.
+//! The simulations are independent and run in parallel (with `rayon`).
+//!
+//! Every simulation gets its own rng, seeded from the simulation index if a seed is given.
+//! The result for a given seed is therefore the same regardless of the number of threads.
+//!
+//! Every worker thread keeps a [`GroupOrderCache`], with only a few games left many simulations
+//! end with the same group results.
+//! The sampled results are ordered through a [`GroupView`], the groups are never cloned.
+use crate::fair_play::FairPlayScore;
+use crate::game::{GameId, Score};
+use crate::goal_model::GoalModel;
+use crate::group::order::GroupRank;
+use crate::group::order_cache::GroupOrderCache;
+use crate::group::view::GroupView;
+use crate::group::{Groups, Rules, Tiebreaker};
+use crate::team::TeamId;
+use num::Zero;
+use rand::{rngs::StdRng, thread_rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Cancellation token
+///
+/// Cheap to clone, all clones share the same state.
+/// Cancelling a running simulation makes [`simulate_n`] return early with
+/// [`SimulationError::Cancelled`].
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Simulation progress, passed to the progress callback
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Progress {
+    pub done: u32,
+    pub total: u32,
+}
+
+/// Final group positions from a number of simulations
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SimulationSummary {
+    pub num_simulations: u32,
+    /// Number of times each team finished in each group position,
+    /// indexed by [`GroupRank`].
+    pub positions: HashMap<TeamId, Vec<u32>>,
+}
+
+impl SimulationSummary {
+    fn empty() -> Self {
+        SimulationSummary {
+            num_simulations: 0,
+            positions: HashMap::new(),
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        self.num_simulations += other.num_simulations;
+        for (team, counts) in other.positions {
+            let entry = self.positions.entry(team).or_default();
+            if entry.len() < counts.len() {
+                entry.resize(counts.len(), 0);
+            }
+            entry
+                .iter_mut()
+                .zip(counts)
+                .for_each(|(acc, count)| *acc += count);
+        }
+        self
+    }
+
+    fn add_position(&mut self, team: TeamId, rank: GroupRank) {
+        let entry = self.positions.entry(team).or_default();
+        if entry.len() <= rank.0 {
+            entry.resize(rank.0 + 1, 0);
+        }
+        entry[rank.0] += 1;
+    }
+
+    /// Estimated probability that `team` finishes at `rank` in its group
+    pub fn probability(&self, team: TeamId, rank: GroupRank) -> f64 {
+        if self.num_simulations == 0 {
+            return 0.0;
+        }
+        let count = self
+            .positions
+            .get(&team)
+            .and_then(|counts| counts.get(rank.0))
+            .copied()
+            .unwrap_or(0);
+        f64::from(count) / f64::from(self.num_simulations)
+    }
+}
+
+/// Simulate the remaining group games `n` times
+///
+/// `model` samples the scores of the unplayed games.
+/// `progress` is called (from the worker threads) roughly every percent of the simulations.
+pub fn simulate_n<T, M, P>(
+    n: u32,
+    groups: &Groups,
+    rules: &Rules<T>,
+    model: &M,
+    seed: Option<u64>,
+    token: &CancellationToken,
+    progress: P,
+) -> Result<SimulationSummary, SimulationError>
+where
+    T: Tiebreaker + Sync,
+    M: GoalModel + Sync,
+    P: Fn(Progress) + Sync,
+{
+    let done = AtomicU32::new(0);
+    let report_interval = (n / 100).max(1);
+    let summary = (0..n)
+        .into_par_iter()
+        .fold(
+            || (SimulationSummary::empty(), GroupOrderCache::new()),
+            |(mut acc, mut cache), idx| {
+                if token.is_cancelled() {
+                    return (acc, cache);
+                }
+                let mut rng = sim_rng(seed, idx);
+                simulate_once(groups, rules, model, &mut rng, &mut cache, &mut acc);
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                if (done % report_interval).is_zero() || done == n {
+                    progress(Progress { done, total: n });
+                }
+                (acc, cache)
+            },
+        )
+        .map(|(acc, _)| acc)
+        .reduce(SimulationSummary::empty, SimulationSummary::merge);
+    if token.is_cancelled() {
+        Err(SimulationError::Cancelled)
+    } else {
+        Ok(summary)
+    }
+}
+
+/// Sample the scores of all unplayed games `n` times
+///
+/// Unlike [`simulate_n`] the outcomes are not aggregated, every simulated tournament is returned
+/// as a map from game to score. Used when the same outcomes are to be evaluated in several ways,
+/// e.g. to project the leaderboard for every player.
+pub fn simulate_scores<M>(
+    n: u32,
+    groups: &Groups,
+    model: &M,
+    seed: Option<u64>,
+) -> Vec<HashMap<GameId, Score>>
+where
+    M: GoalModel + Sync,
+{
+    (0..n)
+        .into_par_iter()
+        .map(|idx| {
+            let mut rng = sim_rng(seed, idx);
+            groups
+                .values()
+                .flat_map(|group| group.unplayed_games())
+                .map(|game| (game.id, model.sample(game.home, game.away, &mut rng)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Rules id in the order cache, the rules are the same for every simulation of a run
+const SIMULATION_RULES: &str = "simulation";
+
+/// Rng for simulation number `idx`
+fn sim_rng(seed: Option<u64>, idx: u32) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(u64::from(idx))),
+        None => StdRng::from_rng(thread_rng()).unwrap(),
+    }
+}
+
+/// Play all unplayed games once and add the final group positions to `acc`
+fn simulate_once<T, M>(
+    groups: &Groups,
+    rules: &Rules<T>,
+    model: &M,
+    rng: &mut StdRng,
+    cache: &mut GroupOrderCache,
+    acc: &mut SimulationSummary,
+) where
+    T: Tiebreaker,
+    M: GoalModel,
+{
+    for group in groups.values() {
+        let results: Vec<_> = group
+            .unplayed_games()
+            .map(|game| {
+                let score = model.sample(game.home, game.away, rng);
+                game.play(score, FairPlayScore::default())
+            })
+            .collect();
+        cache
+            .order(
+                GroupView::with_results(group, &results),
+                SIMULATION_RULES,
+                rules,
+            )
+            .into_iter()
+            .enumerate()
+            .for_each(|(rank, team)| acc.add_position(team, GroupRank(rank)));
+    }
+    acc.num_simulations += 1;
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum SimulationError {
+    #[error("Simulation cancelled")]
+    Cancelled,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Score;
+    use crate::group::mock_data;
+    use crate::group::order::fifa_2018;
+    use rand::Rng;
+
+    struct HomeWin;
+
+    impl GoalModel for HomeWin {
+        fn sample<R: Rng + ?Sized>(&self, _home: TeamId, _away: TeamId, _rng: &mut R) -> Score {
+            Score::from((1, 0))
+        }
+    }
+
+    #[test]
+    fn deterministic_model() {
+        let (groups, _) = mock_data();
+        let max_done = AtomicU32::new(0);
+        let summary = simulate_n(
+            50,
+            &groups,
+            &fifa_2018(),
+            &HomeWin,
+            Some(0),
+            &CancellationToken::new(),
+            |progress| {
+                max_done.fetch_max(progress.done, Ordering::Relaxed);
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.num_simulations, 50);
+        // Group A: 1 beats 2 (played), 3 beats 4 (simulated), 1 wins on goals scored.
+        assert_eq!(summary.probability(TeamId(1), GroupRank(0)), 1.0);
+        assert_eq!(summary.probability(TeamId(3), GroupRank(1)), 1.0);
+        assert_eq!(max_done.into_inner(), 50);
+    }
+
+    #[test]
+    fn cancelled() {
+        let (groups, _) = mock_data();
+        let token = CancellationToken::new();
+        token.cancel();
+        let res = simulate_n(10, &groups, &fifa_2018(), &HomeWin, None, &token, |_| {});
+        assert_eq!(res, Err(SimulationError::Cancelled));
+    }
+}
@@ -0,0 +1,218 @@
+//! Advancement
+//!
+//! The teams advancing from a stage to the next are declared by a list of [`AdvancementRule`]s,
+//! e.g. for the group stage of Euro 2020, the two best teams of every group and the four best
+//! third placed teams:
+//!
+//! ```
+//! # use wwc_core::group::order::GroupRank;
+//! # use wwc_core::stage::advancement::AdvancementRule;
+//! let rules = vec![
+//!     AdvancementRule::Top(2),
+//!     AdvancementRule::BestAtRank {
+//!         rank: GroupRank(2),
+//!         num: 4,
+//!     },
+//! ];
+//! ```
+use crate::group::order::GroupRank;
+use crate::group::{GroupError, GroupId, GroupPoint};
+use crate::stage::Stage;
+use crate::team::TeamId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdvancementRule {
+    /// The `n` best teams of every table
+    Top(usize),
+    /// The `num` best teams at `rank` across all tables, e.g. the best third placed teams,
+    /// compared by [`Stage::rank_across_tables`]
+    BestAtRank { rank: GroupRank, num: usize },
+    /// Every team with at least these points, never met in knockout stages
+    PointsThreshold(GroupPoint),
+}
+
+/// Team advancing to the next stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entrant {
+    /// Table of the team, `None` for stages with a single pool
+    pub group: Option<GroupId>,
+    /// Final position in the table
+    pub rank: GroupRank,
+    pub team: TeamId,
+}
+
+/// Teams advancing from `stage`
+///
+/// The rules are evaluated in order, a team advancing by more than one rule is listed once, by
+/// the first.
+/// The teams of a rule are listed by table and rank, except for
+/// [`AdvancementRule::BestAtRank`] which lists the best team first.
+///
+/// Only final results count: teams advance from a table once it is complete,
+/// and across tables once the whole stage is complete.
+///
+/// # Errors
+///
+/// The teams across tables could not be ranked, see [`Stage::rank_across_tables`].
+pub fn advance<S: Stage + ?Sized>(
+    stage: &S,
+    rules: &[AdvancementRule],
+) -> Result<Vec<Entrant>, GroupError> {
+    let tables: Vec<_> = stage
+        .tables()
+        .into_iter()
+        .filter(|table| table.complete)
+        .collect();
+    let ranked = |filter: &dyn Fn(GroupRank, Option<GroupPoint>) -> bool| -> Vec<Entrant> {
+        tables
+            .iter()
+            .flat_map(|table| {
+                table
+                    .rows
+                    .iter()
+                    .enumerate()
+                    .filter(|(rank, row)| filter(GroupRank(*rank), row.points))
+                    .map(move |(rank, row)| Entrant {
+                        group: table.group,
+                        rank: GroupRank(rank),
+                        team: row.team,
+                    })
+            })
+            .collect()
+    };
+    let mut advanced = HashSet::new();
+    let mut entrants = Vec::new();
+    for rule in rules {
+        let rule_entrants = match *rule {
+            AdvancementRule::Top(num) => ranked(&|rank, _| rank.0 < num),
+            AdvancementRule::BestAtRank { rank, num } if stage.is_complete() => stage
+                .rank_across_tables(rank)?
+                .into_iter()
+                .take(num)
+                .map(|(group, team)| Entrant { group, rank, team })
+                .collect(),
+            AdvancementRule::BestAtRank { .. } => Vec::new(),
+            AdvancementRule::PointsThreshold(threshold) => {
+                ranked(&|_, points| matches!(points, Some(points) if points >= threshold))
+            }
+        };
+        entrants.extend(
+            rule_entrants
+                .into_iter()
+                .filter(|entrant| advanced.insert(entrant.team)),
+        );
+    }
+    Ok(entrants)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::game::Score;
+    use crate::group::game::UnplayedGroupGame;
+    use crate::group::order::fifa_2018;
+    use crate::group::{Group, Groups};
+    use crate::stage::GroupStage;
+    use crate::Date;
+
+    /// Group A: 1 > 2 > 3 (1 point), B: 4 > 5 (3 points) > 6 (0 points), C: 7 > 8, 8 - 9 unplayed
+    fn groups() -> Groups {
+        let game = |id: u32, home: u32, away: u32, score: (u32, u32)| {
+            UnplayedGroupGame::try_new(id, home, away, Date::mock())
+                .unwrap()
+                .play(Score::from(score), FairPlayScore::default())
+        };
+        let group = |ids: [u32; 3], teams: [u32; 3], scores: [(u32, u32); 3]| {
+            Group::try_new(
+                vec![],
+                vec![
+                    game(ids[0], teams[0], teams[1], scores[0]),
+                    game(ids[1], teams[0], teams[2], scores[1]),
+                    game(ids[2], teams[1], teams[2], scores[2]),
+                ],
+            )
+            .unwrap()
+        };
+        let mut groups = Groups::new();
+        groups.insert(
+            GroupId::from('A'),
+            group([1, 2, 3], [1, 2, 3], [(1, 0), (2, 0), (0, 0)]),
+        );
+        groups.insert(
+            GroupId::from('B'),
+            group([4, 5, 6], [4, 5, 6], [(3, 0), (3, 0), (3, 0)]),
+        );
+        groups.insert(
+            GroupId::from('C'),
+            Group::try_new(
+                vec![UnplayedGroupGame::try_new(8, 8, 9, Date::mock()).unwrap()],
+                vec![game(7, 7, 8, (1, 0))],
+            )
+            .unwrap(),
+        );
+        groups
+    }
+
+    fn teams(entrants: &[Entrant]) -> Vec<u32> {
+        entrants.iter().map(|entrant| entrant.team.0).collect()
+    }
+
+    #[test]
+    fn top_and_best_at_rank() {
+        let mut groups = groups();
+        let rules = fifa_2018();
+        let advancement = [
+            AdvancementRule::Top(2),
+            AdvancementRule::BestAtRank {
+                rank: GroupRank(2),
+                num: 1,
+            },
+        ];
+        // Group C is not complete, neither are the third placed teams.
+        let entrants = advance(&GroupStage::new(&groups, &rules), &advancement).unwrap();
+        assert_eq!(teams(&entrants), vec![1, 2, 4, 5]);
+        assert_eq!(entrants[3].group, Some(GroupId::from('B')));
+        assert_eq!(entrants[3].rank, GroupRank(1));
+        groups
+            .get_mut(&GroupId::from('C'))
+            .unwrap()
+            .play_game(8.into(), Score::from((1, 0)));
+        let entrants = advance(&GroupStage::new(&groups, &rules), &advancement).unwrap();
+        // Team 3 has a point, team 6 and 9 none.
+        assert_eq!(teams(&entrants), vec![1, 2, 4, 5, 7, 8, 3]);
+        assert_eq!(entrants[6].rank, GroupRank(2));
+    }
+
+    #[test]
+    fn points_threshold() {
+        let groups = groups();
+        let rules = fifa_2018();
+        let advancement = [
+            AdvancementRule::Top(1),
+            AdvancementRule::PointsThreshold(GroupPoint(3)),
+        ];
+        let entrants = advance(&GroupStage::new(&groups, &rules), &advancement).unwrap();
+        assert_eq!(teams(&entrants), vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn declarative() {
+        let rules: Vec<AdvancementRule> =
+            serde_json::from_str(r#"[{"top": 2}, {"best_at_rank": {"rank": 2, "num": 4}}]"#)
+                .unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                AdvancementRule::Top(2),
+                AdvancementRule::BestAtRank {
+                    rank: GroupRank(2),
+                    num: 4
+                }
+            ]
+        );
+    }
+}
@@ -0,0 +1,539 @@
+//! Tournament stages
+//!
+//! A tournament is a sequence of stages, e.g. a group stage followed by a knockout stage.
+//! The [`Stage`] trait is what every format has in common: the teams and games of the stage,
+//! whether it is complete and the resulting tables, from which the next stage is seeded.
+//!
+//! - [`GroupStage`]: groups played as round-robins, e.g. the World Cup groups,
+//!   or the mini-leagues of one league of the Nations League.
+//! - [`LeagueStage`]: a single league, e.g. a double round-robin with fixtures from
+//!   [`round_robin`].
+//! - [`PlayoffStage`]: a knockout bracket. Losers can be fed to later games, see
+//!   [`Feeder::Loser`](crate::playoff::bracket::Feeder::Loser),
+//!   so a double elimination bracket is a bracket as well.
+//!
+//! The teams advancing from a stage are selected by [`advancement::AdvancementRule`]s.
+pub mod advancement;
+use crate::game::GameId;
+use crate::group::cross_group::{rank_across_groups, CrossGroupRules};
+use crate::group::game::UnplayedGroupGame;
+use crate::group::order::{order_group, GroupRank, Rules, Tiebreaker};
+use crate::group::{Group, GroupError, GroupId, GroupPoint, Groups};
+use crate::playoff::bracket::BracketNode;
+use crate::team::TeamId;
+use crate::Date;
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::iter;
+
+pub trait Stage {
+    /// Teams in the stage, as far as they are known
+    fn teams(&self) -> Vec<TeamId>;
+
+    fn games(&self) -> Vec<GameId>;
+
+    /// Every game of the stage is played
+    fn is_complete(&self) -> bool;
+
+    /// Tables of the stage, one for every pool of teams, e.g. one per group
+    ///
+    /// The tables of an incomplete stage are provisional.
+    fn tables(&self) -> Vec<StageTable>;
+
+    /// The teams at `rank` of every table, best first
+    ///
+    /// Only meaningful for a complete stage.
+    /// By default, the teams are compared by points only, a team without points ranks last.
+    fn rank_across_tables(
+        &self,
+        rank: GroupRank,
+    ) -> Result<Vec<(Option<GroupId>, TeamId)>, GroupError> {
+        Ok(rank_by_points(&self.tables(), rank))
+    }
+}
+
+/// Teams of a pool in a stage, best first
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageTable {
+    /// `None` for stages with a single pool
+    pub group: Option<GroupId>,
+    /// Every game of the pool is played, the table is final
+    pub complete: bool,
+    pub rows: Vec<StageRow>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageRow {
+    pub team: TeamId,
+    /// Accumulated points, `None` in knockout stages
+    pub points: Option<GroupPoint>,
+}
+
+/// Groups, every group ordered by the same rules
+///
+/// Teams from different groups are compared by the cross group rules, if set,
+/// see [`GroupStage::with_cross_group_rules`].
+pub struct GroupStage<'a, T: Tiebreaker, U: Tiebreaker = T> {
+    groups: &'a Groups,
+    rules: &'a Rules<T>,
+    cross_group_rules: Option<&'a CrossGroupRules<U>>,
+}
+
+impl<'a, T: Tiebreaker> GroupStage<'a, T> {
+    pub fn new(groups: &'a Groups, rules: &'a Rules<T>) -> Self {
+        GroupStage {
+            groups,
+            rules,
+            cross_group_rules: None,
+        }
+    }
+}
+
+impl<'a, T: Tiebreaker, U: Tiebreaker> GroupStage<'a, T, U> {
+    /// Compare teams from different groups with `rules`, see [`rank_across_groups`]
+    pub fn with_cross_group_rules<V: Tiebreaker>(
+        self,
+        rules: &'a CrossGroupRules<V>,
+    ) -> GroupStage<'a, T, V> {
+        GroupStage {
+            groups: self.groups,
+            rules: self.rules,
+            cross_group_rules: Some(rules),
+        }
+    }
+}
+
+impl<T: Tiebreaker, U: Tiebreaker> Stage for GroupStage<'_, T, U> {
+    fn teams(&self) -> Vec<TeamId> {
+        self.groups
+            .values()
+            .flat_map(|group| group.teams())
+            .unique()
+            .collect()
+    }
+
+    fn games(&self) -> Vec<GameId> {
+        self.groups.values().flat_map(group_games).collect()
+    }
+
+    fn is_complete(&self) -> bool {
+        !self.groups.is_empty() && self.groups.values().all(group_complete)
+    }
+
+    fn tables(&self) -> Vec<StageTable> {
+        self.groups
+            .iter()
+            .map(|(id, group)| StageTable {
+                group: Some(*id),
+                complete: group_complete(group),
+                rows: group_rows(group, self.rules),
+            })
+            .collect()
+    }
+
+    fn rank_across_tables(
+        &self,
+        rank: GroupRank,
+    ) -> Result<Vec<(Option<GroupId>, TeamId)>, GroupError> {
+        match self.cross_group_rules {
+            Some(cross_group_rules) => {
+                Ok(
+                    rank_across_groups(self.groups, self.rules, rank, cross_group_rules)?
+                        .into_iter()
+                        .map(|entry| (Some(entry.group), entry.team))
+                        .collect(),
+                )
+            }
+            None => Ok(rank_by_points(&self.tables(), rank)),
+        }
+    }
+}
+
+/// Single league, e.g. a double round-robin
+///
+/// Modelled as a single group, without any restriction on the number of games between two teams.
+pub struct LeagueStage<'a, T: Tiebreaker> {
+    league: &'a Group,
+    rules: &'a Rules<T>,
+}
+
+impl<'a, T: Tiebreaker> LeagueStage<'a, T> {
+    pub fn new(league: &'a Group, rules: &'a Rules<T>) -> Self {
+        LeagueStage { league, rules }
+    }
+}
+
+impl<T: Tiebreaker> Stage for LeagueStage<'_, T> {
+    fn teams(&self) -> Vec<TeamId> {
+        self.league.teams().collect()
+    }
+
+    fn games(&self) -> Vec<GameId> {
+        group_games(self.league).collect()
+    }
+
+    fn is_complete(&self) -> bool {
+        group_complete(self.league)
+    }
+
+    fn tables(&self) -> Vec<StageTable> {
+        vec![StageTable {
+            group: None,
+            complete: group_complete(self.league),
+            rows: group_rows(self.league, self.rules),
+        }]
+    }
+}
+
+/// Knockout stage, from a bracket layout, see
+/// [`Bracket::layout`](crate::playoff::bracket::Bracket::layout)
+pub struct PlayoffStage {
+    nodes: Vec<BracketNode>,
+}
+
+impl PlayoffStage {
+    pub fn new(nodes: Vec<BracketNode>) -> Self {
+        PlayoffStage { nodes }
+    }
+}
+
+impl Stage for PlayoffStage {
+    fn teams(&self) -> Vec<TeamId> {
+        self.nodes
+            .iter()
+            .flat_map(|node| node.home.team.into_iter().chain(node.away.team))
+            .unique()
+            .collect()
+    }
+
+    fn games(&self) -> Vec<GameId> {
+        self.nodes.iter().map(|node| node.game_id).collect()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.nodes.iter().all(|node| node.winner.is_some())
+    }
+
+    /// A single table, teams ranked by their last game
+    ///
+    /// Teams whose last game is in a later round rank higher, in the same round by the slot of the
+    /// game, e.g. the final before the third place play-off, and the winner before the loser.
+    /// Teams still waiting for the result of their last game rank as its winner.
+    fn tables(&self) -> Vec<StageTable> {
+        let mut last_game = HashMap::new();
+        for node in self.nodes.iter().sorted_by_key(|node| node.round) {
+            for team in node.home.team.into_iter().chain(node.away.team) {
+                let lost = matches!(node.winner, Some(winner) if winner != team);
+                last_game.insert(team, (Reverse(node.round), node.slot, lost));
+            }
+        }
+        let rows = last_game
+            .into_iter()
+            .sorted_by_key(|(team, key)| (*key, team.0))
+            .map(|(team, _)| StageRow { team, points: None })
+            .collect();
+        vec![StageTable {
+            group: None,
+            complete: self.is_complete(),
+            rows,
+        }]
+    }
+}
+
+/// Round-robin fixtures, where every team meets every other team `legs` times
+///
+/// Scheduled with the circle method: every team plays at most once per matchday,
+/// with an odd number of teams one team rests every matchday.
+/// Home and away are swapped in every other leg.
+/// The games get consecutive id's from `first_id` and `kickoff` gives the kickoff of the games of
+/// a matchday, counted from 0.
+///
+/// # Errors
+///
+/// Fails if a team is listed more than once.
+pub fn round_robin<F: Fn(usize) -> Date>(
+    teams: &[TeamId],
+    legs: u8,
+    first_id: GameId,
+    kickoff: F,
+) -> Result<Vec<UnplayedGroupGame>, GroupError> {
+    // An odd number of teams is padded with a rest, `None`.
+    let mut circle: Vec<Option<TeamId>> = teams.iter().copied().map(Some).collect();
+    if circle.len() % 2 == 1 {
+        circle.push(None);
+    }
+    let num_teams = circle.len();
+    let rounds: Vec<Vec<(TeamId, TeamId)>> = (0..num_teams.saturating_sub(1))
+        .map(|round| {
+            let pairs = (0..num_teams / 2)
+                .filter_map(|idx| match (circle[idx], circle[num_teams - 1 - idx]) {
+                    (Some(home), Some(away)) if idx == 0 && round % 2 == 1 => Some((away, home)),
+                    (Some(home), Some(away)) => Some((home, away)),
+                    _ => None,
+                })
+                .collect();
+            // Keep the first team in place and rotate the others.
+            circle[1..].rotate_right(1);
+            pairs
+        })
+        .collect();
+    let mut ids = iter::successors(Some(u32::from(first_id)), |id| Some(id + 1));
+    (0..usize::from(legs))
+        .flat_map(|leg| iter::repeat(leg).zip(rounds.iter().enumerate()))
+        .flat_map(|(leg, (round, pairs))| {
+            let matchday = leg * rounds.len() + round;
+            pairs.iter().map(move |(home, away)| {
+                if leg % 2 == 0 {
+                    (matchday, *home, *away)
+                } else {
+                    (matchday, *away, *home)
+                }
+            })
+        })
+        .map(|(matchday, home, away)| {
+            let id = ids.next().expect("Unbounded ids");
+            UnplayedGroupGame::try_new(id, home, away, kickoff(matchday))
+        })
+        .collect()
+}
+
+fn group_games(group: &Group) -> impl Iterator<Item = GameId> + '_ {
+    group.games().map(|game| game.id())
+}
+
+/// Teams at `rank` of every table, by points, see [`Stage::rank_across_tables`]
+fn rank_by_points(tables: &[StageTable], rank: GroupRank) -> Vec<(Option<GroupId>, TeamId)> {
+    tables
+        .iter()
+        .filter_map(|table| table.rows.get(rank.0).map(|row| (table.group, *row)))
+        .sorted_by_key(|(_, row)| Reverse(row.points))
+        .map(|(group, row)| (group, row.team))
+        .collect()
+}
+
+/// A group without any games is not complete, there is no order to it
+fn group_complete(group: &Group) -> bool {
+    group.unplayed_games().next().is_none() && group.played_games().next().is_some()
+}
+
+fn group_rows<T: Tiebreaker>(group: &Group, rules: &Rules<T>) -> Vec<StageRow> {
+    let points = group.points();
+    order_group(group, rules)
+        .into_iter()
+        .map(|team| StageRow {
+            team,
+            points: Some(points.get(&team).copied().unwrap_or_default()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::fair_play::FairPlayScore;
+    use crate::game::Score;
+    use crate::group::order::fifa_2018;
+    use crate::playoff::bracket::{BracketTeam, Feeder};
+    use crate::playoff::RoundIdx;
+    use std::collections::HashSet;
+
+    pub(crate) fn teams(ids: &[u32]) -> Vec<TeamId> {
+        ids.iter().map(|id| TeamId(*id)).collect()
+    }
+
+    /// Group of a single round-robin of `teams`, with game ids from `first_id` and the
+    /// `score(home, away)` of every game
+    pub(crate) fn played_round_robin(
+        teams: &[TeamId],
+        first_id: u32,
+        score: impl Fn(TeamId, TeamId) -> (u32, u32),
+    ) -> Group {
+        let games = round_robin(teams, 1, GameId::from(first_id), |_| Date::mock())
+            .unwrap()
+            .into_iter()
+            .map(|game| {
+                let score = Score::from(score(game.home, game.away));
+                game.play(score, FairPlayScore::default())
+            })
+            .collect();
+        Group::try_new(vec![], games).unwrap()
+    }
+
+    /// The team with the lower id wins 1-0
+    pub(crate) fn lower_id_wins(home: TeamId, away: TeamId) -> (u32, u32) {
+        if home.0 < away.0 {
+            (1, 0)
+        } else {
+            (0, 1)
+        }
+    }
+
+    #[test]
+    fn double_round_robin() {
+        let games =
+            round_robin(&teams(&[1, 2, 3, 4]), 2, GameId::from(10), |_| Date::mock()).unwrap();
+        assert_eq!(games.len(), 12);
+        assert_eq!(games.first().unwrap().id, GameId::from(10));
+        assert_eq!(games.last().unwrap().id, GameId::from(21));
+        // Every pair meets once at home and once away.
+        let meetings: HashSet<_> = games.iter().map(|game| (game.home, game.away)).collect();
+        assert_eq!(meetings.len(), 12);
+    }
+
+    #[test]
+    fn odd_round_robin() {
+        let games = round_robin(&teams(&[1, 2, 3, 4, 5]), 1, GameId::from(1), |matchday| {
+            format!("2021-06-{:02} 18:00", matchday + 1)
+                .parse()
+                .unwrap()
+        })
+        .unwrap();
+        assert_eq!(games.len(), 10);
+        let matchdays = games
+            .iter()
+            .into_group_map_by(|game| game.date().to_string());
+        assert_eq!(matchdays.len(), 5);
+        for games in matchdays.values() {
+            // One team rests every matchday.
+            assert_eq!(games.len(), 2);
+            let playing: Vec<TeamId> = games
+                .iter()
+                .flat_map(|game| vec![game.home, game.away])
+                .collect();
+            assert_eq!(playing.iter().unique().count(), 4);
+        }
+        assert!(round_robin(&teams(&[1, 1]), 1, GameId::from(1), |_| Date::mock()).is_err());
+    }
+
+    #[test]
+    fn round_robin_sizes() {
+        for size in 3..=6 {
+            let ids: Vec<u32> = (1..=size).collect();
+            let games = round_robin(&teams(&ids), 1, GameId::from(1), |matchday| {
+                format!("2021-06-{:02} 18:00", matchday + 1)
+                    .parse()
+                    .unwrap()
+            })
+            .unwrap();
+            let size = size as usize;
+            assert_eq!(games.len(), size * (size - 1) / 2);
+            let pairs: HashSet<_> = games
+                .iter()
+                .map(|game| {
+                    let (home, away) = (game.home.0, game.away.0);
+                    (home.min(away), home.max(away))
+                })
+                .collect();
+            assert_eq!(pairs.len(), games.len());
+            let matchdays = games
+                .iter()
+                .into_group_map_by(|game| game.date().to_string());
+            // With an odd number of teams, one team rests every matchday.
+            assert_eq!(matchdays.len(), size + size % 2 - 1);
+            for games in matchdays.values() {
+                let playing = games.iter().flat_map(|game| vec![game.home, game.away]);
+                assert_eq!(playing.clone().unique().count(), playing.count());
+            }
+        }
+    }
+
+    #[test]
+    fn group_order_sizes() {
+        // Every team beats the teams with a higher id.
+        for size in &[3, 5, 6] {
+            let ids: Vec<u32> = (1..=*size).collect();
+            let group = played_round_robin(&teams(&ids), 1, lower_id_wins);
+            assert_eq!(group.num_teams(), *size as usize);
+            let order = order_group(&group, &fifa_2018());
+            assert_eq!(order.len(), *size as usize);
+            assert_eq!(order.into_iter().collect::<Vec<_>>(), teams(&ids));
+            let points = group.points();
+            assert_eq!(points[&TeamId(1)], GroupPoint(3 * (*size as u8 - 1)));
+            assert_eq!(points[&TeamId(*size)], GroupPoint(0));
+        }
+    }
+
+    #[test]
+    fn league_table() {
+        let games = round_robin(&teams(&[1, 2, 3]), 2, GameId::from(1), |_| Date::mock()).unwrap();
+        let (played, unplayed) = games.split_at(5);
+        let played = played
+            .iter()
+            .map(|game| {
+                let score = if game.home == TeamId(1) || game.away == TeamId(3) {
+                    Score::from((1, 0))
+                } else {
+                    Score::from((0, 1))
+                };
+                game.play(score, FairPlayScore::default())
+            })
+            .collect();
+        let league = Group::try_new(unplayed.to_vec(), played).unwrap();
+        let rules = fifa_2018();
+        let stage = LeagueStage::new(&league, &rules);
+        assert!(!stage.is_complete());
+        assert_eq!(stage.games().len(), 6);
+        let tables = stage.tables();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].rows[0].team, TeamId(1));
+        assert_eq!(tables[0].rows[0].points, Some(GroupPoint(9)));
+    }
+
+    #[test]
+    fn group_stage() {
+        let (groups, _) = crate::group::mock_data();
+        let rules = fifa_2018();
+        let stage = GroupStage::new(&groups, &rules);
+        assert_eq!(stage.teams().len(), 8);
+        assert_eq!(stage.games().len(), 4);
+        assert!(!stage.is_complete());
+        let tables = stage.tables();
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].group, Some(GroupId::from('A')));
+        assert_eq!(
+            tables[0].rows[0],
+            StageRow {
+                team: TeamId(1),
+                points: Some(GroupPoint(3))
+            }
+        );
+    }
+
+    #[test]
+    fn playoff_ranking() {
+        let node = |id: u32, round: u8, slot, home: u32, away: u32, winner: Option<u32>| {
+            let team = |id| BracketTeam {
+                feeder: Feeder::Winner(GameId::from(0)),
+                team: Some(TeamId(id)),
+            };
+            BracketNode {
+                game_id: GameId::from(id),
+                round: RoundIdx(round),
+                slot,
+                kickoff: Date::mock(),
+                home: team(home),
+                away: team(away),
+                winner: winner.map(TeamId),
+            }
+        };
+        let stage = PlayoffStage::new(vec![
+            node(1, 0, 0, 1, 2, Some(1)),
+            node(2, 0, 1, 3, 4, Some(4)),
+            node(3, 1, 0, 1, 4, Some(4)),
+            node(4, 1, 1, 2, 3, None),
+        ]);
+        assert!(!stage.is_complete());
+        let order: Vec<TeamId> = stage.tables()[0].rows.iter().map(|row| row.team).collect();
+        // The undecided third place play-off ranks its teams by id.
+        assert_eq!(order, teams(&[4, 1, 2, 3]));
+        assert_eq!(
+            stage
+                .teams()
+                .into_iter()
+                .sorted_by_key(|team| team.0)
+                .collect::<Vec<_>>(),
+            teams(&[1, 2, 3, 4])
+        );
+    }
+}
@@ -1,4 +1,7 @@
 //! Team
+pub mod i18n;
+pub mod resolve;
+
 use derive_more::{AsRef, Display, From, Into};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -80,15 +83,53 @@ pub struct FifaCode(String);
 #[as_ref(forward)]
 pub struct Iso2(String);
 
+/// Player in a team's squad
+///
+/// Not to be confused with [`Player`](crate::player::Player), who is a participant in the betting pool.
+#[derive(
+    Display,
+    Debug,
+    Clone,
+    AsRef,
+    From,
+    Into,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[as_ref(forward)]
+pub struct SquadPlayer(String);
+
+impl SquadPlayer {
+    pub fn new<N: AsRef<str> + ?Sized>(name: &N) -> Self {
+        SquadPlayer(String::from(name.as_ref()))
+    }
+}
+
 const FIFA_CODE_ISO2_MAP: &[(&str, &str)] = &[
+    ("CRO", "hr"),
+    ("DEN", "dk"),
     ("ENG", "gb-eng"),
+    ("GER", "de"),
+    ("KOR", "kr"),
+    ("KSA", "sa"),
+    ("MEX", "mx"),
+    ("NED", "nl"),
     ("POL", "pl"),
     ("POR", "pt"),
+    ("SEN", "sn"),
     ("SLO", "sk"),
+    ("SRB", "rs"),
     ("SUI", "ch"),
     ("SWE", "se"),
+    ("TUN", "tn"),
     ("TUR", "tr"),
     ("UKR", "ua"),
+    ("URU", "uy"),
     ("WAL", "gb-wls"),
 ];
 
@@ -0,0 +1,101 @@
+//! Localized team names
+//!
+//! The names in [`Team`](crate::team::Team) are in English, the default language.
+//! Translations are kept per language as [`LocalizedNames`] and applied with [`localize`].
+use crate::team::{TeamId, TeamName, Teams};
+use derive_more::{AsRef, Display};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Language of the team names in [`Team`](crate::team::Team)
+pub const DEFAULT_LANG: &str = "en";
+
+/// Translated team names in a single language
+pub type LocalizedNames = HashMap<TeamId, TeamName>;
+
+/// Language code, e.g. "sv", or "pt-br" for a regional variant
+///
+/// Always lowercase.
+#[derive(Debug, Clone, Display, AsRef, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[as_ref(forward)]
+pub struct Lang(String);
+
+impl Lang {
+    pub fn is_default(&self) -> bool {
+        self.0 == DEFAULT_LANG
+    }
+}
+
+impl Default for Lang {
+    fn default() -> Self {
+        Lang(String::from(DEFAULT_LANG))
+    }
+}
+
+impl FromStr for Lang {
+    type Err = LangError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lang = s.trim().to_ascii_lowercase().replace('_', "-");
+        let mut parts = lang.split('-');
+        let valid_language = matches!(parts.next(), Some(code)
+            if (2..=3).contains(&code.len()) && code.chars().all(|c| c.is_ascii_lowercase()));
+        let valid_region = parts.all(|part| {
+            (2..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+        if valid_language && valid_region {
+            Ok(Lang(lang))
+        } else {
+            Err(LangError(String::from(s)))
+        }
+    }
+}
+
+/// Replace team names with their translations
+///
+/// Teams without a translation keep their default name.
+pub fn localize(teams: &mut Teams, names: &LocalizedNames) {
+    for (id, team) in teams.iter_mut() {
+        if let Some(name) = names.get(id) {
+            team.name = name.clone();
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Invalid language code '{0}'")]
+pub struct LangError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::team::{Team, TeamRank};
+
+    #[test]
+    fn parse_lang() {
+        assert_eq!("SV".parse::<Lang>().unwrap().to_string(), "sv");
+        assert_eq!("pt_BR".parse::<Lang>().unwrap().to_string(), "pt-br");
+        assert!("en".parse::<Lang>().unwrap().is_default());
+        assert!("swedish".parse::<Lang>().is_err());
+        assert!("".parse::<Lang>().is_err());
+        assert!("sv-".parse::<Lang>().is_err());
+    }
+
+    #[test]
+    fn localize_names() {
+        let mut teams: Teams = vec![
+            Team::new(TeamId(1), "Germany", "GER", "de", TeamRank(0)),
+            Team::new(TeamId(2), "Sweden", "SWE", "se", TeamRank(1)),
+        ]
+        .into_iter()
+        .map(|team| (team.id, team))
+        .collect();
+        let names: LocalizedNames = vec![(TeamId(1), TeamName::from(String::from("Tyskland")))]
+            .into_iter()
+            .collect();
+        localize(&mut teams, &names);
+        assert_eq!(teams[&TeamId(1)].name.to_string(), "Tyskland");
+        assert_eq!(teams[&TeamId(2)].name.to_string(), "Sweden");
+    }
+}
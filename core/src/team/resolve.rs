@@ -0,0 +1,216 @@
+//! Team name resolution
+//!
+//! Maps free text team names, e.g. from imported data, chat bots or typed results, to [`TeamId`]s.
+//! A name is matched, in order, against:
+//!
+//! 1. Team names, Fifa codes and aliases, ignoring casing, punctuation and extra whitespace.
+//! 2. The same, allowing a few typos (Levenshtein distance).
+//!
+//! Aliases cover names that are too different to match on spelling,
+//! e.g. "South Korea" for "Korea Republic".
+//! A set of common aliases is built in, see [`COMMON_ALIASES`],
+//! others are added with [`TeamResolver::with_aliases`], typically from the db.
+use crate::team::{TeamId, Teams};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Common alternative names, as `(alias, Fifa code)`
+pub const COMMON_ALIASES: &[(&str, &str)] = &[
+    ("South Korea", "KOR"),
+    ("Korea", "KOR"),
+    ("North Korea", "PRK"),
+    ("Holland", "NED"),
+    ("The Netherlands", "NED"),
+    ("USA", "USA"),
+    ("United States of America", "USA"),
+    ("Iran", "IRN"),
+    ("IR Iran", "IRN"),
+    ("Ivory Coast", "CIV"),
+    ("Cote d'Ivoire", "CIV"),
+    ("Czechia", "CZE"),
+    ("Czech Rep", "CZE"),
+    ("North Macedonia", "MKD"),
+    ("Macedonia", "MKD"),
+    ("Republic of Ireland", "IRL"),
+    ("Ireland", "IRL"),
+    ("Bosnia", "BIH"),
+    ("Cape Verde", "CPV"),
+    ("China", "CHN"),
+];
+
+/// Resolves free text names to teams
+#[derive(Debug, Clone)]
+pub struct TeamResolver {
+    /// Normalized name, code or alias -> team
+    names: HashMap<String, TeamId>,
+}
+
+impl TeamResolver {
+    /// Resolver for `teams`, including the [`COMMON_ALIASES`] of the teams present
+    pub fn new(teams: &Teams) -> Self {
+        let mut names = HashMap::new();
+        for team in teams.values() {
+            names.insert(normalize(team.name.as_ref()), team.id);
+            names.insert(normalize(team.fifa_code.as_ref()), team.id);
+        }
+        for (alias, code) in COMMON_ALIASES {
+            if let Some(team) = teams
+                .values()
+                .find(|team| team.fifa_code.to_string() == *code)
+            {
+                names.entry(normalize(alias)).or_insert(team.id);
+            }
+        }
+        TeamResolver { names }
+    }
+
+    /// Add aliases, these take precedence over the built in ones
+    pub fn with_aliases<A: AsRef<str>>(
+        mut self,
+        aliases: impl IntoIterator<Item = (A, TeamId)>,
+    ) -> Self {
+        for (alias, team) in aliases {
+            self.names.insert(normalize(alias.as_ref()), team);
+        }
+        self
+    }
+
+    /// Find the team that best matches `name`
+    ///
+    /// # Errors
+    ///
+    /// Fails if no name is close enough,
+    /// or if several teams are equally close.
+    pub fn resolve(&self, name: &str) -> Result<TeamId, ResolveError> {
+        let query = normalize(name);
+        if query.is_empty() {
+            return Err(ResolveError::Unknown(String::from(name.trim())));
+        }
+        if let Some(id) = self.names.get(&query) {
+            return Ok(*id);
+        }
+        let max_distance = max_distance(&query);
+        let mut best: Option<(usize, Vec<TeamId>)> = None;
+        for (candidate, id) in &self.names {
+            let distance = levenshtein(&query, candidate);
+            if distance > max_distance {
+                continue;
+            }
+            match &mut best {
+                Some((best_distance, _)) if distance > *best_distance => {}
+                Some((best_distance, ids)) if distance == *best_distance => {
+                    if !ids.contains(id) {
+                        ids.push(*id);
+                    }
+                }
+                _ => best = Some((distance, vec![*id])),
+            }
+        }
+        match best {
+            Some((_, ids)) if ids.len() == 1 => Ok(ids[0]),
+            Some((_, mut ids)) => {
+                ids.sort_by_key(|id| id.0);
+                Err(ResolveError::Ambiguous(String::from(name.trim()), ids))
+            }
+            None => Err(ResolveError::Unknown(String::from(name.trim()))),
+        }
+    }
+}
+
+/// Lowercase alphanumeric words, separated by a single space
+pub fn normalize(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Number of typos allowed, short names like Fifa codes must match exactly
+fn max_distance(query: &str) -> usize {
+    match query.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Edit distance between two strings, in chars
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ResolveError {
+    #[error("Unknown team '{0}'")]
+    Unknown(String),
+    #[error("Ambiguous team '{0}', matches teams {1:?}")]
+    Ambiguous(String, Vec<TeamId>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::team::{Team, TeamRank};
+
+    fn teams() -> Teams {
+        vec![
+            Team::new(TeamId(1), "Sweden", "SWE", "se", TeamRank(0)),
+            Team::new(TeamId(2), "Korea Republic", "KOR", "kr", TeamRank(0)),
+            Team::new(TeamId(3), "Côte d'Ivoire", "CIV", "ci", TeamRank(0)),
+            Team::new(TeamId(4), "Austria", "AUT", "at", TeamRank(0)),
+            Team::new(TeamId(5), "Australia", "AUS", "au", TeamRank(0)),
+        ]
+        .into_iter()
+        .map(|team| (team.id, team))
+        .collect()
+    }
+
+    #[test]
+    fn exact_and_aliases() {
+        let resolver = TeamResolver::new(&teams());
+        assert_eq!(resolver.resolve("  SWEDEN "), Ok(TeamId(1)));
+        assert_eq!(resolver.resolve("swe"), Ok(TeamId(1)));
+        assert_eq!(resolver.resolve("South Korea"), Ok(TeamId(2)));
+        assert_eq!(resolver.resolve("korea-republic"), Ok(TeamId(2)));
+        assert_eq!(resolver.resolve("Ivory Coast"), Ok(TeamId(3)));
+        let resolver = resolver.with_aliases(vec![("Tre Kronor", TeamId(1))]);
+        assert_eq!(resolver.resolve("tre kronor"), Ok(TeamId(1)));
+    }
+
+    #[test]
+    fn typos() {
+        let resolver = TeamResolver::new(&teams());
+        assert_eq!(resolver.resolve("Swedn"), Ok(TeamId(1)));
+        assert_eq!(resolver.resolve("Australa"), Ok(TeamId(5)));
+        assert_eq!(
+            resolver.resolve("SWX"),
+            Err(ResolveError::Unknown(String::from("SWX")))
+        );
+        // One edit from both Austria and Australia
+        assert_eq!(
+            resolver.resolve("Austrlia"),
+            Err(ResolveError::Ambiguous(
+                String::from("Austrlia"),
+                vec![TeamId(4), TeamId(5)]
+            ))
+        );
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
+}
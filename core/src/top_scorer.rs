@@ -0,0 +1,140 @@
+//! Top scorer
+//!
+//! Goal events and the derived top scorer list (the golden boot race).
+use crate::game::{GameId, GameMinute, GoalCount};
+use crate::team::{SquadPlayer, TeamId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Kind of goal
+///
+/// Own goals count for the opposing team in the game score,
+/// but are not credited to the scorer in the top scorer list.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub enum GoalKind {
+    Regular,
+    Penalty,
+    OwnGoal,
+}
+
+/// Goal event
+///
+/// `team` is the team of the scoring player, also for own goals.
+/// Penalty shoot-out goals are not goal events.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct GoalEvent {
+    pub game: GameId,
+    pub team: TeamId,
+    pub player: SquadPlayer,
+    pub minute: GameMinute,
+    pub kind: GoalKind,
+}
+
+impl GoalEvent {
+    pub fn new<M: Into<GameMinute>>(
+        game: GameId,
+        team: TeamId,
+        player: SquadPlayer,
+        minute: M,
+        kind: GoalKind,
+    ) -> Self {
+        GoalEvent {
+            game,
+            team,
+            player,
+            minute: minute.into(),
+            kind,
+        }
+    }
+}
+
+/// Entry in the top scorer list
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TopScorer {
+    pub player: SquadPlayer,
+    pub team: TeamId,
+    pub goals: GoalCount,
+}
+
+/// Top scorer list
+///
+/// Sorted by number of goals (own goals excluded), most goals first.
+/// Players with the same number of goals are sorted by name to get a stable order,
+/// they share the position in the list.
+pub fn top_scorers<'a>(events: impl IntoIterator<Item = &'a GoalEvent>) -> Vec<TopScorer> {
+    let mut scorers: Vec<TopScorer> = events
+        .into_iter()
+        .filter(|event| event.kind != GoalKind::OwnGoal)
+        .fold(HashMap::new(), |mut acc, event| {
+            *acc.entry((event.team, event.player.clone()))
+                .or_insert_with(|| GoalCount::from(0)) += GoalCount::from(1);
+            acc
+        })
+        .into_iter()
+        .map(|((team, player), goals)| TopScorer {
+            player,
+            team,
+            goals,
+        })
+        .collect();
+    scorers.sort_by(|a, b| b.goals.cmp(&a.goals).then_with(|| a.player.cmp(&b.player)));
+    scorers
+}
+
+/// Top scorers, i.e. the players sharing the first position in [`top_scorers`]
+///
+/// Settles the top scorer bonus question: every player in the returned list is a correct answer.
+pub fn leaders<'a>(events: impl IntoIterator<Item = &'a GoalEvent>) -> Vec<TopScorer> {
+    let scorers = top_scorers(events);
+    match scorers.first() {
+        Some(first) => {
+            let goals = first.goals;
+            scorers
+                .into_iter()
+                .take_while(|scorer| scorer.goals == goals)
+                .collect()
+        }
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal(team: u32, player: &str, kind: GoalKind) -> GoalEvent {
+        GoalEvent::new(
+            GameId::from(0),
+            TeamId(team),
+            SquadPlayer::new(player),
+            45,
+            kind,
+        )
+    }
+
+    #[test]
+    fn own_goals_not_credited() {
+        let events = vec![
+            goal(0, "Kane", GoalKind::Regular),
+            goal(0, "Kane", GoalKind::Penalty),
+            goal(1, "Lindelöf", GoalKind::OwnGoal),
+            goal(1, "Forsberg", GoalKind::Regular),
+        ];
+        let scorers = top_scorers(&events);
+        assert_eq!(scorers.len(), 2);
+        assert_eq!(scorers[0].player, SquadPlayer::new("Kane"));
+        assert_eq!(scorers[0].goals, GoalCount::from(2));
+    }
+
+    #[test]
+    fn shared_lead() {
+        let events = vec![
+            goal(0, "Kane", GoalKind::Regular),
+            goal(1, "Forsberg", GoalKind::Regular),
+        ];
+        let leaders = leaders(&events);
+        assert_eq!(leaders.len(), 2);
+        assert_eq!(leaders[0].player, SquadPlayer::new("Forsberg"));
+        assert!(super::leaders(&[]).is_empty());
+    }
+}
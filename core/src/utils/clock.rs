@@ -0,0 +1,60 @@
+//! Clock
+//!
+//! Source of the current time.
+//! Time dependent code, like deadlines and "games today", asks a [`Clock`] for the current time
+//! instead of reading the system time, which makes it possible to test it deterministically.
+use crate::Date;
+use std::sync::Mutex;
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Date;
+}
+
+/// Wall clock time
+#[cfg(feature = "clock")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+#[cfg(feature = "clock")]
+impl Clock for SystemClock {
+    fn now(&self) -> Date {
+        Date::now()
+    }
+}
+
+/// Clock which is stopped at a set time, until it is explicitly moved.
+#[derive(Debug)]
+pub struct TestClock(Mutex<Date>);
+
+impl TestClock {
+    pub fn new(now: Date) -> Self {
+        Self(Mutex::new(now))
+    }
+
+    /// Move the clock to `now`
+    pub fn set(&self, now: Date) {
+        *self.0.lock().expect("Poisoned clock") = now;
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> Date {
+        *self.0.lock().expect("Poisoned clock")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock() {
+        let start: Date = "2021-06-11 19:00".parse().unwrap();
+        let later: Date = "2021-06-12 19:00".parse().unwrap();
+        let clock = TestClock::new(start);
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.now(), start);
+        clock.set(later);
+        assert_eq!(clock.now(), later);
+    }
+}
@@ -1,17 +1,163 @@
 //! Date
-use chrono::{DateTime, FixedOffset, TimeZone};
+//!
+//! Point in time with a UTC offset, e.g. the kickoff of a game.
+//! Parsing and formatting of dates and times goes through [`Date`].
+//! Time zones and the formats other than RFC 3339 and "YYYY-MM-DD HH:MM[:SS]" need the `chrono`
+//! feature, without it a [`Date`] is a plain timestamp with a UTC offset.
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc};
+#[cfg(feature = "chrono")]
 use serde::{self, Deserialize, Serialize};
+#[cfg(feature = "chrono")]
+use std::fmt;
+#[cfg(feature = "chrono")]
+use std::str::FromStr;
+use thiserror::Error;
 
-#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[cfg(not(feature = "chrono"))]
+mod plain;
+#[cfg(not(feature = "chrono"))]
+pub use plain::Date;
+
+/// Format without offset, common in fixture files. Interpreted as UTC.
+#[cfg(feature = "chrono")]
+const SHORT_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Format of the SQLite `CURRENT_TIMESTAMP`, in UTC.
+#[cfg(feature = "chrono")]
+const SQL_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[cfg(feature = "chrono")]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Date(#[serde(with = "serde_date")] DateTime<FixedOffset>);
 
+#[cfg(feature = "chrono")]
 impl Date {
     pub fn mock() -> Self {
-        let dt = FixedOffset::east(3600).ymd(1632, 11, 6).and_hms(10, 18, 36);
+        let dt = FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(1632, 11, 6, 10, 18, 36)
+            .unwrap();
         Self(dt)
     }
+
+    /// Current time, in UTC.
+    #[cfg(feature = "clock")]
+    pub fn now() -> Self {
+        Self(Utc::now().into())
+    }
+
+    /// The same point in time, with the UTC offset of `tz` at that time
+    pub fn with_timezone<T: TimeZone>(&self, tz: &T) -> Self {
+        let local = self.0.with_timezone(tz);
+        Self(self.0.with_timezone(&local.offset().fix()))
+    }
+
+    /// The same point in time, in UTC
+    pub fn utc(&self) -> Self {
+        self.with_timezone(&Utc)
+    }
+
+    /// Calendar day, in the offset of the date
+    pub fn day(&self) -> NaiveDate {
+        self.0.date_naive()
+    }
+
+    /// Strictly before `other`
+    pub fn is_before(&self, other: Date) -> bool {
+        *self < other
+    }
+
+    /// Strictly after `other`
+    pub fn is_after(&self, other: Date) -> bool {
+        *self > other
+    }
+
+    /// The point in time `hours` later, in the same offset
+    pub fn add_hours(&self, hours: u32) -> Self {
+        Self(self.0 + Duration::hours(i64::from(hours)))
+    }
+
+    /// Whole seconds from the date to `later`, negative if `later` is before the date
+    pub fn seconds_until(&self, later: Date) -> i64 {
+        (later.0 - self.0).num_seconds()
+    }
+
+    /// Unix timestamp, whole seconds since 1970-01-01 00:00 UTC
+    pub fn timestamp(&self) -> i64 {
+        self.0.timestamp()
+    }
+
+    /// Date of a unix timestamp, in UTC, `None` if out of range
+    pub fn from_timestamp(timestamp: i64) -> Option<Self> {
+        Utc.timestamp_opt(timestamp, 0)
+            .single()
+            .map(|date| Self(date.into()))
+    }
+
+    /// Short representation, "YYYY-MM-DD HH:MM", in the offset of the date
+    pub fn to_short_string(&self) -> String {
+        self.0.format(SHORT_FORMAT).to_string()
+    }
+
+    /// HTTP-date, e.g. "Fri, 11 Jun 2021 19:00:00 GMT", as in the `Last-Modified` header
+    ///
+    /// Parses back with [`Date::from_str`], to the second.
+    pub fn to_http_date(&self) -> String {
+        self.0
+            .with_timezone(&Utc)
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string()
+    }
+
+    /// iCalendar representation in UTC, e.g. "20210611T190000Z"
+    pub fn to_ics_string(&self) -> String {
+        self.0
+            .with_timezone(&Utc)
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string()
+    }
+}
+
+/// Parse a date
+///
+/// Accepted formats, tried in order:
+///
+/// - RFC 3339, e.g. "2021-06-11T21:00:00+02:00"
+/// - RFC 2822, e.g. "Fri, 11 Jun 2021 21:00:00 +0200"
+/// - "YYYY-MM-DD HH:MM", e.g. "2021-06-11 19:00", interpreted as UTC
+/// - "YYYY-MM-DD HH:MM:SS", e.g. "2021-06-11 19:00:00", interpreted as UTC, as stored by SQLite.
+#[cfg(feature = "chrono")]
+impl FromStr for Date {
+    type Err = DateError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DateTime::parse_from_rfc3339(s)
+            .or_else(|_| DateTime::parse_from_rfc2822(s))
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(s, SHORT_FORMAT)
+                    .or_else(|_| NaiveDateTime::parse_from_str(s, SQL_FORMAT))
+                    .map(|naive| Utc.from_utc_datetime(&naive).into())
+            })
+            .map(Self)
+            .map_err(|_| DateError::Parse(String::from(s)))
+    }
 }
 
+/// RFC 3339 representation, parses back with [`Date::from_str`]
+#[cfg(feature = "chrono")]
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_rfc3339())
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DateError {
+    #[error("Invalid date '{0}', expected RFC 3339, RFC 2822 or 'YYYY-MM-DD HH:MM[:SS]'")]
+    Parse(String),
+}
+
+#[cfg(feature = "chrono")]
 mod serde_date {
     use chrono::{DateTime, FixedOffset};
     use serde::{self, Deserialize, Deserializer, Serializer};
@@ -45,6 +191,41 @@ mod serde_date {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        DateTime::parse_from_rfc3339(&s).map_err(serde::de::Error::custom)
+        s.parse::<super::Date>()
+            .map(|date| date.0)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_formats() {
+        let expected: Date = "2021-06-11T21:00:00+02:00".parse().unwrap();
+        let rfc_2822: Date = "Fri, 11 Jun 2021 21:00:00 +0200".parse().unwrap();
+        let short: Date = "2021-06-11 19:00".parse().unwrap();
+        let sql: Date = "2021-06-11 19:00:00".parse().unwrap();
+        assert_eq!(rfc_2822, expected);
+        assert_eq!(short, expected);
+        assert_eq!(sql, expected);
+        assert_eq!(expected.to_http_date(), "Fri, 11 Jun 2021 19:00:00 GMT");
+        assert_eq!(expected.to_http_date().parse::<Date>().unwrap(), expected);
+        assert_eq!(short.to_short_string(), "2021-06-11 19:00");
+        assert_eq!(
+            "11/06/2021".parse::<Date>(),
+            Err(DateError::Parse(String::from("11/06/2021")))
+        );
+    }
+
+    #[test]
+    fn timezone_day() {
+        let date: Date = "2021-06-11T23:30:00+00:00".parse().unwrap();
+        let cest = date.with_timezone(&FixedOffset::east_opt(2 * 3600).unwrap());
+        assert_eq!(cest, date);
+        assert_eq!(cest.to_string(), "2021-06-12T01:30:00+02:00");
+        assert_eq!(cest.day(), NaiveDate::from_ymd_opt(2021, 6, 12).unwrap());
+        assert_eq!(date.day(), NaiveDate::from_ymd_opt(2021, 6, 11).unwrap());
     }
 }
@@ -0,0 +1,292 @@
+//! Dates without `chrono`
+//!
+//! A [`Date`] is a unix timestamp with the UTC offset it was given in, enough to order games
+//! by kickoff and to read and write the RFC 3339 dates of the full, `chrono` based, date.
+//! Calendar arithmetic follows the proleptic Gregorian calendar.
+use super::DateError;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+const SECONDS_PER_DAY: i64 = 24 * 3600;
+
+/// Point in time with a UTC offset
+///
+/// Dates are equal and ordered by the point in time, whatever their offsets.
+#[derive(Clone, Copy, Debug)]
+pub struct Date {
+    /// Seconds since 1970-01-01 00:00 UTC
+    timestamp: i64,
+    /// Seconds east of UTC
+    offset: i32,
+}
+
+impl Date {
+    pub fn mock() -> Self {
+        let local = days_from_civil(1632, 11, 6) * SECONDS_PER_DAY + 10 * 3600 + 18 * 60 + 36;
+        Date {
+            timestamp: local - 3600,
+            offset: 3600,
+        }
+    }
+
+    /// Strictly before `other`
+    pub fn is_before(&self, other: Date) -> bool {
+        *self < other
+    }
+
+    /// Strictly after `other`
+    pub fn is_after(&self, other: Date) -> bool {
+        *self > other
+    }
+
+    /// The point in time `hours` later, in the same offset
+    pub fn add_hours(&self, hours: u32) -> Self {
+        Date {
+            timestamp: self.timestamp + i64::from(hours) * 3600,
+            ..*self
+        }
+    }
+
+    /// Whole seconds from the date to `later`, negative if `later` is before the date
+    pub fn seconds_until(&self, later: Date) -> i64 {
+        later.timestamp - self.timestamp
+    }
+
+    /// Unix timestamp, whole seconds since 1970-01-01 00:00 UTC
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Date of a unix timestamp, in UTC, `None` outside of the years 0 to 9999
+    pub fn from_timestamp(timestamp: i64) -> Option<Self> {
+        let (year, _, _) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+        if (0..=9999).contains(&year) {
+            Some(Date {
+                timestamp,
+                offset: 0,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl PartialEq for Date {
+    fn eq(&self, other: &Self) -> bool {
+        self.timestamp == other.timestamp
+    }
+}
+
+impl Eq for Date {}
+
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Date {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.timestamp.cmp(&other.timestamp)
+    }
+}
+
+/// Parse a date
+///
+/// Accepted formats:
+///
+/// - RFC 3339, e.g. "2021-06-11T21:00:00+02:00", fractions of a second are ignored
+/// - "YYYY-MM-DD HH:MM", e.g. "2021-06-11 19:00", interpreted as UTC
+/// - "YYYY-MM-DD HH:MM:SS", e.g. "2021-06-11 19:00:00", interpreted as UTC, as stored by SQLite.
+impl FromStr for Date {
+    type Err = DateError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).ok_or_else(|| DateError::Parse(String::from(s)))
+    }
+}
+
+/// RFC 3339 representation, parses back with [`Date::from_str`]
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let local = self.timestamp + i64::from(self.offset);
+        let (year, month, day) = civil_from_days(local.div_euclid(SECONDS_PER_DAY));
+        let seconds = local.rem_euclid(SECONDS_PER_DAY);
+        let sign = if self.offset < 0 { '-' } else { '+' };
+        let offset = self.offset.abs() / 60;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+            year,
+            month,
+            day,
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            sign,
+            offset / 60,
+            offset % 60
+        )
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+fn parse(s: &str) -> Option<Date> {
+    let number = |part: &str| -> Option<i64> {
+        if !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit()) {
+            part.parse().ok()
+        } else {
+            None
+        }
+    };
+    let field = |from: usize, to: usize| s.get(from..to).and_then(number);
+    let separator = |at: usize, expected: &[u8]| {
+        s.as_bytes()
+            .get(at)
+            .map_or(false, |byte| expected.contains(byte))
+    };
+    if !(separator(4, b"-") && separator(7, b"-") && separator(10, b"Tt ") && separator(13, b":")) {
+        return None;
+    }
+    let (year, month, day) = (field(0, 4)?, field(5, 7)?, field(8, 10)?);
+    let (hour, minute) = (field(11, 13)?, field(14, 16)?);
+    let (second, mut rest) = if separator(16, b":") {
+        (field(17, 19)?, s.get(19..)?)
+    } else {
+        (0, s.get(16..)?)
+    };
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        rest = &fraction[digits..];
+    }
+    let offset = match rest.as_bytes() {
+        // Without an offset only in the formats interpreted as UTC
+        [] if separator(10, b" ") => 0,
+        [b'Z'] | [b'z'] => 0,
+        [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+            let hours = number(&rest[1..3])?;
+            let minutes = number(&rest[4..6])?;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = hours * 3600 + minutes * 60;
+            if *sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        _ => return None,
+    };
+    let valid_day = (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day);
+    if !valid_day || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let local =
+        days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second;
+    Some(Date {
+        timestamp: local - offset,
+        offset: i32::try_from(offset).ok()?,
+    })
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a date, see <https://howardhinnant.github.io/date_algorithms.html>
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of [`days_from_civil`]
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_formats() {
+        let expected: Date = "2021-06-11T21:00:00+02:00".parse().unwrap();
+        assert_eq!(expected.timestamp(), 1_623_438_000);
+        assert_eq!(expected.to_string(), "2021-06-11T21:00:00+02:00");
+        let short: Date = "2021-06-11 19:00".parse().unwrap();
+        let sql: Date = "2021-06-11 19:00:00".parse().unwrap();
+        let utc: Date = "2021-06-11T19:00:00.250Z".parse().unwrap();
+        assert_eq!(short, expected);
+        assert_eq!(sql, expected);
+        assert_eq!(utc, expected);
+        assert_eq!(utc.to_string(), "2021-06-11T19:00:00+00:00");
+        assert_eq!(Date::mock().to_string(), "1632-11-06T10:18:36+01:00");
+        for invalid in &[
+            "11/06/2021",
+            "2021-02-29 19:00",
+            "2021-06-11T19:00:00",
+            "2021-06-11T19:00:00+2:00",
+        ] {
+            assert_eq!(
+                invalid.parse::<Date>(),
+                Err(DateError::Parse(String::from(*invalid)))
+            );
+        }
+    }
+
+    #[test]
+    fn civil_days() {
+        for days in -1_000_000..1_000_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+    }
+}
@@ -1,2 +1,4 @@
 //! Utilities
+pub mod clock;
 pub mod date;
+pub mod table;
@@ -0,0 +1,105 @@
+//! Plain text tables
+//!
+//! Aligned ASCII tables for terminals, curl and chat bots, e.g.
+//!
+//! ```text
+//! # | Team    | Pts
+//! --+---------+----
+//! 1 | Sweden  |   6
+//! 2 | England |   4
+//! ```
+//!
+//! Rows implement [`TableRow`], [`to_ascii_table`] lays them out.
+use std::fmt::Write;
+
+/// Alignment of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// Row of a plain text table
+pub trait TableRow {
+    /// Column titles with their alignment
+    fn columns() -> Vec<(&'static str, Align)>;
+
+    /// Cells of the row, one per column
+    fn cells(&self) -> Vec<String>;
+}
+
+/// Lay out rows as an aligned table, with a header row
+///
+/// Columns are as wide as their widest cell and separated by ` | `.
+/// Trailing whitespace is trimmed from every line.
+pub fn to_ascii_table<R: TableRow>(rows: &[R]) -> String {
+    let columns = R::columns();
+    let cells: Vec<Vec<String>> = rows.iter().map(TableRow::cells).collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, (title, _))| {
+            cells
+                .iter()
+                .filter_map(|row| row.get(idx))
+                .map(|cell| cell.chars().count())
+                .chain(std::iter::once(title.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: Vec<String>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(columns.iter().zip(widths.iter()))
+            .map(|(cell, ((_, align), width))| match align {
+                Align::Left => format!("{:<width$}", cell, width = width),
+                Align::Right => format!("{:>width$}", cell, width = width),
+            })
+            .collect();
+        padded.join(" | ").trim_end().to_string()
+    };
+    let mut table = String::new();
+    let header = columns.iter().map(|(title, _)| title.to_string()).collect();
+    // Writing to a `String` never fails
+    let _ = writeln!(table, "{}", line(header));
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    let _ = writeln!(table, "{}", rule.join("-+-"));
+    for row in cells {
+        let _ = writeln!(table, "{}", line(row));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Row(u32, &'static str, u32);
+
+    impl TableRow for Row {
+        fn columns() -> Vec<(&'static str, Align)> {
+            vec![
+                ("#", Align::Right),
+                ("Team", Align::Left),
+                ("Pts", Align::Right),
+            ]
+        }
+
+        fn cells(&self) -> Vec<String> {
+            vec![self.0.to_string(), self.1.to_string(), self.2.to_string()]
+        }
+    }
+
+    #[test]
+    fn aligned() {
+        let table = to_ascii_table(&[Row(1, "Sweden", 6), Row(2, "England", 4)]);
+        assert_eq!(
+            table,
+            "# | Team    | Pts\n\
+             --+---------+----\n\
+             1 | Sweden  |   6\n\
+             2 | England |   4\n"
+        );
+    }
+}
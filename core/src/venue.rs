@@ -0,0 +1,35 @@
+//! Venues
+//!
+//! The stadium of a game, as given in the fixture data.
+//! Games without a known venue are simply missing in [`GameVenues`].
+use crate::game::GameId;
+use derive_more::{Display, From, Into};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Id of a venue in the fixture data, e.g. "WEM" for Wembley Stadium
+#[derive(
+    Debug, Display, Clone, From, Into, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+pub struct VenueId(pub String);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Venue {
+    pub id: VenueId,
+    /// Name of the stadium, e.g. "Wembley Stadium"
+    pub name: String,
+    pub city: String,
+}
+
+impl Venue {
+    /// Name and city, e.g. "Wembley Stadium, London"
+    pub fn label(&self) -> String {
+        format!("{}, {}", self.name, self.city)
+    }
+}
+
+/// Type alias for a mapping of `VenueId` to `Venue`
+pub type Venues = HashMap<VenueId, Venue>;
+
+/// Venue of every game with a known venue
+pub type GameVenues = HashMap<GameId, Venue>;
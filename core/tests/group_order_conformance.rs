@@ -0,0 +1,166 @@
+//! Data-driven group ordering tests
+//!
+//! Runs every case file in `testdata/group_order`.
+//! A case is a YAML file with a ruleset name (see [`RULE_NAMES`]),
+//! the played games of a single group and the expected final order:
+//!
+//! ```yaml
+//! description: Optional free text
+//! rules: euro_2020
+//! # Team rankings, only needed for rules with a ranking tiebreaker.
+//! ranking: {1: 1, 2: 2, 3: 3, 4: 4}
+//! games:
+//!   - {home: 1, away: 2, score: 1-0}
+//!   - home: 3
+//!     away: 4
+//!     score: 2-2
+//!     fair_play:
+//!       home: {yellow: 1}
+//!       away: {yellow: 2, direct_red: 1}
+//! expected_order: [1, 3, 2, 4]
+//! ```
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use wwc_core::fair_play::{FairPlay, FairPlayScore};
+use wwc_core::game::Score;
+use wwc_core::group::game::UnplayedGroupGame;
+use wwc_core::group::order::{order_group, rules_by_name, RULE_NAMES};
+use wwc_core::group::Group;
+use wwc_core::team::{TeamId, TeamRank};
+use wwc_core::Date;
+
+const CASE_DIR: &str = "testdata/group_order";
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Case {
+    #[serde(default)]
+    description: String,
+    rules: String,
+    #[serde(default)]
+    ranking: HashMap<u32, u32>,
+    games: Vec<CaseGame>,
+    expected_order: Vec<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CaseGame {
+    home: u32,
+    away: u32,
+    score: String,
+    #[serde(default)]
+    fair_play: CaseFairPlay,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct CaseFairPlay {
+    #[serde(default)]
+    home: Cards,
+    #[serde(default)]
+    away: Cards,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Cards {
+    yellow: u32,
+    indirect_red: u32,
+    direct_red: u32,
+    yellow_and_direct: u32,
+}
+
+impl From<Cards> for FairPlay {
+    fn from(cards: Cards) -> Self {
+        FairPlay::new(
+            cards.yellow,
+            cards.indirect_red,
+            cards.direct_red,
+            cards.yellow_and_direct,
+        )
+    }
+}
+
+fn group(games: Vec<CaseGame>) -> Result<Group, String> {
+    let games = games
+        .into_iter()
+        .enumerate()
+        .map(|(idx, game)| {
+            let score: Score = game
+                .score
+                .parse()
+                .map_err(|err| format!("game {}: {}", idx + 1, err))?;
+            let fair_play =
+                FairPlayScore::new(game.fair_play.home.into(), game.fair_play.away.into());
+            UnplayedGroupGame::try_new(idx as u32 + 1, game.home, game.away, Date::mock())
+                .map(|game| game.play(score, fair_play))
+                .map_err(|err| format!("game {}: {}", idx + 1, err))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Group::try_new(vec![], games).map_err(|err| err.to_string())
+}
+
+fn run_case(path: &Path) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let case: Case = serde_yaml::from_str(&content).map_err(|err| err.to_string())?;
+    let group = group(case.games)?;
+    let ranking = case
+        .ranking
+        .into_iter()
+        .map(|(team, rank)| (TeamId(team), TeamRank(rank)))
+        .collect();
+    let rules = rules_by_name(&case.rules, std::slice::from_ref(&group), ranking)
+        .map_err(|err| format!("{} (available rules: {})", err, RULE_NAMES.join(", ")))?;
+    let order: Vec<TeamId> = order_group(&group, &rules).into_iter().collect();
+    let expected: Vec<TeamId> = case.expected_order.into_iter().map(TeamId).collect();
+    if order == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "expected order {:?}, got {:?}\n  {}",
+            expected,
+            order,
+            case.description.trim()
+        ))
+    }
+}
+
+fn case_files() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(CASE_DIR);
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("Could not read '{}': {}", dir.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("yaml") | Some("yml")
+            )
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn group_order_cases() {
+    let files = case_files();
+    assert!(!files.is_empty(), "No case files in '{}'", CASE_DIR);
+    let failures: Vec<String> = files
+        .iter()
+        .filter_map(|path| {
+            run_case(path)
+                .err()
+                .map(|err| format!("{}: {}", path.file_name().unwrap().to_string_lossy(), err))
+        })
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{} of {} group order cases failed:\n{}",
+        failures.len(),
+        files.len(),
+        failures.join("\n")
+    );
+}
@@ -1,2 +1,3 @@
 pub mod file_io;
 pub mod lsv;
+pub mod template;
@@ -8,28 +8,34 @@ use wwc_core::fair_play::{FairPlay, FairPlayScore};
 use wwc_core::game::{GameId, GoalCount, Score};
 use wwc_core::group::game::{PlayedGroupGame, UnplayedGroupGame};
 use wwc_core::group::{Group, GroupError, GroupId, Groups};
+use wwc_core::playoff::bracket::{Bracket, BracketGame, Feeder};
+use wwc_core::playoff::RoundIdx;
+use wwc_core::team::resolve::TeamResolver;
 use wwc_core::team::{FifaCode, Iso2, Team, TeamId, TeamRank, Teams};
+use wwc_core::venue::{GameVenues, Venue, VenueId};
 use wwc_core::Date;
 
-type TeamMap = HashMap<String, TeamId>;
-
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Euro2021Data {
+    stadiums: Vec<ParseStadium>,
     teams: Vec<ParseTeam>,
     groups: Vec<ParseGroup>,
-    team_map: TeamMap,
+    knockout: HashMap<String, ParseKnockoutPhase>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 struct ParseEuro2021Data {
+    #[serde(default)]
+    stadiums: Vec<ParseStadium>,
     teams: Vec<ParseTeam>,
     groups: Vec<ParseGroup>,
+    #[serde(rename = "knockoutphases", default)]
+    knockout: HashMap<String, ParseKnockoutPhase>,
 }
 
 impl LsvData for Euro2021Data {
-    fn try_data_from_file(filename: &str) -> Result<Euro2021Data, LsvParseError> {
-        let data_json = crate::file_io::read_json_file_to_str(filename)?;
-        let mut data: ParseEuro2021Data = serde_json::from_str(&data_json)?;
+    fn try_data_from_str(data_json: &str) -> Result<Euro2021Data, LsvParseError> {
+        let mut data: ParseEuro2021Data = serde_json::from_str(data_json)?;
         data.groups = data
             .groups
             .into_iter()
@@ -38,43 +44,129 @@ impl LsvData for Euro2021Data {
                 pg
             })
             .collect();
-        let team_map = Self::team_map(&data.teams);
         Ok(Self {
+            stadiums: data.stadiums,
             teams: data.teams,
             groups: data.groups,
-            team_map,
+            knockout: data.knockout,
         })
     }
 
+    /// Groups, the teams of the games are resolved by Fifa code or name, see [`TeamResolver`]
     fn try_groups(&self) -> Result<Groups, LsvParseError> {
-        Ok(self
-            .groups
+        let resolver = TeamResolver::new(&self.try_teams()?);
+        self.groups
             .iter()
             .map(|pg| {
                 let id = GroupId::from(pg.id);
-                pg.clone().try_parse_group(&self.team_map).map(|g| (id, g))
+                pg.clone().try_parse_group(&resolver).map(|g| (id, g))
             })
-            .collect::<Result<Groups, GroupError>>()?)
+            .collect()
     }
 
+    /// Teams, numbered in the order of the data
     fn try_teams(&self) -> Result<Teams, LsvParseError> {
         let tmp = self
             .clone()
             .teams
             .into_iter()
-            .map(|team| team.try_parse_team(&self.team_map))
+            .enumerate()
+            .map(|(id, team)| team.try_parse_team(TeamId(id as u32)))
             .collect::<Result<Vec<Team>, LsvParseError>>()?;
         Ok(tmp.into_iter().map(|t| (t.id, t)).collect())
     }
+
+    /// Venues of the group and knockout games
+    fn try_game_venues(&self) -> Result<GameVenues, LsvParseError> {
+        let venues: HashMap<&str, &ParseStadium> = self
+            .stadiums
+            .iter()
+            .map(|stadium| (stadium.id.as_str(), stadium))
+            .collect();
+        let group_games = self
+            .groups
+            .iter()
+            .flat_map(|group| group.games.iter())
+            .map(|game| (game.id, &game.stadium));
+        let knockout_games = self
+            .knockout
+            .values()
+            .flat_map(|phase| phase.games.iter())
+            .map(|game| (game.id, &game.stadium));
+        group_games
+            .chain(knockout_games)
+            .filter_map(|(id, stadium)| stadium.as_ref().map(|stadium| (id, stadium)))
+            .map(|(id, stadium)| {
+                let venue = venues
+                    .get(stadium.as_str())
+                    .ok_or_else(|| LsvParseError::VenueParse(stadium.clone()))?;
+                Ok((GameId::from(id), Venue::from(*venue)))
+            })
+            .collect()
+    }
 }
 
 impl Euro2021Data {
-    fn team_map(teams: &[ParseTeam]) -> TeamMap {
-        teams
+    /// Playoff bracket template
+    ///
+    /// The data has no explicit round order, a game is placed in the round after the latest
+    /// round of the games feeding it.
+    pub fn try_bracket(&self) -> Result<Bracket, LsvParseError> {
+        let feeders = self
+            .knockout
+            .values()
+            .flat_map(|phase| phase.games.iter())
+            .map(|game| {
+                Ok((
+                    game.id,
+                    (
+                        game.qualification.home_team.try_feeder()?,
+                        game.qualification.away_team.try_feeder()?,
+                        game.date,
+                    ),
+                ))
+            })
+            .collect::<Result<HashMap<u32, (Feeder, Feeder, Date)>, LsvParseError>>()?;
+        fn round(id: u32, feeders: &HashMap<u32, (Feeder, Feeder, Date)>, depth: usize) -> u8 {
+            match feeders.get(&id) {
+                // Guard against cyclic data, caught by `Bracket::try_new`.
+                Some((home, away, _)) if depth < feeders.len() => [home, away]
+                    .iter()
+                    .filter_map(|feeder| feeder.game())
+                    .map(|game| 1 + round(game.into(), feeders, depth + 1))
+                    .max()
+                    .unwrap_or(0),
+                _ => 0,
+            }
+        }
+        let games = feeders
             .iter()
-            .enumerate()
-            .map(|(id, t)| (t.fifa_code.clone(), TeamId(id as u32)))
-            .collect()
+            .map(|(id, (home, away, date))| BracketGame {
+                id: GameId::from(*id),
+                round: RoundIdx(round(*id, &feeders, 0)),
+                home: home.clone(),
+                away: away.clone(),
+                kickoff: *date,
+            })
+            .collect();
+        Ok(Bracket::try_new(games)?)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+struct ParseStadium {
+    id: String,
+    name: String,
+    city: String,
+}
+
+impl From<&ParseStadium> for Venue {
+    fn from(stadium: &ParseStadium) -> Self {
+        Venue {
+            id: VenueId::from(stadium.id.clone()),
+            name: stadium.name.clone(),
+            city: stadium.city.clone(),
+        }
     }
 }
 
@@ -87,11 +179,10 @@ struct ParseTeam {
 }
 
 impl ParseTeam {
-    fn try_parse_team(self, team_map: &TeamMap) -> Result<Team, LsvParseError> {
-        let id = team_map.get(&self.fifa_code).unwrap();
+    fn try_parse_team(self, id: TeamId) -> Result<Team, LsvParseError> {
         if let Some(rank) = self.rank {
             Ok(Team::new(
-                *id,
+                id,
                 &self.name,
                 &self.fifa_code,
                 Iso2::from(&FifaCode::from(self.fifa_code.clone())).as_ref(),
@@ -101,7 +192,7 @@ impl ParseTeam {
             //Err(Self::Error::TeamError)
             //TODO: How to solve missing rank?
             Ok(Team::new(
-                *id,
+                id,
                 &self.name,
                 &self.fifa_code,
                 &String::from(Iso2::from(&FifaCode::from(self.fifa_code.clone()))),
@@ -123,21 +214,71 @@ struct ParseGroup {
 }
 
 impl ParseGroup {
-    fn try_parse_group(self, team_map: &TeamMap) -> Result<Group, GroupError> {
+    fn try_parse_group(self, teams: &TeamResolver) -> Result<Group, LsvParseError> {
         let upcoming_games = self
             .games
             .iter()
             .filter(|game| !game.finished)
-            .map(|game| ParseGame::try_parse_unplayed(game.clone(), team_map))
-            .collect::<Result<Vec<UnplayedGroupGame>, GroupError>>()?;
+            .map(|game| ParseGame::try_parse_unplayed(game.clone(), teams))
+            .collect::<Result<Vec<UnplayedGroupGame>, LsvParseError>>()?;
 
         let played_games = self
             .games
             .iter()
             .filter(|game| game.finished)
-            .map(|game| ParseGame::try_parse_played(game.clone(), team_map))
-            .collect::<Result<Vec<PlayedGroupGame>, GroupError>>()?;
-        Group::try_new(upcoming_games, played_games)
+            .map(|game| ParseGame::try_parse_played(game.clone(), teams))
+            .collect::<Result<Vec<PlayedGroupGame>, LsvParseError>>()?;
+        Ok(Group::try_new(upcoming_games, played_games)?)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct ParseKnockoutPhase {
+    #[serde(rename = "matches")]
+    games: Vec<ParseKnockoutGame>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct ParseKnockoutGame {
+    id: u32,
+    qualification: ParseQualification,
+    date: Date,
+    stadium: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct ParseQualification {
+    home_team: ParseFeeder,
+    away_team: ParseFeeder,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct ParseFeeder {
+    #[serde(rename = "qualificationtype")]
+    type_: String,
+    group: Option<String>,
+    #[serde(rename = "match")]
+    game: Option<u32>,
+}
+
+impl ParseFeeder {
+    /// E.g. `{"qualificationtype": "thirdplace", "group": "A/D/E/F"}` -> "3ADEF"
+    fn try_feeder(&self) -> Result<Feeder, LsvParseError> {
+        let groups = self
+            .group
+            .as_ref()
+            .map(|group| group.replace('/', ""))
+            .unwrap_or_default();
+        let feeder = match (self.type_.as_str(), self.game) {
+            ("winner", Some(game)) => format!("W{}", game),
+            ("loser", Some(game)) => format!("L{}", game),
+            ("winner", None) => format!("1{}", groups),
+            ("runnerup", None) => format!("2{}", groups),
+            ("thirdplace", None) => format!("3{}", groups),
+            // Not a valid feeder, fails to parse.
+            (type_, _) => type_.to_string(),
+        };
+        Ok(feeder.parse()?)
     }
 }
 
@@ -156,35 +297,36 @@ struct ParseGame {
     away_fair_play: Option<FairPlay>,
     finished: bool,
     date: Date,
+    stadium: Option<String>,
 }
 
 impl ParseGame {
     fn try_parse_unplayed(
         parse_game: ParseGame,
-        team_map: &TeamMap,
-    ) -> Result<UnplayedGroupGame, GroupError> {
-        UnplayedGroupGame::try_new(
+        teams: &TeamResolver,
+    ) -> Result<UnplayedGroupGame, LsvParseError> {
+        Ok(UnplayedGroupGame::try_new(
             GameId::from(parse_game.id),
-            *team_map.get(&parse_game.home_team).unwrap(),
-            *team_map.get(&parse_game.away_team).unwrap(),
+            teams.resolve(&parse_game.home_team)?,
+            teams.resolve(&parse_game.away_team)?,
             parse_game.date,
-        )
+        )?)
     }
 
     fn try_parse_played(
         parse_game: ParseGame,
-        team_map: &TeamMap,
-    ) -> Result<PlayedGroupGame, GroupError> {
+        teams: &TeamResolver,
+    ) -> Result<PlayedGroupGame, LsvParseError> {
         println!("{:?}", parse_game.home_team);
         let game = UnplayedGroupGame::try_new(
             GameId::from(parse_game.id),
-            *team_map.get(&parse_game.home_team).unwrap(),
-            *team_map.get(&parse_game.away_team).unwrap(),
+            teams.resolve(&parse_game.home_team)?,
+            teams.resolve(&parse_game.away_team)?,
             parse_game.date,
         )?;
         let score = match (parse_game.home_result, parse_game.away_result) {
             (Some(home), Some(away)) => Score::from((home, away)),
-            _ => return Err(GroupError::GenericError),
+            _ => return Err(LsvParseError::GroupParse(GroupError::GenericError)),
         };
         let fair_play_score = match (parse_game.home_fair_play, parse_game.away_fair_play) {
             (Some(home), Some(away)) => FairPlayScore::new(home, away),
@@ -6,22 +6,24 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use wwc_core::fair_play::{FairPlay, FairPlayScore};
-use wwc_core::game::{GoalCount, Score};
+use wwc_core::game::{GameId, GoalCount, Score};
 use wwc_core::group::game::{PlayedGroupGame, UnplayedGroupGame};
 use wwc_core::group::{Group, GroupError, GroupId, Groups};
 use wwc_core::team::{Team, TeamId, TeamRank, Teams};
+use wwc_core::venue::{GameVenues, Venue, VenueId};
 use wwc_core::Date;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Fifa2018Data {
+    #[serde(default)]
+    stadiums: Vec<ParseStadium>,
     teams: Vec<ParseTeam>,
     groups: HashMap<GroupId, ParseGroup>,
 }
 
 impl LsvData for Fifa2018Data {
-    fn try_data_from_file(filename: &str) -> Result<Fifa2018Data, LsvParseError> {
-        let data_json = crate::file_io::read_json_file_to_str(filename)?;
-        let mut data: Fifa2018Data = serde_json::from_str(&data_json)?;
+    fn try_data_from_str(data_json: &str) -> Result<Fifa2018Data, LsvParseError> {
+        let mut data: Fifa2018Data = serde_json::from_str(data_json)?;
         data.groups = data
             .groups
             .into_iter()
@@ -52,6 +54,26 @@ impl LsvData for Fifa2018Data {
             .collect::<Result<Vec<Team>, LsvParseError>>()?;
         Ok(tmp.into_iter().map(|t| (t.id, t)).collect())
     }
+
+    /// Venues of the group games
+    fn try_game_venues(&self) -> Result<GameVenues, LsvParseError> {
+        let venues: HashMap<u32, &ParseStadium> = self
+            .stadiums
+            .iter()
+            .map(|stadium| (stadium.id, stadium))
+            .collect();
+        self.groups
+            .values()
+            .flat_map(|group| group.games.iter())
+            .filter_map(|game| game.stadium.map(|stadium| (game.id, stadium)))
+            .map(|(id, stadium)| {
+                let venue = venues
+                    .get(&stadium)
+                    .ok_or_else(|| LsvParseError::VenueParse(stadium.to_string()))?;
+                Ok((GameId::from(id), Venue::from(*venue)))
+            })
+            .collect()
+    }
 }
 
 /// Used for testing only
@@ -65,6 +87,23 @@ impl Fifa2018Data {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+struct ParseStadium {
+    id: u32,
+    name: String,
+    city: String,
+}
+
+impl From<&ParseStadium> for Venue {
+    fn from(stadium: &ParseStadium) -> Self {
+        Venue {
+            id: VenueId::from(stadium.id.to_string()),
+            name: stadium.name.clone(),
+            city: stadium.city.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 struct ParseTeam {
     id: TeamId,
@@ -152,6 +191,7 @@ struct ParseGame {
     away_fair_play: Option<FairPlay>,
     finished: bool,
     date: Date,
+    stadium: Option<u32>,
 }
 
 impl TryFrom<ParseGame> for UnplayedGroupGame {
@@ -1,8 +1,12 @@
+use crate::template::TemplateError;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use wwc_core::group::GroupError;
 use wwc_core::group::Groups;
+use wwc_core::playoff::bracket::BracketError;
+use wwc_core::team::resolve::ResolveError;
 use wwc_core::team::Teams;
+use wwc_core::venue::GameVenues;
 
 pub mod euro_2021;
 pub mod fifa_2018;
@@ -10,15 +14,31 @@ pub mod fifa_2018;
 pub use euro_2021::Euro2021Data;
 pub use fifa_2018::Fifa2018Data;
 
+/// World Cup 2022 fixtures, in the same format as [`Euro2021Data`]
+pub type Wc2022Data = Euro2021Data;
+
+/// World Cup 2026 template, in the same format as [`Euro2021Data`]
+///
+/// The teams are placeholders named by their position in the group, e.g. "B3".
+pub type Wc2026Data = Euro2021Data;
+
 pub fn get_data<T: LsvData>(data_path: &str) -> Result<T, LsvParseError> {
     let data = T::try_data_from_file(data_path)?;
     Ok(data)
 }
 
 pub trait LsvData: Sized {
-    fn try_data_from_file(filename: &str) -> Result<Self, LsvParseError>;
+    fn try_data_from_file(filename: &str) -> Result<Self, LsvParseError> {
+        let data_json = crate::file_io::read_json_file_to_str(filename)?;
+        Self::try_data_from_str(&data_json)
+    }
+    fn try_data_from_str(data_json: &str) -> Result<Self, LsvParseError>;
     fn try_groups(&self) -> Result<Groups, LsvParseError>;
     fn try_teams(&self) -> Result<Teams, LsvParseError>;
+    /// Venue of every game, empty if the data has no venues
+    fn try_game_venues(&self) -> Result<GameVenues, LsvParseError> {
+        Ok(GameVenues::new())
+    }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -40,4 +60,12 @@ pub enum LsvParseError {
     TeamParse,
     #[error("Error parsing group: {0}")]
     GroupParse(#[from] GroupError),
+    #[error("Error parsing bracket: {0}")]
+    BracketParse(#[from] BracketError),
+    #[error("Unknown venue '{0}'")]
+    VenueParse(String),
+    #[error("Error resolving team: {0}")]
+    TeamResolve(#[from] ResolveError),
+    #[error("Bracket template: {0}")]
+    Template(#[from] TemplateError),
 }
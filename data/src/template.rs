@@ -0,0 +1,158 @@
+//! Built in tournament templates
+//!
+//! The LSV data files of past and upcoming tournaments, embedded in the binary so that a fresh
+//! deployment can be populated without any external files.
+//!
+//! Only tournaments with a data file in `data/tests/data` are included.
+use crate::lsv::{Euro2021Data, Fifa2018Data, LsvData, LsvParseError, Wc2022Data, Wc2026Data};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use wwc_core::group::cross_group::GameFilter;
+use wwc_core::group::Groups;
+use wwc_core::playoff::bracket::Bracket;
+use wwc_core::rules::{ThirdPlacePolicy, TournamentRules};
+use wwc_core::team::Teams;
+use wwc_core::venue::GameVenues;
+
+const EURO_2020: &str = include_str!("../tests/data/euro-2021.json");
+const WC_2018: &str = include_str!("../tests/data/wc-2018.json");
+const WC_2022: &str = include_str!("../tests/data/wc-2022.json");
+const WC_2026: &str = include_str!("../tests/data/wc-2026.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// Uefa Euro 2020, played in 2021
+    Euro2020,
+    /// Fifa World Cup 2018, group stage only
+    Wc2018,
+    /// Fifa World Cup 2022, fixtures only
+    Wc2022,
+    /// Fifa World Cup 2026, with placeholder teams
+    Wc2026,
+}
+
+/// Parsed template, ready to be stored
+pub struct TemplateData {
+    pub teams: Teams,
+    pub groups: Groups,
+    /// Knockout stage, if the data file has one
+    pub bracket: Option<Bracket>,
+    /// Venue of the games, empty if the data file has no stadiums
+    pub venues: GameVenues,
+}
+
+impl Template {
+    pub const ALL: [Template; 4] = [
+        Template::Euro2020,
+        Template::Wc2018,
+        Template::Wc2022,
+        Template::Wc2026,
+    ];
+
+    /// Name used to select the template, e.g. in the seed endpoint
+    pub fn name(&self) -> &'static str {
+        match self {
+            Template::Euro2020 => "euro-2020",
+            Template::Wc2018 => "wc-2018",
+            Template::Wc2022 => "wc-2022",
+            Template::Wc2026 => "wc-2026",
+        }
+    }
+
+    /// Rules of the tournament, with the template as the bracket template
+    pub fn rules(&self) -> TournamentRules {
+        let (group_rules, third_place, bracket) = match self {
+            Template::Euro2020 => ("euro_2020", "euro_2020", Some(self.name())),
+            Template::Wc2018 => ("fifa_2018", "euro_2020", None),
+            Template::Wc2022 => ("fifa_2018", "euro_2020", Some(self.name())),
+            Template::Wc2026 => ("fifa_2026", "fifa_2026", Some(self.name())),
+        };
+        TournamentRules {
+            group_rules: String::from(group_rules),
+            bracket: bracket.map(String::from),
+            third_place: ThirdPlacePolicy {
+                rules: String::from(third_place),
+                filter: GameFilter::All,
+            },
+            ..TournamentRules::default()
+        }
+    }
+
+    pub fn load(&self) -> Result<TemplateData, LsvParseError> {
+        match self {
+            Template::Euro2020 => {
+                let data = Euro2021Data::try_data_from_str(EURO_2020)?;
+                Ok(TemplateData {
+                    teams: data.try_teams()?,
+                    groups: data.try_groups()?,
+                    bracket: Some(data.try_bracket()?),
+                    venues: data.try_game_venues()?,
+                })
+            }
+            Template::Wc2018 => {
+                let data = Fifa2018Data::try_data_from_str(WC_2018)?;
+                Ok(TemplateData {
+                    teams: data.try_teams()?,
+                    groups: data.try_groups()?,
+                    bracket: None,
+                    venues: data.try_game_venues()?,
+                })
+            }
+            Template::Wc2022 => {
+                let data = Wc2022Data::try_data_from_str(WC_2022)?;
+                Ok(TemplateData {
+                    teams: data.try_teams()?,
+                    groups: data.try_groups()?,
+                    bracket: Some(data.try_bracket()?),
+                    venues: data.try_game_venues()?,
+                })
+            }
+            Template::Wc2026 => {
+                let data = Wc2026Data::try_data_from_str(WC_2026)?;
+                Ok(TemplateData {
+                    teams: data.try_teams()?,
+                    groups: data.try_groups()?,
+                    bracket: Some(data.try_bracket()?),
+                    venues: data.try_game_venues()?,
+                })
+            }
+        }
+    }
+}
+
+impl TemplateData {
+    /// Replace the bracket with the one of the bracket template of the `rules`
+    ///
+    /// The data keeps no bracket if the rules have no knockout stage, see
+    /// [`TournamentRules::bracket`].
+    pub fn with_bracket_of(self, rules: &TournamentRules) -> Result<TemplateData, LsvParseError> {
+        let bracket = match &rules.bracket {
+            Some(name) => name.parse::<Template>()?.load()?.bracket,
+            None => None,
+        };
+        Ok(TemplateData { bracket, ..self })
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase().replace('_', "-");
+        Template::ALL
+            .iter()
+            .find(|template| template.name() == name)
+            .copied()
+            .ok_or_else(|| TemplateError(String::from(s)))
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Unknown template '{0}', expected one of: euro-2020, wc-2018, wc-2022, wc-2026")]
+pub struct TemplateError(String);
@@ -12,13 +12,13 @@ fn group_ordering() {
         let group = groups
             .get(&id)
             .unwrap_or_else(|| panic!("No group winner '{:?}' in parsed groups", &id));
-        assert_eq!(group.winner(&rules), true_winner);
+        assert_eq!(group.winner(&rules), Some(true_winner));
     }
 
     for (id, true_runner_up) in data.group_runner_ups() {
         let group = groups
             .get(&id)
             .unwrap_or_else(|| panic!("No group runner up '{:?}' in parsed groups", &id));
-        assert_eq!(group.runner_up(&rules), true_runner_up);
+        assert_eq!(group.runner_up(&rules), Some(true_runner_up));
     }
 }
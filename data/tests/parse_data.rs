@@ -1,7 +1,179 @@
-use wwc_data::lsv::{get_data, Fifa2018Data, LsvData};
+use std::collections::{HashMap, HashSet};
+use wwc_core::game::{GameId, Score};
+use wwc_core::group::cross_group;
+use wwc_core::group::order::{fifa_2026, UefaRanking};
+use wwc_core::group::schedule::check_schedule;
+use wwc_core::group::Group;
+use wwc_core::team::i18n::{localize, LocalizedNames};
+use wwc_core::team::TeamName;
+use wwc_data::lsv::{
+    get_data, Euro2021Data, Fifa2018Data, LsvData, LsvParseError, Wc2022Data, Wc2026Data,
+};
+use wwc_data::template::Template;
 
 #[test]
 fn teams_from_full_data() {
     let data: Fifa2018Data = get_data("tests/data/wc-2018.json").unwrap();
     assert_eq!(data.try_teams().unwrap().len(), 32);
 }
+
+#[test]
+fn euro_2021_bracket() {
+    let data: Euro2021Data = get_data("tests/data/euro-2021.json").unwrap();
+    let bracket = data.try_bracket().unwrap();
+    let num_games = |round| bracket.games().filter(|game| game.round.0 == round).count();
+    assert_eq!(
+        (num_games(0), num_games(1), num_games(2), num_games(3)),
+        (8, 4, 2, 1)
+    );
+    let final_ = bracket.games().find(|game| game.round.0 == 3).unwrap();
+    assert_eq!(final_.home.to_string(), "W49");
+}
+
+#[test]
+fn euro_2021_team_names_sv() {
+    let data: Euro2021Data = get_data("tests/data/euro-2021.json").unwrap();
+    let mut teams = data.try_teams().unwrap();
+    let names: HashMap<String, String> =
+        serde_json::from_str(&std::fs::read_to_string("tests/data/team-names-sv.json").unwrap())
+            .unwrap();
+    let names: LocalizedNames = names
+        .into_iter()
+        .map(|(code, name)| {
+            let team = teams
+                .values()
+                .find(|team| team.fifa_code.to_string() == code)
+                .unwrap_or_else(|| panic!("Unknown team '{}'", code));
+            (team.id, TeamName::from(name))
+        })
+        .collect();
+    assert_eq!(names.len(), teams.len());
+    localize(&mut teams, &names);
+    let swe = teams
+        .values()
+        .find(|team| team.fifa_code.to_string() == "SWE")
+        .unwrap();
+    assert_eq!(swe.name.to_string(), "Sverige");
+}
+
+#[test]
+fn group_teams_are_resolved() {
+    let data = |away: &str| {
+        format!(
+            r#"{{"teams": [
+                {{"id": "KOR", "name": "Korea Republic", "rank": 1}},
+                {{"id": "SWE", "name": "Sweden", "rank": 2}}
+            ],
+            "groups": [{{"id": "f", "name": "Group F", "winner": null, "runnerup": null,
+                "matches": [{{"id": 1, "matchtype": "group", "home_team": "SWE",
+                    "away_team": "{}", "home_result": null, "away_result": null,
+                    "finished": false, "date": "2018-06-18T14:00:00+03:00"}}]}}]}}"#,
+            away
+        )
+    };
+    let teams = |away: &str| {
+        let data = Euro2021Data::try_data_from_str(&data(away)).unwrap();
+        let groups = data.try_groups()?;
+        Ok::<_, LsvParseError>(groups.values().next().unwrap().teams().collect::<Vec<_>>())
+    };
+    assert_eq!(teams("KOR").unwrap(), teams("South Korea").unwrap());
+    assert!(matches!(teams("XYZ"), Err(LsvParseError::TeamResolve(_))));
+}
+
+#[test]
+fn game_venues() {
+    let data: Euro2021Data = get_data("tests/data/euro-2021.json").unwrap();
+    let venues = data.try_game_venues().unwrap();
+    assert_eq!(venues.len(), 51);
+    assert_eq!(venues[&GameId::from(51)].label(), "Wembley Stadium, London");
+    let data: Fifa2018Data = get_data("tests/data/wc-2018.json").unwrap();
+    let venues = data.try_game_venues().unwrap();
+    assert_eq!(venues[&GameId::from(1)].label(), "Luzhniki Stadium, Moscow");
+}
+
+#[test]
+fn embedded_templates() {
+    for template in &Template::ALL {
+        let data = template.load().unwrap();
+        assert!(
+            !data.teams.is_empty() && !data.groups.is_empty(),
+            "{}",
+            template
+        );
+        assert_eq!(template.name().parse::<Template>(), Ok(*template));
+        assert_eq!(check_schedule(&data.groups), vec![], "{}", template);
+    }
+    assert_eq!("WC_2018".parse::<Template>(), Ok(Template::Wc2018));
+}
+
+#[test]
+fn bracket_of_rules() {
+    let data = Template::Wc2018.load().unwrap();
+    let rules = Template::Euro2020.rules();
+    let bracket = data.with_bracket_of(&rules).unwrap().bracket.unwrap();
+    assert_eq!(bracket.games().count(), 15);
+    let data = Template::Euro2020.load().unwrap();
+    let rules = Template::Wc2018.rules();
+    assert!(data.with_bracket_of(&rules).unwrap().bracket.is_none());
+}
+
+#[test]
+fn wc_2022_template() {
+    let data: Wc2022Data = get_data("tests/data/wc-2022.json").unwrap();
+    let teams = data.try_teams().unwrap();
+    let groups = data.try_groups().unwrap();
+    assert_eq!((teams.len(), groups.len()), (32, 8));
+    assert!(groups.values().all(|group| group.num_teams() == 4));
+    assert_eq!(check_schedule(&groups), vec![]);
+    let bracket = data.try_bracket().unwrap();
+    let num_games = |round| bracket.games().filter(|game| game.round.0 == round).count();
+    assert_eq!((0..4).map(num_games).collect::<Vec<_>>(), vec![8, 4, 2, 2]);
+    assert_eq!(data.try_game_venues().unwrap().len(), 64);
+    let netherlands = teams
+        .values()
+        .find(|team| team.fifa_code.to_string() == "NED")
+        .unwrap();
+    assert_eq!(netherlands.iso2.to_string(), "nl");
+}
+
+#[test]
+fn wc_2026_template() {
+    let data: Wc2026Data = get_data("tests/data/wc-2026.json").unwrap();
+    let teams = data.try_teams().unwrap();
+    let mut groups = data.try_groups().unwrap();
+    assert_eq!((teams.len(), groups.len()), (48, 12));
+    assert!(groups.values().all(|group| group.num_teams() == 4));
+    let bracket = data.try_bracket().unwrap();
+    let num_games = |round| bracket.games().filter(|game| game.round.0 == round).count();
+    assert_eq!(
+        (0..5).map(num_games).collect::<Vec<_>>(),
+        vec![16, 8, 4, 2, 2]
+    );
+
+    for group in groups.values_mut() {
+        let ids: Vec<_> = group.unplayed_games().map(|game| game.id).collect();
+        for id in ids {
+            let id_ = u32::from(id);
+            group.play_game(id, Score::from((id_ % 3, (id_ / 3) % 2)));
+        }
+    }
+    let ranking: HashMap<_, _> = teams.values().map(|team| (team.id, team.rank)).collect();
+    let group_list: Vec<Group> = groups.values().cloned().collect();
+    let ranking = || UefaRanking::try_new(&group_list, ranking.clone()).unwrap();
+    let nodes = bracket
+        .layout(
+            &groups,
+            &fifa_2026(ranking()),
+            &cross_group::fifa_2026(ranking()),
+            &HashMap::new(),
+        )
+        .unwrap();
+    let round_of_32: Vec<_> = nodes
+        .iter()
+        .filter(|node| node.round.0 == 0)
+        .flat_map(|node| vec![node.home.team, node.away.team])
+        .collect::<Option<_>>()
+        .expect("Every team in the round of 32 is resolved");
+    let distinct: HashSet<_> = round_of_32.iter().collect();
+    assert_eq!(distinct.len(), 32);
+}
@@ -0,0 +1,51 @@
+//! Leaderboard derived from the predictions in Rust, against the db aggregation of the
+//! materialized scores
+//!
+//! Runs against the db in `DATABASE_URL`, with players, predictions and results.
+//! Run `wwc_cli refresh-scores` first if the scores are not up to date.
+#![feature(test)]
+extern crate test;
+use std::collections::HashMap;
+use test::Bencher;
+use wwc_core::game::{GameId, Score};
+use wwc_core::leaderboard::standings;
+use wwc_core::player::PlayerId;
+use wwc_core::pred_score::SimplePredScoreFn;
+
+#[bench]
+pub fn derived_leaderboard(b: &mut Bencher) {
+    b.iter(|| {
+        let groups = wwc_db::get_groups().unwrap();
+        let results: HashMap<GameId, Score> = groups
+            .values()
+            .flat_map(|group| group.played_games())
+            .map(|game| (game.id, game.score))
+            .collect();
+        let remaining: Vec<GameId> = groups
+            .values()
+            .flat_map(|group| group.unplayed_games())
+            .map(|game| game.id)
+            .collect();
+        let players: Vec<_> = wwc_db::get_players()
+            .unwrap()
+            .into_iter()
+            .map(|player| {
+                let id = PlayerId::from(player.id);
+                (id, wwc_db::get_preds(id).unwrap())
+            })
+            .collect();
+        let jokers = wwc_db::joker::get_all_jokers().unwrap();
+        standings(
+            &players,
+            &jokers,
+            &results,
+            &remaining,
+            &SimplePredScoreFn::default(),
+        )
+    });
+}
+
+#[bench]
+pub fn materialized_leaderboard(b: &mut Bencher) {
+    b.iter(|| wwc_db::score::get_score_leaderboard().unwrap());
+}
@@ -0,0 +1,12 @@
+CREATE TABLE preds_tmp (
+  id INTEGER PRIMARY KEY NOT NULL,
+  player_id INTEGER SECONDARY KEY NOT NULL,
+  game_id INTEGER SECONDARY KEY NOT NULL,
+  home_result INTEGER NOT NULL,
+  away_result INTEGER NOT NULL,
+  FOREIGN KEY(game_id) REFERENCES games(id)
+  FOREIGN KEY(player_id) REFERENCES players(id)
+);
+INSERT INTO preds_tmp SELECT id, player_id, game_id, home_result, away_result FROM preds;
+DROP TABLE preds;
+ALTER TABLE preds_tmp RENAME TO preds;
@@ -0,0 +1 @@
+ALTER TABLE preds ADD COLUMN created_at VARCHAR NOT NULL DEFAULT '1970-01-01T00:00:00+00:00'
\ No newline at end of file
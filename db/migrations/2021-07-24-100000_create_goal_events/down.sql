@@ -0,0 +1 @@
+DROP TABLE goal_events
\ No newline at end of file
@@ -0,0 +1,5 @@
+CREATE TABLE tournaments (
+  id INTEGER PRIMARY KEY NOT NULL,
+  name VARCHAR NOT NULL,
+  sandbox BOOLEAN NOT NULL DEFAULT 'f'
+)
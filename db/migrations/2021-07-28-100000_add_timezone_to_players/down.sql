@@ -0,0 +1,7 @@
+CREATE TABLE players_tmp (
+  id INTEGER PRIMARY KEY NOT NULL,
+  name VARCHAR NOT NULL
+);
+INSERT INTO players_tmp SELECT id, name FROM players;
+DROP TABLE players;
+ALTER TABLE players_tmp RENAME TO players
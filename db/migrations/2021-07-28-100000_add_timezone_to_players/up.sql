@@ -0,0 +1 @@
+ALTER TABLE players ADD COLUMN timezone VARCHAR
@@ -0,0 +1,8 @@
+CREATE TABLE jokers (
+  id INTEGER PRIMARY KEY NOT NULL,
+  player_id INTEGER NOT NULL,
+  game_id INTEGER NOT NULL,
+  UNIQUE(player_id, game_id),
+  FOREIGN KEY(player_id) REFERENCES players(id),
+  FOREIGN KEY(game_id) REFERENCES games(id)
+)
@@ -0,0 +1,2 @@
+DROP TABLE league_members;
+DROP TABLE leagues
@@ -0,0 +1,14 @@
+CREATE TABLE leagues (
+  id INTEGER PRIMARY KEY NOT NULL,
+  name VARCHAR NOT NULL,
+  scoring_rules VARCHAR NOT NULL
+);
+
+CREATE TABLE league_members (
+  id INTEGER PRIMARY KEY NOT NULL,
+  league_id INTEGER NOT NULL,
+  player_id INTEGER NOT NULL,
+  UNIQUE(league_id, player_id),
+  FOREIGN KEY(league_id) REFERENCES leagues(id),
+  FOREIGN KEY(player_id) REFERENCES players(id)
+)
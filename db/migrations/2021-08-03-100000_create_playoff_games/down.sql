@@ -0,0 +1 @@
+DROP TABLE playoff_games
@@ -0,0 +1,9 @@
+CREATE TABLE playoff_games (
+  id INTEGER PRIMARY KEY NOT NULL,
+  round INTEGER NOT NULL,
+  home_feeder VARCHAR NOT NULL,
+  away_feeder VARCHAR NOT NULL,
+  kickoff VARCHAR NOT NULL,
+  winner INTEGER,
+  FOREIGN KEY(winner) REFERENCES teams(id)
+)
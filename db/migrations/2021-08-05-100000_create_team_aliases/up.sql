@@ -0,0 +1,5 @@
+CREATE TABLE team_aliases (
+  alias VARCHAR PRIMARY KEY NOT NULL,
+  team_id INTEGER NOT NULL,
+  FOREIGN KEY(team_id) REFERENCES teams(id)
+)
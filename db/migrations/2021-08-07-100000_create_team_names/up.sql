@@ -0,0 +1,8 @@
+CREATE TABLE team_names (
+  id INTEGER PRIMARY KEY NOT NULL,
+  team_id INTEGER NOT NULL,
+  lang VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  UNIQUE(team_id, lang),
+  FOREIGN KEY(team_id) REFERENCES teams(id)
+)
@@ -0,0 +1,9 @@
+CREATE TABLE achievements (
+  id INTEGER PRIMARY KEY NOT NULL,
+  player_id INTEGER NOT NULL,
+  achievement TEXT NOT NULL,
+  game_id INTEGER NOT NULL,
+  UNIQUE(player_id, achievement),
+  FOREIGN KEY(player_id) REFERENCES players(id),
+  FOREIGN KEY(game_id) REFERENCES games(id)
+)
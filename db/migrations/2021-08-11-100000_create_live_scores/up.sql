@@ -0,0 +1,6 @@
+CREATE TABLE live_scores (
+  game_id INTEGER PRIMARY KEY NOT NULL,
+  home_result INTEGER NOT NULL,
+  away_result INTEGER NOT NULL,
+  FOREIGN KEY(game_id) REFERENCES games(id)
+)
@@ -0,0 +1,8 @@
+CREATE TABLE scores (
+  player_id INTEGER NOT NULL,
+  game_id INTEGER NOT NULL,
+  points REAL NOT NULL,
+  PRIMARY KEY(player_id, game_id),
+  FOREIGN KEY(player_id) REFERENCES players(id),
+  FOREIGN KEY(game_id) REFERENCES games(id)
+)
@@ -0,0 +1,17 @@
+CREATE TABLE games_tmp (
+  id INTEGER PRIMARY KEY NOT NULL,
+  type_ VARCHAR NOT NULL,
+  home_team INTEGER NOT NULL,
+  away_team INTEGER NOT NULL,
+  home_result INTEGER,
+  away_result INTEGER,
+  home_penalty INTEGER,
+  away_penalty INTEGER,
+  home_fair_play INTEGER,
+  away_fair_play INTEGER,
+  played BOOLEAN NOT NULL DEFAULT 'f',
+  kickoff VARCHAR
+);
+INSERT INTO games_tmp SELECT id, type_, home_team, away_team, home_result, away_result, home_penalty, away_penalty, home_fair_play, away_fair_play, played, kickoff FROM games;
+DROP TABLE games;
+ALTER TABLE games_tmp RENAME TO games
@@ -0,0 +1 @@
+ALTER TABLE games ADD COLUMN row_version INTEGER NOT NULL DEFAULT 0
@@ -0,0 +1,7 @@
+CREATE TABLE odds (
+  game_id INTEGER PRIMARY KEY NOT NULL,
+  home REAL NOT NULL,
+  draw REAL NOT NULL,
+  away REAL NOT NULL,
+  FOREIGN KEY(game_id) REFERENCES games(id)
+)
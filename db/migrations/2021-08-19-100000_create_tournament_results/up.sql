@@ -0,0 +1,8 @@
+CREATE TABLE tournament_results (
+  tournament_id INTEGER NOT NULL,
+  player_id INTEGER NOT NULL,
+  points REAL NOT NULL,
+  PRIMARY KEY(tournament_id, player_id),
+  FOREIGN KEY(tournament_id) REFERENCES tournaments(id),
+  FOREIGN KEY(player_id) REFERENCES players(id)
+)
@@ -0,0 +1,8 @@
+CREATE TABLE players_tmp (
+  id INTEGER PRIMARY KEY NOT NULL,
+  name VARCHAR NOT NULL,
+  timezone VARCHAR
+);
+INSERT INTO players_tmp SELECT id, name, timezone FROM players;
+DROP TABLE players;
+ALTER TABLE players_tmp RENAME TO players
@@ -0,0 +1,8 @@
+CREATE TABLE invites (
+  token TEXT PRIMARY KEY NOT NULL,
+  league_id INTEGER NOT NULL,
+  expires_at TEXT NOT NULL,
+  used_by INTEGER,
+  FOREIGN KEY(league_id) REFERENCES leagues(id),
+  FOREIGN KEY(used_by) REFERENCES players(id)
+)
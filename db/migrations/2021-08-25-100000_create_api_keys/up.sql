@@ -0,0 +1,7 @@
+CREATE TABLE api_keys (
+  id INTEGER PRIMARY KEY NOT NULL,
+  name TEXT NOT NULL,
+  key_hash TEXT NOT NULL UNIQUE,
+  scope TEXT NOT NULL,
+  created_at TEXT NOT NULL
+)
@@ -0,0 +1,2 @@
+DROP TABLE game_venues;
+DROP TABLE venues
@@ -0,0 +1,9 @@
+CREATE TABLE venues (
+  id TEXT PRIMARY KEY NOT NULL,
+  name TEXT NOT NULL,
+  city TEXT NOT NULL
+);
+CREATE TABLE game_venues (
+  game_id INTEGER PRIMARY KEY NOT NULL REFERENCES games(id),
+  venue_id TEXT NOT NULL REFERENCES venues(id)
+)
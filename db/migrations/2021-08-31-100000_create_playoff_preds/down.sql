@@ -0,0 +1 @@
+DROP TABLE playoff_preds
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE playoff_preds (
+  id INTEGER PRIMARY KEY NOT NULL,
+  player_id INTEGER NOT NULL,
+  game_id INTEGER NOT NULL,
+  winner INTEGER NOT NULL,
+  UNIQUE(player_id, game_id),
+  FOREIGN KEY(player_id) REFERENCES players(id),
+  FOREIGN KEY(game_id) REFERENCES playoff_games(id),
+  FOREIGN KEY(winner) REFERENCES teams(id)
+)
@@ -0,0 +1 @@
+DROP TABLE standings_snapshots
\ No newline at end of file
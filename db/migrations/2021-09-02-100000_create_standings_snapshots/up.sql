@@ -0,0 +1,9 @@
+CREATE TABLE standings_snapshots (
+  game_id INTEGER NOT NULL,
+  team_id INTEGER NOT NULL,
+  rank INTEGER NOT NULL,
+  points INTEGER NOT NULL,
+  PRIMARY KEY(game_id, team_id),
+  FOREIGN KEY(game_id) REFERENCES games(id),
+  FOREIGN KEY(team_id) REFERENCES teams(id)
+)
@@ -0,0 +1,9 @@
+CREATE TABLE players_tmp (
+  id INTEGER PRIMARY KEY NOT NULL,
+  name VARCHAR NOT NULL,
+  timezone VARCHAR,
+  avatar VARCHAR
+);
+INSERT INTO players_tmp SELECT id, name, timezone, avatar FROM players;
+DROP TABLE players;
+ALTER TABLE players_tmp RENAME TO players
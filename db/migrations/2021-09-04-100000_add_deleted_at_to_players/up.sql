@@ -0,0 +1 @@
+ALTER TABLE players ADD COLUMN deleted_at TEXT
@@ -0,0 +1,13 @@
+CREATE TABLE preds_tmp (
+  id INTEGER PRIMARY KEY NOT NULL,
+  player_id INTEGER SECONDARY KEY NOT NULL,
+  game_id INTEGER SECONDARY KEY NOT NULL,
+  home_result INTEGER NOT NULL,
+  away_result INTEGER NOT NULL,
+  created_at VARCHAR NOT NULL DEFAULT '1970-01-01T00:00:00+00:00',
+  FOREIGN KEY(game_id) REFERENCES games(id)
+  FOREIGN KEY(player_id) REFERENCES players(id)
+);
+INSERT INTO preds_tmp SELECT id, player_id, game_id, home_result, away_result, created_at FROM preds;
+DROP TABLE preds;
+ALTER TABLE preds_tmp RENAME TO preds
@@ -0,0 +1 @@
+ALTER TABLE preds ADD COLUMN deleted_at TEXT
@@ -0,0 +1,8 @@
+CREATE TABLE group_game_map_tmp (
+  id INTEGER PRIMARY KEY NOT NULL,
+  group_id_ CHAR NOT NULL CHECK (group_id_ GLOB '[A-Z]'),
+  FOREIGN KEY(id) REFERENCES games(id)
+);
+INSERT INTO group_game_map_tmp SELECT id, group_id_ FROM group_game_map;
+DROP TABLE group_game_map;
+ALTER TABLE group_game_map_tmp RENAME TO group_game_map
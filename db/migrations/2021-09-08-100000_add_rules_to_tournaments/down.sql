@@ -0,0 +1,8 @@
+CREATE TABLE tournaments_tmp (
+  id INTEGER PRIMARY KEY NOT NULL,
+  name VARCHAR NOT NULL,
+  sandbox BOOLEAN NOT NULL DEFAULT 'f'
+);
+INSERT INTO tournaments_tmp SELECT id, name, sandbox FROM tournaments;
+DROP TABLE tournaments;
+ALTER TABLE tournaments_tmp RENAME TO tournaments
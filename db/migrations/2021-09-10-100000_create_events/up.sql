@@ -0,0 +1,6 @@
+CREATE TABLE events (
+  id INTEGER PRIMARY KEY NOT NULL,
+  kind VARCHAR NOT NULL,
+  payload TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+)
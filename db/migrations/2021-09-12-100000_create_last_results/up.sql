@@ -0,0 +1,4 @@
+CREATE TABLE last_results (
+  group_id CHAR PRIMARY KEY NOT NULL CHECK (group_id GLOB '[A-Z]'),
+  posted_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+)
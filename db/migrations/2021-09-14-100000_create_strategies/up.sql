@@ -0,0 +1,8 @@
+CREATE TABLE strategies (
+  tournament_id INTEGER NOT NULL,
+  player_id INTEGER NOT NULL,
+  strategy TEXT NOT NULL,
+  PRIMARY KEY(tournament_id, player_id),
+  FOREIGN KEY(tournament_id) REFERENCES tournaments(id),
+  FOREIGN KEY(player_id) REFERENCES players(id)
+)
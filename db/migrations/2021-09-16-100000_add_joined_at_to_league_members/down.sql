@@ -0,0 +1,11 @@
+CREATE TABLE league_members_tmp (
+  id INTEGER PRIMARY KEY NOT NULL,
+  league_id INTEGER NOT NULL,
+  player_id INTEGER NOT NULL,
+  UNIQUE(league_id, player_id),
+  FOREIGN KEY(league_id) REFERENCES leagues(id),
+  FOREIGN KEY(player_id) REFERENCES players(id)
+);
+INSERT INTO league_members_tmp SELECT id, league_id, player_id FROM league_members;
+DROP TABLE league_members;
+ALTER TABLE league_members_tmp RENAME TO league_members
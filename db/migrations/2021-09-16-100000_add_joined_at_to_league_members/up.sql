@@ -0,0 +1 @@
+ALTER TABLE league_members ADD COLUMN joined_at TEXT NOT NULL DEFAULT '1970-01-01T00:00:00+00:00'
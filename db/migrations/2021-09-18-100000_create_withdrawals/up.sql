@@ -0,0 +1,7 @@
+CREATE TABLE withdrawals (
+  team_id INTEGER PRIMARY KEY NOT NULL,
+  group_id CHAR NOT NULL CHECK (group_id GLOB '[A-Z]'),
+  policy TEXT NOT NULL,
+  withdrawn_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(team_id) REFERENCES teams(id)
+)
@@ -0,0 +1,9 @@
+CREATE TABLE card_events (
+  id INTEGER PRIMARY KEY NOT NULL,
+  game_id INTEGER NOT NULL,
+  team_id INTEGER NOT NULL,
+  player VARCHAR,
+  minute INTEGER NOT NULL,
+  kind VARCHAR NOT NULL,
+  FOREIGN KEY(game_id) REFERENCES games(id)
+)
@@ -0,0 +1,13 @@
+CREATE TABLE standings (
+  team_id INTEGER PRIMARY KEY NOT NULL,
+  group_id TEXT NOT NULL,
+  rank INTEGER NOT NULL,
+  played INTEGER NOT NULL,
+  wins INTEGER NOT NULL,
+  draws INTEGER NOT NULL,
+  losses INTEGER NOT NULL,
+  goals_for INTEGER NOT NULL,
+  goals_against INTEGER NOT NULL,
+  points INTEGER NOT NULL,
+  FOREIGN KEY(team_id) REFERENCES teams(id)
+)
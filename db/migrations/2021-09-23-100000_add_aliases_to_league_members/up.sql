@@ -0,0 +1,2 @@
+ALTER TABLE league_members ADD COLUMN display_name TEXT;
+ALTER TABLE league_members ADD COLUMN emoji TEXT;
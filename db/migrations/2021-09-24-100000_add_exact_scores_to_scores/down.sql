@@ -0,0 +1,11 @@
+CREATE TABLE scores_tmp (
+  player_id INTEGER NOT NULL,
+  game_id INTEGER NOT NULL,
+  points REAL NOT NULL,
+  PRIMARY KEY(player_id, game_id),
+  FOREIGN KEY(player_id) REFERENCES players(id),
+  FOREIGN KEY(game_id) REFERENCES games(id)
+);
+INSERT INTO scores_tmp SELECT player_id, game_id, points FROM scores;
+DROP TABLE scores;
+ALTER TABLE scores_tmp RENAME TO scores
@@ -0,0 +1 @@
+ALTER TABLE scores ADD COLUMN exact_scores INTEGER NOT NULL DEFAULT 0;
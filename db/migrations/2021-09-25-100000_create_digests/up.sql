@@ -0,0 +1,4 @@
+CREATE TABLE digests (
+  day TEXT PRIMARY KEY NOT NULL,
+  published_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+)
@@ -0,0 +1,76 @@
+//! Achievements
+//!
+//! Badges are derived from the predictions and the played games, see [`wwc_core::achievement`],
+//! and stored once earned.
+//! A stored badge is kept, even if a later correction of a result would no longer earn it.
+use crate::establish_connection;
+use crate::models::{NewPlayerAchievement, PlayerAchievement};
+use crate::schema::achievements;
+use crate::DbError;
+use diesel::prelude::*;
+use std::convert::TryFrom;
+use wwc_core::achievement::{achievements, default_rules, Achievement, Badge};
+use wwc_core::game::GameId;
+use wwc_core::player::PlayerId;
+use wwc_core::team::Teams;
+
+pub fn get_achievements(player_id: PlayerId) -> Result<Vec<Badge>, DbError> {
+    let connection = establish_connection()?;
+    achievements::table
+        .filter(achievements::player_id.eq(i32::from(player_id)))
+        .order(achievements::id)
+        .load::<PlayerAchievement>(&connection)?
+        .iter()
+        .map(badge)
+        .collect()
+}
+
+/// Store badges, keeping already earned ones
+///
+/// Returns the number of new badges.
+pub fn award_achievements(player_id: PlayerId, badges: &[Badge]) -> Result<usize, DbError> {
+    let rows: Vec<NewPlayerAchievement> = badges
+        .iter()
+        .map(|badge| NewPlayerAchievement {
+            player_id: i32::from(player_id),
+            achievement: badge.achievement.name(),
+            game_id: i32::try_from(u32::from(badge.game)).expect("u32 -> i32 conv"),
+        })
+        .collect();
+    let connection = establish_connection()?;
+    Ok(diesel::insert_or_ignore_into(achievements::table)
+        .values(&rows)
+        .execute(&connection)?)
+}
+
+/// Award the badges earned by every player with the current results
+///
+/// Returns the number of new badges.
+pub fn refresh_achievements() -> Result<usize, DbError> {
+    let groups = crate::get_groups()?;
+    let teams: Teams = crate::get_teams()?.map(|team| (team.id, team)).collect();
+    let rules = default_rules();
+    let mut num_new = 0;
+    for player in crate::get_players()? {
+        let player_id = PlayerId::from(player.id);
+        let preds = crate::get_preds(player_id)?;
+        num_new += award_achievements(player_id, &achievements(&preds, &groups, &teams, &rules))?;
+    }
+    Ok(num_new)
+}
+
+pub fn clear_achievements() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(achievements::table).execute(&connection)?;
+    Ok(())
+}
+
+fn badge(row: &PlayerAchievement) -> Result<Badge, DbError> {
+    Ok(Badge {
+        achievement: row
+            .achievement
+            .parse::<Achievement>()
+            .map_err(|err| DbError::Generic(err.to_string()))?,
+        game: GameId::from(u32::try_from(row.game_id).expect("i32 -> u32 conv")),
+    })
+}
@@ -0,0 +1,65 @@
+//! API keys
+//!
+//! Keys of integrations, stored by hash, see [`wwc_core::api_key`].
+use crate::establish_connection;
+use crate::models::{NewApiKey, StoredApiKey};
+use crate::schema::api_keys;
+use crate::DbError;
+use diesel::prelude::*;
+use std::convert::TryFrom;
+use wwc_core::api_key::{ApiKey, ApiKeyInfo, Scope};
+use wwc_core::Date;
+
+/// Create a new random key
+///
+/// Returns the key itself along with the stored info, the key can not be recovered later.
+pub fn create_api_key(
+    name: &str,
+    scope: Scope,
+    created_at: Date,
+) -> Result<(ApiKey, ApiKeyInfo), DbError> {
+    let key = ApiKey::random();
+    let connection = establish_connection()?;
+    diesel::insert_into(api_keys::table)
+        .values(&NewApiKey {
+            name,
+            key_hash: key.hash(),
+            scope: scope.name(),
+            created_at: created_at.to_string(),
+        })
+        .execute(&connection)?;
+    let stored = api_keys::table
+        .filter(api_keys::key_hash.eq(key.hash()))
+        .first::<StoredApiKey>(&connection)?;
+    Ok((key, ApiKeyInfo::try_from(stored)?))
+}
+
+pub fn get_api_keys() -> Result<Vec<ApiKeyInfo>, DbError> {
+    let connection = establish_connection()?;
+    api_keys::table
+        .load::<StoredApiKey>(&connection)?
+        .into_iter()
+        .map(ApiKeyInfo::try_from)
+        .collect()
+}
+
+/// Scope of a key, `None` for an unknown or revoked key
+pub fn get_scope(key: &ApiKey) -> Result<Option<Scope>, DbError> {
+    let connection = establish_connection()?;
+    api_keys::table
+        .filter(api_keys::key_hash.eq(key.hash()))
+        .first::<StoredApiKey>(&connection)
+        .optional()?
+        .map(|stored| ApiKeyInfo::try_from(stored).map(|info| info.scope))
+        .transpose()
+}
+
+/// Revoke a key, requests with the key are no longer authenticated
+pub fn revoke_api_key(id: i32) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    let num_deleted = diesel::delete(api_keys::table.find(id)).execute(&connection)?;
+    if num_deleted == 0 {
+        return Err(DbError::Generic(format!("No API key with id {}", id)));
+    }
+    Ok(())
+}
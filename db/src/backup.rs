@@ -0,0 +1,265 @@
+//! Backup and restore
+//!
+//! A [`Snapshot`] holds every row of every table, including the id's, so that references between
+//! tables survive a round trip.
+//! It only depends on the row types in [`models`](crate::models), not on the SQL backend,
+//! and serializes with serde to any format, e.g. JSON.
+//!
+//! Bump [`SNAPSHOT_VERSION`] whenever a table is added or changed.
+use crate::establish_connection;
+use crate::models::{
+    Game, GameOdds, GameVenue, GoalEvent, GroupGameMap, Joker, LastResult, League, LeagueInvite,
+    LeagueMember, LiveScore, LocalizedTeamName, Player, PlayerAchievement, PlayerScore,
+    PlayoffGame, PlayoffPred, Pred, PublishedDigest, StoredApiKey, StoredCardEvent, StoredEvent,
+    StoredSnapshotRow, StoredStanding, StoredStrategy, StoredVenue, Team, TeamAlias, Tournament,
+    TournamentPoints, Withdrawal,
+};
+use crate::schema::{
+    achievements, api_keys, card_events, digests, events, game_venues, games, goal_events,
+    group_game_map, invites, jokers, last_results, league_members, leagues, live_scores, odds,
+    players, playoff_games, playoff_preds, preds, scores, standings, standings_snapshots,
+    strategies, team_aliases, team_names, teams, tournament_results, tournaments, venues,
+    withdrawals,
+};
+use crate::DbError;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Current snapshot format version
+pub const SNAPSHOT_VERSION: u32 = 34;
+
+/// Complete database content
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub tournaments: Vec<Tournament>,
+    pub teams: Vec<Team>,
+    pub team_aliases: Vec<TeamAlias>,
+    pub team_names: Vec<LocalizedTeamName>,
+    pub games: Vec<Game>,
+    pub group_game_map: Vec<GroupGameMap>,
+    pub playoff_games: Vec<PlayoffGame>,
+    pub venues: Vec<StoredVenue>,
+    pub game_venues: Vec<GameVenue>,
+    pub players: Vec<Player>,
+    pub leagues: Vec<League>,
+    pub league_members: Vec<LeagueMember>,
+    pub invites: Vec<LeagueInvite>,
+    pub preds: Vec<Pred>,
+    pub jokers: Vec<Joker>,
+    pub playoff_preds: Vec<PlayoffPred>,
+    pub goal_events: Vec<GoalEvent>,
+    pub card_events: Vec<StoredCardEvent>,
+    pub live_scores: Vec<LiveScore>,
+    pub achievements: Vec<PlayerAchievement>,
+    pub scores: Vec<PlayerScore>,
+    pub standings_snapshots: Vec<StoredSnapshotRow>,
+    pub standings: Vec<StoredStanding>,
+    pub odds: Vec<GameOdds>,
+    pub tournament_results: Vec<TournamentPoints>,
+    pub api_keys: Vec<StoredApiKey>,
+    pub events: Vec<StoredEvent>,
+    pub last_results: Vec<LastResult>,
+    pub strategies: Vec<StoredStrategy>,
+    pub withdrawals: Vec<Withdrawal>,
+    pub digests: Vec<PublishedDigest>,
+}
+
+/// Read the entire database
+///
+/// All tables are read in a single transaction, giving a consistent snapshot.
+pub fn backup() -> Result<Snapshot, DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        Ok(Snapshot {
+            version: SNAPSHOT_VERSION,
+            tournaments: tournaments::table.load(&connection)?,
+            teams: teams::table.load(&connection)?,
+            team_aliases: team_aliases::table.load(&connection)?,
+            team_names: team_names::table.load(&connection)?,
+            games: games::table.load(&connection)?,
+            group_game_map: group_game_map::table.load(&connection)?,
+            playoff_games: playoff_games::table.load(&connection)?,
+            venues: venues::table.load(&connection)?,
+            game_venues: game_venues::table.load(&connection)?,
+            players: players::table.load(&connection)?,
+            leagues: leagues::table.load(&connection)?,
+            league_members: league_members::table.load(&connection)?,
+            invites: invites::table.load(&connection)?,
+            preds: preds::table.load(&connection)?,
+            jokers: jokers::table.load(&connection)?,
+            playoff_preds: playoff_preds::table.load(&connection)?,
+            goal_events: goal_events::table.load(&connection)?,
+            card_events: card_events::table.load(&connection)?,
+            live_scores: live_scores::table.load(&connection)?,
+            achievements: achievements::table.load(&connection)?,
+            scores: scores::table.load(&connection)?,
+            standings_snapshots: standings_snapshots::table.load(&connection)?,
+            standings: standings::table.load(&connection)?,
+            odds: odds::table.load(&connection)?,
+            tournament_results: tournament_results::table.load(&connection)?,
+            api_keys: api_keys::table.load(&connection)?,
+            events: events::table.load(&connection)?,
+            last_results: last_results::table.load(&connection)?,
+            strategies: strategies::table.load(&connection)?,
+            withdrawals: withdrawals::table.load(&connection)?,
+            digests: digests::table.load(&connection)?,
+        })
+    })
+}
+
+/// Restore a snapshot into an empty database
+///
+/// # Errors
+///
+/// Refuses to restore a snapshot of another version, or into a database with any content,
+/// mixing a snapshot with existing rows would break the references between tables.
+/// Everything is written in a single transaction, either the full snapshot is restored or nothing.
+pub fn restore(snapshot: &Snapshot) -> Result<(), DbError> {
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(DbError::SnapshotVersion(snapshot.version));
+    }
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let num_rows = tournaments::table.count().get_result::<i64>(&connection)?
+            + teams::table.count().get_result::<i64>(&connection)?
+            + team_aliases::table.count().get_result::<i64>(&connection)?
+            + team_names::table.count().get_result::<i64>(&connection)?
+            + games::table.count().get_result::<i64>(&connection)?
+            + group_game_map::table
+                .count()
+                .get_result::<i64>(&connection)?
+            + playoff_games::table
+                .count()
+                .get_result::<i64>(&connection)?
+            + venues::table.count().get_result::<i64>(&connection)?
+            + game_venues::table.count().get_result::<i64>(&connection)?
+            + players::table.count().get_result::<i64>(&connection)?
+            + leagues::table.count().get_result::<i64>(&connection)?
+            + league_members::table
+                .count()
+                .get_result::<i64>(&connection)?
+            + invites::table.count().get_result::<i64>(&connection)?
+            + preds::table.count().get_result::<i64>(&connection)?
+            + jokers::table.count().get_result::<i64>(&connection)?
+            + playoff_preds::table
+                .count()
+                .get_result::<i64>(&connection)?
+            + goal_events::table.count().get_result::<i64>(&connection)?
+            + card_events::table.count().get_result::<i64>(&connection)?
+            + live_scores::table.count().get_result::<i64>(&connection)?
+            + achievements::table.count().get_result::<i64>(&connection)?
+            + scores::table.count().get_result::<i64>(&connection)?
+            + standings_snapshots::table
+                .count()
+                .get_result::<i64>(&connection)?
+            + standings::table.count().get_result::<i64>(&connection)?
+            + odds::table.count().get_result::<i64>(&connection)?
+            + tournament_results::table
+                .count()
+                .get_result::<i64>(&connection)?
+            + api_keys::table.count().get_result::<i64>(&connection)?
+            + events::table.count().get_result::<i64>(&connection)?
+            + last_results::table.count().get_result::<i64>(&connection)?
+            + strategies::table.count().get_result::<i64>(&connection)?
+            + withdrawals::table.count().get_result::<i64>(&connection)?
+            + digests::table.count().get_result::<i64>(&connection)?;
+        if num_rows > 0 {
+            return Err(DbError::NotEmpty);
+        }
+        diesel::insert_into(tournaments::table)
+            .values(&snapshot.tournaments)
+            .execute(&connection)?;
+        diesel::insert_into(teams::table)
+            .values(&snapshot.teams)
+            .execute(&connection)?;
+        diesel::insert_into(team_aliases::table)
+            .values(&snapshot.team_aliases)
+            .execute(&connection)?;
+        diesel::insert_into(team_names::table)
+            .values(&snapshot.team_names)
+            .execute(&connection)?;
+        diesel::insert_into(games::table)
+            .values(&snapshot.games)
+            .execute(&connection)?;
+        diesel::insert_into(group_game_map::table)
+            .values(&snapshot.group_game_map)
+            .execute(&connection)?;
+        diesel::insert_into(playoff_games::table)
+            .values(&snapshot.playoff_games)
+            .execute(&connection)?;
+        diesel::insert_into(venues::table)
+            .values(&snapshot.venues)
+            .execute(&connection)?;
+        diesel::insert_into(game_venues::table)
+            .values(&snapshot.game_venues)
+            .execute(&connection)?;
+        diesel::insert_into(players::table)
+            .values(&snapshot.players)
+            .execute(&connection)?;
+        diesel::insert_into(leagues::table)
+            .values(&snapshot.leagues)
+            .execute(&connection)?;
+        diesel::insert_into(league_members::table)
+            .values(&snapshot.league_members)
+            .execute(&connection)?;
+        diesel::insert_into(invites::table)
+            .values(&snapshot.invites)
+            .execute(&connection)?;
+        diesel::insert_into(preds::table)
+            .values(&snapshot.preds)
+            .execute(&connection)?;
+        diesel::insert_into(jokers::table)
+            .values(&snapshot.jokers)
+            .execute(&connection)?;
+        diesel::insert_into(playoff_preds::table)
+            .values(&snapshot.playoff_preds)
+            .execute(&connection)?;
+        diesel::insert_into(goal_events::table)
+            .values(&snapshot.goal_events)
+            .execute(&connection)?;
+        diesel::insert_into(card_events::table)
+            .values(&snapshot.card_events)
+            .execute(&connection)?;
+        diesel::insert_into(live_scores::table)
+            .values(&snapshot.live_scores)
+            .execute(&connection)?;
+        diesel::insert_into(achievements::table)
+            .values(&snapshot.achievements)
+            .execute(&connection)?;
+        diesel::insert_into(scores::table)
+            .values(&snapshot.scores)
+            .execute(&connection)?;
+        diesel::insert_into(standings_snapshots::table)
+            .values(&snapshot.standings_snapshots)
+            .execute(&connection)?;
+        diesel::insert_into(standings::table)
+            .values(&snapshot.standings)
+            .execute(&connection)?;
+        diesel::insert_into(odds::table)
+            .values(&snapshot.odds)
+            .execute(&connection)?;
+        diesel::insert_into(tournament_results::table)
+            .values(&snapshot.tournament_results)
+            .execute(&connection)?;
+        diesel::insert_into(api_keys::table)
+            .values(&snapshot.api_keys)
+            .execute(&connection)?;
+        diesel::insert_into(events::table)
+            .values(&snapshot.events)
+            .execute(&connection)?;
+        diesel::insert_into(last_results::table)
+            .values(&snapshot.last_results)
+            .execute(&connection)?;
+        diesel::insert_into(strategies::table)
+            .values(&snapshot.strategies)
+            .execute(&connection)?;
+        diesel::insert_into(withdrawals::table)
+            .values(&snapshot.withdrawals)
+            .execute(&connection)?;
+        diesel::insert_into(digests::table)
+            .values(&snapshot.digests)
+            .execute(&connection)?;
+        Ok(())
+    })
+}
@@ -0,0 +1,27 @@
+//! Published matchday digests
+//!
+//! The digest of a matchday is published once, the day is stored in the `digests` table when it
+//! is, see [`wwc_core::digest`].
+use crate::establish_connection;
+use crate::models::NewPublishedDigest;
+use crate::schema::digests;
+use crate::DbError;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+/// Record the digest of `day` as published, `false` if it already was
+pub fn mark_published(day: NaiveDate) -> Result<bool, DbError> {
+    let connection = establish_connection()?;
+    let num_inserted = diesel::insert_or_ignore_into(digests::table)
+        .values(&NewPublishedDigest {
+            day: day.to_string(),
+        })
+        .execute(&connection)?;
+    Ok(num_inserted == 1)
+}
+
+pub fn clear_digests() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(digests::table).execute(&connection)?;
+    Ok(())
+}
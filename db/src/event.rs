@@ -0,0 +1,131 @@
+//! Domain events
+//!
+//! Writes to the tournament state publish a [`DomainEvent`], see [`publish`].
+//! The event is appended to the `events` table, the audit log of the tournament, and then applied
+//! to the derived state, in one place instead of a hook in every write.
+//! Both happen in the transaction of the write, either the write, its event and the derived state
+//! are all stored or none:
+//!
+//! - the live score of a game with a result is removed, see [`live`](crate::live)
+//! - the materialized scores, see [`score`](crate::score), also of a playoff winner
+//! - the group standings, see [`standings`](crate::standings)
+//! - the standings history, see [`standings_history`](crate::standings_history)
+//! - the time of the latest result of the group, see [`last_result`](crate::last_result)
+//!
+//! A result completing a group, or the whole group stage, publishes a
+//! [`StageCompleted`](DomainEvent::StageCompleted) event as well.
+use crate::establish_connection;
+use crate::models::{NewEvent, StoredEvent};
+use crate::schema::events;
+use crate::{last_result, live, score, standings, standings_history, DbError};
+use diesel::prelude::*;
+use serde::Serialize;
+use wwc_core::event::{completed_stages, DomainEvent};
+use wwc_core::game::{GameId, GameStatus, Score};
+
+/// Event read back from the log
+#[derive(Debug, Clone, Serialize)]
+pub struct EventRecord {
+    /// Position in the log, increasing
+    pub id: i32,
+    /// UTC, "YYYY-MM-DD HH:MM:SS"
+    pub created_at: String,
+    #[serde(flatten)]
+    pub event: DomainEvent,
+}
+
+/// Append an event to the log and apply it to the derived state, in the transaction of the caller
+pub(crate) fn publish(connection: &SqliteConnection, event: DomainEvent) -> Result<(), DbError> {
+    diesel::insert_into(events::table)
+        .values(&NewEvent {
+            kind: event.kind(),
+            payload: serde_json::to_string(&event).expect("Domain events serialize"),
+        })
+        .execute(connection)?;
+    apply(connection, &event)
+}
+
+/// Events after the event with id `since`, oldest first, at most `limit`
+pub fn get_events(since: Option<i32>, limit: i64) -> Result<Vec<EventRecord>, DbError> {
+    let connection = establish_connection()?;
+    events::table
+        .filter(events::id.gt(since.unwrap_or(0)))
+        .order(events::id)
+        .limit(limit)
+        .load::<StoredEvent>(&connection)?
+        .into_iter()
+        .map(|row| {
+            let event = serde_json::from_str(&row.payload).map_err(|err| {
+                DbError::Generic(format!("Invalid payload of event {}: {}", row.id, err))
+            })?;
+            Ok(EventRecord {
+                id: row.id,
+                created_at: row.created_at,
+                event,
+            })
+        })
+        .collect()
+}
+
+/// Id of the latest event, `None` if the log is empty
+pub fn get_last_event_id() -> Result<Option<i32>, DbError> {
+    let connection = establish_connection()?;
+    Ok(events::table
+        .select(diesel::dsl::max(events::id))
+        .first::<Option<i32>>(&connection)?)
+}
+
+pub fn clear_events() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(events::table).execute(&connection)?;
+    Ok(())
+}
+
+fn apply(connection: &SqliteConnection, event: &DomainEvent) -> Result<(), DbError> {
+    match event {
+        DomainEvent::ResultPosted { game, score } => {
+            apply_result(connection, *game, Some(*score))?;
+            let groups = crate::load_groups(connection)?;
+            for stage in completed_stages(&groups, *game) {
+                publish(connection, DomainEvent::StageCompleted { stage })?;
+            }
+            Ok(())
+        }
+        DomainEvent::ResultAmended { game, score, .. } => {
+            apply_result(connection, *game, Some(*score))
+        }
+        DomainEvent::GameStatusChanged {
+            game,
+            status: GameStatus::Played,
+            score,
+        } => apply_result(connection, *game, *score),
+        DomainEvent::GameStatusChanged { game, .. } => apply_result(connection, *game, None),
+        DomainEvent::GameRescheduled { .. }
+        | DomainEvent::PredictionSubmitted { .. }
+        | DomainEvent::PredictionsDeleted { .. }
+        | DomainEvent::PredictionsRestored { .. }
+        | DomainEvent::PlayerDeleted { .. }
+        | DomainEvent::PlayerRestored { .. }
+        | DomainEvent::PlayerRenamed { .. }
+        | DomainEvent::PlayerErased { .. }
+        | DomainEvent::StageCompleted { .. }
+        | DomainEvent::TeamWithdrawn { .. } => Ok(()),
+        DomainEvent::PlayoffWinnerSet { .. } => score::score_knockout(connection),
+    }
+}
+
+/// Update the derived state of a game, `None` if the game no longer counts
+fn apply_result(
+    connection: &SqliteConnection,
+    game: GameId,
+    score: Option<Score>,
+) -> Result<(), DbError> {
+    live::remove_live_score(connection, game)?;
+    match score {
+        Some(score) => score::score_game(connection, game, score)?,
+        None => score::unscore_game(connection, game)?,
+    }
+    standings::store_group_standings_of(connection, game)?;
+    standings_history::snapshot_group_of(connection, game)?;
+    last_result::record_result(connection, game)
+}
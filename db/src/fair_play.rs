@@ -0,0 +1,122 @@
+//! Card events and the fair play ranking
+//!
+//! The cards shown in a game are stored in the `card_events` table, see [`set_card_events`].
+//! The fair play ranking is derived from the cards of every played game, see
+//! [`get_fair_play_ranking`].
+use crate::establish_connection;
+use crate::models::{Game, NewCardEvent, StoredCardEvent};
+use crate::schema::{card_events, games};
+use crate::DbError;
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use wwc_core::discipline::GameCards;
+use wwc_core::fair_play::{fair_play_ranking, CardEvent, FairPlayStanding};
+use wwc_core::game::GameId;
+use wwc_core::team::TeamId;
+
+/// Replace the card events of a game
+///
+/// # Errors
+///
+/// Fails if the game does not exist or an event is for a team not playing the game.
+pub fn set_card_events(game_id: GameId, events: &[CardEvent]) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| replace_card_events(&connection, game_id, events))
+}
+
+/// Like [`set_card_events`], in the transaction of the caller
+pub(crate) fn replace_card_events(
+    connection: &SqliteConnection,
+    game_id: GameId,
+    events: &[CardEvent],
+) -> Result<(), DbError> {
+    let id = i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv");
+    let game = games::table
+        .find(id)
+        .first::<Game>(connection)
+        .optional()?
+        .ok_or_else(|| DbError::Generic(format!("No game with id {}", game_id)))?;
+    let teams = [game.home_team, game.away_team];
+    if let Some(event) = events.iter().find(|event| {
+        !teams.contains(&i32::try_from(u32::from(event.team)).expect("u32 -> i32 conv"))
+    }) {
+        return Err(DbError::Generic(format!(
+            "Team {} does not play game {}",
+            event.team, game_id
+        )));
+    }
+    let rows: Vec<NewCardEvent> = events
+        .iter()
+        .map(|event| NewCardEvent::new(game_id, event))
+        .collect();
+    diesel::delete(card_events::table.filter(card_events::game_id.eq(id))).execute(connection)?;
+    diesel::insert_into(card_events::table)
+        .values(&rows)
+        .execute(connection)?;
+    Ok(())
+}
+
+/// Cards of every played game, in chronological order
+///
+/// Games without card events are included, they count as games with a clean record.
+pub fn get_game_cards() -> Result<Vec<GameCards>, DbError> {
+    let connection = establish_connection()?;
+    let played = games::table
+        .filter(games::played.eq(true))
+        .order((games::kickoff, games::id))
+        .load::<Game>(&connection)?;
+    let mut events: HashMap<i32, Vec<CardEvent>> = HashMap::new();
+    for row in card_events::table
+        .order((card_events::minute, card_events::id))
+        .load::<StoredCardEvent>(&connection)?
+    {
+        let game_id = row.game_id;
+        events
+            .entry(game_id)
+            .or_default()
+            .push(CardEvent::try_from(row)?);
+    }
+    let team = |id: i32| TeamId(u32::try_from(id).expect("i32 -> u32 conv"));
+    Ok(played
+        .into_iter()
+        .map(|game| GameCards {
+            id: GameId::from(u32::try_from(game.id).expect("i32 -> u32 conv")),
+            home: team(game.home_team),
+            away: team(game.away_team),
+            events: events.remove(&game.id).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Fair play ranking of every team with a played game, see [`fair_play_ranking`]
+///
+/// Once the group stage is complete, only the teams in the knockout bracket are eligible for
+/// the award. Until then, or without a bracket, every team is.
+pub fn get_fair_play_ranking() -> Result<Vec<FairPlayStanding>, DbError> {
+    let eligible = knockout_teams()?;
+    Ok(fair_play_ranking(&get_game_cards()?, eligible.as_ref()))
+}
+
+/// Teams placed in the knockout bracket, `None` before the group stage is complete
+fn knockout_teams() -> Result<Option<HashSet<TeamId>>, DbError> {
+    let groups = crate::get_groups()?;
+    if groups
+        .values()
+        .any(|group| group.unplayed_games().next().is_some())
+    {
+        return Ok(None);
+    }
+    let layout = crate::playoff::get_bracket_layout()?;
+    let teams: HashSet<TeamId> = layout
+        .iter()
+        .flat_map(|node| node.home.team.into_iter().chain(node.away.team))
+        .collect();
+    Ok(Some(teams).filter(|teams| !teams.is_empty()))
+}
+
+pub fn clear_card_events() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(card_events::table).execute(&connection)?;
+    Ok(())
+}
@@ -0,0 +1,87 @@
+//! Suspicious prediction patterns
+//!
+//! Loads the prediction versions of every player and the deadline extensions from the event
+//! log, see [`GameRescheduled`](DomainEvent::GameRescheduled), for
+//! [`wwc_core::fairness::check_fairness`].
+use crate::establish_connection;
+use crate::models::{Game, StoredEvent};
+use crate::schema::{events, games};
+use crate::DbError;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use wwc_core::event::DomainEvent;
+use wwc_core::fairness::{self, DeadlineExtension, FairnessReport};
+use wwc_core::game::GameId;
+use wwc_core::player::PlayerId;
+use wwc_core::Date;
+
+/// Flag identical sheets and edits shortly before deadline extensions
+///
+/// Deleted players and predictions are left out.
+pub fn check_fairness() -> Result<FairnessReport, DbError> {
+    let versions = crate::get_players()?
+        .into_iter()
+        .map(|player| {
+            let id = PlayerId::from(player.id);
+            crate::get_pred_versions(id).map(|versions| (id, versions))
+        })
+        .collect::<Result<Vec<_>, DbError>>()?;
+    Ok(fairness::check_fairness(
+        &versions,
+        &get_kickoffs()?,
+        &get_extensions()?,
+    ))
+}
+
+fn get_kickoffs() -> Result<HashMap<GameId, Date>, DbError> {
+    let connection = establish_connection()?;
+    games::table
+        .filter(games::kickoff.is_not_null())
+        .load::<Game>(&connection)?
+        .into_iter()
+        .map(|game| {
+            let id = GameId::from(u32::try_from(game.id).expect("i32 -> u32 conv"));
+            game.kickoff_date().map(|kickoff| (id, kickoff))
+        })
+        .collect()
+}
+
+/// Every rescheduling in the event log, oldest first
+fn get_extensions() -> Result<Vec<DeadlineExtension>, DbError> {
+    let connection = establish_connection()?;
+    let rows = events::table
+        .filter(events::kind.eq("game_rescheduled"))
+        .order(events::id)
+        .load::<StoredEvent>(&connection)?;
+    let mut extensions = Vec::new();
+    for row in rows {
+        let event = serde_json::from_str(&row.payload).map_err(|err| {
+            DbError::Generic(format!("Invalid payload of event {}: {}", row.id, err))
+        })?;
+        // A game without a kickoff was open, its first kickoff extends no deadline
+        if let DomainEvent::GameRescheduled {
+            game,
+            old_kickoff: Some(old_kickoff),
+            new_kickoff,
+            extended_at,
+        } = event
+        {
+            // Events logged before the time of the rescheduling was recorded fall back to the
+            // time of the event.
+            let extended_at = match extended_at {
+                Some(extended_at) => extended_at,
+                None => row.created_at.parse().map_err(|err| {
+                    DbError::Generic(format!("Time of event {}: {}", row.id, err))
+                })?,
+            };
+            extensions.push(DeadlineExtension {
+                game,
+                old_kickoff,
+                new_kickoff,
+                extended_at,
+            });
+        }
+    }
+    Ok(extensions)
+}
@@ -0,0 +1,93 @@
+//! Filtered game queries
+//!
+//! Selects a page of games matching a [`GameQuery`], filtered in the db rather than loading the
+//! whole games table.
+use crate::establish_connection;
+use crate::models::Game;
+use crate::schema::{games, group_game_map};
+use crate::DbError;
+use diesel::prelude::*;
+use diesel::sql_types::{Nullable, Text};
+use diesel::sqlite::Sqlite;
+use std::convert::TryFrom;
+use wwc_core::group::GroupId;
+use wwc_core::team::TeamId;
+use wwc_core::Date;
+
+/// Largest allowed page size
+pub const MAX_PER_PAGE: u32 = 200;
+
+sql_function! {
+    /// SQLite `datetime`, normalizes kickoffs with different UTC offsets to comparable UTC strings
+    fn datetime(x: Nullable<Text>) -> Nullable<Text>;
+}
+
+/// Game filters, `None` matches any game
+#[derive(Debug, Clone, Default)]
+pub struct GameQuery {
+    pub group: Option<GroupId>,
+    /// Round of the tournament, as stored with the game, e.g. "group"
+    pub round: Option<String>,
+    /// Home or away team
+    pub team: Option<TeamId>,
+    pub played: Option<bool>,
+    /// Kicking off at or after
+    pub from: Option<Date>,
+    /// Kicking off at or before
+    pub to: Option<Date>,
+}
+
+/// Page of games, `page` starts at 0
+#[derive(Debug, Clone, Copy)]
+pub struct Page {
+    pub page: u32,
+    pub per_page: u32,
+}
+
+/// Games on a page, in kickoff order, and the total number of matching games
+pub fn get_games_page(query: &GameQuery, page: Page) -> Result<(Vec<Game>, i64), DbError> {
+    if page.per_page == 0 || page.per_page > MAX_PER_PAGE {
+        return Err(DbError::Generic(format!(
+            "Page size must be between 1 and {}",
+            MAX_PER_PAGE
+        )));
+    }
+    let connection = establish_connection()?;
+    let total = filtered(query).count().get_result::<i64>(&connection)?;
+    let games = filtered(query)
+        .order((datetime(games::kickoff), games::id))
+        .limit(i64::from(page.per_page))
+        .offset(i64::from(page.page) * i64::from(page.per_page))
+        .load::<Game>(&connection)?;
+    Ok((games, total))
+}
+
+fn filtered(query: &GameQuery) -> games::BoxedQuery<'_, Sqlite> {
+    let mut selection = games::table.into_boxed();
+    if let Some(group) = query.group {
+        selection = selection.filter(
+            games::id.eq_any(
+                group_game_map::table
+                    .filter(group_game_map::group_id_.eq(String::from(char::from(group))))
+                    .select(group_game_map::id),
+            ),
+        );
+    }
+    if let Some(round) = &query.round {
+        selection = selection.filter(games::type_.eq(round));
+    }
+    if let Some(team) = query.team {
+        let team = i32::try_from(team.0).expect("u32 -> i32 conv");
+        selection = selection.filter(games::home_team.eq(team).or(games::away_team.eq(team)));
+    }
+    if let Some(played) = query.played {
+        selection = selection.filter(games::played.eq(played));
+    }
+    if let Some(from) = query.from {
+        selection = selection.filter(datetime(games::kickoff).ge(datetime(Some(from.to_string()))));
+    }
+    if let Some(to) = query.to {
+        selection = selection.filter(datetime(games::kickoff).le(datetime(Some(to.to_string()))));
+    }
+    selection
+}
@@ -0,0 +1,80 @@
+//! Hall of fame
+//!
+//! The db holds the games of one tournament at a time, so the final points of every player are
+//! archived per tournament with [`archive_tournament`] before moving on to the next tournament.
+//! The archived points are aggregated across tournaments by [`wwc_core::hall_of_fame`].
+//!
+//! Players are identified across tournaments by their id, so keep the players when replacing
+//! the games of a tournament.
+use crate::establish_connection;
+use crate::models::TournamentPoints;
+use crate::schema::tournament_results;
+use crate::DbError;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use wwc_core::game::{GameId, Score};
+use wwc_core::hall_of_fame::{TournamentId, TournamentResult};
+use wwc_core::joker::Jokers;
+use wwc_core::leaderboard::points;
+use wwc_core::player::PlayerId;
+use wwc_core::pred_score::{PredScore, SimplePredScoreFn};
+
+pub fn get_tournament_results() -> Result<Vec<TournamentResult>, DbError> {
+    let connection = establish_connection()?;
+    let rows = tournament_results::table.load::<TournamentPoints>(&connection)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| TournamentResult {
+            tournament: TournamentId::from(row.tournament_id),
+            player: PlayerId::from(row.player_id),
+            points: PredScore::from(row.points),
+        })
+        .collect())
+}
+
+/// Store the current points of every player as the result of a tournament
+///
+/// The points are scored like the main leaderboard, with [`SimplePredScoreFn::default`] and the
+/// joker multipliers. Players without predictions did not take part and get no result.
+/// Replaces any previous results of the tournament.
+/// Returns the number of players.
+pub fn archive_tournament(id: i32) -> Result<usize, DbError> {
+    crate::tournament::get_tournament(id)?;
+    let results: HashMap<GameId, Score> = crate::get_groups()?
+        .values()
+        .flat_map(|group| group.played_games())
+        .map(|game| (game.id, game.score))
+        .collect();
+    let jokers = crate::joker::get_all_jokers()?;
+    let no_jokers = Jokers::new();
+    let score_fn = SimplePredScoreFn::default();
+    let mut rows = Vec::new();
+    for player in crate::get_players()? {
+        let player_id = PlayerId::from(player.id);
+        let preds = crate::get_preds(player_id)?;
+        if preds.is_empty() {
+            continue;
+        }
+        let jokers = jokers.get(&player_id).unwrap_or(&no_jokers);
+        rows.push(TournamentPoints {
+            tournament_id: id,
+            player_id: player.id,
+            points: f32::from(points(&preds, jokers, &results, &score_fn)),
+        });
+    }
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        diesel::delete(tournament_results::table.filter(tournament_results::tournament_id.eq(id)))
+            .execute(&connection)?;
+        diesel::insert_into(tournament_results::table)
+            .values(&rows)
+            .execute(&connection)?;
+        Ok(rows.len())
+    })
+}
+
+pub fn clear_tournament_results() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(tournament_results::table).execute(&connection)?;
+    Ok(())
+}
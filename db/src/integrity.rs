@@ -0,0 +1,103 @@
+//! Data integrity checks
+//!
+//! SQLite does not enforce the foreign keys of the schema unless asked to, so rows can refer to
+//! rows which were never inserted or have been cleared, e.g. after clearing the teams with the
+//! CLI but not the games.
+//! The checks find such rows, along with played games without a result, for the admin to fix
+//! before they surface as panics or wrong standings.
+use crate::establish_connection;
+use crate::models::{Game, GroupGameMap, Pred};
+use crate::schema::{games, group_game_map, preds, teams};
+use crate::DbError;
+use diesel::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// A row failing an integrity check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum IntegrityIssue {
+    /// The home or away team of a game is not in the teams table
+    GameMissingTeam { game_id: i32, team_id: i32 },
+    /// A group game mapping refers to a game which is not in the games table
+    GroupMapMissingGame { group_id: String, game_id: i32 },
+    /// A game marked as played lacks the home or away result
+    PlayedWithoutResult { game_id: i32 },
+    /// A prediction refers to a game which is not in the games table
+    PredMissingGame {
+        pred_id: i32,
+        player_id: i32,
+        game_id: i32,
+    },
+}
+
+/// Result of [`check_integrity`]
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    /// True if no check failed
+    pub ok: bool,
+    /// Number of checked games
+    pub num_games: usize,
+    /// Number of checked group game mappings
+    pub num_group_maps: usize,
+    /// Number of checked predictions
+    pub num_preds: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+/// Run every integrity check
+///
+/// Soft-deleted predictions are checked as well, they can be restored.
+pub fn check_integrity() -> Result<IntegrityReport, DbError> {
+    let connection = establish_connection()?;
+    let team_ids: HashSet<i32> = teams::table
+        .select(teams::id)
+        .load::<i32>(&connection)?
+        .into_iter()
+        .collect();
+    let games = games::table.load::<Game>(&connection)?;
+    let game_ids: HashSet<i32> = games.iter().map(|game| game.id).collect();
+    let group_maps = group_game_map::table.load::<GroupGameMap>(&connection)?;
+    let preds = preds::table.load::<Pred>(&connection)?;
+
+    let missing_teams = games.iter().flat_map(|game| {
+        vec![game.home_team, game.away_team]
+            .into_iter()
+            .filter(|team_id| !team_ids.contains(team_id))
+            .map(move |team_id| IntegrityIssue::GameMissingTeam {
+                game_id: game.id,
+                team_id,
+            })
+    });
+    let missing_results = games
+        .iter()
+        .filter(|game| game.played && (game.home_result.is_none() || game.away_result.is_none()))
+        .map(|game| IntegrityIssue::PlayedWithoutResult { game_id: game.id });
+    let missing_map_games = group_maps
+        .iter()
+        .filter(|map| !game_ids.contains(&map.id))
+        .map(|map| IntegrityIssue::GroupMapMissingGame {
+            group_id: map.group_id_.clone(),
+            game_id: map.id,
+        });
+    let missing_pred_games = preds
+        .iter()
+        .filter(|pred| !game_ids.contains(&pred.game_id))
+        .map(|pred| IntegrityIssue::PredMissingGame {
+            pred_id: pred.id,
+            player_id: pred.player_id,
+            game_id: pred.game_id,
+        });
+    let issues: Vec<IntegrityIssue> = missing_teams
+        .chain(missing_results)
+        .chain(missing_map_games)
+        .chain(missing_pred_games)
+        .collect();
+    Ok(IntegrityReport {
+        ok: issues.is_empty(),
+        num_games: games.len(),
+        num_group_maps: group_maps.len(),
+        num_preds: preds.len(),
+        issues,
+    })
+}
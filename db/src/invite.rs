@@ -0,0 +1,104 @@
+//! Invites
+//!
+//! Single-use tokens for registering to a league, see [`wwc_core::invite`].
+use crate::establish_connection;
+use crate::models::{LeagueInvite, NewLeagueMember, NewPlayer, Player};
+use crate::schema::{invites, league_members, players};
+use crate::DbError;
+use diesel::prelude::*;
+use std::convert::TryFrom;
+use wwc_core::invite::{Invite, InviteError, InviteToken};
+use wwc_core::player::PlayerId;
+use wwc_core::Date;
+
+/// Create an invite to a league, with a new random token
+pub fn create_invite(league_id: i32, expires_at: Date) -> Result<Invite, DbError> {
+    crate::league::get_league(league_id)?;
+    let connection = establish_connection()?;
+    let invite = LeagueInvite {
+        token: String::from(InviteToken::random()),
+        league_id,
+        expires_at: expires_at.to_string(),
+        used_by: None,
+    };
+    diesel::insert_into(invites::table)
+        .values(&invite)
+        .execute(&connection)?;
+    Invite::try_from(invite)
+}
+
+/// Invites to a league, used and expired ones included
+pub fn get_invites(league_id: i32) -> Result<Vec<Invite>, DbError> {
+    let connection = establish_connection()?;
+    invites::table
+        .filter(invites::league_id.eq(league_id))
+        .load::<LeagueInvite>(&connection)?
+        .into_iter()
+        .map(Invite::try_from)
+        .collect()
+}
+
+/// Register a new player with an invite
+///
+/// The player joins the league of the invite, and the invite is used up.
+/// Returns the id of the new player.
+///
+/// # Errors
+///
+/// The invite is unknown, expired or already used, see [`Invite::check`],
+/// or the name is taken, see [`register_player`](crate::register_player).
+/// Nothing is changed on error.
+pub fn redeem_invite(token: &InviteToken, name: &str, now: Date) -> Result<PlayerId, DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let invite = invites::table
+            .find(String::from(token.clone()))
+            .first::<LeagueInvite>(&connection)
+            .optional()?
+            .ok_or(InviteError::Unknown)?;
+        let invite = Invite::try_from(invite)?;
+        invite.check(now)?;
+        let taken = players::table
+            .filter(players::name.eq(name))
+            .count()
+            .get_result::<i64>(&connection)?
+            > 0;
+        if taken {
+            return Err(DbError::Generic(format!(
+                "Player with name: '{}' already in db",
+                name
+            )));
+        }
+        diesel::insert_into(players::table)
+            .values(&NewPlayer { name })
+            .execute(&connection)?;
+        let player = players::table
+            .filter(players::name.eq(name))
+            .first::<Player>(&connection)?;
+        diesel::insert_into(league_members::table)
+            .values(&NewLeagueMember {
+                league_id: invite.league,
+                player_id: player.id,
+                joined_at: now.to_string(),
+            })
+            .execute(&connection)?;
+        // Only an unused invite, a concurrent redeem may have used it since it was checked
+        let num_updated = diesel::update(
+            invites::table
+                .find(String::from(token.clone()))
+                .filter(invites::used_by.is_null()),
+        )
+        .set(invites::used_by.eq(player.id))
+        .execute(&connection)?;
+        if num_updated == 0 {
+            return Err(DbError::from(InviteError::Used));
+        }
+        Ok(PlayerId::from(player.id))
+    })
+}
+
+pub fn clear_invites() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(invites::table).execute(&connection)?;
+    Ok(())
+}
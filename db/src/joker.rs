@@ -0,0 +1,81 @@
+//! Joker games
+//!
+//! Stored as plain rows, validation against the kickoff and the matchday is done by the caller,
+//! see [`wwc_core::joker`].
+//! Rescheduling a game drops the jokers that would clash with it, see
+//! [`reschedule_game`](crate::reschedule_game).
+use crate::establish_connection;
+use crate::models::{Joker, NewJoker};
+use crate::schema::jokers;
+use crate::DbError;
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use std::convert::TryFrom;
+use wwc_core::game::GameId;
+use wwc_core::joker::{Jokers, PlayerJokers};
+use wwc_core::player::PlayerId;
+
+pub fn get_jokers(player_id: PlayerId) -> Result<Jokers, DbError> {
+    let connection = establish_connection()?;
+    let rows = jokers::table
+        .filter(jokers::player_id.eq(i32::from(player_id)))
+        .load::<Joker>(&connection)?;
+    Ok(rows.iter().map(game_id).collect())
+}
+
+/// Jokers of every player, players without jokers are left out
+pub fn get_all_jokers() -> Result<PlayerJokers, DbError> {
+    load_all_jokers(&establish_connection()?)
+}
+
+/// Like [`get_all_jokers`], in the transaction of the caller
+pub(crate) fn load_all_jokers(connection: &SqliteConnection) -> Result<PlayerJokers, DbError> {
+    let rows = jokers::table.load::<Joker>(connection)?;
+    let mut player_jokers = PlayerJokers::new();
+    for row in rows.iter() {
+        player_jokers
+            .entry(PlayerId::from(row.player_id))
+            .or_default()
+            .insert(game_id(row));
+    }
+    Ok(player_jokers)
+}
+
+/// Mark a game as a joker, no-op if it already is
+pub fn set_joker(player_id: PlayerId, game_id: GameId) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::replace_into(jokers::table)
+        .values(&NewJoker {
+            player_id: i32::from(player_id),
+            game_id: db_game_id(game_id),
+        })
+        .execute(&connection)?;
+    Ok(())
+}
+
+pub fn clear_joker(player_id: PlayerId, game_id: GameId) -> Result<(), DbError> {
+    clear_joker_in(&establish_connection()?, player_id, game_id)
+}
+
+/// Like [`clear_joker`], in the transaction of the caller
+pub(crate) fn clear_joker_in(
+    connection: &SqliteConnection,
+    player_id: PlayerId,
+    game_id: GameId,
+) -> Result<(), DbError> {
+    diesel::delete(
+        jokers::table
+            .filter(jokers::player_id.eq(i32::from(player_id)))
+            .filter(jokers::game_id.eq(db_game_id(game_id))),
+    )
+    .execute(connection)?;
+    Ok(())
+}
+
+fn game_id(row: &Joker) -> GameId {
+    GameId::from(u32::try_from(row.game_id).expect("i32 -> u32 conv"))
+}
+
+fn db_game_id(game_id: GameId) -> i32 {
+    i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv")
+}
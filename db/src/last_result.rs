@@ -0,0 +1,72 @@
+//! Time of the latest result per group
+//!
+//! Stored in the `last_results` table whenever a result of a group is posted or amended, or the
+//! status of a game changes, see [`event`](crate::event), and for every group when the rules of
+//! the active tournament change, see [`set_rules`](crate::tournament::set_rules).
+//! The standings of a group only change with its results and the rules, the time is served as the
+//! `Last-Modified` of the standings.
+use crate::establish_connection;
+use crate::models::{GroupGameMap, LastResult, NewLastResult, StoredGroupId};
+use crate::schema::{group_game_map, last_results};
+use crate::DbError;
+use diesel::prelude::*;
+use std::convert::TryFrom;
+use wwc_core::game::GameId;
+use wwc_core::group::GroupId;
+use wwc_core::Date;
+
+/// Set the latest result of the group of a game to now, no-op for games outside the group stage
+///
+/// Runs in the transaction of the caller, see [`event`](crate::event).
+pub(crate) fn record_result(connection: &SqliteConnection, game_id: GameId) -> Result<(), DbError> {
+    let map = group_game_map::table
+        .find(i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv"))
+        .first::<GroupGameMap>(connection)
+        .optional()?;
+    if let Some(map) = map {
+        // Replacing the row sets the time to the default, the current time.
+        diesel::replace_into(last_results::table)
+            .values(&NewLastResult {
+                group_id: String::from(StoredGroupId(map.group_id()?)),
+            })
+            .execute(connection)?;
+    }
+    Ok(())
+}
+
+/// Set the latest result of every group to now
+///
+/// Runs in the transaction of the caller, see [`set_rules`](crate::tournament::set_rules).
+pub(crate) fn record_all_groups(connection: &SqliteConnection) -> Result<(), DbError> {
+    let groups: Vec<String> = group_game_map::table
+        .select(group_game_map::group_id_)
+        .distinct()
+        .load(connection)?;
+    for group_id in groups {
+        diesel::replace_into(last_results::table)
+            .values(&NewLastResult { group_id })
+            .execute(connection)?;
+    }
+    Ok(())
+}
+
+/// Time of the latest result of a group, `None` if the group has no result yet
+pub fn get_last_result(group_id: GroupId) -> Result<Option<Date>, DbError> {
+    let connection = establish_connection()?;
+    last_results::table
+        .find(String::from(StoredGroupId(group_id)))
+        .first::<LastResult>(&connection)
+        .optional()?
+        .map(|row| {
+            row.posted_at.parse().map_err(|err| {
+                DbError::Generic(format!("Last result of group {}: {}", group_id, err))
+            })
+        })
+        .transpose()
+}
+
+pub fn clear_last_results() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(last_results::table).execute(&connection)?;
+    Ok(())
+}
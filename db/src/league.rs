@@ -0,0 +1,148 @@
+//! Leagues
+//!
+//! A league is a subset of the players, competing with its own
+//! [`ScoringRules`](wwc_core::pred_score::ScoringRules).
+//! The rules are stored as JSON, so that they can be extended without a migration.
+//!
+//! Members may go by another name and emoji in every league, see
+//! [`LeagueAlias`](wwc_core::player::LeagueAlias).
+//!
+//! The time a player joined a league decides which games the player missed, see
+//! [`late_joiner_points`](wwc_core::leaderboard::late_joiner_points).
+use crate::establish_connection;
+use crate::models::{League, LeagueMember, NewLeague, NewLeagueMember};
+use crate::schema::{league_members, leagues};
+use crate::DbError;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use wwc_core::player::{LeagueAlias, PlayerId};
+use wwc_core::pred_score::ScoringRules;
+use wwc_core::Date;
+
+pub fn get_league(id: i32) -> Result<League, DbError> {
+    let connection = establish_connection()?;
+    leagues::table
+        .find(id)
+        .first(&connection)
+        .optional()?
+        .ok_or(DbError::UnknownLeague(id))
+}
+
+pub fn get_leagues() -> Result<Vec<League>, DbError> {
+    let connection = establish_connection()?;
+    Ok(leagues::table.load(&connection)?)
+}
+
+pub fn register_league(name: &str, rules: &ScoringRules) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::insert_into(leagues::table)
+        .values(&NewLeague {
+            name,
+            scoring_rules: serde_json::to_string(rules)?,
+        })
+        .execute(&connection)?;
+    Ok(())
+}
+
+pub fn get_scoring_rules(id: i32) -> Result<ScoringRules, DbError> {
+    Ok(serde_json::from_str(&get_league(id)?.scoring_rules)?)
+}
+
+pub fn set_scoring_rules(id: i32, rules: &ScoringRules) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    let num_updated = diesel::update(leagues::table.find(id))
+        .set(leagues::scoring_rules.eq(serde_json::to_string(rules)?))
+        .execute(&connection)?;
+    if num_updated == 0 {
+        return Err(DbError::UnknownLeague(id));
+    }
+    Ok(())
+}
+
+/// Add a player to a league, joining at `joined_at`
+///
+/// A member keeps the original join time.
+pub fn add_member(id: i32, player_id: PlayerId, joined_at: Date) -> Result<(), DbError> {
+    get_league(id)?;
+    let connection = establish_connection()?;
+    diesel::insert_or_ignore_into(league_members::table)
+        .values(&NewLeagueMember {
+            league_id: id,
+            player_id: i32::from(player_id),
+            joined_at: joined_at.to_string(),
+        })
+        .execute(&connection)?;
+    Ok(())
+}
+
+pub fn get_members(id: i32) -> Result<Vec<PlayerId>, DbError> {
+    let connection = establish_connection()?;
+    let members = league_members::table
+        .filter(league_members::league_id.eq(id))
+        .load::<LeagueMember>(&connection)?;
+    Ok(members
+        .into_iter()
+        .map(|member| PlayerId::from(member.player_id))
+        .collect())
+}
+
+/// Time every member joined a league
+pub fn get_join_times(id: i32) -> Result<HashMap<PlayerId, Date>, DbError> {
+    let connection = establish_connection()?;
+    league_members::table
+        .filter(league_members::league_id.eq(id))
+        .load::<LeagueMember>(&connection)?
+        .into_iter()
+        .map(|member| {
+            let joined_at = member.joined_at.parse().map_err(|err| {
+                DbError::Generic(format!(
+                    "Join time of player {} in league {}: {}",
+                    member.player_id, id, err
+                ))
+            })?;
+            Ok((PlayerId::from(member.player_id), joined_at))
+        })
+        .collect()
+}
+
+/// Set the name and emoji of a member in a league, unset fields fall back to the account
+pub fn set_member_alias(id: i32, player_id: PlayerId, alias: &LeagueAlias) -> Result<(), DbError> {
+    get_league(id)?;
+    let connection = establish_connection()?;
+    let num_updated = diesel::update(
+        league_members::table
+            .filter(league_members::league_id.eq(id))
+            .filter(league_members::player_id.eq(i32::from(player_id))),
+    )
+    .set((
+        league_members::display_name.eq(&alias.display_name),
+        league_members::emoji.eq(&alias.emoji),
+    ))
+    .execute(&connection)?;
+    if num_updated == 0 {
+        return Err(DbError::Generic(format!(
+            "Player {} is not a member of league {}",
+            player_id, id
+        )));
+    }
+    Ok(())
+}
+
+/// Name and emoji of every member of a league
+pub fn get_member_aliases(id: i32) -> Result<HashMap<PlayerId, LeagueAlias>, DbError> {
+    let connection = establish_connection()?;
+    Ok(league_members::table
+        .filter(league_members::league_id.eq(id))
+        .load::<LeagueMember>(&connection)?
+        .into_iter()
+        .map(|member| {
+            (
+                PlayerId::from(member.player_id),
+                LeagueAlias {
+                    display_name: member.display_name,
+                    emoji: member.emoji,
+                },
+            )
+        })
+        .collect())
+}
@@ -1,12 +1,44 @@
 #[macro_use]
 extern crate diesel;
 
+pub mod achievement;
+pub mod api_key;
+pub mod backup;
+pub mod digest;
+pub mod event;
+pub mod fair_play;
+pub mod fairness;
+pub mod game_query;
+pub mod hall_of_fame;
+pub mod integrity;
+pub mod invite;
+pub mod joker;
+pub mod last_result;
+pub mod league;
+pub mod live;
 pub mod models;
+pub mod odds;
+pub mod playoff;
+pub mod replay;
+pub mod result_import;
 pub mod schema;
+pub mod score;
+pub mod seed;
+pub mod standings;
+pub mod standings_history;
+pub mod strategy;
+pub mod team_alias;
+pub mod team_name;
+pub mod tournament;
+pub mod venue;
+pub mod version;
+pub mod withdrawal;
 
 use crate::models::*;
 use crate::schema::games::dsl::*;
+use crate::schema::goal_events::dsl::goal_events;
 use crate::schema::group_game_map::dsl::*;
+use crate::schema::players::dsl::deleted_at as player_deleted_at;
 use crate::schema::players::dsl::name as player_name;
 use crate::schema::players::dsl::players;
 use crate::schema::preds::dsl::*;
@@ -16,16 +48,28 @@ use diesel::result::ConnectionError;
 use diesel::result::Error as QueryError;
 use dotenv::dotenv;
 use itertools::{Either, Itertools};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::env;
 use thiserror::Error;
-use wwc_core::error::WwcError;
-use wwc_core::game::GameId;
+use wwc_core::error::{ErrorCode, WwcError};
+use wwc_core::event::DomainEvent;
+use wwc_core::fair_play::FairPlayScore;
+use wwc_core::game::{GameId, GameStatus, GoalCount, Score};
 use wwc_core::group::{
-    game::{PlayedGroupGame, UnplayedGroupGame},
-    GroupId,
+    game::{PlayedGroupGame, UnplayedGroupGame, VoidGroupGame},
+    Group, GroupId, Groups,
 };
-use wwc_core::player::{PlayerId, PlayerPredictions, Prediction};
+use wwc_core::invite::InviteError;
+use wwc_core::joker::clashing_jokers;
+use wwc_core::odds::OddsError;
+use wwc_core::player::{
+    anonymous_name, effective_prediction, PlayerId, PlayerPredictions, Prediction,
+    PredictionVersion, Profile,
+};
+use wwc_core::playoff::bracket::BracketError;
+use wwc_core::team::TeamId;
+use wwc_core::Date;
 
 fn establish_connection() -> Result<SqliteConnection, DbError> {
     dotenv().ok();
@@ -33,6 +77,26 @@ fn establish_connection() -> Result<SqliteConnection, DbError> {
     Ok(SqliteConnection::establish(&database_url)?)
 }
 
+/// Whether the database can only be read
+///
+/// E.g. a snapshot opened with `DATABASE_URL=file:archive.db?mode=ro`, or a file without write
+/// permission. Probed by rewriting the unchanged `user_version` of the database.
+pub fn is_read_only() -> Result<bool, DbError> {
+    #[derive(QueryableByName)]
+    struct UserVersion {
+        #[sql_type = "diesel::sql_types::Integer"]
+        user_version: i32,
+    }
+    let connection = establish_connection()?;
+    let UserVersion { user_version } =
+        diesel::sql_query("PRAGMA user_version").get_result(&connection)?;
+    match connection.execute(&format!("PRAGMA user_version = {}", user_version)) {
+        Ok(_) => Ok(false),
+        Err(QueryError::DatabaseError(_, info)) if info.message().contains("readonly") => Ok(true),
+        Err(err) => Err(err.into()),
+    }
+}
+
 pub fn register_player(name_: &str) -> Result<(), DbError> {
     let connection = establish_connection()?;
     let db_players = players
@@ -52,18 +116,323 @@ pub fn register_player(name_: &str) -> Result<(), DbError> {
     }
 }
 
+/// Get effective predictions for a player
+///
+/// A player may have several versions of a prediction for the same game.
+/// Only the latest version submitted before kickoff is returned, see [`effective_prediction`].
+/// For games without a kickoff date, the latest version is used.
 pub fn get_preds(player_id_: PlayerId) -> Result<Vec<Prediction>, DbError> {
-    let connection = establish_connection()?;
+    load_preds(&establish_connection()?, player_id_)
+}
+
+/// Like [`get_preds`], in the transaction of the caller
+pub(crate) fn load_preds(
+    connection: &SqliteConnection,
+    player_id_: PlayerId,
+) -> Result<Vec<Prediction>, DbError> {
+    let versions = load_pred_versions(connection, player_id_)?;
+    let game_ids: Vec<i32> = versions
+        .iter()
+        .map(|version| i32::try_from(u32::from(version.pred.0)).expect("u32 -> i32 conv"))
+        .unique()
+        .collect();
+    let deadlines = games
+        .filter(crate::schema::games::id.eq_any(game_ids))
+        .load::<Game>(connection)?
+        .into_iter()
+        .filter(|game| game.kickoff.is_some())
+        .map(|game| {
+            let game_id_ = GameId::from(u32::try_from(game.id).unwrap());
+            game.kickoff_date().map(|date| (game_id_, date))
+        })
+        .collect::<Result<HashMap<GameId, Date>, DbError>>()?;
+    Ok(versions
+        .into_iter()
+        .map(|version| (version.pred.0, version))
+        .into_group_map()
+        .into_iter()
+        .filter_map(|(game_id_, versions)| match deadlines.get(&game_id_) {
+            Some(deadline) => effective_prediction(versions.iter(), *deadline),
+            None => versions
+                .iter()
+                .max_by_key(|version| version.submitted)
+                .map(|version| version.pred),
+        })
+        .collect())
+}
+
+/// Get all prediction versions for a player, except soft-deleted ones
+pub fn get_pred_versions(player_id_: PlayerId) -> Result<Vec<PredictionVersion>, DbError> {
+    load_pred_versions(&establish_connection()?, player_id_)
+}
+
+fn load_pred_versions(
+    connection: &SqliteConnection,
+    player_id_: PlayerId,
+) -> Result<Vec<PredictionVersion>, DbError> {
     let player_id_ = i32::from(player_id_);
-    let db_preds = preds
+    preds
         .filter(player_id.eq(player_id_))
-        .load::<Pred>(&connection)?;
-    Ok(db_preds.into_iter().map(Prediction::from).collect())
+        .filter(deleted_at.is_null())
+        .load::<Pred>(connection)?
+        .into_iter()
+        .map(PredictionVersion::try_from)
+        .collect()
+}
+
+/// Get all prediction versions for a game, from all players
+///
+/// Soft-deleted predictions and the predictions of soft-deleted players are left out.
+pub fn get_game_pred_versions(
+    game_id_: GameId,
+) -> Result<Vec<(PlayerId, PredictionVersion)>, DbError> {
+    let connection = establish_connection()?;
+    let active_players = players
+        .select(crate::schema::players::id)
+        .filter(player_deleted_at.is_null());
+    preds
+        .filter(game_id.eq(i32::try_from(u32::from(game_id_)).expect("u32 -> i32 conv")))
+        .filter(deleted_at.is_null())
+        .filter(player_id.eq_any(active_players))
+        .load::<Pred>(&connection)?
+        .into_iter()
+        .map(|pred| {
+            let player = PlayerId::from(pred.player_id);
+            PredictionVersion::try_from(pred).map(|version| (player, version))
+        })
+        .collect()
+}
+
+/// Get the effective predictions of all players for a game, see [`get_preds`]
+pub fn get_game_preds(game_id_: GameId) -> Result<Vec<(PlayerId, Score)>, DbError> {
+    let connection = establish_connection()?;
+    let game = games
+        .find(i32::try_from(u32::from(game_id_)).expect("u32 -> i32 conv"))
+        .first::<Game>(&connection)
+        .optional()?
+        .ok_or_else(|| DbError::Generic(format!("No game with id {}", game_id_)))?;
+    let deadline = game.scheduled_kickoff()?;
+    Ok(get_game_pred_versions(game_id_)?
+        .into_iter()
+        .into_group_map()
+        .into_iter()
+        .filter_map(|(player, versions)| {
+            match deadline {
+                Some(deadline) => effective_prediction(versions.iter(), deadline),
+                None => versions
+                    .iter()
+                    .max_by_key(|version| version.submitted)
+                    .map(|version| version.pred),
+            }
+            .map(|Prediction(_, score)| (player, score))
+        })
+        .collect())
+}
+
+pub fn get_profile(player_id_: PlayerId) -> Result<Profile, DbError> {
+    let connection = establish_connection()?;
+    let player = players
+        .find(i32::from(player_id_))
+        .filter(player_deleted_at.is_null())
+        .first::<Player>(&connection)
+        .optional()?
+        .ok_or_else(|| DbError::Generic(format!("No player with id {}", player_id_)))?;
+    Ok(Profile {
+        timezone: player.timezone,
+        avatar: player.avatar,
+    })
+}
+
+pub fn set_profile(player_id_: PlayerId, profile: &Profile) -> Result<(), DbError> {
+    use crate::schema::players::columns;
+    let connection = establish_connection()?;
+    let num_updated = diesel::update(
+        players
+            .find(i32::from(player_id_))
+            .filter(player_deleted_at.is_null()),
+    )
+    .set((
+        columns::timezone.eq(profile.timezone.as_deref()),
+        columns::avatar.eq(profile.avatar.as_deref()),
+    ))
+    .execute(&connection)?;
+    if num_updated == 0 {
+        return Err(DbError::Generic(format!(
+            "No player with id {}",
+            player_id_
+        )));
+    }
+    Ok(())
+}
+
+/// Change the display name of a player
+///
+/// Publishes a [`PlayerRenamed`](DomainEvent::PlayerRenamed) event, without the name.
+///
+/// # Errors
+///
+/// Names are unique, see [`register_player`].
+pub fn rename_player(player_id_: PlayerId, name_: &str) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let taken = players
+            .filter(player_name.eq(name_))
+            .load::<Player>(&connection)?
+            .iter()
+            .any(|player| player.id != i32::from(player_id_));
+        if taken {
+            return Err(DbError::Generic(format!(
+                "Player with name: '{}' already in db",
+                name_
+            )));
+        }
+        let num_updated = diesel::update(
+            players
+                .find(i32::from(player_id_))
+                .filter(player_deleted_at.is_null()),
+        )
+        .set(player_name.eq(name_))
+        .execute(&connection)?;
+        if num_updated == 0 {
+            return Err(DbError::Generic(format!(
+                "No player with id {}",
+                player_id_
+            )));
+        }
+        event::publish(
+            &connection,
+            DomainEvent::PlayerRenamed { player: player_id_ },
+        )
+    })
+}
+
+/// Soft-delete the account of a player
+///
+/// The player is hidden rather than removed: left out of [`get_players`], the profiles and the
+/// leaderboards, while the name, predictions, jokers and league memberships are kept, so that an
+/// accidental deletion can be undone with [`restore_player`].
+/// The name stays taken, see [`register_player`].
+/// To remove the personal data of a player for good, see [`erase_player`].
+pub fn delete_player(player_id_: PlayerId, now: Date) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let num_updated = diesel::update(
+            players
+                .find(i32::from(player_id_))
+                .filter(player_deleted_at.is_null()),
+        )
+        .set(player_deleted_at.eq(Some(now.to_string())))
+        .execute(&connection)?;
+        if num_updated == 0 {
+            return Err(DbError::Generic(format!(
+                "No player with id {}",
+                player_id_
+            )));
+        }
+        event::publish(
+            &connection,
+            DomainEvent::PlayerDeleted { player: player_id_ },
+        )
+    })
 }
 
+/// Undo [`delete_player`]
+///
+/// The materialized scores are recomputed, see [`score::refresh_scores`].
+pub fn restore_player(player_id_: PlayerId) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let num_updated = diesel::update(
+            players
+                .find(i32::from(player_id_))
+                .filter(player_deleted_at.is_not_null()),
+        )
+        .set(player_deleted_at.eq(None::<String>))
+        .execute(&connection)?;
+        if num_updated == 0 {
+            return Err(DbError::Generic(format!(
+                "No deleted player with id {}",
+                player_id_
+            )));
+        }
+        score::refresh_scores_in(&connection)?;
+        event::publish(
+            &connection,
+            DomainEvent::PlayerRestored { player: player_id_ },
+        )
+    })
+}
+
+/// Erase the personal data of a player, e.g. on a GDPR request
+///
+/// The player is anonymized rather than hidden: the name is replaced by [`anonymous_name`] and
+/// the profile and the league aliases are cleared, while the predictions, jokers and league
+/// memberships are kept, so that the leaderboards do not change.
+/// Unlike [`delete_player`], this cannot be undone.
+/// Publishes a [`PlayerErased`](DomainEvent::PlayerErased) event.
+pub fn erase_player(player_id_: PlayerId) -> Result<(), DbError> {
+    use crate::schema::league_members;
+    use crate::schema::players::columns;
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let num_updated = diesel::update(players.find(i32::from(player_id_)))
+            .set((
+                player_name.eq(anonymous_name(player_id_)),
+                columns::timezone.eq(None::<String>),
+                columns::avatar.eq(None::<String>),
+            ))
+            .execute(&connection)?;
+        if num_updated == 0 {
+            return Err(DbError::Generic(format!(
+                "No player with id {}",
+                player_id_
+            )));
+        }
+        diesel::update(
+            league_members::table.filter(league_members::player_id.eq(i32::from(player_id_))),
+        )
+        .set((
+            league_members::display_name.eq(None::<String>),
+            league_members::emoji.eq(None::<String>),
+        ))
+        .execute(&connection)?;
+        event::publish(
+            &connection,
+            DomainEvent::PlayerErased { player: player_id_ },
+        )
+    })
+}
+
+/// Soft-deleted players, see [`delete_player`]
+pub fn get_deleted_players() -> Result<Vec<Player>, DbError> {
+    let connection = establish_connection()?;
+    Ok(players
+        .filter(player_deleted_at.is_not_null())
+        .load::<Player>(&connection)?)
+}
+
+/// Get all players, except soft-deleted ones
 pub fn get_players() -> Result<Vec<Player>, DbError> {
+    load_players(&establish_connection()?)
+}
+
+/// Like [`get_players`], in the transaction of the caller
+pub(crate) fn load_players(connection: &SqliteConnection) -> Result<Vec<Player>, DbError> {
+    Ok(players
+        .filter(player_deleted_at.is_null())
+        .load::<Player>(connection)?)
+}
+
+/// Whether the player exists and is not soft-deleted, see [`delete_player`]
+pub fn is_active_player(player_id_: PlayerId) -> Result<bool, DbError> {
     let connection = establish_connection()?;
-    Ok(players.load::<Player>(&connection)?)
+    let found = players
+        .find(i32::from(player_id_))
+        .filter(player_deleted_at.is_null())
+        .select(crate::schema::players::id)
+        .first::<i32>(&connection)
+        .optional()?;
+    Ok(found.is_some())
 }
 
 pub fn get_games() -> Result<Vec<Game>, DbError> {
@@ -71,17 +440,116 @@ pub fn get_games() -> Result<Vec<Game>, DbError> {
     Ok(games.load::<Game>(&connection)?)
 }
 
-pub fn get_group_games() -> Result<(Vec<PlayedGroupGame>, Vec<UnplayedGroupGame>), DbError> {
+/// Group games without a kickoff yet, open for predictions
+///
+/// Loaded with a mock kickoff, see [`get_groups`].
+pub fn get_unscheduled_games() -> Result<HashSet<GameId>, DbError> {
     let connection = establish_connection()?;
-    let group_games = games.filter(type_.eq("group")).load::<Game>(&connection)?;
+    Ok(games
+        .filter(type_.eq("group"))
+        .filter(kickoff.is_null())
+        .select(crate::schema::games::id)
+        .load::<i32>(&connection)?
+        .into_iter()
+        .map(|game| GameId::from(u32::try_from(game).expect("i32 -> u32 conv")))
+        .collect())
+}
+
+/// Played, unplayed and void group games
+pub fn get_group_games() -> Result<GroupGames, DbError> {
+    load_group_games(&establish_connection()?)
+}
 
-    Ok(group_games.into_iter().partition_map(|game| {
+fn load_group_games(connection: &SqliteConnection) -> Result<GroupGames, DbError> {
+    let group_games = games.filter(type_.eq("group")).load::<Game>(connection)?;
+
+    let (void_games, group_games): (Vec<Game>, Vec<Game>) = group_games
+        .into_iter()
+        .partition(|game| game.void_status.is_some());
+    let void_games = void_games
+        .into_iter()
+        .map(VoidGroupGame::try_from)
+        .collect::<Result<_, _>>()?;
+    let (played_games, unplayed_games) = group_games.into_iter().partition_map(|game| {
         if game.played {
             Either::Left(PlayedGroupGame::try_from(game).unwrap())
         } else {
             Either::Right(UnplayedGroupGame::try_from(game).unwrap())
         }
-    }))
+    });
+    Ok((played_games, unplayed_games, void_games))
+}
+
+/// Group games by status: played, unplayed and void
+pub type GroupGames = (
+    Vec<PlayedGroupGame>,
+    Vec<UnplayedGroupGame>,
+    Vec<VoidGroupGame>,
+);
+
+/// Get groups
+///
+/// Loads group games and a GameId: GroupId map from the db
+/// The games (played and unplayed) games are then mapped to prospective groups.
+/// The final groups are validated (with a fallible constructor) and collected together.
+pub fn get_groups() -> Result<Groups, DbError> {
+    load_groups(&establish_connection()?)
+}
+
+/// Like [`get_groups`], in the transaction of the caller
+pub(crate) fn load_groups(connection: &SqliteConnection) -> Result<Groups, DbError> {
+    let (played_games, unplayed_games, void_games) = load_group_games(connection)?;
+    let game_group_map = load_group_game_maps(connection)?.collect::<HashMap<GameId, GroupId>>();
+
+    let group_of = |game: &GameId| {
+        *game_group_map
+            .get(game)
+            .unwrap_or_else(|| panic!("game group map discrepancy: no id: {:?}", game))
+    };
+
+    // Every group with a game, also the ones where every game is void
+    let mut group_games: BTreeMap<GroupId, GroupGames> = BTreeMap::new();
+    for game in played_games {
+        group_games
+            .entry(group_of(&game.id))
+            .or_default()
+            .0
+            .push(game);
+    }
+    for game in unplayed_games {
+        group_games
+            .entry(group_of(&game.id))
+            .or_default()
+            .1
+            .push(game);
+    }
+    for game in void_games {
+        group_games
+            .entry(group_of(&game.id))
+            .or_default()
+            .2
+            .push(game);
+    }
+
+    let groups: Result<Groups, WwcError> = group_games
+        .into_iter()
+        .map(|(group_id, (played_, unplayed_, void_))| {
+            Group::try_new(unplayed_, played_)
+                .and_then(|group| group.with_void_games(void_))
+                .map(|group| (group_id, group))
+                .map_err(WwcError::from)
+        })
+        .collect();
+    Ok(groups?)
+}
+
+pub fn get_goal_events() -> Result<Vec<wwc_core::top_scorer::GoalEvent>, DbError> {
+    let connection = establish_connection()?;
+    goal_events
+        .load::<GoalEvent>(&connection)?
+        .into_iter()
+        .map(wwc_core::top_scorer::GoalEvent::try_from)
+        .collect()
 }
 
 pub fn get_teams() -> Result<impl Iterator<Item = wwc_core::Team>, DbError> {
@@ -91,26 +559,66 @@ pub fn get_teams() -> Result<impl Iterator<Item = wwc_core::Team>, DbError> {
 }
 
 pub fn get_group_game_maps() -> Result<impl Iterator<Item = (GameId, GroupId)>, DbError> {
+    load_group_game_maps(&establish_connection()?)
+}
+
+fn load_group_game_maps(
+    connection: &SqliteConnection,
+) -> Result<impl Iterator<Item = (GameId, GroupId)>, DbError> {
+    let maps = group_game_map
+        .load::<GroupGameMap>(connection)?
+        .into_iter()
+        .map(|map_| {
+            map_.group_id()
+                .map(|group_id| (GameId::from(u32::try_from(map_.id).unwrap()), group_id))
+        })
+        .collect::<Result<Vec<_>, DbError>>()?;
+    Ok(maps.into_iter())
+}
+
+/// Insert predictions for a player
+///
+/// Stored as new versions, submitted at `submitted`, see [`insert_pred_versions`].
+pub fn insert_preds(preds_: &PlayerPredictions, submitted: Date) -> Result<(), DbError> {
+    let new_preds: Vec<Prediction> = preds_.preds().copied().collect();
+    insert_pred_versions(preds_.id, &new_preds, submitted)
+}
+
+/// Insert new prediction versions for a player
+///
+/// Previous versions are kept, making it possible to revise a prediction any number of times.
+/// Which version is effective is decided when reading, see [`get_preds`].
+/// All predictions are written in a single transaction, either all are stored or none.
+pub fn insert_pred_versions(
+    player_id_: PlayerId,
+    preds_: &[Prediction],
+    submitted: Date,
+) -> Result<(), DbError> {
     let connection = establish_connection()?;
-    let db_teams = group_game_map.load::<GroupGameMap>(&connection)?;
-    Ok(db_teams.into_iter().map(|map_| {
-        (
-            GameId::from(u32::try_from(map_.id).unwrap()),
-            GroupId::from(map_.group_id_.chars().next().unwrap()),
+    let new_preds: Vec<NewPred> = preds_
+        .iter()
+        .map(|pred| NewPred::from(&(player_id_, PredictionVersion::new(*pred, submitted))))
+        .collect();
+    connection.transaction::<_, DbError, _>(|| {
+        diesel::insert_into(preds)
+            .values(&new_preds)
+            .execute(&connection)?;
+        event::publish(
+            &connection,
+            DomainEvent::PredictionSubmitted {
+                player: player_id_,
+                preds: preds_.to_vec(),
+                submitted,
+            },
         )
-    }))
+    })
 }
 
-pub fn insert_preds(preds_: &PlayerPredictions) -> Result<(), DbError> {
+pub fn insert_goal_events(events: &[wwc_core::top_scorer::GoalEvent]) -> Result<(), DbError> {
+    let events: Vec<NewGoalEvent> = events.iter().map(NewGoalEvent::from).collect();
     let connection = establish_connection()?;
-    let player_id_ = i32::from(preds_.id);
-    diesel::delete(preds.filter(player_id.eq(player_id_))).execute(&connection)?;
-    let preds_: Vec<NewPred> = preds_
-        .preds()
-        .map(move |pred| NewPred::from(&(preds_.id, *pred)))
-        .collect();
-    diesel::insert_into(preds)
-        .values(&preds_)
+    diesel::insert_into(goal_events)
+        .values(&events)
         .execute(&connection)?;
     Ok(())
 }
@@ -126,23 +634,315 @@ pub fn insert_teams(teams_: &[wwc_core::Team]) -> Result<(), DbError> {
     Ok(())
 }
 
+/// Insert new games, in a single transaction
+///
+/// # Errors
+///
+/// Fails with [`DbError::DuplicateGameId`] if two of the games share an id, or a game with the
+/// same id is already stored. No game is inserted then.
 pub fn insert_games<'a, T: 'a>(games_: &'a [T]) -> Result<(), DbError>
 where
     &'a T: Into<NewGame<'a>>,
 {
     let games_: Vec<NewGame> = games_.iter().map(|game| game.into()).collect();
+    let game_ids: Vec<i32> = games_.iter().map(|game| game.id).collect();
+    let duplicate_id = |id_: i32| {
+        DbError::DuplicateGameId(GameId::from(u32::try_from(id_).expect("i32 -> u32 conv")))
+    };
+    if let Some(id_) = game_ids.iter().duplicates().next() {
+        return Err(duplicate_id(*id_));
+    }
     let connection = establish_connection()?;
-    diesel::insert_into(games)
-        .values(&games_)
-        .execute(&connection)?;
-    Ok(())
+    connection.transaction::<_, DbError, _>(|| {
+        let stored = games
+            .select(crate::schema::games::id)
+            .filter(crate::schema::games::id.eq_any(&game_ids))
+            .first::<i32>(&connection)
+            .optional()?;
+        if let Some(id_) = stored {
+            return Err(duplicate_id(id_));
+        }
+        diesel::insert_into(games)
+            .values(&games_)
+            .execute(&connection)?;
+        Ok(())
+    })
+}
+
+/// Set the final score of a game
+///
+/// Overwrites the score of already played games, an abandoned or annulled game is played again.
+/// Publishes the result, or the amended result, as a [`DomainEvent`], see [`event`].
+pub fn play_game(game_id_: GameId, score: Score) -> Result<(), DbError> {
+    set_result(game_id_, score, None).map(|_| ())
+}
+
+/// Set the final score and the cards of a game, e.g. of a result typed as text
+///
+/// The cards are only known by their count, see [`FairPlayScore::events`], and replace the card
+/// events of the game before the result is published. Otherwise like [`play_game`].
+pub fn play_game_with_cards(
+    game_id_: GameId,
+    score: Score,
+    fair_play: FairPlayScore,
+) -> Result<(), DbError> {
+    set_result_with_cards(game_id_, score, fair_play, None).map(|_| ())
+}
+
+/// Set the final score and the cards of a game, unless the game changed since it was read
+///
+/// Like [`play_game_with_cards`], with the version check of [`update_game_result`].
+/// Returns the new version.
+///
+/// # Errors
+///
+/// Fails with [`DbError::Conflict`] if the game has been updated since it was read.
+pub fn update_game_result_with_cards(
+    game_id_: GameId,
+    score: Score,
+    fair_play: FairPlayScore,
+    version: i32,
+) -> Result<i32, DbError> {
+    set_result_with_cards(game_id_, score, fair_play, Some(version))
+}
+
+fn set_result_with_cards(
+    game_id_: GameId,
+    score: Score,
+    fair_play: FairPlayScore,
+    expected: Option<i32>,
+) -> Result<i32, DbError> {
+    let id_ = i32::try_from(u32::from(game_id_)).expect("u32 -> i32 conv");
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let game = games
+            .find(id_)
+            .first::<Game>(&connection)
+            .optional()?
+            .ok_or_else(|| DbError::Generic(format!("No game with id {}", game_id_)))?;
+        let team = |team: i32| TeamId(u32::try_from(team).expect("i32 -> u32 conv"));
+        let events = fair_play.events(team(game.home_team), team(game.away_team));
+        fair_play::replace_card_events(&connection, game_id_, &events)?;
+        set_result_in(&connection, game_id_, score, expected)
+    })
+}
+
+/// Set the final score of a game, unless the game changed since it was read
+///
+/// `version` is the [`row_version`](Game::row_version) of the game when it was read.
+/// Otherwise like [`play_game`].
+/// Returns the new version.
+///
+/// # Errors
+///
+/// Fails with [`DbError::Conflict`] if the game has been updated since it was read,
+/// e.g. by another admin entering a result at the same time.
+pub fn update_game_result(game_id_: GameId, score: Score, version: i32) -> Result<i32, DbError> {
+    set_result(game_id_, score, Some(version))
+}
+
+fn set_result(game_id_: GameId, score: Score, expected: Option<i32>) -> Result<i32, DbError> {
+    let connection = establish_connection()?;
+    connection
+        .transaction::<_, DbError, _>(|| set_result_in(&connection, game_id_, score, expected))
+}
+
+/// Like [`play_game`], in the transaction of the caller
+pub(crate) fn set_result_in(
+    connection: &SqliteConnection,
+    game_id_: GameId,
+    score: Score,
+    expected: Option<i32>,
+) -> Result<i32, DbError> {
+    use crate::schema::games::columns;
+    let id_ = i32::try_from(u32::from(game_id_)).expect("u32 -> i32 conv");
+    let goals = |goals: GoalCount| i32::try_from(u32::from(goals)).expect("u32 -> i32 conv");
+    let game = games
+        .find(id_)
+        .first::<Game>(connection)
+        .optional()?
+        .ok_or_else(|| DbError::Generic(format!("No game with id {}", game_id_)))?;
+    let current = game.row_version;
+    let expected = expected.unwrap_or(current);
+    let conflict = || DbError::Conflict {
+        game: game_id_,
+        version: expected,
+    };
+    if expected != current {
+        return Err(conflict());
+    }
+    // The version check in the update itself guards against a write between the read and
+    // the update.
+    let num_updated = diesel::update(games.find(id_).filter(columns::row_version.eq(current)))
+        .set((
+            columns::played.eq(true),
+            columns::home_result.eq(Some(goals(score.home))),
+            columns::away_result.eq(Some(goals(score.away))),
+            columns::void_status.eq(None::<String>),
+            columns::row_version.eq(current + 1),
+        ))
+        .execute(connection)?;
+    if num_updated == 0 {
+        return Err(conflict());
+    }
+    let previous = match (game.played, game.home_result, game.away_result) {
+        (true, Some(home), Some(away)) => Some(Score::from((
+            u32::try_from(home).expect("i32 -> u32 conv"),
+            u32::try_from(away).expect("i32 -> u32 conv"),
+        ))),
+        _ => None,
+    };
+    event::publish(
+        connection,
+        match previous {
+            Some(previous) => DomainEvent::ResultAmended {
+                game: game_id_,
+                previous,
+                score,
+            },
+            None => DomainEvent::ResultPosted {
+                game: game_id_,
+                score,
+            },
+        },
+    )?;
+    Ok(current + 1)
+}
+
+/// Move the kickoff of an unplayed game
+///
+/// Returns the previous kickoff, `None` if the game had no kickoff and was open.
+/// Predictions are not touched, the effective prediction follows the new kickoff,
+/// see [`get_preds`].
+/// A joker on the game is dropped if the player has another joker on the new matchday, see
+/// [`clashing_jokers`].
+/// `now` is logged as the time of the rescheduling, see [`fairness`].
+pub fn reschedule_game(
+    game_id_: GameId,
+    kickoff_: Date,
+    now: Date,
+) -> Result<Option<Date>, DbError> {
+    use crate::schema::games::columns;
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let game = games
+            .find(i32::try_from(u32::from(game_id_)).expect("u32 -> i32 conv"))
+            .first::<Game>(&connection)
+            .optional()?
+            .ok_or_else(|| DbError::Generic(format!("No game with id {}", game_id_)))?;
+        if game.played {
+            return Err(DbError::Generic(format!(
+                "Game {} is already played",
+                game_id_
+            )));
+        }
+        let old_kickoff = game.scheduled_kickoff()?;
+        let clashes = clashing_jokers(
+            game_id_,
+            kickoff_,
+            &joker::load_all_jokers(&connection)?,
+            &load_groups(&connection)?,
+        );
+        for player in clashes {
+            joker::clear_joker_in(&connection, player, game_id_)?;
+        }
+        diesel::update(&game)
+            .set((
+                columns::kickoff.eq(Some(kickoff_.to_string())),
+                columns::row_version.eq(game.row_version + 1),
+            ))
+            .execute(&connection)?;
+        event::publish(
+            &connection,
+            DomainEvent::GameRescheduled {
+                game: game_id_,
+                old_kickoff,
+                new_kickoff: kickoff_,
+                extended_at: Some(now),
+            },
+        )?;
+        Ok(old_kickoff)
+    })
+}
+
+/// Change the status of a group game
+///
+/// - Abandoned or annulled: the game no longer counts, its points are removed from the
+///   materialized scores. A stored result is kept, so that the game can be restored.
+/// - Played: the stored result counts again.
+/// - Scheduled: the result is removed, the game is to be (re)played.
+///
+/// Returns the new version of the game, see [`update_game_result`].
+///
+/// # Errors
+///
+/// A game can only be marked as played if it has a result.
+pub fn set_game_status(game_id_: GameId, status: GameStatus) -> Result<i32, DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| set_game_status_in(&connection, game_id_, status))
+}
+
+/// Like [`set_game_status`], in the transaction of the caller
+pub(crate) fn set_game_status_in(
+    connection: &SqliteConnection,
+    game_id_: GameId,
+    status: GameStatus,
+) -> Result<i32, DbError> {
+    use crate::schema::games::columns;
+    let game = games
+        .find(i32::try_from(u32::from(game_id_)).expect("u32 -> i32 conv"))
+        .first::<Game>(connection)
+        .optional()?
+        .ok_or_else(|| DbError::Generic(format!("No game with id {}", game_id_)))?;
+    let score = match (game.home_result, game.away_result) {
+        (Some(home), Some(away)) => Some(Score::from((
+            u32::try_from(home).expect("i32 -> u32 conv"),
+            u32::try_from(away).expect("i32 -> u32 conv"),
+        ))),
+        _ => None,
+    };
+    let score = match status {
+        GameStatus::Played => Some(
+            score.ok_or_else(|| DbError::Generic(format!("Game {} has no result", game_id_)))?,
+        ),
+        GameStatus::Scheduled => None,
+        GameStatus::Abandoned | GameStatus::Annulled => score,
+    };
+    let (home, away) = match score {
+        Some(score) => (
+            Some(i32::try_from(u32::from(score.home)).expect("u32 -> i32 conv")),
+            Some(i32::try_from(u32::from(score.away)).expect("u32 -> i32 conv")),
+        ),
+        None => (None, None),
+    };
+    diesel::update(&game)
+        .set((
+            columns::played.eq(status == GameStatus::Played),
+            columns::home_result.eq(home),
+            columns::away_result.eq(away),
+            columns::void_status.eq(Some(status.name()).filter(|_| status.is_void())),
+            columns::row_version.eq(game.row_version + 1),
+        ))
+        .execute(connection)?;
+    event::publish(
+        connection,
+        DomainEvent::GameStatusChanged {
+            game: game_id_,
+            status,
+            score,
+        },
+    )?;
+    Ok(game.row_version + 1)
 }
 
 pub fn insert_group_game_mappings(group_mappings: &[(GroupId, GameId)]) -> Result<(), DbError> {
-    let mappings: Vec<_> = group_mappings
+    let mappings: Vec<(String, GameId)> = group_mappings
         .iter()
-        .map(|(group_id, game_id_)| (String::from(char::from(*group_id)), *game_id_))
-        .collect();
+        .map(|(group_id, game_id_)| {
+            StoredGroupId::try_from(String::from(char::from(*group_id)))
+                .map(|label| (String::from(label), *game_id_))
+        })
+        .collect::<Result<_, _>>()?;
     let mappings: Vec<_> = mappings.iter().map(NewGroupGameMap::from).collect();
     let connection = establish_connection()?;
     diesel::insert_into(group_game_map)
@@ -159,12 +959,85 @@ pub fn clear_players() -> Result<(), DbError> {
     Ok(())
 }
 
-pub fn clear_preds() -> Result<(), DbError> {
+/// Soft-delete every prediction version of a player
+///
+/// The predictions are left out of every read until restored with [`restore_preds`].
+/// A prediction submitted after the deletion is a new version and is not affected by a restore,
+/// unless it is older than the restored ones, see [`effective_prediction`].
+/// Returns the number of deleted versions.
+pub fn delete_preds(player_id_: PlayerId, now: Date) -> Result<usize, DbError> {
     let connection = establish_connection()?;
-    diesel::delete(preds)
-        .execute(&connection)
-        .expect("Could not clear table");
-    Ok(())
+    connection.transaction::<_, DbError, _>(|| {
+        let num_deleted = diesel::update(
+            preds
+                .filter(player_id.eq(i32::from(player_id_)))
+                .filter(deleted_at.is_null()),
+        )
+        .set(deleted_at.eq(Some(now.to_string())))
+        .execute(&connection)?;
+        score::refresh_scores_in(&connection)?;
+        event::publish(
+            &connection,
+            DomainEvent::PredictionsDeleted {
+                player: Some(player_id_),
+            },
+        )?;
+        Ok(num_deleted)
+    })
+}
+
+/// Undo [`delete_preds`], returns the number of restored versions
+pub fn restore_preds(player_id_: PlayerId) -> Result<usize, DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let num_restored = diesel::update(
+            preds
+                .filter(player_id.eq(i32::from(player_id_)))
+                .filter(deleted_at.is_not_null()),
+        )
+        .set(deleted_at.eq(None::<String>))
+        .execute(&connection)?;
+        score::refresh_scores_in(&connection)?;
+        event::publish(
+            &connection,
+            DomainEvent::PredictionsRestored {
+                player: Some(player_id_),
+            },
+        )?;
+        Ok(num_restored)
+    })
+}
+
+/// Soft-delete the predictions of every player, see [`delete_preds`]
+pub fn clear_preds(now: Date) -> Result<usize, DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let num_deleted = diesel::update(preds.filter(deleted_at.is_null()))
+            .set(deleted_at.eq(Some(now.to_string())))
+            .execute(&connection)?;
+        score::refresh_scores_in(&connection)?;
+        event::publish(
+            &connection,
+            DomainEvent::PredictionsDeleted { player: None },
+        )?;
+        Ok(num_deleted)
+    })
+}
+
+/// Undo [`clear_preds`], restoring the soft-deleted predictions of every player
+pub fn restore_all_preds() -> Result<usize, DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let num_restored = diesel::update(preds.filter(deleted_at.is_not_null()))
+            .set(deleted_at.eq(None::<String>))
+            .execute(&connection)?;
+        score::refresh_scores_in(&connection)?;
+        event::publish(
+            &connection,
+            DomainEvent::PredictionsRestored { player: None },
+        )?;
+        Ok(num_restored)
+    })
 }
 
 pub fn clear_teams() -> Result<(), DbError> {
@@ -183,6 +1056,14 @@ pub fn clear_games() -> Result<(), DbError> {
     Ok(())
 }
 
+pub fn clear_goal_events() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(goal_events)
+        .execute(&connection)
+        .expect("Could not clear table");
+    Ok(())
+}
+
 pub fn clear_group_game_maps() -> Result<(), DbError> {
     let connection = establish_connection()?;
     diesel::delete(group_game_map)
@@ -203,4 +1084,87 @@ pub enum DbError {
     Core(#[from] WwcError),
     #[error("Could you be more specific: {0}")]
     Generic(String),
+    #[error("Unsupported snapshot version: {0}")]
+    SnapshotVersion(u32),
+    #[error("Database is not empty")]
+    NotEmpty,
+    #[error("No tournament with id {0}")]
+    UnknownTournament(i32),
+    #[error("Tournament {0} is not a sandbox")]
+    NotSandbox(i32),
+    #[error("Tournament {0} is not the active tournament")]
+    NotActive(i32),
+    #[error("No league with id {0}")]
+    UnknownLeague(i32),
+    #[error("Invalid scoring rules: {0}")]
+    ScoringRules(#[from] serde_json::Error),
+    #[error("Invalid tournament rules: {0}")]
+    TournamentRules(String),
+    #[error("Invalid bracket: {0}")]
+    Bracket(#[from] BracketError),
+    #[error("Unknown group label '{0}', expected a single upper case letter")]
+    InvalidGroupLabel(String),
+    #[error("Game id {0} is used by more than one fixture")]
+    DuplicateGameId(GameId),
+    #[error("Game {game} was changed by someone else since version {version}")]
+    Conflict { game: GameId, version: i32 },
+    #[error("{0}")]
+    Odds(#[from] OddsError),
+    #[error("{0}")]
+    Invite(#[from] InviteError),
+}
+
+impl DbError {
+    /// Whether the error is about a missing row, e.g. an unknown id
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            DbError::Query(QueryError::NotFound)
+                | DbError::UnknownTournament(_)
+                | DbError::UnknownLeague(_)
+        )
+    }
+}
+
+impl ErrorCode for DbError {
+    fn code(&self) -> &'static str {
+        match self {
+            DbError::Query(QueryError::NotFound) => "NOT_FOUND",
+            DbError::DbUrlMissing | DbError::Connection(_) | DbError::Query(_) => "DATABASE_ERROR",
+            DbError::Core(err) => err.code(),
+            DbError::Generic(_) => "OPERATION_FAILED",
+            DbError::SnapshotVersion(_) => "UNSUPPORTED_SNAPSHOT_VERSION",
+            DbError::NotEmpty => "DATABASE_NOT_EMPTY",
+            DbError::UnknownTournament(_) => "UNKNOWN_TOURNAMENT",
+            DbError::NotSandbox(_) => "NOT_A_SANDBOX",
+            DbError::NotActive(_) => "TOURNAMENT_NOT_ACTIVE",
+            DbError::UnknownLeague(_) => "UNKNOWN_LEAGUE",
+            DbError::ScoringRules(_) => "INVALID_SCORING_RULES",
+            DbError::TournamentRules(_) => "INVALID_TOURNAMENT_RULES",
+            DbError::Bracket(err) => err.code(),
+            DbError::InvalidGroupLabel(_) => "INVALID_GROUP_LABEL",
+            DbError::DuplicateGameId(_) => "DUPLICATE_GAME_ID",
+            DbError::Conflict { .. } => "GAME_VERSION_CONFLICT",
+            DbError::Odds(err) => err.code(),
+            DbError::Invite(err) => err.code(),
+        }
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            DbError::Core(err) => err.details(),
+            DbError::Bracket(err) => err.details(),
+            DbError::UnknownTournament(tournament)
+            | DbError::NotSandbox(tournament)
+            | DbError::NotActive(tournament) => {
+                Some(serde_json::json!({ "tournament": tournament }))
+            }
+            DbError::UnknownLeague(league) => Some(serde_json::json!({ "league": league })),
+            DbError::DuplicateGameId(game) => Some(serde_json::json!({ "game": game })),
+            DbError::Conflict { game, version } => {
+                Some(serde_json::json!({ "game": game, "version": version }))
+            }
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,82 @@
+//! Live scores
+//!
+//! Provisional, non-final, scores of games in progress, e.g. posted by an admin or an external
+//! feed during a game.
+//! A live score only ever counts as provisional, see [`wwc_core::leaderboard::provisional_standings`],
+//! and is removed when the final score is set with [`play_game`](crate::play_game).
+use crate::establish_connection;
+use crate::models::{Game, LiveScore};
+use crate::schema::{games, live_scores};
+use crate::DbError;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use wwc_core::game::{GameId, GoalCount, Score};
+
+pub fn get_live_scores() -> Result<HashMap<GameId, Score>, DbError> {
+    let connection = establish_connection()?;
+    let rows = live_scores::table.load::<LiveScore>(&connection)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let goals = |goals: i32| u32::try_from(goals).expect("i32 -> u32 conv");
+            (
+                GameId::from(u32::try_from(row.game_id).expect("i32 -> u32 conv")),
+                Score::new(goals(row.home_result), goals(row.away_result)),
+            )
+        })
+        .collect())
+}
+
+/// Set the current score of a game in progress, replacing any previous live score
+///
+/// # Errors
+///
+/// Played games already have a final score and can not get a live score.
+pub fn set_live_score(game_id: GameId, score: Score) -> Result<(), DbError> {
+    let id = i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv");
+    let goals = |goals: GoalCount| i32::try_from(u32::from(goals)).expect("u32 -> i32 conv");
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let game = games::table
+            .find(id)
+            .first::<Game>(&connection)
+            .optional()?
+            .ok_or_else(|| DbError::Generic(format!("No game with id {}", game_id)))?;
+        if game.played {
+            return Err(DbError::Generic(format!(
+                "Game {} is already played",
+                game_id
+            )));
+        }
+        diesel::replace_into(live_scores::table)
+            .values(&LiveScore {
+                game_id: id,
+                home_result: goals(score.home),
+                away_result: goals(score.away),
+            })
+            .execute(&connection)?;
+        Ok(())
+    })
+}
+
+/// Remove the live score of a game, no-op if it has none
+pub fn clear_live_score(game_id: GameId) -> Result<(), DbError> {
+    remove_live_score(&establish_connection()?, game_id)
+}
+
+/// Like [`clear_live_score`], in the transaction of the caller
+pub(crate) fn remove_live_score(
+    connection: &SqliteConnection,
+    game_id: GameId,
+) -> Result<(), DbError> {
+    let id = i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv");
+    diesel::delete(live_scores::table.find(id)).execute(connection)?;
+    Ok(())
+}
+
+pub fn clear_live_scores() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(live_scores::table).execute(&connection)?;
+    Ok(())
+}
@@ -1,15 +1,29 @@
-use crate::schema::{games, group_game_map, players, preds, teams};
+use crate::schema::{
+    achievements, api_keys, card_events, digests, events, game_venues, games, goal_events,
+    group_game_map, invites, jokers, last_results, league_members, leagues, live_scores, odds,
+    players, playoff_games, playoff_preds, preds, scores, standings, standings_snapshots,
+    strategies, team_aliases, team_names, teams, tournament_results, tournaments, venues,
+    withdrawals,
+};
 use crate::DbError;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::convert::{TryFrom, TryInto};
+use wwc_core::api_key::{ApiKeyInfo, Scope};
 use wwc_core::error::WwcError;
-use wwc_core::fair_play::FairPlayScore;
-use wwc_core::game::{GameId, Score};
-use wwc_core::group::game::{PlayedGroupGame, UnplayedGroupGame};
-use wwc_core::player::{PlayerId, Prediction};
-use wwc_core::team::{FifaCode, Iso2, TeamId, TeamName, TeamRank};
+use wwc_core::fair_play::{CardEvent, CardKind, FairPlayScore};
+use wwc_core::game::{GameId, GameStatus, Score};
+use wwc_core::group::game::{PlayedGroupGame, UnplayedGroupGame, VoidGroupGame};
+use wwc_core::group::GroupId;
+use wwc_core::invite::{Invite, InviteToken};
+use wwc_core::player::{PlayerId, Prediction, PredictionVersion};
+use wwc_core::playoff::bracket::BracketGame;
+use wwc_core::playoff::RoundIdx;
+use wwc_core::team::{FifaCode, Iso2, SquadPlayer, TeamId, TeamName, TeamRank};
+use wwc_core::top_scorer::GoalKind;
+use wwc_core::venue::{Venue, VenueId};
+use wwc_core::Date;
 
-#[derive(Debug, Serialize, Queryable, Identifiable)]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
 pub struct Team {
     pub id: i32,
     pub name: String,
@@ -57,7 +71,7 @@ impl<'a> From<&'a wwc_core::Team> for NewTeam<'a> {
     }
 }
 
-#[derive(Debug, Serialize, Queryable, Associations, Identifiable)]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Associations, Identifiable)]
 #[belongs_to(parent = "Team", foreign_key = "id")]
 pub struct Game {
     pub id: i32,
@@ -71,6 +85,11 @@ pub struct Game {
     pub home_fair_play: Option<i32>,
     pub away_fair_play: Option<i32>,
     pub played: bool,
+    pub kickoff: Option<String>,
+    /// Incremented on every update, see [`update_game_result`](crate::update_game_result)
+    pub row_version: i32,
+    /// `abandoned` or `annulled`, see [`GameStatus`]
+    pub void_status: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -87,6 +106,8 @@ pub struct NewGame<'a> {
     pub home_fair_play: Option<i32>,
     pub away_fair_play: Option<i32>,
     pub played: bool,
+    pub kickoff: Option<String>,
+    pub void_status: Option<&'static str>,
 }
 
 impl<'a> From<&'a UnplayedGroupGame> for NewGame<'a> {
@@ -108,6 +129,8 @@ impl<'a> From<&'a UnplayedGroupGame> for NewGame<'a> {
             home_fair_play: None,
             away_fair_play: None,
             played: false,
+            kickoff: Some(game.date().to_string()),
+            void_status: None,
         }
     }
 }
@@ -142,18 +165,67 @@ impl<'a> From<&'a PlayedGroupGame> for NewGame<'a> {
             home_fair_play: None,
             away_fair_play: None,
             played: true,
+            kickoff: Some(game.date().to_string()),
+            void_status: None,
         }
     }
 }
 
+impl<'a> From<&'a VoidGroupGame> for NewGame<'a> {
+    fn from(game: &'a VoidGroupGame) -> Self {
+        NewGame {
+            id: u32::from(game.id).try_into().unwrap_or_else(|err| {
+                panic!(
+                    "Void group game id conversion, game.id={}. {}",
+                    game.id, err
+                )
+            }),
+            type_: "group",
+            home_team: u32::from(game.home).try_into().expect("team id u32 -> i32"),
+            away_team: u32::from(game.away).try_into().expect("team id u32 -> i32"),
+            home_result: None,
+            away_result: None,
+            home_penalty: None,
+            away_penalty: None,
+            home_fair_play: None,
+            away_fair_play: None,
+            played: false,
+            kickoff: Some(game.date().to_string()),
+            void_status: Some(game.status().name()),
+        }
+    }
+}
+
+impl Game {
+    /// Parse kickoff date
+    ///
+    /// Games inserted before the kickoff column existed fall back to a mock date.
+    pub(crate) fn kickoff_date(&self) -> Result<Date, DbError> {
+        Ok(self.scheduled_kickoff()?.unwrap_or_else(Date::mock))
+    }
+
+    /// Parse kickoff date, `None` if the game has no kickoff yet
+    pub fn scheduled_kickoff(&self) -> Result<Option<Date>, DbError> {
+        self.kickoff
+            .as_ref()
+            .map(|kickoff| {
+                kickoff
+                    .parse()
+                    .map_err(|err| DbError::Generic(format!("Kickoff date '{}': {}", kickoff, err)))
+            })
+            .transpose()
+    }
+}
+
 impl TryFrom<Game> for PlayedGroupGame {
     type Error = DbError;
     fn try_from(game: Game) -> Result<Self, Self::Error> {
+        let date = game.kickoff_date()?;
         Ok(UnplayedGroupGame::try_new(
             u32::try_from(game.id).unwrap(),
             u32::try_from(game.home_team).unwrap(),
             u32::try_from(game.away_team).unwrap(),
-            wwc_core::Date::mock(),
+            date,
         )
         .map_err(WwcError::from)
         .map_err(DbError::from)?
@@ -167,21 +239,38 @@ impl TryFrom<Game> for PlayedGroupGame {
     }
 }
 
+impl TryFrom<Game> for VoidGroupGame {
+    type Error = DbError;
+    fn try_from(game: Game) -> Result<Self, Self::Error> {
+        let status: GameStatus = game
+            .void_status
+            .as_deref()
+            .unwrap_or_default()
+            .parse()
+            .map_err(|err| DbError::Generic(format!("Game {}: {}", game.id, err)))?;
+        UnplayedGroupGame::try_from(game)?
+            .void(status)
+            .map_err(WwcError::from)
+            .map_err(DbError::from)
+    }
+}
+
 impl TryFrom<Game> for UnplayedGroupGame {
     type Error = DbError;
     fn try_from(game: Game) -> Result<Self, Self::Error> {
+        let date = game.kickoff_date()?;
         UnplayedGroupGame::try_new(
             u32::try_from(game.id).unwrap(),
             u32::try_from(game.home_team).unwrap(),
             u32::try_from(game.away_team).unwrap(),
-            wwc_core::Date::mock(),
+            date,
         )
         .map_err(WwcError::from)
         .map_err(DbError::from)
     }
 }
 
-#[derive(Debug, Serialize, Queryable, Associations, Identifiable)]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Associations, Identifiable)]
 #[primary_key(id)]
 #[table_name = "group_game_map"]
 #[belongs_to(parent = "Game", foreign_key = "id")]
@@ -190,6 +279,36 @@ pub struct GroupGameMap {
     pub group_id_: String,
 }
 
+impl GroupGameMap {
+    pub fn group_id(&self) -> Result<GroupId, DbError> {
+        StoredGroupId::try_from(self.group_id_.clone()).map(|id| id.0)
+    }
+}
+
+/// Group label in the `group_game_map` table, a single upper case letter
+///
+/// Checked when mappings are loaded and inserted, an unknown label is a
+/// [`DbError::InvalidGroupLabel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoredGroupId(pub GroupId);
+
+impl TryFrom<String> for StoredGroupId {
+    type Error = DbError;
+    fn try_from(label: String) -> Result<Self, Self::Error> {
+        let mut chars = label.chars();
+        match (chars.next(), chars.next()) {
+            (Some(id), None) if id.is_ascii_uppercase() => Ok(StoredGroupId(GroupId::from(id))),
+            _ => Err(DbError::InvalidGroupLabel(label)),
+        }
+    }
+}
+
+impl From<StoredGroupId> for String {
+    fn from(id: StoredGroupId) -> Self {
+        String::from(char::from(id.0))
+    }
+}
+
 #[derive(Insertable)]
 #[table_name = "group_game_map"]
 pub struct NewGroupGameMap<'a> {
@@ -208,7 +327,7 @@ impl<'a> From<&'a (String, GameId)> for NewGroupGameMap<'a> {
     }
 }
 
-#[derive(Debug, Serialize, Queryable, Associations, Identifiable)]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Associations, Identifiable)]
 #[belongs_to(parent = "Game")]
 #[belongs_to(parent = "Player")]
 #[table_name = "preds"]
@@ -218,15 +337,28 @@ pub struct Pred {
     pub game_id: i32,
     pub home_result: i32,
     pub away_result: i32,
+    pub created_at: String,
+    /// Set when soft-deleted, see [`delete_preds`](crate::delete_preds)
+    pub deleted_at: Option<String>,
 }
 
-impl From<Pred> for Prediction {
-    fn from(pred: Pred) -> Prediction {
+impl From<&Pred> for Prediction {
+    fn from(pred: &Pred) -> Prediction {
         let score = Score::from((
             u32::try_from(pred.home_result).unwrap(),
             u32::try_from(pred.away_result).unwrap(),
         ));
-        Prediction(GameId::from(u32::try_from(pred.id).unwrap()), score)
+        Prediction(GameId::from(u32::try_from(pred.game_id).unwrap()), score)
+    }
+}
+
+impl TryFrom<Pred> for PredictionVersion {
+    type Error = DbError;
+    fn try_from(pred: Pred) -> Result<Self, Self::Error> {
+        let submitted = pred.created_at.parse().map_err(|err| {
+            DbError::Generic(format!("Prediction date '{}': {}", pred.created_at, err))
+        })?;
+        Ok(PredictionVersion::new(Prediction::from(&pred), submitted))
     }
 }
 
@@ -237,24 +369,32 @@ pub struct NewPred {
     pub game_id: i32,
     pub home_result: i32,
     pub away_result: i32,
+    pub created_at: String,
 }
 
-impl From<&(PlayerId, Prediction)> for NewPred {
-    fn from(player_pred: &(PlayerId, Prediction)) -> NewPred {
-        let (player_id, pred) = player_pred;
+impl From<&(PlayerId, PredictionVersion)> for NewPred {
+    fn from(player_pred: &(PlayerId, PredictionVersion)) -> NewPred {
+        let (player_id, version) = player_pred;
+        let pred = version.pred;
         NewPred {
             player_id: i32::from(*player_id),
             game_id: u32::from(pred.0).try_into().expect("u32 -> i32 conv"),
             home_result: u32::from(pred.1.home).try_into().expect("u32 -> i32 conv"),
             away_result: u32::from(pred.1.away).try_into().expect("u32 -> i32 conv"),
+            created_at: version.submitted.to_string(),
         }
     }
 }
 
-#[derive(Debug, Serialize, Queryable, Associations, Identifiable)]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Associations, Identifiable)]
 pub struct Player {
     pub id: i32,
     pub name: String,
+    pub timezone: Option<String>,
+    /// Image URL or emoji, see [`wwc_core::player::validate_avatar`]
+    pub avatar: Option<String>,
+    /// Set when soft-deleted, see [`delete_player`](crate::delete_player)
+    pub deleted_at: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -262,3 +402,557 @@ pub struct Player {
 pub struct NewPlayer<'a> {
     pub name: &'a str,
 }
+
+/// Joker game of a player, see [`wwc_core::joker`]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
+pub struct Joker {
+    pub id: i32,
+    pub player_id: i32,
+    pub game_id: i32,
+}
+
+#[derive(Insertable)]
+#[table_name = "jokers"]
+pub struct NewJoker {
+    pub player_id: i32,
+    pub game_id: i32,
+}
+
+/// Achievement earned by a player, see [`wwc_core::achievement`]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
+#[table_name = "achievements"]
+pub struct PlayerAchievement {
+    pub id: i32,
+    pub player_id: i32,
+    pub achievement: String,
+    pub game_id: i32,
+}
+
+#[derive(Insertable)]
+#[table_name = "achievements"]
+pub struct NewPlayerAchievement<'a> {
+    pub player_id: i32,
+    pub achievement: &'a str,
+    pub game_id: i32,
+}
+
+/// API key of an integration, see [`api_key`](crate::api_key)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
+#[table_name = "api_keys"]
+pub struct StoredApiKey {
+    pub id: i32,
+    pub name: String,
+    /// See [`ApiKey::hash`](wwc_core::api_key::ApiKey::hash)
+    pub key_hash: String,
+    /// See [`Scope::name`](wwc_core::api_key::Scope::name)
+    pub scope: String,
+    pub created_at: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "api_keys"]
+pub struct NewApiKey<'a> {
+    pub name: &'a str,
+    pub key_hash: String,
+    pub scope: &'a str,
+    pub created_at: String,
+}
+
+impl TryFrom<StoredApiKey> for ApiKeyInfo {
+    type Error = DbError;
+    fn try_from(stored: StoredApiKey) -> Result<Self, Self::Error> {
+        let created_at = stored.created_at.parse().map_err(|err| {
+            DbError::Generic(format!("API key date '{}': {}", stored.created_at, err))
+        })?;
+        let scope = stored
+            .scope
+            .parse::<Scope>()
+            .map_err(|err| DbError::Generic(err.to_string()))?;
+        Ok(ApiKeyInfo {
+            id: stored.id,
+            name: stored.name,
+            scope,
+            created_at,
+        })
+    }
+}
+
+/// Provisional score of a game in progress, see [`live`](crate::live)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "live_scores"]
+pub struct LiveScore {
+    pub game_id: i32,
+    pub home_result: i32,
+    pub away_result: i32,
+}
+
+/// Decimal odds of a game, see [`odds`](crate::odds)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "odds"]
+pub struct GameOdds {
+    pub game_id: i32,
+    pub home: f32,
+    pub draw: f32,
+    pub away: f32,
+}
+
+/// Venue, see [`venue`](crate::venue)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "venues"]
+pub struct StoredVenue {
+    pub id: String,
+    pub name: String,
+    pub city: String,
+}
+
+impl From<&Venue> for StoredVenue {
+    fn from(venue: &Venue) -> Self {
+        StoredVenue {
+            id: venue.id.to_string(),
+            name: venue.name.clone(),
+            city: venue.city.clone(),
+        }
+    }
+}
+
+impl From<StoredVenue> for Venue {
+    fn from(venue: StoredVenue) -> Self {
+        Venue {
+            id: VenueId::from(venue.id),
+            name: venue.name,
+            city: venue.city,
+        }
+    }
+}
+
+/// Venue of a game, see [`venue`](crate::venue)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "game_venues"]
+pub struct GameVenue {
+    pub game_id: i32,
+    pub venue_id: String,
+}
+
+/// Points of a player for a played group game or a decided playoff game, see
+/// [`score`](crate::score)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "scores"]
+pub struct PlayerScore {
+    pub player_id: i32,
+    pub game_id: i32,
+    pub points: f32,
+    /// 1 if the score was predicted exactly, or the winner of a playoff game predicted
+    /// correctly, otherwise 0, see [`exact_scores`](wwc_core::leaderboard::exact_scores)
+    pub exact_scores: i32,
+    /// 'group_stage' or 'knockout', see [`ScoringStage`](wwc_core::leaderboard::ScoringStage)
+    pub stage: String,
+}
+
+/// Rank and points of a team after a played game, see [`standings_history`](crate::standings_history)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "standings_snapshots"]
+pub struct StoredSnapshotRow {
+    pub game_id: i32,
+    pub team_id: i32,
+    pub rank: i32,
+    pub points: i32,
+}
+
+/// Current row of the group table of a team, see [`standings`](crate::standings)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "standings"]
+pub struct StoredStanding {
+    pub team_id: i32,
+    pub group_id: String,
+    pub rank: i32,
+    pub played: i32,
+    pub wins: i32,
+    pub draws: i32,
+    pub losses: i32,
+    pub goals_for: i32,
+    pub goals_against: i32,
+    pub points: i32,
+}
+
+/// Domain event as JSON, see [`event`](crate::event)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "events"]
+pub struct StoredEvent {
+    pub id: i32,
+    /// [`DomainEvent::kind`](wwc_core::event::DomainEvent::kind)
+    pub kind: String,
+    pub payload: String,
+    /// UTC, "YYYY-MM-DD HH:MM:SS"
+    pub created_at: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "events"]
+pub struct NewEvent<'a> {
+    pub kind: &'a str,
+    pub payload: String,
+}
+
+/// Time of the latest result of a group, see [`last_result`](crate::last_result)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "last_results"]
+pub struct LastResult {
+    pub group_id: String,
+    /// UTC, "YYYY-MM-DD HH:MM:SS"
+    pub posted_at: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "last_results"]
+pub struct NewLastResult {
+    pub group_id: String,
+}
+
+/// Matchday with a published digest, see [`digest`](crate::digest)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "digests"]
+pub struct PublishedDigest {
+    /// "YYYY-MM-DD"
+    pub day: String,
+    /// UTC, "YYYY-MM-DD HH:MM:SS"
+    pub published_at: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "digests"]
+pub struct NewPublishedDigest {
+    pub day: String,
+}
+
+/// Final points of a player in a tournament, see [`hall_of_fame`](crate::hall_of_fame)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "tournament_results"]
+pub struct TournamentPoints {
+    pub tournament_id: i32,
+    pub player_id: i32,
+    pub points: f32,
+}
+
+/// Prediction strategy of a player in a tournament as JSON, see [`strategy`](crate::strategy)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "strategies"]
+pub struct StoredStrategy {
+    pub tournament_id: i32,
+    pub player_id: i32,
+    pub strategy: String,
+}
+
+/// Team withdrawn from its group, see [`withdrawal`](crate::withdrawal)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "withdrawals"]
+pub struct Withdrawal {
+    pub team_id: i32,
+    pub group_id: String,
+    /// [`WithdrawalPolicy`](wwc_core::group::withdrawal::WithdrawalPolicy) applied, snake case
+    pub policy: String,
+    /// UTC, "YYYY-MM-DD HH:MM:SS"
+    pub withdrawn_at: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "withdrawals"]
+pub struct NewWithdrawal {
+    pub team_id: i32,
+    pub group_id: String,
+    pub policy: String,
+}
+
+/// Invite to a league, see [`invite`](crate::invite)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "invites"]
+pub struct LeagueInvite {
+    pub token: String,
+    pub league_id: i32,
+    pub expires_at: String,
+    pub used_by: Option<i32>,
+}
+
+impl TryFrom<LeagueInvite> for Invite {
+    type Error = DbError;
+    fn try_from(invite: LeagueInvite) -> Result<Self, Self::Error> {
+        let expires_at = invite.expires_at.parse().map_err(|err| {
+            DbError::Generic(format!("Invite expiry '{}': {}", invite.expires_at, err))
+        })?;
+        Ok(Invite {
+            token: InviteToken::from(invite.token),
+            league: invite.league_id,
+            expires_at,
+            used_by: invite.used_by.map(PlayerId::from),
+        })
+    }
+}
+
+/// League, a group of players competing with their own scoring rules
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
+pub struct League {
+    pub id: i32,
+    pub name: String,
+    /// [`ScoringRules`](wwc_core::pred_score::ScoringRules) as JSON
+    pub scoring_rules: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "leagues"]
+pub struct NewLeague<'a> {
+    pub name: &'a str,
+    pub scoring_rules: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
+pub struct LeagueMember {
+    pub id: i32,
+    pub league_id: i32,
+    pub player_id: i32,
+    /// Members from before join times were recorded joined at the Unix epoch
+    pub joined_at: String,
+    /// Name of the player in the league, instead of the name of the account
+    pub display_name: Option<String>,
+    pub emoji: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "league_members"]
+pub struct NewLeagueMember {
+    pub league_id: i32,
+    pub player_id: i32,
+    pub joined_at: String,
+}
+
+/// Tournament
+///
+/// The games are not yet linked to a tournament, there is a single tournament per database.
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
+pub struct Tournament {
+    pub id: i32,
+    pub name: String,
+    /// Sandbox tournaments accept fake results and can be reset, see
+    /// [`reset_sandbox`](crate::tournament::reset_sandbox).
+    pub sandbox: bool,
+    /// [`TournamentRules`](wwc_core::rules::TournamentRules) as JSON, the default rules if unset
+    pub rules: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "tournaments"]
+pub struct NewTournament<'a> {
+    pub name: &'a str,
+    pub sandbox: bool,
+    pub rules: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Associations, Identifiable)]
+#[belongs_to(parent = "Game")]
+#[table_name = "goal_events"]
+pub struct GoalEvent {
+    pub id: i32,
+    pub game_id: i32,
+    pub team_id: i32,
+    pub player: String,
+    pub minute: i32,
+    pub kind: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "goal_events"]
+pub struct NewGoalEvent<'a> {
+    pub game_id: i32,
+    pub team_id: i32,
+    pub player: &'a str,
+    pub minute: i32,
+    pub kind: &'a str,
+}
+
+fn goal_kind_str(kind: GoalKind) -> &'static str {
+    match kind {
+        GoalKind::Regular => "regular",
+        GoalKind::Penalty => "penalty",
+        GoalKind::OwnGoal => "own_goal",
+    }
+}
+
+impl<'a> From<&'a wwc_core::top_scorer::GoalEvent> for NewGoalEvent<'a> {
+    fn from(event: &'a wwc_core::top_scorer::GoalEvent) -> Self {
+        NewGoalEvent {
+            game_id: u32::from(event.game).try_into().expect("u32 -> i32 conv"),
+            team_id: u32::from(event.team).try_into().expect("u32 -> i32 conv"),
+            player: event.player.as_ref(),
+            minute: u8::from(event.minute).into(),
+            kind: goal_kind_str(event.kind),
+        }
+    }
+}
+
+impl TryFrom<GoalEvent> for wwc_core::top_scorer::GoalEvent {
+    type Error = DbError;
+    fn try_from(event: GoalEvent) -> Result<Self, Self::Error> {
+        let kind = match event.kind.as_str() {
+            "regular" => GoalKind::Regular,
+            "penalty" => GoalKind::Penalty,
+            "own_goal" => GoalKind::OwnGoal,
+            kind => return Err(DbError::Generic(format!("Unknown goal kind '{}'", kind))),
+        };
+        Ok(wwc_core::top_scorer::GoalEvent::new(
+            GameId::from(u32::try_from(event.game_id).unwrap()),
+            TeamId(u32::try_from(event.team_id).unwrap()),
+            SquadPlayer::from(event.player),
+            u8::try_from(event.minute).unwrap(),
+            kind,
+        ))
+    }
+}
+
+/// Card shown in a game, see [`fair_play`](crate::fair_play)
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Associations, Identifiable)]
+#[belongs_to(parent = "Game")]
+#[table_name = "card_events"]
+pub struct StoredCardEvent {
+    pub id: i32,
+    pub game_id: i32,
+    pub team_id: i32,
+    pub player: Option<String>,
+    pub minute: i32,
+    pub kind: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "card_events"]
+pub struct NewCardEvent<'a> {
+    pub game_id: i32,
+    pub team_id: i32,
+    pub player: Option<&'a str>,
+    pub minute: i32,
+    pub kind: &'a str,
+}
+
+fn card_kind_str(kind: CardKind) -> &'static str {
+    match kind {
+        CardKind::Yellow => "yellow",
+        CardKind::IndirectRed => "indirect_red",
+        CardKind::DirectRed => "direct_red",
+        CardKind::YellowAndDirectRed => "yellow_and_direct_red",
+    }
+}
+
+impl<'a> NewCardEvent<'a> {
+    pub fn new(game: GameId, event: &'a CardEvent) -> Self {
+        NewCardEvent {
+            game_id: u32::from(game).try_into().expect("u32 -> i32 conv"),
+            team_id: u32::from(event.team).try_into().expect("u32 -> i32 conv"),
+            player: event.player.as_ref().map(|player| player.as_ref()),
+            minute: event.minute.0.into(),
+            kind: card_kind_str(event.kind),
+        }
+    }
+}
+
+impl TryFrom<StoredCardEvent> for CardEvent {
+    type Error = DbError;
+    fn try_from(event: StoredCardEvent) -> Result<Self, Self::Error> {
+        let kind = match event.kind.as_str() {
+            "yellow" => CardKind::Yellow,
+            "indirect_red" => CardKind::IndirectRed,
+            "direct_red" => CardKind::DirectRed,
+            "yellow_and_direct_red" => CardKind::YellowAndDirectRed,
+            kind => return Err(DbError::Generic(format!("Unknown card kind '{}'", kind))),
+        };
+        Ok(CardEvent::new(
+            TeamId(u32::try_from(event.team_id).expect("i32 -> u32 conv")),
+            event.player.map(SquadPlayer::from),
+            u8::try_from(event.minute).expect("i32 -> u8 conv"),
+            kind,
+        ))
+    }
+}
+
+/// Game in the playoff bracket, see [`wwc_core::playoff::bracket`]
+///
+/// The feeders are stored in their text form, e.g. "1A" or "W37".
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
+pub struct PlayoffGame {
+    pub id: i32,
+    pub round: i32,
+    pub home_feeder: String,
+    pub away_feeder: String,
+    pub kickoff: String,
+    pub winner: Option<i32>,
+}
+
+impl From<&BracketGame> for PlayoffGame {
+    fn from(game: &BracketGame) -> Self {
+        PlayoffGame {
+            id: u32::from(game.id).try_into().expect("u32 -> i32 conv"),
+            round: game.round.0.into(),
+            home_feeder: game.home.to_string(),
+            away_feeder: game.away.to_string(),
+            kickoff: game.kickoff.to_string(),
+            winner: None,
+        }
+    }
+}
+
+impl TryFrom<PlayoffGame> for BracketGame {
+    type Error = DbError;
+    fn try_from(game: PlayoffGame) -> Result<Self, Self::Error> {
+        let kickoff = game
+            .kickoff
+            .parse()
+            .map_err(|err| DbError::Generic(format!("Kickoff date '{}': {}", game.kickoff, err)))?;
+        Ok(BracketGame {
+            id: GameId::from(u32::try_from(game.id).unwrap()),
+            round: RoundIdx(u8::try_from(game.round).unwrap()),
+            home: game.home_feeder.parse()?,
+            away: game.away_feeder.parse()?,
+            kickoff,
+        })
+    }
+}
+
+/// Predicted winner of a playoff game, see [`wwc_core::playoff::prediction`]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
+pub struct PlayoffPred {
+    pub id: i32,
+    pub player_id: i32,
+    pub game_id: i32,
+    pub winner: i32,
+}
+
+#[derive(Insertable)]
+#[table_name = "playoff_preds"]
+pub struct NewPlayoffPred {
+    pub player_id: i32,
+    pub game_id: i32,
+    pub winner: i32,
+}
+
+/// Alternative team name, see [`wwc_core::team::resolve`]
+///
+/// The alias is stored normalized, so that it is unique regardless of casing.
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[table_name = "team_aliases"]
+pub struct TeamAlias {
+    pub alias: String,
+    pub team_id: i32,
+}
+
+/// Team name in another language than the default, see [`wwc_core::team::i18n`]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Identifiable)]
+#[table_name = "team_names"]
+pub struct LocalizedTeamName {
+    pub id: i32,
+    pub team_id: i32,
+    pub lang: String,
+    pub name: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "team_names"]
+pub struct NewLocalizedTeamName<'a> {
+    pub team_id: i32,
+    pub lang: &'a str,
+    pub name: &'a str,
+}
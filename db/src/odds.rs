@@ -0,0 +1,79 @@
+//! Betting odds
+//!
+//! Decimal odds per game, e.g. imported from a bookmaker before kickoff.
+//! Used for the optional odds bonus of the league
+//! [`ScoringRules`](wwc_core::pred_score::ScoringRules), see [`wwc_core::odds::OddsBonus`].
+use crate::establish_connection;
+use crate::models::{Game, GameOdds};
+use crate::schema::{games, odds};
+use crate::DbError;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use wwc_core::game::GameId;
+use wwc_core::odds::Odds;
+
+pub fn get_odds() -> Result<HashMap<GameId, Odds>, DbError> {
+    let connection = establish_connection()?;
+    let rows = odds::table.load::<GameOdds>(&connection)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                GameId::from(u32::try_from(row.game_id).expect("i32 -> u32 conv")),
+                Odds {
+                    home: row.home,
+                    draw: row.draw,
+                    away: row.away,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Store the odds of games, replacing any previous odds of the games
+///
+/// Either all odds are stored or none.
+/// Returns the number of games.
+///
+/// # Errors
+///
+/// Every game must exist and have valid odds, see [`Odds::try_new`].
+pub fn import_odds(game_odds: &[(GameId, Odds)]) -> Result<usize, DbError> {
+    let rows = game_odds
+        .iter()
+        .map(|(game_id, odds)| {
+            Odds::try_new(odds.home, odds.draw, odds.away).map(|odds| GameOdds {
+                game_id: i32::try_from(u32::from(*game_id)).expect("u32 -> i32 conv"),
+                home: odds.home,
+                draw: odds.draw,
+                away: odds.away,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        for row in &rows {
+            games::table
+                .find(row.game_id)
+                .first::<Game>(&connection)
+                .optional()?
+                .ok_or_else(|| DbError::Generic(format!("No game with id {}", row.game_id)))?;
+        }
+        diesel::replace_into(odds::table)
+            .values(&rows)
+            .execute(&connection)?;
+        Ok(rows.len())
+    })
+}
+
+/// Store the odds of a game, see [`import_odds`]
+pub fn set_odds(game_id: GameId, odds: Odds) -> Result<(), DbError> {
+    import_odds(&[(game_id, odds)]).map(|_| ())
+}
+
+pub fn clear_odds() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(odds::table).execute(&connection)?;
+    Ok(())
+}
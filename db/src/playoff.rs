@@ -0,0 +1,213 @@
+//! Playoff bracket
+//!
+//! The bracket template, the winners of the played playoff games and the players' predicted
+//! winners.
+use crate::establish_connection;
+use crate::event::publish;
+use crate::models::{NewPlayoffPred, PlayoffGame, PlayoffPred};
+use crate::schema::{players, playoff_games, playoff_preds};
+use crate::DbError;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use wwc_core::error::WwcError;
+use wwc_core::event::DomainEvent;
+use wwc_core::game::GameId;
+use wwc_core::group::Group;
+use wwc_core::player::PlayerId;
+use wwc_core::playoff::bracket::{Bracket, BracketError, BracketGame, BracketNode};
+use wwc_core::playoff::prediction::PlayoffPrediction;
+use wwc_core::team::{TeamId, Teams};
+
+pub fn get_bracket() -> Result<Bracket, DbError> {
+    load_bracket(&establish_connection()?)
+}
+
+/// Like [`get_bracket`], in the transaction of the caller
+fn load_bracket(connection: &SqliteConnection) -> Result<Bracket, DbError> {
+    let games = playoff_games::table
+        .load::<PlayoffGame>(connection)?
+        .into_iter()
+        .map(BracketGame::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Bracket::try_new(games)?)
+}
+
+pub fn insert_bracket(bracket: &Bracket) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    let games: Vec<PlayoffGame> = bracket.games().map(PlayoffGame::from).collect();
+    diesel::insert_into(playoff_games::table)
+        .values(&games)
+        .execute(&connection)?;
+    Ok(())
+}
+
+/// Clear the bracket, including the predictions of its games
+pub fn clear_bracket() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(playoff_preds::table).execute(&connection)?;
+    diesel::delete(playoff_games::table).execute(&connection)?;
+    Ok(())
+}
+
+/// Winners of the played playoff games
+pub fn get_playoff_winners() -> Result<HashMap<GameId, TeamId>, DbError> {
+    load_playoff_winners(&establish_connection()?)
+}
+
+/// Like [`get_playoff_winners`], in the transaction of the caller
+pub(crate) fn load_playoff_winners(
+    connection: &SqliteConnection,
+) -> Result<HashMap<GameId, TeamId>, DbError> {
+    Ok(playoff_games::table
+        .load::<PlayoffGame>(connection)?
+        .into_iter()
+        .filter_map(|game| {
+            game.winner.map(|winner| {
+                (
+                    GameId::from(u32::try_from(game.id).unwrap()),
+                    TeamId(u32::try_from(winner).unwrap()),
+                )
+            })
+        })
+        .collect())
+}
+
+/// Bracket layout with the teams decided so far, see [`Bracket::layout`]
+///
+/// The groups are ordered by the rules of the tournament, see
+/// [`get_active_rules`](crate::tournament::get_active_rules).
+pub fn get_bracket_layout() -> Result<Vec<BracketNode>, DbError> {
+    load_bracket_layout(&establish_connection()?)
+}
+
+/// Like [`get_bracket_layout`], in the transaction of the caller
+pub(crate) fn load_bracket_layout(
+    connection: &SqliteConnection,
+) -> Result<Vec<BracketNode>, DbError> {
+    let groups = crate::load_groups(connection)?;
+    let teams: Teams = crate::get_teams()?.map(|team| (team.id, team)).collect();
+    let rules = crate::tournament::load_active_rules(connection)?;
+    let group_list: Vec<Group> = groups.values().cloned().collect();
+    let group_rules = rules
+        .group_rules(&group_list, &teams)
+        .map_err(WwcError::from)?;
+    let third_place_rules = rules
+        .third_place_rules(&group_list, &teams)
+        .map_err(WwcError::from)?;
+    Ok(load_bracket(connection)?.layout(
+        &groups,
+        &group_rules,
+        &third_place_rules,
+        &load_playoff_winners(connection)?,
+    )?)
+}
+
+/// Set (or with `None`, clear) the winner of a playoff game
+///
+/// Publishes a [`PlayoffWinnerSet`](DomainEvent::PlayoffWinnerSet) event in the same transaction.
+///
+/// # Errors
+///
+/// The game does not exist, or the winner is not one of the two teams of the game, which are
+/// only known once the game is decided in the bracket layout, see [`get_bracket_layout`].
+pub fn set_playoff_winner(game_id: GameId, winner: Option<TeamId>) -> Result<(), DbError> {
+    if let Some(team) = winner {
+        let plays = get_bracket_layout()?
+            .iter()
+            .filter(|node| node.game_id == game_id)
+            .any(|node| node.home.team == Some(team) || node.away.team == Some(team));
+        if !plays {
+            return Err(DbError::from(BracketError::WinnerNotInGame(game_id, team)));
+        }
+    }
+    let connection = establish_connection()?;
+    let id: i32 = u32::from(game_id).try_into().expect("u32 -> i32 conv");
+    let stored = winner.map(|team| i32::try_from(team.0).expect("u32 -> i32 conv"));
+    connection.transaction::<_, DbError, _>(|| {
+        let num_updated = diesel::update(playoff_games::table.find(id))
+            .set(playoff_games::winner.eq(stored))
+            .execute(&connection)?;
+        if num_updated == 0 {
+            return Err(DbError::Generic(format!(
+                "No playoff game with id {}",
+                game_id
+            )));
+        }
+        publish(
+            &connection,
+            DomainEvent::PlayoffWinnerSet {
+                game: game_id,
+                winner,
+            },
+        )
+    })
+}
+
+pub fn get_playoff_prediction(player_id: PlayerId) -> Result<PlayoffPrediction, DbError> {
+    load_playoff_prediction(&establish_connection()?, player_id)
+}
+
+/// Like [`get_playoff_prediction`], in the transaction of the caller
+pub(crate) fn load_playoff_prediction(
+    connection: &SqliteConnection,
+    player_id: PlayerId,
+) -> Result<PlayoffPrediction, DbError> {
+    let rows = playoff_preds::table
+        .filter(playoff_preds::player_id.eq(i32::from(player_id)))
+        .load::<PlayoffPred>(connection)?;
+    Ok(PlayoffPrediction::new(
+        rows.iter()
+            .map(|row| {
+                (
+                    GameId::from(u32::try_from(row.game_id).unwrap()),
+                    TeamId(u32::try_from(row.winner).unwrap()),
+                )
+            })
+            .collect(),
+    ))
+}
+
+/// Predicted winners of a playoff game, from all players
+///
+/// The predictions of soft-deleted players are left out.
+pub fn get_playoff_game_preds(game_id: GameId) -> Result<Vec<(PlayerId, TeamId)>, DbError> {
+    let connection = establish_connection()?;
+    let active_players = players::table
+        .select(players::id)
+        .filter(players::deleted_at.is_null());
+    let rows = playoff_preds::table
+        .filter(
+            playoff_preds::game_id.eq(i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv")),
+        )
+        .filter(playoff_preds::player_id.eq_any(active_players))
+        .load::<PlayoffPred>(&connection)?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            (
+                PlayerId::from(row.player_id),
+                TeamId(u32::try_from(row.winner).unwrap()),
+            )
+        })
+        .collect())
+}
+
+/// Set the predicted winner of a playoff game, replacing any earlier prediction
+///
+/// Validation against the kickoff is done by the caller.
+pub fn set_playoff_pred(
+    player_id: PlayerId,
+    game_id: GameId,
+    winner: TeamId,
+) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::replace_into(playoff_preds::table)
+        .values(&NewPlayoffPred {
+            player_id: i32::from(player_id),
+            game_id: u32::from(game_id).try_into().expect("u32 -> i32 conv"),
+            winner: i32::try_from(winner.0).expect("u32 -> i32 conv"),
+        })
+        .execute(&connection)?;
+    Ok(())
+}
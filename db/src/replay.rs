@@ -0,0 +1,252 @@
+//! Replay of the event log
+//!
+//! The results and the predictions are rebuilt from the events alone, see
+//! [`wwc_core::replay`], and compared to the stored games, the standings and the materialized
+//! leaderboard, see [`check_replay`].
+//! If the games table is lost or corrupted, the results can be written back from the log with
+//! [`restore_from_events`].
+//!
+//! The fixtures, the teams and the jokers are not part of the log and are taken from the db.
+//! Results entered before the log existed are only in the games table, replaying them reports
+//! a mismatch, and a restore leaves them as they are.
+use crate::event::get_events;
+use crate::schema::{games, last_results};
+use crate::{establish_connection, score, standings, standings_history, DbError};
+use diesel::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::convert::TryFrom;
+use wwc_core::error::WwcError;
+use wwc_core::export::{group_standings, StandingRow};
+use wwc_core::game::{GameId, GameStatus, Score};
+use wwc_core::group::{Group, GroupId, Groups};
+use wwc_core::joker::Jokers;
+use wwc_core::leaderboard::{points, ScoringStage};
+use wwc_core::replay::{replay, GameState, ReplayedState};
+use wwc_core::team::Teams;
+
+/// A difference between the stored state and the replayed events
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum ReplayIssue {
+    /// The status or result of a game differs
+    Game {
+        game_id: GameId,
+        stored: GameState,
+        replayed: GameState,
+    },
+    /// The table row of a team differs
+    Standings {
+        group_id: GroupId,
+        stored: StandingRow,
+        replayed: StandingRow,
+    },
+    /// The points of a player differ from the materialized leaderboard
+    Points {
+        player_id: i32,
+        stored: f32,
+        replayed: f32,
+    },
+}
+
+/// Result of [`check_replay`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    /// True if the replayed state matches the stored state
+    pub ok: bool,
+    /// Number of replayed events
+    pub num_events: usize,
+    pub issues: Vec<ReplayIssue>,
+}
+
+/// Replay the whole event log and compare the result to the stored state
+pub fn check_replay() -> Result<ReplayReport, DbError> {
+    let (state, num_events) = replay_log()?;
+    let stored = crate::get_groups()?;
+    let replayed = state.groups(&stored).map_err(WwcError::from)?;
+    let mut issues = game_issues(&stored, &replayed);
+    issues.extend(standings_issues(&stored, &replayed)?);
+    issues.extend(points_issues(&state, &replayed)?);
+    Ok(ReplayReport {
+        ok: issues.is_empty(),
+        num_events,
+        issues,
+    })
+}
+
+/// Write the replayed results, statuses and kickoffs to the games table
+///
+/// Games without an event in the log are left as they are, e.g. results entered before the log
+/// existed. The materialized scores, the group standings and the standings history are
+/// recomputed in the same transaction, see [`score::refresh_scores`],
+/// [`standings::refresh_standings`] and [`standings_history::refresh_standings_history`], and the
+/// times of the latest results are cleared, see [`last_result`](crate::last_result).
+/// No events are published, the log is the source of the restore.
+///
+/// Returns the number of restored games.
+pub fn restore_from_events() -> Result<usize, DbError> {
+    let (state, _) = replay_log()?;
+    let game_ids: Vec<GameId> = crate::get_groups()?
+        .values()
+        .flat_map(|group| group.games().map(|game| game.id()))
+        .filter(|game_id| state.games.contains_key(game_id) || state.kickoffs.contains_key(game_id))
+        .collect();
+    let goals = |score: Option<Score>, home: bool| {
+        score.map(|score| {
+            let goals = if home { score.home } else { score.away };
+            i32::try_from(u32::from(goals)).expect("u32 -> i32 conv")
+        })
+    };
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        for game_id in &game_ids {
+            let id = i32::try_from(u32::from(*game_id)).expect("u32 -> i32 conv");
+            if let Some(GameState { status, score }) = state.games.get(game_id).copied() {
+                diesel::update(games::table.find(id))
+                    .set((
+                        games::played.eq(status == GameStatus::Played),
+                        games::home_result.eq(goals(score, true)),
+                        games::away_result.eq(goals(score, false)),
+                        games::void_status.eq(Some(status.name()).filter(|_| status.is_void())),
+                    ))
+                    .execute(&connection)?;
+            }
+            if let Some(kickoff) = state.kickoffs.get(game_id) {
+                diesel::update(games::table.find(id))
+                    .set(games::kickoff.eq(Some(kickoff.to_string())))
+                    .execute(&connection)?;
+            }
+        }
+        score::refresh_scores_in(&connection)?;
+        standings::refresh_standings_in(&connection)?;
+        standings_history::refresh_standings_history_in(&connection)?;
+        diesel::delete(last_results::table).execute(&connection)?;
+        Ok(())
+    })?;
+    Ok(game_ids.len())
+}
+
+fn replay_log() -> Result<(ReplayedState, usize), DbError> {
+    let events = get_events(None, i64::MAX)?;
+    let state = replay(events.iter().map(|record| &record.event));
+    Ok((state, events.len()))
+}
+
+fn game_issues(stored: &Groups, replayed: &Groups) -> Vec<ReplayIssue> {
+    let replayed: HashMap<GameId, GameState> = replayed
+        .values()
+        .flat_map(|group| group.games())
+        .map(|game| (game.id(), GameState::from(game)))
+        .collect();
+    stored
+        .values()
+        .flat_map(|group| group.games())
+        .filter_map(|game| {
+            let stored = GameState::from(game);
+            let replayed = replayed[&game.id()];
+            if stored == replayed {
+                None
+            } else {
+                Some(ReplayIssue::Game {
+                    game_id: game.id(),
+                    stored,
+                    replayed,
+                })
+            }
+        })
+        .collect()
+}
+
+/// Group table rows by the active rules
+///
+/// The rank is left out of the comparison, teams still tied after every tiebreaker are ranked
+/// by drawing of lots, which differs between two orderings of the same group.
+fn standings_issues(stored: &Groups, replayed: &Groups) -> Result<Vec<ReplayIssue>, DbError> {
+    let rules = crate::tournament::get_active_rules()?;
+    let teams: Teams = crate::get_teams()?.map(|team| (team.id, team)).collect();
+    let tables = |groups: &Groups| -> Result<BTreeMap<(GroupId, u32), StandingRow>, DbError> {
+        let all: Vec<Group> = groups.values().cloned().collect();
+        let group_rules = rules.group_rules(&all, &teams).map_err(WwcError::from)?;
+        Ok(groups
+            .iter()
+            .flat_map(|(id, group)| {
+                group_standings(group, &teams, &group_rules)
+                    .into_iter()
+                    .map(move |row| ((*id, row.team_id), row))
+            })
+            .map(|(key, row)| {
+                let points = rules.points.record_points(row.wins, row.draws, row.losses);
+                (key, StandingRow { points, ..row })
+            })
+            .collect())
+    };
+    let mut replayed = tables(replayed)?;
+    Ok(tables(stored)?
+        .into_iter()
+        .filter_map(|((group_id, _), stored)| {
+            let replayed = replayed.remove(&(group_id, stored.team_id))?;
+            if replayed
+                == (StandingRow {
+                    rank: replayed.rank,
+                    ..stored.clone()
+                })
+            {
+                None
+            } else {
+                Some(ReplayIssue::Standings {
+                    group_id,
+                    stored,
+                    replayed,
+                })
+            }
+        })
+        .collect())
+}
+
+/// Group stage points of the replayed predictions, with the jokers of the db and the scoring of
+/// the active rules
+fn points_issues(state: &ReplayedState, replayed: &Groups) -> Result<Vec<ReplayIssue>, DbError> {
+    let results: HashMap<GameId, Score> = replayed
+        .values()
+        .flat_map(|group| group.played_games())
+        .map(|game| (game.id, game.score))
+        .collect();
+    let jokers = crate::joker::get_all_jokers()?;
+    let no_jokers = Jokers::new();
+    let score_fn = crate::tournament::get_active_rules()?.scoring;
+    let replayed_points: HashMap<i32, f32> = state
+        .predictions(replayed)
+        .into_iter()
+        .map(|(player, preds)| {
+            let jokers = jokers.get(&player).unwrap_or(&no_jokers);
+            let points = f32::from(points(&preds, jokers, &results, &score_fn));
+            (i32::from(player), points)
+        })
+        .collect();
+    let stored_points: HashMap<i32, f32> =
+        score::get_stage_score_leaderboard(ScoringStage::GroupStage)?
+            .into_iter()
+            .map(|row| (row.player_id, row.points))
+            .collect();
+    let player_ids: BTreeSet<i32> = stored_points
+        .keys()
+        .chain(replayed_points.keys())
+        .copied()
+        .collect();
+    Ok(player_ids
+        .into_iter()
+        .filter_map(|player_id| {
+            let stored = stored_points.get(&player_id).copied().unwrap_or_default();
+            let replayed = replayed_points.get(&player_id).copied().unwrap_or_default();
+            if (stored - replayed).abs() < f32::EPSILON {
+                None
+            } else {
+                Some(ReplayIssue::Points {
+                    player_id,
+                    stored,
+                    replayed,
+                })
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,83 @@
+//! Bulk result import
+//!
+//! Stores the results of many games at once, e.g. from a CSV file after the server was down
+//! during a matchday, see [`wwc_core::parse::result_csv`].
+//! Either every result is stored or none. The derived state is updated game by game in kickoff
+//! order in the same transaction, like for results entered one at a time.
+use crate::establish_connection;
+use crate::event;
+use crate::fair_play::replace_card_events;
+use crate::models::Game;
+use crate::schema::games;
+use crate::DbError;
+use diesel::prelude::*;
+use std::convert::TryFrom;
+use wwc_core::event::DomainEvent;
+use wwc_core::game::GoalCount;
+use wwc_core::parse::result_csv::ImportedResult;
+use wwc_core::team::TeamId;
+
+/// Store the results, and the cards of results with cards, in the given order
+///
+/// The results are expected in kickoff order, see
+/// [`in_kickoff_order`](wwc_core::parse::result_csv::in_kickoff_order).
+///
+/// # Errors
+///
+/// Fails, without storing anything, if a game does not exist or is already played, or with
+/// [`DbError::Conflict`] if a game is updated by someone else during the import.
+pub fn import_results(results: &[ImportedResult]) -> Result<(), DbError> {
+    let goals = |goals: GoalCount| i32::try_from(u32::from(goals)).expect("u32 -> i32 conv");
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        for result in results {
+            let game_id = result.submission.game_id;
+            let game = games::table
+                .find(i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv"))
+                .first::<Game>(&connection)
+                .optional()?
+                .ok_or_else(|| DbError::Generic(format!("No game with id {}", game_id)))?;
+            if game.played {
+                return Err(DbError::Generic(format!(
+                    "Game {} is already played",
+                    game_id
+                )));
+            }
+            let score = result.submission.score;
+            // Like for a single result, the version check in the update guards against a write
+            // between the read and the update, see `set_result_in`.
+            let num_updated = diesel::update(&game)
+                .filter(games::row_version.eq(game.row_version))
+                .set((
+                    games::played.eq(true),
+                    games::home_result.eq(Some(goals(score.home))),
+                    games::away_result.eq(Some(goals(score.away))),
+                    games::void_status.eq(None::<String>),
+                    games::row_version.eq(game.row_version + 1),
+                ))
+                .execute(&connection)?;
+            if num_updated == 0 {
+                return Err(DbError::Conflict {
+                    game: game_id,
+                    version: game.row_version,
+                });
+            }
+            if result.has_cards {
+                let team = |id: i32| TeamId(u32::try_from(id).expect("i32 -> u32 conv"));
+                let events = result
+                    .submission
+                    .fair_play
+                    .events(team(game.home_team), team(game.away_team));
+                replace_card_events(&connection, game_id, &events)?;
+            }
+            event::publish(
+                &connection,
+                DomainEvent::ResultPosted {
+                    game: game_id,
+                    score,
+                },
+            )?;
+        }
+        Ok(())
+    })
+}
@@ -1,3 +1,49 @@
+table! {
+    achievements (id) {
+        id -> Integer,
+        player_id -> Integer,
+        achievement -> Text,
+        game_id -> Integer,
+    }
+}
+
+table! {
+    api_keys (id) {
+        id -> Integer,
+        name -> Text,
+        key_hash -> Text,
+        scope -> Text,
+        created_at -> Text,
+    }
+}
+
+table! {
+    card_events (id) {
+        id -> Integer,
+        game_id -> Integer,
+        team_id -> Integer,
+        player -> Nullable<Text>,
+        minute -> Integer,
+        kind -> Text,
+    }
+}
+
+table! {
+    digests (day) {
+        day -> Text,
+        published_at -> Text,
+    }
+}
+
+table! {
+    events (id) {
+        id -> Integer,
+        kind -> Text,
+        payload -> Text,
+        created_at -> Text,
+    }
+}
+
 table! {
     games (id) {
         id -> Integer,
@@ -11,6 +57,27 @@ table! {
         home_fair_play -> Nullable<Integer>,
         away_fair_play -> Nullable<Integer>,
         played -> Bool,
+        kickoff -> Nullable<Text>,
+        row_version -> Integer,
+        void_status -> Nullable<Text>,
+    }
+}
+
+table! {
+    game_venues (game_id) {
+        game_id -> Integer,
+        venue_id -> Text,
+    }
+}
+
+table! {
+    goal_events (id) {
+        id -> Integer,
+        game_id -> Integer,
+        team_id -> Integer,
+        player -> Text,
+        minute -> Integer,
+        kind -> Text,
     }
 }
 
@@ -21,10 +88,93 @@ table! {
     }
 }
 
+table! {
+    invites (token) {
+        token -> Text,
+        league_id -> Integer,
+        expires_at -> Text,
+        used_by -> Nullable<Integer>,
+    }
+}
+
+table! {
+    jokers (id) {
+        id -> Integer,
+        player_id -> Integer,
+        game_id -> Integer,
+    }
+}
+
+table! {
+    last_results (group_id) {
+        group_id -> Text,
+        posted_at -> Text,
+    }
+}
+
+table! {
+    league_members (id) {
+        id -> Integer,
+        league_id -> Integer,
+        player_id -> Integer,
+        joined_at -> Text,
+        display_name -> Nullable<Text>,
+        emoji -> Nullable<Text>,
+    }
+}
+
+table! {
+    leagues (id) {
+        id -> Integer,
+        name -> Text,
+        scoring_rules -> Text,
+    }
+}
+
+table! {
+    live_scores (game_id) {
+        game_id -> Integer,
+        home_result -> Integer,
+        away_result -> Integer,
+    }
+}
+
+table! {
+    odds (game_id) {
+        game_id -> Integer,
+        home -> Float,
+        draw -> Float,
+        away -> Float,
+    }
+}
+
+table! {
+    playoff_games (id) {
+        id -> Integer,
+        round -> Integer,
+        home_feeder -> Text,
+        away_feeder -> Text,
+        kickoff -> Text,
+        winner -> Nullable<Integer>,
+    }
+}
+
+table! {
+    playoff_preds (id) {
+        id -> Integer,
+        player_id -> Integer,
+        game_id -> Integer,
+        winner -> Integer,
+    }
+}
+
 table! {
     players (id) {
         id -> Integer,
         name -> Text,
+        timezone -> Nullable<Text>,
+        avatar -> Nullable<Text>,
+        deleted_at -> Nullable<Text>,
     }
 }
 
@@ -35,6 +185,66 @@ table! {
         game_id -> Integer,
         home_result -> Integer,
         away_result -> Integer,
+        created_at -> Text,
+        deleted_at -> Nullable<Text>,
+    }
+}
+
+table! {
+    scores (player_id, game_id, stage) {
+        player_id -> Integer,
+        game_id -> Integer,
+        points -> Float,
+        exact_scores -> Integer,
+        stage -> Text,
+    }
+}
+
+table! {
+    standings (team_id) {
+        team_id -> Integer,
+        group_id -> Text,
+        rank -> Integer,
+        played -> Integer,
+        wins -> Integer,
+        draws -> Integer,
+        losses -> Integer,
+        goals_for -> Integer,
+        goals_against -> Integer,
+        points -> Integer,
+    }
+}
+
+table! {
+    standings_snapshots (game_id, team_id) {
+        game_id -> Integer,
+        team_id -> Integer,
+        rank -> Integer,
+        points -> Integer,
+    }
+}
+
+table! {
+    strategies (tournament_id, player_id) {
+        tournament_id -> Integer,
+        player_id -> Integer,
+        strategy -> Text,
+    }
+}
+
+table! {
+    team_aliases (alias) {
+        alias -> Text,
+        team_id -> Integer,
+    }
+}
+
+table! {
+    team_names (id) {
+        id -> Integer,
+        team_id -> Integer,
+        lang -> Text,
+        name -> Text,
     }
 }
 
@@ -48,8 +258,103 @@ table! {
     }
 }
 
+table! {
+    tournament_results (tournament_id, player_id) {
+        tournament_id -> Integer,
+        player_id -> Integer,
+        points -> Float,
+    }
+}
+
+table! {
+    tournaments (id) {
+        id -> Integer,
+        name -> Text,
+        sandbox -> Bool,
+        rules -> Nullable<Text>,
+    }
+}
+
+table! {
+    venues (id) {
+        id -> Text,
+        name -> Text,
+        city -> Text,
+    }
+}
+
+table! {
+    withdrawals (team_id) {
+        team_id -> Integer,
+        group_id -> Text,
+        policy -> Text,
+        withdrawn_at -> Text,
+    }
+}
+
+joinable!(achievements -> games (game_id));
+joinable!(achievements -> players (player_id));
+joinable!(card_events -> games (game_id));
+joinable!(game_venues -> games (game_id));
+joinable!(game_venues -> venues (venue_id));
+joinable!(goal_events -> games (game_id));
 joinable!(group_game_map -> games (id));
+joinable!(invites -> leagues (league_id));
+joinable!(invites -> players (used_by));
+joinable!(jokers -> games (game_id));
+joinable!(jokers -> players (player_id));
+joinable!(league_members -> leagues (league_id));
+joinable!(league_members -> players (player_id));
+joinable!(live_scores -> games (game_id));
+joinable!(odds -> games (game_id));
+joinable!(playoff_games -> teams (winner));
+joinable!(playoff_preds -> playoff_games (game_id));
+joinable!(playoff_preds -> players (player_id));
+joinable!(playoff_preds -> teams (winner));
 joinable!(preds -> games (game_id));
 joinable!(preds -> players (player_id));
+joinable!(scores -> players (player_id));
+joinable!(standings -> teams (team_id));
+joinable!(standings_snapshots -> games (game_id));
+joinable!(standings_snapshots -> teams (team_id));
+joinable!(strategies -> players (player_id));
+joinable!(strategies -> tournaments (tournament_id));
+joinable!(team_aliases -> teams (team_id));
+joinable!(team_names -> teams (team_id));
+joinable!(tournament_results -> players (player_id));
+joinable!(tournament_results -> tournaments (tournament_id));
+joinable!(withdrawals -> teams (team_id));
 
-allow_tables_to_appear_in_same_query!(games, group_game_map, players, preds, teams,);
+allow_tables_to_appear_in_same_query!(
+    achievements,
+    api_keys,
+    card_events,
+    digests,
+    events,
+    game_venues,
+    games,
+    goal_events,
+    group_game_map,
+    invites,
+    jokers,
+    last_results,
+    league_members,
+    leagues,
+    live_scores,
+    odds,
+    players,
+    playoff_games,
+    playoff_preds,
+    preds,
+    scores,
+    standings,
+    standings_snapshots,
+    strategies,
+    team_aliases,
+    team_names,
+    teams,
+    tournament_results,
+    tournaments,
+    venues,
+    withdrawals,
+);
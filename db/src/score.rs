@@ -0,0 +1,228 @@
+//! Materialized scores
+//!
+//! The leaderboard is normally derived from the predictions and the results on every request,
+//! see [`wwc_core::leaderboard`].
+//! As an alternative, the points of every player for every played game are stored in the `scores`
+//! table, updated in the transaction of every result entered with [`play_game`](crate::play_game)
+//! and of every playoff winner set with
+//! [`set_playoff_winner`](crate::playoff::set_playoff_winner), and the ranked leaderboard is
+//! aggregated by the db, see [`get_score_leaderboard`].
+//!
+//! The points are scored like the main leaderboard, with the scoring of the tournament rules,
+//! see [`get_active_rules`](crate::tournament::get_active_rules), and the joker multipliers.
+//! Every row belongs to a [`ScoringStage`]: the group games score the predicted scores, the
+//! playoff games the predicted winners, see
+//! [`stage_standings`](wwc_core::leaderboard::stage_standings).
+use crate::establish_connection;
+use crate::models::PlayerScore;
+use crate::schema::scores;
+use crate::DbError;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Float, Integer, Text};
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::iter;
+use wwc_core::game::{GameId, Score};
+use wwc_core::joker::Jokers;
+use wwc_core::leaderboard::{exact_scores, points, ScoringStage, Tiebreak};
+use wwc_core::player::{PlayerId, Prediction};
+use wwc_core::playoff::prediction::SlotStatus;
+
+/// Value of the `stage` column of the scores of a stage
+fn stage_name(stage: ScoringStage) -> &'static str {
+    match stage {
+        ScoringStage::GroupStage => "group_stage",
+        ScoringStage::Knockout => "knockout",
+    }
+}
+
+/// Players ranked by their summed points of the `stage`, or of every stage for `None`, players
+/// without points have zero
+///
+/// Players on equal points are ordered by the `tiebreaks`, like the main leaderboard, see
+/// [`rank`](wwc_core::leaderboard::rank). Players still tied share the rank, e.g. 1, 2, 2, 4.
+/// Soft-deleted players are left out.
+fn leaderboard_query(tiebreaks: &[Tiebreak], stage: Option<ScoringStage>) -> String {
+    let order = iter::once("points DESC")
+        .chain(tiebreaks.iter().map(|tiebreak| match tiebreak {
+            Tiebreak::ExactScores => "exact_scores DESC",
+            Tiebreak::Registration => "player_id",
+        }))
+        .join(", ");
+    format!(
+        "\
+        SELECT RANK() OVER (ORDER BY {order}) AS rank, player_id, player, points, exact_scores \
+        FROM (\
+            SELECT players.id AS player_id, players.name AS player, \
+                CAST(COALESCE(SUM(scores.points), 0) AS REAL) AS points, \
+                CAST(COALESCE(SUM(scores.exact_scores), 0) AS INTEGER) AS exact_scores \
+            FROM players LEFT JOIN scores ON scores.player_id = players.id{stage} \
+            WHERE players.deleted_at IS NULL \
+            GROUP BY players.id\
+        ) \
+        ORDER BY rank, player_id",
+        order = order,
+        stage = stage
+            .map(|stage| format!(" AND scores.stage = '{}'", stage_name(stage)))
+            .unwrap_or_default()
+    )
+}
+
+/// Leaderboard row, aggregated by the db
+#[derive(Debug, Clone, Serialize, QueryableByName)]
+pub struct ScoreRow {
+    #[sql_type = "BigInt"]
+    pub rank: i64,
+    #[sql_type = "Integer"]
+    pub player_id: i32,
+    #[sql_type = "Text"]
+    pub player: String,
+    #[sql_type = "Float"]
+    pub points: f32,
+    #[sql_type = "Integer"]
+    pub exact_scores: i32,
+}
+
+/// Ranked leaderboard from the materialized scores of every stage, best first
+///
+/// The same total as the derived leaderboard, see
+/// [`stage_standings`](wwc_core::leaderboard::stage_standings).
+/// Ties are broken by the tiebreaks of the tournament scoring rules.
+pub fn get_score_leaderboard() -> Result<Vec<ScoreRow>, DbError> {
+    load_score_leaderboard(None)
+}
+
+/// Like [`get_score_leaderboard`], with only the scores of the `stage`
+pub fn get_stage_score_leaderboard(stage: ScoringStage) -> Result<Vec<ScoreRow>, DbError> {
+    load_score_leaderboard(Some(stage))
+}
+
+fn load_score_leaderboard(stage: Option<ScoringStage>) -> Result<Vec<ScoreRow>, DbError> {
+    let tiebreaks = crate::tournament::get_active_rules()?.scoring.tiebreaks;
+    let connection = establish_connection()?;
+    Ok(diesel::sql_query(leaderboard_query(&tiebreaks, stage)).load(&connection)?)
+}
+
+/// Store the points of every player for a played game, replacing any previous points
+///
+/// Players without a prediction for the game get no row.
+/// Runs in the transaction of the caller, see [`event`](crate::event).
+pub(crate) fn score_game(
+    connection: &SqliteConnection,
+    game_id: GameId,
+    score: Score,
+) -> Result<(), DbError> {
+    let id = i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv");
+    let results: HashMap<GameId, Score> = iter::once((game_id, score)).collect();
+    let jokers = crate::joker::get_all_jokers()?;
+    let no_jokers = Jokers::new();
+    let score_fn = crate::tournament::load_active_rules(connection)?.scoring;
+    let mut rows = Vec::new();
+    for player in crate::load_players(connection)? {
+        let player_id = PlayerId::from(player.id);
+        let preds: Vec<Prediction> = crate::load_preds(connection, player_id)?
+            .into_iter()
+            .filter(|Prediction(id, _)| *id == game_id)
+            .collect();
+        if preds.is_empty() {
+            continue;
+        }
+        let jokers = jokers.get(&player_id).unwrap_or(&no_jokers);
+        rows.push(PlayerScore {
+            player_id: player.id,
+            game_id: id,
+            points: f32::from(points(&preds, jokers, &results, &score_fn)),
+            exact_scores: i32::try_from(exact_scores(&preds, &results)).expect("u32 -> i32 conv"),
+            stage: String::from(stage_name(ScoringStage::GroupStage)),
+        });
+    }
+    unscore_game(connection, game_id)?;
+    diesel::insert_into(scores::table)
+        .values(&rows)
+        .execute(connection)?;
+    Ok(())
+}
+
+/// Remove the points of every player for a group game, e.g. when the game is annulled
+pub(crate) fn unscore_game(connection: &SqliteConnection, game_id: GameId) -> Result<(), DbError> {
+    let id = i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv");
+    diesel::delete(
+        scores::table
+            .filter(scores::game_id.eq(id))
+            .filter(scores::stage.eq(stage_name(ScoringStage::GroupStage))),
+    )
+    .execute(connection)?;
+    Ok(())
+}
+
+/// Store the points of every player for the decided playoff games, replacing the previous
+/// knockout scores
+///
+/// Every correctly predicted winner is worth the points of its round by the tournament scoring
+/// rules, see [`ScoringRules::winner_points`](wwc_core::pred_score::ScoringRules::winner_points),
+/// and counts as an exact score, like
+/// [`knockout_standings`](wwc_core::leaderboard::knockout_standings).
+/// Runs in the transaction of the caller, see [`event`](crate::event).
+pub(crate) fn score_knockout(connection: &SqliteConnection) -> Result<(), DbError> {
+    let knockout = stage_name(ScoringStage::Knockout);
+    diesel::delete(scores::table.filter(scores::stage.eq(knockout))).execute(connection)?;
+    if crate::playoff::load_playoff_winners(connection)?.is_empty() {
+        return Ok(());
+    }
+    let layout = crate::playoff::load_bracket_layout(connection)?;
+    let scoring = crate::tournament::load_active_rules(connection)?.scoring;
+    let mut rows = Vec::new();
+    for player in crate::load_players(connection)? {
+        let prediction =
+            crate::playoff::load_playoff_prediction(connection, PlayerId::from(player.id))?;
+        rows.extend(
+            prediction
+                .compare(&layout)
+                .into_iter()
+                .filter(|slot| slot.status == SlotStatus::Correct)
+                .map(|slot| PlayerScore {
+                    player_id: player.id,
+                    game_id: i32::try_from(u32::from(slot.game_id)).expect("u32 -> i32 conv"),
+                    points: scoring.winner_points(slot.round),
+                    exact_scores: 1,
+                    stage: String::from(knockout),
+                }),
+        );
+    }
+    diesel::insert_into(scores::table)
+        .values(&rows)
+        .execute(connection)?;
+    Ok(())
+}
+
+/// Recompute the scores of every played group game and of the decided playoff games, e.g. after
+/// changing predictions directly in the db
+///
+/// Returns the number of scored group games.
+pub fn refresh_scores() -> Result<usize, DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| refresh_scores_in(&connection))
+}
+
+/// Like [`refresh_scores`], in the transaction of the caller
+pub(crate) fn refresh_scores_in(connection: &SqliteConnection) -> Result<usize, DbError> {
+    let played: Vec<(GameId, Score)> = crate::load_groups(connection)?
+        .values()
+        .flat_map(|group| group.played_games())
+        .map(|game| (game.id, game.score))
+        .collect();
+    diesel::delete(scores::table).execute(connection)?;
+    for (game_id, score) in &played {
+        score_game(connection, *game_id, *score)?;
+    }
+    score_knockout(connection)?;
+    Ok(played.len())
+}
+
+pub fn clear_scores() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(scores::table).execute(&connection)?;
+    Ok(())
+}
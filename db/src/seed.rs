@@ -0,0 +1,86 @@
+//! Seeding
+//!
+//! Populates an empty db with the teams, group games, bracket and venues of a tournament in one
+//! step,
+//! e.g. from a built in template.
+use crate::establish_connection;
+use crate::models::{NewGame, NewGroupGameMap, NewTeam, PlayoffGame};
+use crate::schema::{game_venues, games, group_game_map, playoff_games, teams};
+use crate::DbError;
+use diesel::prelude::*;
+use wwc_core::group::Groups;
+use wwc_core::playoff::bracket::Bracket;
+use wwc_core::team::Teams;
+use wwc_core::venue::GameVenues;
+
+/// Store a tournament in a single transaction
+///
+/// The group standings are stored afterwards, see
+/// [`refresh_standings`](crate::standings::refresh_standings).
+///
+/// # Errors
+///
+/// Fails with [`DbError::NotEmpty`] if there already are teams, games, a bracket or venues in
+/// the db.
+pub fn seed(
+    teams_: &Teams,
+    groups: &Groups,
+    bracket: Option<&Bracket>,
+    venues: &GameVenues,
+) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let num_rows = teams::table.count().get_result::<i64>(&connection)?
+            + games::table.count().get_result::<i64>(&connection)?
+            + group_game_map::table
+                .count()
+                .get_result::<i64>(&connection)?
+            + playoff_games::table
+                .count()
+                .get_result::<i64>(&connection)?
+            + game_venues::table.count().get_result::<i64>(&connection)?;
+        if num_rows > 0 {
+            return Err(DbError::NotEmpty);
+        }
+        let new_teams: Vec<NewTeam> = teams_.values().map(NewTeam::from).collect();
+        diesel::insert_into(teams::table)
+            .values(&new_teams)
+            .execute(&connection)?;
+        let new_games: Vec<NewGame> = groups
+            .values()
+            .flat_map(|group| {
+                group
+                    .unplayed_games()
+                    .map(NewGame::from)
+                    .chain(group.played_games().map(NewGame::from))
+                    .chain(group.void_games().map(NewGame::from))
+            })
+            .collect();
+        diesel::insert_into(games::table)
+            .values(&new_games)
+            .execute(&connection)?;
+        let mappings: Vec<(String, _)> = groups
+            .iter()
+            .flat_map(|(id, group)| {
+                group
+                    .games()
+                    .map(|game| game.id())
+                    .map(move |game_id| (String::from(char::from(*id)), game_id))
+            })
+            .collect();
+        let mappings: Vec<NewGroupGameMap> = mappings.iter().map(NewGroupGameMap::from).collect();
+        diesel::insert_into(group_game_map::table)
+            .values(&mappings)
+            .execute(&connection)?;
+        if let Some(bracket) = bracket {
+            let playoff: Vec<PlayoffGame> = bracket.games().map(PlayoffGame::from).collect();
+            diesel::insert_into(playoff_games::table)
+                .values(&playoff)
+                .execute(&connection)?;
+        }
+        crate::venue::store_game_venues(&connection, venues)?;
+        Ok(())
+    })?;
+    crate::standings::refresh_standings()?;
+    Ok(())
+}
@@ -0,0 +1,233 @@
+//! Materialized group standings
+//!
+//! The current group table of every team, its rank, W/D/L, goals and points, is stored in the
+//! `standings` table for cheap reads, see [`get_group_standings`].
+//! The rows of a group are replaced, in one transaction, whenever a result of the group is
+//! entered or changed, like the rest of the derived state, see [`event`](crate::event).
+//!
+//! The groups are ordered by the group rules and scored by the point system of the tournament,
+//! like the standings served by the server, see
+//! [`get_active_rules`](crate::tournament::get_active_rules).
+//! A change of the rules is not picked up until [`refresh_standings`], [`check_standings`] finds
+//! such stale rows.
+use crate::establish_connection;
+use crate::models::{StoredGroupId, StoredStanding};
+use crate::schema::{standings, teams};
+use crate::DbError;
+use diesel::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use wwc_core::error::WwcError;
+use wwc_core::export::{group_standings, StandingRow};
+use wwc_core::game::GameId;
+use wwc_core::group::{Group, GroupId, Groups};
+use wwc_core::team::Teams;
+
+/// Group table with stored rows which differ from the rows computed from the games
+#[derive(Debug, Clone, Serialize)]
+pub struct StandingsMismatch {
+    pub group: GroupId,
+    pub stored: Vec<StandingRow>,
+    pub expected: Vec<StandingRow>,
+}
+
+/// Stored group table, best first
+///
+/// Empty for unknown groups.
+pub fn get_group_standings(group_id: GroupId) -> Result<Vec<StandingRow>, DbError> {
+    let connection = establish_connection()?;
+    let rows = standings::table
+        .inner_join(teams::table)
+        .filter(standings::group_id.eq(String::from(StoredGroupId(group_id))))
+        .order(standings::rank)
+        .select((standings::all_columns, teams::name, teams::fifa_code))
+        .load::<(StoredStanding, String, String)>(&connection)?;
+    Ok(rows
+        .into_iter()
+        .map(|(row, team, fifa_code)| standing_row(row, team, fifa_code))
+        .collect())
+}
+
+/// Recompute the table of the group of a game, no-op for games outside the group stage
+///
+/// Runs in the transaction of the caller, see [`event`](crate::event).
+pub(crate) fn store_group_standings_of(
+    connection: &SqliteConnection,
+    game_id: GameId,
+) -> Result<(), DbError> {
+    let groups = crate::load_groups(connection)?;
+    match groups
+        .iter()
+        .find(|(_, group)| group.games().any(|game| game.id() == game_id))
+    {
+        Some((group_id, _)) => {
+            let expected = compute_standings(connection, &groups, &load_teams()?)?;
+            store_standings(connection, *group_id, expected_rows(&expected, *group_id))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Recompute the tables of every group, e.g. after changing the tournament rules
+///
+/// Returns the number of groups.
+pub fn refresh_standings() -> Result<usize, DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| refresh_standings_in(&connection))
+}
+
+/// Like [`refresh_standings`], in the transaction of the caller
+pub(crate) fn refresh_standings_in(connection: &SqliteConnection) -> Result<usize, DbError> {
+    let groups = crate::load_groups(connection)?;
+    let expected = compute_standings(connection, &groups, &load_teams()?)?;
+    diesel::delete(standings::table).execute(connection)?;
+    for group_id in groups.keys() {
+        store_standings(connection, *group_id, expected_rows(&expected, *group_id))?;
+    }
+    Ok(groups.len())
+}
+
+/// Compare the stored tables to the tables computed from the games
+///
+/// Returns the groups which differ, e.g. after a result was entered directly in the db.
+/// Teams with the same record may be ranked in either order, the random tiebreaker draws the
+/// order anew every time.
+pub fn check_standings() -> Result<Vec<StandingsMismatch>, DbError> {
+    let connection = establish_connection()?;
+    let groups = crate::load_groups(&connection)?;
+    let expected = compute_standings(&connection, &groups, &load_teams()?)?;
+    groups
+        .keys()
+        .map(|group_id| {
+            Ok(StandingsMismatch {
+                group: *group_id,
+                stored: get_group_standings(*group_id)?,
+                expected: expected_rows(&expected, *group_id).to_vec(),
+            })
+        })
+        .filter(|mismatch| {
+            mismatch
+                .as_ref()
+                .map(|mismatch| !agrees(&mismatch.stored, &mismatch.expected))
+                .unwrap_or(true)
+        })
+        .collect()
+}
+
+pub fn clear_standings() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(standings::table).execute(&connection)?;
+    Ok(())
+}
+
+/// Tables of every group by the tournament rules, sorted by group id
+fn compute_standings(
+    connection: &SqliteConnection,
+    groups: &Groups,
+    teams: &Teams,
+) -> Result<Vec<(GroupId, Vec<StandingRow>)>, DbError> {
+    let rules = crate::tournament::load_active_rules(connection)?;
+    let group_list: Vec<Group> = groups.values().cloned().collect();
+    let group_rules = rules
+        .group_rules(&group_list, teams)
+        .map_err(WwcError::from)?;
+    Ok(groups
+        .iter()
+        .map(|(id, group)| {
+            let rows = group_standings(group, teams, &group_rules)
+                .into_iter()
+                .map(|row| StandingRow {
+                    points: rules.points.record_points(row.wins, row.draws, row.losses),
+                    ..row
+                })
+                .collect();
+            (*id, rows)
+        })
+        .collect())
+}
+
+fn expected_rows(expected: &[(GroupId, Vec<StandingRow>)], group_id: GroupId) -> &[StandingRow] {
+    expected
+        .iter()
+        .find(|(id, _)| *id == group_id)
+        .map(|(_, rows)| rows.as_slice())
+        .unwrap_or_default()
+}
+
+/// Whether the tables have the same records in the same order and the same record for every team
+fn agrees(stored: &[StandingRow], expected: &[StandingRow]) -> bool {
+    let record = |row: &StandingRow| {
+        (
+            row.played,
+            row.wins,
+            row.draws,
+            row.losses,
+            row.goals_scored,
+            row.goals_conceded,
+            row.points,
+        )
+    };
+    let by_team = |rows: &[StandingRow]| -> HashMap<u32, _> {
+        rows.iter().map(|row| (row.team_id, record(row))).collect()
+    };
+    stored.len() == expected.len()
+        && stored
+            .iter()
+            .zip(expected)
+            .all(|(stored, expected)| record(stored) == record(expected))
+        && by_team(stored) == by_team(expected)
+}
+
+/// Replace the rows of a group
+fn store_standings(
+    connection: &SqliteConnection,
+    group_id: GroupId,
+    rows: &[StandingRow],
+) -> Result<(), DbError> {
+    let stored_group_id = String::from(StoredGroupId(group_id));
+    let int = |value: u32| i32::try_from(value).expect("u32 -> i32 conv");
+    let new_rows: Vec<StoredStanding> = rows
+        .iter()
+        .map(|row| StoredStanding {
+            team_id: int(row.team_id),
+            group_id: stored_group_id.clone(),
+            rank: i32::try_from(row.rank).expect("usize -> i32 conv"),
+            played: int(row.played),
+            wins: int(row.wins),
+            draws: int(row.draws),
+            losses: int(row.losses),
+            goals_for: int(row.goals_scored),
+            goals_against: int(row.goals_conceded),
+            points: int(row.points),
+        })
+        .collect();
+    diesel::delete(standings::table.filter(standings::group_id.eq(&stored_group_id)))
+        .execute(connection)?;
+    diesel::insert_into(standings::table)
+        .values(&new_rows)
+        .execute(connection)?;
+    Ok(())
+}
+
+fn standing_row(row: StoredStanding, team: String, fifa_code: String) -> StandingRow {
+    let int = |value: i32| u32::try_from(value).expect("i32 -> u32 conv");
+    StandingRow {
+        rank: usize::try_from(row.rank).expect("i32 -> usize conv"),
+        team_id: int(row.team_id),
+        team,
+        fifa_code,
+        played: int(row.played),
+        wins: int(row.wins),
+        draws: int(row.draws),
+        losses: int(row.losses),
+        goals_scored: int(row.goals_for),
+        goals_conceded: int(row.goals_against),
+        goal_diff: row.goals_for - row.goals_against,
+        points: int(row.points),
+    }
+}
+
+fn load_teams() -> Result<Teams, DbError> {
+    Ok(crate::get_teams()?.map(|team| (team.id, team)).collect())
+}
@@ -0,0 +1,147 @@
+//! Standings history
+//!
+//! The rank and points of every team after every played group game are stored in the
+//! `standings_snapshots` table, see [`wwc_core::group::history`].
+//! The snapshots of a group are recomputed whenever a result of the group is entered or changed
+//! with [`play_game`](crate::play_game) or [`set_game_status`](crate::set_game_status),
+//! so a late or corrected result does not leave stale snapshots behind.
+//!
+//! The groups are ordered by the group rules of the tournament, like the standings served by the
+//! server, see [`get_active_rules`](crate::tournament::get_active_rules).
+//! A tie decided by the random tiebreaker is drawn when the snapshot is stored.
+use crate::establish_connection;
+use crate::models::StoredSnapshotRow;
+use crate::schema::standings_snapshots;
+use crate::DbError;
+use diesel::prelude::*;
+use itertools::Itertools;
+use std::convert::TryFrom;
+use wwc_core::error::WwcError;
+use wwc_core::game::GameId;
+use wwc_core::group::history::{standings_history, SnapshotRow, StandingsSnapshot};
+use wwc_core::group::order::GroupRank;
+use wwc_core::group::{Group, GroupId, GroupPoint};
+use wwc_core::team::{TeamId, Teams};
+
+/// Standings after every played game of a group, in kickoff order
+///
+/// Played games without a stored snapshot are left out, see [`refresh_standings_history`].
+pub fn get_standings_history(group_id: GroupId) -> Result<Vec<StandingsSnapshot>, DbError> {
+    let groups = crate::get_groups()?;
+    let group = groups
+        .get(&group_id)
+        .ok_or_else(|| DbError::Generic(format!("No group with id {}", group_id)))?;
+    let connection = establish_connection()?;
+    let mut rows = standings_snapshots::table
+        .filter(standings_snapshots::game_id.eq_any(db_game_ids(group)))
+        .load::<StoredSnapshotRow>(&connection)?
+        .into_iter()
+        .into_group_map_by(|row| row.game_id);
+    Ok(group
+        .played_games()
+        .sorted_by_key(|game| (game.date(), game.id))
+        .filter_map(|game| {
+            let game_rows = rows.remove(&db_game_id(game.id))?;
+            Some(StandingsSnapshot {
+                game_id: game.id,
+                kickoff: game.date(),
+                rows: game_rows
+                    .into_iter()
+                    .sorted_by_key(|row| row.rank)
+                    .map(|row| SnapshotRow {
+                        team: TeamId(u32::try_from(row.team_id).expect("i32 -> u32 conv")),
+                        rank: GroupRank(usize::try_from(row.rank).expect("i32 -> usize conv")),
+                        points: GroupPoint(u8::try_from(row.points).expect("i32 -> u8 conv")),
+                    })
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+/// Recompute the snapshots of the group of a game, no-op for games outside the group stage
+///
+/// Runs in the transaction of the caller, see [`event`](crate::event).
+pub(crate) fn snapshot_group_of(
+    connection: &SqliteConnection,
+    game_id: GameId,
+) -> Result<(), DbError> {
+    let groups = crate::load_groups(connection)?;
+    match groups
+        .values()
+        .find(|group| group.games().any(|game| game.id() == game_id))
+    {
+        Some(group) => store_history(connection, group, &load_teams()?),
+        None => Ok(()),
+    }
+}
+
+/// Recompute the snapshots of every group, e.g. for results entered before the history existed
+///
+/// Returns the number of groups.
+pub fn refresh_standings_history() -> Result<usize, DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| refresh_standings_history_in(&connection))
+}
+
+/// Like [`refresh_standings_history`], in the transaction of the caller
+pub(crate) fn refresh_standings_history_in(
+    connection: &SqliteConnection,
+) -> Result<usize, DbError> {
+    let groups = crate::load_groups(connection)?;
+    let teams = load_teams()?;
+    diesel::delete(standings_snapshots::table).execute(connection)?;
+    for group in groups.values() {
+        store_history(connection, group, &teams)?;
+    }
+    Ok(groups.len())
+}
+
+pub fn clear_standings_history() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(standings_snapshots::table).execute(&connection)?;
+    Ok(())
+}
+
+/// Replace the snapshots of every game of a group
+fn store_history(
+    connection: &SqliteConnection,
+    group: &Group,
+    teams: &Teams,
+) -> Result<(), DbError> {
+    let rules = crate::tournament::load_active_rules(connection)?
+        .group_rules(std::slice::from_ref(group), teams)
+        .map_err(WwcError::from)?;
+    let rows: Vec<StoredSnapshotRow> = standings_history(group, &rules)
+        .into_iter()
+        .flat_map(|snapshot| {
+            let game_id = db_game_id(snapshot.game_id);
+            snapshot.rows.into_iter().map(move |row| StoredSnapshotRow {
+                game_id,
+                team_id: i32::try_from(row.team.0).expect("u32 -> i32 conv"),
+                rank: i32::try_from(row.rank.0).expect("usize -> i32 conv"),
+                points: i32::from(row.points.0),
+            })
+        })
+        .collect();
+    diesel::delete(
+        standings_snapshots::table.filter(standings_snapshots::game_id.eq_any(db_game_ids(group))),
+    )
+    .execute(connection)?;
+    diesel::insert_into(standings_snapshots::table)
+        .values(&rows)
+        .execute(connection)?;
+    Ok(())
+}
+
+fn load_teams() -> Result<Teams, DbError> {
+    Ok(crate::get_teams()?.map(|team| (team.id, team)).collect())
+}
+
+fn db_game_ids(group: &Group) -> Vec<i32> {
+    group.games().map(|game| db_game_id(game.id())).collect()
+}
+
+fn db_game_id(game_id: GameId) -> i32 {
+    i32::try_from(u32::from(game_id)).expect("u32 -> i32 conv")
+}
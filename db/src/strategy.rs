@@ -0,0 +1,91 @@
+//! Prediction strategies
+//!
+//! The predictions of a tournament are lost when its games are replaced, but how every player
+//! predicted is archived as a [`Strategy`] with [`archive_strategies`], along with the points of
+//! the tournament, see [`hall_of_fame`](crate::hall_of_fame).
+//! The latest strategy of a player is applied to the games of the next tournament as a starting
+//! point for the new predictions, see [`Strategy::predict`].
+use crate::establish_connection;
+use crate::models::StoredStrategy;
+use crate::schema::strategies;
+use crate::DbError;
+use diesel::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use wwc_core::player::PlayerId;
+use wwc_core::pred::strategy::Strategy;
+use wwc_core::team::{TeamId, TeamRank};
+
+/// Strategy of a player in an archived tournament
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchivedStrategy {
+    pub tournament_id: i32,
+    pub strategy: Strategy,
+}
+
+/// Store the strategy of every player in the current games as the strategy of a tournament
+///
+/// The strategies are inferred from the effective predictions and the current ranking of the
+/// teams, see [`Strategy::infer`]. Players without predictions get no strategy.
+/// Replaces any previous strategies of the tournament.
+/// Returns the number of players.
+pub fn archive_strategies(id: i32) -> Result<usize, DbError> {
+    crate::tournament::get_tournament(id)?;
+    let groups = crate::get_groups()?;
+    let ranking = get_ranking()?;
+    let mut rows = Vec::new();
+    for player in crate::get_players()? {
+        let preds = crate::get_preds(PlayerId::from(player.id))?;
+        if let Some(strategy) = Strategy::infer(&preds, &groups, &ranking) {
+            rows.push(StoredStrategy {
+                tournament_id: id,
+                player_id: player.id,
+                strategy: serde_json::to_string(&strategy).expect("Strategies serialize"),
+            });
+        }
+    }
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        diesel::delete(strategies::table.filter(strategies::tournament_id.eq(id)))
+            .execute(&connection)?;
+        diesel::insert_into(strategies::table)
+            .values(&rows)
+            .execute(&connection)?;
+        Ok(rows.len())
+    })
+}
+
+/// Strategy of a player in the latest archived tournament the player took part in
+pub fn get_strategy(player_id: PlayerId) -> Result<Option<ArchivedStrategy>, DbError> {
+    let connection = establish_connection()?;
+    strategies::table
+        .filter(strategies::player_id.eq(i32::from(player_id)))
+        .order(strategies::tournament_id.desc())
+        .first::<StoredStrategy>(&connection)
+        .optional()?
+        .map(|row| {
+            Ok(ArchivedStrategy {
+                tournament_id: row.tournament_id,
+                strategy: serde_json::from_str(&row.strategy).map_err(|err| {
+                    DbError::Generic(format!(
+                        "Invalid strategy of player {} in tournament {}: {}",
+                        row.player_id, row.tournament_id, err
+                    ))
+                })?,
+            })
+        })
+        .transpose()
+}
+
+/// Rank of every team, as used by [`Strategy`]
+pub fn get_ranking() -> Result<HashMap<TeamId, TeamRank>, DbError> {
+    Ok(crate::get_teams()?
+        .map(|team| (team.id, team.rank))
+        .collect())
+}
+
+pub fn clear_strategies() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(strategies::table).execute(&connection)?;
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Team aliases
+//!
+//! Alternative team names used when resolving free text, see [`wwc_core::team::resolve`].
+use crate::establish_connection;
+use crate::models::TeamAlias;
+use crate::schema::team_aliases;
+use crate::DbError;
+use diesel::prelude::*;
+use std::convert::TryFrom;
+use wwc_core::team::resolve::{normalize, TeamResolver};
+use wwc_core::team::{TeamId, Teams};
+
+/// All aliases, as `(normalized alias, team)`
+pub fn get_team_aliases() -> Result<Vec<(String, TeamId)>, DbError> {
+    let connection = establish_connection()?;
+    let rows = team_aliases::table.load::<TeamAlias>(&connection)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let team_id = TeamId(u32::try_from(row.team_id).expect("i32 -> u32 conv"));
+            (row.alias, team_id)
+        })
+        .collect())
+}
+
+/// Add an alias, replacing any previous team with the same alias
+pub fn set_team_alias(alias: &str, team_id: TeamId) -> Result<(), DbError> {
+    let alias = normalize(alias);
+    if alias.is_empty() {
+        return Err(DbError::Generic(String::from("Empty team alias")));
+    }
+    let connection = establish_connection()?;
+    diesel::replace_into(team_aliases::table)
+        .values(&TeamAlias {
+            alias,
+            team_id: i32::try_from(team_id.0).expect("u32 -> i32 conv"),
+        })
+        .execute(&connection)?;
+    Ok(())
+}
+
+/// Remove an alias, no-op if it does not exist
+pub fn remove_team_alias(alias: &str) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(team_aliases::table.filter(team_aliases::alias.eq(normalize(alias))))
+        .execute(&connection)?;
+    Ok(())
+}
+
+pub fn clear_team_aliases() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(team_aliases::table).execute(&connection)?;
+    Ok(())
+}
+
+/// Names resolved besides the names and Fifa codes of the teams: the translated names, then the
+/// stored aliases, see [`get_team_resolver`]
+pub fn get_resolver_aliases() -> Result<Vec<(String, TeamId)>, DbError> {
+    let mut aliases: Vec<(String, TeamId)> = crate::team_name::get_all_team_names()?
+        .into_iter()
+        .map(|(_, id, name)| (name.to_string(), id))
+        .collect();
+    aliases.extend(get_team_aliases()?);
+    Ok(aliases)
+}
+
+/// Resolver for the teams in the db, including the stored aliases and translated names
+pub fn get_team_resolver() -> Result<TeamResolver, DbError> {
+    let teams: Teams = crate::get_teams()?.map(|team| (team.id, team)).collect();
+    Ok(TeamResolver::new(&teams).with_aliases(get_resolver_aliases()?))
+}
@@ -0,0 +1,73 @@
+//! Localized team names
+//!
+//! Teams are stored with their default (English) name,
+//! translations are stored per language, see [`wwc_core::team::i18n`].
+use crate::establish_connection;
+use crate::models::{LocalizedTeamName, NewLocalizedTeamName};
+use crate::schema::team_names;
+use crate::DbError;
+use diesel::prelude::*;
+use std::convert::TryFrom;
+use wwc_core::team::i18n::{localize, Lang, LocalizedNames};
+use wwc_core::team::{TeamId, TeamName, Teams};
+
+/// Translated names in a language, teams without a translation are left out
+pub fn get_team_names(lang: &Lang) -> Result<LocalizedNames, DbError> {
+    let connection = establish_connection()?;
+    let rows = team_names::table
+        .filter(team_names::lang.eq(lang.to_string()))
+        .load::<LocalizedTeamName>(&connection)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (team_id(row.team_id), TeamName::from(row.name)))
+        .collect())
+}
+
+/// Translated names in every language
+pub fn get_all_team_names() -> Result<Vec<(Lang, TeamId, TeamName)>, DbError> {
+    let connection = establish_connection()?;
+    let rows = team_names::table.load::<LocalizedTeamName>(&connection)?;
+    rows.into_iter()
+        .map(|row| {
+            let lang = row
+                .lang
+                .parse()
+                .map_err(|err: wwc_core::team::i18n::LangError| {
+                    DbError::Generic(err.to_string())
+                })?;
+            Ok((lang, team_id(row.team_id), TeamName::from(row.name)))
+        })
+        .collect()
+}
+
+/// All teams, with names in `lang` where there is a translation
+pub fn get_localized_teams(lang: &Lang) -> Result<Teams, DbError> {
+    let mut teams: Teams = crate::get_teams()?.map(|team| (team.id, team)).collect();
+    if !lang.is_default() {
+        localize(&mut teams, &get_team_names(lang)?);
+    }
+    Ok(teams)
+}
+
+/// Set the name of a team in a language, replacing any previous translation
+pub fn set_team_name(team: TeamId, lang: &Lang, name: &str) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::replace_into(team_names::table)
+        .values(&NewLocalizedTeamName {
+            team_id: i32::try_from(team.0).expect("u32 -> i32 conv"),
+            lang: lang.as_ref(),
+            name,
+        })
+        .execute(&connection)?;
+    Ok(())
+}
+
+pub fn clear_team_names() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(team_names::table).execute(&connection)?;
+    Ok(())
+}
+
+fn team_id(db_id: i32) -> TeamId {
+    TeamId(u32::try_from(db_id).expect("i32 -> u32 conv"))
+}
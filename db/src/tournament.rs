@@ -0,0 +1,203 @@
+//! Tournaments
+//!
+//! A tournament carries a name, the sandbox flag and its rules, see [`TournamentRules`].
+//! In a sandbox tournament results can be entered freely to try out the rules and the scoring,
+//! and everything but the fixtures and the players can be wiped with [`reset_sandbox`].
+use crate::establish_connection;
+use crate::models::{NewTournament, Tournament};
+use crate::schema::{
+    achievements, card_events, digests, events, games, goal_events, jokers, last_results,
+    live_scores, playoff_games, playoff_preds, preds, scores, standings, standings_snapshots,
+    tournaments, withdrawals,
+};
+use crate::DbError;
+use diesel::prelude::*;
+use wwc_core::error::WwcError;
+use wwc_core::game::{GameId, Score};
+use wwc_core::rules::TournamentRules;
+
+pub fn get_tournament(id: i32) -> Result<Tournament, DbError> {
+    let connection = establish_connection()?;
+    tournaments::table
+        .find(id)
+        .first(&connection)
+        .optional()?
+        .ok_or(DbError::UnknownTournament(id))
+}
+
+pub fn get_tournaments() -> Result<Vec<Tournament>, DbError> {
+    let connection = establish_connection()?;
+    Ok(tournaments::table.load(&connection)?)
+}
+
+/// Register a tournament, with the default rules unless `rules` are given
+pub fn register_tournament(
+    name: &str,
+    sandbox: bool,
+    rules: Option<&TournamentRules>,
+) -> Result<(), DbError> {
+    let rules = rules.map(encode_rules).transpose()?;
+    let connection = establish_connection()?;
+    diesel::insert_into(tournaments::table)
+        .values(&NewTournament {
+            name,
+            sandbox,
+            rules,
+        })
+        .execute(&connection)?;
+    Ok(())
+}
+
+/// Rules of a tournament, the default rules if none are stored
+pub fn get_rules(id: i32) -> Result<TournamentRules, DbError> {
+    decode_rules(get_tournament(id)?.rules)
+}
+
+/// Replace the rules of a tournament
+///
+/// The group standings, the standings history and the materialized scores of the active
+/// tournament follow from its rules and are recomputed in the same transaction, see
+/// [`get_active_id`]. The standings of every group are then recorded as modified, see
+/// [`last_result`](crate::last_result).
+pub fn set_rules(id: i32, rules: &TournamentRules) -> Result<(), DbError> {
+    let rules = encode_rules(rules)?;
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let num_rows = diesel::update(tournaments::table.find(id))
+            .set(tournaments::rules.eq(rules))
+            .execute(&connection)?;
+        if num_rows == 0 {
+            return Err(DbError::UnknownTournament(id));
+        }
+        if load_active_id(&connection)? == Some(id) {
+            crate::standings::refresh_standings_in(&connection)?;
+            crate::standings_history::refresh_standings_history_in(&connection)?;
+            crate::score::refresh_scores_in(&connection)?;
+            crate::last_result::record_all_groups(&connection)?;
+        }
+        Ok(())
+    })
+}
+
+/// Id of the tournament with the lowest id, `None` if there is no tournament
+///
+/// The games are not yet linked to a tournament, so this is the tournament of the games in the
+/// db.
+pub fn get_active_id() -> Result<Option<i32>, DbError> {
+    load_active_id(&establish_connection()?)
+}
+
+fn load_active_id(connection: &SqliteConnection) -> Result<Option<i32>, DbError> {
+    Ok(tournaments::table
+        .select(tournaments::id)
+        .order(tournaments::id)
+        .first(connection)
+        .optional()?)
+}
+
+/// Rules of the tournament with the lowest id
+///
+/// The games are not yet linked to a tournament, so these are the rules of the games in the db.
+/// The default rules if there is no tournament.
+pub fn get_active_rules() -> Result<TournamentRules, DbError> {
+    load_active_rules(&establish_connection()?)
+}
+
+/// Like [`get_active_rules`], in the transaction of the caller
+pub(crate) fn load_active_rules(connection: &SqliteConnection) -> Result<TournamentRules, DbError> {
+    let rules = tournaments::table
+        .select(tournaments::rules)
+        .order(tournaments::id)
+        .first::<Option<String>>(connection)
+        .optional()?;
+    decode_rules(rules.flatten())
+}
+
+fn encode_rules(rules: &TournamentRules) -> Result<String, DbError> {
+    rules.validate().map_err(WwcError::from)?;
+    serde_json::to_string(rules).map_err(|err| DbError::TournamentRules(err.to_string()))
+}
+
+fn decode_rules(rules: Option<String>) -> Result<TournamentRules, DbError> {
+    match rules {
+        Some(rules) => {
+            serde_json::from_str(&rules).map_err(|err| DbError::TournamentRules(err.to_string()))
+        }
+        None => Ok(TournamentRules::default()),
+    }
+}
+
+/// Enter a (fake) result in a sandbox tournament
+///
+/// Overwrites the score of already played games.
+/// The result is only entered if the game has not changed since `version`,
+/// see [`update_game_result`](crate::update_game_result).
+/// Returns the new version of the game.
+pub fn set_sandbox_result(
+    id: i32,
+    game_id: GameId,
+    score: Score,
+    version: i32,
+) -> Result<i32, DbError> {
+    ensure_sandbox(id)?;
+    crate::update_game_result(game_id, score, version)
+}
+
+/// Reset a sandbox tournament
+///
+/// Only the active tournament can be reset, see [`get_active_id`], since the games are not
+/// linked to a tournament and resetting wipes every game in the db.
+/// All games are marked as scheduled, playoff winners are cleared and goal and card events,
+/// live scores, predictions, jokers, achievements, materialized scores, the times of the latest
+/// results, withdrawals, the published digests and the event log are deleted, only the fixtures,
+/// teams and players remain. The group standings are recomputed, see
+/// [`refresh_standings`](crate::standings::refresh_standings).
+pub fn reset_sandbox(id: i32) -> Result<(), DbError> {
+    ensure_sandbox(id)?;
+    if get_active_id()? != Some(id) {
+        return Err(DbError::NotActive(id));
+    }
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        diesel::update(games::table)
+            .set((
+                games::played.eq(false),
+                games::home_result.eq(None::<i32>),
+                games::away_result.eq(None::<i32>),
+                games::home_penalty.eq(None::<i32>),
+                games::away_penalty.eq(None::<i32>),
+                games::home_fair_play.eq(None::<i32>),
+                games::away_fair_play.eq(None::<i32>),
+                games::void_status.eq(None::<String>),
+                games::row_version.eq(games::row_version + 1),
+            ))
+            .execute(&connection)?;
+        diesel::update(playoff_games::table)
+            .set(playoff_games::winner.eq(None::<i32>))
+            .execute(&connection)?;
+        diesel::delete(goal_events::table).execute(&connection)?;
+        diesel::delete(card_events::table).execute(&connection)?;
+        diesel::delete(live_scores::table).execute(&connection)?;
+        diesel::delete(preds::table).execute(&connection)?;
+        diesel::delete(jokers::table).execute(&connection)?;
+        diesel::delete(playoff_preds::table).execute(&connection)?;
+        diesel::delete(achievements::table).execute(&connection)?;
+        diesel::delete(scores::table).execute(&connection)?;
+        diesel::delete(standings_snapshots::table).execute(&connection)?;
+        diesel::delete(standings::table).execute(&connection)?;
+        diesel::delete(last_results::table).execute(&connection)?;
+        diesel::delete(withdrawals::table).execute(&connection)?;
+        diesel::delete(digests::table).execute(&connection)?;
+        diesel::delete(events::table).execute(&connection)?;
+        crate::standings::refresh_standings_in(&connection)?;
+        Ok(())
+    })
+}
+
+fn ensure_sandbox(id: i32) -> Result<(), DbError> {
+    if get_tournament(id)?.sandbox {
+        Ok(())
+    } else {
+        Err(DbError::NotSandbox(id))
+    }
+}
@@ -0,0 +1,77 @@
+//! Venues
+//!
+//! The stadiums of a tournament and the venue of every game, see [`wwc_core::venue`].
+use crate::establish_connection;
+use crate::models::{GameVenue, StoredVenue};
+use crate::schema::{game_venues, venues};
+use crate::DbError;
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use wwc_core::game::GameId;
+use wwc_core::venue::{GameVenues, Venue, VenueId};
+
+/// Venue of every game with a venue
+pub fn get_game_venues() -> Result<GameVenues, DbError> {
+    let connection = establish_connection()?;
+    let venues: HashMap<VenueId, Venue> = venues::table
+        .load::<StoredVenue>(&connection)?
+        .into_iter()
+        .map(Venue::from)
+        .map(|venue| (venue.id.clone(), venue))
+        .collect();
+    game_venues::table
+        .load::<GameVenue>(&connection)?
+        .into_iter()
+        .map(|row| {
+            let venue = venues
+                .get(&VenueId::from(row.venue_id.clone()))
+                .ok_or_else(|| DbError::Generic(format!("No venue with id {}", row.venue_id)))?;
+            Ok((
+                GameId::from(u32::try_from(row.game_id).expect("i32 -> u32 conv")),
+                venue.clone(),
+            ))
+        })
+        .collect()
+}
+
+/// Store the venues of games, replacing any previous venue of the games
+pub fn insert_game_venues(game_venues: &GameVenues) -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| store_game_venues(&connection, game_venues))
+}
+
+/// Store the venues of games with an existing connection, e.g. inside a transaction
+pub(crate) fn store_game_venues(
+    connection: &SqliteConnection,
+    game_venues_: &GameVenues,
+) -> Result<(), DbError> {
+    let new_venues: Vec<StoredVenue> = game_venues_
+        .values()
+        .unique_by(|venue| &venue.id)
+        .map(StoredVenue::from)
+        .collect();
+    diesel::replace_into(venues::table)
+        .values(&new_venues)
+        .execute(connection)?;
+    let rows: Vec<GameVenue> = game_venues_
+        .iter()
+        .map(|(game_id, venue)| GameVenue {
+            game_id: i32::try_from(u32::from(*game_id)).expect("u32 -> i32 conv"),
+            venue_id: venue.id.to_string(),
+        })
+        .collect();
+    diesel::replace_into(game_venues::table)
+        .values(&rows)
+        .execute(connection)?;
+    Ok(())
+}
+
+pub fn clear_venues() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(game_venues::table).execute(&connection)?;
+    diesel::delete(venues::table).execute(&connection)?;
+    Ok(())
+}
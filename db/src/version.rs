@@ -0,0 +1,64 @@
+//! Version of the data that the standings and the leaderboard are derived from
+//!
+//! Read with a few aggregate queries, without loading any rows, so that the server can tag a
+//! derived response and answer a conditional request before deriving the response.
+//! Any write that can change the standings or the leaderboard changes the version:
+//!
+//! - Results, predictions and changes of players, including renamed and erased players, are
+//!   published as events, see [`event`](crate::event).
+//! - Every write of a game bumps its [`row_version`](crate::models::Game::row_version).
+//! - A result, or a change of the rules, sets the latest result of a group, see
+//!   [`last_result`](crate::last_result).
+//! - New players, jokers and playoff predictions get new row id's, see [`DataVersion`].
+use crate::establish_connection;
+use crate::schema::{events, games, jokers, last_results, players, playoff_preds};
+use crate::DbError;
+use diesel::prelude::*;
+use serde::Serialize;
+
+/// Opaque version of the stored data, equal versions derive equal responses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DataVersion {
+    last_event: Option<i32>,
+    num_games: i64,
+    /// Sum of the row versions of the games
+    games: Option<i64>,
+    last_result: Option<String>,
+    last_player: Option<i32>,
+    /// A changed joker or playoff prediction is a new row, a moved joker a new game
+    num_jokers: i64,
+    last_joker: Option<i32>,
+    joker_games: Option<i64>,
+    num_playoff_preds: i64,
+    last_playoff_pred: Option<i32>,
+}
+
+pub fn get_data_version() -> Result<DataVersion, DbError> {
+    let connection = establish_connection()?;
+    Ok(DataVersion {
+        last_event: events::table
+            .select(diesel::dsl::max(events::id))
+            .first(&connection)?,
+        num_games: games::table.count().get_result(&connection)?,
+        games: games::table
+            .select(diesel::dsl::sum(games::row_version))
+            .first(&connection)?,
+        last_result: last_results::table
+            .select(diesel::dsl::max(last_results::posted_at))
+            .first(&connection)?,
+        last_player: players::table
+            .select(diesel::dsl::max(players::id))
+            .first(&connection)?,
+        num_jokers: jokers::table.count().get_result(&connection)?,
+        last_joker: jokers::table
+            .select(diesel::dsl::max(jokers::id))
+            .first(&connection)?,
+        joker_games: jokers::table
+            .select(diesel::dsl::sum(jokers::game_id))
+            .first(&connection)?,
+        num_playoff_preds: playoff_preds::table.count().get_result(&connection)?,
+        last_playoff_pred: playoff_preds::table
+            .select(diesel::dsl::max(playoff_preds::id))
+            .first(&connection)?,
+    })
+}
@@ -0,0 +1,128 @@
+//! Withdrawn teams
+//!
+//! A team withdrawing from its group is stored in the `withdrawals` table and the games of the
+//! team are changed by the [`WithdrawalPolicy`] of the active tournament rules, see
+//! [`withdraw_team`].
+//! The changes are ordinary results and statuses, so the scores, standings and audit log follow
+//! from their events as for any other result.
+use crate::establish_connection;
+use crate::models::{NewWithdrawal, StoredGroupId, Withdrawal};
+use crate::schema::withdrawals;
+use crate::{event, DbError};
+use diesel::prelude::*;
+use serde::Serialize;
+use std::convert::TryFrom;
+use wwc_core::error::WwcError;
+use wwc_core::event::DomainEvent;
+use wwc_core::game::GameStatus;
+use wwc_core::group::withdrawal::{withdraw, WithdrawalChange, WithdrawalPolicy};
+use wwc_core::group::GroupId;
+use wwc_core::team::TeamId;
+
+/// Withdrawn team read back from the table
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamWithdrawal {
+    pub team: TeamId,
+    pub group: GroupId,
+    pub policy: WithdrawalPolicy,
+    /// UTC, "YYYY-MM-DD HH:MM:SS"
+    pub withdrawn_at: String,
+}
+
+/// Withdraw a team from its group
+///
+/// The games of the team are annulled or forfeited by the withdrawal policy of the active
+/// tournament rules, see [`withdraw`].
+/// The withdrawal, the changed games and their events are stored in one transaction.
+/// Returns the changed games.
+///
+/// # Errors
+///
+/// Fails if the team does not play in any group, or has already withdrawn.
+pub fn withdraw_team(team: TeamId) -> Result<Vec<WithdrawalChange>, DbError> {
+    let team_id = i32::try_from(u32::from(team)).expect("u32 -> i32 conv");
+    let connection = establish_connection()?;
+    connection.transaction::<_, DbError, _>(|| {
+        let withdrawn = withdrawals::table
+            .find(team_id)
+            .first::<Withdrawal>(&connection)
+            .optional()?;
+        if withdrawn.is_some() {
+            return Err(DbError::Generic(format!(
+                "Team {} has already withdrawn",
+                team
+            )));
+        }
+        let groups = crate::load_groups(&connection)?;
+        let (group_id, group) = groups
+            .iter()
+            .find(|(_, group)| group.contains(team))
+            .ok_or_else(|| DbError::Generic(format!("Team {} does not play in any group", team)))?;
+        let policy = crate::tournament::load_active_rules(&connection)?.withdrawal;
+        let changes = withdraw(group, team, policy).map_err(WwcError::from)?;
+        diesel::insert_into(withdrawals::table)
+            .values(&NewWithdrawal {
+                team_id,
+                group_id: String::from(StoredGroupId(*group_id)),
+                policy: policy_name(policy),
+            })
+            .execute(&connection)?;
+        for change in &changes {
+            match *change {
+                WithdrawalChange::Annulled { game } => {
+                    crate::set_game_status_in(&connection, game, GameStatus::Annulled)?;
+                }
+                WithdrawalChange::Forfeited { game, score } => {
+                    crate::set_result_in(&connection, game, score, None)?;
+                }
+            }
+        }
+        event::publish(
+            &connection,
+            DomainEvent::TeamWithdrawn {
+                team,
+                group: *group_id,
+                policy,
+            },
+        )?;
+        Ok(changes)
+    })
+}
+
+/// Every withdrawn team, in the order of withdrawal
+pub fn get_withdrawals() -> Result<Vec<TeamWithdrawal>, DbError> {
+    let connection = establish_connection()?;
+    withdrawals::table
+        .order((withdrawals::withdrawn_at, withdrawals::team_id))
+        .load::<Withdrawal>(&connection)?
+        .into_iter()
+        .map(|row| {
+            let policy = serde_json::from_value(serde_json::Value::String(row.policy.clone()))
+                .map_err(|err| {
+                    DbError::Generic(format!(
+                        "Invalid withdrawal policy of team {}: {}",
+                        row.team_id, err
+                    ))
+                })?;
+            Ok(TeamWithdrawal {
+                team: TeamId::from(u32::try_from(row.team_id).expect("i32 -> u32 conv")),
+                group: StoredGroupId::try_from(row.group_id)?.0,
+                policy,
+                withdrawn_at: row.withdrawn_at,
+            })
+        })
+        .collect()
+}
+
+pub fn clear_withdrawals() -> Result<(), DbError> {
+    let connection = establish_connection()?;
+    diesel::delete(withdrawals::table).execute(&connection)?;
+    Ok(())
+}
+
+fn policy_name(policy: WithdrawalPolicy) -> String {
+    match serde_json::to_value(policy).expect("Withdrawal policies serialize") {
+        serde_json::Value::String(name) => name,
+        _ => unreachable!("Unit variants serialize to strings"),
+    }
+}
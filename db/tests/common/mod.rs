@@ -0,0 +1,73 @@
+//! Test databases
+//!
+//! The db functions connect to the database at `DATABASE_URL`, so every test sets it to a fresh
+//! database of its own, see [`TestDb`], and the tests of a binary run one at a time.
+use diesel::connection::{Connection, SimpleConnection};
+use diesel::sqlite::SqliteConnection;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use wwc_data::template::Template;
+
+static LOCK: Mutex<()> = Mutex::new(());
+static NUM_DBS: AtomicUsize = AtomicUsize::new(0);
+
+/// Empty database with every migration applied, removed on drop
+///
+/// Holds a lock, other tests wait until it is dropped.
+pub struct TestDb {
+    path: PathBuf,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TestDb {
+    pub fn new() -> Self {
+        // A failed test poisons the lock, the database of the next test is fresh anyway.
+        let lock = LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let path = env::temp_dir().join(format!(
+            "wwc_db_test_{}_{}.db",
+            process::id(),
+            NUM_DBS.fetch_add(1, Ordering::SeqCst)
+        ));
+        let url = path.to_str().expect("Temp path is UTF-8");
+        let connection = SqliteConnection::establish(url).expect("Test db connects");
+        let migrations = Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+        let mut ups: Vec<PathBuf> = fs::read_dir(migrations)
+            .expect("Migrations dir")
+            .map(|entry| entry.expect("Migration dir entry").path().join("up.sql"))
+            .filter(|up| up.exists())
+            .collect();
+        ups.sort();
+        for up in ups {
+            let sql = fs::read_to_string(&up).expect("Migration readable");
+            connection
+                .batch_execute(&sql)
+                .unwrap_or_else(|err| panic!("Migration {}: {}", up.display(), err));
+        }
+        env::set_var("DATABASE_URL", url);
+        TestDb { path, _lock: lock }
+    }
+
+    /// Database with the teams and games of Euro 2020, without results
+    pub fn euro_2020() -> Self {
+        let db = TestDb::new();
+        let data = Template::Euro2020.load().expect("Euro 2020 template loads");
+        wwc_db::seed::seed(
+            &data.teams,
+            &data.groups,
+            data.bracket.as_ref(),
+            &data.venues,
+        )
+        .expect("Euro 2020 seeds");
+        db
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
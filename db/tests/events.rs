@@ -0,0 +1,118 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::TestDb;
+use wwc_core::event::DomainEvent;
+use wwc_core::game::{GameId, Score};
+use wwc_core::group::GroupId;
+use wwc_core::player::PlayerId;
+use wwc_core::Date;
+use wwc_db::digest::mark_published;
+use wwc_db::event::get_events;
+use wwc_db::DbError;
+
+fn events() -> Vec<DomainEvent> {
+    get_events(None, 100)
+        .unwrap()
+        .into_iter()
+        .map(|record| record.event)
+        .collect()
+}
+
+#[test]
+fn posted_result_updates_derived_state() {
+    let _db = TestDb::euro_2020();
+    let game = GameId::from(1);
+    let score = Score::from((1, 0));
+
+    wwc_db::play_game(game, score).unwrap();
+
+    assert_eq!(events(), vec![DomainEvent::ResultPosted { game, score }]);
+    let group = GroupId::try_new('A').unwrap();
+    let standings = wwc_db::standings::get_group_standings(group).unwrap();
+    assert_eq!(standings.iter().filter(|row| row.played == 1).count(), 2);
+    assert!(wwc_db::last_result::get_last_result(group)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn failed_write_publishes_nothing() {
+    let _db = TestDb::euro_2020();
+    let game = GameId::from(1);
+    let version = wwc_db::update_game_result(game, Score::from((1, 0)), 0).unwrap();
+
+    let stale = wwc_db::update_game_result(game, Score::from((2, 0)), version - 1);
+
+    assert!(matches!(stale, Err(DbError::Conflict { .. })));
+    assert_eq!(events().len(), 1);
+}
+
+#[test]
+fn rescheduling_logs_time_of_extension() {
+    let _db = TestDb::euro_2020();
+    let game = GameId::from(1);
+    let now = Date::mock();
+    let new_kickoff = now.add_hours(48);
+
+    let old_kickoff = wwc_db::reschedule_game(game, new_kickoff, now).unwrap();
+
+    assert_eq!(
+        events(),
+        vec![DomainEvent::GameRescheduled {
+            game,
+            old_kickoff,
+            new_kickoff,
+            extended_at: Some(now),
+        }]
+    );
+}
+
+#[test]
+fn rescheduling_drops_clashing_jokers() {
+    let _db = TestDb::euro_2020();
+    wwc_db::register_player("Anna").unwrap();
+    let player = PlayerId::from(wwc_db::get_players().unwrap()[0].id);
+    let groups = wwc_db::get_groups().unwrap();
+    let kickoff = |game: GameId| {
+        groups
+            .values()
+            .flat_map(|group| group.games())
+            .find(|other| other.id() == game)
+            .unwrap()
+            .date()
+    };
+    let (game, other) = (GameId::from(1), GameId::from(30));
+    assert_ne!(kickoff(game).day(), kickoff(other).day());
+    wwc_db::joker::set_joker(player, game).unwrap();
+    wwc_db::joker::set_joker(player, other).unwrap();
+
+    wwc_db::reschedule_game(game, kickoff(other).add_hours(1), Date::mock()).unwrap();
+
+    let jokers = wwc_db::joker::get_jokers(player).unwrap();
+    assert!(!jokers.contains(&game));
+    assert!(jokers.contains(&other));
+}
+
+#[test]
+fn playoff_winner_is_logged() {
+    let _db = TestDb::euro_2020();
+    let game = GameId::from(37);
+
+    wwc_db::playoff::set_playoff_winner(game, None).unwrap();
+
+    assert_eq!(
+        events(),
+        vec![DomainEvent::PlayoffWinnerSet { game, winner: None }]
+    );
+}
+
+#[test]
+fn digest_is_published_once_per_day() {
+    let _db = TestDb::new();
+    let day = NaiveDate::from_ymd_opt(2021, 6, 11).unwrap();
+
+    assert!(mark_published(day).unwrap());
+    assert!(!mark_published(day).unwrap());
+    assert!(mark_published(day.succ_opt().unwrap()).unwrap());
+}
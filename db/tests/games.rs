@@ -0,0 +1,32 @@
+mod common;
+
+use common::TestDb;
+use wwc_core::game::GameId;
+use wwc_core::group::game::UnplayedGroupGame;
+use wwc_db::DbError;
+
+fn num_games() -> usize {
+    let (played, unplayed, void) = wwc_db::get_group_games().unwrap();
+    played.len() + unplayed.len() + void.len()
+}
+
+#[test]
+fn duplicate_game_ids_are_rejected() {
+    let _db = TestDb::euro_2020();
+    let (_, unplayed, _) = wwc_db::get_group_games().unwrap();
+    let stored = unplayed[0].clone();
+    let num_stored = num_games();
+    let new_game = |id: u32| {
+        UnplayedGroupGame::try_new(GameId::from(id), stored.home, stored.away, stored.date())
+            .unwrap()
+    };
+
+    let err = wwc_db::insert_games(&[new_game(100), stored.clone()]).unwrap_err();
+    assert!(matches!(err, DbError::DuplicateGameId(id) if id == stored.id));
+    let err = wwc_db::insert_games(&[new_game(100), new_game(100)]).unwrap_err();
+    assert!(matches!(err, DbError::DuplicateGameId(id) if id == GameId::from(100)));
+    assert_eq!(num_games(), num_stored);
+
+    wwc_db::insert_games(&[new_game(100)]).unwrap();
+    assert_eq!(num_games(), num_stored + 1);
+}
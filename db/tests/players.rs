@@ -0,0 +1,89 @@
+mod common;
+
+use common::TestDb;
+use wwc_core::game::{GameId, Score};
+use wwc_core::player::{anonymous_name, PlayerId, PlayerPredictions, Prediction};
+use wwc_core::Date;
+use wwc_db::score::get_score_leaderboard;
+
+/// Players "Anna" and "Bert", Anna predicted the result of game 1 and Bert did not
+fn played_game() -> (PlayerId, PlayerId) {
+    wwc_db::register_player("Anna").unwrap();
+    wwc_db::register_player("Bert").unwrap();
+    let id = |name: &str| {
+        wwc_db::get_players()
+            .unwrap()
+            .into_iter()
+            .find(|player| player.name == name)
+            .map(|player| PlayerId::from(player.id))
+            .unwrap()
+    };
+    let (anna, bert) = (id("Anna"), id("Bert"));
+    let game = GameId::from(1);
+    let pred = |player, score: (u32, u32)| {
+        PlayerPredictions::new(player, vec![Prediction(game, Score::from(score))])
+    };
+    wwc_db::insert_preds(&pred(anna, (1, 0)), Date::mock()).unwrap();
+    wwc_db::insert_preds(&pred(bert, (0, 2)), Date::mock()).unwrap();
+    wwc_db::play_game(game, Score::from((1, 0))).unwrap();
+    (anna, bert)
+}
+
+#[test]
+fn erased_player_stays_on_leaderboard() {
+    let _db = TestDb::euro_2020();
+    let (anna, _) = played_game();
+    let before = get_score_leaderboard().unwrap();
+
+    wwc_db::erase_player(anna).unwrap();
+
+    let players = wwc_db::get_players().unwrap();
+    assert!(players.iter().all(|player| player.name != "Anna"));
+    assert_eq!(wwc_db::get_preds(anna).unwrap().len(), 1);
+    let after = get_score_leaderboard().unwrap();
+    assert_eq!(after.len(), before.len());
+    assert_eq!(after[0].player_id, i32::from(anna));
+    assert_eq!(after[0].player, anonymous_name(anna));
+    assert_eq!(after[0].points, before[0].points);
+}
+
+#[test]
+fn erased_name_is_free() {
+    let _db = TestDb::euro_2020();
+    let (anna, _) = played_game();
+    wwc_db::erase_player(anna).unwrap();
+    wwc_db::register_player("Anna").unwrap();
+    assert_eq!(wwc_db::get_players().unwrap().len(), 3);
+}
+
+#[test]
+fn deleted_player_leaves_leaderboard_until_restored() {
+    let _db = TestDb::euro_2020();
+    let (anna, bert) = played_game();
+
+    wwc_db::delete_player(anna, Date::mock()).unwrap();
+    let leaderboard = get_score_leaderboard().unwrap();
+    assert_eq!(
+        leaderboard
+            .iter()
+            .map(|row| row.player_id)
+            .collect::<Vec<_>>(),
+        vec![i32::from(bert)]
+    );
+    assert!(!wwc_db::is_active_player(anna).unwrap());
+
+    wwc_db::restore_player(anna).unwrap();
+    let leaderboard = get_score_leaderboard().unwrap();
+    assert_eq!(leaderboard[0].player_id, i32::from(anna));
+    assert!(wwc_db::is_active_player(anna).unwrap());
+}
+
+#[test]
+fn deleted_preds_can_be_restored() {
+    let _db = TestDb::euro_2020();
+    let (anna, _) = played_game();
+    assert_eq!(wwc_db::delete_preds(anna, Date::mock()).unwrap(), 1);
+    assert!(wwc_db::get_preds(anna).unwrap().is_empty());
+    assert_eq!(wwc_db::restore_preds(anna).unwrap(), 1);
+    assert_eq!(wwc_db::get_preds(anna).unwrap().len(), 1);
+}
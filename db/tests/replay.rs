@@ -0,0 +1,29 @@
+mod common;
+
+use common::TestDb;
+use wwc_core::game::{GameId, Score};
+use wwc_db::replay::restore_from_events;
+
+fn played_games() -> Vec<(GameId, Score)> {
+    let mut played: Vec<(GameId, Score)> = wwc_db::get_groups()
+        .unwrap()
+        .values()
+        .flat_map(|group| group.played_games())
+        .map(|game| (game.id, game.score))
+        .collect();
+    played.sort_by_key(|(game, _)| *game);
+    played
+}
+
+#[test]
+fn restore_keeps_results_from_before_the_log() {
+    let _db = TestDb::euro_2020();
+    wwc_db::play_game(GameId::from(1), Score::from((1, 0))).unwrap();
+    wwc_db::event::clear_events().unwrap();
+    wwc_db::play_game(GameId::from(2), Score::from((2, 2))).unwrap();
+    let before = played_games();
+
+    assert_eq!(restore_from_events().unwrap(), 1);
+
+    assert_eq!(played_games(), before);
+}
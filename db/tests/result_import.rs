@@ -0,0 +1,73 @@
+mod common;
+
+use common::TestDb;
+use wwc_core::event::DomainEvent;
+use wwc_core::game::{GameId, Score};
+use wwc_core::parse::result::parse_result;
+use wwc_core::parse::result_csv::{in_kickoff_order, parse_result_csv, ImportedResult};
+use wwc_db::result_import::import_results;
+
+/// Games 2 and 1, in file order
+fn results() -> Vec<ImportedResult> {
+    let csv = "game,home,away,home_goals,away_goals,cards\n2,,,1,1,\n1,,,0,3,\n";
+    let teams = wwc_db::team_alias::get_team_resolver().unwrap();
+    let rows = parse_result_csv(csv, &teams, &wwc_db::get_groups().unwrap());
+    in_kickoff_order(&rows)
+}
+
+fn posted_games() -> Vec<GameId> {
+    wwc_db::event::get_events(None, 100)
+        .unwrap()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            DomainEvent::ResultPosted { game, .. } => Some(game),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn results_are_published_in_kickoff_order() {
+    let _db = TestDb::euro_2020();
+    import_results(&results()).unwrap();
+    assert_eq!(posted_games(), vec![GameId::from(1), GameId::from(2)]);
+}
+
+#[test]
+fn failed_import_stores_nothing() {
+    let _db = TestDb::euro_2020();
+    let results = results();
+    wwc_db::play_game(GameId::from(2), Score::from((2, 0))).unwrap();
+
+    assert!(import_results(&results).is_err());
+
+    assert_eq!(posted_games(), vec![GameId::from(2)]);
+    let groups = wwc_db::get_groups().unwrap();
+    let played = groups
+        .values()
+        .flat_map(|group| group.played_games())
+        .count();
+    assert_eq!(played, 1);
+}
+
+#[test]
+fn typed_result_stores_cards() {
+    let _db = TestDb::euro_2020();
+    let teams = wwc_db::team_alias::get_team_resolver().unwrap();
+    let submission = parse_result("Italy 3-0 Turkey (yellow: tur 2)", &teams)
+        .and_then(|entry| entry.submission(&wwc_db::get_groups().unwrap()))
+        .unwrap();
+
+    wwc_db::play_game_with_cards(submission.game_id, submission.score, submission.fair_play)
+        .unwrap();
+
+    assert_eq!(posted_games(), vec![GameId::from(1)]);
+    let cards = wwc_db::fair_play::get_game_cards().unwrap();
+    assert_eq!(cards.len(), 1);
+    assert_eq!(cards[0].id, GameId::from(1));
+    assert_eq!(cards[0].events.len(), 2);
+    assert!(cards[0]
+        .events
+        .iter()
+        .all(|event| event.team == cards[0].home));
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use common::TestDb;
+use wwc_core::game::{GameId, Score};
+use wwc_core::leaderboard::{ScoringStage, Tiebreak};
+use wwc_core::player::{PlayerId, PlayerPredictions, Prediction};
+use wwc_core::pred_score::ScoringRules;
+use wwc_core::rules::TournamentRules;
+use wwc_core::team::TeamId;
+use wwc_core::Date;
+use wwc_db::score::{get_score_leaderboard, get_stage_score_leaderboard};
+
+#[test]
+fn tied_players_share_rank() {
+    let _db = TestDb::euro_2020();
+    let rules = TournamentRules {
+        scoring: ScoringRules {
+            tiebreaks: vec![Tiebreak::ExactScores],
+            ..ScoringRules::default()
+        },
+        ..TournamentRules::default()
+    };
+    wwc_db::tournament::register_tournament("Euro 2020", false, Some(&rules)).unwrap();
+    let game = GameId::from(1);
+    let preds = [
+        ("Anna", (1, 0)),
+        ("Bert", (1, 0)),
+        ("Cleo", (2, 0)),
+        ("Dave", (0, 1)),
+    ];
+    for (name, _) in &preds {
+        wwc_db::register_player(name).unwrap();
+    }
+    for player in wwc_db::get_players().unwrap() {
+        let (_, score) = preds.iter().find(|(name, _)| *name == player.name).unwrap();
+        let pred = Prediction(game, Score::from(*score));
+        let player = PlayerPredictions::new(PlayerId::from(player.id), vec![pred]);
+        wwc_db::insert_preds(&player, Date::mock()).unwrap();
+    }
+
+    wwc_db::play_game(game, Score::from((1, 0))).unwrap();
+
+    let ranks: Vec<(String, i64)> = get_score_leaderboard()
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.player, row.rank))
+        .collect();
+    let expected = [("Anna", 1), ("Bert", 1), ("Cleo", 3), ("Dave", 4)];
+    assert_eq!(
+        ranks,
+        expected
+            .iter()
+            .map(|(name, rank)| (String::from(*name), *rank))
+            .collect::<Vec<_>>()
+    );
+}
+
+/// Play every group game, the teams of the playoff `game` are then decided
+fn play_group_stage(game: GameId) -> (TeamId, TeamId) {
+    let unplayed: Vec<GameId> = wwc_db::get_groups()
+        .unwrap()
+        .values()
+        .flat_map(|group| {
+            group
+                .unplayed_games()
+                .map(|game| game.id)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    for game in unplayed {
+        wwc_db::play_game(game, Score::from((1, 0))).unwrap();
+    }
+    let node = wwc_db::playoff::get_bracket_layout()
+        .unwrap()
+        .into_iter()
+        .find(|node| node.game_id == game)
+        .unwrap();
+    (node.home.team.unwrap(), node.away.team.unwrap())
+}
+
+#[test]
+fn correct_playoff_winners_are_scored_in_the_knockout_stage() {
+    let _db = TestDb::euro_2020();
+    wwc_db::tournament::register_tournament("Euro 2020", false, None).unwrap();
+    let game = GameId::from(37);
+    let (home, away) = play_group_stage(game);
+    let winners = [("Anna", home), ("Bert", away)];
+    for (name, _) in &winners {
+        wwc_db::register_player(name).unwrap();
+    }
+    for player in wwc_db::get_players().unwrap() {
+        let (_, winner) = winners
+            .iter()
+            .find(|(name, _)| *name == player.name)
+            .unwrap();
+        wwc_db::playoff::set_playoff_pred(PlayerId::from(player.id), game, *winner).unwrap();
+    }
+
+    wwc_db::playoff::set_playoff_winner(game, Some(home)).unwrap();
+
+    let points = |rows: Vec<wwc_db::score::ScoreRow>| -> Vec<(String, f32, i32)> {
+        rows.into_iter()
+            .map(|row| (row.player, row.points, row.exact_scores))
+            .collect()
+    };
+    let per_winner = ScoringRules::default().knockout_winner;
+    let expected = vec![
+        (String::from("Anna"), per_winner, 1),
+        (String::from("Bert"), 0.0, 0),
+    ];
+    assert_eq!(points(get_score_leaderboard().unwrap()), expected);
+    assert_eq!(
+        points(get_stage_score_leaderboard(ScoringStage::Knockout).unwrap()),
+        expected
+    );
+    assert!(
+        points(get_stage_score_leaderboard(ScoringStage::GroupStage).unwrap())
+            .iter()
+            .all(|(_, points, _)| *points == 0.0)
+    );
+
+    wwc_db::playoff::set_playoff_winner(game, None).unwrap();
+
+    assert!(points(get_score_leaderboard().unwrap())
+        .iter()
+        .all(|(_, points, _)| *points == 0.0));
+}
+
+#[test]
+fn knockout_points_are_weighted_by_round() {
+    let _db = TestDb::euro_2020();
+    let rules = TournamentRules {
+        scoring: ScoringRules::default().with_bracket_weights(&[3.0]),
+        ..TournamentRules::default()
+    };
+    wwc_db::tournament::register_tournament("Euro 2020", false, Some(&rules)).unwrap();
+    // First game of the round of 16
+    let game = GameId::from(37);
+    let (home, _) = play_group_stage(game);
+    wwc_db::register_player("Anna").unwrap();
+    let player = PlayerId::from(wwc_db::get_players().unwrap()[0].id);
+    wwc_db::playoff::set_playoff_pred(player, game, home).unwrap();
+
+    wwc_db::playoff::set_playoff_winner(game, Some(home)).unwrap();
+
+    let points: Vec<f32> = get_stage_score_leaderboard(ScoringStage::Knockout)
+        .unwrap()
+        .into_iter()
+        .map(|row| row.points)
+        .collect();
+    assert_eq!(points, vec![3.0 * ScoringRules::default().knockout_winner]);
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::TestDb;
+use wwc_core::game::{GameId, Score};
+use wwc_core::group::GroupId;
+use wwc_core::rules::{PointSystem, TournamentRules};
+use wwc_db::last_result::get_last_result;
+use wwc_db::standings::get_group_standings;
+use wwc_db::tournament::{get_active_id, register_tournament, set_rules};
+
+#[test]
+fn new_rules_refresh_standings() {
+    let _db = TestDb::euro_2020();
+    register_tournament("Euro 2020", false, None).unwrap();
+    let id = get_active_id().unwrap().unwrap();
+    wwc_db::play_game(GameId::from(1), Score::from((1, 0))).unwrap();
+    let group = GroupId::try_new('A').unwrap();
+    let winner_points = || get_group_standings(group).unwrap()[0].points;
+    assert_eq!(winner_points(), 3);
+
+    let rules = TournamentRules {
+        group_rules: String::from("fifa_1990"),
+        points: PointSystem::TwoPoints,
+        ..TournamentRules::default()
+    };
+    set_rules(id, &rules).unwrap();
+
+    assert_eq!(winner_points(), 2);
+}
+
+#[test]
+fn new_rules_modify_every_group() {
+    let _db = TestDb::euro_2020();
+    register_tournament("Euro 2020", false, None).unwrap();
+    let id = get_active_id().unwrap().unwrap();
+    let group = GroupId::try_new('F').unwrap();
+    assert_eq!(get_last_result(group).unwrap(), None);
+
+    set_rules(id, &TournamentRules::default()).unwrap();
+
+    assert!(get_last_result(group).unwrap().is_some());
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::TestDb;
+use wwc_core::game::{GameId, Score};
+use wwc_core::player::{PlayerId, PlayerPredictions, Prediction};
+use wwc_core::Date;
+use wwc_db::version::get_data_version;
+
+#[test]
+fn writes_change_the_data_version() {
+    let _db = TestDb::euro_2020();
+    wwc_db::tournament::register_tournament("Euro 2020", false, None).unwrap();
+    let initial = get_data_version().unwrap();
+    assert_eq!(get_data_version().unwrap(), initial);
+
+    wwc_db::register_player("Anna").unwrap();
+    let registered = get_data_version().unwrap();
+    assert_ne!(registered, initial);
+
+    let player = PlayerId::from(wwc_db::get_players().unwrap()[0].id);
+    let game = GameId::from(1);
+    let preds = PlayerPredictions::new(player, vec![Prediction(game, Score::from((1, 0)))]);
+    wwc_db::insert_preds(&preds, Date::mock()).unwrap();
+    let predicted = get_data_version().unwrap();
+    assert_ne!(predicted, registered);
+
+    wwc_db::joker::set_joker(player, game).unwrap();
+    let joker = get_data_version().unwrap();
+    assert_ne!(joker, predicted);
+    wwc_db::joker::clear_joker(player, game).unwrap();
+    wwc_db::joker::set_joker(player, GameId::from(2)).unwrap();
+    let moved = get_data_version().unwrap();
+    assert_ne!(moved, joker);
+
+    wwc_db::play_game(game, Score::from((1, 0))).unwrap();
+    assert_ne!(get_data_version().unwrap(), moved);
+}
+
+#[test]
+fn renamed_and_erased_players_change_the_data_version() {
+    let _db = TestDb::euro_2020();
+    wwc_db::register_player("Anna").unwrap();
+    let player = PlayerId::from(wwc_db::get_players().unwrap()[0].id);
+    let registered = get_data_version().unwrap();
+
+    wwc_db::rename_player(player, "Anna B").unwrap();
+    let renamed = get_data_version().unwrap();
+    assert_ne!(renamed, registered);
+
+    wwc_db::erase_player(player).unwrap();
+    assert_ne!(get_data_version().unwrap(), renamed);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::TestDb;
+use wwc_core::event::DomainEvent;
+use wwc_core::game::{GameId, GameStatus};
+use wwc_core::group::GroupId;
+use wwc_core::team::TeamId;
+use wwc_db::withdrawal::{get_withdrawals, withdraw_team};
+
+fn group_a() -> GroupId {
+    GroupId::try_new('A').unwrap()
+}
+
+fn first_team() -> TeamId {
+    wwc_db::get_groups().unwrap()[&group_a()]
+        .teams()
+        .next()
+        .unwrap()
+}
+
+#[test]
+fn withdrawal_annuls_games_and_recomputes_standings() {
+    let _db = TestDb::euro_2020();
+    let team = first_team();
+
+    let changes = withdraw_team(team).unwrap();
+
+    assert_eq!(changes.len(), 3);
+    let group = &wwc_db::get_groups().unwrap()[&group_a()];
+    assert_eq!(group.void_games().count(), 3);
+    let standings = wwc_db::standings::get_group_standings(group_a()).unwrap();
+    let row = standings
+        .iter()
+        .find(|row| row.team_id == u32::from(team))
+        .unwrap();
+    assert_eq!(row.played, 0);
+    let events = wwc_db::event::get_events(None, 100).unwrap();
+    assert!(matches!(
+        events.last().map(|record| &record.event),
+        Some(DomainEvent::TeamWithdrawn { team: withdrawn, .. }) if *withdrawn == team
+    ));
+}
+
+#[test]
+fn second_withdrawal_changes_nothing() {
+    let _db = TestDb::euro_2020();
+    let team = first_team();
+    withdraw_team(team).unwrap();
+    let num_events = wwc_db::event::get_events(None, 100).unwrap().len();
+
+    assert!(withdraw_team(team).is_err());
+
+    assert_eq!(get_withdrawals().unwrap().len(), 1);
+    assert_eq!(
+        wwc_db::event::get_events(None, 100).unwrap().len(),
+        num_events
+    );
+}
+
+#[test]
+fn group_with_only_void_games() {
+    let _db = TestDb::euro_2020();
+    let games: Vec<GameId> = wwc_db::get_groups().unwrap()[&group_a()]
+        .games()
+        .map(|game| game.id())
+        .collect();
+    for game in &games {
+        wwc_db::set_game_status(*game, GameStatus::Annulled).unwrap();
+    }
+
+    let groups = wwc_db::get_groups().unwrap();
+    assert_eq!(groups[&group_a()].void_games().count(), games.len());
+    assert_eq!(groups[&group_a()].num_teams(), 4);
+}
@@ -0,0 +1,139 @@
+//! # Python bindings for the *wwc* core library
+//!
+//! A `wwc` Python module, for analysing the pool in e.g. Jupyter notebooks.
+//! Build and install into the current virtual env with `maturin develop`.
+//!
+//! Core types are passed as plain dicts and lists, with the same structure as the JSON of the
+//! server API, e.g. a group is
+//!
+//! ```python
+//! {
+//!     "played_games": [
+//!         {"id": 1, "home": 1, "away": 2, "score": {"home": 2, "away": 1}, "date": "2021-06-11 19:00"},
+//!     ],
+//!     "unplayed_games": [
+//!         {"id": 2, "home": 3, "away": 4, "date": "2021-06-11 21:00"},
+//!     ],
+//! }
+//! ```
+//!
+//! and teams are keyed by team id, e.g. `{1: {"id": 1, "name": "Sweden", ...}}`.
+//! The conversion goes through JSON, so the returned dicts have string keys, e.g. team id's.
+//! Group ordering rules are selected by name, see [`RULE_NAMES`].
+#![forbid(unsafe_code)]
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wwc_core::export;
+use wwc_core::goal_model::PoissonModel;
+use wwc_core::group::order::{self, AnyTiebreaker, Rules, RULE_NAMES};
+use wwc_core::group::{Group, Groups};
+use wwc_core::simulation::{simulate_n, CancellationToken};
+use wwc_core::team::Teams;
+
+/// Order a group, returns the team id's from best to worst
+#[pyfunction]
+#[pyo3(signature = (group, rules = "fifa_2018", teams = None))]
+fn order_group(group: &PyAny, rules: &str, teams: Option<&PyAny>) -> PyResult<Vec<u32>> {
+    let group: Group = from_py(group)?;
+    let teams = parse_teams(teams)?;
+    let rules = rules_by_name(rules, std::slice::from_ref(&group), &teams)?;
+    Ok(order::order_group(&group, &rules)
+        .iter()
+        .map(|id| id.0)
+        .collect())
+}
+
+/// Group table, a list of dicts with one row per team
+#[pyfunction]
+#[pyo3(signature = (group, teams, rules = "fifa_2018"))]
+fn standings(py: Python, group: &PyAny, teams: &PyAny, rules: &str) -> PyResult<PyObject> {
+    let group: Group = from_py(group)?;
+    let teams: Teams = from_py(teams)?;
+    let rules = rules_by_name(rules, std::slice::from_ref(&group), &teams)?;
+    to_py(py, &export::group_standings(&group, &teams, &rules))
+}
+
+/// Simulate the remaining group games `n` times
+///
+/// `groups` are keyed by group id, e.g. `{"A": {...}, "B": {...}}`.
+/// The scores are sampled from a Poisson model calibrated on the played games.
+/// Returns the number of times each team finished in each group position,
+/// `{"num_simulations": n, "positions": {team_id: [first, second, ...]}}`.
+#[pyfunction]
+#[pyo3(signature = (groups, n, rules = "fifa_2018", teams = None, seed = None))]
+fn simulate(
+    py: Python,
+    groups: &PyAny,
+    n: u32,
+    rules: &str,
+    teams: Option<&PyAny>,
+    seed: Option<u64>,
+) -> PyResult<PyObject> {
+    let groups: Groups = from_py(groups)?;
+    let teams = parse_teams(teams)?;
+    let all_groups: Vec<Group> = groups.values().cloned().collect();
+    let rules = rules_by_name(rules, &all_groups, &teams)?;
+    let model = PoissonModel::calibrate(groups.values().flat_map(|group| group.played_games()));
+    let summary = py
+        .allow_threads(|| {
+            simulate_n(
+                n,
+                &groups,
+                &rules,
+                &model,
+                seed,
+                &CancellationToken::default(),
+                |_| {},
+            )
+        })
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    to_py(py, &summary)
+}
+
+/// Convert a Python object to a core type, by way of JSON
+///
+/// Dict keys become strings in JSON, but e.g. team id's still parse from them.
+fn from_py<T: DeserializeOwned>(obj: &PyAny) -> PyResult<T> {
+    let json: String = obj
+        .py()
+        .import("json")?
+        .call_method1("dumps", (obj,))?
+        .extract()?;
+    serde_json::from_str(&json).map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Convert a core type to plain Python dicts and lists, by way of JSON
+fn to_py<T: Serialize>(py: Python, value: &T) -> PyResult<PyObject> {
+    let json =
+        serde_json::to_string(value).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.into())
+}
+
+fn parse_teams(teams: Option<&PyAny>) -> PyResult<Teams> {
+    Ok(match teams {
+        Some(teams) => from_py(teams)?,
+        None => Teams::new(),
+    })
+}
+
+/// Group ordering rules by name, ranking tiebreakers use the rank of the `teams`
+fn rules_by_name(name: &str, groups: &[Group], teams: &Teams) -> PyResult<Rules<AnyTiebreaker>> {
+    let ranking = teams.iter().map(|(id, team)| (*id, team.rank)).collect();
+    order::rules_by_name(name, groups, ranking).map_err(|err| {
+        PyValueError::new_err(format!(
+            "Rules '{}': {}, available rules: {:?}",
+            name, err, RULE_NAMES
+        ))
+    })
+}
+
+#[pymodule]
+fn wwc(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("RULE_NAMES", RULE_NAMES.to_vec())?;
+    m.add_function(wrap_pyfunction!(order_group, m)?)?;
+    m.add_function(wrap_pyfunction!(standings, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! Error responses
+//!
+//! Every error response has a JSON body `{"code": ..., "message": ..., "details": ...}`, see
+//! [`ErrorBody`].
+//! The code is one of [`ERROR_CODES`](wwc_core::error::ERROR_CODES), served at `GET /errors`,
+//! and is what clients should branch on. The message is for humans and the details, if any, are
+//! specific to the code, e.g. `{"game": 3}` for `PREDICTION_DEADLINE_PASSED`.
+use crate::validate::ValidationErrors;
+use crate::ServerError;
+use rocket::http::Status;
+use rocket::response::{self, Responder};
+use rocket::serde::json::{serde_json, Json, Value};
+use rocket::serde::Serialize;
+use rocket::Request;
+use wwc_core::error::ErrorCode;
+
+/// Body of an error response
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+    pub details: Option<Value>,
+}
+
+impl ErrorBody {
+    pub fn new(err: &impl ErrorCode) -> Self {
+        ErrorBody {
+            code: err.code(),
+            message: err.to_string(),
+            details: err.details(),
+        }
+    }
+}
+
+impl From<ValidationErrors> for ErrorBody {
+    fn from(errors: ValidationErrors) -> Self {
+        ErrorBody {
+            code: "VALIDATION_FAILED",
+            message: format!("{} invalid field(s)", errors.errors.len()),
+            details: Some(serde_json::to_value(errors).expect("Validation errors serialize")),
+        }
+    }
+}
+
+/// Rejected request, `404 Not Found` for a missing resource, `403 Forbidden` for acting for
+/// another player and `400 Bad Request` otherwise
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: Status,
+    pub body: Json<ErrorBody>,
+}
+
+impl From<ServerError> for ApiError {
+    fn from(server_err: ServerError) -> Self {
+        ApiError {
+            status: if server_err.is_not_found() {
+                Status::NotFound
+            } else if server_err.is_forbidden() {
+                Status::Forbidden
+            } else {
+                Status::BadRequest
+            },
+            body: Json(ErrorBody::new(&server_err)),
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        (self.status, self.body).respond_to(request)
+    }
+}
+
+/// Error body of responses without one, e.g. a missing API key or an unknown route
+#[catch(default)]
+pub fn default_catcher(status: Status, _request: &Request) -> Json<ErrorBody> {
+    let code = match status.code {
+        400 | 422 => "INVALID_INPUT",
+        401 => "UNAUTHORIZED",
+        403 => "FORBIDDEN",
+        404 => "NOT_FOUND",
+        _ => "HTTP_ERROR",
+    };
+    Json(ErrorBody {
+        code,
+        message: status.to_string(),
+        details: None,
+    })
+}
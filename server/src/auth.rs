@@ -0,0 +1,166 @@
+//! Authentication
+//!
+//! Integrations authenticate with an API key in the [`API_KEY_HEADER`], see [`ApiKeys`], and
+//! routes requiring the admin scope with the [`Admin`] guard.
+//!
+//! Players log in with personal magic links, see [`wwc_core::auth`]: an admin generates a link,
+//! `/login/<token>`, and hands it out. Visiting the link sets the [`SESSION_COOKIE`], a session
+//! token signed like the link, and routes for the logged in player use the [`Session`] guard.
+//! Links are signed with the secret in `WWC_LOGIN_SECRET`, without it the login routes fail
+//! with `LOGIN_LINKS_DISABLED`.
+use crate::{AppClock, ServerError};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Status;
+use rocket::outcome::Outcome;
+use rocket::request::{self, FromRequest};
+use rocket::{Data, Request};
+use wwc_core::api_key::{ApiKey, Scope};
+use wwc_core::auth::{LoginSecret, LoginToken, TokenKind};
+use wwc_core::player::PlayerId;
+
+/// Header with the API key of a request
+pub(crate) const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Cookie with the session token of a logged in player
+pub(crate) const SESSION_COOKIE: &str = "wwc_session";
+
+/// Scope of the API key of a request, `None` without a valid key
+#[derive(Debug, Clone, Copy)]
+struct RequestScope(Option<Scope>);
+
+/// API key authentication
+///
+/// Looks up the key in the [`API_KEY_HEADER`] of every request and attaches its scope to the
+/// request, see [`RequestScope`].
+/// Requests without a valid key are not rejected here, routes requiring a scope do so with a
+/// request guard, e.g. [`Admin`].
+pub(crate) struct ApiKeys;
+
+#[rocket::async_trait]
+impl Fairing for ApiKeys {
+    fn info(&self) -> Info {
+        Info {
+            name: "API keys",
+            kind: Kind::Request,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        let scope = request
+            .headers()
+            .get_one(API_KEY_HEADER)
+            .map(|key| ApiKey::from(String::from(key)))
+            .and_then(|key| wwc_db::api_key::get_scope(&key).ok().flatten());
+        request.local_cache(|| RequestScope(scope));
+    }
+}
+
+/// Request guard for routes requiring an API key with the [`Scope::Admin`] scope
+///
+/// Fails with `401 Unauthorized` without a valid key and `403 Forbidden` for a read-only key.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Admin;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = ();
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match request.local_cache(|| RequestScope(None)).0 {
+            Some(scope) if scope.allows(Scope::Admin) => Outcome::Success(Admin),
+            Some(_) => Outcome::Failure((Status::Forbidden, ())),
+            None => Outcome::Failure((Status::Unauthorized, ())),
+        }
+    }
+}
+
+/// Secret signing login links, `None` if not configured
+pub(crate) struct LoginSecrets(pub(crate) Option<LoginSecret>);
+
+impl LoginSecrets {
+    pub(crate) fn from_env() -> Self {
+        LoginSecrets(LoginSecret::from_env())
+    }
+}
+
+/// Request guard for routes of the logged in player
+///
+/// Fails with `401 Unauthorized` without a valid, unexpired session cookie, or if the player has
+/// been deleted since logging in.
+/// Routes acting on a given player check that it is the logged in one, see
+/// [`Session::ensure_player`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Session(pub(crate) LoginToken);
+
+impl Session {
+    /// Fails with `NOT_LOGGED_IN_AS_PLAYER`, responded with `403 Forbidden`, unless `player` is
+    /// the logged in player
+    pub(crate) fn ensure_player(&self, player: PlayerId) -> Result<(), ServerError> {
+        if self.0.player == player {
+            Ok(())
+        } else {
+            Err(ServerError::NotLoggedInAs(player))
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Session {
+    type Error = ();
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let rocket = request.rocket();
+        let session = match (
+            rocket
+                .state::<LoginSecrets>()
+                .and_then(|secrets| secrets.0.as_ref()),
+            rocket.state::<AppClock>(),
+            request.cookies().get(SESSION_COOKIE),
+        ) {
+            (Some(secret), Some(clock), Some(cookie)) => {
+                LoginToken::verify(cookie.value(), TokenKind::Session, secret, clock.now())
+                    .ok()
+                    .filter(|token| wwc_db::is_active_player(token.player).unwrap_or(false))
+            }
+            _ => None,
+        };
+        match session {
+            Some(token) => Outcome::Success(Session(token)),
+            None => Outcome::Failure((Status::Unauthorized, ())),
+        }
+    }
+}
+
+/// Request guard for routes of a player which an admin may also use, e.g. to correct the name
+/// of a player in a league
+///
+/// Succeeds with an API key with the [`Scope::Admin`] scope, see [`Admin`], or else with the
+/// session of a player, see [`Session`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum SessionOrAdmin {
+    Admin,
+    Session(Session),
+}
+
+impl SessionOrAdmin {
+    /// Fails unless the request is by an admin or `player` is the logged in player, see
+    /// [`Session::ensure_player`]
+    pub(crate) fn ensure_player(&self, player: PlayerId) -> Result<(), ServerError> {
+        match self {
+            SessionOrAdmin::Admin => Ok(()),
+            SessionOrAdmin::Session(session) => session.ensure_player(player),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SessionOrAdmin {
+    type Error = ();
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        if let Outcome::Success(Admin) = request.guard::<Admin>().await {
+            return Outcome::Success(SessionOrAdmin::Admin);
+        }
+        request
+            .guard::<Session>()
+            .await
+            .map(SessionOrAdmin::Session)
+    }
+}
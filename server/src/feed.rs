@@ -0,0 +1,192 @@
+//! Event feed
+//!
+//! The server follows the event log of the database, see [`wwc_db::event`], instead of hooking
+//! into every write route:
+//!
+//! - a rescheduled game becomes a [`Notification::GameRescheduled`]
+//! - a result, or a playoff winner, completing a matchday becomes a
+//!   [`Notification::MatchdayDigest`], published once per day, see [`wwc_db::digest`]
+//! - a changed or removed result clears the cached group orders, see [`OrderCache`]
+//!
+//! The log is read after every mutating request, see [`EventFeed`], and on every read of the
+//! notifications, so that writes by other processes, e.g. the CLI, are picked up as well.
+//! New notifications are delivered to the webhooks, see [`Webhooks`].
+use crate::webhook::Webhooks;
+use crate::{OrderCache, ServerError};
+use chrono::NaiveDate;
+use itertools::Itertools;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Method;
+use rocket::{Request, Response};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use wwc_core::event::DomainEvent;
+use wwc_core::game::GameId;
+use wwc_core::joker::matchday;
+use wwc_core::notification::Notification;
+use wwc_core::player::deadline_change_affects;
+use wwc_core::Date;
+
+/// Events read from the log at a time
+const BATCH_SIZE: i64 = 100;
+
+/// Notifications since server start, kept in memory only, consumers are expected to poll or
+/// to register a webhook
+pub(crate) struct Notifications {
+    feed: Mutex<Feed>,
+    webhooks: Webhooks,
+}
+
+struct Feed {
+    /// Id of the last event read from the log
+    last_event: Option<i32>,
+    notifications: Vec<Notification>,
+}
+
+impl Notifications {
+    /// Notifications of the events after the current end of the log
+    ///
+    /// A log which cannot be read is followed from the start, the error surfaces on the first
+    /// request instead.
+    pub(crate) fn from_log(webhooks: Webhooks) -> Self {
+        Notifications {
+            feed: Mutex::new(Feed {
+                last_event: wwc_db::event::get_last_event_id().unwrap_or(None),
+                notifications: Vec::new(),
+            }),
+            webhooks,
+        }
+    }
+
+    /// Notifications after the first `seen`
+    pub(crate) fn since(&self, seen: usize) -> Vec<Notification> {
+        let feed = self.feed.lock().expect("Notification lock poisoned");
+        feed.notifications.iter().skip(seen).cloned().collect()
+    }
+
+    /// Read the new events of the log, returns their notifications
+    ///
+    /// The position in the log only moves on success, a failed read is retried on the next call.
+    pub(crate) fn follow(&self, cache: &OrderCache) -> Result<Vec<Notification>, ServerError> {
+        let mut feed = self.feed.lock().expect("Notification lock poisoned");
+        let mut new = Vec::new();
+        loop {
+            let records = wwc_db::event::get_events(feed.last_event, BATCH_SIZE)?;
+            let last = match records.last() {
+                Some(record) => record.id,
+                None => return Ok(new),
+            };
+            let mut batch = Vec::new();
+            let mut decided = Vec::new();
+            for record in records {
+                match record.event {
+                    DomainEvent::GameRescheduled {
+                        game,
+                        old_kickoff,
+                        new_kickoff,
+                        ..
+                    } => batch.push(rescheduled(game, old_kickoff, new_kickoff)?),
+                    DomainEvent::ResultAmended { .. } | DomainEvent::GameStatusChanged { .. } => {
+                        cache.clear()
+                    }
+                    DomainEvent::ResultPosted { game, .. }
+                    | DomainEvent::PlayoffWinnerSet {
+                        game,
+                        winner: Some(_),
+                    } => decided.push(game),
+                    _ => {}
+                }
+            }
+            if !decided.is_empty() {
+                batch.extend(digests(&decided)?);
+            }
+            self.webhooks.deliver(&batch);
+            feed.notifications.extend(batch.iter().cloned());
+            feed.last_event = Some(last);
+            new.extend(batch);
+        }
+    }
+}
+
+/// Digests of the matchdays completed by the `decided` games and not yet published
+///
+/// A digest is only recorded as published once it is composed, see
+/// [`mark_published`](wwc_db::digest::mark_published).
+fn digests(decided: &[GameId]) -> Result<Vec<Notification>, ServerError> {
+    let group_kickoffs = crate::load_groups()?
+        .values()
+        .flat_map(|group| group.games().map(|game| (game.id(), game.date())))
+        .collect::<Vec<_>>();
+    let playoff_kickoffs = wwc_db::playoff::get_bracket()?
+        .games()
+        .map(|game| (game.id, game.kickoff))
+        .collect::<Vec<_>>();
+    let kickoffs: HashMap<GameId, Date> =
+        group_kickoffs.into_iter().chain(playoff_kickoffs).collect();
+    let days: BTreeSet<NaiveDate> = decided
+        .iter()
+        .filter_map(|game| kickoffs.get(game))
+        .map(|kickoff| matchday(*kickoff))
+        .collect();
+    let mut notifications = Vec::new();
+    for day in days {
+        if let Some(digest) = crate::matchday_digest(day)? {
+            let notification = crate::digest_notification(digest)?;
+            if wwc_db::digest::mark_published(day)? {
+                notifications.push(notification);
+            }
+        }
+    }
+    Ok(notifications)
+}
+
+fn rescheduled(
+    game: GameId,
+    old_kickoff: Option<Date>,
+    new_kickoff: Date,
+) -> Result<Notification, ServerError> {
+    let affected_players = wwc_db::get_game_pred_versions(game)?
+        .into_iter()
+        .into_group_map()
+        .into_iter()
+        .filter(|(_, versions)| deadline_change_affects(versions, old_kickoff, new_kickoff))
+        .map(|(player, _)| player)
+        .sorted_by_key(|player| i32::from(*player))
+        .collect();
+    Ok(Notification::GameRescheduled {
+        game,
+        old_kickoff,
+        new_kickoff,
+        affected_players,
+    })
+}
+
+/// Follow the event log after every mutating request, see [`Notifications::follow`]
+pub(crate) struct EventFeed;
+
+#[rocket::async_trait]
+impl Fairing for EventFeed {
+    fn info(&self) -> Info {
+        Info {
+            name: "Event feed",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, _: &mut Response<'r>) {
+        if matches!(
+            request.method(),
+            Method::Get | Method::Head | Method::Options
+        ) {
+            return;
+        }
+        let rocket = request.rocket();
+        if let (Some(notifications), Some(cache)) = (
+            rocket.state::<Notifications>(),
+            rocket.state::<OrderCache>(),
+        ) {
+            // A failed read is retried by the next request.
+            let _ = notifications.follow(cache);
+        }
+    }
+}
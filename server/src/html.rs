@@ -0,0 +1,32 @@
+//! Minimal HTML pages
+//!
+//! Server rendered fallback for the group tables and the leaderboard, readable in any phone
+//! browser even if the WASM frontend is broken or too heavy.
+//! Plain tables without scripts, rendered from the same rows as the CSV and JSON exports.
+use crate::ServerError;
+use askama::Template;
+use rocket::http::ContentType;
+use wwc_core::export::{LeaderboardRow, StandingRow};
+use wwc_core::group::GroupId;
+
+/// Table of a group, with links to the other groups
+#[derive(Template)]
+#[template(path = "standings.html")]
+pub(crate) struct StandingsPage {
+    pub group: GroupId,
+    pub groups: Vec<GroupId>,
+    pub rows: Vec<StandingRow>,
+}
+
+/// Leaderboard, with links to the groups
+#[derive(Template)]
+#[template(path = "leaderboard.html")]
+pub(crate) struct LeaderboardPage {
+    pub groups: Vec<GroupId>,
+    pub rows: Vec<LeaderboardRow>,
+}
+
+/// Render a page as an HTML response
+pub(crate) fn render<T: Template>(page: &T) -> Result<(ContentType, String), ServerError> {
+    Ok((ContentType::HTML, page.render()?))
+}
@@ -1,135 +1,2341 @@
 #[macro_use]
 extern crate rocket;
+mod api_error;
+mod auth;
+mod feed;
+mod html;
+mod negotiate;
+mod read_only;
+#[cfg(test)]
+mod tests;
+mod validate;
+mod version;
+mod webhook;
+use api_error::{ApiError, ErrorBody};
+use auth::{Admin, ApiKeys, LoginSecrets, Session, SessionOrAdmin, SESSION_COOKIE};
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use feed::{EventFeed, Notifications};
+use html::{LeaderboardPage, StandingsPage};
 use itertools::Itertools;
-use rocket::http::Method;
-use rocket::{Rocket, Build};
-use rocket::response::status::BadRequest;
-use rocket::serde::{Serialize, json::Json};
+use negotiate::{Format, Negotiated};
+use read_only::{guard_writes, ReadOnly};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{ContentType, Cookie, CookieJar, Method, Status};
+use rocket::request::{self, FromRequest};
+use rocket::response::{self, Responder, Response};
+use rocket::serde::{json::Json, Serialize};
+use rocket::{Build, Request, Rocket, Route, State};
 use rocket_cors::{Cors, CorsOptions};
+use serde::Deserialize;
 use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::io::Cursor;
+use std::sync::Mutex;
 use thiserror::Error;
-use wwc_core::error::WwcError;
-use wwc_core::game::GameId;
-use wwc_core::group::{game::PlayedGroupGame, game::UnplayedGroupGame, Group, GroupId, Groups};
-use wwc_core::player::{PlayerId, PlayerPredictions, Prediction};
-use wwc_core::team::Teams;
+use validate::validate;
+use version::{ApiVersion, ApiVersions, Versioning, VERSION_HEADERS};
+use webhook::Webhooks;
+use wwc_core::achievement::Badge;
+use wwc_core::api_key::{ApiKey, ApiKeyInfo, Scope};
+use wwc_core::auth::{
+    LoginError, LoginToken, TokenKind, DEFAULT_LINK_VALIDITY_HOURS, LOGIN_SECRET_VAR,
+    SESSION_VALIDITY_HOURS,
+};
+use wwc_core::consensus::{consensus, heatmap, winner_consensus, Consensus, ScoreHeatmap};
+use wwc_core::digest::{digest, Digest};
+use wwc_core::error::{ErrorCode, ErrorCodeInfo, WwcError, ERROR_CODES};
+use wwc_core::export::{
+    self, GroupStandings, GroupTable, LeaderboardRow, ScheduleRow, StandingRow,
+};
+use wwc_core::fair_play::{fair_play_award, CardEvent, FairPlayStanding};
+use wwc_core::fairness::FairnessReport;
+use wwc_core::game::{GameId, GameStatus, GoalCount, Score};
+use wwc_core::goal_model::PoissonModel;
+use wwc_core::group::history::StandingsSnapshot;
+use wwc_core::group::order::{AnyTiebreaker, Rules};
+use wwc_core::group::order_cache::GroupOrderCache;
+use wwc_core::group::projection::{project_group, ScoreProjection};
+use wwc_core::group::stats::{fun_stats, FunStats};
+use wwc_core::group::withdrawal::WithdrawalChange;
+use wwc_core::group::{Group, GroupError, GroupId, Groups};
+use wwc_core::hall_of_fame::{hall_of_fame, Aggregation, AggregationError, HallOfFameEntry};
+use wwc_core::invite::{Invite, InviteToken, DEFAULT_VALIDITY_HOURS};
+use wwc_core::joker::{validate_clear, validate_joker, JokerError, PlayerJokers};
+use wwc_core::leaderboard::{
+    knockout_standings, late_joiner_points, project, provisional_standings, stage_standings,
+    standings, standings_with_compensation, LateJoiner, Projection, ProvisionalStanding,
+    ScoringStage, StageStandings, Standing,
+};
+use wwc_core::notification::Notification;
+use wwc_core::odds::{Odds, OddsBonus};
+use wwc_core::parse::result::parse_result;
+use wwc_core::player::{
+    upcoming_games, validate_predictions, LeagueAlias, Player, PlayerId, PlayerPredictions,
+    Prediction, PredictionReport, Profile, UpcomingGame,
+};
+use wwc_core::playoff::bracket::{BracketGame, BracketNode};
+use wwc_core::playoff::prediction::SlotComparison;
+use wwc_core::pred::strategy::{
+    autofill, AutoPick, MostLikely, Pick, RandomPick, RankedStrategy, Strategy,
+};
+use wwc_core::pred_score::{PredScoreFn, ScoringRules};
+use wwc_core::rules::{PointSystem, TournamentRules};
+use wwc_core::simulation::simulate_scores;
+use wwc_core::team::i18n::{Lang, LangError};
+use wwc_core::team::{TeamId, TeamRank, Teams};
+use wwc_core::top_scorer::{leaders, top_scorers, TopScorer};
+use wwc_core::utils::clock::{Clock, SystemClock};
+use wwc_core::utils::date::DateError;
+use wwc_core::Date;
+use wwc_data::template::{Template, TemplateError};
+use wwc_db::event::EventRecord;
+use wwc_db::game_query::{GameQuery, Page};
+use wwc_db::integrity::IntegrityReport;
+use wwc_db::models::{Game, League, Tournament};
+use wwc_db::replay::ReplayReport;
+use wwc_db::score::ScoreRow;
+use wwc_db::standings::StandingsMismatch;
+use wwc_db::strategy::ArchivedStrategy;
+use wwc_db::version::DataVersion;
+use wwc_db::withdrawal::TeamWithdrawal;
 
 /// Save preds
+///
+/// Requires the session of the player.
 #[put("/save_preds", format = "application/json", data = "<player_preds>")]
-fn save_preds(player_preds: Json<PlayerPredictions>) -> Result<(), BadRequest<String>> {
+fn save_preds(
+    session: Session,
+    player_preds: Json<PlayerPredictions>,
+    clock: &State<AppClock>,
+) -> Result<(), WriteError> {
     let player_preds = player_preds.into_inner();
+    session.ensure_player(player_preds.id)?;
     println!("Preds:\n{:?}", player_preds);
-    let tmp = wwc_db::insert_preds(&player_preds)
+    validate(&player_preds)?;
+    wwc_db::insert_preds(&player_preds, clock.now()).map_err(ServerError::from)?;
+    Ok(())
+}
+
+/// Get teams
+///
+/// Team names are in English, or in `lang` (e.g. "sv") where there is a translation.
+#[get("/get_teams?<lang>")]
+fn get_teams(lang: Option<&str>) -> Result<Json<Teams>, ApiError> {
+    Ok(Json(load_teams(lang).map_err(ApiError::from)?))
+}
+
+/// Alternative team names, the translated names and the stored aliases, as `[name, team]` pairs
+///
+/// With the teams, clients resolve typed team names like the server,
+/// see [`TeamResolver`](wwc_core::team::resolve::TeamResolver).
+#[get("/teams/aliases")]
+fn get_team_aliases() -> Result<Json<Vec<(String, TeamId)>>, ApiError> {
+    let aliases = wwc_db::team_alias::get_resolver_aliases()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(aliases))
+}
+
+/// Get players
+#[get("/players")]
+fn get_players() -> Result<Json<Vec<Player>>, ApiError> {
+    let players = wwc_db::get_players()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|player| Player::new(PlayerId::from(player.id), player.name))
+        .collect();
+    Ok(Json(players))
+}
+
+/// Get player preferences
+#[get("/players/<id>/profile")]
+fn get_profile(id: i32) -> Result<Json<Profile>, ApiError> {
+    let profile = wwc_db::get_profile(PlayerId::from(id))
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(profile))
+}
+
+/// Badges earned by a player, in the order they were earned
+#[get("/players/<id>/achievements")]
+fn get_achievements(id: i32) -> Result<Json<Vec<Badge>>, ApiError> {
+    let badges = wwc_db::achievement::get_achievements(PlayerId::from(id))
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(badges))
+}
+
+/// Update player preferences, requires the session of the player
+#[put(
+    "/players/<id>/profile",
+    format = "application/json",
+    data = "<profile>"
+)]
+fn put_profile(session: Session, id: i32, profile: Json<Profile>) -> Result<(), WriteError> {
+    session.ensure_player(PlayerId::from(id))?;
+    let profile = profile.into_inner();
+    validate(&profile)?;
+    Ok(wwc_db::set_profile(PlayerId::from(id), &profile).map_err(ServerError::from)?)
+}
+
+#[derive(Deserialize)]
+struct Rename {
+    name: String,
+}
+
+/// Change the display name of a player, e.g. `{"name": "Anna"}`
+/// Requires an admin API key.
+#[put("/players/<id>/name", format = "application/json", data = "<rename>")]
+fn put_player_name(_admin: Admin, id: i32, rename: Json<Rename>) -> Result<(), WriteError> {
+    let rename = rename.into_inner();
+    validate(&rename)?;
+    Ok(wwc_db::rename_player(PlayerId::from(id), rename.name.trim()).map_err(ServerError::from)?)
+}
+
+/// Delete the account of a player
+///
+/// The player is soft-deleted and can be restored by an admin, see [`wwc_db::delete_player`]
+/// and [`restore_player`].
+/// Requires an admin API key.
+#[delete("/players/<id>")]
+fn delete_player(_admin: Admin, id: i32, clock: &State<AppClock>) -> Result<(), ApiError> {
+    wwc_db::delete_player(PlayerId::from(id), clock.now())
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)
+}
+
+/// Erase the personal data of a player, e.g. on a GDPR request
+///
+/// The player is anonymized, the predictions still count in the leaderboards,
+/// see [`wwc_db::erase_player`]. Cannot be undone.
+/// Requires the session of the player or an admin API key.
+#[post("/players/<id>/erase")]
+fn erase_player(actor: SessionOrAdmin, id: i32) -> Result<(), ApiError> {
+    let player = PlayerId::from(id);
+    actor.ensure_player(player)?;
+    wwc_db::erase_player(player)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)
+}
+
+/// Delete the predictions of a player, responds with the number of deleted versions
+///
+/// The predictions are soft-deleted and can be restored by an admin,
+/// see [`wwc_db::delete_preds`] and [`restore_player_preds`].
+/// Requires the session of the player or an admin API key.
+#[delete("/players/<id>/preds")]
+fn delete_player_preds(
+    actor: SessionOrAdmin,
+    id: i32,
+    clock: &State<AppClock>,
+) -> Result<Json<usize>, ApiError> {
+    actor.ensure_player(PlayerId::from(id))?;
+    Ok(Json(
+        wwc_db::delete_preds(PlayerId::from(id), clock.now())
+            .map_err(ServerError::from)
+            .map_err(ApiError::from)?,
+    ))
+}
+
+/// Soft-deleted players, see [`delete_player`]
+#[get("/admin/players/deleted")]
+fn get_deleted_players(_admin: Admin) -> Result<Json<Vec<wwc_db::models::Player>>, ApiError> {
+    Ok(Json(
+        wwc_db::get_deleted_players()
+            .map_err(ServerError::from)
+            .map_err(ApiError::from)?,
+    ))
+}
+
+/// Restore a soft-deleted player, see [`delete_player`]
+#[post("/admin/players/<id>/restore")]
+fn restore_player(_admin: Admin, id: i32) -> Result<(), ApiError> {
+    wwc_db::restore_player(PlayerId::from(id))
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)
+}
+
+/// Restore the soft-deleted predictions of a player, see [`delete_player_preds`]
+///
+/// Responds with the number of restored versions.
+#[post("/admin/players/<id>/preds/restore")]
+fn restore_player_preds(_admin: Admin, id: i32) -> Result<Json<usize>, ApiError> {
+    Ok(Json(
+        wwc_db::restore_preds(PlayerId::from(id))
+            .map_err(ServerError::from)
+            .map_err(ApiError::from)?,
+    ))
+}
+
+/// Restore the predictions of every player, after [`clear_preds`]
+///
+/// Responds with the number of restored versions.
+#[post("/admin/preds/restore")]
+fn restore_all_preds(_admin: Admin) -> Result<Json<usize>, ApiError> {
+    Ok(Json(
+        wwc_db::restore_all_preds()
+            .map_err(ServerError::from)
+            .map_err(ApiError::from)?,
+    ))
+}
+
+/// Get the schedule
+///
+/// Kickoff times are in the time zone of `player`, UTC if not given or not set in the profile.
+/// With `today`, only games kicking off today (in the same time zone) are included.
+#[get("/schedule?<player>&<today>")]
+fn get_schedule(
+    player: Option<i32>,
+    today: Option<bool>,
+    clock: &State<AppClock>,
+) -> Result<Json<Vec<ScheduleRow>>, ApiError> {
+    Ok(Json(
+        schedule(
+            player.map(PlayerId::from),
+            today.unwrap_or(false),
+            clock.now(),
+        )
+        .map_err(ApiError::from)?,
+    ))
+}
+
+/// Get the schedule as an iCalendar file, for subscribing from a calendar app
+///
+/// Same games as [`get_schedule`], kickoff times are always in UTC.
+#[get("/schedule.ics")]
+fn get_schedule_ics(clock: &State<AppClock>) -> Result<(ContentType, String), ApiError> {
+    let now = clock.now();
+    let rows = schedule(None, false, now).map_err(ApiError::from)?;
+    Ok((ContentType::Calendar, export::to_ics(&rows, now)))
+}
+
+fn schedule(
+    player: Option<PlayerId>,
+    today: bool,
+    now: Date,
+) -> Result<Vec<ScheduleRow>, ServerError> {
+    let timezone = match player {
+        Some(player) => wwc_db::get_profile(player)?
+            .timezone
+            .map(|timezone| parse_timezone(&timezone))
+            .transpose()?,
+        None => None,
+    }
+    .unwrap_or(Tz::UTC);
+    let groups = load_groups()?;
+    let teams: Teams = wwc_db::get_teams()?.map(|x| (x.id, x)).collect();
+    let venues = wwc_db::venue::get_game_venues()?;
+    let now = now.with_timezone(&timezone);
+    Ok(export::schedule(&groups, &teams, &venues)
+        .into_iter()
+        .map(|row| ScheduleRow {
+            kickoff: row.kickoff.with_timezone(&timezone),
+            ..row
+        })
+        .filter(|row| !today || row.kickoff.day() == now.day())
+        .collect())
+}
+
+fn parse_timezone(timezone: &str) -> Result<Tz, ServerError> {
+    timezone
+        .parse()
+        .map_err(|_| ServerError::Invalid(format!("Unknown time zone '{}'", timezone)))
+}
+
+/// Get predictions
+#[get("/get_preds/<player_id>")]
+fn get_preds(player_id: i32) -> Result<Json<Vec<Prediction>>, ApiError> {
+    let preds = wwc_db::get_preds(PlayerId::from(player_id))
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(preds))
+}
+
+/// Predictions by the strategy of a player in the latest archived tournament, for the unplayed
+/// group games
+///
+/// A starting point for the player to edit and save, nothing is stored,
+/// see [`wwc_db::strategy`].
+#[get("/players/<id>/preds/strategy")]
+fn get_strategy_preds(id: i32) -> Result<Json<StrategyPreds>, ApiError> {
+    strategy_preds(PlayerId::from(id))
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct StrategyPreds {
+    #[serde(flatten)]
+    archived: ArchivedStrategy,
+    preds: Vec<Prediction>,
+}
+
+fn strategy_preds(player: PlayerId) -> Result<StrategyPreds, ServerError> {
+    let archived = wwc_db::strategy::get_strategy(player)?
+        .ok_or_else(|| ServerError::NotFound(format!("Strategy of player {}", player)))?;
+    let groups = load_groups()?;
+    let preds = archived.strategy.predict(
+        groups.values().flat_map(|group| group.unplayed_games()),
+        &wwc_db::strategy::get_ranking()?,
+    );
+    Ok(StrategyPreds { archived, preds })
+}
+
+/// Clear predictions
+///
+/// The predictions are soft-deleted and can be restored by an admin, see [`restore_all_preds`].
+/// Requires an admin API key.
+#[delete("/clear_preds")]
+fn clear_preds(_admin: Admin, clock: &State<AppClock>) -> Result<(), ApiError> {
+    let preds = wwc_db::clear_preds(clock.now())
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    println!("Clear preds res {:?}", preds);
+    Ok(())
+}
+
+/// Get top scorers
+///
+/// The list is derived from all goal events, most goals first.
+#[get("/stats/top-scorers")]
+fn get_top_scorers() -> Result<Json<Vec<TopScorer>>, ApiError> {
+    let events = wwc_db::get_goal_events()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(top_scorers(&events)))
+}
+
+/// Get the players sharing the first position of the top scorers, see [`leaders`]
+#[get("/stats/top-scorers/leaders")]
+fn get_top_scorer_leaders() -> Result<Json<Vec<TopScorer>>, ApiError> {
+    let events = wwc_db::get_goal_events()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(leaders(&events)))
+}
+
+/// Fair play ranking and award, see [`fair_play_ranking`](wwc_core::fair_play::fair_play_ranking)
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct FairPlayTable {
+    /// Best eligible team, `None` without played games
+    award: Option<TeamId>,
+    ranking: Vec<FairPlayStanding>,
+}
+
+/// Get the fair play ranking of every team, from the cards of all played games
+///
+/// Once the group stage is complete, only the teams in the knockout stage are eligible for the
+/// award.
+#[get("/stats/fair-play")]
+fn get_fair_play() -> Result<Json<FairPlayTable>, ApiError> {
+    let ranking = wwc_db::fair_play::get_fair_play_ranking()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(FairPlayTable {
+        award: fair_play_award(&ranking).map(|standing| standing.team),
+        ranking,
+    }))
+}
+
+/// Get trivia stats for every group
+#[get("/stats/groups")]
+fn get_group_fun_stats() -> Result<Json<BTreeMap<GroupId, FunStats>>, ApiError> {
+    let groups = load_groups().map_err(ApiError::from)?;
+    Ok(Json(
+        groups
+            .iter()
+            .map(|(id, group)| (*id, fun_stats(group)))
+            .collect(),
+    ))
+}
+
+/// Number of simulated tournaments for the leaderboard projection
+const NUM_PROJECTION_SIMULATIONS: u32 = 1000;
+
+/// Projected final leaderboard
+///
+/// The expected points from the remaining group games are estimated by simulating them with a
+/// goal model calibrated on the played games.
+#[get("/leaderboard/projection")]
+fn get_leaderboard_projection() -> Result<Json<Vec<Projection>>, ApiError> {
+    let groups = load_groups().map_err(ApiError::from)?;
+    let results = game_results(&groups);
+    let model = PoissonModel::calibrate(groups.values().flat_map(|group| group.played_games()));
+    let outcomes = simulate_scores(NUM_PROJECTION_SIMULATIONS, &groups, &model, None);
+    let players = load_player_preds().map_err(ApiError::from)?;
+    let jokers = wwc_db::joker::get_all_jokers()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    let rules = load_rules().map_err(ApiError::from)?;
+    Ok(Json(project(
+        &players,
+        &jokers,
+        &results,
+        &outcomes,
+        &rules.scoring,
+    )))
+}
+
+/// Provisional leaderboard
+///
+/// Adds the points of the games in progress, with their current live score, to the settled
+/// points of the played games.
+#[get("/leaderboard/provisional")]
+fn get_provisional_leaderboard() -> Result<Json<Vec<ProvisionalStanding>>, ApiError> {
+    let groups = load_groups().map_err(ApiError::from)?;
+    let results = game_results(&groups);
+    let live = wwc_db::live::get_live_scores()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    let players = load_player_preds().map_err(ApiError::from)?;
+    let jokers = wwc_db::joker::get_all_jokers()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    let rules = load_rules().map_err(ApiError::from)?;
+    Ok(Json(provisional_standings(
+        &players,
+        &jokers,
+        &results,
+        &live,
+        &rules.scoring,
+    )))
+}
+
+/// Leaderboard aggregated by the db, from the scores stored when the results were entered
+///
+/// An alternative to `/leaderboard`, which derives the points on every request.
+#[get("/leaderboard/materialized")]
+fn get_materialized_leaderboard() -> Result<Json<Vec<ScoreRow>>, ApiError> {
+    wwc_db::score::get_score_leaderboard()
+        .map(Json)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)
+}
+
+/// Leaderboards of the group stage, the knockout stage and their total
+///
+/// The knockout stage scores the predicted winners of the playoff games, with the
+/// `knockout_winner` points of the tournament scoring rules, weighted by the round, so that the
+/// pool can give a prize for every stage.
+#[get("/leaderboard/stages")]
+fn get_stage_leaderboards() -> Result<Json<StageStandings>, ApiError> {
+    current_stage_standings().map(Json).map_err(ApiError::from)
+}
+
+/// Leaderboard of a single stage: 'group_stage', 'knockout' or 'total',
+/// see [`get_stage_leaderboards`]
+#[get("/leaderboard/stages/<stage>")]
+fn get_stage_leaderboard(stage: &str) -> Result<Json<Vec<Standing>>, ApiError> {
+    let stage = match stage {
+        "total" => None,
+        "group_stage" => Some(ScoringStage::GroupStage),
+        "knockout" => Some(ScoringStage::Knockout),
+        _ => {
+            return Err(ApiError::from(ServerError::Invalid(format!(
+                "Unknown stage '{}', expected 'group_stage', 'knockout' or 'total'",
+                stage
+            ))))
+        }
+    };
+    let stages = current_stage_standings().map_err(ApiError::from)?;
+    Ok(Json(stages.stage(stage).to_vec()))
+}
+
+fn current_stage_standings() -> Result<StageStandings, ServerError> {
+    let group_stage = group_stage_standings()?;
+    let scoring = load_rules()?.scoring;
+    let players: Vec<PlayerId> = wwc_db::get_players()?
+        .into_iter()
+        .map(|player| PlayerId::from(player.id))
+        .collect();
+    let knockout = knockout_stage_standings(&players, &scoring)?;
+    Ok(stage_standings(group_stage, knockout, &scoring.tiebreaks))
+}
+
+/// Knockout leaderboard of the `players`, see [`knockout_standings`]
+fn knockout_stage_standings(
+    players: &[PlayerId],
+    scoring: &ScoringRules,
+) -> Result<Vec<Standing>, ServerError> {
+    let predictions = players
+        .iter()
+        .map(|id| wwc_db::playoff::get_playoff_prediction(*id).map(|prediction| (*id, prediction)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(knockout_standings(
+        &predictions,
+        &bracket_layout()?,
+        |round| scoring.winner_points(round),
+        &scoring.tiebreaks,
+    ))
+}
+
+/// Current leaderboard
+///
+/// The total of the group stage and the knockout stage, see [`get_stage_leaderboards`].
+/// Includes the maximum attainable points of every player and whether they can still win.
+/// In the format of the `Accept` header, see [`negotiate`]. Other formats than JSON have the
+/// columns of the CSV export.
+#[get("/leaderboard")]
+fn get_leaderboard(
+    format: Format,
+    if_none_match: IfNoneMatch,
+) -> Result<Tagged<Negotiated<Vec<Standing>>>, ApiError> {
+    data_version()
+        .and_then(|version| {
+            Tagged::derived(&(version, format), &if_none_match, || {
+                Ok(match format {
+                    Format::Json => Negotiated::json(current_standings()?),
+                    Format::Csv => {
+                        Negotiated::other(ContentType::CSV, export::to_csv(&leaderboard_rows()?)?)
+                    }
+                    Format::Html => {
+                        let (content_type, html) = leaderboard_page(leaderboard_rows()?)?;
+                        Negotiated::other(content_type, html)
+                    }
+                    Format::Text => Negotiated::other(
+                        ContentType::Plain,
+                        export::to_ascii_table(&leaderboard_rows()?),
+                    ),
+                })
+            })
+        })
+        .map_err(ApiError::from)
+}
+
+fn current_standings() -> Result<Vec<Standing>, ServerError> {
+    Ok(current_stage_standings()?.total)
+}
+
+fn group_stage_standings() -> Result<Vec<Standing>, ServerError> {
+    let groups = load_groups()?;
+    let results = game_results(&groups);
+    let remaining = remaining_games(&groups);
+    let players = load_player_preds()?;
+    let jokers = wwc_db::joker::get_all_jokers()?;
+    Ok(standings(
+        &players,
+        &jokers,
+        &results,
+        &remaining,
+        &load_rules()?.scoring,
+    ))
+}
+
+/// Leaderboard across the archived tournaments
+///
+/// The points of every tournament are normalized by the points of its winner, then summed or
+/// averaged with `aggregation`: 'sum' (default) or 'average'.
+#[get("/hall-of-fame?<aggregation>")]
+fn get_hall_of_fame(aggregation: Option<&str>) -> Result<Json<Vec<HallOfFameEntry>>, ApiError> {
+    let aggregation: Aggregation = aggregation
+        .map(str::parse)
+        .transpose()
+        .map_err(|err: AggregationError| ServerError::Invalid(err.to_string()))
+        .map_err(ApiError::from)?
+        .unwrap_or(Aggregation::Sum);
+    let results = wwc_db::hall_of_fame::get_tournament_results()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(hall_of_fame(&results, aggregation)))
+}
+
+/// Get leagues
+#[get("/leagues")]
+fn get_leagues() -> Result<Json<Vec<League>>, ApiError> {
+    let leagues = wwc_db::league::get_leagues()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(leagues))
+}
+
+/// Get the scoring rules of a league
+#[get("/leagues/<id>/rules")]
+fn get_league_rules(id: i32) -> Result<Json<ScoringRules>, ApiError> {
+    let rules = wwc_db::league::get_scoring_rules(id)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(rules))
+}
+
+/// Change the scoring rules of a league
+///
+/// Applies to all played games, the leaderboard is always derived from the current rules.
+/// Requires an admin API key.
+#[put("/leagues/<id>/rules", format = "application/json", data = "<rules>")]
+fn put_league_rules(_admin: Admin, id: i32, rules: Json<ScoringRules>) -> Result<(), WriteError> {
+    let rules = rules.into_inner();
+    validate(&rules)?;
+    Ok(wwc_db::league::set_scoring_rules(id, &rules).map_err(ServerError::from)?)
+}
+
+/// Leaderboard of a league
+///
+/// Only the members of the league, scored with the rules of the league, the total of the group
+/// stage and the knockout stage like `/leaderboard`.
+/// Members who joined after the first kickoff get points for the games they missed by the late
+/// joiner rule of the league, see [`late_joiner_points`].
+/// Every row has the name and emoji of the player in the league, if set, see [`LeagueAlias`].
+#[get("/leagues/<id>/leaderboard")]
+fn get_league_leaderboard(
+    id: i32,
+    if_none_match: IfNoneMatch,
+) -> Result<Tagged<Json<Vec<LeagueStanding>>>, ApiError> {
+    league_version(id)
+        .and_then(|version| {
+            Tagged::derived(&version, &if_none_match, || {
+                let mut aliases = wwc_db::league::get_member_aliases(id)?;
+                let rows = league_standings(id)?
+                    .into_iter()
+                    .map(|standing| LeagueStanding {
+                        alias: aliases.remove(&standing.player).unwrap_or_default(),
+                        standing,
+                    })
+                    .collect();
+                Ok(Json(rows))
+            })
+        })
+        .map_err(ApiError::from)
+}
+
+/// Version of the leaderboard of a league: the data, and the rules and members of the league
+///
+/// Includes the odds if the league has an odds bonus, see [`league_standings`].
+fn league_version(id: i32) -> Result<impl Serialize, ServerError> {
+    let rules = wwc_db::league::get_scoring_rules(id)?;
+    let odds: Option<Vec<(GameId, Odds)>> = match rules.odds_bonus {
+        Some(_) => Some(
+            wwc_db::odds::get_odds()?
+                .into_iter()
+                .sorted_by_key(|(game, _)| *game)
+                .collect(),
+        ),
+        None => None,
+    };
+    let joined: Vec<(PlayerId, Date)> = wwc_db::league::get_join_times(id)?
+        .into_iter()
+        .sorted_by_key(|(player, _)| i32::from(*player))
+        .collect();
+    let aliases: Vec<(PlayerId, LeagueAlias)> = wwc_db::league::get_member_aliases(id)?
+        .into_iter()
+        .sorted_by_key(|(player, _)| i32::from(*player))
+        .collect();
+    Ok((data_version()?, rules, odds, joined, aliases))
+}
+
+/// Row of the leaderboard of a league
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct LeagueStanding {
+    #[serde(flatten)]
+    standing: Standing,
+    #[serde(flatten)]
+    alias: LeagueAlias,
+}
+
+/// Set the name and emoji of a player in a league, e.g. `{"display_name": "Zlatan_4ever", "emoji": "🦁"}`
+///
+/// Unset fields fall back to the name and avatar of the player.
+/// The name is trimmed before it is validated.
+/// Requires the session of the player or an admin API key.
+#[put(
+    "/leagues/<id>/members/<player>/alias",
+    format = "application/json",
+    data = "<alias>"
+)]
+fn put_member_alias(
+    actor: SessionOrAdmin,
+    id: i32,
+    player: i32,
+    alias: Json<LeagueAlias>,
+) -> Result<(), WriteError> {
+    actor.ensure_player(PlayerId::from(player))?;
+    let alias = alias.into_inner();
+    let alias = LeagueAlias {
+        display_name: alias.display_name.map(|name| String::from(name.trim())),
+        ..alias
+    };
+    validate(&alias)?;
+    Ok(
+        wwc_db::league::set_member_alias(id, PlayerId::from(player), &alias)
+            .map_err(ServerError::from)?,
+    )
+}
+
+fn league_standings(id: i32) -> Result<Vec<Standing>, ServerError> {
+    let rules = wwc_db::league::get_scoring_rules(id)?;
+    let members = wwc_db::league::get_members(id)?;
+    let groups = load_groups()?;
+    let players: Vec<_> = load_player_preds()?
+        .into_iter()
+        .filter(|(player, _)| members.contains(player))
+        .collect();
+    let jokers = wwc_db::joker::get_all_jokers()?;
+    let joined = wwc_db::league::get_join_times(id)?;
+    let table = LeagueTable {
+        players: &players,
+        jokers: &jokers,
+        joined: &joined,
+        groups: &groups,
+        late_joiner: rules.late_joiner,
+    };
+    let knockout = knockout_stage_standings(&members, &rules)?;
+    let tiebreaks = rules.tiebreaks.clone();
+    let group_stage = match rules.odds_bonus {
+        Some(factor) => {
+            let odds = wwc_db::odds::get_odds()?;
+            table.standings(&OddsBonus::new(rules, &odds, factor))
+        }
+        None => table.standings(&rules),
+    };
+    Ok(stage_standings(group_stage, knockout, &tiebreaks).total)
+}
+
+/// Input of the leaderboard of a league, see [`league_standings`]
+struct LeagueTable<'a> {
+    players: &'a [(PlayerId, Vec<Prediction>)],
+    jokers: &'a PlayerJokers,
+    joined: &'a HashMap<PlayerId, Date>,
+    groups: &'a Groups,
+    late_joiner: LateJoiner,
+}
+
+impl LeagueTable<'_> {
+    fn standings<S: PredScoreFn>(&self, score_fn: &S) -> Vec<Standing> {
+        let (results, remaining) = (game_results(self.groups), remaining_games(self.groups));
+        let kickoffs: HashMap<GameId, Date> = self
+            .groups
+            .values()
+            .flat_map(|group| group.games())
+            .map(|game| (game.id(), game.date()))
+            .collect();
+        let compensation = late_joiner_points(
+            self.late_joiner,
+            self.players,
+            self.joined,
+            &kickoffs,
+            &results,
+            score_fn,
+        );
+        standings_with_compensation(
+            self.players,
+            &compensation,
+            self.jokers,
+            &results,
+            &remaining,
+            score_fn,
+        )
+    }
+}
+
+/// Create a single-use invite to a league
+///
+/// Valid for `hours`, one week if not given. Hand out the token, see [`register`].
+/// Requires an admin API key.
+#[post("/leagues/<id>/invites?<hours>")]
+fn post_league_invite(
+    _admin: Admin,
+    id: i32,
+    hours: Option<u32>,
+    clock: &State<AppClock>,
+) -> Result<Json<Invite>, ApiError> {
+    let expires_at = clock
+        .now()
+        .add_hours(hours.unwrap_or(DEFAULT_VALIDITY_HOURS));
+    let invite = wwc_db::invite::create_invite(id, expires_at)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(invite))
+}
+
+/// Invites to a league, used and expired ones included
+#[get("/leagues/<id>/invites")]
+fn get_league_invites(_admin: Admin, id: i32) -> Result<Json<Vec<Invite>>, ApiError> {
+    let invites = wwc_db::invite::get_invites(id)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(invites))
+}
+
+#[derive(Deserialize)]
+struct Registration {
+    token: InviteToken,
+    name: String,
+}
+
+/// Register a new player with an invite, e.g. `{"token": "...", "name": "Anna"}`
+///
+/// The player joins the league of the invite, and the invite is used up.
+/// Returns the id of the new player.
+#[post("/register", format = "application/json", data = "<registration>")]
+fn register(
+    registration: Json<Registration>,
+    clock: &State<AppClock>,
+) -> Result<Json<PlayerId>, WriteError> {
+    let registration = registration.into_inner();
+    validate(&registration)?;
+    let player =
+        wwc_db::invite::redeem_invite(&registration.token, registration.name.trim(), clock.now())
+            .map_err(ServerError::from)?;
+    Ok(Json(player))
+}
+
+/// Personal login link of a player
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct LoginLink {
+    player: PlayerId,
+    /// Path of the link, see [`login`]
+    link: String,
+    expires_at: Date,
+}
+
+/// Create a personal login link for a player, see [`auth`]
+///
+/// Valid for `hours`, two weeks if not given. Hand out the link, visiting it logs the player in.
+/// Requires an admin API key.
+#[post("/players/<id>/login-link?<hours>")]
+fn post_login_link(
+    _admin: Admin,
+    id: i32,
+    hours: Option<u32>,
+    secrets: &State<LoginSecrets>,
+    clock: &State<AppClock>,
+) -> Result<Json<LoginLink>, ApiError> {
+    let secret = secrets.0.as_ref().ok_or(ServerError::LoginDisabled)?;
+    let player = PlayerId::from(id);
+    let players = wwc_db::get_players().map_err(ServerError::from)?;
+    if !players.iter().any(|stored| stored.id == id) {
+        return Err(ApiError::from(ServerError::NotFound(format!(
+            "No player with id {}",
+            player
+        ))));
+    }
+    let expires_at = clock
+        .now()
+        .add_hours(hours.unwrap_or(DEFAULT_LINK_VALIDITY_HOURS));
+    let token = LoginToken::new(player, expires_at).sign(TokenKind::Link, secret);
+    Ok(Json(LoginLink {
+        player,
+        link: format!("{}/login/{}", ApiVersion::V1.base(), token),
+        expires_at,
+    }))
+}
+
+/// Log in with a login link, starting a session in the session cookie
+///
+/// Returns the session, i.e. the player and when the session expires.
+#[get("/login/<token>")]
+fn login(
+    token: &str,
+    cookies: &CookieJar<'_>,
+    secrets: &State<LoginSecrets>,
+    clock: &State<AppClock>,
+) -> Result<Json<LoginToken>, ApiError> {
+    let secret = secrets.0.as_ref().ok_or(ServerError::LoginDisabled)?;
+    let now = clock.now();
+    let link =
+        LoginToken::verify(token, TokenKind::Link, secret, now).map_err(ServerError::from)?;
+    if !wwc_db::is_active_player(link.player).map_err(ServerError::from)? {
+        return Err(ApiError::from(ServerError::from(LoginError::Deleted(
+            link.player,
+        ))));
+    }
+    let session = LoginToken::new(link.player, now.add_hours(SESSION_VALIDITY_HOURS));
+    let cookie = Cookie::parse(format!(
+        "{}={}; Max-Age={}; Path=/; HttpOnly; SameSite=Lax",
+        SESSION_COOKIE,
+        session.sign(TokenKind::Session, secret),
+        u64::from(SESSION_VALIDITY_HOURS) * 3600
+    ))
+    .expect("Session cookie parses");
+    cookies.add(cookie);
+    Ok(Json(session))
+}
+
+/// Session of the logged in player, `401 Unauthorized` if not logged in
+#[get("/session")]
+fn get_session(session: Session) -> Json<LoginToken> {
+    Json(session.0)
+}
+
+/// Log out, removing the session cookie
+#[delete("/session")]
+fn delete_session(cookies: &CookieJar<'_>) {
+    cookies.remove(Cookie::named(SESSION_COOKIE));
+}
+
+/// Group table as CSV, team names in `lang` if given
+#[get("/groups/<id>/standings.csv?<lang>")]
+fn get_group_standings_csv(
+    id: &str,
+    lang: Option<&str>,
+    cache: &State<OrderCache>,
+    if_none_match: IfNoneMatch,
+) -> Result<Tagged<(ContentType, String)>, ApiError> {
+    data_version()
+        .and_then(|version| {
+            Tagged::derived(&version, &if_none_match, || {
+                let rows = group_standing_rows(id, lang, cache)?;
+                Ok((ContentType::CSV, export::to_csv(&rows)?))
+            })
+        })
+        .map_err(ApiError::from)
+}
+
+/// Group table as JSON, same fields as the CSV export
+#[get("/groups/<id>/standings.json?<lang>")]
+fn get_group_standings_json(
+    id: &str,
+    lang: Option<&str>,
+    cache: &State<OrderCache>,
+    if_none_match: IfNoneMatch,
+) -> Result<Tagged<Json<Vec<StandingRow>>>, ApiError> {
+    data_version()
+        .and_then(|version| {
+            Tagged::derived(&version, &if_none_match, || {
+                Ok(Json(group_standing_rows(id, lang, cache)?))
+            })
+        })
+        .map_err(ApiError::from)
+}
+
+/// Tables of all groups, sorted by group id, team names in `lang` if given
+#[get("/groups/tables?<lang>")]
+fn get_group_tables(
+    lang: Option<&str>,
+    cache: &State<OrderCache>,
+    if_none_match: IfNoneMatch,
+) -> Result<Tagged<Json<Vec<GroupTable>>>, ApiError> {
+    data_version()
+        .and_then(|version| {
+            Tagged::derived(&version, &if_none_match, || {
+                let groups = load_groups()?;
+                let teams = load_teams(lang)?;
+                let rules = load_rules()?;
+                let tables = groups
+                    .iter()
+                    .map(|(id, group)| {
+                        Ok(GroupTable {
+                            id: *id,
+                            rows: cache.standings(group, &teams, &rules)?,
+                        })
+                    })
+                    .collect::<Result<_, ServerError>>()?;
+                Ok(Json(tables))
+            })
+        })
+        .map_err(ApiError::from)
+}
+
+/// Knockout bracket layout
+///
+/// Teams are filled in as the groups finish and playoff winners are set,
+/// see [`Bracket::layout`](wwc_core::playoff::bracket::Bracket::layout).
+#[get("/bracket")]
+fn get_bracket() -> Result<Json<Vec<BracketNode>>, ApiError> {
+    Ok(Json(bracket_layout().map_err(ApiError::from)?))
+}
+
+fn bracket_layout() -> Result<Vec<BracketNode>, ServerError> {
+    Ok(wwc_db::playoff::get_bracket_layout()?)
+}
+
+/// Leaderboard as CSV
+#[get("/leaderboard.csv")]
+fn get_leaderboard_csv(
+    if_none_match: IfNoneMatch,
+) -> Result<Tagged<(ContentType, String)>, ApiError> {
+    data_version()
+        .and_then(|version| {
+            Tagged::derived(&version, &if_none_match, || {
+                Ok((ContentType::CSV, export::to_csv(&leaderboard_rows()?)?))
+            })
+        })
+        .map_err(ApiError::from)
+}
+
+/// Leaderboard as JSON, same fields as the CSV export
+#[get("/leaderboard.json")]
+fn get_leaderboard_json(
+    if_none_match: IfNoneMatch,
+) -> Result<Tagged<Json<Vec<LeaderboardRow>>>, ApiError> {
+    data_version()
+        .and_then(|version| {
+            Tagged::derived(&version, &if_none_match, || Ok(Json(leaderboard_rows()?)))
+        })
+        .map_err(ApiError::from)
+}
+
+/// Group table, in the format of the `Accept` header, see [`negotiate`]
+///
+/// Browsers get a minimal HTML page, see [`html`].
+/// Team names in `lang` if given.
+/// The `Last-Modified` header is the time of the latest result of the group, see
+/// [`ConditionalGet`].
+#[get("/standings/<id>?<lang>")]
+fn get_standings(
+    id: &str,
+    lang: Option<&str>,
+    format: Format,
+    cache: &State<OrderCache>,
+    if_none_match: IfNoneMatch,
+) -> Result<LastModified<Tagged<Negotiated<Vec<StandingRow>>>>, ApiError> {
+    let modified = group_last_modified(id).map_err(ApiError::from)?;
+    let inner = data_version()
+        .and_then(|version| {
+            Tagged::derived(&(version, format), &if_none_match, || {
+                let rows = group_standing_rows(id, lang, cache)?;
+                Ok(match format {
+                    Format::Json => Negotiated::json(rows),
+                    Format::Csv => Negotiated::other(ContentType::CSV, export::to_csv(&rows)?),
+                    Format::Html => {
+                        let (content_type, html) = standings_page(id, rows)?;
+                        Negotiated::other(content_type, html)
+                    }
+                    Format::Text => Negotiated::other(
+                        ContentType::Plain,
+                        GroupStandings::from(rows).to_ascii_table(),
+                    ),
+                })
+            })
+        })
+        .map_err(ApiError::from)?;
+    Ok(LastModified { modified, inner })
+}
+
+/// Group table with the unplayed games filled in, team names in `lang` if given
+///
+/// `projection` is 'draw' (default), 'ranking' (the better ranked team wins) or 'predictions'
+/// (the predictions of `player`), see [`ScoreProjection`].
+#[get("/groups/<id>/standings/projected?<projection>&<player>&<lang>")]
+fn get_projected_standings(
+    id: &str,
+    projection: Option<&str>,
+    player: Option<i32>,
+    lang: Option<&str>,
+) -> Result<Json<Vec<StandingRow>>, ApiError> {
+    projected_standing_rows(id, projection, player, lang)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+fn projected_standing_rows(
+    id: &str,
+    projection: Option<&str>,
+    player: Option<i32>,
+    lang: Option<&str>,
+) -> Result<Vec<StandingRow>, ServerError> {
+    let group_id = parse_group_id(id)?;
+    let groups = load_groups()?;
+    let group = groups
+        .get(&group_id)
+        .ok_or_else(|| ServerError::NotFound(format!("Group {}", group_id)))?;
+    let teams = load_teams(lang)?;
+    let ranking: HashMap<TeamId, TeamRank> =
+        teams.values().map(|team| (team.id, team.rank)).collect();
+    let preds = match player {
+        Some(player) => wwc_db::get_preds(PlayerId::from(player))?,
+        None => Vec::new(),
+    };
+    let projection = match projection.unwrap_or("draw") {
+        "draw" => ScoreProjection::Draw,
+        "ranking" => ScoreProjection::Ranking(&ranking),
+        "predictions" if player.is_some() => ScoreProjection::Predictions(&preds),
+        "predictions" => {
+            return Err(ServerError::Invalid(String::from(
+                "Projection 'predictions' needs a player",
+            )))
+        }
+        other => {
+            return Err(ServerError::Invalid(format!(
+                "Unknown projection '{}', expected 'draw', 'ranking' or 'predictions'",
+                other
+            )))
+        }
+    };
+    let projected = project_group(group, &projection);
+    let rules = load_rules()?;
+    let rows = export::group_standings(&projected, &teams, &group_rules(&rules, &groups, &teams)?);
+    Ok(with_points(rows, rules.points))
+}
+
+/// Group standings after every played game of the group, in kickoff order
+///
+/// The standings are stored when a result is entered, see [`wwc_db::standings_history`].
+#[get("/groups/<id>/standings/history")]
+fn get_standings_history(id: &str) -> Result<Json<Vec<StandingsSnapshot>>, ApiError> {
+    parse_group_id(id)
+        .and_then(|group_id| Ok(wwc_db::standings_history::get_standings_history(group_id)?))
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// Group table stored when a result of the group is entered, see [`wwc_db::standings`]
+///
+/// An alternative to `/groups/<id>/standings`, which orders the group on every request.
+#[get("/groups/<id>/standings/materialized")]
+fn get_materialized_standings(id: &str) -> Result<Json<Vec<StandingRow>>, ApiError> {
+    parse_group_id(id)
+        .and_then(|group_id| Ok(wwc_db::standings::get_group_standings(group_id)?))
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// Groups with stored standings differing from the standings computed from the games
+#[get("/admin/standings/check")]
+fn check_materialized_standings(_admin: Admin) -> Result<Json<Vec<StandingsMismatch>>, ApiError> {
+    wwc_db::standings::check_standings()
+        .map(Json)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)
+}
+
+/// Leaderboard as a minimal HTML page, see [`html`]
+#[get("/leaderboard.html")]
+fn get_leaderboard_html() -> Result<(ContentType, String), ApiError> {
+    leaderboard_rows()
+        .and_then(leaderboard_page)
+        .map_err(ApiError::from)
+}
+
+fn standings_page(id: &str, rows: Vec<StandingRow>) -> Result<(ContentType, String), ServerError> {
+    html::render(&StandingsPage {
+        group: parse_group_id(id)?,
+        groups: load_groups()?.keys().copied().collect(),
+        rows,
+    })
+}
+
+fn leaderboard_page(rows: Vec<LeaderboardRow>) -> Result<(ContentType, String), ServerError> {
+    html::render(&LeaderboardPage {
+        groups: load_groups()?.keys().copied().collect(),
+        rows,
+    })
+}
+
+fn group_standing_rows(
+    id: &str,
+    lang: Option<&str>,
+    cache: &OrderCache,
+) -> Result<Vec<StandingRow>, ServerError> {
+    let group_id = parse_group_id(id)?;
+    let groups = load_groups()?;
+    let group = groups
+        .get(&group_id)
+        .ok_or_else(|| ServerError::NotFound(format!("Group {}", group_id)))?;
+    let teams = load_teams(lang)?;
+    cache.standings(group, &teams, &load_rules()?)
+}
+
+/// Group id from e.g. "a" or "A"
+fn parse_group_id(id: &str) -> Result<GroupId, ServerError> {
+    Ok(id
+        .chars()
+        .next()
+        .ok_or(GroupError::GenericError)
+        .and_then(GroupId::try_new)
+        .map(GroupId::into_uppercase)
+        .map_err(WwcError::from)?)
+}
+
+/// Time of the latest result of a group, see [`wwc_db::last_result`]
+fn group_last_modified(id: &str) -> Result<Option<Date>, ServerError> {
+    Ok(wwc_db::last_result::get_last_result(parse_group_id(id)?)?)
+}
+
+fn leaderboard_rows() -> Result<Vec<LeaderboardRow>, ServerError> {
+    let names: HashMap<PlayerId, String> = wwc_db::get_players()?
+        .into_iter()
+        .map(|player| (PlayerId::from(player.id), player.name))
+        .collect();
+    Ok(export::leaderboard(&current_standings()?, &names))
+}
+
+/// Final scores of the played group games
+fn game_results(groups: &Groups) -> HashMap<GameId, Score> {
+    groups
+        .values()
+        .flat_map(|group| group.played_games())
+        .map(|game| (game.id, game.score))
+        .collect()
+}
+
+/// Id's of the unplayed group games
+fn remaining_games(groups: &Groups) -> Vec<GameId> {
+    groups
+        .values()
+        .flat_map(|group| group.unplayed_games())
+        .map(|game| game.id)
+        .collect()
+}
+
+/// Load all players with their effective predictions
+fn load_player_preds() -> Result<Vec<(PlayerId, Vec<Prediction>)>, ServerError> {
+    Ok(wwc_db::get_players()?
+        .into_iter()
+        .map(|player| {
+            let id = PlayerId::from(player.id);
+            wwc_db::get_preds(id).map(|preds| (id, preds))
+        })
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Get the joker games of a player
+#[get("/players/<id>/jokers")]
+fn get_jokers(id: i32) -> Result<Json<Vec<GameId>>, ApiError> {
+    let jokers = wwc_db::joker::get_jokers(PlayerId::from(id))
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(
+        jokers
+            .into_iter()
+            .sorted_by_key(|id| u32::from(*id))
+            .collect(),
+    ))
+}
+
+/// Mark a game as a joker
+///
+/// At most one joker per matchday, set before the kickoff of the game, see [`validate_joker`].
+/// Requires the session of the player.
+#[put("/players/<id>/jokers/<game_id>")]
+fn put_joker(
+    session: Session,
+    id: i32,
+    game_id: u32,
+    clock: &State<AppClock>,
+) -> Result<(), ApiError> {
+    let (player, game_id) = (PlayerId::from(id), GameId::from(game_id));
+    session.ensure_player(player)?;
+    let groups = load_groups().map_err(ApiError::from)?;
+    let jokers = wwc_db::joker::get_jokers(player)
         .map_err(ServerError::from)
-        .map_err(BadRequest::from);
-    tmp?;
-    Ok(())
+        .map_err(ApiError::from)?;
+    validate_joker(game_id, &jokers, &groups, clock.now())
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    wwc_db::joker::set_joker(player, game_id)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)
 }
 
-/// Get teams
-#[get("/get_teams")]
-fn get_teams() -> Result<Json<Teams>, BadRequest<String>> {
-    let teams: Teams = wwc_db::get_teams()
+/// Clear a joker, only before the kickoff of the game
+///
+/// Requires the session of the player.
+#[delete("/players/<id>/jokers/<game_id>")]
+fn delete_joker(
+    session: Session,
+    id: i32,
+    game_id: u32,
+    clock: &State<AppClock>,
+) -> Result<(), ApiError> {
+    let (player, game_id) = (PlayerId::from(id), GameId::from(game_id));
+    session.ensure_player(player)?;
+    let groups = load_groups().map_err(ApiError::from)?;
+    let jokers = wwc_db::joker::get_jokers(player)
         .map_err(ServerError::from)
-        .map_err(BadRequest::from)?
-        .map(|x| (x.id, x))
-        .collect();
-    Ok(Json(teams))
+        .map_err(ApiError::from)?;
+    validate_clear(game_id, &jokers, &groups, clock.now())
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    wwc_db::joker::clear_joker(player, game_id)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)
 }
 
-/// Get predictions
-#[get("/get_preds/<player_id>")]
-fn get_preds(player_id: i32) -> Result<Json<Vec<Prediction>>, BadRequest<String>> {
-    let preds = wwc_db::get_preds(PlayerId::from(player_id))
+/// Personal bracket of a player: the predicted bracket overlaid with the actual one
+///
+/// Every playoff game is marked correct, incorrect or pending,
+/// see [`PlayoffPrediction::compare`](wwc_core::playoff::prediction::PlayoffPrediction::compare).
+#[get("/players/<id>/bracket")]
+fn get_personal_bracket(id: i32) -> Result<Json<Vec<SlotComparison>>, ApiError> {
+    let prediction = wwc_db::playoff::get_playoff_prediction(PlayerId::from(id))
         .map_err(ServerError::from)
-        .map_err(BadRequest::from)?;
-    Ok(Json(preds))
+        .map_err(ApiError::from)?;
+    let layout = bracket_layout().map_err(ApiError::from)?;
+    Ok(Json(prediction.compare(&layout)))
 }
 
-/// Clear predictions
-#[get("/clear_preds")]
-fn clear_preds() -> Result<(), BadRequest<String>> {
-    let preds = wwc_db::clear_preds()
+/// Predict the winner of a playoff game, before the kickoff of the game
+///
+/// Requires the session of the player.
+#[put("/players/<id>/playoff-preds/<game_id>/<winner>")]
+fn put_playoff_pred(
+    session: Session,
+    id: i32,
+    game_id: u32,
+    winner: u32,
+    clock: &State<AppClock>,
+) -> Result<(), ApiError> {
+    session.ensure_player(PlayerId::from(id))?;
+    let (game_id, winner) = (GameId::from(game_id), TeamId(winner));
+    validate_playoff_pred(game_id, winner, clock.now()).map_err(ApiError::from)?;
+    wwc_db::playoff::set_playoff_pred(PlayerId::from(id), game_id, winner)
         .map_err(ServerError::from)
-        .map_err(BadRequest::from)?;
-    println!("Clear preds res {:?}", preds);
+        .map_err(ApiError::from)
+}
+
+fn validate_playoff_pred(game_id: GameId, winner: TeamId, now: Date) -> Result<(), ServerError> {
+    let bracket = wwc_db::playoff::get_bracket()?;
+    let game = bracket
+        .games()
+        .find(|game| game.id == game_id)
+        .ok_or_else(|| ServerError::NotFound(format!("Playoff game {}", game_id)))?;
+    if !now.is_before(game.kickoff) {
+        return Err(ServerError::Invalid(format!(
+            "Playoff game {} has already kicked off",
+            game_id
+        )));
+    }
+    if !load_teams(None)?.contains_key(&winner) {
+        return Err(ServerError::NotFound(format!("Team {}", winner)));
+    }
     Ok(())
 }
 
-/// Get groups
+/// Get tournaments
+#[get("/tournaments")]
+fn get_tournaments() -> Result<Json<Vec<Tournament>>, ApiError> {
+    let tournaments = wwc_db::tournament::get_tournaments()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(tournaments))
+}
+
+/// Rules of a tournament, the default rules if none are stored
+#[get("/tournaments/<id>/rules")]
+fn get_tournament_rules(id: i32) -> Result<Json<TournamentRules>, ApiError> {
+    let rules = wwc_db::tournament::get_rules(id)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(rules))
+}
+
+/// Replace the rules of a tournament, requires an admin API key
 ///
-/// Loads group games and a GameId: GroupId map from the db
-/// The games (played and unplayed) games are then mapped to prospective groups.
-/// The final groups are validated (with a fallible constructor) and collected together.
-#[get("/get_groups")]
-fn get_groups() -> Result<Json<Groups>, BadRequest<String>> {
-    let (played_games, unplayed_games) = wwc_db::get_group_games()
+/// The standings, the bracket and the leaderboards follow the new rules right away, the stored
+/// standings, standings history and scores of the active tournament are recomputed, see
+/// [`wwc_db::tournament::set_rules`].
+#[put(
+    "/tournaments/<id>/rules",
+    format = "application/json",
+    data = "<rules>"
+)]
+fn put_tournament_rules(
+    _admin: Admin,
+    id: i32,
+    rules: Json<TournamentRules>,
+) -> Result<(), WriteError> {
+    let rules = rules.into_inner();
+    validate(&rules)?;
+    Ok(wwc_db::tournament::set_rules(id, &rules).map_err(ServerError::from)?)
+}
+
+/// Score of a game, e.g. `{"home": 2, "away": 1, "version": 3}`
+///
+/// The `version` is the `row_version` of the game when it was read, see [`get_games`], the
+/// result is rejected if the game has changed since.
+#[derive(Deserialize)]
+struct ResultSubmission {
+    #[serde(flatten)]
+    score: Score,
+    version: i32,
+}
+
+/// Rejected write request, with an [`ErrorBody`]
+#[derive(Responder)]
+enum WriteError {
+    /// Invalid fields of the request body, see [`validate`]
+    #[response(status = 422)]
+    Unprocessable(Json<ErrorBody>),
+    /// The game changed since it was read, see [`ResultSubmission`]
+    #[response(status = 409)]
+    Conflict(Json<ErrorBody>),
+    #[response(status = 404)]
+    NotFound(Json<ErrorBody>),
+    /// The session belongs to another player, see [`Session::ensure_player`]
+    #[response(status = 403)]
+    Forbidden(Json<ErrorBody>),
+    #[response(status = 400)]
+    Invalid(Json<ErrorBody>),
+}
+
+impl From<ServerError> for WriteError {
+    fn from(server_err: ServerError) -> Self {
+        let body = Json(ErrorBody::new(&server_err));
+        match server_err {
+            ServerError::Db(wwc_db::DbError::Conflict { .. }) => WriteError::Conflict(body),
+            _ if server_err.is_not_found() => WriteError::NotFound(body),
+            _ if server_err.is_forbidden() => WriteError::Forbidden(body),
+            _ => WriteError::Invalid(body),
+        }
+    }
+}
+
+/// Enter a (fake) result in a sandbox tournament
+///
+/// Responds with the new version of the game, or 409 Conflict if the game changed since the
+/// submitted version.
+/// Requires an admin API key.
+#[put(
+    "/tournaments/<id>/games/<game_id>/result",
+    format = "application/json",
+    data = "<result>"
+)]
+fn put_sandbox_result(
+    _admin: Admin,
+    id: i32,
+    game_id: u32,
+    result: Json<ResultSubmission>,
+) -> Result<Json<i32>, WriteError> {
+    let result = result.into_inner();
+    validate(&result)?;
+    let version = wwc_db::tournament::set_sandbox_result(
+        id,
+        GameId::from(game_id),
+        result.score,
+        result.version,
+    )
+    .map_err(ServerError::from)?;
+    wwc_db::achievement::refresh_achievements().map_err(ServerError::from)?;
+    Ok(Json(version))
+}
+
+/// Wipe results and predictions of a sandbox tournament, keeping fixtures and players
+///
+/// Only the active tournament can be reset. Requires an admin API key.
+#[post("/tournaments/<id>/reset-sandbox")]
+fn reset_sandbox(_admin: Admin, id: i32) -> Result<(), ApiError> {
+    wwc_db::tournament::reset_sandbox(id)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)
+}
+
+/// Source of the current time for all routes, see [`Clock`]
+type AppClock = Box<dyn Clock>;
+
+/// Group orders of the standings routes, shared between requests
+///
+/// Entries are keyed by the games of a group, a new result is picked up without invalidation,
+/// see [`GroupOrderCache`]. The orders of replaced results are dropped by the event feed, see
+/// [`feed`].
+#[derive(Default)]
+struct OrderCache(Mutex<GroupOrderCache>);
+
+impl OrderCache {
+    fn clear(&self) {
+        self.0.lock().expect("Order cache lock poisoned").clear()
+    }
+
+    /// Group table, ordered and scored by the tournament rules
+    ///
+    /// The orders are keyed by the name of the group rules.
+    fn standings(
+        &self,
+        group: &Group,
+        teams: &Teams,
+        rules: &TournamentRules,
+    ) -> Result<Vec<StandingRow>, ServerError> {
+        let group_rules = rules
+            .group_rules(std::slice::from_ref(group), teams)
+            .map_err(WwcError::from)?;
+        let mut cache = self.0.lock().expect("Order cache lock poisoned");
+        let order = cache.order(group, &rules.group_rules, &group_rules);
+        Ok(with_points(
+            export::ordered_standings(group, teams, order),
+            rules.points,
+        ))
+    }
+}
+
+/// Response with an `ETag` header, a tag of the data the response is derived from
+///
+/// Without content, the response is an empty `304 Not Modified`, see [`Tagged::derived`].
+struct Tagged<R> {
+    etag: String,
+    inner: Option<R>,
+}
+
+impl<R> Tagged<R> {
+    /// Response derived from the stored data, tagged with the `version` of the data
+    ///
+    /// The tag is a hash of the version, e.g. the [`DataVersion`], so it is known before the
+    /// response is derived. `derive` is only called if the tag does not match the
+    /// `If-None-Match` header of the request.
+    fn derived<V: Serialize>(
+        version: &V,
+        if_none_match: &IfNoneMatch,
+        derive: impl FnOnce() -> Result<R, ServerError>,
+    ) -> Result<Self, ServerError> {
+        let etag = export::content_hash(version)?;
+        let inner = if if_none_match.matches(&etag) {
+            None
+        } else {
+            Some(derive()?)
+        };
+        Ok(Tagged { etag, inner })
+    }
+}
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Tagged<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        match self.inner {
+            Some(inner) => Response::build_from(inner.respond_to(request)?)
+                .raw_header("ETag", self.etag)
+                .ok(),
+            None => Response::build()
+                .status(Status::NotModified)
+                .raw_header("ETag", self.etag)
+                .ok(),
+        }
+    }
+}
+
+/// Entity tags of the `If-None-Match` header of a request, see [`Tagged`]
+struct IfNoneMatch(Vec<String>);
+
+impl IfNoneMatch {
+    fn matches(&self, etag: &str) -> bool {
+        self.0.iter().any(|tag| tag == "*" || tag == etag)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let tags = request
+            .headers()
+            .get("If-None-Match")
+            .flat_map(|tags| tags.split(','))
+            .map(|tag| tag.trim().trim_start_matches("W/").to_string())
+            .collect();
+        request::Outcome::Success(IfNoneMatch(tags))
+    }
+}
+
+/// Version of the data the standings and the leaderboards are derived from, see [`Tagged`]
+fn data_version() -> Result<DataVersion, ServerError> {
+    Ok(wwc_db::version::get_data_version()?)
+}
+
+/// Response with a `Last-Modified` header if the time is known, see [`ConditionalGet`]
+struct LastModified<R> {
+    modified: Option<Date>,
+    inner: R,
+}
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for LastModified<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        let mut response = self.inner.respond_to(request)?;
+        if let Some(modified) = self.modified {
+            response.set_raw_header("Last-Modified", modified.to_http_date());
+        }
+        Ok(response)
+    }
+}
+
+/// Conditional GET requests by time
+///
+/// Responds with an empty `304 Not Modified` when the response is not modified after the
+/// `If-Modified-Since` header of the request, see [`LastModified`].
+/// The response is still computed, only the body is saved.
+/// Requests with `If-None-Match` are answered by the tag instead, before the response is
+/// computed, see [`Tagged`].
+struct ConditionalGet;
+
+#[rocket::async_trait]
+impl Fairing for ConditionalGet {
+    fn info(&self) -> Info {
+        Info {
+            name: "Conditional GET",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if request.method() != Method::Get
+            || response.status() != Status::Ok
+            || request.headers().contains("If-None-Match")
+        {
+            return;
+        }
+        let modified = response
+            .headers()
+            .get_one("Last-Modified")
+            .and_then(|date| date.parse::<Date>().ok());
+        let since = request
+            .headers()
+            .get_one("If-Modified-Since")
+            .and_then(|date| date.parse::<Date>().ok());
+        if let (Some(modified), Some(since)) = (modified, since) {
+            if !modified.is_after(since) {
+                response.set_status(Status::NotModified);
+                response.remove_header("Content-Type");
+                response.set_sized_body(0, Cursor::new(""));
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Reschedule {
+    /// See [`Date::from_str`](std::str::FromStr::from_str) for the formats
+    kickoff: String,
+}
+
+/// Move the kickoff of an unplayed game
+///
+/// Responds with (and publishes) a notification, listing the players whose effective prediction
+/// changed with the new deadline.
+/// Requires an admin API key.
+#[put(
+    "/games/<id>/kickoff",
+    format = "application/json",
+    data = "<reschedule>"
+)]
+fn reschedule_game(
+    _admin: Admin,
+    id: u32,
+    reschedule: Json<Reschedule>,
+    notifications: &State<Notifications>,
+    cache: &State<OrderCache>,
+    clock: &State<AppClock>,
+) -> Result<Json<Notification>, WriteError> {
+    validate(&*reschedule)?;
+    let game = GameId::from(id);
+    wwc_db::reschedule_game(game, parse_date(&reschedule.kickoff)?, clock.now())
+        .map_err(ServerError::from)?;
+    // The event may already have been read by a concurrent request.
+    notifications.follow(cache)?;
+    notifications
+        .since(0)
+        .into_iter()
+        .rev()
+        .find(|notification| match notification {
+            Notification::GameRescheduled {
+                game: rescheduled, ..
+            } => *rescheduled == game,
+            Notification::MatchdayDigest { .. } => false,
+        })
+        .map(Json)
+        .ok_or_else(|| {
+            WriteError::from(ServerError::NotFound(format!(
+                "Notification of rescheduled game {}",
+                game
+            )))
+        })
+}
+
+/// New status of a game, e.g. `{"status": "annulled"}`
+#[derive(Deserialize)]
+struct StatusChange {
+    status: GameStatus,
+}
+
+/// Change the status of a game, e.g. annul it
+///
+/// Abandoned and annulled games are excluded from the standings and the leaderboard.
+/// Responds with the new version of the game.
+/// Requires an admin API key.
+#[put("/games/<id>/status", format = "application/json", data = "<change>")]
+fn put_game_status(
+    _admin: Admin,
+    id: u32,
+    change: Json<StatusChange>,
+) -> Result<Json<i32>, WriteError> {
+    let version =
+        wwc_db::set_game_status(GameId::from(id), change.status).map_err(ServerError::from)?;
+    Ok(Json(version))
+}
+
+/// Withdraw a team from its group
+///
+/// The games of the team are annulled or forfeited by the withdrawal policy of the tournament
+/// rules. Responds with the changed games.
+/// Requires an admin API key.
+#[post("/teams/<id>/withdraw")]
+fn withdraw_team(_admin: Admin, id: u32) -> Result<Json<Vec<WithdrawalChange>>, WriteError> {
+    let changes = wwc_db::withdrawal::withdraw_team(TeamId::from(id)).map_err(ServerError::from)?;
+    Ok(Json(changes))
+}
+
+/// Withdrawn teams, in the order of withdrawal
+#[get("/withdrawals")]
+fn get_withdrawals() -> Result<Json<Vec<TeamWithdrawal>>, ApiError> {
+    Ok(Json(
+        wwc_db::withdrawal::get_withdrawals()
+            .map_err(ServerError::from)
+            .map_err(ApiError::from)?,
+    ))
+}
+
+/// Post the current, non-final, score of a game in progress
+///
+/// Replaces any previous live score of the game, the final score is entered as a result.
+/// Requires an admin API key.
+#[put("/games/<id>/live", format = "application/json", data = "<score>")]
+fn put_live_score(_admin: Admin, id: u32, score: Json<Score>) -> Result<(), WriteError> {
+    let score = score.into_inner();
+    validate(&score)?;
+    Ok(wwc_db::live::set_live_score(GameId::from(id), score).map_err(ServerError::from)?)
+}
+
+/// Replace the card events of a game
+/// Requires an admin API key.
+#[put("/games/<id>/cards", format = "application/json", data = "<events>")]
+fn put_game_cards(_admin: Admin, id: u32, events: Json<Vec<CardEvent>>) -> Result<(), WriteError> {
+    Ok(wwc_db::fair_play::set_card_events(GameId::from(id), &events).map_err(ServerError::from)?)
+}
+
+/// Result typed as text, e.g. `{"text": "Sweden 3-0 England (yellow: swe 2)", "version": 0}`,
+/// see [`parse_result`]
+///
+/// The `version` is the `row_version` of the game when it was read, like in a
+/// [`ResultSubmission`].
+#[derive(Deserialize)]
+struct ResultText {
+    text: String,
+    version: i32,
+}
+
+/// Enter the result of an unplayed group game, typed as text, e.g. from a chat bot
+///
+/// Teams are resolved by name, Fifa code or alias, the cards replace the card events of the
+/// game, see [`update_game_result_with_cards`](wwc_db::update_game_result_with_cards).
+/// Responds with the id of the game, or 409 Conflict if the game changed since the submitted
+/// version.
+/// Requires an admin API key.
+#[post("/results", format = "application/json", data = "<result>")]
+fn post_result_text(_admin: Admin, result: Json<ResultText>) -> Result<Json<GameId>, WriteError> {
+    let teams = wwc_db::team_alias::get_team_resolver().map_err(ServerError::from)?;
+    let groups = load_groups()?;
+    let submission = parse_result(&result.text, &teams)
+        .and_then(|entry| entry.submission(&groups))
+        .map_err(|err| ServerError::Invalid(err.to_string()))?;
+    wwc_db::update_game_result_with_cards(
+        submission.game_id,
+        submission.score,
+        submission.fair_play,
+        result.version,
+    )
+    .map_err(ServerError::from)?;
+    wwc_db::achievement::refresh_achievements().map_err(ServerError::from)?;
+    Ok(Json(submission.game_id))
+}
+
+/// Remove the live score of a game, e.g. if it was posted by mistake
+/// Requires an admin API key.
+#[delete("/games/<id>/live")]
+fn delete_live_score(_admin: Admin, id: u32) -> Result<(), ApiError> {
+    wwc_db::live::clear_live_score(GameId::from(id))
         .map_err(ServerError::from)
-        .map_err(BadRequest::from)?;
-    let game_group_map = wwc_db::get_group_game_maps()
+        .map_err(ApiError::from)
+}
+
+/// Decimal odds of all games with odds
+#[get("/odds")]
+fn get_odds() -> Result<Json<HashMap<GameId, Odds>>, ApiError> {
+    let odds = wwc_db::odds::get_odds()
         .map_err(ServerError::from)
-        .map_err(BadRequest::from)?
-        .collect::<HashMap<GameId, GroupId>>();
+        .map_err(ApiError::from)?;
+    Ok(Json(odds))
+}
 
-    let empty_groups = game_group_map
-        .iter()
-        .map(|(_game_id, group_id)| group_id)
-        .unique();
-
-    let groups_played =
-        played_games.into_iter().fold(
-            empty_groups
-                .clone()
-                .map(|group_id| (*group_id, Vec::new()))
-                .collect::<BTreeMap<GroupId, Vec<PlayedGroupGame>>>(),
-            |mut acc, game| {
-                let entry = acc
-                    .entry(*game_group_map.get(&game.id).unwrap_or_else(|| {
-                        panic!("game group map discrepancy: no id: {:?}", game.id)
-                    }))
-                    .or_insert_with(Vec::new);
-                entry.push(game);
-                acc
-            },
-        );
+/// Set the decimal odds of a game, e.g. `{"home": 1.8, "draw": 3.4, "away": 4.5}`
+/// Requires an admin API key.
+#[put("/games/<id>/odds", format = "application/json", data = "<odds>")]
+fn put_odds(_admin: Admin, id: u32, odds: Json<Odds>) -> Result<(), WriteError> {
+    let odds = odds.into_inner();
+    validate(&odds)?;
+    Ok(wwc_db::odds::set_odds(GameId::from(id), odds).map_err(ServerError::from)?)
+}
 
-    let groups_unplayed =
-        unplayed_games.into_iter().fold(
-            empty_groups
-                .clone()
-                .map(|group_id| (*group_id, Vec::new()))
-                .collect::<BTreeMap<GroupId, Vec<UnplayedGroupGame>>>(),
-            |mut acc, game| {
-                let entry = acc
-                    .entry(*game_group_map.get(&game.id).unwrap_or_else(|| {
-                        panic!("game group map discrepancy: no id: {:?}", game.id)
-                    }))
-                    .or_insert_with(Vec::new);
-                entry.push(game);
-                acc
-            },
-        );
+/// Distribution of the predictions of all players for a group or playoff game
+///
+/// Playoff games are predicted by the winner, their consensus is the share of players picking
+/// each team, see [`winner_consensus`].
+/// Only available after kickoff, to not give away the community picks while predictions can
+/// still be changed.
+#[get("/games/<id>/consensus")]
+fn get_consensus(id: u32, clock: &State<AppClock>) -> Result<Json<Consensus>, ApiError> {
+    let game_id = GameId::from(id);
+    let playoff_game = wwc_db::playoff::get_bracket()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?
+        .games()
+        .find(|game| game.id == game_id)
+        .cloned();
+    let consensus = match playoff_game {
+        Some(game) => {
+            revealed_winners(&game, clock.now()).map(|winners| winner_consensus(&winners))
+        }
+        None => revealed_preds(game_id, clock.now()).map(|preds| consensus(&preds)),
+    };
+    consensus.map(Json).map_err(ApiError::from)
+}
+
+/// Number of predictions of every score of a group game, for a heatmap
+///
+/// Only available after kickoff, like the [consensus](get_consensus).
+#[get("/games/<id>/heatmap")]
+fn get_heatmap(id: u32, clock: &State<AppClock>) -> Result<Json<ScoreHeatmap>, ApiError> {
+    let preds = revealed_preds(GameId::from(id), clock.now()).map_err(ApiError::from)?;
+    Ok(Json(heatmap(&preds)))
+}
 
-    let groups: Result<Groups, WwcError> = groups_played
+/// Predicted scores of all players for a game, which are hidden until kickoff
+///
+/// Predictions for a game without a kickoff can still be changed, so they stay hidden.
+fn revealed_preds(game_id: GameId, now: Date) -> Result<Vec<Score>, ServerError> {
+    let game = wwc_db::get_games()?
         .into_iter()
-        .zip(groups_unplayed.into_iter())
-        .map(
-            |((group_id_played, played), (group_id_unplayed, unplayed))| {
-                assert!(group_id_played == group_id_unplayed);
-                Group::try_new(unplayed, played)
-                    .map(|group| (group_id_played, group))
-                    .map_err(WwcError::from)
-            },
-        )
+        .find(|game| u32::try_from(game.id).ok() == Some(u32::from(game_id)))
+        .ok_or_else(|| ServerError::NotFound(format!("Game {}", game_id)))?;
+    ensure_revealed(game_id, game.scheduled_kickoff()?, now)?;
+    Ok(wwc_db::get_game_preds(game_id)?
+        .into_iter()
+        .map(|(_, score)| score)
+        .collect())
+}
+
+/// Predicted winners of all players for a playoff game, which are hidden until kickoff
+fn revealed_winners(game: &BracketGame, now: Date) -> Result<Vec<TeamId>, ServerError> {
+    ensure_revealed(game.id, Some(game.kickoff), now)?;
+    Ok(wwc_db::playoff::get_playoff_game_preds(game.id)?
+        .into_iter()
+        .map(|(_, winner)| winner)
+        .collect())
+}
+
+fn ensure_revealed(game_id: GameId, kickoff: Option<Date>, now: Date) -> Result<(), ServerError> {
+    match kickoff {
+        Some(kickoff) if !now.is_before(kickoff) => Ok(()),
+        _ => Err(ServerError::Invalid(format!(
+            "Predictions for game {} are hidden until kickoff",
+            game_id
+        ))),
+    }
+}
+
+/// Get notifications
+///
+/// `since` skips the first notifications, pass the number of notifications already seen.
+/// The notifications follow the event log, see [`feed`].
+#[get("/notifications?<since>")]
+fn get_notifications(
+    since: Option<usize>,
+    notifications: &State<Notifications>,
+    cache: &State<OrderCache>,
+) -> Result<Json<Vec<Notification>>, ApiError> {
+    notifications.follow(cache).map_err(ApiError::from)?;
+    Ok(Json(notifications.since(since.unwrap_or(0))))
+}
+
+/// Digest of a completed matchday, a UTC calendar day, e.g. `2021-06-11`
+///
+/// The digest is published as a notification once the last game of the day is played, see
+/// [`feed`].
+#[get("/digests/<day>")]
+fn get_digest(day: &str) -> Result<Json<Digest>, ApiError> {
+    let day: NaiveDate = day
+        .parse()
+        .map_err(|_| ServerError::Invalid(format!("Invalid day '{}', expected YYYY-MM-DD", day)))
+        .map_err(ApiError::from)?;
+    let digest = matchday_digest(day)
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ServerError::NotFound(format!("Completed matchday {}", day)))
+        .map_err(ApiError::from)?;
+    Ok(Json(digest))
+}
+
+/// Digest of `day`, `None` if the matchday is not completed, see [`digest`]
+fn matchday_digest(day: NaiveDate) -> Result<Option<Digest>, ServerError> {
+    let groups = load_groups()?;
+    let playoff = wwc_db::playoff::get_bracket_layout()?;
+    let players = load_player_preds()?;
+    let jokers = wwc_db::joker::get_all_jokers()?;
+    let rules = load_rules()?;
+    let group_rules = group_rules(&rules, &groups, &load_teams(None)?)?;
+    Ok(digest(
+        day,
+        &groups,
+        &playoff,
+        &players,
+        &jokers,
+        &rules.scoring,
+        &group_rules,
+    ))
+}
+
+/// Notification of a digest, rendered with the English team names
+fn digest_notification(digest: Digest) -> Result<Notification, ServerError> {
+    let teams = load_teams(None)?;
+    let names: HashMap<PlayerId, String> = wwc_db::get_players()?
+        .into_iter()
+        .map(|player| (PlayerId::from(player.id), player.name))
         .collect();
+    let markdown = digest.to_markdown(&teams, &names);
+    Ok(Notification::MatchdayDigest { digest, markdown })
+}
+
+/// Populate an empty db with a built in tournament template, e.g. "euro-2020"
+///
+/// The bracket is the one of the bracket template in the rules of the active tournament, see
+/// [`TournamentRules::bracket`](wwc_core::rules::TournamentRules::bracket).
+/// Responds with the number of teams stored. Requires an admin API key.
+#[post("/admin/seed/<template>")]
+fn seed(_admin: Admin, template: &str) -> Result<Json<usize>, ApiError> {
+    seed_template(template).map(Json).map_err(ApiError::from)
+}
+
+fn seed_template(template: &str) -> Result<usize, ServerError> {
+    let template: Template = template
+        .parse()
+        .map_err(|err: TemplateError| ServerError::NotFound(err.to_string()))?;
+    let rules = match wwc_db::tournament::get_active_id()? {
+        Some(_) => wwc_db::tournament::get_active_rules()?,
+        None => template.rules(),
+    };
+    let data = template
+        .load()
+        .and_then(|data| data.with_bracket_of(&rules))
+        .map_err(|err| ServerError::Invalid(err.to_string()))?;
+    wwc_db::seed::seed(
+        &data.teams,
+        &data.groups,
+        data.bracket.as_ref(),
+        &data.venues,
+    )?;
+    Ok(data.teams.len())
+}
+
+#[derive(Deserialize)]
+struct NewKey {
+    name: String,
+    scope: Scope,
+}
+
+/// Newly created API key, the key is only shown once
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct CreatedKey {
+    key: ApiKey,
+    #[serde(flatten)]
+    info: ApiKeyInfo,
+}
+
+/// Create an API key, e.g. `{"name": "Discord bot", "scope": "read"}`
+#[post("/admin/api-keys", format = "application/json", data = "<new_key>")]
+fn post_api_key(
+    _admin: Admin,
+    new_key: Json<NewKey>,
+    clock: &State<AppClock>,
+) -> Result<Json<CreatedKey>, WriteError> {
+    let new_key = new_key.into_inner();
+    validate(&new_key)?;
+    let (key, info) =
+        wwc_db::api_key::create_api_key(new_key.name.trim(), new_key.scope, clock.now())
+            .map_err(ServerError::from)?;
+    Ok(Json(CreatedKey { key, info }))
+}
+
+/// Data integrity report, see [`wwc_db::integrity`]
+#[get("/admin/integrity")]
+fn get_integrity(_admin: Admin) -> Result<Json<IntegrityReport>, ApiError> {
+    let report = wwc_db::integrity::check_integrity()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(report))
+}
+
+/// Differences between the stored state and the replayed event log, see [`wwc_db::replay`]
+#[get("/admin/replay")]
+fn get_replay(_admin: Admin) -> Result<Json<ReplayReport>, ApiError> {
+    let report = wwc_db::replay::check_replay()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(report))
+}
+
+/// Suspicious prediction patterns, e.g. identical sheets, see [`wwc_core::fairness`]
+#[get("/admin/fairness")]
+fn get_fairness(_admin: Admin) -> Result<Json<FairnessReport>, ApiError> {
+    let report = wwc_db::fairness::check_fairness()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(report))
+}
+
+/// Most events returned by one request to the event log
+const MAX_EVENTS: i64 = 500;
+
+/// Event log, oldest first, see [`wwc_db::event`]
+///
+/// `since` is the id of the last event already seen, `limit` defaults to and is capped at
+/// [`MAX_EVENTS`].
+#[get("/admin/events?<since>&<limit>")]
+fn get_events(
+    _admin: Admin,
+    since: Option<i32>,
+    limit: Option<i64>,
+) -> Result<Json<Vec<EventRecord>>, ApiError> {
+    let limit = limit.unwrap_or(MAX_EVENTS).clamp(0, MAX_EVENTS);
+    let events = wwc_db::event::get_events(since, limit)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(events))
+}
+
+/// API keys, without the keys themselves
+#[get("/admin/api-keys")]
+fn get_api_keys(_admin: Admin) -> Result<Json<Vec<ApiKeyInfo>>, ApiError> {
+    let keys = wwc_db::api_key::get_api_keys()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(keys))
+}
+
+/// Revoke an API key
+#[delete("/admin/api-keys/<id>")]
+fn delete_api_key(_admin: Admin, id: i32) -> Result<(), ApiError> {
+    wwc_db::api_key::revoke_api_key(id)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)
+}
+
+/// Get groups
+#[get("/get_groups")]
+fn get_groups() -> Result<Json<Groups>, ApiError> {
+    Ok(Json(load_groups().map_err(ApiError::from)?))
+}
+
+/// Page of games, in kickoff order
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct GamePage {
+    games: Vec<Game>,
+    page: u32,
+    per_page: u32,
+    /// Number of games matching the filters, on all pages
+    total: i64,
+}
 
+/// Games open for predictions, with their deadlines and whether `player` has predicted them
+///
+/// Computed with the server clock, so that countdowns do not depend on the clock of the client.
+/// Earliest deadline first, see [`upcoming_games`].
+#[get("/games/upcoming?<player>")]
+fn get_upcoming_games(
+    player: i32,
+    clock: &State<AppClock>,
+) -> Result<Json<Vec<UpcomingGame>>, ApiError> {
+    let groups = load_groups().map_err(ApiError::from)?;
+    let preds = wwc_db::get_preds(PlayerId::from(player))
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(upcoming_games(&groups, &preds, clock.now())))
+}
+
+/// Get games
+///
+/// All filters are optional: `group` (e.g. "A"), `round` (e.g. "group"), `team` (home or away),
+/// `played`, and kickoff between `from` and `to` (inclusive, e.g. "2021-06-11T21:00:00+02:00").
+/// Paginated with `page` (starting at 0) and `per_page` (default 50).
+#[allow(clippy::too_many_arguments)]
+#[get("/games?<group>&<round>&<team>&<played>&<from>&<to>&<page>&<per_page>")]
+fn get_games(
+    group: Option<&str>,
+    round: Option<String>,
+    team: Option<u32>,
+    played: Option<bool>,
+    from: Option<&str>,
+    to: Option<&str>,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> Result<Json<GamePage>, ApiError> {
+    let query = GameQuery {
+        group: group
+            .map(parse_group_id)
+            .transpose()
+            .map_err(ApiError::from)?,
+        round,
+        team: team.map(TeamId),
+        played,
+        from: from.map(parse_date).transpose().map_err(ApiError::from)?,
+        to: to.map(parse_date).transpose().map_err(ApiError::from)?,
+    };
+    let page = Page {
+        page: page.unwrap_or(0),
+        per_page: per_page.unwrap_or(DEFAULT_PER_PAGE),
+    };
+    let (games, total) = wwc_db::game_query::get_games_page(&query, page)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    Ok(Json(GamePage {
+        games,
+        page: page.page,
+        per_page: page.per_page,
+        total,
+    }))
+}
+
+const DEFAULT_PER_PAGE: u32 = 50;
+
+fn parse_date(date: &str) -> Result<Date, ServerError> {
+    date.parse()
+        .map_err(|err: DateError| ServerError::Invalid(err.to_string()))
+}
+
+/// Save a full sheet of group stage predictions
+///
+/// Every prediction is validated individually, a game without a kickoff yet is open.
+/// The valid predictions are stored (in a single transaction), the invalid ones are rejected.
+/// Responds with an accepted/rejected report for every submitted prediction.
+/// Requires the session of the player.
+#[put(
+    "/predictions/group-stage",
+    format = "application/json",
+    data = "<player_preds>"
+)]
+fn save_group_stage_preds(
+    session: Session,
+    player_preds: Json<PlayerPredictions>,
+    clock: &State<AppClock>,
+) -> Result<Json<Vec<PredictionReport>>, ApiError> {
+    let player_preds = player_preds.into_inner();
+    session.ensure_player(player_preds.id)?;
+    let groups = load_groups().map_err(ApiError::from)?;
+    let teams: Teams = wwc_db::get_teams()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?
+        .map(|x| (x.id, x))
+        .collect();
+    let unscheduled = wwc_db::get_unscheduled_games()
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
+    let now = clock.now();
+    let validated = validate_predictions(player_preds.preds(), &groups, &teams, &unscheduled, now);
+    let valid_preds: Vec<Prediction> = validated
+        .iter()
+        .filter(|(_, res)| res.is_ok())
+        .map(|(pred, _)| *pred)
+        .collect();
+    wwc_db::insert_pred_versions(player_preds.id, &valid_preds, now)
+        .map_err(ServerError::from)
+        .map_err(ApiError::from)?;
     Ok(Json(
-        groups
-            .map_err(ServerError::from)
-            .map_err(BadRequest::from)?,
+        validated
+            .iter()
+            .map(|(pred, res)| PredictionReport::new(pred.0, res))
+            .collect(),
     ))
 }
 
+/// Fill in the missing predictions of a player with an auto-pick strategy
+///
+/// Only games without a prediction which have not kicked off are filled in, existing predictions
+/// are kept. Strategies, see [`wwc_core::pred::strategy::AutoPick`]:
+///
+/// - `favourite`: the better ranked team wins 1-0
+/// - `draw`: every game is a 1-1 draw
+/// - `random`: random scores, from `seed`, the id of the player by default
+/// - `poisson`: the most likely score of a Poisson goal model of the played games
+///
+/// Responds with the added predictions. Requires the session of the player.
+#[post("/predictions/autofill?<player>&<strategy>&<seed>")]
+fn autofill_preds(
+    session: Session,
+    player: i32,
+    strategy: &str,
+    seed: Option<u64>,
+    clock: &State<AppClock>,
+) -> Result<Json<Vec<Prediction>>, WriteError> {
+    let seed = seed.unwrap_or_else(|| u64::try_from(player).unwrap_or_default());
+    let player = PlayerId::from(player);
+    session.ensure_player(player)?;
+    let now = clock.now();
+    let groups = load_groups()?;
+    let ranking = wwc_db::strategy::get_ranking().map_err(ServerError::from)?;
+    let model = PoissonModel::calibrate(groups.values().flat_map(|group| group.played_games()));
+    let ranked = |pick, goals| RankedStrategy {
+        strategy: Strategy {
+            pick,
+            winner_goals: GoalCount::from(goals),
+            loser_goals: GoalCount::from(goals - 1),
+        },
+        ranking: &ranking,
+    };
+    let mut picker: Box<dyn AutoPick> = match strategy {
+        "favourite" => Box::new(ranked(Pick::Favourite, 1)),
+        "draw" => Box::new(ranked(Pick::Draw, 1)),
+        "random" => Box::new(RandomPick::seeded(seed)),
+        "poisson" => Box::new(MostLikely(&model)),
+        _ => {
+            return Err(WriteError::from(ServerError::Invalid(format!(
+                "Unknown strategy '{}', expected favourite, draw, random or poisson",
+                strategy
+            ))))
+        }
+    };
+    let preds = wwc_db::get_preds(player).map_err(ServerError::from)?;
+    let filled = autofill(
+        &preds,
+        groups.values().flat_map(|group| group.unplayed_games()),
+        now,
+        picker.as_mut(),
+    );
+    if !filled.is_empty() {
+        wwc_db::insert_pred_versions(player, &filled, now).map_err(ServerError::from)?;
+    }
+    Ok(Json(filled))
+}
+
+/// Teams with names in `lang`, English if not given
+fn load_teams(lang: Option<&str>) -> Result<Teams, ServerError> {
+    let lang: Lang = lang
+        .map(str::parse)
+        .transpose()
+        .map_err(|err: LangError| ServerError::Invalid(err.to_string()))?
+        .unwrap_or_default();
+    Ok(wwc_db::team_name::get_localized_teams(&lang)?)
+}
+
+/// Load groups
+fn load_groups() -> Result<Groups, ServerError> {
+    Ok(wwc_db::get_groups()?)
+}
+
+/// Rules of the tournament, see [`wwc_db::tournament::get_active_rules`]
+fn load_rules() -> Result<TournamentRules, ServerError> {
+    Ok(wwc_db::tournament::get_active_rules()?)
+}
+
+/// Group ordering rules of the tournament, ranking tiebreakers use the rank of the `teams`
+fn group_rules(
+    rules: &TournamentRules,
+    groups: &Groups,
+    teams: &Teams,
+) -> Result<Rules<AnyTiebreaker>, ServerError> {
+    let groups: Vec<Group> = groups.values().cloned().collect();
+    Ok(rules.group_rules(&groups, teams).map_err(WwcError::from)?)
+}
+
+/// Table points by the point system of the tournament
+fn with_points(rows: Vec<StandingRow>, points: PointSystem) -> Vec<StandingRow> {
+    rows.into_iter()
+        .map(|row| StandingRow {
+            points: points.record_points(row.wins, row.draws, row.losses),
+            ..row
+        })
+        .collect()
+}
+
 fn make_cors() -> Cors {
     // let allowed_origins = AllowedOrigins::some_exact(&[
     //     "http://129.16.37.14:8888",
@@ -139,7 +2345,7 @@ fn make_cors() -> Cors {
 
     CorsOptions {
         // allowed_origins,
-        allowed_methods: vec![Method::Get, Method::Put]
+        allowed_methods: vec![Method::Get, Method::Put, Method::Post, Method::Delete]
             .into_iter()
             .map(From::from)
             .collect(),
@@ -149,22 +2355,177 @@ fn make_cors() -> Cors {
         //     "Access-Control-Allow-Origin",
         // ]),
         allow_credentials: true,
+        expose_headers: VERSION_HEADERS
+            .iter()
+            .map(|header| header.to_string())
+            .collect(),
         ..Default::default()
     }
     .to_cors()
     .expect("error while building CORS")
 }
 
+/// The API versions and the deprecated endpoints, see [`version`]
+#[get("/")]
+fn get_api_versions() -> Json<ApiVersions> {
+    Json(version::api_versions())
+}
+
+/// Every error code, with a description, see [`api_error`]
+#[get("/errors")]
+fn get_error_codes() -> Json<&'static [ErrorCodeInfo]> {
+    Json(ERROR_CODES)
+}
+
+/// Read-only mode, see [`read_only`]
+#[get("/read-only")]
+fn get_read_only(read_only: &State<ReadOnly>) -> Json<bool> {
+    Json(read_only.is_on())
+}
+
+/// Turn read-only mode on or off, e.g. `true` for a maintenance window
+///
+/// Fails when turning it off on a read-only database.
+#[put("/admin/read-only", format = "application/json", data = "<on>")]
+fn put_read_only(
+    _admin: Admin,
+    on: Json<bool>,
+    read_only: &State<ReadOnly>,
+) -> Result<(), ApiError> {
+    let on = on.into_inner();
+    if !on && wwc_db::is_read_only().map_err(ServerError::from)? {
+        return Err(ApiError::from(ServerError::Invalid(String::from(
+            "The database is read-only",
+        ))));
+    }
+    read_only.set(on);
+    Ok(())
+}
+
+/// Every route of the API, mounted once per version, see [`version`]
+///
+/// The mutating routes are blocked in read-only mode, except for [`put_read_only`] and
+/// [`delete_session`], which only touch the server and the client.
+fn api_routes() -> Vec<Route> {
+    let mut routes = guard_writes(routes![
+        get_teams,
+        get_team_aliases,
+        get_groups,
+        get_games,
+        get_upcoming_games,
+        save_preds,
+        save_group_stage_preds,
+        get_preds,
+        get_strategy_preds,
+        autofill_preds,
+        get_players,
+        clear_preds,
+        get_top_scorers,
+        get_top_scorer_leaders,
+        get_fair_play,
+        get_group_fun_stats,
+        get_leaderboard,
+        get_leaderboard_projection,
+        get_provisional_leaderboard,
+        get_materialized_leaderboard,
+        get_materialized_standings,
+        check_materialized_standings,
+        get_stage_leaderboards,
+        get_stage_leaderboard,
+        get_hall_of_fame,
+        get_group_standings_csv,
+        get_group_standings_json,
+        get_group_tables,
+        get_bracket,
+        get_leaderboard_csv,
+        get_leaderboard_json,
+        get_leaderboard_html,
+        get_standings,
+        get_projected_standings,
+        get_standings_history,
+        get_tournaments,
+        get_tournament_rules,
+        put_tournament_rules,
+        put_sandbox_result,
+        reset_sandbox,
+        reschedule_game,
+        put_game_status,
+        withdraw_team,
+        get_withdrawals,
+        put_live_score,
+        delete_live_score,
+        put_game_cards,
+        post_result_text,
+        get_consensus,
+        get_heatmap,
+        get_odds,
+        put_odds,
+        get_notifications,
+        get_digest,
+        get_profile,
+        get_achievements,
+        put_profile,
+        put_player_name,
+        delete_player,
+        erase_player,
+        delete_player_preds,
+        get_deleted_players,
+        restore_player,
+        restore_player_preds,
+        restore_all_preds,
+        get_schedule,
+        get_schedule_ics,
+        get_jokers,
+        put_joker,
+        delete_joker,
+        get_personal_bracket,
+        put_playoff_pred,
+        get_leagues,
+        get_league_rules,
+        put_league_rules,
+        get_league_leaderboard,
+        put_member_alias,
+        post_league_invite,
+        get_league_invites,
+        register,
+        post_login_link,
+        login,
+        get_session,
+        post_api_key,
+        get_api_keys,
+        get_integrity,
+        get_events,
+        get_replay,
+        get_fairness,
+        delete_api_key,
+        seed,
+        get_error_codes,
+        get_read_only
+    ]);
+    routes.extend(routes![put_read_only, delete_session]);
+    routes
+}
+
 #[launch]
 fn rocket() -> _ {
     rocket::build()
-        .mount(
-            "/",
-            routes![get_teams, get_groups, save_preds, get_preds, clear_preds],
-        )
+        .mount(ApiVersion::V1.base(), api_routes())
+        // Deprecated, see `version::LEGACY_SUNSET`
+        .mount("/", api_routes())
+        .mount("/api", routes![get_api_versions])
+        .register("/", catchers![api_error::default_catcher])
+        .manage(Notifications::from_log(Webhooks::from_env()))
+        .manage(OrderCache::default())
+        .manage::<AppClock>(Box::new(SystemClock))
+        .manage(ReadOnly::from_env())
+        .manage(LoginSecrets::from_env())
         // Can't get this catch_all... to work.
         // .mount("/", catch_all_options_routes())
         .attach(make_cors())
+        .attach(ConditionalGet)
+        .attach(EventFeed)
+        .attach(ApiKeys)
+        .attach(Versioning)
 }
 
 #[derive(Error, Debug)]
@@ -173,10 +2534,64 @@ enum ServerError {
     Db(#[from] wwc_db::DbError),
     #[error("Wwc core error: {0}")]
     Wwc(#[from] WwcError),
+    #[error("Export error: {0}")]
+    Export(#[from] export::ExportError),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Invalid input: {0}")]
+    Invalid(String),
+    #[error("Joker error: {0}")]
+    Joker(#[from] JokerError),
+    #[error("Render error: {0}")]
+    Render(#[from] askama::Error),
+    #[error("Login error: {0}")]
+    Login(#[from] LoginError),
+    #[error("Login links are disabled, set {}", LOGIN_SECRET_VAR)]
+    LoginDisabled,
+    #[error("Not logged in as player {0}")]
+    NotLoggedInAs(PlayerId),
 }
 
-impl From<ServerError> for BadRequest<String> {
-    fn from(server_err: ServerError) -> Self {
-        BadRequest(Some(server_err.to_string()))
+impl ServerError {
+    /// Whether the error is about a missing resource, responded with `404 Not Found`
+    fn is_not_found(&self) -> bool {
+        match self {
+            ServerError::NotFound(_) => true,
+            ServerError::Db(err) => err.is_not_found(),
+            _ => false,
+        }
+    }
+
+    /// Whether the error is about acting for another player, responded with `403 Forbidden`
+    fn is_forbidden(&self) -> bool {
+        matches!(self, ServerError::NotLoggedInAs(_))
+    }
+}
+
+impl ErrorCode for ServerError {
+    fn code(&self) -> &'static str {
+        match self {
+            ServerError::Db(err) => err.code(),
+            ServerError::Wwc(err) => err.code(),
+            ServerError::Export(_) => "EXPORT_FAILED",
+            ServerError::NotFound(_) => "NOT_FOUND",
+            ServerError::Invalid(_) => "INVALID_INPUT",
+            ServerError::Joker(err) => err.code(),
+            ServerError::Render(_) => "RENDER_FAILED",
+            ServerError::Login(err) => err.code(),
+            ServerError::LoginDisabled => "LOGIN_LINKS_DISABLED",
+            ServerError::NotLoggedInAs(_) => "NOT_LOGGED_IN_AS_PLAYER",
+        }
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            ServerError::Db(err) => err.details(),
+            ServerError::Wwc(err) => err.details(),
+            ServerError::Joker(err) => err.details(),
+            ServerError::Login(err) => err.details(),
+            ServerError::NotLoggedInAs(player) => Some(serde_json::json!({ "player": player })),
+            _ => None,
+        }
     }
 }
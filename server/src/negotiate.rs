@@ -0,0 +1,97 @@
+//! Content negotiation
+//!
+//! The standings and the leaderboard are served in the format asked for by the `Accept` header
+//! of the request: JSON, CSV, HTML or a plain text table, see [`Format`].
+//! Clients that accept anything, e.g. the frontend and curl without an `Accept` header, get JSON.
+//! The `.csv`, `.json` and `.html` routes remain for links and clients that can not set headers.
+use rocket::http::{ContentType, MediaType};
+use rocket::request::{self, FromRequest, Request};
+use rocket::response::{self, Responder, Response};
+use rocket::serde::{json::Json, Serialize};
+
+/// Response format, chosen from the `Accept` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub(crate) enum Format {
+    Json,
+    Csv,
+    Html,
+    /// Aligned plain text table, for terminals and chat bots
+    Text,
+}
+
+impl Format {
+    fn from_media_type(media_type: &MediaType) -> Option<Self> {
+        if media_type.is_json() || *media_type == MediaType::Any {
+            Some(Format::Json)
+        } else if media_type.is_csv() {
+            Some(Format::Csv)
+        } else if media_type.is_html() {
+            Some(Format::Html)
+        } else if media_type.is_plain() {
+            Some(Format::Text)
+        } else {
+            None
+        }
+    }
+}
+
+/// The known format with the highest weight, the first one listed on equal weights.
+/// JSON if the request has no `Accept` header or no known format is accepted.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Format {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let format = request
+            .accept()
+            .and_then(|accept| {
+                accept
+                    .iter()
+                    .filter_map(|media_type| {
+                        Format::from_media_type(media_type.media_type())
+                            .map(|format| (format, media_type.weight_or(1.0)))
+                    })
+                    .fold(
+                        None,
+                        |best: Option<(Format, f32)>, (format, weight)| match best {
+                            Some((_, best_weight)) if best_weight >= weight => best,
+                            _ => Some((format, weight)),
+                        },
+                    )
+            })
+            .map(|(format, _)| format)
+            .unwrap_or(Format::Json);
+        request::Outcome::Success(format)
+    }
+}
+
+/// Response in a negotiated [`Format`], with `Vary: Accept`
+///
+/// The tag of a negotiated response is of the format and the data, see [`Tagged`](crate::Tagged).
+pub(crate) enum Negotiated<T> {
+    Json(Json<T>),
+    Other(Box<(ContentType, String)>),
+}
+
+impl<T: Serialize> Negotiated<T> {
+    pub(crate) fn json(content: T) -> Self {
+        Negotiated::Json(Json(content))
+    }
+
+    /// Content rendered as CSV, HTML or text
+    pub(crate) fn other(content_type: ContentType, content: String) -> Self {
+        Negotiated::Other(Box::new((content_type, content)))
+    }
+}
+
+impl<'r, 'o: 'r, T: Serialize> Responder<'r, 'o> for Negotiated<T> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        let response = match self {
+            Negotiated::Json(json) => json.respond_to(request)?,
+            Negotiated::Other(other) => other.respond_to(request)?,
+        };
+        Response::build_from(response)
+            .raw_header("Vary", "Accept")
+            .ok()
+    }
+}
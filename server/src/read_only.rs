@@ -0,0 +1,85 @@
+//! Read-only mode
+//!
+//! In read-only mode every mutating route, i.e. every route but `GET`, `HEAD` and `OPTIONS`,
+//! responds with `503 Service Unavailable` and the code `READ_ONLY` without running its handler.
+//! Useful for serving the archive of a tournament from a snapshot, and for maintenance windows
+//! during live matches.
+//!
+//! The mode is on at launch if `WWC_READ_ONLY` is set, to anything but `0` or `false`, or if the
+//! database is read-only, see [`wwc_db::is_read_only`].
+//! An admin turns it on and off with `PUT /admin/read-only`, which is never blocked.
+use crate::api_error::ErrorBody;
+use rocket::http::{Method, Status};
+use rocket::route::{Handler, Outcome};
+use rocket::serde::json::Json;
+use rocket::{Data, Request, Route};
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Environment variable turning read-only mode on at launch
+pub(crate) const READ_ONLY_VAR: &str = "WWC_READ_ONLY";
+
+/// Whether read-only mode is on, shared between requests
+pub(crate) struct ReadOnly(AtomicBool);
+
+impl ReadOnly {
+    /// Mode at launch, from [`READ_ONLY_VAR`] and the database
+    ///
+    /// A database which cannot be probed is assumed writable, the error surfaces on the first
+    /// request instead.
+    pub(crate) fn from_env() -> Self {
+        let flag = env::var(READ_ONLY_VAR)
+            .map(|value| !matches!(value.trim(), "" | "0" | "false"))
+            .unwrap_or(false);
+        ReadOnly(AtomicBool::new(
+            flag || wwc_db::is_read_only().unwrap_or(false),
+        ))
+    }
+
+    pub(crate) fn is_on(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn set(&self, on: bool) {
+        self.0.store(on, Ordering::SeqCst)
+    }
+}
+
+/// Block the mutating routes of `routes` in read-only mode
+pub(crate) fn guard_writes(routes: Vec<Route>) -> Vec<Route> {
+    routes
+        .into_iter()
+        .map(|mut route| {
+            if !matches!(route.method, Method::Get | Method::Head | Method::Options) {
+                route.handler = Box::new(WriteGuard(route.handler));
+            }
+            route
+        })
+        .collect()
+}
+
+/// Handler of a mutating route, refusing requests in read-only mode
+#[derive(Clone)]
+struct WriteGuard(Box<dyn Handler>);
+
+#[rocket::async_trait]
+impl Handler for WriteGuard {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let read_only = request
+            .rocket()
+            .state::<ReadOnly>()
+            .map(ReadOnly::is_on)
+            .unwrap_or(false);
+        if !read_only {
+            return self.0.handle(request, data).await;
+        }
+        let body = ErrorBody {
+            code: "READ_ONLY",
+            message: String::from(
+                "The server is read-only for now, e.g. during maintenance. Please try again later.",
+            ),
+            details: None,
+        };
+        Outcome::from(request, (Status::ServiceUnavailable, Json(body)))
+    }
+}
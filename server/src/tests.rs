@@ -0,0 +1,103 @@
+//! Tests of the routes, against a fresh database with the games of Euro 2020
+use super::rocket;
+use diesel::connection::{Connection, SimpleConnection};
+use diesel::sqlite::SqliteConnection;
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::Client;
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use wwc_core::api_key::Scope;
+use wwc_core::Date;
+use wwc_data::template::Template;
+
+/// Database with every migration applied and the games of Euro 2020, removed on drop
+struct TestDb(PathBuf);
+
+impl TestDb {
+    fn euro_2020() -> Self {
+        let path = env::temp_dir().join(format!("wwc_server_test_{}.db", process::id()));
+        let url = path.to_str().expect("Temp path is UTF-8");
+        let connection = SqliteConnection::establish(url).expect("Test db connects");
+        let migrations = Path::new(env!("CARGO_MANIFEST_DIR")).join("../db/migrations");
+        let mut ups: Vec<PathBuf> = fs::read_dir(migrations)
+            .expect("Migrations dir")
+            .map(|entry| entry.expect("Migration dir entry").path().join("up.sql"))
+            .filter(|up| up.exists())
+            .collect();
+        ups.sort();
+        for up in ups {
+            let sql = fs::read_to_string(&up).expect("Migration readable");
+            connection
+                .batch_execute(&sql)
+                .unwrap_or_else(|err| panic!("Migration {}: {}", up.display(), err));
+        }
+        env::set_var("DATABASE_URL", url);
+        let data = Template::Euro2020.load().expect("Euro 2020 template loads");
+        wwc_db::seed::seed(
+            &data.teams,
+            &data.groups,
+            data.bracket.as_ref(),
+            &data.venues,
+        )
+        .expect("Euro 2020 seeds");
+        TestDb(path)
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn stale_result_versions_are_rejected() {
+    let _db = TestDb::euro_2020();
+    wwc_db::tournament::register_tournament("Sandbox", true, None).unwrap();
+    let (key, _) = wwc_db::api_key::create_api_key("test", Scope::Admin, Date::mock()).unwrap();
+    let client = Client::tracked(rocket()).unwrap();
+    let put_result = |body: Value| {
+        client
+            .put("/api/v1/tournaments/1/games/1/result")
+            .header(ContentType::JSON)
+            .header(Header::new(super::auth::API_KEY_HEADER, key.to_string()))
+            .body(body.to_string())
+            .dispatch()
+    };
+
+    let response = put_result(json!({"home": 1, "away": 0, "version": 0}));
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "1");
+    let response = put_result(json!({"home": 2, "away": 0, "version": 0}));
+    assert_eq!(response.status(), Status::Conflict);
+    let body: Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_eq!(body["code"], "GAME_VERSION_CONFLICT");
+    let response = put_result(json!({"home": 2, "away": 0}));
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let (_, unplayed, _) = wwc_db::get_group_games().unwrap();
+    let game = &unplayed[0];
+    let teams = wwc_db::get_teams().unwrap().collect::<Vec<_>>();
+    let code = |id| {
+        teams
+            .iter()
+            .find(|team| team.id == id)
+            .unwrap()
+            .fifa_code
+            .to_string()
+    };
+    let text = format!("{}-{} 2:1", code(game.home), code(game.away));
+    let post_result = |version: i32| {
+        client
+            .post("/api/v1/results")
+            .header(ContentType::JSON)
+            .header(Header::new(super::auth::API_KEY_HEADER, key.to_string()))
+            .body(json!({"text": text, "version": version}).to_string())
+            .dispatch()
+    };
+    assert_eq!(post_result(1).status(), Status::Conflict);
+    assert_eq!(post_result(0).status(), Status::Ok);
+}
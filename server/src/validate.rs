@@ -0,0 +1,295 @@
+//! Request validation
+//!
+//! The bodies of write requests implement [`Validate`], which checks every field and collects
+//! all errors rather than stopping at the first one.
+//! Invalid requests are rejected with `422 Unprocessable Entity` and the code
+//! `VALIDATION_FAILED`, the details list the invalid fields, see [`ValidationErrors`].
+//!
+//! Only the content of a request is checked here, e.g. score bounds, date formats and references
+//! to existing games and teams. Rules depending on the state of the tournament, e.g. prediction
+//! deadlines, are left to the handlers.
+use crate::api_error::ErrorBody;
+use crate::{load_groups, ServerError, WriteError};
+use crate::{NewKey, Registration, Rename, Reschedule, ResultSubmission};
+use chrono_tz::Tz;
+use rocket::serde::{json::Json, Serialize};
+use std::fmt;
+use wwc_core::game::{Game, Score};
+use wwc_core::group::cross_group;
+use wwc_core::group::order::RULE_NAMES;
+use wwc_core::odds::Odds;
+use wwc_core::player::{
+    validate_avatar, validate_emoji, validate_name, LeagueAlias, PlayerPredictions, Profile,
+};
+use wwc_core::player::{PlayerId, MAX_PREDICTED_GOALS};
+use wwc_core::pred_score::ScoringRules;
+use wwc_core::rules::{PointSystem, TournamentRules};
+use wwc_core::team::Teams;
+use wwc_core::Date;
+use wwc_data::template::Template;
+
+/// Largest number of goals of a team in a result, the record is 31-0
+pub const MAX_RESULT_GOALS: u32 = 31;
+
+/// Invalid field of a request body
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct FieldError {
+    /// Path of the field, e.g. `preds[2].home`
+    pub field: String,
+    pub message: String,
+}
+
+/// All invalid fields of a request body, the details of a `422` response
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: impl Into<String>, message: impl fmt::Display) {
+        self.errors.push(FieldError {
+            field: field.into(),
+            message: message.to_string(),
+        });
+    }
+
+    /// Add the error of `res`, if any
+    pub fn check<T, E: fmt::Display>(&mut self, field: impl Into<String>, res: Result<T, E>) {
+        if let Err(err) = res {
+            self.add(field, err);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Validation of a request body
+pub trait Validate {
+    /// Add an error for every invalid field
+    ///
+    /// # Errors
+    ///
+    /// Only if the validation itself fails, e.g. when looking up games in the db.
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError>;
+}
+
+/// Validate a request body, see [`Validate`]
+pub fn validate<T: Validate>(body: &T) -> Result<(), WriteError> {
+    let mut errors = ValidationErrors::default();
+    body.validate(&mut errors)?;
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(WriteError::Unprocessable(Json(ErrorBody::from(errors))))
+    }
+}
+
+/// Check the goals of a score, at most `max` per team
+fn check_score(errors: &mut ValidationErrors, prefix: &str, score: Score, max: u32) {
+    for (field, goals) in [("home", score.home), ("away", score.away)] {
+        if u32::from(goals) > max {
+            errors.add(
+                format!("{}{}", prefix, field),
+                format!("At most {} goals", max),
+            );
+        }
+    }
+}
+
+impl Validate for Score {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        check_score(errors, "", *self, MAX_RESULT_GOALS);
+        Ok(())
+    }
+}
+
+impl Validate for ResultSubmission {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        self.score.validate(errors)
+    }
+}
+
+/// Predictions refer to existing group games, between existing teams
+impl Validate for PlayerPredictions {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        if !wwc_db::get_players()?
+            .iter()
+            .any(|player| PlayerId::from(player.id) == self.id)
+        {
+            errors.add("id", format!("No player with id {}", self.id));
+        }
+        let groups = load_groups()?;
+        let teams: Teams = wwc_db::get_teams()?.map(|team| (team.id, team)).collect();
+        for (idx, pred) in self.preds().enumerate() {
+            let game = groups
+                .values()
+                .flat_map(|group| group.games())
+                .find(|game| game.id() == pred.0);
+            match game {
+                Some(game) => {
+                    for team in [game.home_team(), game.away_team()] {
+                        if !teams.contains_key(&team) {
+                            errors.add(format!("preds[{}]", idx), format!("Unknown team {}", team));
+                        }
+                    }
+                }
+                None => errors.add(
+                    format!("preds[{}]", idx),
+                    format!("No group game with id {}", pred.0),
+                ),
+            }
+            check_score(
+                errors,
+                &format!("preds[{}].", idx),
+                pred.1,
+                MAX_PREDICTED_GOALS,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Validate for Profile {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        if let Some(timezone) = &self.timezone {
+            if timezone.parse::<Tz>().is_err() {
+                errors.add("timezone", format!("Unknown time zone '{}'", timezone));
+            }
+        }
+        if let Some(avatar) = &self.avatar {
+            errors.check("avatar", validate_avatar(avatar));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for LeagueAlias {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        if let Some(name) = &self.display_name {
+            errors.check("display_name", validate_name(name));
+        }
+        if let Some(emoji) = &self.emoji {
+            errors.check("emoji", validate_emoji(emoji));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for Rename {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        errors.check("name", validate_name(&self.name));
+        Ok(())
+    }
+}
+
+impl Validate for Registration {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        errors.check("name", validate_name(&self.name));
+        Ok(())
+    }
+}
+
+impl Validate for NewKey {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        if self.name.trim().is_empty() {
+            errors.add("name", "Empty name");
+        }
+        Ok(())
+    }
+}
+
+impl Validate for Reschedule {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        errors.check("kickoff", self.kickoff.parse::<Date>());
+        Ok(())
+    }
+}
+
+impl Validate for Odds {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        for (field, odds) in [
+            ("home", self.home),
+            ("draw", self.draw),
+            ("away", self.away),
+        ] {
+            if !Odds::is_valid(odds) {
+                errors.add(field, "Decimal odds are at least 1.0");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Validate for TournamentRules {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        if !RULE_NAMES.contains(&self.group_rules.as_str()) {
+            errors.add(
+                "group_rules",
+                format!("Unknown rules, expected one of: {}", RULE_NAMES.join(", ")),
+            );
+        }
+        if let Some(points) = PointSystem::of_rules(&self.group_rules) {
+            if points != self.points {
+                errors.add(
+                    "points",
+                    format!("The rules {} award {:?}", self.group_rules, points),
+                );
+            }
+        }
+        if !cross_group::RULE_NAMES.contains(&self.third_place.rules.as_str()) {
+            errors.add(
+                "third_place.rules",
+                format!(
+                    "Unknown rules, expected one of: {}",
+                    cross_group::RULE_NAMES.join(", ")
+                ),
+            );
+        }
+        if let Some(bracket) = &self.bracket {
+            errors.check("bracket", bracket.parse::<Template>());
+        }
+        let mut scoring = ValidationErrors::default();
+        self.scoring.validate(&mut scoring)?;
+        for error in scoring.errors {
+            errors.add(format!("scoring.{}", error.field), error.message);
+        }
+        Ok(())
+    }
+}
+
+impl Validate for ScoringRules {
+    fn validate(&self, errors: &mut ValidationErrors) -> Result<(), ServerError> {
+        let points = [
+            ("exact", Some(self.exact)),
+            ("goal_diff", Some(self.goal_diff)),
+            ("outcome", Some(self.outcome)),
+            ("odds_bonus", self.odds_bonus),
+            ("knockout_winner", Some(self.knockout_winner)),
+        ];
+        for (field, points) in points.iter() {
+            if let Some(points) = points {
+                if !points.is_finite() || *points < 0.0 {
+                    errors.add(*field, "Points are finite and not negative");
+                }
+            }
+        }
+        for (idx, weight) in self.bracket_weights.iter().enumerate() {
+            if !weight.is_finite() || *weight < 0.0 {
+                errors.add(
+                    format!("bracket_weights[{}]", idx),
+                    "Weights are finite and not negative",
+                );
+            }
+        }
+        for (idx, tiebreak) in self.tiebreaks.iter().enumerate() {
+            if self.tiebreaks[..idx].contains(tiebreak) {
+                errors.add(format!("tiebreaks[{}]", idx), "Duplicate tiebreak");
+            }
+        }
+        Ok(())
+    }
+}
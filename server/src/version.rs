@@ -0,0 +1,174 @@
+//! API versioning
+//!
+//! Every route is served under the prefix of the API version, e.g. `/api/v1/leaderboard`,
+//! see [`ApiVersion`].
+//! The policy for clients, the frontend and the bots:
+//!
+//! - The version is chosen by the path prefix only. A new version is added for breaking changes
+//!   of a route or a DTO; new routes and new fields are added to the current version.
+//! - Every response carries an `Api-Version` header with the version that served it.
+//! - The routes are also mounted without a prefix, for clients from before the versioning.
+//!   Those are served by the latest version and are deprecated, see [`LEGACY_SUNSET`].
+//! - A deprecated endpoint keeps working until its sunset date. Its responses carry a
+//!   `Deprecation` header, a `Sunset` header ([RFC 8594](https://tools.ietf.org/html/rfc8594))
+//!   and, if there is one, a `Link` to the successor.
+//!   The deprecated endpoints are listed in [`DEPRECATED`] and at `GET /api`.
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::serde::Serialize;
+use rocket::{Request, Response};
+
+/// Version of the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) enum ApiVersion {
+    V1,
+}
+
+impl ApiVersion {
+    /// Every version, oldest first
+    pub(crate) const ALL: [ApiVersion; 1] = [ApiVersion::V1];
+
+    /// The version serving the unprefixed routes
+    pub(crate) const LATEST: ApiVersion = ApiVersion::V1;
+
+    /// Path prefix, the mount point of the routes of the version
+    pub(crate) fn base(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "/api/v1",
+        }
+    }
+
+    fn number(self) -> u32 {
+        match self {
+            ApiVersion::V1 => 1,
+        }
+    }
+
+    fn from_base(base: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|version| version.base() == base)
+    }
+}
+
+/// Sunset of the unprefixed routes, as an HTTP date
+pub(crate) const LEGACY_SUNSET: &str = "Fri, 01 Jul 2022 00:00:00 GMT";
+
+/// A deprecated endpoint of an API version
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct Deprecation {
+    /// Name of the route handler
+    pub(crate) route: &'static str,
+    /// The endpoint is removed after this HTTP date
+    pub(crate) sunset: &'static str,
+    /// Path of the replacing endpoint, relative to the version prefix
+    pub(crate) successor: Option<&'static str>,
+}
+
+/// Deprecated endpoints of the current version
+pub(crate) const DEPRECATED: &[Deprecation] = &[
+    Deprecation {
+        route: "save_preds",
+        sunset: LEGACY_SUNSET,
+        successor: Some("/predictions/group-stage"),
+    },
+    // Deletes every prediction, of every player, in a single request
+    Deprecation {
+        route: "clear_preds",
+        sunset: LEGACY_SUNSET,
+        successor: None,
+    },
+];
+
+/// Response headers set by [`Versioning`], exposed to the frontend through CORS
+pub(crate) const VERSION_HEADERS: [&str; 4] = ["Api-Version", "Deprecation", "Sunset", "Link"];
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct VersionInfo {
+    version: u32,
+    base: &'static str,
+    latest: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ApiVersions {
+    versions: Vec<VersionInfo>,
+    deprecated: &'static [Deprecation],
+    /// Sunset of the routes without a version prefix
+    legacy_sunset: &'static str,
+}
+
+/// The API versions and the deprecated endpoints
+pub(crate) fn api_versions() -> ApiVersions {
+    ApiVersions {
+        versions: ApiVersion::ALL
+            .iter()
+            .map(|version| VersionInfo {
+                version: version.number(),
+                base: version.base(),
+                latest: *version == ApiVersion::LATEST,
+            })
+            .collect(),
+        deprecated: DEPRECATED,
+        legacy_sunset: LEGACY_SUNSET,
+    }
+}
+
+/// Version and deprecation headers, see the [module docs](self)
+pub(crate) struct Versioning;
+
+#[rocket::async_trait]
+impl Fairing for Versioning {
+    fn info(&self) -> Info {
+        Info {
+            name: "API versioning",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let route = match request.route() {
+            Some(route) => route,
+            None => return,
+        };
+        let base = route.uri.base();
+        if base == "/api" {
+            return;
+        }
+        let (version, legacy) = match ApiVersion::from_base(base) {
+            Some(version) => (version, false),
+            None => (ApiVersion::LATEST, true),
+        };
+        response.set_raw_header("Api-Version", version.number().to_string());
+        let deprecation = route.name.as_ref().and_then(|name| {
+            DEPRECATED
+                .iter()
+                .find(|deprecated| deprecated.route == *name)
+        });
+        let (sunset, successor) = match deprecation {
+            Some(deprecation) => (
+                deprecation.sunset,
+                deprecation
+                    .successor
+                    .map(|successor| format!("{}{}", version.base(), successor)),
+            ),
+            None if legacy => (
+                LEGACY_SUNSET,
+                Some(format!("{}{}", version.base(), request.uri())),
+            ),
+            None => return,
+        };
+        response.set_raw_header("Deprecation", "true");
+        response.set_raw_header("Sunset", sunset);
+        if let Some(successor) = successor {
+            response.set_raw_header(
+                "Link",
+                format!("<{}>; rel=\"successor-version\"", successor),
+            );
+        }
+    }
+}
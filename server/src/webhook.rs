@@ -0,0 +1,68 @@
+//! Webhooks
+//!
+//! Every new notification, see [`feed`](crate::feed), is posted as JSON to the URLs in
+//! `WWC_WEBHOOK_URLS`, separated by commas, e.g. the incoming webhook of a chat channel.
+//!
+//! Delivery is best effort: the posts run in the background, a failed post is logged and not
+//! retried. Consumers that must not miss a notification poll `GET /notifications` instead.
+use reqwest::Client;
+use std::env;
+use wwc_core::notification::Notification;
+
+/// Environment variable with the webhook URLs
+pub(crate) const WEBHOOK_URLS_VAR: &str = "WWC_WEBHOOK_URLS";
+
+/// Webhooks notified of every new notification, none if [`WEBHOOK_URLS_VAR`] is not set
+pub(crate) struct Webhooks {
+    urls: Vec<String>,
+    client: Client,
+}
+
+impl Webhooks {
+    pub(crate) fn from_env() -> Self {
+        let urls = env::var(WEBHOOK_URLS_VAR)
+            .map(|urls| {
+                urls.split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Webhooks {
+            urls,
+            client: Client::new(),
+        }
+    }
+
+    /// Post the `notifications` to every webhook, in the background
+    ///
+    /// Outside of the async runtime nothing is posted.
+    pub(crate) fn deliver(&self, notifications: &[Notification]) {
+        if self.urls.is_empty() || notifications.is_empty() {
+            return;
+        }
+        let runtime = match rocket::tokio::runtime::Handle::try_current() {
+            Ok(runtime) => runtime,
+            Err(err) => {
+                eprintln!("Webhooks not delivered: {}", err);
+                return;
+            }
+        };
+        for notification in notifications {
+            for url in &self.urls {
+                let request = self.client.post(url).json(notification);
+                let url = url.clone();
+                runtime.spawn(async move {
+                    let response = request
+                        .send()
+                        .await
+                        .and_then(|response| response.error_for_status());
+                    if let Err(err) = response {
+                        eprintln!("Webhook {} failed: {}", url, err);
+                    }
+                });
+            }
+        }
+    }
+}
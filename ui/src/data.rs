@@ -24,8 +24,10 @@ pub(crate) async fn get_preds(player_id: PlayerId) -> Result<Vec<Prediction>, Ui
 
 pub(crate) async fn save_preds(preds: PlayerPredictions) -> Result<(), UiError> {
     let url = format!("{}/{}", SERVER_IP, "save_preds");
+    // Saving requires the session cookie of the player, see `/login/<token>`.
     Request::new(&url)
         .method(Method::Put)
+        .credentials(web_sys::RequestCredentials::Include)
         .json(&preds)
         .expect("Could not serialise PlayerPredictions")
         .fetch()
@@ -36,6 +38,7 @@ pub(crate) async fn save_preds(preds: PlayerPredictions) -> Result<(), UiError>
 
 pub(crate) async fn clear_preds() -> Result<(), UiError> {
     Request::new(&format!("{}/{}", SERVER_IP, "clear_preds"))
+        .method(Method::Delete)
         .fetch()
         .await?
         .check_status()?;
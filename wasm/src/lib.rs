@@ -0,0 +1,186 @@
+//! # WASM bindings for the *wwc* core library
+//!
+//! Thin `wasm-bindgen` wrappers around the group ordering and the prediction scoring,
+//! so that the frontend can compute hypothetical standings and score predictions client-side,
+//! without a round-trip to the server.
+//!
+//! All data is exchanged as JSON strings, in the same format as the server API,
+//! e.g. a group as returned by `/get_groups` and teams as returned by `/get_teams`.
+//! Errors are thrown as JS strings.
+//!
+//! Group ordering rules are selected by name, see [`RULE_NAMES`].
+#![forbid(unsafe_code)]
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use thiserror::Error;
+use wasm_bindgen::prelude::*;
+use wwc_core::export;
+use wwc_core::game::{GameId, Score};
+use wwc_core::group::order::{self, AnyTiebreaker, Rules, RULE_NAMES};
+use wwc_core::group::{Group, GroupError};
+use wwc_core::joker::Jokers;
+use wwc_core::leaderboard;
+use wwc_core::player::Prediction;
+use wwc_core::pred_score::{PredScoreFn, ScoringRules};
+use wwc_core::team::{TeamId, Teams};
+
+/// Order a group
+///
+/// Returns the team id's, from best to worst.
+#[wasm_bindgen(js_name = orderGroup)]
+pub fn order_group(group: &str, teams: &str, rules: &str) -> Result<String, JsValue> {
+    to_js(order_group_json(group, teams, rules))
+}
+
+/// Group table
+///
+/// Same rows as the `/groups/<id>/standings.json` endpoint.
+#[wasm_bindgen]
+pub fn standings(group: &str, teams: &str, rules: &str) -> Result<String, JsValue> {
+    to_js(standings_json(group, teams, "[]", rules))
+}
+
+/// Group table if the unplayed games end with the given `results`
+///
+/// `results` are predictions, `[[game_id, {"home": .., "away": ..}], ..]`,
+/// e.g. to show the table if the predictions of a player come true.
+#[wasm_bindgen(js_name = whatIf)]
+pub fn what_if(group: &str, teams: &str, results: &str, rules: &str) -> Result<String, JsValue> {
+    to_js(standings_json(group, teams, results, rules))
+}
+
+/// Points for a single prediction
+#[wasm_bindgen(js_name = predScore)]
+pub fn pred_score(pred: &str, truth: &str, scoring_rules: &str) -> Result<f32, JsValue> {
+    to_js(pred_score_json(pred, truth, scoring_rules))
+}
+
+/// Total points for the predictions of a player, joker games included
+///
+/// `results` are the final scores in the same format as the predictions.
+#[wasm_bindgen]
+pub fn points(
+    preds: &str,
+    jokers: &str,
+    results: &str,
+    scoring_rules: &str,
+) -> Result<f32, JsValue> {
+    to_js(points_json(preds, jokers, results, scoring_rules))
+}
+
+fn order_group_json(group: &str, teams: &str, rules_name: &str) -> Result<String, WasmError> {
+    let (group, teams): (Group, Teams) = (parse(group)?, parse(teams)?);
+    let order: Vec<TeamId> = order::order_group(&group, &rules(rules_name, &group, &teams)?)
+        .iter()
+        .copied()
+        .collect();
+    Ok(serde_json::to_string(&order)?)
+}
+
+fn standings_json(
+    group: &str,
+    teams: &str,
+    results: &str,
+    rules_name: &str,
+) -> Result<String, WasmError> {
+    let (mut group, teams): (Group, Teams) = (parse(group)?, parse(teams)?);
+    let results: Vec<Prediction> = parse(results)?;
+    for Prediction(game_id, score) in results {
+        if !group.unplayed_games().any(|game| game.id == game_id) {
+            return Err(WasmError::UnknownGame(game_id));
+        }
+        group.play_game(game_id, score);
+    }
+    let rows = export::group_standings(&group, &teams, &rules(rules_name, &group, &teams)?);
+    Ok(serde_json::to_string(&rows)?)
+}
+
+fn pred_score_json(pred: &str, truth: &str, scoring_rules: &str) -> Result<f32, WasmError> {
+    let (pred, truth): (Score, Score) = (parse(pred)?, parse(truth)?);
+    let scoring_rules: ScoringRules = parse(scoring_rules)?;
+    Ok(scoring_rules.pred_score(pred, truth).into())
+}
+
+fn points_json(
+    preds: &str,
+    jokers: &str,
+    results: &str,
+    scoring_rules: &str,
+) -> Result<f32, WasmError> {
+    let preds: Vec<Prediction> = parse(preds)?;
+    let jokers: Jokers = parse(jokers)?;
+    let results: HashMap<GameId, Score> = parse::<Vec<(GameId, Score)>>(results)?
+        .into_iter()
+        .collect();
+    let scoring_rules: ScoringRules = parse(scoring_rules)?;
+    Ok(leaderboard::points(&preds, &jokers, &results, &scoring_rules).into())
+}
+
+/// Group ordering rules by name
+///
+/// The rank of the `teams` is used by rules with a ranking tiebreaker.
+fn rules(name: &str, group: &Group, teams: &Teams) -> Result<Rules<AnyTiebreaker>, WasmError> {
+    let ranking = teams.iter().map(|(id, team)| (*id, team.rank)).collect();
+    order::rules_by_name(name, std::slice::from_ref(group), ranking).map_err(|err| match err {
+        GroupError::UnknownRules => WasmError::UnknownRules(String::from(name)),
+        err => WasmError::Group(err),
+    })
+}
+
+fn parse<T: DeserializeOwned>(json: &str) -> Result<T, WasmError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+fn to_js<T>(res: Result<T, WasmError>) -> Result<T, JsValue> {
+    res.map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+#[derive(Error, Debug)]
+pub enum WasmError {
+    #[error("JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Group: {0}")]
+    Group(#[from] GroupError),
+    #[error("Unknown rules '{0}', expected one of {names:?}", names = RULE_NAMES)]
+    UnknownRules(String),
+    #[error("No unplayed game with id {0}")]
+    UnknownGame(GameId),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wwc_core::export::StandingRow;
+    use wwc_core::group::{mock_data, GroupId};
+
+    #[test]
+    fn what_if_predictions_come_true() {
+        let (groups, teams) = mock_data();
+        let group = &groups[&GroupId::from('A')];
+        let game = group.unplayed_games().next().unwrap();
+        let (group_json, teams_json) = (
+            serde_json::to_string(group).unwrap(),
+            serde_json::to_string(&teams).unwrap(),
+        );
+        let results = serde_json::to_string(&[Prediction(game.id, Score::new(9, 0))]).unwrap();
+        let rows: Vec<StandingRow> = serde_json::from_str(
+            &standings_json(&group_json, &teams_json, &results, "fifa_2018").unwrap(),
+        )
+        .unwrap();
+        let home = rows.iter().find(|row| row.team_id == game.home.0).unwrap();
+        assert_eq!(home.goals_scored, 9);
+        assert!(matches!(
+            standings_json(&group_json, &teams_json, &results, "wc_1930"),
+            Err(WasmError::UnknownRules(_))
+        ));
+    }
+
+    #[test]
+    fn joker_points() {
+        let preds = r#"[[1, {"home": 1, "away": 0}], [2, {"home": 0, "away": 0}]]"#;
+        let results = r#"[[1, {"home": 2, "away": 0}], [2, {"home": 0, "away": 0}]]"#;
+        let rules = r#"{"exact": 5, "goal_diff": 3, "outcome": 1}"#;
+        let points = points_json(preds, "[2]", results, rules).unwrap();
+        assert!((points - 11.0).abs() < f32::EPSILON);
+    }
+}